@@ -1,4 +1,15 @@
 fn main() {
+    // Short git hash for build_info (absent outside a git checkout)
+    if let Ok(out) = std::process::Command::new("git").args(["rev-parse", "--short", "HEAD"]).output() {
+        if out.status.success() {
+            println!("cargo:rustc-env=STRINGDRIVER_GIT_HASH={}", String::from_utf8_lossy(&out.stdout).trim());
+        }
+    }
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    if let Some(branch) = std::fs::read_to_string(".git/HEAD").ok().and_then(|h| h.strip_prefix("ref: ").map(|r| r.trim().to_string())) {
+        println!("cargo:rerun-if-changed=.git/{}", branch);
+    }
+
     // Add system library paths - these may differ by platform
     println!("cargo:rustc-link-search=native=/usr/local/lib");
     
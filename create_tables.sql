@@ -42,9 +42,15 @@ CREATE TABLE IF NOT EXISTS machine_state (
     amp_sum_min INTEGER[] NOT NULL,
     amp_sum_max INTEGER[] NOT NULL,
     
+    -- GUI versions, git hashes and socket/firmware protocols at the time of the snapshot
+    build_info TEXT,
+    
     FOREIGN KEY (controls_id) REFERENCES controls(controls_id) ON DELETE SET NULL
 );
 
+-- Tables created before build_info existed
+ALTER TABLE machine_state ADD COLUMN IF NOT EXISTS build_info TEXT;
+
 CREATE INDEX IF NOT EXISTS idx_machine_state_recorded_at ON machine_state(recorded_at);
 CREATE INDEX IF NOT EXISTS idx_machine_state_controls_id ON machine_state(controls_id);
 CREATE INDEX IF NOT EXISTS idx_machine_state_host ON machine_state(host);
@@ -63,10 +69,14 @@ CREATE TABLE IF NOT EXISTS operations (
     
     stepper_indices INTEGER[] NOT NULL,  -- Which steppers were involved
     final_positions INTEGER[] NOT NULL,  -- Final positions after operation
+    started_at TIMESTAMP WITH TIME ZONE,  -- recorded_at is when the operation finished
     
     FOREIGN KEY (state_id) REFERENCES machine_state(state_id) ON DELETE SET NULL
 );
 
+-- Tables created before started_at existed
+ALTER TABLE operations ADD COLUMN IF NOT EXISTS started_at TIMESTAMP WITH TIME ZONE;
+
 CREATE INDEX IF NOT EXISTS idx_operations_recorded_at ON operations(recorded_at);
 CREATE INDEX IF NOT EXISTS idx_operations_state_id ON operations(state_id);
 CREATE INDEX IF NOT EXISTS idx_operations_type ON operations(operation_type);
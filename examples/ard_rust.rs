@@ -83,8 +83,6 @@ fn load_config() -> Result<(String, u32, usize)> {
 }
 
 fn main() -> Result<()> {
-    env_logger::init();
-    
     let (port, baud, steppers) = load_config()?;
     println!("Connecting to Arduino on {} at {} baud ({} steppers)", port, baud, steppers);
     
@@ -0,0 +1,57 @@
+/// Custom operation example - a string scan outside operations.rs (see src/plugins.rs)
+///
+/// Run with: cargo run --example custom_operation
+///
+/// Registering the operation is all it takes for operations_gui to offer it. To load
+/// it into an unmodified operations_gui instead, build the same code in a `cdylib`
+/// crate with `stringdriver::export_operations!(ScanAmplitude);` and list the library
+/// under PLUGINS (operations_gui built with `--features plugins`).
+
+use std::sync::Arc;
+use stringdriver::operations::operations_help;
+use stringdriver::plugins::{self, Operation, OperationContext, OperationDescriptor, OperationParam, PluginResult, Report};
+
+const PARAMS: &[OperationParam] = &[
+    OperationParam::new("SCAN_DEPTH", "Z steps below the start to scan", 1.0, 200.0, 40.0, true),
+    OperationParam::new("SCAN_STEP", "Z steps between readings", 1.0, 20.0, 4.0, true),
+];
+
+/// Lower every string's Z pair through SCAN_DEPTH, logging its amplitude at each step
+struct ScanAmplitude;
+
+impl Operation for ScanAmplitude {
+    fn descriptor(&self) -> OperationDescriptor {
+        OperationDescriptor::new("scan_amplitude", "Scan Amplitude", "Record amplitude against Z depth for every string", &[], PARAMS)
+    }
+
+    fn run(&self, ctx: &mut OperationContext<'_>) -> PluginResult<Report> {
+        let depth = ctx.param("SCAN_DEPTH").unwrap_or(40.0) as i32;
+        let step = ctx.param("SCAN_STEP").unwrap_or(4.0) as i32;
+        let mut report = Report::default();
+        let mut channel = 0;
+        while let Some((left, right)) = ctx.z_pair_for_channel(channel) {
+            let start = ctx.positions()[left];
+            for _ in 0..depth / step {
+                if ctx.stop_requested() {
+                    return Ok(report);
+                }
+                ctx.rel_move(left, -step)?;
+                ctx.rel_move(right, -step)?;
+                let amp = ctx.amp_sum().get(channel).copied().unwrap_or(0.0);
+                report.line(format!("channel {} Z {}: amp {:.1}", channel, ctx.positions()[left], amp));
+            }
+            ctx.abs_move(left, start)?;
+            ctx.abs_move(right, start)?;
+            channel += 1;
+        }
+        Ok(report)
+    }
+}
+
+fn main() {
+    if let Err(e) = plugins::register(Arc::new(ScanAmplitude)) {
+        eprintln!("✗ {}", e);
+        return;
+    }
+    print!("{}", operations_help(&stringdriver::operations::operation_catalog(&["X"])));
+}
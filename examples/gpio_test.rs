@@ -2,12 +2,8 @@
 /// 
 /// Run with: cargo run --example gpio_test --features gpiod
 
-#[path = "../src/config_loader.rs"]
-mod config_loader;
-#[path = "../src/gpio.rs"]
-mod gpio;
-
 use anyhow::Result;
+use stringdriver::gpio;
 use std::time::Duration;
 use std::thread;
 
@@ -22,7 +18,7 @@ fn main() -> Result<()> {
         loop {
             println!("--- GPIO Test Loop ---");
             
-            if let Some(ref z_pins) = gpio.z_touch_lines {
+            if gpio.z_touch_lines.is_some() {
                 match gpio.press_check(None) {
                     Ok(states) => println!("Z-Touch state: {:?}", states),
                     Err(e) => println!("Z-Touch error: {}", e),
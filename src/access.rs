@@ -0,0 +1,140 @@
+/// Who may command the instrument from outside its GUIs
+///
+/// IPC_AUTH lists clients, each with a pre-shared token and an allow-list of
+/// command names: stepper_gui socket commands (`rel_move`, `get_status`, ...)
+/// and operation names for the command inbox (`z_calibrate`, ...). `full`
+/// allows everything and `read_only` the queries. Every remote surface asks
+/// the same AccessPolicy: the Unix socket (clients that do not send
+/// `auth <token>` get the socket default), the TCP bridge (a token is always
+/// required) and COMMAND_INBOX files (`"token"` field). Without IPC_AUTH the
+/// policy is open and nothing changes.
+
+/// Socket commands that only read state; what `read_only` allows
+pub const READ_COMMANDS: &[&str] = &[
+    "get_positions",
+    "get_status",
+    "get_encoders",
+    "get_tuners",
+    "get_bow_speeds",
+    "get_x_step",
+    "get_shared",
+    "subscribe",
+    "hello",
+];
+
+/// Preset/params changes in an inbox file need this on top of the operation
+pub const SET_PARAMS: &str = "set_params";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Allow {
+    pub all: bool,
+    pub reads: bool,
+    pub commands: Vec<String>,
+}
+
+impl Allow {
+    pub fn full() -> Self {
+        Self { all: true, ..Self::default() }
+    }
+
+    /// `full`, `read_only` and command names, in any mix
+    pub fn parse(entries: &[String]) -> Self {
+        let mut allow = Self::default();
+        for entry in entries {
+            match entry.as_str() {
+                "full" => allow.all = true,
+                "read_only" => allow.reads = true,
+                command => allow.commands.push(command.to_string()),
+            }
+        }
+        allow
+    }
+
+    pub fn permits(&self, command: &str) -> bool {
+        self.all || (self.reads && READ_COMMANDS.contains(&command)) || self.commands.iter().any(|c| c == command)
+    }
+}
+
+/// An authenticated (or default) client and what it may do
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grant {
+    pub name: String,
+    pub allow: Allow,
+}
+
+#[derive(Debug, Clone)]
+pub struct AccessPolicy {
+    clients: Vec<(String, Grant)>, // (token, grant)
+    socket_default: Allow,
+    enforced: bool,
+}
+
+impl Default for AccessPolicy {
+    /// No IPC_AUTH: every client may do everything
+    fn default() -> Self {
+        Self { clients: Vec::new(), socket_default: Allow::full(), enforced: false }
+    }
+}
+
+impl AccessPolicy {
+    pub fn new(clients: Vec<(String, Grant)>, socket_default: Allow) -> Self {
+        Self { clients, socket_default, enforced: true }
+    }
+
+    /// Also accept `token` with full control (STEPPER_BRIDGE.TOKEN)
+    pub fn with_client(mut self, token: String, grant: Grant) -> Self {
+        self.clients.push((token, grant));
+        self
+    }
+
+    /// IPC_AUTH is configured; an open policy has no tokens to check
+    pub fn enforced(&self) -> bool {
+        self.enforced
+    }
+
+    /// Unix socket clients that have not authenticated
+    pub fn socket_default(&self) -> Grant {
+        Grant { name: "unauthenticated".to_string(), allow: self.socket_default.clone() }
+    }
+
+    pub fn authenticate(&self, token: &str) -> Option<Grant> {
+        self.clients
+            .iter()
+            .find(|(expected, _)| tokens_equal(expected.as_bytes(), token.as_bytes()))
+            .map(|(_, grant)| grant.clone())
+    }
+}
+
+/// Compare without stopping at the first differing byte
+fn tokens_equal(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_map_to_allow_lists() {
+        let grant = |name: &str, entries: &[&str]| Grant {
+            name: name.to_string(),
+            allow: Allow::parse(&entries.iter().map(|e| e.to_string()).collect::<Vec<_>>()),
+        };
+        let policy = AccessPolicy::new(
+            vec![
+                ("ops-token".to_string(), grant("operations_gui", &["full"])),
+                ("dash-token".to_string(), grant("dashboard", &["read_only", "z_calibrate"])),
+            ],
+            Allow::parse(&["read_only".to_string()]),
+        );
+
+        let dashboard = policy.authenticate("dash-token").unwrap();
+        assert!(dashboard.allow.permits("get_status"));
+        assert!(dashboard.allow.permits("z_calibrate"));
+        assert!(!dashboard.allow.permits("rel_move"));
+        assert!(policy.authenticate("ops-token").unwrap().allow.permits("rel_move"));
+        assert!(policy.authenticate("dash-tokem").is_none());
+        assert!(!policy.socket_default().allow.permits("abs_move"));
+        assert!(AccessPolicy::default().socket_default().allow.permits("abs_move"));
+    }
+}
@@ -0,0 +1,233 @@
+/// Alerts on critical events - so an overnight failure is seen overnight
+///
+/// Events (stepper disabled, operation failed, string break, serial lost) are
+/// filtered by ALERTS.EVENTS and sent to every ALERTS sink. Sinks shell out to
+/// curl and notify-send on a short-lived thread, so a slow or unreachable
+/// endpoint never holds up an operation. The same event and message is not
+/// re-sent within ALERTS.MIN_INTERVAL. Credentials reach curl through a
+/// private config file (CurlConfig), never its command line.
+
+use crate::config_loader::{AlertEvent, AlertSettings, AlertSink};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Seconds curl may spend on one delivery
+const SEND_TIMEOUT_SECS: &str = "15";
+
+/// What a webhook receives as JSON
+#[derive(Debug, Clone, Serialize)]
+struct Alert {
+    host: String,
+    event: &'static str,
+    message: String,
+    time: String,
+}
+
+#[derive(Debug)]
+pub struct Alerter {
+    host: String,
+    settings: AlertSettings,
+    last_sent: Mutex<HashMap<(AlertEvent, String), Instant>>,
+}
+
+impl Alerter {
+    pub fn new(host: &str, settings: AlertSettings) -> Self {
+        Self { host: host.to_string(), settings, last_sent: Mutex::new(HashMap::new()) }
+    }
+
+    /// True when `event` is enabled and there is somewhere to send it
+    pub fn wants(&self, event: AlertEvent) -> bool {
+        !self.settings.sinks.is_empty() && self.settings.events.contains(&event)
+    }
+
+    pub fn settings(&self) -> &AlertSettings {
+        &self.settings
+    }
+
+    /// Enabled and not a repeat within MIN_INTERVAL; records the send
+    fn should_send(&self, event: AlertEvent, message: &str) -> bool {
+        if !self.wants(event) {
+            return false;
+        }
+        let Ok(mut last_sent) = self.last_sent.lock() else {
+            return true;
+        };
+        let key = (event, message.to_string());
+        if last_sent.get(&key).is_some_and(|at| at.elapsed() < self.settings.min_interval) {
+            return false;
+        }
+        last_sent.insert(key, Instant::now());
+        true
+    }
+
+    /// Send an alert to every sink in the background (no-op for disabled events and repeats)
+    pub fn raise(&self, event: AlertEvent, message: &str) {
+        if !self.should_send(event, message) {
+            return;
+        }
+        tracing::warn!("ALERT {}: {}", event.key(), message);
+        let alert = Alert {
+            host: self.host.clone(),
+            event: event.key(),
+            message: message.to_string(),
+            time: chrono::Local::now().to_rfc3339(),
+        };
+        let sinks = self.settings.sinks.clone();
+        std::thread::spawn(move || {
+            for sink in &sinks {
+                if let Err(e) = send(sink, &alert) {
+                    tracing::warn!("Alert '{}' not delivered via {:?}: {}", alert.event, sink, e);
+                }
+            }
+        });
+    }
+}
+
+fn send(sink: &AlertSink, alert: &Alert) -> Result<(), String> {
+    let title = format!("{}: {}", alert.host, alert.event.replace('_', " "));
+    match sink {
+        AlertSink::Webhook { url } => {
+            let json = serde_json::to_string(alert).map_err(|e| e.to_string())?;
+            run(curl().args(["-H", "Content-Type: application/json", "--data-binary", "@-", url]), Some(&json))
+        }
+        AlertSink::Ntfy { url } => {
+            run(curl().args(["-H", &format!("Title: {}", title), "-H", "Priority: high", "--data-binary", "@-", url]), Some(&alert.message))
+        }
+        AlertSink::Email { smtp_url, from, to, user, password_env } => {
+            let mut cmd = curl();
+            cmd.args(["--ssl-reqd", "--url", smtp_url, "--mail-from", from]);
+            for rcpt in to {
+                cmd.args(["--mail-rcpt", rcpt]);
+            }
+            // Stdin carries the mail, so the login goes in a config file; kept until curl is done
+            let _credentials = match user {
+                Some(user) => {
+                    let password = password_env.as_ref().and_then(|var| std::env::var(var).ok()).unwrap_or_default();
+                    let config = CurlConfig::write(&[("user", &format!("{}:{}", user, password))])
+                        .map_err(|e| format!("cannot write curl credentials: {}", e))?;
+                    cmd.arg("-K").arg(config.path());
+                    Some(config)
+                }
+                None => None,
+            };
+            let mail = format!(
+                "From: {}\r\nTo: {}\r\nSubject: [stringdriver] {}\r\nDate: {}\r\n\r\n{}\r\n\r\n{}\r\n",
+                from, to.join(", "), title, chrono::Local::now().to_rfc2822(), alert.message, alert.time
+            );
+            run(cmd.args(["--upload-file", "-"]), Some(&mail))
+        }
+        AlertSink::Desktop => run(Command::new("notify-send").args(["-u", "critical", &title, &alert.message]), None),
+    }
+}
+
+fn curl() -> Command {
+    let mut cmd = Command::new("curl");
+    cmd.args(["-sS", "--fail", "--max-time", SEND_TIMEOUT_SECS]);
+    cmd
+}
+
+/// A curl config file (`curl -K <path>`) for options that must not appear on
+/// curl's command line, where any local user can read them with ps. Created
+/// readable by this user only and removed when dropped.
+pub(crate) struct CurlConfig {
+    path: PathBuf,
+}
+
+impl CurlConfig {
+    /// Write `name = "value"` lines, one per option
+    pub(crate) fn write(options: &[(&str, &str)]) -> std::io::Result<Self> {
+        use std::os::unix::fs::OpenOptionsExt;
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!("stringdriver-curl-{}-{}", std::process::id(), NEXT.fetch_add(1, Ordering::Relaxed)));
+        let mut file = std::fs::OpenOptions::new().write(true).create_new(true).mode(0o600).open(&path)?;
+        let config = Self { path };
+        for (name, value) in options {
+            writeln!(file, "{} = \"{}\"", name, curl_quote(value))?;
+        }
+        Ok(config)
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for CurlConfig {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Escape a value for a double-quoted curl config string
+fn curl_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted
+}
+
+/// Run a sink command, feeding `input` on stdin; Err carries its stderr
+fn run(cmd: &mut Command, input: Option<&str>) -> Result<(), String> {
+    let mut child = cmd
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("cannot run {:?}: {}", cmd.get_program(), e))?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} ({})", String::from_utf8_lossy(&output.stderr).trim(), output.status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alerts_are_filtered_and_rate_limited() {
+        let settings = AlertSettings {
+            events: vec![AlertEvent::StepperDisabled],
+            sinks: vec![AlertSink::Desktop],
+            ..AlertSettings::default()
+        };
+        let alerter = Alerter::new("stringdriver-2", settings);
+        assert!(!alerter.should_send(AlertEvent::OperationFailed, "z_adjust failed"));
+        assert!(alerter.should_send(AlertEvent::StepperDisabled, "Stepper 3 disabled"));
+        assert!(!alerter.should_send(AlertEvent::StepperDisabled, "Stepper 3 disabled"));
+        assert!(alerter.should_send(AlertEvent::StepperDisabled, "Stepper 4 disabled"));
+
+        let silent = Alerter::new("stringdriver-2", AlertSettings { events: AlertEvent::ALL.to_vec(), ..AlertSettings::default() });
+        assert!(!silent.wants(AlertEvent::SerialLost)); // No sinks
+    }
+
+    #[test]
+    fn test_curl_config_is_private_and_removed() {
+        use std::os::unix::fs::PermissionsExt;
+        let config = CurlConfig::write(&[("user", "alerts:pa\"ss\\word")]).unwrap();
+        let path = config.path().to_path_buf();
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "user = \"alerts:pa\\\"ss\\\\word\"\n");
+        drop(config);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,206 @@
+/// Operation arbitration: which operations may run together, and in what order
+///
+/// A single "operation running" flag used to hold back every request while any
+/// operation ran, even a probe that only reads the touch sensors. Operations are
+/// now sorted into two categories. Probes (descriptors marked `read_only`) never
+/// move a stepper, so they run beside anything, one of each at a time. Motion
+/// operations hold the stepper link: one runs at a time and the others queue by
+/// priority, estop > bump recovery > user command > scheduled task, first come
+/// first served within a priority, and start in that order as the running one
+/// ends. bump_check asked for while a sweep is paused (holding for fresh audio,
+/// not moving) does not wait for the sweep: the sweep runs it at its pause.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Probe,  // Reads sensors only
+    Motion, // Moves steppers
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Scheduled,    // Repeat, autostart, idle park
+    User,         // Run button, inbox, broadcast
+    BumpRecovery, // bump_check: a string may be pressed down
+    Estop,        // PARK pressed while an operation runs
+}
+
+impl Priority {
+    pub fn name(self) -> &'static str {
+        match self {
+            Priority::Scheduled => "scheduled",
+            Priority::User => "user",
+            Priority::BumpRecovery => "bump recovery",
+            Priority::Estop => "estop",
+        }
+    }
+
+    /// `requested`, raised to BumpRecovery for bump_check whoever asks for it
+    pub fn for_operation(operation: &str, requested: Priority) -> Priority {
+        if operation == "bump_check" {
+            requested.max(Priority::BumpRecovery)
+        } else {
+            requested
+        }
+    }
+}
+
+/// A motion request waiting for the running one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Request {
+    pub operation: String,
+    pub priority: Priority,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Admission {
+    Start,              // Nothing in the way; `claim` it when it starts
+    Queued(usize),      // Motion is running: position in the queue, from 1
+    Interject,          // bump_check during a paused sweep: the sweep runs it
+    Refused(String),    // The same probe is already running
+}
+
+#[derive(Debug, Default)]
+pub struct Arbiter {
+    motion: Option<String>,
+    probes: Vec<String>,
+    queue: Vec<Request>, // Highest priority first
+    interjections: VecDeque<String>,
+}
+
+impl Arbiter {
+    /// Whether `operation` may start now, queueing it if not. `paused`: the running
+    /// motion operation is holding (InstrumentState::Paused).
+    pub fn admit(&mut self, operation: &str, category: Category, priority: Priority, paused: bool) -> Admission {
+        if category == Category::Probe {
+            if self.probes.iter().any(|p| p == operation) {
+                return Admission::Refused(format!("{} is already running", operation));
+            }
+            return Admission::Start;
+        }
+        if self.motion.is_none() {
+            return Admission::Start;
+        }
+        if operation == "bump_check" && paused {
+            if !self.interjections.iter().any(|op| op == operation) {
+                self.interjections.push_back(operation.to_string());
+            }
+            return Admission::Interject;
+        }
+        // Asked for again: keep one entry, at the higher of the two priorities
+        let priority = match self.queue.iter().position(|r| r.operation == operation) {
+            Some(idx) => self.queue.remove(idx).priority.max(priority),
+            None => priority,
+        };
+        let idx = self.queue.iter().position(|r| r.priority < priority).unwrap_or(self.queue.len());
+        self.queue.insert(idx, Request { operation: operation.to_string(), priority });
+        Admission::Queued(idx + 1)
+    }
+
+    /// `operation` has started
+    pub fn claim(&mut self, operation: &str, category: Category) {
+        match category {
+            Category::Probe => self.probes.push(operation.to_string()),
+            Category::Motion => self.motion = Some(operation.to_string()),
+        }
+    }
+
+    /// `operation` has finished
+    pub fn release(&mut self, operation: &str, category: Category) {
+        match category {
+            Category::Probe => self.probes.retain(|p| p != operation),
+            Category::Motion => {
+                if self.motion.as_deref() == Some(operation) {
+                    self.motion = None;
+                    self.interjections.clear();
+                }
+            }
+        }
+    }
+
+    /// The queued request to start now that no motion operation runs
+    pub fn next_to_start(&mut self) -> Option<Request> {
+        if self.motion.is_some() || self.queue.is_empty() {
+            return None;
+        }
+        Some(self.queue.remove(0))
+    }
+
+    /// An operation the paused motion operation should run before it carries on
+    pub fn take_interjection(&mut self) -> Option<String> {
+        self.interjections.pop_front()
+    }
+
+    /// Drop every queued request below `keep` (BREAK keeps only estops); returns them
+    pub fn drop_below(&mut self, keep: Priority) -> Vec<Request> {
+        let (kept, dropped) = std::mem::take(&mut self.queue).into_iter().partition(|r| r.priority >= keep);
+        self.queue = kept;
+        dropped
+    }
+
+    pub fn queued(&self) -> &[Request] {
+        &self.queue
+    }
+
+    pub fn motion(&self) -> Option<&str> {
+        self.motion.as_deref()
+    }
+
+    pub fn probes(&self) -> &[String] {
+        &self.probes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probes_run_beside_motion_and_motion_queues_by_priority() {
+        let mut arbiter = Arbiter::default();
+        assert_eq!(arbiter.admit("right_left_move", Category::Motion, Priority::User, false), Admission::Start);
+        arbiter.claim("right_left_move", Category::Motion);
+
+        assert_eq!(arbiter.admit("bump_probe", Category::Probe, Priority::User, false), Admission::Start);
+        arbiter.claim("bump_probe", Category::Probe);
+        assert!(matches!(arbiter.admit("bump_probe", Category::Probe, Priority::User, false), Admission::Refused(_)));
+        arbiter.release("bump_probe", Category::Probe);
+        assert!(arbiter.probes().is_empty());
+
+        assert_eq!(arbiter.admit("x_home", Category::Motion, Priority::Scheduled, false), Admission::Queued(1));
+        assert_eq!(arbiter.admit("z_adjust", Category::Motion, Priority::User, false), Admission::Queued(1));
+        let bump = Priority::for_operation("bump_check", Priority::Scheduled);
+        assert_eq!(arbiter.admit("bump_check", Category::Motion, bump, false), Admission::Queued(1));
+        assert_eq!(arbiter.admit("park", Category::Motion, Priority::Estop, false), Admission::Queued(1));
+        // The same request again is not queued twice; a second user z_adjust stays behind bump_check
+        assert_eq!(arbiter.admit("z_adjust", Category::Motion, Priority::User, false), Admission::Queued(3));
+        let order: Vec<&str> = arbiter.queued().iter().map(|r| r.operation.as_str()).collect();
+        assert_eq!(order, ["park", "bump_check", "z_adjust", "x_home"]);
+
+        // Nothing starts until the running operation ends
+        assert!(arbiter.next_to_start().is_none());
+        arbiter.release("right_left_move", Category::Motion);
+        assert_eq!(arbiter.next_to_start().unwrap().operation, "park");
+
+        // BREAK drops the rest unless it is an estop
+        let dropped = arbiter.drop_below(Priority::Estop);
+        assert_eq!(dropped.len(), 3);
+        assert!(arbiter.next_to_start().is_none());
+    }
+
+    #[test]
+    fn test_bump_check_interjects_in_a_paused_sweep() {
+        let mut arbiter = Arbiter::default();
+        arbiter.claim("ping_pong_move", Category::Motion);
+        assert_eq!(arbiter.admit("bump_check", Category::Motion, Priority::BumpRecovery, true), Admission::Interject);
+        assert_eq!(arbiter.admit("bump_check", Category::Motion, Priority::BumpRecovery, true), Admission::Interject);
+        // Other motion still waits for the sweep
+        assert_eq!(arbiter.admit("z_adjust", Category::Motion, Priority::User, true), Admission::Queued(1));
+        assert_eq!(arbiter.take_interjection().as_deref(), Some("bump_check"));
+        assert!(arbiter.take_interjection().is_none());
+        assert_eq!(arbiter.motion(), Some("ping_pong_move"));
+    }
+}
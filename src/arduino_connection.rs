@@ -12,7 +12,8 @@ use std::process::Command;
 use std::sync::{Arc, Mutex};
 use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::Path;
-use anyhow::{Result, anyhow};
+use crate::error::{Error, Result};
+use crate::transport::Transport;
 use serde_json;
 
 /// Command types for IPC communication
@@ -30,6 +31,280 @@ pub enum ArduinoResponse {
     Error(String),
 }
 
+// -------------------- CmdMessenger wire format --------------------
+// Shared by ArduinoConnectionManager and stepper_gui's serial threads. The
+// firmware is built with CmdMessenger(Serial, ',', ';', '/'): binary arguments
+// escape ',' ';' '/' and NUL with a leading '/'.
+
+const FIELD_SEPARATOR: u8 = b',';
+const COMMAND_SEPARATOR: u8 = b';';
+const ESCAPE_CHAR: u8 = b'/';
+
+pub fn escape_cmdmessenger_bytes(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() * 2); // May double in size if all bytes escaped
+    for &b in data {
+        match b {
+            FIELD_SEPARATOR | COMMAND_SEPARATOR | ESCAPE_CHAR | 0 => {
+                out.push(ESCAPE_CHAR);
+                out.push(b);
+            }
+            _ => out.push(b),
+        }
+    }
+    out
+}
+
+/// "<id>,<stepper i16>,<value i32>;" as PyCmdMessenger sends it ("il" format).
+/// v1/v2 firmware reads the value as a 2-byte int, i.e. its low half.
+pub fn encode_cmd_bin(cmd_id: u8, stepper_idx: i16, value: i32) -> Vec<u8> {
+    let mut buf: Vec<u8> = Vec::with_capacity(20);
+    buf.extend_from_slice(cmd_id.to_string().as_bytes());
+    buf.push(FIELD_SEPARATOR);
+    buf.extend_from_slice(&escape_cmdmessenger_bytes(&stepper_idx.to_le_bytes()));
+    buf.push(FIELD_SEPARATOR);
+    buf.extend_from_slice(&escape_cmdmessenger_bytes(&value.to_le_bytes()));
+    buf.push(COMMAND_SEPARATOR);
+    buf
+}
+
+/// CRC-8 (polynomial 0x07, init 0) over a reply's value bytes, for firmware
+/// that appends one (ARD_CHECKSUM)
+pub fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &b in data {
+        crc ^= b;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 { (crc << 1) ^ 0x07 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DecodeState {
+    Seeking, // Outside a frame: debug prints, line ends, stale bytes
+    Field,
+    Escape,
+}
+
+/// Resumable decoder for one reply "<id>,<arg>,<arg>...;" of `num_values`
+/// binary arguments `width` bytes wide, fed with whatever each read returned.
+///
+/// Bytes outside a frame are skipped, so debug prints around the reply do no
+/// harm. A frame is only accepted with exactly `num_values` arguments of
+/// `width` bytes each (plus a 1-byte CRC-8 argument when `checksum` is set);
+/// anything else is dropped and the bytes after its start are searched again.
+#[derive(Debug)]
+pub struct FrameDecoder {
+    id: Vec<u8>,
+    num_values: usize,
+    width: usize,
+    checksum: bool,
+    state: DecodeState,
+    digits: Vec<u8>,       // Candidate id while seeking
+    fields: Vec<Vec<u8>>,  // Unescaped arguments of the frame in progress
+    raw: Vec<u8>,          // Bytes since the frame's id, replayed if it is dropped
+    pub skipped: usize,    // Non-frame bytes discarded
+    pub rejected: usize,   // Frames dropped as malformed
+}
+
+impl FrameDecoder {
+    pub fn new(id: u8, num_values: usize, width: usize, checksum: bool) -> Self {
+        Self {
+            id: id.to_string().into_bytes(),
+            num_values,
+            width,
+            checksum,
+            state: DecodeState::Seeking,
+            digits: Vec::new(),
+            fields: Vec::new(),
+            raw: Vec::new(),
+            skipped: 0,
+            rejected: 0,
+        }
+    }
+
+    /// Decoder for the reply to a query command such as b"1;" (replies echo the id)
+    pub fn for_query(cmd: &[u8], num_values: usize, width: usize, checksum: bool) -> Option<Self> {
+        let id = std::str::from_utf8(cmd).ok()?.trim_end_matches(';').parse().ok()?;
+        Some(Self::new(id, num_values, width, checksum))
+    }
+
+    /// Feed received bytes; returns the values once a valid frame has arrived.
+    /// Bytes after that frame are ignored.
+    pub fn push(&mut self, bytes: &[u8]) -> Option<Vec<i32>> {
+        let mut input: std::collections::VecDeque<u8> = bytes.iter().copied().collect();
+        while let Some(b) = input.pop_front() {
+            match self.step(b) {
+                Ok(Some(values)) => return Some(values),
+                Ok(None) => {}
+                Err(()) => {
+                    // Not a frame after all: search again from just past its first byte
+                    self.rejected += 1;
+                    self.skipped += 1;
+                    self.state = DecodeState::Seeking;
+                    self.fields.clear();
+                    let replay: Vec<u8> = self.raw.drain(..).skip(1).collect();
+                    for &r in replay.iter().rev() {
+                        input.push_front(r);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn field_limit(&self) -> usize {
+        self.num_values + usize::from(self.checksum)
+    }
+
+    fn field_width(&self, index: usize) -> usize {
+        if index < self.num_values { self.width } else { 1 }
+    }
+
+    fn step(&mut self, b: u8) -> std::result::Result<Option<Vec<i32>>, ()> {
+        if self.state != DecodeState::Seeking {
+            self.raw.push(b);
+        }
+        match self.state {
+            DecodeState::Seeking => {
+                if b.is_ascii_digit() {
+                    self.digits.push(b);
+                } else if b == FIELD_SEPARATOR && self.digits == self.id {
+                    self.raw = self.digits.drain(..).collect();
+                    self.raw.push(b);
+                    self.fields = vec![Vec::new()];
+                    self.state = DecodeState::Field;
+                } else {
+                    self.skipped += self.digits.len() + 1;
+                    self.digits.clear();
+                }
+                Ok(None)
+            }
+            DecodeState::Escape => {
+                self.state = DecodeState::Field;
+                self.push_value_byte(b)
+            }
+            DecodeState::Field => match b {
+                ESCAPE_CHAR => {
+                    self.state = DecodeState::Escape;
+                    Ok(None)
+                }
+                FIELD_SEPARATOR => {
+                    let index = self.fields.len() - 1;
+                    if self.fields[index].len() != self.field_width(index) || self.fields.len() >= self.field_limit() {
+                        return Err(());
+                    }
+                    self.fields.push(Vec::new());
+                    Ok(None)
+                }
+                COMMAND_SEPARATOR => self.finish().map(Some),
+                _ => self.push_value_byte(b),
+            },
+        }
+    }
+
+    fn push_value_byte(&mut self, b: u8) -> std::result::Result<Option<Vec<i32>>, ()> {
+        let index = self.fields.len() - 1;
+        if self.fields[index].len() >= self.field_width(index) {
+            return Err(()); // Longer than any argument: this was text, not a frame
+        }
+        self.fields[index].push(b);
+        Ok(None)
+    }
+
+    fn finish(&mut self) -> std::result::Result<Vec<i32>, ()> {
+        let widths_ok = self.fields.iter().enumerate().all(|(i, f)| f.len() == self.field_width(i));
+        if self.fields.len() != self.field_limit() || !widths_ok {
+            return Err(());
+        }
+        let values: Vec<u8> = self.fields[..self.num_values].concat();
+        if self.checksum && self.fields[self.num_values][0] != crc8(&values) {
+            return Err(());
+        }
+        self.state = DecodeState::Seeking;
+        self.raw.clear();
+        Ok(values
+            .chunks_exact(self.width)
+            .map(|b| match self.width {
+                4 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+                _ => i16::from_le_bytes([b[0], b[1]]) as i32,
+            })
+            .collect())
+    }
+}
+
+/// Read timeouts just mean the reply has not (fully) arrived yet
+pub fn is_timeout(err: &std::io::Error) -> bool {
+    err.kind() == std::io::ErrorKind::TimedOut || err.kind() == std::io::ErrorKind::WouldBlock
+}
+
+/// Identifies a board on the USB bus whichever /dev/tty* it enumerated as
+/// (ARD_USB / ARD_T_USB in string_driver.yaml). Unset fields match anything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsbMatch {
+    pub vid: Option<u16>,
+    pub pid: Option<u16>,
+    pub serial_number: Option<String>,
+}
+
+impl UsbMatch {
+    pub fn matches(&self, info: &serialport::UsbPortInfo) -> bool {
+        self.vid.is_none_or(|vid| vid == info.vid)
+            && self.pid.is_none_or(|pid| pid == info.pid)
+            && self.serial_number.as_deref().is_none_or(|sn| info.serial_number.as_deref() == Some(sn))
+    }
+}
+
+/// Device path a board was bound to, with a description for the log
+#[derive(Debug, Clone)]
+pub struct ResolvedPort {
+    pub path: String,
+    pub detail: String,
+}
+
+fn describe_usb(info: &serialport::UsbPortInfo) -> String {
+    format!(
+        "USB {:04x}:{:04x} serial {} ({})",
+        info.vid,
+        info.pid,
+        info.serial_number.as_deref().unwrap_or("-"),
+        info.product.as_deref().unwrap_or("unknown product")
+    )
+}
+
+/// Current device path for the `role` board ("main", "tuner").
+/// Without a USB match the configured path is used as is. Otherwise the USB
+/// ports are searched: a single match wins, no match falls back to the
+/// configured path, and several matches are an error (add SERIAL to the match).
+pub fn resolve_port(role: &str, configured_path: &str, usb: Option<&UsbMatch>) -> Result<ResolvedPort> {
+    let Some(usb) = usb else {
+        return Ok(ResolvedPort { path: configured_path.to_string(), detail: "configured path".to_string() });
+    };
+    let ports = serialport::available_ports().map_err(|e| Error::Serial(format!("Cannot list serial ports: {}", e)))?;
+    let found: Vec<(String, serialport::UsbPortInfo)> = ports
+        .into_iter()
+        .filter_map(|p| match p.port_type {
+            serialport::SerialPortType::UsbPort(info) if usb.matches(&info) => Some((p.port_name, info)),
+            _ => None,
+        })
+        .collect();
+    match found.as_slice() {
+        [(path, info)] => Ok(ResolvedPort { path: path.clone(), detail: describe_usb(info) }),
+        [] => Ok(ResolvedPort {
+            path: configured_path.to_string(),
+            detail: format!("no USB device matched {:?}, using configured path", usb),
+        }),
+        several => Err(Error::ConfigInvalid(format!(
+            "{} board: {} USB devices match {:?} ({}); set SERIAL to choose one",
+            role,
+            several.len(),
+            usb,
+            several.iter().map(|(path, _)| path.as_str()).collect::<Vec<_>>().join(", ")
+        ))),
+    }
+}
+
 /// Unix socket path for IPC (fixed path so both processes can find it)
 /// Uses port path to create unique socket per Arduino port
 fn get_socket_path(port_path: &str) -> String {
@@ -38,11 +313,26 @@ fn get_socket_path(port_path: &str) -> String {
     format!("/tmp/arduino_connection_{}.sock", port_id)
 }
 
+type OpenFn = dyn Fn(&str) -> serialport::Result<Box<dyn serialport::SerialPort>> + Send;
+
+/// Opens the serial device in place of serialport::new (tests pass a loopback port)
+struct PortOpener(Box<OpenFn>);
+
+impl std::fmt::Debug for PortOpener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PortOpener")
+    }
+}
+
 /// Arduino connection manager that handles a single shared connection
 #[derive(Debug)]
 pub struct ArduinoConnectionManager {
     port: Option<Box<dyn serialport::SerialPort>>,
-    port_path: String,
+    port_path: String,         // Configured path; also names the IPC socket
+    usb: Option<UsbMatch>,     // When set, the device path is looked up on every (re)connect
+    device_path: String,       // Path actually opened
+    opener: Option<PortOpener>, // None opens the real device
+    transport: Transport,      // Serial device or BLE UART (ARD_TRANSPORT)
     connected: bool,
 }
 
@@ -50,29 +340,73 @@ impl ArduinoConnectionManager {
     pub fn new(port_path: String) -> Self {
         Self {
             port: None,
+            device_path: port_path.clone(),
             port_path,
+            usb: None,
+            opener: None,
+            transport: Transport::Serial,
             connected: false,
         }
     }
     
+    /// Find the board by USB VID/PID/serial number instead of trusting the configured path
+    pub fn with_usb_match(mut self, usb: UsbMatch) -> Self {
+        self.usb = Some(usb);
+        self
+    }
+    
+    /// Reach the board over `transport`; a BLE UART is found by name/MAC on every (re)connect
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.transport = transport;
+        self
+    }
+    
+    /// Open ports through `opener` instead of the serial device. Such ports are
+    /// not assumed to reset the board, so connect() does not wait for a reboot.
+    pub fn with_port_opener(
+        mut self,
+        opener: impl Fn(&str) -> serialport::Result<Box<dyn serialport::SerialPort>> + Send + 'static,
+    ) -> Self {
+        self.opener = Some(PortOpener(Box::new(opener)));
+        self
+    }
+    
     pub fn connect(&mut self) -> Result<()> {
         // Close existing connection if any
         self.disconnect();
         
-        let port_path = self.port_path.clone();
-        self.kill_port_users(&port_path);
-        match serialport::new(self.port_path.as_str(), 115200)
-            .timeout(Duration::from_secs(2))
-            .open() {
+        let serial = self.transport.resets_board();
+        if serial {
+            // USB re-enumeration can move the board to a different /dev/tty*
+            let resolved = resolve_port("main", &self.port_path, self.usb.as_ref())?;
+            tracing::info!("main board bound to {} ({})", resolved.path, resolved.detail);
+            self.device_path = resolved.path;
+        }
+        
+        let port_path = self.device_path.clone();
+        let opened = match &self.opener {
+            Some(opener) => (opener.0)(&port_path),
+            None => {
+                if serial {
+                    self.kill_port_users(&port_path);
+                }
+                self.transport.open(&port_path, Duration::from_secs(2))
+            }
+        };
+        #[cfg(feature = "fault-injection")]
+        let opened = opened.map(crate::fault_injection::wrap_port);
+        match opened {
             Ok(port) => {
-                std::thread::sleep(Duration::from_millis(2000)); // Arduino reset delay
+                if self.opener.is_none() && serial {
+                    std::thread::sleep(Duration::from_millis(2000)); // Arduino reset delay
+                }
                 self.port = Some(port);
                 self.connected = true;
                 Ok(())
             }
             Err(e) => {
                 self.connected = false;
-                Err(anyhow!("Connection failed: {}", e))
+                Err(Error::Serial(format!("Connection failed: {}", e)))
             }
         }
     }
@@ -84,7 +418,11 @@ impl ArduinoConnectionManager {
     
     /// Check if the port file exists (device is available)
     fn port_available(&self) -> bool {
-        Path::new(&self.port_path).exists()
+        if self.usb.is_some() || self.opener.is_some() || !self.transport.resets_board() {
+            // The device may have come back under another name (or is not a device); connect() finds it
+            return true;
+        }
+        Path::new(&self.device_path).exists()
     }
     
     /// Attempt to reconnect if connection is lost
@@ -95,7 +433,7 @@ impl ArduinoConnectionManager {
         
         // Check if port is available before attempting connection
         if !self.port_available() {
-            return Err(anyhow!("Port {} not available", self.port_path));
+            return Err(Error::Serial(format!("Port {} not available", self.device_path)));
         }
         
         // Attempt to reconnect
@@ -103,8 +441,8 @@ impl ArduinoConnectionManager {
     }
     
     /// Check if an error indicates connection loss
-    fn is_connection_error(err: &anyhow::Error) -> bool {
-        let err_str = format!("{:#}", err);
+    fn is_connection_error(err: &dyn std::fmt::Display) -> bool {
+        let err_str = err.to_string();
         err_str.contains("Broken pipe")
             || err_str.contains("Connection reset")
             || err_str.contains("Device not found")
@@ -131,42 +469,11 @@ impl ArduinoConnectionManager {
         }
     }
     
-    fn pack_i16_le(v: i16) -> [u8; 2] {
-        i16::to_le_bytes(v)
-    }
-    
-    fn pack_i32_le(v: i32) -> [u8; 4] {
-        i32::to_le_bytes(v)
-    }
-    
-    fn escape_cmdmessenger_bytes(bytes: &[u8]) -> Vec<u8> {
-        let mut escaped = Vec::new();
-        for &b in bytes {
-            if b == b',' || b == b';' || b == b'0' || b == b'1' || b == b'2' || b == b'3' || b == b'4' || b == b'5' || b == b'6' || b == b'7' || b == b'8' || b == b'9' {
-                escaped.push(0x47); // ESC
-                escaped.push(b ^ 0x20);
-            } else {
-                escaped.push(b);
-            }
-        }
-        escaped
-    }
-    
     fn send_cmd_bin(&mut self, cmd_id: u8, stepper_idx: i16, value: i32) -> Result<()> {
         // Ensure connection before sending
         self.ensure_connected()?;
         
-        let mut buf: Vec<u8> = Vec::with_capacity(20);
-        buf.push(b'0' + cmd_id);
-        buf.push(b',');
-        let stepper_bytes = Self::pack_i16_le(stepper_idx);
-        let escaped_stepper = Self::escape_cmdmessenger_bytes(&stepper_bytes);
-        buf.extend_from_slice(&escaped_stepper);
-        buf.push(b',');
-        let value_bytes = Self::pack_i32_le(value);
-        let escaped_value = Self::escape_cmdmessenger_bytes(&value_bytes);
-        buf.extend_from_slice(&escaped_value);
-        buf.push(b';');
+        let buf = encode_cmd_bin(cmd_id, stepper_idx, value);
         
         match self.port.as_mut() {
             Some(p) => {
@@ -175,22 +482,22 @@ impl ArduinoConnectionManager {
                         match p.flush() {
                             Ok(_) => Ok(()),
                             Err(e) => {
-                                if Self::is_connection_error(&anyhow!(e)) {
+                                if Self::is_connection_error(&e) {
                                     self.disconnect();
                                 }
-                                Err(anyhow!("Flush error: {}", e))
+                                Err(Error::Serial(format!("Flush error: {}", e)))
                             }
                         }
                     }
                     Err(e) => {
-                        if Self::is_connection_error(&anyhow!(e)) {
+                        if Self::is_connection_error(&e) {
                             self.disconnect();
                         }
-                        Err(anyhow!("Write error: {}", e))
+                        Err(Error::Serial(format!("Write error: {}", e)))
                     }
                 }
             }
-            None => Err(anyhow!("Port not connected")),
+            None => Err(Error::Serial("Port not connected".to_string())),
         }
     }
     
@@ -270,7 +577,7 @@ impl ArduinoConnectionManager {
         // Ensure connection before reading
         self.ensure_connected()?;
         
-        let port = self.port.as_mut().ok_or_else(|| anyhow!("Port not available"))?;
+        let port = self.port.as_mut().ok_or_else(|| Error::Serial("Port not available".to_string()))?;
         let send = b"1;";
         
         // Flush input buffer before command
@@ -282,26 +589,26 @@ impl ArduinoConnectionManager {
                 match port.flush() {
                     Ok(_) => {}
                     Err(e) => {
-                        if Self::is_connection_error(&anyhow!(e)) {
+                        if Self::is_connection_error(&e) {
                             self.disconnect();
                         }
-                        return Err(anyhow!("Flush error: {}", e));
+                        return Err(Error::Serial(format!("Flush error: {}", e)));
                     }
                 }
             }
             Err(e) => {
-                if Self::is_connection_error(&anyhow!(e)) {
+                if Self::is_connection_error(&e) {
                     self.disconnect();
                 }
-                return Err(anyhow!("Write error: {}", e));
+                return Err(Error::Serial(format!("Write error: {}", e)));
             }
         }
         
         // Wait for Arduino to send positions
         std::thread::sleep(Duration::from_millis(50));
         
-        // Read response
-        let mut buffer = Vec::new();
+        // Read response, decoding CmdMessenger "1,<escaped-binary>,...;" as it arrives
+        let mut decoder = FrameDecoder::new(1, num_steppers, 2, false);
         let start_time = std::time::Instant::now();
         let timeout = Duration::from_secs(2);
         
@@ -309,74 +616,32 @@ impl ArduinoConnectionManager {
             let mut chunk = vec![0u8; 256];
             match port.read(&mut chunk) {
                 Ok(bytes_read) if bytes_read > 0 => {
-                    buffer.extend_from_slice(&chunk[..bytes_read]);
-                    if buffer.iter().any(|&b| b == b';') {
-                        break;
+                    if let Some(positions) = decoder.push(&chunk[..bytes_read]) {
+                        return Ok(positions);
                     }
                 }
                 Ok(_) => {
                     std::thread::sleep(Duration::from_millis(10));
                 }
                 Err(e) => {
-                    let err_str = e.to_string();
-                    if err_str.contains("timeout") || err_str.contains("TimedOut") {
+                    if is_timeout(&e) {
                         std::thread::sleep(Duration::from_millis(10));
                         continue;
                     }
                     // Connection error - mark as disconnected
-                    if Self::is_connection_error(&anyhow!(&err_str)) {
+                    if Self::is_connection_error(&e) {
                         self.disconnect();
                     }
-                    return Err(anyhow!("Read error: {}", e));
+                    return Err(Error::Serial(format!("Read error: {}", e)));
                 }
             }
         }
         
-        if buffer.is_empty() || !buffer.iter().any(|&b| b == b';') {
-            return Err(anyhow!("Failed to read positions from Arduino"));
-        }
-        
-        // Decode CmdMessenger: "1,<escaped-binary>;"
-        let mut data_bytes: Vec<u8> = Vec::new();
-        let mut seen_comma = false;
-        let mut i = 0usize;
-        while i < buffer.len() {
-            let b = buffer[i];
-            if !seen_comma {
-                if b == b',' { seen_comma = true; }
-                i += 1;
-                continue;
-            }
-            if b == b';' { break; }
-            if b == b'/' {
-                if i + 1 < buffer.len() {
-                    data_bytes.push(buffer[i + 1]);
-                    i += 2;
-                    continue;
-                } else {
-                    break;
-                }
-            }
-            if b == b',' { i += 1; continue; }
-            data_bytes.push(b);
-            i += 1;
-        }
-        
-        let expected_bytes = num_steppers * 2;
-        if data_bytes.len() < expected_bytes {
-            return Err(anyhow!("Expected at least {} bytes, got {}", expected_bytes, data_bytes.len()));
-        }
-        
-        let mut positions = vec![0i32; num_steppers];
-        for idx in 0..num_steppers {
-            let lo = idx * 2;
-            let hi = lo + 1;
-            if hi < data_bytes.len() {
-                positions[idx] = i16::from_le_bytes([data_bytes[lo], data_bytes[hi]]) as i32;
-            }
-        }
-        
-        Ok(positions)
+        tracing::warn!(
+            "Failed to read positions from Arduino ({} malformed frame(s), {} stray byte(s))",
+            decoder.rejected, decoder.skipped
+        );
+        Err(Error::SerialTimeout { what: "positions".to_string(), after: timeout })
     }
     
     /// Start background connection monitoring thread
@@ -413,7 +678,8 @@ impl ArduinoConnectionManager {
         // Start connection monitoring thread
         Self::start_connection_monitor(Arc::clone(&manager));
         
-        let listener = UnixListener::bind(&socket_path)?;
+        let listener = UnixListener::bind(&socket_path)
+            .map_err(|e| Error::io(format!("Cannot bind IPC socket {}", socket_path), e))?;
         
         std::thread::spawn(move || {
             for stream in listener.incoming() {
@@ -476,35 +742,34 @@ impl ArduinoConnectionClient {
     }
     
     fn send_command(&self, cmd: ArduinoCommand) -> Result<ArduinoResponse> {
-        let mut stream = UnixStream::connect(&self.socket_path)?;
-        let cmd_bytes = serde_json::to_vec(&cmd)?;
-        stream.write_all(&cmd_bytes)?;
-        stream.flush()?;
+        let link = |e: &dyn std::fmt::Display| Error::StepperLink(format!("Arduino IPC socket {}: {}", self.socket_path, e));
+        let mut stream = UnixStream::connect(&self.socket_path).map_err(|e| link(&e))?;
+        let cmd_bytes = serde_json::to_vec(&cmd).map_err(|e| link(&e))?;
+        stream.write_all(&cmd_bytes).and_then(|_| stream.flush()).map_err(|e| link(&e))?;
         
         let mut buf = vec![0u8; 1024];
-        let len = stream.read(&mut buf)?;
-        let response: ArduinoResponse = serde_json::from_slice(&buf[..len])?;
-        Ok(response)
+        let len = stream.read(&mut buf).map_err(|e| link(&e))?;
+        serde_json::from_slice(&buf[..len]).map_err(|e| link(&e))
     }
     
     pub fn rel_move(&self, stepper: usize, delta: i32) -> Result<()> {
         match self.send_command(ArduinoCommand::RelMove { stepper, delta })? {
             ArduinoResponse::Ok => Ok(()),
-            ArduinoResponse::Error(e) => Err(anyhow!("{}", e)),
+            ArduinoResponse::Error(e) => Err(Error::Serial(e)),
         }
     }
     
     pub fn abs_move(&self, stepper: usize, position: i32) -> Result<()> {
         match self.send_command(ArduinoCommand::AbsMove { stepper, position })? {
             ArduinoResponse::Ok => Ok(()),
-            ArduinoResponse::Error(e) => Err(anyhow!("{}", e)),
+            ArduinoResponse::Error(e) => Err(Error::Serial(e)),
         }
     }
     
     pub fn reset(&self, stepper: usize, position: i32) -> Result<()> {
         match self.send_command(ArduinoCommand::Reset { stepper, position })? {
             ArduinoResponse::Ok => Ok(()),
-            ArduinoResponse::Error(e) => Err(anyhow!("{}", e)),
+            ArduinoResponse::Error(e) => Err(Error::Serial(e)),
         }
     }
 }
@@ -0,0 +1,220 @@
+/// Simulated audio response for the loopback board (closed-loop sandbox)
+///
+/// Closes the loop without strings or audio_monitor: `AudioSim` turns the Z
+/// heights and X position of the emulated board into the partials audio_monitor
+/// would report and publishes them through a PartialsWriter, the way the GUI's
+/// partials reader thread does. The model is deliberately simple: a string is
+/// silent until its Z pair comes down past the contact height, then gets louder
+/// (amplitude sum) and richer (voice count) the further it is pressed, loudest
+/// with the carriage mid-travel, with seeded noise on every partial so runs are
+/// repeatable. With `LoopbackBoard` standing in as the StepperOperations backend,
+/// z_adjust, z_hold and sweeps run end to end in `cargo test`.
+///
+/// Partials below the voice floor carry only the noise floor: give the host a
+/// VOICE_FLOOR above `noise_floor` so they are not counted as voices.
+
+use crate::error::{Error, Result};
+use crate::loopback_serial::LoopbackBoard;
+use crate::operations::StepperOperations;
+use crate::partials_buffer::{PartialsFrame, PartialsWriter};
+use crate::topology::Topology;
+
+/// Shape of the simulated response
+#[derive(Debug, Clone)]
+pub struct AudioSimSettings {
+    pub partials: usize,      // Partials per channel, as audio_monitor's NUM_PARTIALS
+    pub amp_per_step: f32,    // Amplitude sum gained per Z step past contact
+    pub steps_per_voice: f32, // Z steps past contact for each further voice
+    pub x_max: Option<i32>,   // X travel; None leaves X out of the model
+    pub noise: f32,           // Relative noise on each sounding partial (0.05 = ±5%)
+    pub noise_floor: f32,     // Largest amplitude of a silent partial
+    pub seed: u64,
+}
+
+impl Default for AudioSimSettings {
+    fn default() -> Self {
+        Self { partials: 12, amp_per_step: 2.0, steps_per_voice: 10.0, x_max: None, noise: 0.05, noise_floor: 0.2, seed: 1 }
+    }
+}
+
+/// One simulated string
+#[derive(Debug, Clone)]
+pub struct SimString {
+    pub z_pair: (usize, usize), // Stepper indices of its Z pair
+    pub channel: usize,         // Audio channel it sounds on (CHANNEL_MAP)
+    pub contact: i32,           // Mean Z position where the bow first touches it
+    pub fundamental: f32,       // Hz
+}
+
+#[derive(Debug)]
+pub struct AudioSim {
+    pub settings: AudioSimSettings,
+    pub strings: Vec<SimString>,
+    x_index: Option<usize>,
+    rng: u64,
+}
+
+impl AudioSim {
+    /// A string per topology string, contacting at `contacts[string]` (0 for strings past the end)
+    pub fn new(topology: &Topology, contacts: &[i32], settings: AudioSimSettings) -> Self {
+        let strings = (0..topology.string_num())
+            .filter_map(|string| {
+                Some(SimString {
+                    z_pair: topology.z_pair(string)?,
+                    channel: topology.channel_for_string(string)?,
+                    contact: contacts.get(string).copied().unwrap_or(0),
+                    fundamental: 110.0 * (string + 1) as f32,
+                })
+            })
+            .collect();
+        let rng = settings.seed.max(1);
+        Self { settings, strings, x_index: topology.x_index(), rng }
+    }
+
+    /// Noise-free (amplitude sum, voice count) of `string` at `positions`
+    pub fn response(&self, string: &SimString, positions: &[i32]) -> (f32, usize) {
+        let z = |idx: usize| positions.get(idx).copied().unwrap_or(0) as f32;
+        // More negative Z is closer to the string
+        let depth = string.contact as f32 - (z(string.z_pair.0) + z(string.z_pair.1)) / 2.0;
+        if depth <= 0.0 {
+            return (0.0, 0);
+        }
+        let voices = ((depth / self.settings.steps_per_voice.max(1.0)).ceil() as usize).clamp(1, self.settings.partials);
+        (self.settings.amp_per_step * depth * self.bowing_factor(positions), voices)
+    }
+
+    /// Half as loud at either end of the X travel as in the middle
+    fn bowing_factor(&self, positions: &[i32]) -> f32 {
+        let (Some(x_index), Some(x_max)) = (self.x_index, self.settings.x_max.filter(|&max| max > 0)) else {
+            return 1.0;
+        };
+        let along = (positions.get(x_index).copied().unwrap_or(0) as f32 / x_max as f32).clamp(0.0, 1.0);
+        0.5 + 0.5 * (along * std::f32::consts::PI).sin()
+    }
+
+    /// Fill `frame` with the partials for `positions`; one channel per mapped channel
+    pub fn fill(&mut self, frame: &mut PartialsFrame, positions: &[i32]) -> bool {
+        let channels = self.strings.iter().map(|s| s.channel + 1).max().unwrap_or(0);
+        let (channels, partials) = frame.reshape(channels, self.settings.partials);
+        for ch in 0..channels {
+            let floor = self.settings.noise_floor;
+            for (k, partial) in frame.channel_mut(ch).iter_mut().enumerate() {
+                *partial = (110.0 * (k + 1) as f32, floor * self.next_unit());
+            }
+        }
+        for i in 0..self.strings.len() {
+            let string = self.strings[i].clone();
+            if string.channel >= channels {
+                continue;
+            }
+            let (amp_sum, voices) = self.response(&string, positions);
+            // 1/k rolloff over the sounding partials, scaled so they add up to amp_sum
+            let harmonic: f32 = (1..=voices).map(|k| 1.0 / k as f32).sum();
+            for k in 0..partials {
+                let freq = string.fundamental * (k + 1) as f32;
+                let amp = if k < voices {
+                    let jitter = 1.0 + self.settings.noise * (2.0 * self.next_unit() - 1.0);
+                    amp_sum / (k + 1) as f32 / harmonic * jitter
+                } else {
+                    self.settings.noise_floor * self.next_unit()
+                };
+                frame.channel_mut(string.channel)[k] = (freq, amp);
+            }
+        }
+        channels > 0
+    }
+
+    /// Publish the partials for `positions` to the analysis end of `writer`
+    pub fn publish(&mut self, writer: &mut PartialsWriter, positions: &[i32]) -> bool {
+        writer.write(|frame| self.fill(frame, positions))
+    }
+
+    /// Uniform in [0, 1) (xorshift64*)
+    fn next_unit(&mut self) -> f32 {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        (self.rng.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+/// Operations drives the emulated board's stepper model directly, without the serial link
+impl StepperOperations for LoopbackBoard {
+    fn rel_move(&mut self, stepper: usize, delta: i32) -> Result<()> {
+        let mut positions = self.positions();
+        let position = positions.get_mut(stepper).ok_or_else(|| Error::Other(format!("No stepper {} on the loopback board", stepper)))?;
+        *position += delta;
+        self.set_positions(&positions);
+        Ok(())
+    }
+
+    fn abs_move(&mut self, stepper: usize, position: i32) -> Result<()> {
+        let current = self.positions().get(stepper).copied().ok_or_else(|| Error::Other(format!("No stepper {} on the loopback board", stepper)))?;
+        self.rel_move(stepper, position - current)
+    }
+
+    fn reset(&mut self, stepper: usize, position: i32) -> Result<()> {
+        self.abs_move(stepper, position)
+    }
+
+    // The stepper model has no driver current; Operations keeps the lockout itself
+    fn disable(&mut self, _stepper: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn enable(&mut self, _stepper: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn reported_positions(&mut self) -> Result<Option<Vec<i32>>> {
+        Ok(Some(self.positions()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::partials_buffer::triple_buffer;
+    use crate::topology::TunerLayout;
+
+    fn sim(x_max: Option<i32>) -> AudioSim {
+        let topology = Topology::new(2, Some(1), Some(0), TunerLayout::Main(Vec::new()), None);
+        AudioSim::new(&topology, &[-20, -30], AudioSimSettings { x_max, ..AudioSimSettings::default() })
+    }
+
+    #[test]
+    fn test_louder_and_richer_past_contact() {
+        let quiet_x = sim(None);
+        let string = quiet_x.strings[0].clone();
+        assert_eq!(string.z_pair, (1, 2));
+        assert_eq!(quiet_x.response(&string, &[0, -10, -10, 0, 0]), (0.0, 0));
+        let (amp, voices) = quiet_x.response(&string, &[0, -30, -30, 0, 0]);
+        assert_eq!((amp, voices), (20.0, 1));
+        let (louder, more) = quiet_x.response(&string, &[0, -60, -40, 0, 0]);
+        assert!(louder > amp && more > voices);
+
+        // X at the ends of the travel halves the level
+        let with_x = sim(Some(1000));
+        let middle = with_x.response(&string, &[500, -30, -30, 0, 0]).0;
+        let end = with_x.response(&string, &[0, -30, -30, 0, 0]).0;
+        assert!((end - middle / 2.0).abs() < 1e-3, "{} vs {}", end, middle);
+    }
+
+    #[test]
+    fn test_published_frames_carry_the_response() {
+        let mut sim = sim(None);
+        let (mut writer, mut reader) = triple_buffer();
+        let positions = [0, -60, -60, -10, -10];
+        assert!(sim.publish(&mut writer, &positions));
+        let frame = reader.take_new().unwrap();
+        assert_eq!(frame.num_channels(), 2);
+        let amp_sum: f32 = frame.channel(0).iter().map(|p| p.1).sum();
+        let (expected, voices) = sim.response(&sim.strings[0].clone(), &positions);
+        // Noise on the sounding partials, the noise floor on the rest
+        assert!((amp_sum - expected).abs() < expected * 0.05 + 0.2 * 12.0, "{} vs {}", amp_sum, expected);
+        assert_eq!(frame.channel(0).iter().filter(|p| p.1 > 0.2).count(), voices);
+        assert_eq!(frame.channel(0)[1].0, 220.0);
+        // String 1 is clear of its strings: only the noise floor
+        assert!(frame.channel(1).iter().all(|p| p.1 < 0.2));
+    }
+}
@@ -0,0 +1,177 @@
+/// Audio-triggered start for unattended installations
+///
+/// With an AUDIO_TRIGGER block the instrument can wait armed, parked and with
+/// nothing running, for someone to play. Once any channel's amp sum stays above
+/// THRESHOLD for SECONDS, the trigger starts its OPERATION (performance_mode or a
+/// sweep); once every channel stays below it for SILENCE_MINUTES, the operation
+/// is stopped, the instrument parks and the trigger is armed again. Operations
+/// owns the trigger and feeds it the analysis; operations_gui acts on what it
+/// returns and shows it in its "Audio trigger" panel.
+
+use crate::config_loader::AudioTriggerSettings;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerState {
+    Disarmed,                                  // Not listening
+    Armed { loud_since: Option<Instant> },     // Waiting for sound
+    Running { quiet_since: Option<Instant> },  // The operation is running, waiting for silence
+}
+
+impl TriggerState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TriggerState::Disarmed => "disarmed",
+            TriggerState::Armed { .. } => "armed",
+            TriggerState::Running { .. } => "running",
+        }
+    }
+}
+
+/// What the trigger wants done
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerAction {
+    Start, // Sound for SECONDS: start the operation
+    Stop,  // Silence for SILENCE_MINUTES: stop it and park
+    Ended, // The operation stopped by itself (or never started); armed again
+}
+
+#[derive(Debug)]
+pub struct AudioTrigger {
+    pub settings: AudioTriggerSettings,
+    state: TriggerState,
+}
+
+impl AudioTrigger {
+    /// Armed at once when the settings say so
+    pub fn new(settings: AudioTriggerSettings) -> Self {
+        let state = if settings.armed { TriggerState::Armed { loud_since: None } } else { TriggerState::Disarmed };
+        Self { settings, state }
+    }
+
+    pub fn state(&self) -> TriggerState {
+        self.state
+    }
+
+    /// Start listening; leaves a running operation alone
+    pub fn arm(&mut self) {
+        if self.state == TriggerState::Disarmed {
+            self.state = TriggerState::Armed { loud_since: None };
+        }
+    }
+
+    pub fn disarm(&mut self) {
+        self.state = TriggerState::Disarmed;
+    }
+
+    /// Any channel above THRESHOLD (non-finite levels are not sound)
+    fn loud(&self, amp_sums: &[f32]) -> bool {
+        amp_sums.iter().any(|amp| amp.is_finite() && *amp > self.settings.threshold)
+    }
+
+    /// Feed one analysis update. `running`: the trigger's operation is running.
+    /// While armed, other operations (`busy`) hold the trigger off.
+    pub fn observe(&mut self, at: Instant, amp_sums: &[f32], running: bool, busy: bool) -> Option<TriggerAction> {
+        let loud = self.loud(amp_sums);
+        match self.state {
+            TriggerState::Disarmed => None,
+            TriggerState::Armed { loud_since } => {
+                let loud_since = (loud && !busy).then(|| loud_since.unwrap_or(at));
+                if loud_since.is_some_and(|since| at.duration_since(since) >= self.settings.sound_for) {
+                    self.state = TriggerState::Running { quiet_since: None };
+                    return Some(TriggerAction::Start);
+                }
+                self.state = TriggerState::Armed { loud_since };
+                None
+            }
+            TriggerState::Running { quiet_since } => {
+                if !running {
+                    self.state = TriggerState::Armed { loud_since: None };
+                    return Some(TriggerAction::Ended);
+                }
+                let quiet_since = (!loud).then(|| quiet_since.unwrap_or(at));
+                if quiet_since.is_some_and(|since| at.duration_since(since) >= self.settings.silence_for) {
+                    self.state = TriggerState::Armed { loud_since: None };
+                    return Some(TriggerAction::Stop);
+                }
+                self.state = TriggerState::Running { quiet_since };
+                None
+            }
+        }
+    }
+
+    /// For the panel: "armed", "armed - sound for 2s of 3s", "running - silent for 4:10 of 10:00"
+    pub fn describe(&self, now: Instant) -> String {
+        let mmss = |d: Duration| format!("{}:{:02}", d.as_secs() / 60, d.as_secs() % 60);
+        match self.state {
+            TriggerState::Armed { loud_since: Some(since) } => format!(
+                "armed - sound for {:.0}s of {:.0}s",
+                now.duration_since(since).as_secs_f32(),
+                self.settings.sound_for.as_secs_f32()
+            ),
+            TriggerState::Running { quiet_since: Some(since) } => format!(
+                "running {} - silent for {} of {}",
+                self.settings.operation,
+                mmss(now.duration_since(since)),
+                mmss(self.settings.silence_for)
+            ),
+            TriggerState::Running { quiet_since: None } => format!("running {}", self.settings.operation),
+            state => state.name().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trigger() -> AudioTrigger {
+        AudioTrigger::new(AudioTriggerSettings {
+            operation: "performance_mode".to_string(),
+            threshold: 20.0,
+            sound_for: Duration::from_secs(3),
+            silence_for: Duration::from_secs(600),
+            armed: true,
+        })
+    }
+
+    #[test]
+    fn test_starts_on_sustained_sound_and_stops_after_silence() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut trigger = trigger();
+        assert_eq!(trigger.observe(at(0), &[5.0, 30.0], false, false), None);
+        // A gap in the sound starts the count over
+        assert_eq!(trigger.observe(at(2), &[5.0, 10.0], false, false), None);
+        assert_eq!(trigger.observe(at(3), &[25.0, f32::NAN], false, false), None);
+        assert_eq!(trigger.observe(at(5), &[25.0, 0.0], false, false), None);
+        assert_eq!(trigger.describe(at(5)), "armed - sound for 2s of 3s");
+        assert_eq!(trigger.observe(at(6), &[25.0, 0.0], false, false), Some(TriggerAction::Start));
+
+        // Sound now and then keeps it running
+        assert_eq!(trigger.observe(at(10), &[0.0, 0.0], true, true), None);
+        assert_eq!(trigger.observe(at(500), &[0.0, 40.0], true, true), None);
+        assert_eq!(trigger.observe(at(1000), &[0.0, 0.0], true, true), None);
+        assert_eq!(trigger.describe(at(1250)), "running performance_mode - silent for 4:10 of 10:00");
+        assert_eq!(trigger.observe(at(1600), &[], true, true), Some(TriggerAction::Stop));
+        assert_eq!(trigger.state(), TriggerState::Armed { loud_since: None });
+    }
+
+    #[test]
+    fn test_other_operations_and_disarm_hold_it_off() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut trigger = trigger();
+        // Something else is running: sound does not count
+        assert_eq!(trigger.observe(at(0), &[50.0], false, true), None);
+        assert_eq!(trigger.observe(at(5), &[50.0], false, true), None);
+        assert_eq!(trigger.observe(at(6), &[50.0], false, false), None);
+        assert_eq!(trigger.observe(at(9), &[50.0], false, false), Some(TriggerAction::Start));
+        // The operation failed or was stopped elsewhere
+        assert_eq!(trigger.observe(at(10), &[50.0], false, false), Some(TriggerAction::Ended));
+        trigger.disarm();
+        assert_eq!(trigger.observe(at(20), &[50.0], false, false), None);
+        assert_eq!(trigger.observe(at(30), &[50.0], false, false), None);
+        assert_eq!(trigger.describe(at(30)), "disarmed");
+    }
+}
@@ -0,0 +1,107 @@
+/// Motion audit log - persistent record of every stepper command
+///
+/// stepper_gui owns the serial ports, so every move/reset/disable passes through
+/// it. Each command is appended as one line with a timestamp, its source (UI
+/// button, IPC client and operation name), the stepper, the requested value and
+/// the position reported back afterwards. The file rotates by size so it can be
+/// left running overnight.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+const AUDIT_FILE_NAME: &str = "motion_audit.log";
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+const KEEP_ROTATED: usize = 5;
+
+/// One motion command as issued (the resulting position is filled in once known)
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    pub source: String,
+    pub action: &'static str,
+    pub stepper: usize,
+    pub value: i32,
+    pub result: Option<i32>,
+}
+
+impl AuditEntry {
+    pub fn new(source: &str, action: &'static str, stepper: usize, value: i32) -> Self {
+        Self {
+            source: source.to_string(),
+            action,
+            stepper,
+            value,
+            result: None,
+        }
+    }
+
+    fn to_line(&self) -> String {
+        let result = self.result.map(|p| p.to_string()).unwrap_or_else(|| "-".to_string());
+        format!(
+            "{} source={} action={} stepper={} value={} result={}",
+            chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+            self.source,
+            self.action,
+            self.stepper,
+            self.value,
+            result
+        )
+    }
+}
+
+/// Size-rotated append-only audit file (motion_audit.log, .1 .. .5)
+#[derive(Debug)]
+pub struct AuditLog {
+    dir: PathBuf,
+    file: Option<File>,
+}
+
+impl AuditLog {
+    /// Open (or create) the audit log under `dir`. Failures are reported once and
+    /// leave the log disabled rather than stopping the GUI.
+    pub fn open(dir: PathBuf) -> Self {
+        let mut log = Self { dir, file: None };
+        if let Err(e) = fs::create_dir_all(&log.dir) {
+            eprintln!("Audit log disabled: cannot create {:?}: {}", log.dir, e);
+            return log;
+        }
+        log.file = log.open_file();
+        log
+    }
+
+    fn path(&self) -> PathBuf {
+        self.dir.join(AUDIT_FILE_NAME)
+    }
+
+    fn open_file(&self) -> Option<File> {
+        match OpenOptions::new().create(true).append(true).open(self.path()) {
+            Ok(f) => Some(f),
+            Err(e) => {
+                eprintln!("Audit log disabled: cannot open {:?}: {}", self.path(), e);
+                None
+            }
+        }
+    }
+
+    pub fn record(&mut self, entry: &AuditEntry) {
+        self.rotate_if_needed();
+        if let Some(f) = self.file.as_mut() {
+            let _ = writeln!(f, "{}", entry.to_line());
+        }
+    }
+
+    fn rotate_if_needed(&mut self) {
+        let size = fs::metadata(self.path()).map(|m| m.len()).unwrap_or(0);
+        if size < MAX_FILE_BYTES {
+            return;
+        }
+        self.file = None;
+        for i in (1..KEEP_ROTATED).rev() {
+            let from = self.dir.join(format!("{}.{}", AUDIT_FILE_NAME, i));
+            let to = self.dir.join(format!("{}.{}", AUDIT_FILE_NAME, i + 1));
+            let _ = fs::rename(from, to);
+        }
+        let _ = fs::rename(self.path(), self.dir.join(format!("{}.1", AUDIT_FILE_NAME)));
+        self.file = self.open_file();
+    }
+}
@@ -0,0 +1,412 @@
+/// Client side of audmon (audio_monitor): reading the partials it leaves in shared memory
+///
+/// audmon writes each frame of partials to `audio_peaks` in shared memory (/dev/shm on
+/// Linux, /tmp elsewhere) and describes it in the control file next to it (see
+/// get_results::AudioControl). `AudmonClient::spawn` starts the slot reader: a thread
+/// that copies every frame into a triple buffer at REFRESH_RATES SLOT_POLL_HZ, so the
+/// analysis (Operations) never waits on the file. The returned AnalysisHandle hands
+/// out the reading end and stops the thread on `shutdown` or Drop. Freshness tracks
+/// when the data last changed, for the staleness interlock.
+///
+/// audmon can be reconfigured while running (e.g. from 12 to 32 partials per
+/// channel). The slot reader follows the control file frame by frame, logs each
+/// change of layout once and sends it to every `subscribe`r; voice-count caps and
+/// thresholds set on the old layout are carried over with `rescale_voice_count`.
+
+use crate::config_loader::{self, RefreshRates};
+use crate::error::Result;
+use crate::get_results::{partials_format, read_audio_control, AudioControl, ByteOrder, PartialsData};
+use crate::lifecycle::BackgroundThreads;
+use crate::partials_buffer::{triple_buffer, PartialsFrame, PartialsReader};
+use memmap2::Mmap;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Bytes per partial in the shared memory file: 2 * f32 (freq, amp)
+const PARTIAL_SIZE: usize = 8;
+pub const DEFAULT_PARTIALS_PER_CHANNEL: usize = 12;
+/// Channels asked for when the control file does not say; actual_channels_written caps it
+const LARGE_CHANNEL_HINT: usize = 100;
+
+/// Path of the shared memory file where audio_monitor writes partials
+pub fn shared_memory_path() -> PathBuf {
+    let shm_dir = if cfg!(target_os = "linux") { "/dev/shm" } else { "/tmp" };
+    PathBuf::from(shm_dir).join("audio_peaks")
+}
+
+/// Channels and partials per channel from audio_monitor's control file, None without one
+pub fn control_layout() -> Option<(usize, usize)> {
+    read_audio_control().map(|control| (control.channels, control.partials_per_channel))
+}
+
+/// Marker that changes with every frame audio_monitor writes: the control
+/// file's frame sequence if present, else the shared memory file's mtime in nanoseconds.
+fn frame_marker(control: Option<&AudioControl>) -> Option<u64> {
+    if let Some(sequence) = control.and_then(|c| c.frame_sequence) {
+        return Some(sequence);
+    }
+    let modified = std::fs::metadata(shared_memory_path()).ok()?.modified().ok()?;
+    modified.duration_since(std::time::UNIX_EPOCH).ok().map(|d| d.as_nanos() as u64)
+}
+
+/// Channels to read and partials per channel in `data_len` bytes of partials.
+/// num_channels: maximum number of channels to read (actual_channels_written from the control file caps it)
+/// num_partials_per_channel: hint, overridden by the control file if available
+fn shared_memory_layout(data_len: usize, control: Option<&AudioControl>, num_channels: usize, num_partials_per_channel: usize) -> (usize, usize) {
+    // Actual channel count and partials per channel written by audio_monitor
+    let (actual_channels_written, actual_partials_per_channel) = match control {
+        Some(control) => (control.channels, control.partials_per_channel),
+        // No control file: detect from the file size, assuming num_channels is right
+        None if num_channels > 0 && data_len / PARTIAL_SIZE / num_channels > 0 => (num_channels, data_len / PARTIAL_SIZE / num_channels),
+        None => (num_channels, num_partials_per_channel),
+    };
+    let num_partials_per_channel = match actual_partials_per_channel {
+        0 => DEFAULT_PARTIALS_PER_CHANNEL,
+        n => n,
+    };
+    // Read min(actual_channels_written, num_channels) channels
+    // This respects the caller's request while not reading beyond what was written
+    (actual_channels_written.min(num_channels), num_partials_per_channel)
+}
+
+fn decode_partial(bytes: &[u8], order: ByteOrder) -> (f32, f32) {
+    let freq = order.f32_from([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let amp = order.f32_from([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    (freq, amp)
+}
+
+/// Map the partials file and work out its byte order and data offset against the
+/// control file. A file that does not match the control file is skipped with one
+/// warning (until a frame decodes again) rather than read as nonsense frequencies.
+fn map_partials(path: &Path) -> Option<(Mmap, ByteOrder, usize, Option<AudioControl>)> {
+    static FORMAT_WARNED: AtomicBool = AtomicBool::new(false);
+    let file = OpenOptions::new().read(true).open(path).ok()?;
+    let mmap = unsafe { Mmap::map(&file).ok()? };
+    let control = read_audio_control();
+    match partials_format(&mmap, control.as_ref()) {
+        Ok((order, offset)) => {
+            FORMAT_WARNED.store(false, Ordering::Relaxed);
+            Some((mmap, order, offset, control))
+        }
+        Err(e) => {
+            if !FORMAT_WARNED.swap(true, Ordering::Relaxed) {
+                tracing::warn!("Skipping partials frames: {}", e);
+            }
+            None
+        }
+    }
+}
+
+/// Read partials data from shared memory file
+/// Returns None if file doesn't exist, can't be read or doesn't match the control file
+/// Format: optional header (see get_results::partials_format), then channel 0 partials,
+/// channel 1 partials, etc., each partial (f32 freq, f32 amp) in the writer's byte order
+pub fn read_partials(num_channels: usize, num_partials_per_channel: usize) -> Option<PartialsData> {
+    let (mmap, order, offset, control) = map_partials(&shared_memory_path())?;
+    let data = &mmap[offset..];
+    let (channels_to_read, num_partials_per_channel) = shared_memory_layout(data.len(), control.as_ref(), num_channels, num_partials_per_channel);
+    let partials: PartialsData = data
+        .chunks(num_partials_per_channel * PARTIAL_SIZE)
+        .take(channels_to_read)
+        .map(|channel| channel.chunks_exact(PARTIAL_SIZE).map(|partial| decode_partial(partial, order)).collect())
+        .collect();
+    (!partials.is_empty()).then_some(partials)
+}
+
+/// Like read_partials, but into `frame`'s storage without allocating.
+/// Channels and partials past the frame's maximums are dropped. False if nothing was read.
+pub fn fill_partials(frame: &mut PartialsFrame, num_channels: usize, num_partials_per_channel: usize) -> bool {
+    fill_partials_from(&shared_memory_path(), frame, num_channels, num_partials_per_channel)
+}
+
+fn fill_partials_from(path: &Path, frame: &mut PartialsFrame, num_channels: usize, num_partials_per_channel: usize) -> bool {
+    let Some((mmap, order, offset, control)) = map_partials(path) else {
+        return false;
+    };
+    let data = &mmap[offset..];
+    let (channels_to_read, num_partials_per_channel) = shared_memory_layout(data.len(), control.as_ref(), num_channels, num_partials_per_channel);
+    let channel_size = num_partials_per_channel * PARTIAL_SIZE;
+    let complete_channels = channels_to_read.min(data.len() / channel_size);
+    let (channels, partials) = frame.reshape(complete_channels, num_partials_per_channel);
+    for ch in 0..channels {
+        let bytes = &data[ch * channel_size..];
+        for (slot, partial) in frame.channel_mut(ch).iter_mut().zip(bytes.chunks_exact(PARTIAL_SIZE).take(partials)) {
+            *slot = decode_partial(partial, order);
+        }
+    }
+    !frame.is_empty()
+}
+
+/// When the partials data last changed. audio_monitor leaves its last frame in
+/// shared memory if it stops, so a frame only counts as new when the frame
+/// marker or the data itself changes.
+#[derive(Debug, Default)]
+pub struct Freshness {
+    marker: Option<u64>, // Control-file sequence or shared memory mtime of the last frame
+    last_data: Option<PartialsFrame>, // Copied into reused storage, not reallocated per frame
+    last_change: Option<Instant>,
+}
+
+impl Freshness {
+    /// Note a frame read alongside `control`; true when it is a new one
+    pub fn observe(&mut self, frame: &PartialsFrame, control: Option<&AudioControl>) -> bool {
+        let marker = frame_marker(control);
+        let marker_changed = marker.is_some() && marker != self.marker;
+        let data_changed = !self.last_data.as_ref().is_some_and(|last| last.same_data(frame));
+        if marker_changed || data_changed {
+            self.marker = marker;
+            self.last_data.get_or_insert_with(PartialsFrame::default).copy_from(frame);
+            self.last_change = Some(Instant::now());
+        }
+        marker_changed || data_changed
+    }
+
+    /// Time since the data last changed (None if no frame seen yet)
+    pub fn age(&self) -> Option<Duration> {
+        self.last_change.map(|t| t.elapsed())
+    }
+}
+
+/// A per-channel voice count set against `from` partials per channel, on a scale of
+/// `to`: one at the old cap stays at the cap, others keep their proportion
+pub fn rescale_voice_count(count: i32, from: usize, to: usize) -> i32 {
+    let (from, to) = (from.max(1) as i32, to.max(1) as i32);
+    if count >= from {
+        return to;
+    }
+    ((count.max(0) as f64 * to as f64 / from as f64).round() as i32).min(to)
+}
+
+/// Shape of the frames audmon writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    pub channels: usize,
+    pub partials_per_channel: usize,
+}
+
+impl Layout {
+    fn of(frame: &PartialsFrame) -> Self {
+        let partials_per_channel = if frame.is_empty() { 0 } else { frame.channel(0).len() };
+        Self { channels: frame.num_channels(), partials_per_channel }
+    }
+}
+
+/// audmon's layout changed between two frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutChange {
+    pub from: Layout,
+    pub to: Layout,
+}
+
+type Subscribers = Arc<Mutex<Vec<Sender<LayoutChange>>>>;
+
+/// Send `change` to every subscriber, forgetting those that hung up
+fn notify(subscribers: &Subscribers, change: LayoutChange) {
+    if let Ok(mut subscribers) = subscribers.lock() {
+        subscribers.retain(|tx| tx.send(change).is_ok());
+    }
+}
+
+/// What the slot reader reads and how often
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudmonConfig {
+    pub shm_path: PathBuf,
+    pub slot_poll: Duration,            // REFRESH_RATES SLOT_POLL_HZ
+    pub partials_per_channel: usize,    // Starting hint until a frame says otherwise
+}
+
+impl Default for AudmonConfig {
+    fn default() -> Self {
+        Self {
+            shm_path: shared_memory_path(),
+            slot_poll: RefreshRates::interval(RefreshRates::DESKTOP.slot_poll_hz),
+            partials_per_channel: DEFAULT_PARTIALS_PER_CHANNEL,
+        }
+    }
+}
+
+impl AudmonConfig {
+    /// The slot polling rate from the host's REFRESH_RATES
+    pub fn for_host(hostname: &str) -> Result<Self> {
+        let rates = config_loader::load_refresh_rates(hostname)?;
+        Ok(Self { slot_poll: RefreshRates::interval(rates.slot_poll_hz), ..Self::default() })
+    }
+}
+
+/// Starts the shared memory slot reader
+pub struct AudmonClient;
+
+impl AudmonClient {
+    /// Read audmon's frames into a triple buffer on a background thread
+    pub fn spawn(config: AudmonConfig) -> AnalysisHandle {
+        let (mut writer, reader) = triple_buffer();
+        let partials_per_channel = Arc::new(AtomicUsize::new(config.partials_per_channel.max(1)));
+        let detected = Arc::clone(&partials_per_channel);
+        let subscribers = Subscribers::default();
+        let notified = Arc::clone(&subscribers);
+        let mut background = BackgroundThreads::new();
+        background.spawn("partials reader", move |shutdown| {
+            let mut layout: Option<Layout> = None;
+            while !shutdown.is_set() {
+                let hint = detected.load(Ordering::Relaxed).max(1);
+                // Read from shared memory straight into the back frame and publish it
+                let mut observed = None;
+                writer.write(|frame| {
+                    let read = fill_partials_from(&config.shm_path, frame, LARGE_CHANNEL_HINT, hint);
+                    observed = read.then(|| Layout::of(frame));
+                    read
+                });
+                if let Some(observed) = observed {
+                    detected.store(observed.partials_per_channel, Ordering::Relaxed);
+                    if let Some(from) = layout.replace(observed).filter(|from| *from != observed) {
+                        tracing::info!(
+                            "audmon layout changed: {} channels x {} partials -> {} channels x {} partials",
+                            from.channels,
+                            from.partials_per_channel,
+                            observed.channels,
+                            observed.partials_per_channel
+                        );
+                        notify(&notified, LayoutChange { from, to: observed });
+                    }
+                }
+                shutdown.sleep(config.slot_poll);
+            }
+        });
+        AnalysisHandle { reader: Some(reader), partials_per_channel, subscribers, background }
+    }
+
+    /// A feed nothing writes to, for an instrument on another machine whose strings
+    /// this machine's audmon does not hear: audio-driven moves then stop on the
+    /// staleness interlock instead of following the wrong strings
+    pub fn silent() -> AnalysisHandle {
+        let (_writer, reader) = triple_buffer();
+        AnalysisHandle {
+            reader: Some(reader),
+            partials_per_channel: Arc::new(AtomicUsize::new(DEFAULT_PARTIALS_PER_CHANNEL)),
+            subscribers: Subscribers::default(),
+            background: BackgroundThreads::new(),
+        }
+    }
+}
+
+/// A running slot reader
+#[derive(Debug)]
+pub struct AnalysisHandle {
+    reader: Option<PartialsReader>,
+    partials_per_channel: Arc<AtomicUsize>,
+    subscribers: Subscribers, // Told of each layout change
+    background: BackgroundThreads,
+}
+
+impl AnalysisHandle {
+    /// The reading end of the triple buffer, for Operations; there is one
+    pub fn take_reader(&mut self) -> Option<PartialsReader> {
+        self.reader.take()
+    }
+
+    /// Partials per channel in the last frame read (the configured hint before one)
+    pub fn partials_per_channel(&self) -> usize {
+        self.partials_per_channel.load(Ordering::Relaxed)
+    }
+
+    /// Layout changes from now on, one per change (the first frame is not one)
+    pub fn subscribe(&self) -> Receiver<LayoutChange> {
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(tx);
+        }
+        rx
+    }
+
+    /// Stop the reader thread; names it if it did not stop within `timeout`
+    pub fn shutdown(&mut self, timeout: Duration) -> Vec<String> {
+        self.background.shutdown(timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_comes_from_the_control_file_or_the_file_size() {
+        let control = AudioControl { channels: 2, partials_per_channel: 8, ..AudioControl::default() };
+        assert_eq!(shared_memory_layout(1000, Some(&control), LARGE_CHANNEL_HINT, 12), (2, 8));
+        assert_eq!(shared_memory_layout(1000, Some(&control), 1, 12), (1, 8));
+        // Without a control file: 4 channels of 3 partials in 96 bytes
+        assert_eq!(shared_memory_layout(96, None, 4, 12), (4, 3));
+        assert_eq!(shared_memory_layout(0, None, 4, 0), (4, DEFAULT_PARTIALS_PER_CHANNEL));
+    }
+
+    #[test]
+    fn test_slot_reader_publishes_frames() {
+        let path = std::env::temp_dir().join(format!("stringdriver-audmon-{}", std::process::id()));
+        // No header and no control file: native order, read as one partial per channel
+        let bytes: Vec<u8> = (0..6).flat_map(|i| [110.0f32 * (i + 1) as f32, 1.0].into_iter().flat_map(f32::to_ne_bytes)).collect();
+        std::fs::write(&path, &bytes).unwrap();
+        if read_audio_control().is_some() {
+            // A live audmon's control file would describe its own file, not this one
+            let _ = std::fs::remove_file(&path);
+            return;
+        }
+
+        let mut handle = AudmonClient::spawn(AudmonConfig { shm_path: path.clone(), slot_poll: Duration::from_millis(5), partials_per_channel: 1 });
+        let mut reader = handle.take_reader().unwrap();
+        assert!(handle.take_reader().is_none());
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while reader.latest().is_none_or(|frame| frame.is_empty()) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        let frame = reader.latest().unwrap();
+        assert_eq!((frame.num_channels(), frame.channel(0)[0]), (6, (110.0, 1.0)));
+        assert_eq!(handle.partials_per_channel(), 1);
+
+        // The same frame again is not new
+        let mut freshness = Freshness::default();
+        assert!(freshness.observe(&frame, None));
+        assert!(!freshness.observe(&frame, None));
+        assert!(freshness.age().is_some());
+        drop(frame);
+        assert!(handle.shutdown(Duration::from_secs(1)).is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_layout_changes_reach_subscribers() {
+        // A max at the old cap follows the cap; a min of 3 in 12 becomes 8 in 32
+        assert_eq!((rescale_voice_count(12, 12, 32), rescale_voice_count(3, 12, 32), rescale_voice_count(0, 12, 32)), (32, 8, 0));
+        assert_eq!((rescale_voice_count(32, 32, 12), rescale_voice_count(8, 32, 12)), (12, 3));
+
+        if read_audio_control().is_some() {
+            return;
+        }
+        let path = std::env::temp_dir().join(format!("stringdriver-audmon-layout-{}", std::process::id()));
+        // Replaced whole, as audmon does, so the reader never sees half a file
+        let write_channels = |channels: usize| {
+            let bytes: Vec<u8> = (0..channels).flat_map(|i| [110.0f32 * (i + 1) as f32, 1.0].into_iter().flat_map(f32::to_ne_bytes)).collect();
+            let tmp = path.with_extension("tmp");
+            std::fs::write(&tmp, &bytes).unwrap();
+            std::fs::rename(&tmp, &path).unwrap();
+        };
+        write_channels(6);
+        let mut handle = AudmonClient::spawn(AudmonConfig { shm_path: path.clone(), slot_poll: Duration::from_millis(5), partials_per_channel: 1 });
+        let changes = handle.subscribe();
+        let mut reader = handle.take_reader().unwrap();
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while reader.latest().is_none_or(|frame| frame.is_empty()) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(changes.try_recv().is_err());
+
+        write_channels(12);
+        let change = changes.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(change.from, Layout { channels: 6, partials_per_channel: 1 });
+        assert_eq!(change.to, Layout { channels: 12, partials_per_channel: 1 });
+        assert!(handle.shutdown(Duration::from_secs(1)).is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+}
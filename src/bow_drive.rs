@@ -0,0 +1,148 @@
+/// Rotary bow wheel speed control (BOW_DRIVE)
+///
+/// Each string is bowed by a wheel whose speed is set here, either as the duty
+/// cycle of a kernel PWM channel or as a binary command the carriage board's
+/// firmware turns into motor speed. Speeds run from 0 (stopped) to MAX_SPEED.
+/// stepper_gui, or stringdriverd, owns the wheels and answers `bow_speed` and
+/// `get_bow_speeds` on its socket; Operations goes through StepperOperations like
+/// any other client, and z_adjust can change a wheel's speed instead of its Z
+/// height when only the amplitude is out of range (MODULATE).
+
+use crate::config_loader::{BowDriveSettings, BowOutput};
+use crate::error::{Error, Result};
+use std::path::PathBuf;
+
+/// Where the kernel exposes PWM chips
+const PWM_ROOT: &str = "/sys/class/pwm";
+
+/// Duty cycle (ns) for `speed` out of `max_speed` over a `period_ns` period
+pub fn duty_ns(speed: u32, max_speed: u32, period_ns: u32) -> u32 {
+    (u64::from(speed.min(max_speed)) * u64::from(period_ns) / u64::from(max_speed.max(1))) as u32
+}
+
+/// Speed z_adjust moves a running wheel to: slower when the string is too loud,
+/// faster when too quiet. None when the wheel is stopped or already at
+/// MIN_SPEED / MAX_SPEED, so z_adjust moves Z instead.
+pub fn modulated_speed(settings: &BowDriveSettings, speed: u32, too_loud: bool) -> Option<u32> {
+    if speed == 0 {
+        return None;
+    }
+    let next = if too_loud {
+        speed.saturating_sub(settings.speed_step).max(settings.min_speed)
+    } else {
+        speed.saturating_add(settings.speed_step).min(settings.max_speed)
+    };
+    let moved = if too_loud { next < speed } else { next > speed };
+    moved.then_some(next)
+}
+
+/// The wheels and the speed last sent to each
+#[derive(Debug)]
+pub struct BowDrive {
+    pub settings: BowDriveSettings,
+    speeds: Vec<u32>,       // Per string; 0 until a speed is sent
+    pwm_enabled: Vec<u32>,  // PWM channels exported and enabled so far
+}
+
+impl BowDrive {
+    pub fn new(settings: BowDriveSettings, string_num: usize) -> Self {
+        Self { settings, speeds: vec![0; string_num], pwm_enabled: Vec::new() }
+    }
+
+    pub fn speeds(&self) -> &[u32] {
+        &self.speeds
+    }
+
+    /// Firmware command id when the carriage board drives the wheels
+    pub fn serial_command(&self) -> Option<u8> {
+        match self.settings.output {
+            BowOutput::Serial { command_id } => Some(command_id),
+            BowOutput::Pwm { .. } => None,
+        }
+    }
+
+    /// Set string `string_idx`'s wheel to `speed` (capped at MAX_SPEED) and return
+    /// the speed applied. PWM is written here; with a serial output the caller
+    /// sends `serial_command()` to the board.
+    pub fn set_speed(&mut self, string_idx: usize, speed: u32) -> Result<u32> {
+        if string_idx >= self.speeds.len() {
+            return Err(Error::Other(format!("No bow wheel for string {} ({} strings)", string_idx, self.speeds.len())));
+        }
+        let speed = speed.min(self.settings.max_speed);
+        if let BowOutput::Pwm { chip, ref channels, period_ns } = self.settings.output {
+            let channel = *channels.get(string_idx)
+                .ok_or_else(|| Error::ConfigInvalid(format!("BOW_DRIVE.PWM_CHANNELS has no channel for string {}", string_idx)))?;
+            let duty = duty_ns(speed, self.settings.max_speed, period_ns);
+            self.write_pwm(chip, channel, period_ns, duty)?;
+        }
+        self.speeds[string_idx] = speed;
+        Ok(speed)
+    }
+
+    /// Write a PWM channel's duty cycle, exporting and enabling it on first use
+    fn write_pwm(&mut self, chip: u32, channel: u32, period_ns: u32, duty: u32) -> Result<()> {
+        let chip_dir = PathBuf::from(PWM_ROOT).join(format!("pwmchip{}", chip));
+        let dir = chip_dir.join(format!("pwm{}", channel));
+        let write = |path: PathBuf, value: u32| {
+            std::fs::write(&path, value.to_string()).map_err(|e| Error::io(format!("Writing {}", path.display()), e))
+        };
+        if !self.pwm_enabled.contains(&channel) {
+            if !dir.exists() {
+                write(chip_dir.join("export"), channel)?;
+            }
+            write(dir.join("period"), period_ns)?;
+            write(dir.join("duty_cycle"), duty)?;
+            write(dir.join("enable"), 1)?;
+            self.pwm_enabled.push(channel);
+            return Ok(());
+        }
+        write(dir.join("duty_cycle"), duty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serial_settings() -> BowDriveSettings {
+        BowDriveSettings {
+            output: BowOutput::Serial { command_id: 15 },
+            speeds: vec![120],
+            max_speed: 200,
+            min_speed: 50,
+            speed_step: 10,
+            modulate: true,
+        }
+    }
+
+    #[test]
+    fn test_duty_scales_speed_to_period() {
+        assert_eq!(duty_ns(0, 255, 51_000), 0);
+        assert_eq!(duty_ns(255, 255, 51_000), 51_000);
+        assert_eq!(duty_ns(51, 255, 51_000), 10_200);
+        // Over MAX_SPEED is full duty, never more
+        assert_eq!(duty_ns(400, 255, 51_000), 51_000);
+    }
+
+    #[test]
+    fn test_modulated_speed_stays_within_limits() {
+        let settings = serial_settings();
+        assert_eq!(modulated_speed(&settings, 120, true), Some(110));
+        assert_eq!(modulated_speed(&settings, 120, false), Some(130));
+        assert_eq!(modulated_speed(&settings, 55, true), Some(50));
+        assert_eq!(modulated_speed(&settings, 50, true), None);
+        assert_eq!(modulated_speed(&settings, 195, false), Some(200));
+        assert_eq!(modulated_speed(&settings, 200, false), None);
+        // A stopped wheel is not started by z_adjust
+        assert_eq!(modulated_speed(&settings, 0, false), None);
+    }
+
+    #[test]
+    fn test_set_speed_caps_and_records() {
+        let mut drive = BowDrive::new(serial_settings(), 2);
+        assert_eq!(drive.serial_command(), Some(15));
+        assert_eq!(drive.set_speed(1, 500).unwrap(), 200);
+        assert_eq!(drive.speeds(), &[0, 200]);
+        assert!(drive.set_speed(2, 100).is_err());
+    }
+}
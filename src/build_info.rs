@@ -0,0 +1,126 @@
+/// Build and protocol identity, so long-running processes built at different times can tell
+///
+/// Each GUI logs its build line at startup. operations_gui and stepper_gui swap
+/// `hello` lines on the stepper socket (`hello component=.. version=.. git=..
+/// protocol=..`) and warn when their PROTOCOL_VERSIONs differ. stepper_gui also
+/// asks firmware with a version query (v3) for its protocol number and reports it
+/// in its hello. The result goes to the About panel, the status file and the
+/// machine-state snapshots.
+
+use serde::Serialize;
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Set by build.rs from `git rev-parse --short HEAD`
+pub const GIT_HASH: &str = match option_env!("STRINGDRIVER_GIT_HASH") {
+    Some(hash) => hash,
+    None => "unknown",
+};
+/// Stepper socket protocol; bump when a command or reply changes incompatibly
+pub const PROTOCOL_VERSION: u32 = 1;
+/// Firmware protocol these GUIs drive (the firmware's answer to its version query)
+pub const FIRMWARE_PROTOCOL: i32 = 1;
+
+/// One process's build, as announced in its hello
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BuildInfo {
+    pub component: String,
+    pub version: String,
+    pub git: String,
+    pub protocol: u32,
+    pub firmware: Option<i32>, // stepper_gui only: main board firmware protocol, if it answered
+}
+
+impl BuildInfo {
+    pub fn local(component: &str) -> Self {
+        Self {
+            component: component.to_string(),
+            version: VERSION.to_string(),
+            git: GIT_HASH.to_string(),
+            protocol: PROTOCOL_VERSION,
+            firmware: None,
+        }
+    }
+
+    pub fn hello_line(&self) -> String {
+        let mut line = format!("hello component={} version={} git={} protocol={}", self.component, self.version, self.git, self.protocol);
+        if let Some(firmware) = self.firmware {
+            line.push_str(&format!(" firmware={}", firmware));
+        }
+        line
+    }
+
+    /// Parse a `hello ...` line; unknown keys are skipped so later builds can add some
+    pub fn parse_hello(line: &str) -> Option<Self> {
+        let mut tokens = line.split_whitespace();
+        if tokens.next() != Some("hello") {
+            return None;
+        }
+        let mut info = BuildInfo { component: String::new(), version: String::new(), git: String::new(), protocol: 0, firmware: None };
+        for (key, value) in tokens.filter_map(|t| t.split_once('=')) {
+            match key {
+                "component" => info.component = value.to_string(),
+                "version" => info.version = value.to_string(),
+                "git" => info.git = value.to_string(),
+                "protocol" => info.protocol = value.parse().ok()?,
+                "firmware" => info.firmware = value.parse().ok(),
+                _ => {}
+            }
+        }
+        (!info.component.is_empty()).then_some(info)
+    }
+
+    pub fn describe(&self) -> String {
+        let mut text = format!("{} {} ({}) protocol {}", self.component, self.version, self.git, self.protocol);
+        if let Some(firmware) = self.firmware {
+            text.push_str(&format!(", firmware protocol {}", firmware));
+        }
+        text
+    }
+
+    /// What `peer` disagrees with this build on; protocol differences are the ones that break things
+    pub fn mismatches(&self, peer: &BuildInfo) -> Vec<String> {
+        let mut problems = Vec::new();
+        if peer.protocol != self.protocol {
+            problems.push(format!("{} speaks protocol {}, {} expects {}", peer.component, peer.protocol, self.component, self.protocol));
+        }
+        if let Some(firmware) = peer.firmware.filter(|f| *f != FIRMWARE_PROTOCOL) {
+            problems.push(format!("firmware speaks protocol {}, expected {}", firmware, FIRMWARE_PROTOCOL));
+        }
+        if peer.git != self.git && peer.protocol == self.protocol {
+            problems.push(format!("{} was built from {}, {} from {}", peer.component, peer.git, self.component, self.git));
+        }
+        problems
+    }
+}
+
+/// One text line for the machine-state log: this build and, once known, stepper_gui's
+pub fn summary(own: &BuildInfo, stepper: Option<&BuildInfo>) -> String {
+    match stepper {
+        Some(peer) => format!("{}; {}", own.describe(), peer.describe()),
+        None => format!("{}; stepper_gui unknown", own.describe()),
+    }
+}
+
+/// Log this process's build at startup
+pub fn log_startup(component: &str) {
+    tracing::info!("Starting {}", BuildInfo::local(component).describe());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hello_round_trip_and_mismatch() {
+        let mut stepper = BuildInfo::local("stepper_gui");
+        stepper.firmware = Some(FIRMWARE_PROTOCOL);
+        let parsed = BuildInfo::parse_hello(&format!("{} extra=1", stepper.hello_line())).unwrap();
+        assert_eq!(parsed, stepper);
+
+        let ops = BuildInfo::local("operations_gui");
+        assert!(ops.mismatches(&parsed).is_empty());
+        let old = BuildInfo { protocol: PROTOCOL_VERSION + 1, ..parsed };
+        assert_eq!(ops.mismatches(&old).len(), 1);
+        assert!(BuildInfo::parse_hello("positions 0=1").is_none());
+    }
+}
@@ -0,0 +1,268 @@
+/// Bump events and the audio level around them
+///
+/// Each touch sensor bump_check finds pressed is recorded as a BumpEvent: when,
+/// which Z stepper (and its string and channel), where the X carriage was, the
+/// stepper's Z position and z_down_step at the time, and the channel's amp sum
+/// at that moment next to its mean over the seconds before (AmpHistory). A Z
+/// stepper really pressing on its string damps it, so a bump with a level dip
+/// reads as contact and one without as likely sensor noise. `correlate` sums
+/// this up per string, with a smaller z_down_step for a string that keeps being
+/// pressed down; operations_gui shows it in its "Bumps" panel. The events go to
+/// the machine state backends (the bump_events table, `stringdriver_bump` points).
+
+use chrono::{DateTime, Local, Utc};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// How far back the amp sums are kept for the baseline
+pub const AMP_WINDOW: Duration = Duration::from_secs(5);
+/// The latest frames, which may already carry the dip, are left out of the baseline
+const BASELINE_GAP: Duration = Duration::from_millis(500);
+/// A level this far below the baseline (as a fraction of it) is a dip
+pub const DIP_RATIO: f32 = 0.2;
+/// Below this baseline the channel was silent and a dip cannot be told
+const MIN_BASELINE_AMP: f32 = 1.0;
+/// Bumps a string needs before a z_down_step is suggested
+const MIN_BUMPS_FOR_SUGGESTION: usize = 3;
+/// The smallest step Z_DOWN_STEP takes
+const SMALLEST_Z_DOWN_STEP: i32 = -2;
+
+/// One pressed touch sensor found by bump_check
+#[derive(Debug, Clone, PartialEq)]
+pub struct BumpEvent {
+    pub recorded_at: DateTime<Utc>,
+    pub host: String,
+    pub stepper: usize,
+    pub string: Option<usize>,
+    pub channel: Option<usize>,
+    pub x_position: Option<i32>, // None without an X axis
+    pub z_position: i32,
+    pub z_down_step: i32,
+    pub amp_sum: Option<f32>,      // The channel's level when the sensor read pressed
+    pub amp_baseline: Option<f32>, // Its mean over AMP_WINDOW before
+}
+
+/// What a bump looks like against the audio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Contact, // The level dipped: the stepper was on the string
+    Noise,   // The level held: the sensor fired without contact
+    Unknown, // No channel, no analysis or a silent string
+}
+
+impl Verdict {
+    pub fn name(self) -> &'static str {
+        match self {
+            Verdict::Contact => "contact",
+            Verdict::Noise => "noise",
+            Verdict::Unknown => "unknown",
+        }
+    }
+}
+
+impl BumpEvent {
+    /// How far the level fell below the baseline, as a fraction of it (negative: it rose)
+    pub fn dip(&self) -> Option<f32> {
+        let (amp, baseline) = (self.amp_sum?, self.amp_baseline?);
+        (amp.is_finite() && baseline.is_finite() && baseline >= MIN_BASELINE_AMP).then(|| 1.0 - amp / baseline)
+    }
+
+    pub fn verdict(&self) -> Verdict {
+        match self.dip() {
+            Some(dip) if dip >= DIP_RATIO => Verdict::Contact,
+            Some(_) => Verdict::Noise,
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+/// Per-channel amp sums of the last AMP_WINDOW, one entry per analysed frame
+#[derive(Debug, Default)]
+pub struct AmpHistory {
+    frames: VecDeque<(Instant, Vec<f32>)>,
+}
+
+impl AmpHistory {
+    pub fn push(&mut self, at: Instant, amp_sums: &[f32]) {
+        while self.frames.front().is_some_and(|(t, _)| at.duration_since(*t) > AMP_WINDOW) {
+            self.frames.pop_front();
+        }
+        self.frames.push_back((at, amp_sums.to_vec()));
+    }
+
+    /// `channel`'s latest level
+    pub fn latest(&self, channel: usize) -> Option<f32> {
+        self.frames.back().and_then(|(_, amps)| amps.get(channel).copied())
+    }
+
+    /// `channel`'s mean level over the window, leaving out the BASELINE_GAP before `at`
+    pub fn baseline(&self, channel: usize, at: Instant) -> Option<f32> {
+        let amps: Vec<f32> = self.frames.iter()
+            .filter(|(t, _)| at.checked_duration_since(*t).is_some_and(|age| age >= BASELINE_GAP && age <= AMP_WINDOW))
+            .filter_map(|(_, amps)| amps.get(channel).copied().filter(|a| a.is_finite()))
+            .collect();
+        (!amps.is_empty()).then(|| amps.iter().sum::<f32>() / amps.len() as f32)
+    }
+}
+
+/// One string's bumps against the audio
+#[derive(Debug, Clone, PartialEq)]
+pub struct StringBumps {
+    pub string: Option<usize>, // None: steppers without a string in the topology
+    pub bumps: usize,
+    pub contact: usize,
+    pub noise: usize,
+    pub unknown: usize,
+    pub mean_dip: Option<f32>, // Over the bumps with a known dip
+    pub z_down_step: i32,      // As of the latest bump
+    pub suggested_z_down_step: Option<i32>,
+}
+
+impl StringBumps {
+    pub fn label(&self) -> String {
+        self.string.map_or("no string".to_string(), |s| format!("string {}", s + 1))
+    }
+
+    /// What the bumps point to, for the report
+    pub fn advice(&self) -> String {
+        if let Some(step) = self.suggested_z_down_step {
+            format!("pressed down in {} of {} bumps - try z_down_step {}", self.contact, self.bumps, step)
+        } else if self.bumps >= MIN_BUMPS_FOR_SUGGESTION && self.contact * 2 > self.bumps {
+            format!("pressed down in {} of {} bumps, already at the smallest z_down_step", self.contact, self.bumps)
+        } else if self.bumps >= MIN_BUMPS_FOR_SUGGESTION && self.noise * 2 > self.bumps {
+            format!("no level dip in {} of {} bumps - check the touch sensor", self.noise, self.bumps)
+        } else {
+            String::new()
+        }
+    }
+}
+
+/// Bumps per string (those without a string first): mostly contact suggests halving
+/// z_down_step (down to SMALLEST_Z_DOWN_STEP), mostly noise points at the sensor
+pub fn correlate(events: &[BumpEvent]) -> Vec<StringBumps> {
+    let mut by_string: BTreeMap<Option<usize>, Vec<&BumpEvent>> = BTreeMap::new();
+    for event in events {
+        by_string.entry(event.string).or_default().push(event);
+    }
+    by_string
+        .into_iter()
+        .map(|(string, mut events)| {
+            events.sort_by_key(|e| e.recorded_at);
+            let count = |verdict: Verdict| events.iter().filter(|e| e.verdict() == verdict).count();
+            let (contact, noise, unknown) = (count(Verdict::Contact), count(Verdict::Noise), count(Verdict::Unknown));
+            let dips: Vec<f32> = events.iter().filter_map(|e| e.dip()).collect();
+            let z_down_step = events.last().map_or(0, |e| e.z_down_step);
+            let halved = (z_down_step / 2).min(SMALLEST_Z_DOWN_STEP);
+            let suggested_z_down_step = (events.len() >= MIN_BUMPS_FOR_SUGGESTION && contact * 2 > events.len() && halved != z_down_step)
+                .then_some(halved);
+            StringBumps {
+                string,
+                bumps: events.len(),
+                contact,
+                noise,
+                unknown,
+                mean_dip: (!dips.is_empty()).then(|| dips.iter().sum::<f32>() / dips.len() as f32),
+                z_down_step,
+                suggested_z_down_step,
+            }
+        })
+        .collect()
+}
+
+/// Plain-text report: one line per string, then each bump (for the Copy button)
+pub fn report_lines(events: &[BumpEvent]) -> Vec<String> {
+    let show = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+    let mut lines = Vec::new();
+    for stats in correlate(events) {
+        let mut line = format!(
+            "{}: {} bumps, {} contact, {} noise, {} unknown, mean dip {}",
+            stats.label(), stats.bumps, stats.contact, stats.noise, stats.unknown,
+            show(stats.mean_dip.map(|d| format!("{:.0}%", d * 100.0)))
+        );
+        let advice = stats.advice();
+        if !advice.is_empty() {
+            line.push_str(&format!(" - {}", advice));
+        }
+        lines.push(line);
+    }
+    for event in events {
+        lines.push(format!(
+            "{} stepper {} x {} z {}: amp {} (baseline {}) {}",
+            event.recorded_at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+            event.stepper,
+            show(event.x_position.map(|x| x.to_string())),
+            event.z_position,
+            show(event.amp_sum.map(|a| format!("{:.2}", a))),
+            show(event.amp_baseline.map(|a| format!("{:.2}", a))),
+            event.verdict().name()
+        ));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bump(string: Option<usize>, amp_sum: Option<f32>, amp_baseline: Option<f32>) -> BumpEvent {
+        BumpEvent {
+            recorded_at: Utc::now(),
+            host: "stringdriver-1".to_string(),
+            stepper: 3,
+            string,
+            channel: string,
+            x_position: Some(1200),
+            z_position: -4,
+            z_down_step: -4,
+            amp_sum,
+            amp_baseline,
+        }
+    }
+
+    #[test]
+    fn test_amp_history_baseline_leaves_out_the_latest_frames() {
+        let start = Instant::now();
+        let mut history = AmpHistory::default();
+        for tenth in 0..30 {
+            let amp = if tenth < 27 { 100.0 } else { 40.0 };
+            history.push(start + Duration::from_millis(tenth * 100), &[amp, 5.0]);
+        }
+        let at = start + Duration::from_millis(2900);
+        assert_eq!(history.latest(0), Some(40.0));
+        assert_eq!(history.baseline(0, at), Some(100.0));
+        assert_eq!(history.baseline(2, at), None);
+        // Frames older than AMP_WINDOW are dropped
+        let later = start + AMP_WINDOW + Duration::from_secs(3);
+        history.push(later, &[10.0]);
+        assert_eq!(history.latest(0), Some(10.0));
+        assert_eq!(history.baseline(0, later), None);
+    }
+
+    #[test]
+    fn test_correlate_tells_contact_from_noise() {
+        let events = vec![
+            bump(Some(0), Some(40.0), Some(100.0)),
+            bump(Some(0), Some(60.0), Some(100.0)),
+            bump(Some(0), Some(95.0), Some(100.0)),
+            bump(Some(1), Some(100.0), Some(100.0)),
+            bump(Some(1), Some(104.0), Some(100.0)),
+            bump(Some(1), Some(0.2), Some(0.3)),
+            bump(Some(1), Some(98.0), Some(100.0)),
+        ];
+        assert_eq!(events[0].verdict(), Verdict::Contact);
+        assert_eq!(events[5].verdict(), Verdict::Unknown);
+        let stats = correlate(&events);
+        assert_eq!(stats.len(), 2);
+        assert_eq!((stats[0].contact, stats[0].noise, stats[0].suggested_z_down_step), (2, 1, Some(-2)));
+        assert!((stats[0].mean_dip.unwrap() - 0.35).abs() < 1e-4);
+        assert_eq!((stats[1].contact, stats[1].noise, stats[1].unknown, stats[1].suggested_z_down_step), (0, 3, 1, None));
+        assert!(stats[1].advice().contains("check the touch sensor"));
+        let lines = report_lines(&events);
+        assert_eq!(lines[0], "string 1: 3 bumps, 2 contact, 1 noise, 0 unknown, mean dip 35% - pressed down in 2 of 3 bumps - try z_down_step -2");
+        // z_down_step -2 is as small as it goes
+        let mut small = vec![bump(Some(0), Some(10.0), Some(100.0)); 3];
+        small.iter_mut().for_each(|e| e.z_down_step = -2);
+        assert_eq!(correlate(&small)[0].suggested_z_down_step, None);
+        assert!(correlate(&small)[0].advice().contains("already at the smallest"));
+    }
+}
@@ -0,0 +1,211 @@
+/// File-based command inbox - scripted control without opening a network port
+///
+/// Command files (`<name>.json`) are dropped into the COMMAND_INBOX directory by
+/// rsync or from a USB stick. operations_gui (or stringdriverd) takes them one at
+/// a time in name order, runs each and writes `<name>.result.json` next to it. A command file is
+/// renamed to `<name>.json.done` before it runs so it can never run twice.
+
+use crate::access::{self, AccessPolicy, Allow, Grant};
+use crate::config_loader::{BumpOverrides, IpcAuthSettings, RestOverrides};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const COMMAND_EXTENSION: &str = "json";
+const RESULT_SUFFIX: &str = ".result.json";
+const DONE_SUFFIX: &str = ".done";
+
+/// One command file, e.g. `{"operation": "z_calibrate", "preset": "rehearsal", "params": {"LAP_REST": 2.0}}`.
+/// The preset, then the params, are applied before the operation; all three are optional.
+/// `"max_duration": 900` (seconds) replaces the operation's MAX_DURATION for this run only,
+/// `"rests": {"Z_REST": 0.5}` its rests (unset ones follow OPERATIONS, then the globals),
+/// `"bump": {"FINAL_MARGIN": 10}` its bump_check strategy (unset ones follow BUMP_*).
+/// The operation `clear_fault` leaves the Faulted state without running anything;
+/// `list_operations` answers with the host's operations (name, hardware, parameters),
+/// `effective_config` with every setting in effect and its source.
+/// With IPC_AUTH configured the file also needs a `"token"` allowed to do all of it (see access.rs).
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InboxCommand {
+    #[serde(default)]
+    pub operation: Option<String>,
+    #[serde(default)]
+    pub preset: Option<String>,
+    #[serde(default)]
+    pub params: Option<serde_json::Value>, // PRESETS-style keys (LAP_REST, X_START, ...)
+    #[serde(default)]
+    pub max_duration: Option<f64>, // Seconds; run time limit for this command's operation
+    #[serde(default)]
+    pub rests: Option<RestOverrides>, // Rests for this command's operation only
+    #[serde(default)]
+    pub bump: Option<BumpOverrides>, // bump_check strategy for this command's operation only
+    #[serde(default)]
+    pub token: Option<String>, // IPC_AUTH client token; required when IPC_AUTH is configured
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InboxStatus {
+    Ok,
+    Failed,   // The operation ran and failed
+    Timeout,  // The operation ran past its time limit and was stopped, Z retracted
+    Rejected, // Unreadable, locked or could not start
+}
+
+/// What is written to `<name>.result.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct InboxResult {
+    pub command: String, // Command file name
+    pub status: InboxStatus,
+    pub message: String,
+    pub received_at: String,
+    pub finished_at: String,
+    pub state: String, // Instrument state after the command, e.g. "idle" or "faulted (z_home: ...)"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operations: Option<serde_json::Value>, // list_operations: the operation descriptors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<serde_json::Value>, // effective_config: the settings with their sources
+}
+
+fn is_command_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    path.extension().and_then(|e| e.to_str()) == Some(COMMAND_EXTENSION)
+        && !name.ends_with(RESULT_SUFFIX)
+        && !name.starts_with('.') // rsync's in-progress temp files
+}
+
+/// The inbox's access policy: IPC_AUTH's client tokens, none for a host without it
+pub fn access_policy(auth: Option<&IpcAuthSettings>) -> AccessPolicy {
+    match auth {
+        Some(auth) => AccessPolicy::new(
+            auth.clients
+                .iter()
+                .map(|c| (c.token.clone(), Grant { name: c.name.clone(), allow: Allow::parse(&c.allow) }))
+                .collect(),
+            Allow::default(),
+        ),
+        None => AccessPolicy::default(),
+    }
+}
+
+/// With IPC_AUTH, a command file needs a token whose allow-list covers its
+/// operation, and set_params for any preset or params
+pub fn check_access(policy: &AccessPolicy, command: &InboxCommand) -> Result<(), String> {
+    if !policy.enforced() {
+        return Ok(());
+    }
+    let grant = command.token.as_deref()
+        .and_then(|token| policy.authenticate(token))
+        .ok_or_else(|| "not authorized: missing or unknown token".to_string())?;
+    let changes_params = command.preset.is_some() || command.params.is_some();
+    let needed = command.operation.iter().map(String::as_str).chain(changes_params.then_some(access::SET_PARAMS));
+    for name in needed {
+        if !grant.allow.permits(name) {
+            return Err(format!("not authorized: {} may not {}", grant.name, name));
+        }
+    }
+    Ok(())
+}
+
+/// The next command file to run (lowest name first), if any
+pub fn next_command(dir: &Path) -> Option<PathBuf> {
+    let entries = std::fs::read_dir(dir).ok()?;
+    entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && is_command_file(p))
+        .min()
+}
+
+pub fn read_command(path: &Path) -> Result<InboxCommand, String> {
+    let json = std::fs::read_to_string(path).map_err(|e| format!("cannot read {:?}: {}", path, e))?;
+    serde_json::from_str(&json).map_err(|e| format!("invalid command file {:?}: {}", path, e))
+}
+
+pub fn result_path(command: &Path) -> PathBuf {
+    let stem = command.file_stem().and_then(|s| s.to_str()).unwrap_or("command");
+    command.with_file_name(format!("{}{}", stem, RESULT_SUFFIX))
+}
+
+/// Rename the command file to `<name>.json.done` so next_command skips it
+pub fn retire(command: &Path) -> std::io::Result<()> {
+    let mut done = command.as_os_str().to_owned();
+    done.push(DONE_SUFFIX);
+    std::fs::rename(command, done)
+}
+
+/// Write `<name>.result.json` next to the command (through a temporary file)
+pub fn write_result(command: &Path, result: &InboxResult) -> std::io::Result<()> {
+    let json = serde_json::to_string_pretty(result).map_err(std::io::Error::other)?;
+    let out = result_path(command);
+    let tmp = out.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inbox_runs_commands_once_in_name_order() {
+        let dir = std::env::temp_dir().join(format!("stringdriver_inbox_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("02-bump.json"), r#"{"operation": "bump_check", "max_duration": 90, "rests": {"Z_REST": 0.5}, "bump": {"FINAL_MARGIN": 10}}"#).unwrap();
+        std::fs::write(dir.join("01-rests.json"), r#"{"params": {"LAP_REST": 2.0}}"#).unwrap();
+        std::fs::write(dir.join(".03-home.json.abc123"), "{").unwrap();
+
+        let first = next_command(&dir).unwrap();
+        assert_eq!(first, dir.join("01-rests.json"));
+        let command = read_command(&first).unwrap();
+        assert_eq!(command.operation, None);
+        assert!(command.params.is_some());
+        retire(&first).unwrap();
+
+        let result = InboxResult {
+            command: "01-rests.json".to_string(),
+            status: InboxStatus::Ok,
+            message: "Parameters applied".to_string(),
+            received_at: String::new(),
+            finished_at: String::new(),
+            state: "idle".to_string(),
+            operations: None,
+            config: None,
+        };
+        write_result(&first, &result).unwrap();
+        assert!(dir.join("01-rests.result.json").exists());
+        assert!(dir.join("01-rests.json.done").exists());
+        let written = std::fs::read_to_string(dir.join("01-rests.result.json")).unwrap();
+        assert!(!written.contains("\"config\""), "{}", written);
+
+        // Neither the result nor the retired command is picked up again
+        assert_eq!(next_command(&dir), Some(dir.join("02-bump.json")));
+        let bump = read_command(&dir.join("02-bump.json")).unwrap();
+        assert_eq!(bump.max_duration, Some(90.0));
+        assert_eq!(bump.rests, Some(RestOverrides { z_rest: Some(0.5), ..Default::default() }));
+        assert_eq!(bump.bump, Some(BumpOverrides { final_margin: Some(10), ..Default::default() }));
+        assert_eq!(BumpOverrides { retract_step: Some(0), ..Default::default() }.invalid(), Some("RETRACT_STEP"));
+        assert_eq!(serde_json::to_string(&InboxStatus::Timeout).unwrap(), r#""timeout""#);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_inbox_access_needs_a_token_covering_operation_and_params() {
+        assert_eq!(check_access(&access_policy(None), &InboxCommand::default()), Ok(()));
+
+        let auth = IpcAuthSettings {
+            clients: vec![crate::config_loader::IpcClientSettings {
+                name: "scheduler".to_string(),
+                token: "s3cret".to_string(),
+                allow: vec!["bump_check".to_string()],
+            }],
+            socket_default: Vec::new(),
+        };
+        let policy = access_policy(Some(&auth));
+        let bump = InboxCommand { operation: Some("bump_check".to_string()), token: Some("s3cret".to_string()), ..Default::default() };
+        assert_eq!(check_access(&policy, &bump), Ok(()));
+        assert!(check_access(&policy, &InboxCommand { token: None, ..bump.clone() }).is_err());
+        let with_preset = InboxCommand { preset: Some("rehearsal".to_string()), ..bump };
+        assert_eq!(check_access(&policy, &with_preset), Err("not authorized: scheduler may not set_params".to_string()));
+    }
+}
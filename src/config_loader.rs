@@ -4,19 +4,32 @@
 /// This module loads Arduino, Operations, and GPIO settings for GUI applications.
 
 use serde_yaml;
-use anyhow::{anyhow, Result};
+use crate::error::{Error, Result};
 use std::fs::File;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use std::env;
 use dotenvy::dotenv;
 use gethostname::gethostname;
 
+/// Environment variable naming a different string_driver.yaml, e.g. a test fixture
+pub const CONFIG_ENV: &str = "STRING_DRIVER_CONFIG";
+
+/// string_driver.yaml in the project root, unless STRING_DRIVER_CONFIG names another file
+pub fn config_path() -> PathBuf {
+    match env::var_os(CONFIG_ENV) {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("string_driver.yaml"),
+    }
+}
+
 // -------------------- Arduino (carriage) config --------------------
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ArduinoFirmware {
     StringDriverV1,
     StringDriverV2,
+    StringDriverV3, // V2 command IDs, 32-bit positions in the positions reply
 }
 
 impl ArduinoFirmware {
@@ -24,8 +37,89 @@ impl ArduinoFirmware {
         match value.unwrap_or("string_driver_v2") {
             "string_driver_v1" => Ok(ArduinoFirmware::StringDriverV1),
             "string_driver_v2" => Ok(ArduinoFirmware::StringDriverV2),
-            other => Err(anyhow!("Unknown ARDUINO_FIRMWARE value '{}'", other)),
+            "string_driver_v3" => Ok(ArduinoFirmware::StringDriverV3),
+            other => Err(Error::ConfigInvalid(format!("Unknown ARDUINO_FIRMWARE value '{}'", other))),
+        }
+    }
+}
+
+/// USB identity of a board (ARD_USB / ARD_T_USB); unset fields match anything
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsbIdSettings {
+    pub vid: Option<u16>,
+    pub pid: Option<u16>,
+    pub serial_number: Option<String>,
+}
+
+/// Parse `{ VID: 0x2341, PID: 0x0043, SERIAL: "..." }`; VID/PID may be integers or hex strings
+fn parse_usb_id(host_block: &serde_yaml::Mapping, key: &str, hostname: &str) -> Result<Option<UsbIdSettings>> {
+    let Some(value) = host_block.get(&serde_yaml::Value::from(key)) else { return Ok(None) };
+    if value.is_null() {
+        return Ok(None);
+    }
+    let map = value.as_mapping()
+        .ok_or_else(|| Error::ConfigInvalid(format!("{} for '{}' must be a mapping with VID, PID and/or SERIAL", key, hostname)))?;
+    let id = |field: &str| -> Result<Option<u16>> {
+        match map.get(&serde_yaml::Value::from(field)) {
+            None => Ok(None),
+            Some(v) => v.as_u64()
+                .and_then(|n| u16::try_from(n).ok())
+                .or_else(|| v.as_str().and_then(|s| u16::from_str_radix(s.trim_start_matches("0x"), 16).ok()))
+                .map(Some)
+                .ok_or_else(|| Error::ConfigInvalid(format!("{}.{} for '{}' is not a 16-bit USB id: {:?}", key, field, hostname, v))),
+        }
+    };
+    let settings = UsbIdSettings {
+        vid: id("VID")?,
+        pid: id("PID")?,
+        serial_number: map.get(&serde_yaml::Value::from("SERIAL")).and_then(|v| match v {
+            serde_yaml::Value::String(s) => Some(s.clone()),
+            serde_yaml::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }),
+    };
+    if settings == UsbIdSettings::default() {
+        return Err(Error::ConfigInvalid(format!("{} for '{}' needs at least one of VID, PID or SERIAL", key, hostname)));
+    }
+    Ok(Some(settings))
+}
+
+/// Link to a board (ARD_TRANSPORT / ARD_T_TRANSPORT)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum TransportSettings {
+    #[default]
+    Serial,
+    Ble { name: Option<String>, address: Option<String>, scan: Duration },
+}
+
+/// Parse `serial` or `{ TYPE: ble, NAME: "...", MAC: "...", SCAN_SECONDS: 5 }`
+fn parse_transport(host_block: &serde_yaml::Mapping, key: &str, hostname: &str) -> Result<TransportSettings> {
+    let Some(value) = host_block.get(&serde_yaml::Value::from(key)) else { return Ok(TransportSettings::Serial) };
+    let (kind, map) = match value {
+        serde_yaml::Value::Null => return Ok(TransportSettings::Serial),
+        serde_yaml::Value::String(kind) => (kind.as_str(), None),
+        serde_yaml::Value::Mapping(map) => (
+            map.get(&serde_yaml::Value::from("TYPE")).and_then(|v| v.as_str()).unwrap_or("serial"),
+            Some(map),
+        ),
+        other => return Err(Error::ConfigInvalid(format!("{} for '{}' must be serial or a ble mapping, got {:?}", key, hostname, other))),
+    };
+    match kind {
+        "serial" => Ok(TransportSettings::Serial),
+        "ble" => {
+            let text = |field: &str| map.and_then(|m| m.get(&serde_yaml::Value::from(field))).and_then(|v| v.as_str()).map(str::to_string);
+            let (name, address) = (text("NAME"), text("MAC"));
+            if name.is_none() && address.is_none() {
+                return Err(Error::ConfigInvalid(format!("{} for '{}' needs NAME and/or MAC to find the BLE board", key, hostname)));
+            }
+            let scan = map
+                .and_then(|m| m.get(&serde_yaml::Value::from("SCAN_SECONDS")))
+                .and_then(|v| v.as_f64())
+                .filter(|s| *s > 0.0)
+                .unwrap_or(5.0);
+            Ok(TransportSettings::Ble { name, address, scan: Duration::from_secs_f64(scan) })
         }
+        other => Err(Error::ConfigInvalid(format!("{} for '{}': unknown TYPE '{}' (serial or ble)", key, hostname, other))),
     }
 }
 
@@ -41,31 +135,397 @@ pub struct ArduinoSettings {
     pub ard_t_port: Option<String>, // None means tuners on main board or no tuners
     pub ard_t_num_steppers: Option<usize>, // Number of tuner steppers
     pub firmware: ArduinoFirmware,
+    pub ard_t_firmware: Option<ArduinoFirmware>, // None means tuner board runs string_driver_v2
+    pub encoder_slip_threshold: i32, // Steps of encoder vs step-count disagreement flagged as slippage
+    pub usb: Option<UsbIdSettings>,      // Find the main board by USB identity instead of ARD_PORT alone
+    pub ard_t_usb: Option<UsbIdSettings>, // Same for the tuner board
+    pub transport: TransportSettings,      // ARD_TRANSPORT: serial device or BLE UART for the main board
+    pub ard_t_transport: TransportSettings, // ARD_T_TRANSPORT: same for the tuner board
+    pub checksum: bool,       // Main board firmware ends replies with a CRC-8 (ARD_CHECKSUM)
+    pub ard_t_checksum: bool, // Same for the tuner board (ARD_T_CHECKSUM)
+    pub reply_timeouts: ReplyTimeouts, // ARD_REPLY_TIMEOUTS, applied to both boards
+    pub tuner_steps: TunerSteps,       // TUNER_STEPS nudge sizes for stepper_gui
+    pub jog_rate_hz: f32,              // JOG_RATE_HZ: moves per second while a +/- button or arrow key is held
+    pub motion_params: Option<MotionParams>, // MOTION_PARAMS pushed to the boards on connect (None = firmware defaults)
+    pub strings: Vec<StringInfo>,      // STRINGS metadata, indexed by string (Z pair); may be shorter than STRING_NUM
+    pub axes: Vec<AxisSettings>,       // AXES: linear axes besides X (e.g. a Y bow tilt)
+    pub tuner_safety: Option<TunerSafetySettings>, // TUNER_SAFETY pitch limits (None = tuner moves unchecked)
 }
 
-/// Load ARD_PORT and ARD_NUM_STEPPERS for a given hostname from string_driver.yaml.
-/// Fails loudly if required keys are missing.
-pub fn load_arduino_settings(hostname: &str) -> Result<ArduinoSettings> {
-    let yaml_path: PathBuf = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("string_driver.yaml");
-    let file = File::open(&yaml_path)
-        .map_err(|e| anyhow!("Missing required string_driver.yaml at {:?}: {}", yaml_path, e))?;
-    let yaml: serde_yaml::Value = serde_yaml::from_reader(file)?;
-
-    // Search across known OS sections to find a host block matching hostname
-    let mut host_block: Option<&serde_yaml::Mapping> = None;
-    for os_key in ["RaspberryPi", "Ubuntu", "macOS"].iter() {
-        if let Some(os_map) = yaml.get(*os_key).and_then(|v| v.as_mapping()) {
-            for (k, v) in os_map.iter() {
-                if k.as_str() == Some(hostname) {
-                    host_block = v.as_mapping();
-                    break;
-                }
+/// Descriptive metadata for one string, used for labels in the GUIs and logs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StringInfo {
+    pub name: Option<String>,      // Note name, e.g. "A2"
+    pub gauge: Option<String>,     // As written in the YAML, e.g. "0.042" or "42w"
+    pub scale_length: Option<f32>, // mm
+    pub color: Option<[u8; 3]>,    // Overrides the channel color in stepper_gui
+    pub max_pitch: Option<f32>,    // Hz; tuner moves predicted past it are refused (see tension.rs)
+    pub z_up_step: Option<i32>,    // Replaces the global Z_UP_STEP for this string's Z pair
+    pub z_down_step: Option<i32>,  // Replaces the global Z_DOWN_STEP for this string's Z pair
+}
+
+/// "String 4 (A2)", or "String 4" when the string has no NAME
+pub fn string_label(strings: &[StringInfo], string_idx: usize) -> String {
+    match strings.get(string_idx).and_then(|s| s.name.as_deref()) {
+        Some(name) => format!("String {} ({})", string_idx, name),
+        None => format!("String {}", string_idx),
+    }
+}
+
+/// `[255, 120, 0]` or `"#ff7800"`
+fn parse_string_color(value: &serde_yaml::Value) -> Option<[u8; 3]> {
+    if let Some(hex) = value.as_str() {
+        let hex = hex.strip_prefix('#')?;
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some([channel(0)?, channel(2)?, channel(4)?]);
+    }
+    let list = value.as_sequence().filter(|l| l.len() == 3)?;
+    let mut rgb = [0u8; 3];
+    for (slot, v) in rgb.iter_mut().zip(list) {
+        *slot = u8::try_from(v.as_u64()?).ok()?;
+    }
+    Some(rgb)
+}
+
+/// Parse the optional STRINGS list, one entry per string in Z pair order, e.g.
+/// `STRINGS: [{ NAME: A2, GAUGE: 0.042, SCALE_LENGTH: 650, COLOR: "#ff7800", MAX_PITCH: 115 }, D3]`.
+/// A bare entry is just the NAME. Z_UP_STEP (positive) and Z_DOWN_STEP (negative)
+/// give a string its own Z step sizes, e.g. larger ones for a wound bass string.
+fn parse_strings(host_block: &serde_yaml::Mapping, hostname: &str, string_num: usize) -> Result<Vec<StringInfo>> {
+    let Some(value) = get_either_case(host_block, "strings") else {
+        return Ok(Vec::new());
+    };
+    let entries = value.as_sequence()
+        .ok_or_else(|| Error::ConfigInvalid(format!("STRINGS for '{}' must be a list", hostname)))?;
+    if entries.len() > string_num {
+        return Err(Error::ConfigInvalid(format!("STRINGS for '{}' has {} entries but STRING_NUM is {}", hostname, entries.len(), string_num)));
+    }
+    let mut strings = Vec::new();
+    for (idx, entry) in entries.iter().enumerate() {
+        if let Some(name) = entry.as_str() {
+            strings.push(StringInfo { name: Some(name.to_string()), ..StringInfo::default() });
+            continue;
+        }
+        let entry = entry.as_mapping()
+            .ok_or_else(|| Error::ConfigInvalid(format!("STRINGS entry {} for '{}' must be a name or a mapping", idx, hostname)))?;
+        let text = |key: &str| get_either_case(entry, key).and_then(|v| match v {
+            serde_yaml::Value::String(s) => Some(s.clone()),
+            serde_yaml::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        });
+        let scale_length = get_either_case(entry, "scale_length")
+            .map(|v| v.as_f64()
+                .filter(|l| *l > 0.0)
+                .map(|l| l as f32)
+                .ok_or_else(|| Error::ConfigInvalid(format!("STRINGS entry {} for '{}': SCALE_LENGTH must be a positive number", idx, hostname))))
+            .transpose()?;
+        let color = get_either_case(entry, "color")
+            .map(|v| parse_string_color(v)
+                .ok_or_else(|| Error::ConfigInvalid(format!("STRINGS entry {} for '{}': COLOR must be [r, g, b] or \"#rrggbb\"", idx, hostname))))
+            .transpose()?;
+        let max_pitch = get_either_case(entry, "max_pitch")
+            .map(|v| v.as_f64()
+                .filter(|hz| *hz > 0.0)
+                .map(|hz| hz as f32)
+                .ok_or_else(|| Error::ConfigInvalid(format!("STRINGS entry {} for '{}': MAX_PITCH must be a positive number of Hz", idx, hostname))))
+            .transpose()?;
+        let z_step = |key: &str, upward: bool| {
+            get_either_case(entry, key)
+                .map(|v| v.as_i64()
+                    .filter(|&step| if upward { step > 0 } else { step < 0 })
+                    .and_then(|step| i32::try_from(step).ok())
+                    .ok_or_else(|| Error::ConfigInvalid(format!(
+                        "STRINGS entry {} for '{}': {} must be a {} number of steps",
+                        idx, hostname, key.to_ascii_uppercase(), if upward { "positive" } else { "negative" }
+                    ))))
+                .transpose()
+        };
+        let z_up_step = z_step("z_up_step", true)?;
+        let z_down_step = z_step("z_down_step", false)?;
+        strings.push(StringInfo { name: text("name"), gauge: text("gauge"), scale_length, color, max_pitch, z_up_step, z_down_step });
+    }
+    Ok(strings)
+}
+
+/// Tuner moves checked against each string's pitch curve (see tension.rs)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TunerSafetySettings {
+    pub max_pitch: Vec<Option<f32>>, // Per string, from STRINGS MAX_PITCH
+    pub probe_steps: i32,            // Largest tuner move while a string's curve is unknown
+    pub log_dir: PathBuf,            // Pitch-vs-rotation logs, string_<n>.csv
+    pub samples: usize,              // Newest samples per string the fit uses
+}
+
+/// Parse the optional TUNER_SAFETY block, e.g.
+/// `TUNER_SAFETY: { PROBE_STEPS: 50, LOG_DIR: logs/pitch_curves, SAMPLES: 32 }`.
+/// Giving any string a MAX_PITCH turns it on with those defaults; None when neither is set.
+fn parse_tuner_safety(host_block: &serde_yaml::Mapping, hostname: &str, strings: &[StringInfo]) -> Result<Option<TunerSafetySettings>> {
+    let block = get_either_case(host_block, "tuner_safety");
+    if block.is_none() && strings.iter().all(|s| s.max_pitch.is_none()) {
+        return Ok(None);
+    }
+    let empty = serde_yaml::Mapping::new();
+    let block = match block {
+        None => &empty,
+        Some(value) => value.as_mapping()
+            .ok_or_else(|| Error::ConfigInvalid(format!("TUNER_SAFETY for '{}' must be a mapping", hostname)))?,
+    };
+    let positive = |key: &str, default: i64| match get_either_case(block, key) {
+        None => Ok(default),
+        Some(v) => v.as_i64()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| Error::ConfigInvalid(format!("TUNER_SAFETY {} for '{}' must be a positive whole number", key.to_uppercase(), hostname))),
+    };
+    let probe_steps = positive("probe_steps", 50)? as i32;
+    let samples = positive("samples", 32)? as usize;
+    let log_dir = get_either_case(block, "log_dir")
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("logs/pitch_curves"));
+    Ok(Some(TunerSafetySettings {
+        max_pitch: strings.iter().map(|s| s.max_pitch).collect(),
+        probe_steps,
+        log_dir,
+        samples,
+    }))
+}
+
+/// Tuner nudge sizes for stepper_gui's coarse / fine toggle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TunerSteps {
+    pub coarse: i32,
+    pub fine: i32,
+}
+
+impl Default for TunerSteps {
+    fn default() -> Self {
+        Self { coarse: 100, fine: 10 }
+    }
+}
+
+/// Parse the optional TUNER_STEPS block (steps per nudge), e.g. `{ COARSE: 1000, FINE: 20 }`
+fn parse_tuner_steps(host_block: &serde_yaml::Mapping, hostname: &str) -> Result<TunerSteps> {
+    let mut steps = TunerSteps::default();
+    let Some(value) = host_block.get(&serde_yaml::Value::from("TUNER_STEPS")) else {
+        return Ok(steps);
+    };
+    let block = value.as_mapping()
+        .ok_or_else(|| Error::ConfigInvalid(format!("TUNER_STEPS for '{}' must be a mapping", hostname)))?;
+    for (key, slot) in [("COARSE", &mut steps.coarse), ("FINE", &mut steps.fine)] {
+        let Some(v) = get_either_case(block, key) else { continue; };
+        let step = v.as_i64()
+            .filter(|s| *s > 0)
+            .ok_or_else(|| Error::ConfigInvalid(format!("TUNER_STEPS {} for '{}' must be a positive number of steps", key, hostname)))?;
+        *slot = step as i32;
+    }
+    Ok(steps)
+}
+
+/// Jog moves per second unless JOG_RATE_HZ says otherwise
+pub const DEFAULT_JOG_RATE_HZ: f32 = 10.0;
+
+/// Parse the optional JOG_RATE_HZ (moves per second while a +/- button is held)
+fn parse_jog_rate(host_block: &serde_yaml::Mapping, hostname: &str) -> Result<f32> {
+    let Some(value) = host_block.get(&serde_yaml::Value::from("JOG_RATE_HZ")) else {
+        return Ok(DEFAULT_JOG_RATE_HZ);
+    };
+    value.as_f64()
+        .filter(|hz| *hz > 0.0 && *hz <= 50.0)
+        .map(|hz| hz as f32)
+        .ok_or_else(|| Error::ConfigInvalid(format!("JOG_RATE_HZ for '{}' must be above 0 and at most 50", hostname)))
+}
+
+/// Speed / accel / travel limits for one group of steppers; unset values keep stepper_gui's defaults
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AxisMotion {
+    pub speed: Option<i32>,
+    pub accel: Option<i32>,
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+}
+
+/// Firmware motion parameters per stepper group, re-sent after every connect
+/// because an Arduino reset puts them back to the firmware's defaults
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MotionParams {
+    pub x: AxisMotion,
+    pub z: AxisMotion,
+    pub tuner: AxisMotion,
+}
+
+/// Parse the optional MOTION_PARAMS block, e.g.
+/// `{ X: { SPEED: 500, ACCEL: 10000, MIN: 0, MAX: 2600 }, Z: { SPEED: 100 }, TUNER: { ... } }`
+fn parse_motion_params(host_block: &serde_yaml::Mapping, hostname: &str) -> Result<Option<MotionParams>> {
+    let Some(value) = host_block.get(&serde_yaml::Value::from("MOTION_PARAMS")) else {
+        return Ok(None);
+    };
+    let block = value.as_mapping()
+        .ok_or_else(|| Error::ConfigInvalid(format!("MOTION_PARAMS for '{}' must be a mapping", hostname)))?;
+    let mut params = MotionParams::default();
+    for (group, axis) in [("X", &mut params.x), ("Z", &mut params.z), ("TUNER", &mut params.tuner)] {
+        let Some(v) = get_either_case(block, group) else { continue; };
+        let entry = v.as_mapping()
+            .ok_or_else(|| Error::ConfigInvalid(format!("MOTION_PARAMS {} for '{}' must be a mapping", group, hostname)))?;
+        for (key, slot) in [("SPEED", &mut axis.speed), ("ACCEL", &mut axis.accel), ("MIN", &mut axis.min), ("MAX", &mut axis.max)] {
+            let Some(v) = get_either_case(entry, key) else { continue; };
+            let n = v.as_i64()
+                .and_then(|n| i32::try_from(n).ok())
+                .ok_or_else(|| Error::ConfigInvalid(format!("MOTION_PARAMS {}.{} for '{}' must be a whole number", group, key, hostname)))?;
+            *slot = Some(n);
+        }
+        if axis.speed.is_some_and(|n| n <= 0) || axis.accel.is_some_and(|n| n <= 0) {
+            return Err(Error::ConfigInvalid(format!("MOTION_PARAMS {} for '{}': SPEED and ACCEL must be positive", group, hostname)));
+        }
+        if let (Some(min), Some(max)) = (axis.min, axis.max) {
+            if min >= max {
+                return Err(Error::ConfigInvalid(format!("MOTION_PARAMS {} for '{}': MIN ({}) must be below MAX ({})", group, hostname, min, max)));
+            }
+        }
+    }
+    Ok(Some(params))
+}
+
+/// A linear axis from the AXES list, driven and homed the way X is. X itself keeps
+/// its X_STEP_INDEX / X_MAX_POS / X_REST / X_STEP keys and GPIO_COMPONENTS pins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AxisSettings {
+    pub name: String,          // NAME, upper case (e.g. Y); operations are y_home / y_away / y_calibrate
+    pub step_index: usize,     // STEP_INDEX on the carriage board
+    pub max_pos: i32,          // MAX_POS: the away end in steps; 0 = dummy, home/away skipped
+    pub step: i32,             // STEP: stepper_gui nudge (default 10)
+    pub rest: Option<f32>,     // REST: seconds after each move (None = 5, as X_REST)
+    pub home_pin: Option<u32>, // HOME_PIN at 0 and AWAY_PIN at MAX_POS; GPIO_LINES applies
+    pub away_pin: Option<u32>,
+    pub motion: AxisMotion,    // SPEED / ACCEL pushed on connect like MOTION_PARAMS (no MIN / MAX)
+}
+
+/// Parse the optional AXES list, e.g.
+/// `AXES: [{ NAME: Y, STEP_INDEX: 13, MAX_POS: 800, STEP: 5, REST: 1.0, HOME_PIN: 22, AWAY_PIN: 23, SPEED: 200 }]`
+fn parse_axes(host_block: &serde_yaml::Mapping, hostname: &str) -> Result<Vec<AxisSettings>> {
+    let Some(value) = get_either_case(host_block, "axes") else {
+        return Ok(Vec::new());
+    };
+    let entries = value.as_sequence()
+        .ok_or_else(|| Error::ConfigInvalid(format!("AXES for '{}' must be a list", hostname)))?;
+    let mut axes: Vec<AxisSettings> = Vec::new();
+    for (idx, entry) in entries.iter().enumerate() {
+        let invalid = |msg: String| Error::ConfigInvalid(format!("AXES entry {} for '{}': {}", idx, hostname, msg));
+        let entry = entry.as_mapping().ok_or_else(|| invalid("must be a mapping with NAME, STEP_INDEX and MAX_POS".to_string()))?;
+        let name = get_either_case(entry, "name")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_uppercase())
+            .filter(|s| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric()))
+            .ok_or_else(|| invalid("NAME must be a letter or short name such as Y".to_string()))?;
+        if matches!(name.as_str(), "X" | "Z" | "TUNER") {
+            return Err(invalid(format!("{} has its own keys and cannot be listed in AXES", name)));
+        }
+        if axes.iter().any(|axis| axis.name == name) {
+            return Err(invalid(format!("axis {} is listed twice", name)));
+        }
+        let whole = |key: &str| -> Result<Option<i64>> {
+            match get_either_case(entry, key) {
+                None => Ok(None),
+                Some(v) => v.as_i64().filter(|n| *n >= 0).map(Some)
+                    .ok_or_else(|| invalid(format!("{} must be a non-negative whole number", key.to_uppercase()))),
             }
+        };
+        let step_index = whole("step_index")?.ok_or_else(|| invalid("STEP_INDEX is required".to_string()))? as usize;
+        let max_pos = whole("max_pos")?
+            .and_then(|n| i32::try_from(n).ok())
+            .ok_or_else(|| invalid("MAX_POS is required".to_string()))?;
+        let step = whole("step")?.map_or(10, |n| n as i32);
+        let rest = get_either_case(entry, "rest")
+            .map(|v| v.as_f64().filter(|s| *s >= 0.0).map(|s| s as f32).ok_or_else(|| invalid("REST must be seconds".to_string())))
+            .transpose()?;
+        let motion = AxisMotion {
+            speed: whole("speed")?.map(|n| n as i32),
+            accel: whole("accel")?.map(|n| n as i32),
+            ..AxisMotion::default()
+        };
+        if step == 0 || motion.speed == Some(0) || motion.accel == Some(0) {
+            return Err(invalid("STEP, SPEED and ACCEL must be positive".to_string()));
+        }
+        axes.push(AxisSettings {
+            name,
+            step_index,
+            max_pos,
+            step,
+            rest,
+            home_pin: whole("home_pin")?.map(|n| n as u32),
+            away_pin: whole("away_pin")?.map(|n| n as u32),
+            motion,
+        });
+    }
+    Ok(axes)
+}
+
+/// AXES steppers must be spare carriage board steppers: not X, a Z pair, a tuner or another axis
+fn check_axis_indices(settings: &ArduinoSettings, hostname: &str) -> Result<()> {
+    let z_range = settings.z_first_index.map(|first| first..first + settings.string_num * 2);
+    let tuners = mainboard_tuner_indices(settings);
+    for (i, axis) in settings.axes.iter().enumerate() {
+        let idx = axis.step_index;
+        let clash = if settings.x_step_index == Some(idx) {
+            Some("X_STEP_INDEX".to_string())
+        } else if z_range.as_ref().is_some_and(|range| range.contains(&idx)) {
+            Some("a Z stepper".to_string())
+        } else if tuners.contains(&idx) {
+            Some("a tuner".to_string())
+        } else {
+            settings.axes[..i].iter().find(|other| other.step_index == idx).map(|other| format!("axis {}", other.name))
+        };
+        if let Some(clash) = clash {
+            return Err(Error::ConfigInvalid(format!("AXES {} for '{}': STEP_INDEX {} is already {}", axis.name, hostname, idx, clash)));
+        }
+        if settings.num_steppers.is_some_and(|num| idx >= num) {
+            return Err(Error::ConfigInvalid(format!(
+                "AXES {} for '{}': STEP_INDEX {} is past ARD_NUM_STEPPERS ({})",
+                axis.name, hostname, idx, settings.num_steppers.unwrap_or(0)
+            )));
         }
-        if host_block.is_some() { break; }
     }
+    Ok(())
+}
+
+/// How long to wait for each query's reply before treating the board as not answering
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplyTimeouts {
+    pub positions: Duration,
+    pub encoders: Duration,
+}
+
+impl Default for ReplyTimeouts {
+    fn default() -> Self {
+        Self { positions: Duration::from_secs(2), encoders: Duration::from_secs(2) }
+    }
+}
+
+/// Parse the optional ARD_REPLY_TIMEOUTS block (seconds), e.g. `{ POSITIONS: 2.0, ENCODERS: 0.5 }`
+fn parse_reply_timeouts(host_block: &serde_yaml::Mapping, hostname: &str) -> Result<ReplyTimeouts> {
+    let mut timeouts = ReplyTimeouts::default();
+    let Some(value) = host_block.get(&serde_yaml::Value::from("ARD_REPLY_TIMEOUTS")) else {
+        return Ok(timeouts);
+    };
+    let block = value.as_mapping()
+        .ok_or_else(|| Error::ConfigInvalid(format!("ARD_REPLY_TIMEOUTS for '{}' must be a mapping", hostname)))?;
+    for (key, slot) in [("POSITIONS", &mut timeouts.positions), ("ENCODERS", &mut timeouts.encoders)] {
+        let Some(v) = get_either_case(block, key) else { continue; };
+        let seconds = v.as_f64()
+            .filter(|s| *s > 0.0)
+            .ok_or_else(|| Error::ConfigInvalid(format!("ARD_REPLY_TIMEOUTS {} for '{}' must be a positive number of seconds", key, hostname)))?;
+        *slot = Duration::from_secs_f64(seconds);
+    }
+    Ok(timeouts)
+}
 
-    let host_block = host_block.ok_or_else(|| anyhow!("No host entry for '{}' in string_driver.yaml", hostname))?;
+/// Load ARD_PORT and ARD_NUM_STEPPERS for a given hostname from string_driver.yaml.
+/// Fails loudly if required keys are missing.
+pub fn load_arduino_settings(hostname: &str) -> Result<ArduinoSettings> {
+    let host_block = load_host_block(hostname)?;
+    let host_block = &host_block;
 
     let ard_port = host_block.get(&serde_yaml::Value::from("ARD_PORT"))
         .and_then(|v| {
@@ -129,7 +589,36 @@ pub fn load_arduino_settings(hostname: &str) -> Result<ArduinoSettings> {
             .and_then(|v| v.as_str()),
     )?;
 
-    Ok(ArduinoSettings {
+    let ard_t_firmware = host_block.get(&serde_yaml::Value::from("ARD_T_FIRMWARE"))
+        .and_then(|v| v.as_str())
+        .map(|v| ArduinoFirmware::from_value(Some(v)))
+        .transpose()?;
+
+    let encoder_slip_threshold = host_block.get(&serde_yaml::Value::from("ENCODER_SLIP_THRESHOLD"))
+        .and_then(|v| v.as_i64())
+        .map(|v| v as i32)
+        .unwrap_or(4);
+
+    let usb = parse_usb_id(host_block, "ARD_USB", hostname)?;
+    let ard_t_usb = parse_usb_id(host_block, "ARD_T_USB", hostname)?;
+    let transport = parse_transport(host_block, "ARD_TRANSPORT", hostname)?;
+    let ard_t_transport = parse_transport(host_block, "ARD_T_TRANSPORT", hostname)?;
+
+    let checksum = host_block.get(&serde_yaml::Value::from("ARD_CHECKSUM"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let ard_t_checksum = host_block.get(&serde_yaml::Value::from("ARD_T_CHECKSUM"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let reply_timeouts = parse_reply_timeouts(host_block, hostname)?;
+    let tuner_steps = parse_tuner_steps(host_block, hostname)?;
+    let jog_rate_hz = parse_jog_rate(host_block, hostname)?;
+    let motion_params = parse_motion_params(host_block, hostname)?;
+    let strings = parse_strings(host_block, hostname, string_num)?;
+    let tuner_safety = parse_tuner_safety(host_block, hostname, &strings)?;
+    let axes = parse_axes(host_block, hostname)?;
+
+    let settings = ArduinoSettings {
         port: ard_port,
         num_steppers: num,
         string_num,
@@ -140,7 +629,24 @@ pub fn load_arduino_settings(hostname: &str) -> Result<ArduinoSettings> {
         ard_t_port,
         ard_t_num_steppers,
         firmware,
-    })
+        ard_t_firmware,
+        encoder_slip_threshold,
+        usb,
+        ard_t_usb,
+        transport,
+        ard_t_transport,
+        checksum,
+        ard_t_checksum,
+        reply_timeouts,
+        tuner_steps,
+        jog_rate_hz,
+        motion_params,
+        strings,
+        axes,
+        tuner_safety,
+    };
+    check_axis_indices(&settings, hostname)?;
+    Ok(settings)
 }
 
 pub fn mainboard_tuner_indices(settings: &ArduinoSettings) -> Vec<usize> {
@@ -185,31 +691,34 @@ pub struct OperationsSettings {
     pub x_start: Option<i32>,
     pub x_finish: Option<i32>,
     pub x_step: Option<i32>,
+    // bump_check strategy (None = use Operations defaults)
+    pub bump_retract_step: Option<i32>,
+    pub bump_clear_readings: Option<u32>,
+    pub bump_settle_rest: Option<f32>,
+    pub bump_final_margin: Option<i32>,
+    // Adaptive z_adjust step sizing
+    pub adaptive_z_step: bool,
+    pub z_max_step: Option<i32>,
+    // Performance mode
+    pub performance_rest: Option<f32>,
+    pub performance_bump_interval: Option<u32>,
+    pub performance_use_controller: bool,
+    // Seconds without a new partials frame before audio-driven operations stop
+    pub partials_stale_limit: Option<f32>,
+    // Voice counting amplitude floor: VOICE_FLOOR for all channels, VOICE_FLOORS per channel;
+    // relative floors are fractions of the channel's loudest partial
+    pub voice_floor_relative: bool,
+    pub voice_floor: Option<f32>,
+    pub voice_floors: Vec<f32>,
+    // CHANNEL_MAP[channel] = string (Z pair) index; None = channel i drives string i
+    pub channel_map: Option<Vec<usize>>,
 }
 
 /// Load operations settings for a given hostname from string_driver.yaml.
 /// Fails loudly if required keys are missing.
 pub fn load_operations_settings(hostname: &str) -> Result<OperationsSettings> {
-    let yaml_path: PathBuf = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("string_driver.yaml");
-    let file = File::open(&yaml_path)
-        .map_err(|e| anyhow!("Missing required string_driver.yaml at {:?}: {}", yaml_path, e))?;
-    let yaml: serde_yaml::Value = serde_yaml::from_reader(file)?;
-
-    // Search across known OS sections to find a host block matching hostname
-    let mut host_block: Option<&serde_yaml::Mapping> = None;
-    for os_key in ["RaspberryPi", "Ubuntu", "macOS"].iter() {
-        if let Some(os_map) = yaml.get(*os_key).and_then(|v| v.as_mapping()) {
-            for (k, v) in os_map.iter() {
-                if k.as_str() == Some(hostname) {
-                    host_block = v.as_mapping();
-                    break;
-                }
-            }
-        }
-        if host_block.is_some() { break; }
-    }
-
-    let host_block = host_block.ok_or_else(|| anyhow!("No host entry for '{}' in string_driver.yaml", hostname))?;
+    let host_block = load_host_block(hostname)?;
+    let host_block = &host_block;
 
     let z_up_step = host_block.get(&serde_yaml::Value::from("Z_UP_STEP"))
         .and_then(|v| v.as_i64())
@@ -267,6 +776,74 @@ pub fn load_operations_settings(hostname: &str) -> Result<OperationsSettings> {
         .and_then(|v| v.as_i64())
         .map(|v| v as i32);
 
+    let bump_retract_step = host_block.get(&serde_yaml::Value::from("BUMP_RETRACT_STEP"))
+        .and_then(|v| v.as_i64())
+        .map(|v| v as i32);
+
+    let bump_clear_readings = host_block.get(&serde_yaml::Value::from("BUMP_CLEAR_READINGS"))
+        .and_then(|v| v.as_u64())
+        .map(|v| v as u32);
+
+    let bump_settle_rest = host_block.get(&serde_yaml::Value::from("BUMP_SETTLE_REST"))
+        .and_then(|v| v.as_f64())
+        .map(|v| v as f32);
+
+    let bump_final_margin = host_block.get(&serde_yaml::Value::from("BUMP_FINAL_MARGIN"))
+        .and_then(|v| v.as_i64())
+        .map(|v| v as i32);
+
+    let adaptive_z_step = host_block.get(&serde_yaml::Value::from("ADAPTIVE_Z_STEP"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let z_max_step = host_block.get(&serde_yaml::Value::from("Z_MAX_STEP"))
+        .and_then(|v| v.as_i64())
+        .map(|v| v as i32);
+
+    let performance_rest = host_block.get(&serde_yaml::Value::from("PERFORMANCE_REST"))
+        .and_then(|v| v.as_f64())
+        .map(|v| v as f32);
+
+    let performance_bump_interval = host_block.get(&serde_yaml::Value::from("PERFORMANCE_BUMP_INTERVAL"))
+        .and_then(|v| v.as_u64())
+        .map(|v| v as u32);
+
+    let performance_use_controller = host_block.get(&serde_yaml::Value::from("PERFORMANCE_USE_CONTROLLER"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let partials_stale_limit = host_block.get(&serde_yaml::Value::from("PARTIALS_STALE_LIMIT"))
+        .and_then(|v| v.as_f64())
+        .map(|v| v as f32);
+
+    let voice_floor_relative = match host_block.get(&serde_yaml::Value::from("VOICE_FLOOR_MODE")).and_then(|v| v.as_str()) {
+        None | Some("absolute") => false,
+        Some("relative") => true,
+        Some(other) => return Err(Error::ConfigInvalid(format!("Invalid VOICE_FLOOR_MODE '{}' for '{}' (expected absolute or relative)", other, hostname))),
+    };
+
+    let voice_floor = host_block.get(&serde_yaml::Value::from("VOICE_FLOOR"))
+        .and_then(|v| v.as_f64())
+        .map(|v| v as f32);
+
+    let voice_floors = host_block.get(&serde_yaml::Value::from("VOICE_FLOORS"))
+        .and_then(|v| v.as_sequence())
+        .map(|seq| seq.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect())
+        .unwrap_or_default();
+
+    let channel_map = match host_block.get(&serde_yaml::Value::from("CHANNEL_MAP")) {
+        None | Some(serde_yaml::Value::Null) => None,
+        Some(v) => {
+            let seq = v.as_sequence()
+                .ok_or_else(|| Error::ConfigInvalid(format!("CHANNEL_MAP for '{}' must be a list of string indices", hostname)))?;
+            let map = seq.iter()
+                .map(|e| e.as_u64().map(|n| n as usize)
+                    .ok_or_else(|| Error::ConfigInvalid(format!("CHANNEL_MAP for '{}' contains a non-integer entry: {:?}", hostname, e))))
+                .collect::<Result<Vec<usize>>>()?;
+            Some(map)
+        }
+    };
+
     Ok(OperationsSettings {
         z_up_step,
         z_down_step,
@@ -282,117 +859,2237 @@ pub fn load_operations_settings(hostname: &str) -> Result<OperationsSettings> {
         x_start,
         x_finish,
         x_step,
+        bump_retract_step,
+        bump_clear_readings,
+        bump_settle_rest,
+        bump_final_margin,
+        adaptive_z_step,
+        z_max_step,
+        performance_rest,
+        performance_bump_interval,
+        performance_use_controller,
+        partials_stale_limit,
+        voice_floor_relative,
+        voice_floor,
+        voice_floors,
+        channel_map,
     })
 }
 
-// -------------------- GPIO config --------------------
+/// Check that a CHANNEL_MAP assigns every string to exactly one channel:
+/// STRING_NUM entries forming a permutation of 0..STRING_NUM.
+pub fn validate_channel_map(map: &[usize], string_num: usize) -> Result<()> {
+    if map.len() != string_num {
+        return Err(Error::ConfigInvalid(format!("CHANNEL_MAP has {} entries but STRING_NUM is {}", map.len(), string_num)));
+    }
+    let mut seen = vec![false; string_num];
+    for (channel, &string) in map.iter().enumerate() {
+        if string >= string_num {
+            return Err(Error::ConfigInvalid(format!("CHANNEL_MAP entry {} (channel {}) is out of range 0..{}", string, channel, string_num)));
+        }
+        if seen[string] {
+            return Err(Error::ConfigInvalid(format!("CHANNEL_MAP maps more than one channel to string {}", string)));
+        }
+        seen[string] = true;
+    }
+    Ok(())
+}
 
-#[derive(Debug, Clone)]
-pub struct GpioComponents {
-    pub z_touch_pins: Option<Vec<u32>>,
-    pub x_home_pin: Option<u32>,
-    pub x_away_pin: Option<u32>,
-    pub x_limit_pin: Option<u32>,
-    pub rotary_encoder_pins: Option<RotaryEncoderPins>,
-    pub distance_sensor_pins: Option<DistanceSensorPins>,
+// -------------------- Shared helpers --------------------
+
+/// Load string_driver.yaml and return the block for `hostname`, searching the
+/// known OS sections, with what it inherits (see resolve_host_block)
+fn load_host_block(hostname: &str) -> Result<serde_yaml::Mapping> {
+    load_host_section(hostname).map(|(_, block)| block)
 }
 
-#[derive(Debug, Clone)]
-pub struct RotaryEncoderPins {
-    pub a: u32,
-    pub b: u32,
+/// Top-level block of settings every host inherits
+pub const DEFAULTS_KEY: &str = "DEFAULTS";
+/// Top-level block of named settings blocks a host can build on with EXTENDS
+pub const PROFILES_KEY: &str = "PROFILES";
+
+/// `hostname`'s block as written, and the OS section holding it
+fn find_own_host_block<'a>(yaml: &'a serde_yaml::Value, hostname: &str) -> Option<(&'static str, &'a serde_yaml::Mapping)> {
+    OS_SECTIONS.iter().find_map(|os_key| {
+        yaml.get(*os_key)
+            .and_then(|os| os.get(hostname))
+            .and_then(|v| v.as_mapping())
+            .map(|block| (*os_key, block))
+    })
 }
 
-#[derive(Debug, Clone)]
-pub struct DistanceSensorPins {
-    pub trig: u32,
-    pub echo: u32,
+/// `hostname`'s block with what it inherits, and the OS section holding it. From
+/// the bottom up: DEFAULTS, then each EXTENDS entry in the order listed (a PROFILES
+/// entry, or another host by name, with its own EXTENDS resolved the same way),
+/// then the host's own keys; see merge_blocks for how they combine. None when no
+/// OS section has the host.
+fn resolve_host_block(yaml: &serde_yaml::Value, hostname: &str) -> Result<Option<(&'static str, serde_yaml::Mapping)>> {
+    let Some((os_key, own)) = find_own_host_block(yaml, hostname) else {
+        return Ok(None);
+    };
+    let mut block = match yaml.get(DEFAULTS_KEY) {
+        None => serde_yaml::Mapping::new(),
+        Some(v) => v.as_mapping().cloned()
+            .ok_or_else(|| Error::ConfigInvalid(format!("{} must be a mapping of settings", DEFAULTS_KEY)))?,
+    };
+    let mut chain = vec![hostname.to_string()];
+    merge_blocks(&mut block, resolve_extends(yaml, own, &mut chain)?);
+    Ok(Some((os_key, block)))
 }
 
-#[derive(Debug, Clone)]
-pub struct GpioSettings {
-    pub enabled: bool,
-    pub library: Option<String>,
-    pub max_steps: Option<u32>,
-    pub components: Option<GpioComponents>,
+/// `own` over the blocks its EXTENDS names (without DEFAULTS). `chain` holds the
+/// host and the profiles on the way here, its last entry being `own`'s name.
+fn resolve_extends(yaml: &serde_yaml::Value, own: &serde_yaml::Mapping, chain: &mut Vec<String>) -> Result<serde_yaml::Mapping> {
+    let name = chain.last().cloned().unwrap_or_default();
+    let parents: Vec<String> = match get_either_case(own, "extends") {
+        None => Vec::new(),
+        Some(serde_yaml::Value::String(parent)) => vec![parent.clone()],
+        Some(serde_yaml::Value::Sequence(list)) => list.iter()
+            .map(|v| v.as_str().map(|s| s.to_string()))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| Error::ConfigInvalid(format!("EXTENDS for '{}' must be a profile name or a list of them", name)))?,
+        Some(_) => return Err(Error::ConfigInvalid(format!("EXTENDS for '{}' must be a profile name or a list of them", name))),
+    };
+
+    let mut block = serde_yaml::Mapping::new();
+    for parent in parents {
+        if chain.contains(&parent) {
+            chain.push(parent);
+            return Err(Error::ConfigInvalid(format!("EXTENDS loops back on itself: {}", chain.join(" -> "))));
+        }
+        let parent_block = yaml.get(PROFILES_KEY)
+            .and_then(|profiles| profiles.get(parent.as_str()))
+            .and_then(|v| v.as_mapping())
+            .or_else(|| find_own_host_block(yaml, &parent).map(|(_, block)| block))
+            .ok_or_else(|| Error::ConfigInvalid(format!(
+                "EXTENDS for '{}' names '{}', which is neither a {} entry nor a host", name, parent, PROFILES_KEY
+            )))?;
+        chain.push(parent);
+        let resolved = resolve_extends(yaml, parent_block, chain)?;
+        chain.pop();
+        merge_blocks(&mut block, resolved);
+    }
+    let mut own = own.clone();
+    own.retain(|k, _| !k.as_str().is_some_and(|k| k.eq_ignore_ascii_case("extends")));
+    merge_blocks(&mut block, own);
+    Ok(block)
 }
 
-/// Load GPIO configuration for a given hostname from string_driver.yaml.
-/// Returns None if GPIO_ENABLED is false or not present.
-/// Fails loudly if GPIO_ENABLED is true but required keys are missing.
-pub fn load_gpio_settings(hostname: &str) -> Result<Option<GpioSettings>> {
-    let yaml_path: PathBuf = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("string_driver.yaml");
-    let file = File::open(&yaml_path)
-        .map_err(|e| anyhow!("Missing required string_driver.yaml at {:?}: {}", yaml_path, e))?;
-    let yaml: serde_yaml::Value = serde_yaml::from_reader(file)?;
-
-    // Search across known OS sections to find a host block matching hostname
-    let mut host_block: Option<&serde_yaml::Mapping> = None;
-    for os_key in ["RaspberryPi", "Ubuntu", "macOS"].iter() {
-        if let Some(os_map) = yaml.get(*os_key).and_then(|v| v.as_mapping()) {
-            for (k, v) in os_map.iter() {
-                if k.as_str() == Some(hostname) {
-                    host_block = v.as_mapping();
-                    break;
-                }
+/// Merge `over` into `base`: a mapping present in both is merged key by key the
+/// same way; any other value (a number, a string, a list, null) replaces the
+/// inherited one whole, so a host's STRINGS list is its own and `KEY: null` unsets
+/// an inherited KEY. Keys match as written (`Z_UP_STEP` does not replace `z_up_step`).
+fn merge_blocks(base: &mut serde_yaml::Mapping, over: serde_yaml::Mapping) {
+    for (key, value) in over {
+        match (base.get_mut(&key), value) {
+            (Some(serde_yaml::Value::Mapping(inherited)), serde_yaml::Value::Mapping(over_map)) => merge_blocks(inherited, over_map),
+            (_, value) => {
+                base.insert(key, value);
             }
         }
-        if host_block.is_some() { break; }
     }
+}
 
-    let host_block = host_block.ok_or_else(|| anyhow!("No host entry for '{}' in string_driver.yaml", hostname))?;
+// -------------------- Z controller config --------------------
 
-    // Check if GPIO is enabled
-    let gpio_enabled = host_block.get(&serde_yaml::Value::from("GPIO_ENABLED"))
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false);
+/// PID gains for one string (or the host-wide default)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PidGainsSettings {
+    pub kp: f32,
+    pub ki: f32,
+    pub kd: f32,
+}
 
-    if !gpio_enabled {
-        return Ok(None);
+#[derive(Debug, Clone)]
+pub struct ZControllerSettings {
+    pub metric: String,                            // "amp_sum" or "voice_count"
+    pub default_gains: PidGainsSettings,
+    pub string_gains: Vec<Option<PidGainsSettings>>, // Per-string overrides (index = string/channel)
+    pub integral_limit: f32,
+    pub output_limit: i32,
+    pub period: f32,                               // Seconds between controller updates
+}
+
+fn parse_pid_gains(map: &serde_yaml::Mapping, fallback: PidGainsSettings) -> PidGainsSettings {
+    let get = |key: &str| map.get(&serde_yaml::Value::from(key)).and_then(|v| v.as_f64()).map(|v| v as f32);
+    PidGainsSettings {
+        kp: get("KP").unwrap_or(fallback.kp),
+        ki: get("KI").unwrap_or(fallback.ki),
+        kd: get("KD").unwrap_or(fallback.kd),
     }
+}
 
-    // GPIO is enabled, so we require GPIO_LIBRARY and GPIO_MAX_STEPS
-    let library = host_block.get(&serde_yaml::Value::from("GPIO_LIBRARY"))
+/// Load the optional Z_CONTROLLER block for a given hostname from string_driver.yaml.
+/// Returns None if the host has no Z_CONTROLLER block.
+pub fn load_z_controller_settings(hostname: &str) -> Result<Option<ZControllerSettings>> {
+    let host_block = load_host_block(hostname)?;
+    let host_block = &host_block;
+
+    let ctrl = match host_block.get(&serde_yaml::Value::from("Z_CONTROLLER")).and_then(|v| v.as_mapping()) {
+        Some(m) => m,
+        None => return Ok(None),
+    };
+
+    let metric = ctrl.get(&serde_yaml::Value::from("METRIC"))
         .and_then(|v| v.as_str())
-        .map(|s| s.to_string());
+        .unwrap_or("amp_sum")
+        .to_string();
+    if metric != "amp_sum" && metric != "voice_count" {
+        return Err(Error::ConfigInvalid(format!("Unknown Z_CONTROLLER.METRIC '{}' for '{}' (expected amp_sum or voice_count)", metric, hostname)));
+    }
 
-    let max_steps = host_block.get(&serde_yaml::Value::from("GPIO_MAX_STEPS"))
-        .and_then(|v| v.as_i64())
-        .map(|v| v as u32);
+    let default_gains = parse_pid_gains(ctrl, PidGainsSettings { kp: 0.05, ki: 0.0, kd: 0.0 });
 
-    // Parse GPIO_COMPONENTS
-    let components = host_block.get(&serde_yaml::Value::from("GPIO_COMPONENTS"))
-        .and_then(|v| v.as_mapping())
-        .map(|comp_map| {
-            let z_touch_pins = comp_map.get(&serde_yaml::Value::from("Z_TOUCH_PINS"))
-                .and_then(|v| v.as_sequence())
-                .map(|seq| seq.iter().filter_map(|v| v.as_i64().map(|n| n as u32)).collect());
+    let string_gains = ctrl.get(&serde_yaml::Value::from("STRINGS"))
+        .and_then(|v| v.as_sequence())
+        .map(|seq| {
+            seq.iter()
+                .map(|entry| entry.as_mapping().map(|m| parse_pid_gains(m, default_gains)))
+                .collect()
+        })
+        .unwrap_or_default();
 
-            let x_home_pin = comp_map.get(&serde_yaml::Value::from("X_HOME_PIN"))
-                .and_then(|v| v.as_i64())
-                .map(|n| n as u32);
+    let integral_limit = ctrl.get(&serde_yaml::Value::from("INTEGRAL_LIMIT"))
+        .and_then(|v| v.as_f64())
+        .map(|v| v as f32)
+        .unwrap_or(100.0);
 
-            let x_away_pin = comp_map.get(&serde_yaml::Value::from("X_AWAY_PIN"))
-                .and_then(|v| v.as_i64())
-                .map(|n| n as u32);
+    let output_limit = ctrl.get(&serde_yaml::Value::from("OUTPUT_LIMIT"))
+        .and_then(|v| v.as_i64())
+        .map(|v| v as i32)
+        .unwrap_or(10);
 
-            let x_limit_pin = comp_map.get(&serde_yaml::Value::from("X_LIMIT_PIN"))
-                .and_then(|v| v.as_i64())
-                .map(|n| n as u32);
+    let period = ctrl.get(&serde_yaml::Value::from("PERIOD"))
+        .and_then(|v| v.as_f64())
+        .map(|v| v as f32)
+        .unwrap_or(1.0);
+
+    Ok(Some(ZControllerSettings {
+        metric,
+        default_gains,
+        string_gains,
+        integral_limit,
+        output_limit,
+        period,
+    }))
+}
 
-            let rotary_encoder_pins = comp_map.get(&serde_yaml::Value::from("ROTARY_ENCODER_PINS"))
-                .and_then(|v| v.as_mapping())
-                .and_then(|m| {
-                    let a = m.get(&serde_yaml::Value::from("A"))?.as_i64()? as u32;
-                    let b = m.get(&serde_yaml::Value::from("B"))?.as_i64()? as u32;
-                    Some(RotaryEncoderPins { a, b })
-                });
+// -------------------- Response map config --------------------
 
-            let distance_sensor_pins = comp_map.get(&serde_yaml::Value::from("DISTANCE_SENSOR_PINS"))
-                .and_then(|v| v.as_mapping())
-                .and_then(|m| {
-                    let trig = m.get(&serde_yaml::Value::from("TRIG"))?.as_i64()? as u32;
-                    let echo = m.get(&serde_yaml::Value::from("ECHO"))?.as_i64()? as u32;
+/// Grid and output settings for the response_map sweep
+#[derive(Debug, Clone)]
+pub struct ResponseMapSettings {
+    pub z_min: i32,
+    pub z_max: i32,
+    pub z_step: i32,
+    pub samples: u32,         // Audio readings averaged per grid point
+    pub output_dir: PathBuf,
+}
+
+/// Load the optional RESPONSE_MAP block for a given hostname from string_driver.yaml.
+/// Missing keys fall back to a conservative grid written to ./response_maps.
+pub fn load_response_map_settings(hostname: &str) -> Result<ResponseMapSettings> {
+    let host_block = load_host_block(hostname)?;
+    let block = host_block.get(&serde_yaml::Value::from("RESPONSE_MAP")).and_then(|v| v.as_mapping());
+    let get_i64 = |key: &str| block.and_then(|m| m.get(&serde_yaml::Value::from(key))).and_then(|v| v.as_i64());
+
+    let z_min = get_i64("Z_MIN").map(|v| v as i32).unwrap_or(0);
+    let z_max = get_i64("Z_MAX").map(|v| v as i32).unwrap_or(20);
+    let z_step = get_i64("Z_STEP").map(|v| v as i32).unwrap_or(2);
+    let samples = get_i64("SAMPLES").map(|v| v.max(1) as u32).unwrap_or(5);
+    let output_dir = block
+        .and_then(|m| m.get(&serde_yaml::Value::from("OUTPUT_DIR")))
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("response_maps"));
+
+    if z_step <= 0 {
+        return Err(Error::ConfigInvalid(format!("RESPONSE_MAP.Z_STEP must be positive for '{}' (got {})", hostname, z_step)));
+    }
+    if z_max < z_min {
+        return Err(Error::ConfigInvalid(format!("RESPONSE_MAP.Z_MAX ({}) is below Z_MIN ({}) for '{}'", z_max, z_min, hostname)));
+    }
+
+    Ok(ResponseMapSettings { z_min, z_max, z_step, samples, output_dir })
+}
+
+// -------------------- Height calibration config --------------------
+
+/// Heights and output file for height_calibrate, and the default height target
+#[derive(Debug, Clone)]
+pub struct HeightCalibrationSettings {
+    pub max_steps: i32,               // Highest height above contact to record
+    pub step: i32,
+    pub samples: u32,                 // Audio readings averaged per height
+    pub file: PathBuf,                // Where the curves are saved and loaded from
+    pub target: Option<(i32, i32)>,   // Steps above contact applied as amp_sum thresholds at startup
+}
+
+/// Load the optional HEIGHT_CALIBRATION block for a given hostname from string_driver.yaml.
+/// Missing keys fall back to 0..=20 by 1 step, saved to ./height_maps/height_map_<host>.json.
+pub fn load_height_calibration_settings(hostname: &str) -> Result<HeightCalibrationSettings> {
+    let host_block = load_host_block(hostname)?;
+    let block = host_block.get(&serde_yaml::Value::from("HEIGHT_CALIBRATION")).and_then(|v| v.as_mapping());
+    let get = |key: &str| block.and_then(|m| m.get(&serde_yaml::Value::from(key)));
+    let get_i64 = |key: &str| get(key).and_then(|v| v.as_i64());
+
+    let max_steps = get_i64("MAX_STEPS").map(|v| v as i32).unwrap_or(20);
+    let step = get_i64("STEP").map(|v| v as i32).unwrap_or(1);
+    let samples = get_i64("SAMPLES").map(|v| v.max(1) as u32).unwrap_or(5);
+    let file = get("FILE")
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("height_maps").join(format!("height_map_{}.json", hostname)));
+    let target = match get("TARGET_STEPS").and_then(|v| v.as_sequence()) {
+        Some(seq) => match seq.iter().map(|v| v.as_i64()).collect::<Option<Vec<_>>>().as_deref() {
+            Some([low, high]) if *low >= 0 && low <= high => Some((*low as i32, *high as i32)),
+            _ => {
+                return Err(Error::ConfigInvalid(format!(
+                    "HEIGHT_CALIBRATION.TARGET_STEPS must be [low, high] steps above contact with 0 <= low <= high for '{}'",
+                    hostname
+                )))
+            }
+        },
+        None => None,
+    };
+
+    if step <= 0 {
+        return Err(Error::ConfigInvalid(format!("HEIGHT_CALIBRATION.STEP must be positive for '{}' (got {})", hostname, step)));
+    }
+    if max_steps < step {
+        return Err(Error::ConfigInvalid(format!("HEIGHT_CALIBRATION.MAX_STEPS ({}) is below STEP ({}) for '{}'", max_steps, step, hostname)));
+    }
+
+    Ok(HeightCalibrationSettings { max_steps, step, samples, file, target })
+}
+
+// -------------------- Z homing config --------------------
+
+/// Two-phase Z homing parameters (fast approach, back off, slow re-approach)
+#[derive(Debug, Clone, Copy)]
+pub struct ZHomeSettings {
+    pub backoff: i32,             // Steps to retreat from the sensor before each slow approach
+    pub slow_step: i32,           // Step size (magnitude) for the slow re-approach
+    pub slow_speed: Option<i32>,  // Stepper speed during the slow re-approach (None = unchanged)
+    pub normal_speed: i32,        // Speed restored after the slow re-approach
+    pub samples: u32,             // Slow re-approaches averaged to set zero
+}
+
+/// Load the optional Z_HOME block for a given hostname from string_driver.yaml.
+pub fn load_z_home_settings(hostname: &str) -> Result<ZHomeSettings> {
+    let host_block = load_host_block(hostname)?;
+    let block = host_block.get(&serde_yaml::Value::from("Z_HOME")).and_then(|v| v.as_mapping());
+    let get_i64 = |key: &str| block.and_then(|m| m.get(&serde_yaml::Value::from(key))).and_then(|v| v.as_i64());
+
+    let backoff = get_i64("BACKOFF").map(|v| v as i32).unwrap_or(10);
+    let slow_step = get_i64("SLOW_STEP").map(|v| (v as i32).abs()).unwrap_or(1);
+    let slow_speed = get_i64("SLOW_SPEED").map(|v| v as i32);
+    let normal_speed = get_i64("NORMAL_SPEED").map(|v| v as i32).unwrap_or(100);
+    let samples = get_i64("SAMPLES").map(|v| v.max(1) as u32).unwrap_or(3);
+
+    if backoff <= 0 {
+        return Err(Error::ConfigInvalid(format!("Z_HOME.BACKOFF must be positive for '{}' (got {})", hostname, backoff)));
+    }
+    if slow_step == 0 {
+        return Err(Error::ConfigInvalid(format!("Z_HOME.SLOW_STEP must be non-zero for '{}'", hostname)));
+    }
+
+    Ok(ZHomeSettings { backoff, slow_step, slow_speed, normal_speed, samples })
+}
+
+// -------------------- X move verification config --------------------
+
+/// How sweeps confirm X moves against the positions the board reports
+#[derive(Debug, Clone, Copy)]
+pub struct XVerifySettings {
+    pub timeout: Duration,  // How long a commanded X move may take to show up
+    pub tolerance: i32,     // Steps the reported position may miss the target by
+    pub max_failures: u32,  // Unconfirmed moves in a row before the sweep is aborted
+}
+
+/// Load the optional X_VERIFY block for a given hostname from string_driver.yaml.
+pub fn load_x_verify_settings(hostname: &str) -> Result<XVerifySettings> {
+    let host_block = load_host_block(hostname)?;
+    let block = host_block.get(&serde_yaml::Value::from("X_VERIFY")).and_then(|v| v.as_mapping());
+    let get_i64 = |key: &str| block.and_then(|m| m.get(&serde_yaml::Value::from(key))).and_then(|v| v.as_i64());
+
+    let timeout_ms = get_i64("TIMEOUT_MS").unwrap_or(2000);
+    let tolerance = get_i64("TOLERANCE").map(|v| v as i32).unwrap_or(1);
+    let max_failures = get_i64("MAX_FAILURES").unwrap_or(3);
+
+    if timeout_ms <= 0 {
+        return Err(Error::ConfigInvalid(format!("X_VERIFY.TIMEOUT_MS must be positive for '{}' (got {})", hostname, timeout_ms)));
+    }
+    if tolerance < 0 {
+        return Err(Error::ConfigInvalid(format!("X_VERIFY.TOLERANCE cannot be negative for '{}' (got {})", hostname, tolerance)));
+    }
+    if max_failures <= 0 {
+        return Err(Error::ConfigInvalid(format!("X_VERIFY.MAX_FAILURES must be positive for '{}' (got {})", hostname, max_failures)));
+    }
+
+    Ok(XVerifySettings { timeout: Duration::from_millis(timeout_ms as u64), tolerance, max_failures: max_failures as u32 })
+}
+
+// -------------------- Sweep X regions --------------------
+
+/// Pass criteria for one stretch of the X travel. Each threshold holds one value for
+/// every channel or one per channel; empty keeps the operations_gui threshold.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XRegion {
+    pub x_min: i32,
+    pub x_max: i32, // Inclusive
+    pub amp_min: Vec<f32>,
+    pub amp_max: Vec<f32>,
+    pub voice_min: Vec<usize>,
+    pub voice_max: Vec<usize>,
+}
+
+fn parse_x_region(entry: &serde_yaml::Value, idx: usize, source: &str) -> Result<XRegion> {
+    let invalid = |msg: &str| Error::ConfigInvalid(format!("X_REGIONS entry {} in {}: {}", idx, source, msg));
+    let entry = entry.as_mapping().ok_or_else(|| invalid("must be a mapping with X: [from, to]"))?;
+    let x = get_either_case(entry, "x")
+        .and_then(|v| v.as_sequence())
+        .filter(|r| r.len() == 2)
+        .and_then(|r| Some((r[0].as_i64()? as i32, r[1].as_i64()? as i32)))
+        .ok_or_else(|| invalid("X must be [from, to] in steps"))?;
+    let numbers = |key: &str| -> Result<Vec<f64>> {
+        match get_either_case(entry, key) {
+            None => Ok(Vec::new()),
+            Some(serde_yaml::Value::Sequence(list)) => list.iter()
+                .map(|v| v.as_f64().filter(|n| *n >= 0.0))
+                .collect::<Option<Vec<f64>>>()
+                .ok_or_else(|| invalid(&format!("{} must be non-negative numbers", key.to_uppercase()))),
+            Some(v) => v.as_f64()
+                .filter(|n| *n >= 0.0)
+                .map(|n| vec![n])
+                .ok_or_else(|| invalid(&format!("{} must be a non-negative number or a list per channel", key.to_uppercase()))),
+        }
+    };
+    Ok(XRegion {
+        x_min: x.0.min(x.1),
+        x_max: x.0.max(x.1),
+        amp_min: numbers("amp_min")?.into_iter().map(|n| n as f32).collect(),
+        amp_max: numbers("amp_max")?.into_iter().map(|n| n as f32).collect(),
+        voice_min: numbers("voice_min")?.into_iter().map(|n| n as usize).collect(),
+        voice_max: numbers("voice_max")?.into_iter().map(|n| n as usize).collect(),
+    })
+}
+
+/// Load the optional X_REGIONS list, e.g.
+/// `X_REGIONS: [{ X: [0, 400], AMP_MIN: 20, AMP_MAX: [80, 90, 100, 100], VOICE_MAX: 6 }]`,
+/// or `X_REGIONS: { FILE: response_maps/regions.yaml }` for the same list in its own
+/// YAML/JSON file (e.g. derived from a response_map sweep). Where regions overlap the
+/// first one listed wins.
+pub fn load_x_regions(hostname: &str) -> Result<Vec<XRegion>> {
+    let host_block = load_host_block(hostname)?;
+    let Some(value) = get_either_case(&host_block, "x_regions") else {
+        return Ok(Vec::new());
+    };
+    let (list, source) = match value.as_mapping().and_then(|m| get_either_case(m, "file")).and_then(|v| v.as_str()) {
+        Some(path) => {
+            let file = File::open(path)
+                .map_err(|e| Error::ConfigInvalid(format!("X_REGIONS file {} for '{}' cannot be read: {}", path, hostname, e)))?;
+            (serde_yaml::from_reader::<_, serde_yaml::Value>(file)?, path.to_string())
+        }
+        None => (value.clone(), format!("'{}'", hostname)),
+    };
+    let entries = list.as_sequence()
+        .ok_or_else(|| Error::ConfigInvalid(format!("X_REGIONS in {} must be a list (or {{ FILE: path }})", source)))?;
+    entries.iter().enumerate().map(|(idx, entry)| parse_x_region(entry, idx, &source)).collect()
+}
+
+// -------------------- X carriage clearance --------------------
+
+/// What Operations does about a Z stepper below its clearance before an X move
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClearanceAction {
+    /// Raise the low Z steppers to the zone's Z_MIN, then move X
+    #[default]
+    Raise,
+    /// Stop the operation and report the low Z steppers
+    Refuse,
+}
+
+/// Minimum Z height while the carriage is anywhere in x_min..=x_max
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClearanceZone {
+    pub x_min: i32,
+    pub x_max: i32,      // Inclusive
+    pub z_min: Vec<i32>, // One height for every string, or one per string (Z pair)
+}
+
+impl ClearanceZone {
+    pub fn z_min_for(&self, string_idx: usize) -> Option<i32> {
+        match self.z_min.as_slice() {
+            [all] => Some(*all),
+            per_string => per_string.get(string_idx).copied(),
+        }
+    }
+}
+
+/// Where the X carriage would hit the Z hardware (X_CLEARANCE); no zones = X moves unchecked
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XClearance {
+    pub action: ClearanceAction,
+    pub zones: Vec<ClearanceZone>,
+}
+
+impl XClearance {
+    /// Lowest Z height string `string_idx` may be at while X travels from `from` to `to`:
+    /// the highest Z_MIN of the zones the path crosses, None when it crosses none
+    pub fn required_z(&self, from: i32, to: i32, string_idx: usize) -> Option<i32> {
+        let (lo, hi) = (from.min(to), from.max(to));
+        self.zones
+            .iter()
+            .filter(|zone| zone.x_min <= hi && zone.x_max >= lo)
+            .filter_map(|zone| zone.z_min_for(string_idx))
+            .max()
+    }
+}
+
+/// Load the optional X_CLEARANCE block, e.g.
+/// `X_CLEARANCE: { ACTION: raise, ZONES: [{ X: [0, 300], Z_MIN: 30 }, { X: [1400, 1550], Z_MIN: [20, 20, 35, 35] }] }`.
+/// ACTION is raise (default) or refuse.
+pub fn load_x_clearance(hostname: &str) -> Result<XClearance> {
+    let host_block = load_host_block(hostname)?;
+    let Some(block) = get_either_case(&host_block, "x_clearance").and_then(|v| v.as_mapping()) else {
+        return Ok(XClearance::default());
+    };
+    let invalid = |msg: String| Error::ConfigInvalid(format!("X_CLEARANCE for '{}': {}", hostname, msg));
+    let action = match get_either_case(block, "action") {
+        None => ClearanceAction::Raise,
+        Some(value) => match value.as_str().map(|a| a.to_lowercase()).as_deref() {
+            Some("raise") => ClearanceAction::Raise,
+            Some("refuse") => ClearanceAction::Refuse,
+            _ => return Err(invalid("ACTION must be raise or refuse".to_string())),
+        },
+    };
+    let zones = get_either_case(block, "zones")
+        .and_then(|v| v.as_sequence())
+        .ok_or_else(|| invalid("ZONES must be a list of { X: [from, to], Z_MIN: steps }".to_string()))?;
+    let zones = zones.iter().enumerate().map(|(idx, zone)| {
+        let zone = zone.as_mapping().ok_or_else(|| invalid(format!("zone {} must be a mapping", idx)))?;
+        let x = get_either_case(zone, "x")
+            .and_then(|v| v.as_sequence())
+            .filter(|r| r.len() == 2)
+            .and_then(|r| Some((r[0].as_i64()? as i32, r[1].as_i64()? as i32)))
+            .ok_or_else(|| invalid(format!("zone {}: X must be [from, to] in steps", idx)))?;
+        let z_min = match get_either_case(zone, "z_min") {
+            Some(serde_yaml::Value::Sequence(list)) if !list.is_empty() => {
+                list.iter().map(|v| v.as_i64().map(|n| n as i32)).collect::<Option<Vec<i32>>>()
+            }
+            Some(value) => value.as_i64().map(|n| vec![n as i32]),
+            None => None,
+        }
+        .ok_or_else(|| invalid(format!("zone {}: Z_MIN must be whole steps, one value or a list per string", idx)))?;
+        Ok(ClearanceZone { x_min: x.0.min(x.1), x_max: x.0.max(x.1), z_min })
+    }).collect::<Result<Vec<_>>>()?;
+    Ok(XClearance { action, zones })
+}
+
+// -------------------- Park config --------------------
+
+/// Where the park operation leaves the machine
+#[derive(Debug, Clone, Default)]
+pub struct ParkSettings {
+    pub z_positions: Vec<i32>,   // Safe height per Z stepper, in Z order (missing = the stepper's max)
+    pub x_position: Option<i32>, // X park position (None = X stays put)
+    pub disable_motors: bool,    // Disable every stepper once parked
+}
+
+/// Load the optional PARK block for a given hostname from string_driver.yaml.
+pub fn load_park_settings(hostname: &str) -> Result<ParkSettings> {
+    let host_block = load_host_block(hostname)?;
+    let Some(block) = host_block.get(&serde_yaml::Value::from("PARK")).and_then(|v| v.as_mapping()) else {
+        return Ok(ParkSettings::default());
+    };
+    let get = |key: &str| block.get(&serde_yaml::Value::from(key));
+
+    let z_positions = match get("PARK_POS") {
+        None => Vec::new(),
+        Some(value) => value
+            .as_sequence()
+            .ok_or_else(|| Error::ConfigInvalid(format!("PARK.PARK_POS must be a list of Z positions for '{}'", hostname)))?
+            .iter()
+            .map(|v| v.as_i64().map(|p| p as i32))
+            .collect::<Option<Vec<i32>>>()
+            .ok_or_else(|| Error::ConfigInvalid(format!("PARK.PARK_POS must contain whole step counts for '{}'", hostname)))?,
+    };
+    let x_position = get("X_POS").and_then(|v| v.as_i64()).map(|v| v as i32);
+    let disable_motors = get("DISABLE_MOTORS").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    Ok(ParkSettings { z_positions, x_position, disable_motors })
+}
+
+/// Inactivity watchdog that parks the instrument
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IdleParkSettings {
+    pub timeout: Duration, // No operation and no interaction for this long
+    pub margin: i32,       // Z steppers more than this below their park height count as near the strings
+}
+
+/// Load the optional IDLE_PARK block, e.g. `IDLE_PARK: { MINUTES: 30, MARGIN: 10 }`.
+/// None when the block is absent (no watchdog).
+pub fn load_idle_park(hostname: &str) -> Result<Option<IdleParkSettings>> {
+    let host_block = load_host_block(hostname)?;
+    let Some(block) = get_either_case(&host_block, "idle_park").and_then(|v| v.as_mapping()) else {
+        return Ok(None);
+    };
+    let timeout = get_either_case(block, "minutes")
+        .and_then(|v| v.as_f64())
+        .filter(|m| *m > 0.0)
+        .map(|m| Duration::from_secs_f64(m * 60.0))
+        .ok_or_else(|| Error::ConfigInvalid(format!("IDLE_PARK.MINUTES for '{}' must be a positive number of minutes", hostname)))?;
+    let margin = match get_either_case(block, "margin") {
+        None => 10,
+        Some(value) => value.as_i64()
+            .filter(|m| *m >= 0)
+            .ok_or_else(|| Error::ConfigInvalid(format!("IDLE_PARK.MARGIN for '{}' must be a whole number of steps", hostname)))? as i32,
+    };
+    Ok(Some(IdleParkSettings { timeout, margin }))
+}
+
+// -------------------- Bow drive config --------------------
+
+/// Where a bow wheel's speed goes
+#[derive(Debug, Clone, PartialEq)]
+pub enum BowOutput {
+    /// Kernel PWM channels, one per string: /sys/class/pwm/pwmchip<chip>/pwm<channel>
+    Pwm { chip: u32, channels: Vec<u32>, period_ns: u32 },
+    /// Binary command to the carriage board: COMMAND_ID <string> <speed>
+    Serial { command_id: u8 },
+}
+
+/// Rotary bow wheels, one per string (BOW_DRIVE)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BowDriveSettings {
+    pub output: BowOutput,
+    pub speeds: Vec<u32>, // Setpoint for every string, or one per string
+    pub max_speed: u32,   // Full scale (100% duty with PWM)
+    pub min_speed: u32,   // z_adjust never slows a wheel below this
+    pub speed_step: u32,  // z_adjust change per out-of-range channel
+    pub modulate: bool,   // z_adjust changes wheel speed for amplitude before moving Z
+}
+
+impl BowDriveSettings {
+    pub fn speed_for(&self, string_idx: usize) -> u32 {
+        match self.speeds.as_slice() {
+            [all] => *all,
+            per_string => per_string.get(string_idx).copied().unwrap_or(0),
+        }
+    }
+}
+
+/// Load the optional BOW_DRIVE block, e.g.
+/// `BOW_DRIVE: { OUTPUT: pwm, PWM_CHIP: 0, PWM_CHANNELS: [0, 1], SPEED: [120, 140], MODULATE: true }` or
+/// `BOW_DRIVE: { OUTPUT: serial, COMMAND_ID: 15, SPEED: 120 }`. None when the block is absent.
+pub fn load_bow_drive_settings(hostname: &str) -> Result<Option<BowDriveSettings>> {
+    let host_block = load_host_block(hostname)?;
+    let Some(block) = get_either_case(&host_block, "bow_drive").and_then(|v| v.as_mapping()) else {
+        return Ok(None);
+    };
+    let invalid = |msg: &str| Error::ConfigInvalid(format!("BOW_DRIVE for '{}': {}", hostname, msg));
+    let whole = |key: &str| -> Result<Option<u32>> {
+        match get_either_case(block, key) {
+            None => Ok(None),
+            Some(value) => value.as_u64()
+                .and_then(|n| u32::try_from(n).ok())
+                .map(Some)
+                .ok_or_else(|| invalid(&format!("{} must be a whole number", key.to_uppercase()))),
+        }
+    };
+    let output = match get_either_case(block, "output").and_then(|v| v.as_str()).map(str::to_lowercase).as_deref() {
+        Some("pwm") => {
+            let channels = get_either_case(block, "pwm_channels")
+                .and_then(|v| v.as_sequence())
+                .and_then(|list| list.iter().map(|v| v.as_u64().map(|n| n as u32)).collect::<Option<Vec<u32>>>())
+                .filter(|list| !list.is_empty())
+                .ok_or_else(|| invalid("PWM_CHANNELS must list a PWM channel per string"))?;
+            let period_ns = whole("period_ns")?.unwrap_or(50_000);
+            if period_ns == 0 {
+                return Err(invalid("PERIOD_NS must be positive"));
+            }
+            BowOutput::Pwm { chip: whole("pwm_chip")?.unwrap_or(0), channels, period_ns }
+        }
+        Some("serial") => {
+            let command_id = whole("command_id")?
+                .ok_or_else(|| invalid("COMMAND_ID is required with OUTPUT: serial"))?;
+            BowOutput::Serial {
+                command_id: u8::try_from(command_id).map_err(|_| invalid("COMMAND_ID must be 0-255"))?,
+            }
+        }
+        _ => return Err(invalid("OUTPUT must be pwm or serial")),
+    };
+    let max_speed = whole("max_speed")?.unwrap_or(255);
+    if max_speed == 0 {
+        return Err(invalid("MAX_SPEED must be positive"));
+    }
+    let speeds = match get_either_case(block, "speed") {
+        None => vec![0],
+        Some(serde_yaml::Value::Sequence(list)) if !list.is_empty() => list
+            .iter()
+            .map(|v| v.as_u64().map(|n| n as u32))
+            .collect::<Option<Vec<u32>>>()
+            .ok_or_else(|| invalid("SPEED must be whole numbers"))?,
+        Some(value) => vec![value.as_u64().ok_or_else(|| invalid("SPEED must be one value or a list per string"))? as u32],
+    };
+    if speeds.iter().any(|&s| s > max_speed) {
+        return Err(invalid(&format!("SPEED must not exceed MAX_SPEED ({})", max_speed)));
+    }
+    let min_speed = whole("min_speed")?.unwrap_or(0);
+    if min_speed > max_speed {
+        return Err(invalid("MIN_SPEED must not exceed MAX_SPEED"));
+    }
+    Ok(Some(BowDriveSettings {
+        output,
+        speeds,
+        max_speed,
+        min_speed,
+        speed_step: whole("speed_step")?.unwrap_or(5).max(1),
+        modulate: get_either_case(block, "modulate").and_then(|v| v.as_bool()).unwrap_or(false),
+    }))
+}
+
+// -------------------- Dampers config --------------------
+
+/// What drives the damper relays
+#[derive(Debug, Clone, PartialEq)]
+pub enum DamperOutput {
+    /// One GPIO output line per string, driven by Operations' GPIO board
+    Gpio { pins: Vec<u32>, active_low: bool },
+    /// Digital-out command to the carriage board: COMMAND_ID <string> <1|0>
+    Serial { command_id: u8 },
+}
+
+/// Per-string dampers on relays (DAMPERS)
+#[derive(Debug, Clone, PartialEq)]
+pub struct DamperSettings {
+    pub output: DamperOutput,
+    pub settle: Duration,        // Wait after the dampers change before the next move
+    pub during: Vec<String>,     // Operations that run with every string damped
+}
+
+impl DamperSettings {
+    /// Firmware command id when the carriage board drives the relays
+    pub fn serial_command(&self) -> Option<u8> {
+        match self.output {
+            DamperOutput::Serial { command_id } => Some(command_id),
+            DamperOutput::Gpio { .. } => None,
+        }
+    }
+}
+
+/// Load the optional DAMPERS block, e.g.
+/// `DAMPERS: { OUTPUT: gpio, PINS: [5, 6, 13, 19], ACTIVE_LOW: true, SETTLE: 0.3, DURING: [z_calibrate, x_calibrate] }` or
+/// `DAMPERS: { OUTPUT: serial, COMMAND_ID: 16 }`. DURING defaults to [z_calibrate]. None when the block is absent.
+pub fn load_damper_settings(hostname: &str) -> Result<Option<DamperSettings>> {
+    let host_block = load_host_block(hostname)?;
+    parse_dampers(&host_block, hostname)
+}
+
+fn parse_dampers(host_block: &serde_yaml::Mapping, hostname: &str) -> Result<Option<DamperSettings>> {
+    let Some(block) = get_either_case(host_block, "dampers").and_then(|v| v.as_mapping()) else {
+        return Ok(None);
+    };
+    let invalid = |msg: &str| Error::ConfigInvalid(format!("DAMPERS for '{}': {}", hostname, msg));
+    let output = match get_either_case(block, "output").and_then(|v| v.as_str()).map(str::to_lowercase).as_deref() {
+        Some("gpio") => {
+            let pins = get_either_case(block, "pins")
+                .and_then(|v| v.as_sequence())
+                .and_then(|list| list.iter().map(|v| v.as_u64().map(|n| n as u32)).collect::<Option<Vec<u32>>>())
+                .filter(|list| !list.is_empty())
+                .ok_or_else(|| invalid("PINS must list a relay GPIO line per string"))?;
+            let active_low = get_either_case(block, "active_low").and_then(|v| v.as_bool()).unwrap_or(false);
+            DamperOutput::Gpio { pins, active_low }
+        }
+        Some("serial") => {
+            let command_id = get_either_case(block, "command_id")
+                .and_then(|v| v.as_u64())
+                .and_then(|n| u8::try_from(n).ok())
+                .ok_or_else(|| invalid("COMMAND_ID (0-255) is required with OUTPUT: serial"))?;
+            DamperOutput::Serial { command_id }
+        }
+        _ => return Err(invalid("OUTPUT must be gpio or serial")),
+    };
+    let settle = match get_either_case(block, "settle") {
+        None => Duration::from_millis(300),
+        Some(value) => value.as_f64()
+            .filter(|s| *s >= 0.0)
+            .map(Duration::from_secs_f64)
+            .ok_or_else(|| invalid("SETTLE must be a number of seconds"))?,
+    };
+    let during = match get_either_case(block, "during") {
+        None => vec!["z_calibrate".to_string()],
+        Some(value) => value.as_sequence()
+            .and_then(|list| list.iter().map(|v| v.as_str().map(str::to_lowercase)).collect::<Option<Vec<String>>>())
+            .ok_or_else(|| invalid("DURING must be a list of operation names"))?,
+    };
+    Ok(Some(DamperSettings { output, settle, during }))
+}
+
+// -------------------- Touch sensor health --------------------
+
+/// When a Z touch sensor counts as broken, and what to do about it
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorHealthSettings {
+    pub stuck_pressed: Duration,  // Pressed continuously for this long
+    pub silent_calibrations: u32, // Calibrations in a row without a touch (0 = not checked)
+    pub auto_quarantine: bool,    // Quarantine a stuck sensor instead of disabling its stepper
+    pub quarantine: Vec<usize>,   // Z touch indices quarantined from the start
+}
+
+impl Default for SensorHealthSettings {
+    fn default() -> Self {
+        Self { stuck_pressed: Duration::from_secs(10 * 60), silent_calibrations: 3, auto_quarantine: false, quarantine: Vec::new() }
+    }
+}
+
+/// Load the optional SENSOR_HEALTH block, e.g.
+/// `SENSOR_HEALTH: { STUCK_MINUTES: 10, SILENT_CALIBRATIONS: 3, AUTO_QUARANTINE: true, QUARANTINE: [2] }`.
+pub fn load_sensor_health_settings(hostname: &str) -> Result<SensorHealthSettings> {
+    let host_block = load_host_block(hostname)?;
+    let mut settings = SensorHealthSettings::default();
+    let Some(block) = get_either_case(&host_block, "sensor_health").and_then(|v| v.as_mapping()) else {
+        return Ok(settings);
+    };
+    if let Some(value) = get_either_case(block, "stuck_minutes") {
+        let minutes = value.as_f64()
+            .filter(|m| *m > 0.0)
+            .ok_or_else(|| Error::ConfigInvalid(format!("SENSOR_HEALTH.STUCK_MINUTES for '{}' must be a positive number of minutes", hostname)))?;
+        settings.stuck_pressed = Duration::from_secs_f64(minutes * 60.0);
+    }
+    if let Some(value) = get_either_case(block, "silent_calibrations") {
+        settings.silent_calibrations = value.as_u64()
+            .ok_or_else(|| Error::ConfigInvalid(format!("SENSOR_HEALTH.SILENT_CALIBRATIONS for '{}' must be a whole number", hostname)))? as u32;
+    }
+    if let Some(value) = get_either_case(block, "auto_quarantine") {
+        settings.auto_quarantine = value.as_bool()
+            .ok_or_else(|| Error::ConfigInvalid(format!("SENSOR_HEALTH.AUTO_QUARANTINE for '{}' must be true or false", hostname)))?;
+    }
+    if let Some(value) = get_either_case(block, "quarantine") {
+        settings.quarantine = value.as_sequence()
+            .and_then(|seq| seq.iter().map(|v| v.as_u64().map(|n| n as usize)).collect())
+            .ok_or_else(|| Error::ConfigInvalid(format!("SENSOR_HEALTH.QUARANTINE for '{}' must be a list of Z touch indices", hostname)))?;
+    }
+    Ok(settings)
+}
+
+// -------------------- Logging config --------------------
+
+/// Where the GUIs and launcher write their log files, and how large they may grow
+#[derive(Debug, Clone)]
+pub struct LoggingSettings {
+    pub dir: PathBuf,          // Base directory; files go under <dir>/<hostname>/
+    pub max_file_bytes: u64,   // Rotate a log file once it reaches this size
+    pub keep_files: usize,     // Rotated files kept per log (<name>.log.1 .. .N)
+    pub machine_state: MachineStateLogSettings, // MACHINE_STATE: where machine_state_logger writes
+}
+
+/// Where machine_state_logger sends the 1 Hz machine state and the operation history
+#[derive(Debug, Clone, PartialEq)]
+pub struct MachineStateLogSettings {
+    pub postgres: bool,                // The machine_state / operations tables (PG_* / DB_* environment)
+    pub influx: Option<InfluxTarget>,  // InfluxDB line protocol
+}
+
+impl Default for MachineStateLogSettings {
+    fn default() -> Self {
+        Self { postgres: true, influx: None }
+    }
+}
+
+/// Where line protocol goes
+#[derive(Debug, Clone, PartialEq)]
+pub enum InfluxTarget {
+    /// POSTed to a write endpoint: v1 `/write?db=..` or v2 `/api/v2/write?org=..&bucket=..`
+    Http { url: String, token_env: Option<String> }, // Environment variable holding the API token
+    /// Appended to a file, e.g. for Telegraf's tail input
+    File(PathBuf),
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("logs"),
+            max_file_bytes: 10 * 1024 * 1024,
+            keep_files: 5,
+            machine_state: MachineStateLogSettings::default(),
+        }
+    }
+}
+
+/// Load the optional LOGGING block for a given hostname from string_driver.yaml.
+/// A relative DIR or MACHINE_STATE.INFLUX.FILE is taken relative to the project root.
+pub fn load_logging_settings(hostname: &str) -> Result<LoggingSettings> {
+    let host_block = load_host_block(hostname)?;
+    let block = host_block.get(&serde_yaml::Value::from("LOGGING")).and_then(|v| v.as_mapping());
+    let get = |key: &str| block.and_then(|m| m.get(&serde_yaml::Value::from(key)));
+    let defaults = LoggingSettings::default();
+
+    let dir = match get("DIR").and_then(|v| v.as_str()) {
+        Some(dir) => PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(dir),
+        None => defaults.dir,
+    };
+    let max_file_bytes = match get("MAX_FILE_MB").and_then(|v| v.as_f64()) {
+        Some(mb) if mb > 0.0 => (mb * 1024.0 * 1024.0) as u64,
+        Some(mb) => return Err(Error::ConfigInvalid(format!("LOGGING.MAX_FILE_MB must be positive for '{}' (got {})", hostname, mb))),
+        None => defaults.max_file_bytes,
+    };
+    let keep_files = get("KEEP_FILES").and_then(|v| v.as_u64()).map(|v| v as usize).unwrap_or(defaults.keep_files);
+    let machine_state = match get("MACHINE_STATE") {
+        Some(value) => parse_machine_state_log(value, hostname)?,
+        None => defaults.machine_state,
+    };
+
+    Ok(LoggingSettings { dir, max_file_bytes, keep_files, machine_state })
+}
+
+/// LOGGING.MACHINE_STATE, e.g. `{ BACKENDS: [postgres, influx], INFLUX: { URL: ..., TOKEN_ENV: INFLUX_TOKEN } }`
+fn parse_machine_state_log(value: &serde_yaml::Value, hostname: &str) -> Result<MachineStateLogSettings> {
+    let invalid = |msg: &str| Error::ConfigInvalid(format!("LOGGING.MACHINE_STATE for '{}': {}", hostname, msg));
+    let block = value.as_mapping().ok_or_else(|| invalid("must be a mapping"))?;
+    let backends: Vec<String> = match get_either_case(block, "backends") {
+        None => vec!["postgres".to_string()],
+        Some(serde_yaml::Value::String(one)) => vec![one.to_lowercase()],
+        Some(serde_yaml::Value::Sequence(list)) => list
+            .iter()
+            .map(|v| v.as_str().map(str::to_lowercase))
+            .collect::<Option<_>>()
+            .ok_or_else(|| invalid("BACKENDS must list backend names"))?,
+        Some(_) => return Err(invalid("BACKENDS must be a backend name or a list of them")),
+    };
+    if let Some(other) = backends.iter().find(|b| *b != "postgres" && *b != "influx") {
+        return Err(invalid(&format!("unknown backend '{}' (expected postgres or influx)", other)));
+    }
+    let influx = if backends.iter().any(|b| b == "influx") {
+        let influx = get_either_case(block, "influx")
+            .and_then(|v| v.as_mapping())
+            .ok_or_else(|| invalid("the influx backend needs an INFLUX mapping with a URL or FILE"))?;
+        let get_str = |key: &str| get_either_case(influx, key).and_then(|v| v.as_str()).map(str::to_string);
+        match (get_str("url"), get_str("file")) {
+            (Some(url), None) => Some(InfluxTarget::Http { url, token_env: get_str("token_env") }),
+            (None, Some(file)) => Some(InfluxTarget::File(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(file))),
+            _ => return Err(invalid("INFLUX needs either a URL or a FILE")),
+        }
+    } else {
+        None
+    };
+    Ok(MachineStateLogSettings { postgres: backends.iter().any(|b| b == "postgres"), influx })
+}
+
+// -------------------- GUI language --------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocaleSettings {
+    pub language: Option<String>, // LANGUAGE, e.g. de; None = English
+    pub dir: PathBuf,             // Where <language>.yaml translation files are looked up
+}
+
+impl Default for LocaleSettings {
+    fn default() -> Self {
+        Self { language: None, dir: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("locales") }
+    }
+}
+
+/// Load LANGUAGE and LOCALES_DIR for a given hostname, e.g. `LANGUAGE: de`.
+/// A relative LOCALES_DIR is taken relative to the project root (default locales/).
+pub fn load_locale_settings(hostname: &str) -> Result<LocaleSettings> {
+    let host_block = load_host_block(hostname)?;
+    let mut settings = LocaleSettings::default();
+    if let Some(value) = get_either_case(&host_block, "language") {
+        let language = value.as_str()
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .ok_or_else(|| Error::ConfigInvalid(format!("LANGUAGE for '{}' must be a language code such as de or pt-br", hostname)))?;
+        settings.language = (language != "en").then_some(language);
+    }
+    if let Some(dir) = get_either_case(&host_block, "locales_dir").and_then(|v| v.as_str()) {
+        settings.dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(dir);
+    }
+    Ok(settings)
+}
+
+// -------------------- Launcher config --------------------
+
+/// How the launcher decides a component has finished starting
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReadinessCheck {
+    Socket(String),                                // Unix socket path exists
+    File { path: String, contains: Option<String> }, // File exists and is non-empty (and contains the text, if given)
+    Port(u16),                                     // TCP port on localhost accepts connections
+}
+
+/// What the launcher does when a component exits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    Never,
+    OnFailure, // Restart on non-zero exit
+    Always,
+}
+
+impl RestartPolicy {
+    fn from_value(value: Option<&str>) -> Result<Self> {
+        match value.unwrap_or("never") {
+            "never" => Ok(RestartPolicy::Never),
+            "on_failure" => Ok(RestartPolicy::OnFailure),
+            "always" => Ok(RestartPolicy::Always),
+            other => Err(Error::ConfigInvalid(format!("Unknown RESTART value '{}' (expected never, on_failure or always)", other))),
+        }
+    }
+}
+
+/// One entry of the LAUNCH list: a process the launcher starts and supervises
+#[derive(Debug, Clone)]
+pub struct LaunchComponent {
+    pub name: String,
+    pub command: Vec<String>,          // Program followed by its arguments
+    pub working_dir: Option<PathBuf>,  // Relative paths are taken from the project root
+    pub build_bin: Option<String>,     // cargo --release binary to (re)build in working_dir before launch
+    pub ready: Option<ReadinessCheck>,
+    pub ready_timeout: f32,            // Seconds to wait for `ready` before moving on
+    pub restart: RestartPolicy,
+    pub processes: Vec<String>,        // Other processes it leaves running (xterm titles, children), for shutdown
+}
+
+/// Built-in component lists used when the host has no LAUNCH section: master_gui
+/// through master_gui.sh, or (`separate_mode`) audio_monitor, stepper_gui and operations_gui
+pub fn default_launch_components(separate_mode: bool, project_root: &Path) -> Vec<LaunchComponent> {
+    if !separate_mode {
+        // master_gui.sh keeps master_gui running itself
+        return vec![LaunchComponent {
+            name: "master_gui".to_string(),
+            command: vec!["bash".to_string(), "master_gui.sh".to_string()],
+            working_dir: None,
+            build_bin: Some("master_gui".to_string()),
+            ready: Some(ReadinessCheck::File {
+                path: project_root.join(".master_gui_status").to_string_lossy().to_string(),
+                contains: Some("ready".to_string()),
+            }),
+            ready_timeout: 30.0,
+            restart: RestartPolicy::Never,
+            processes: vec![
+                "target/release/master_gui".to_string(),
+                "Master GUI Persist Monitor".to_string(),
+                "qjackctl".to_string(),
+            ],
+        }];
+    }
+    vec![
+        // audmon.sh maintains persistence for JACK audio
+        LaunchComponent {
+            name: "audio_monitor".to_string(),
+            command: vec!["bash".to_string(), "audmon.sh".to_string()],
+            working_dir: Some(PathBuf::from("../audmon")),
+            build_bin: Some("audio_monitor".to_string()),
+            ready: Some(ReadinessCheck::File { path: "{shm}".to_string(), contains: None }),
+            ready_timeout: 30.0,
+            restart: RestartPolicy::Never,
+            processes: vec![
+                "target/release/audio_monitor".to_string(),
+                "Persist Monitor".to_string(),
+                "qjackctl".to_string(),
+            ],
+        },
+        LaunchComponent {
+            name: "stepper_gui".to_string(),
+            command: vec!["target/release/stepper_gui".to_string()],
+            working_dir: None,
+            build_bin: Some("stepper_gui".to_string()),
+            ready: Some(ReadinessCheck::Socket("{stepper_socket}".to_string())),
+            ready_timeout: 6.0,
+            restart: RestartPolicy::Never,
+            processes: Vec::new(),
+        },
+        LaunchComponent {
+            name: "operations_gui".to_string(),
+            command: vec!["target/release/operations_gui".to_string()],
+            working_dir: None,
+            build_bin: Some("operations_gui".to_string()),
+            ready: None,
+            ready_timeout: 0.0,
+            restart: RestartPolicy::Never,
+            processes: Vec::new(),
+        },
+    ]
+}
+
+/// Load the optional LAUNCH list for a given hostname from string_driver.yaml.
+/// Returns an empty list when the host has no LAUNCH section.
+pub fn load_launch_settings(hostname: &str) -> Result<Vec<LaunchComponent>> {
+    let host_block = load_host_block(hostname)?;
+    let Some(list) = host_block.get(&serde_yaml::Value::from("LAUNCH")) else {
+        return Ok(Vec::new());
+    };
+    let entries = list.as_sequence()
+        .ok_or_else(|| Error::ConfigInvalid(format!("LAUNCH must be a list of components for '{}'", hostname)))?;
+
+    let mut components = Vec::with_capacity(entries.len());
+    for (idx, entry) in entries.iter().enumerate() {
+        let map = entry.as_mapping()
+            .ok_or_else(|| Error::ConfigInvalid(format!("LAUNCH[{}] must be a mapping for '{}'", idx, hostname)))?;
+        let get_str = |key: &str| map.get(&serde_yaml::Value::from(key)).and_then(|v| v.as_str());
+
+        let name = get_str("NAME")
+            .ok_or_else(|| Error::ConfigMissing(format!("LAUNCH[{}] is missing NAME for '{}'", idx, hostname)))?
+            .to_string();
+        let command: Vec<String> = get_str("COMMAND")
+            .ok_or_else(|| Error::ConfigMissing(format!("LAUNCH component '{}' is missing COMMAND", name)))?
+            .split_whitespace()
+            .map(|s| s.to_string())
+            .collect();
+        if command.is_empty() {
+            return Err(Error::ConfigInvalid(format!("LAUNCH component '{}' has an empty COMMAND", name)));
+        }
+
+        let ready = match map.get(&serde_yaml::Value::from("READY")).and_then(|v| v.as_mapping()) {
+            None => None,
+            Some(r) => {
+                let get_ready = |key: &str| r.get(&serde_yaml::Value::from(key));
+                if let Some(path) = get_ready("SOCKET").and_then(|v| v.as_str()) {
+                    Some(ReadinessCheck::Socket(path.to_string()))
+                } else if let Some(path) = get_ready("FILE").and_then(|v| v.as_str()) {
+                    let contains = get_ready("CONTAINS").and_then(|v| v.as_str()).map(|s| s.to_string());
+                    Some(ReadinessCheck::File { path: path.to_string(), contains })
+                } else if let Some(port) = get_ready("PORT").and_then(|v| v.as_u64()) {
+                    let port = u16::try_from(port)
+                        .map_err(|_| Error::ConfigInvalid(format!("LAUNCH component '{}' READY.PORT {} is out of range", name, port)))?;
+                    Some(ReadinessCheck::Port(port))
+                } else {
+                    return Err(Error::ConfigInvalid(format!("LAUNCH component '{}' READY needs SOCKET, FILE or PORT", name)));
+                }
+            }
+        };
+
+        let processes = match map.get(&serde_yaml::Value::from("PROCESSES")) {
+            None => Vec::new(),
+            Some(v) => v.as_sequence()
+                .and_then(|list| list.iter().map(|p| p.as_str().map(|s| s.to_string())).collect::<Option<Vec<_>>>())
+                .ok_or_else(|| Error::ConfigInvalid(format!("LAUNCH component '{}' PROCESSES must be a list of strings", name)))?,
+        };
+
+        components.push(LaunchComponent {
+            working_dir: get_str("WORKING_DIR").map(PathBuf::from),
+            build_bin: get_str("BUILD_BIN").map(|s| s.to_string()),
+            ready,
+            ready_timeout: map.get(&serde_yaml::Value::from("READY_TIMEOUT"))
+                .and_then(|v| v.as_f64())
+                .map(|v| v as f32)
+                .unwrap_or(30.0),
+            restart: RestartPolicy::from_value(get_str("RESTART"))
+                .map_err(|e| Error::ConfigInvalid(format!("LAUNCH component '{}': {}", name, e)))?,
+            processes,
+            name,
+            command,
+        });
+    }
+    Ok(components)
+}
+
+// -------------------- Per-operation rest overrides --------------------
+
+/// Rest overrides for a single operation (None = use the global value). Also the
+/// `rests` of an inbox command or score cue: `{ Z_REST: 0.5, LAP_REST: 2.0 }`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", deny_unknown_fields)]
+pub struct RestOverrides {
+    pub tune_rest: Option<f32>,
+    pub x_rest: Option<f32>,
+    pub z_rest: Option<f32>,
+    pub lap_rest: Option<f32>,
+}
+
+impl RestOverrides {
+    /// Fill unset values from `fallback`
+    pub fn or(self, fallback: RestOverrides) -> RestOverrides {
+        RestOverrides {
+            tune_rest: self.tune_rest.or(fallback.tune_rest),
+            x_rest: self.x_rest.or(fallback.x_rest),
+            z_rest: self.z_rest.or(fallback.z_rest),
+            lap_rest: self.lap_rest.or(fallback.lap_rest),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == RestOverrides::default()
+    }
+
+    /// A rest given as a negative or non-finite number of seconds
+    pub fn invalid(&self) -> Option<&'static str> {
+        [("TUNE_REST", self.tune_rest), ("X_REST", self.x_rest), ("Z_REST", self.z_rest), ("LAP_REST", self.lap_rest)]
+            .into_iter()
+            .find(|(_, rest)| rest.is_some_and(|r| !r.is_finite() || r < 0.0))
+            .map(|(name, _)| name)
+    }
+}
+
+/// bump_check strategy overrides (None = keep the BUMP_* setting). The `bump` of an
+/// inbox command, or a preset's `BUMP`: `{ RETRACT_STEP: 20, FINAL_MARGIN: 10 }`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", deny_unknown_fields)]
+pub struct BumpOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retract_step: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clear_readings: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settle_rest: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_margin: Option<i32>,
+}
+
+impl BumpOverrides {
+    /// Fill unset values from `fallback`
+    pub fn or(self, fallback: BumpOverrides) -> BumpOverrides {
+        BumpOverrides {
+            retract_step: self.retract_step.or(fallback.retract_step),
+            clear_readings: self.clear_readings.or(fallback.clear_readings),
+            settle_rest: self.settle_rest.or(fallback.settle_rest),
+            final_margin: self.final_margin.or(fallback.final_margin),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == BumpOverrides::default()
+    }
+
+    /// A value bump_check would refuse: a retract step that does not move away
+    /// from the string, no clear readings, a negative margin or settle rest
+    pub fn invalid(&self) -> Option<&'static str> {
+        if self.retract_step.is_some_and(|step| step <= 0) {
+            Some("RETRACT_STEP")
+        } else if self.clear_readings == Some(0) {
+            Some("CLEAR_READINGS")
+        } else if self.settle_rest.is_some_and(|r| !r.is_finite() || r < 0.0) {
+            Some("SETTLE_REST")
+        } else if self.final_margin.is_some_and(|margin| margin < 0) {
+            Some("FINAL_MARGIN")
+        } else {
+            None
+        }
+    }
+}
+
+/// Look up a key accepting either the upper- or lower-case spelling
+fn get_either_case<'a>(map: &'a serde_yaml::Mapping, key: &str) -> Option<&'a serde_yaml::Value> {
+    map.get(&serde_yaml::Value::from(key.to_uppercase()))
+        .or_else(|| map.get(&serde_yaml::Value::from(key.to_lowercase())))
+}
+
+/// Load per-operation rest overrides from the optional OPERATIONS block, e.g.
+/// `OPERATIONS: { z_adjust: { LAP_REST: 2.0 } }`. Keys may be upper or lower case.
+pub fn load_rest_overrides(hostname: &str) -> Result<std::collections::HashMap<String, RestOverrides>> {
+    let host_block = load_host_block(hostname)?;
+    let mut overrides = std::collections::HashMap::new();
+    let Some(ops_block) = get_either_case(&host_block, "operations").and_then(|v| v.as_mapping()) else {
+        return Ok(overrides);
+    };
+    for (name, entry) in ops_block.iter() {
+        let (Some(name), Some(entry)) = (name.as_str(), entry.as_mapping()) else { continue; };
+        let get = |key: &str| get_either_case(entry, key).and_then(|v| v.as_f64()).map(|v| v as f32);
+        overrides.insert(name.to_string(), RestOverrides {
+            tune_rest: get("tune_rest"),
+            x_rest: get("x_rest"),
+            z_rest: get("z_rest"),
+            lap_rest: get("lap_rest"),
+        });
+    }
+    Ok(overrides)
+}
+
+/// Run time limit of the sensor seeks when the OPERATIONS block sets none: with a
+/// miswired sensor they would otherwise search forever
+const SEEK_TIME_BUDGET: Duration = Duration::from_secs(600);
+const SEEK_OPERATIONS: &[&str] = &["z_calibrate", "z_home", "x_home", "x_away", "x_calibrate"];
+
+/// Run time limits from the OPERATIONS block (seconds in YAML)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimeBudgets {
+    pub per_operation: std::collections::HashMap<String, Duration>, // `<operation>: { MAX_DURATION }`
+    pub default: Option<Duration>,                                  // OPERATIONS-level MAX_DURATION
+    pub axis_seeks: Vec<String>,                                    // <axis>_home / _away / _calibrate of the AXES axes
+}
+
+impl TimeBudgets {
+    /// The operation's own MAX_DURATION, else the block's, else SEEK_TIME_BUDGET for the seeks
+    pub fn budget(&self, operation: &str) -> Option<Duration> {
+        self.per_operation
+            .get(operation)
+            .copied()
+            .or(self.default)
+            .or_else(|| {
+                let seek = SEEK_OPERATIONS.contains(&operation) || self.axis_seeks.iter().any(|s| s == operation);
+                seek.then_some(SEEK_TIME_BUDGET)
+            })
+    }
+}
+
+/// Load run time limits from the same OPERATIONS block, e.g.
+/// `OPERATIONS: { MAX_DURATION: 3600, z_calibrate: { MAX_DURATION: 600 } }` (seconds).
+pub fn load_time_budgets(hostname: &str) -> Result<TimeBudgets> {
+    let host_block = load_host_block(hostname)?;
+    let mut budgets = TimeBudgets {
+        axis_seeks: parse_axes(&host_block, hostname)?
+            .iter()
+            .flat_map(|axis| ["home", "away", "calibrate"].map(|seek| format!("{}_{}", axis.name.to_lowercase(), seek)))
+            .collect(),
+        ..TimeBudgets::default()
+    };
+    let Some(ops_block) = get_either_case(&host_block, "operations").and_then(|v| v.as_mapping()) else {
+        return Ok(budgets);
+    };
+    let seconds = |value: &serde_yaml::Value, what: &str| {
+        value.as_f64()
+            .filter(|s| *s > 0.0)
+            .and_then(|s| Duration::try_from_secs_f64(s).ok())
+            .ok_or_else(|| Error::ConfigInvalid(format!("OPERATIONS {}MAX_DURATION for '{}' must be a positive number of seconds", what, hostname)))
+    };
+    if let Some(value) = get_either_case(ops_block, "max_duration") {
+        budgets.default = Some(seconds(value, "")?);
+    }
+    for (name, entry) in ops_block.iter() {
+        let (Some(name), Some(entry)) = (name.as_str(), entry.as_mapping()) else { continue; };
+        let Some(value) = get_either_case(entry, "max_duration") else { continue; };
+        budgets.per_operation.insert(name.to_string(), seconds(value, &format!("{} ", name))?);
+    }
+    Ok(budgets)
+}
+
+// -------------------- Startup auto-sequence --------------------
+
+/// Step time limit when an AUTOSTART entry gives no TIMEOUT
+const AUTOSTART_STEP_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// One operation of the AUTOSTART sequence
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutostartStep {
+    pub operation: String,
+    pub timeout: Duration,
+}
+
+/// Load the optional AUTOSTART list, e.g.
+/// `AUTOSTART: [x_home, { OPERATION: z_calibrate, TIMEOUT: 120 }, bump_check]` (seconds).
+pub fn load_autostart(hostname: &str) -> Result<Vec<AutostartStep>> {
+    let host_block = load_host_block(hostname)?;
+    let Some(list) = get_either_case(&host_block, "autostart") else {
+        return Ok(Vec::new());
+    };
+    let entries = list.as_sequence()
+        .ok_or_else(|| Error::ConfigInvalid(format!("AUTOSTART for '{}' must be a list of operations", hostname)))?;
+    let mut steps = Vec::new();
+    for entry in entries {
+        let step = match entry {
+            serde_yaml::Value::String(operation) => AutostartStep { operation: operation.clone(), timeout: AUTOSTART_STEP_TIMEOUT },
+            serde_yaml::Value::Mapping(entry) => {
+                let operation = get_either_case(entry, "operation").and_then(|v| v.as_str())
+                    .ok_or_else(|| Error::ConfigInvalid(format!("AUTOSTART entry for '{}' is missing OPERATION", hostname)))?;
+                let timeout = match get_either_case(entry, "timeout") {
+                    None => AUTOSTART_STEP_TIMEOUT,
+                    Some(value) => value.as_f64()
+                        .filter(|s| *s > 0.0)
+                        .map(Duration::from_secs_f64)
+                        .ok_or_else(|| Error::ConfigInvalid(format!("AUTOSTART {} TIMEOUT for '{}' must be a positive number of seconds", operation, hostname)))?,
+                };
+                AutostartStep { operation: operation.to_string(), timeout }
+            }
+            _ => return Err(Error::ConfigInvalid(format!("AUTOSTART entries for '{}' must be operation names or mappings with OPERATION", hostname))),
+        };
+        steps.push(step);
+    }
+    Ok(steps)
+}
+
+// -------------------- Audio-triggered start --------------------
+
+/// When the AUDIO_TRIGGER starts and stops its operation (see audio_trigger)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioTriggerSettings {
+    pub operation: String,     // Started on sound, e.g. performance_mode or ping_pong_move
+    pub threshold: f32,        // Amp sum any channel has to exceed
+    pub sound_for: Duration,   // For this long before the operation starts
+    pub silence_for: Duration, // Every channel below the threshold this long stops it
+    pub armed: bool,           // Armed at start (else from the GUI)
+}
+
+/// Load the optional AUDIO_TRIGGER block, e.g.
+/// `AUDIO_TRIGGER: { OPERATION: performance_mode, THRESHOLD: 20.0, SECONDS: 3, SILENCE_MINUTES: 10, ARMED: true }`.
+/// None when the block is absent (no trigger).
+pub fn load_audio_trigger(hostname: &str) -> Result<Option<AudioTriggerSettings>> {
+    let host_block = load_host_block(hostname)?;
+    let Some(block) = get_either_case(&host_block, "audio_trigger").and_then(|v| v.as_mapping()) else {
+        return Ok(None);
+    };
+    let operation = get_either_case(block, "operation")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| Error::ConfigInvalid(format!("AUDIO_TRIGGER for '{}' is missing OPERATION", hostname)))?
+        .to_string();
+    let threshold = get_either_case(block, "threshold")
+        .and_then(|v| v.as_f64())
+        .filter(|t| *t > 0.0)
+        .ok_or_else(|| Error::ConfigInvalid(format!("AUDIO_TRIGGER.THRESHOLD for '{}' must be a positive amp sum", hostname)))? as f32;
+    let sound_for = match get_either_case(block, "seconds") {
+        None => Duration::from_secs(3),
+        Some(value) => value.as_f64()
+            .filter(|s| *s >= 0.0)
+            .map(Duration::from_secs_f64)
+            .ok_or_else(|| Error::ConfigInvalid(format!("AUDIO_TRIGGER.SECONDS for '{}' must be a number of seconds", hostname)))?,
+    };
+    let silence_for = match get_either_case(block, "silence_minutes") {
+        None => Duration::from_secs(600),
+        Some(value) => value.as_f64()
+            .filter(|m| *m > 0.0)
+            .map(|m| Duration::from_secs_f64(m * 60.0))
+            .ok_or_else(|| Error::ConfigInvalid(format!("AUDIO_TRIGGER.SILENCE_MINUTES for '{}' must be a positive number of minutes", hostname)))?,
+    };
+    let armed = get_either_case(block, "armed").and_then(|v| v.as_bool()).unwrap_or(true);
+    Ok(Some(AudioTriggerSettings { operation, threshold, sound_for, silence_for, armed }))
+}
+
+// -------------------- Command inbox --------------------
+
+/// Directory operations_gui watches for command files
+#[derive(Debug, Clone)]
+pub struct CommandInboxSettings {
+    pub dir: PathBuf,
+    pub poll: Duration, // How often the directory is scanned
+}
+
+/// Load the optional COMMAND_INBOX block, e.g. `COMMAND_INBOX: { DIR: /media/usb/inbox, POLL: 2.0 }`.
+/// None when the block is absent (no inbox).
+pub fn load_command_inbox(hostname: &str) -> Result<Option<CommandInboxSettings>> {
+    let host_block = load_host_block(hostname)?;
+    let Some(block) = get_either_case(&host_block, "command_inbox").and_then(|v| v.as_mapping()) else {
+        return Ok(None);
+    };
+    let dir = get_either_case(block, "dir")
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+        .ok_or_else(|| Error::ConfigInvalid(format!("COMMAND_INBOX for '{}' is missing DIR", hostname)))?;
+    let poll = match get_either_case(block, "poll") {
+        None => Duration::from_secs(2),
+        Some(value) => value.as_f64()
+            .filter(|s| *s > 0.0)
+            .map(Duration::from_secs_f64)
+            .ok_or_else(|| Error::ConfigInvalid(format!("COMMAND_INBOX.POLL for '{}' must be a positive number of seconds", hostname)))?,
+    };
+    Ok(Some(CommandInboxSettings { dir, poll }))
+}
+
+// -------------------- Scores --------------------
+
+/// Directory operations_gui watches for composition scores (see score.rs)
+#[derive(Debug, Clone)]
+pub struct ScoreSettings {
+    pub dir: PathBuf,
+    pub poll: Duration,              // How often the directory is scanned
+    pub autoplay: bool,              // Play a newly dropped score when no other is playing
+    pub midi_clock: Option<PathBuf>, // Raw MIDI device whose clock the transport follows
+}
+
+/// Load the optional SCORES block, e.g.
+/// `SCORES: { DIR: /media/usb/scores, POLL: 2.0, AUTOPLAY: true, MIDI_CLOCK: /dev/snd/midiC1D0 }`.
+/// None when the block is absent (no scores).
+pub fn load_score_settings(hostname: &str) -> Result<Option<ScoreSettings>> {
+    let host_block = load_host_block(hostname)?;
+    let Some(block) = get_either_case(&host_block, "scores").and_then(|v| v.as_mapping()) else {
+        return Ok(None);
+    };
+    let dir = get_either_case(block, "dir")
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+        .ok_or_else(|| Error::ConfigInvalid(format!("SCORES for '{}' is missing DIR", hostname)))?;
+    let poll = match get_either_case(block, "poll") {
+        None => Duration::from_secs(2),
+        Some(value) => value.as_f64()
+            .filter(|s| *s > 0.0)
+            .map(Duration::from_secs_f64)
+            .ok_or_else(|| Error::ConfigInvalid(format!("SCORES.POLL for '{}' must be a positive number of seconds", hostname)))?,
+    };
+    let autoplay = get_either_case(block, "autoplay").and_then(|v| v.as_bool()).unwrap_or(false);
+    let midi_clock = get_either_case(block, "midi_clock").and_then(|v| v.as_str()).map(PathBuf::from);
+    Ok(Some(ScoreSettings { dir, poll, autoplay, midi_clock }))
+}
+
+// -------------------- Scenes --------------------
+
+/// Directory the host's scenes are saved in (see scene), from the optional
+/// `SCENES: { DIR: /media/usb/scenes }`. Without the block: ./scenes/<host>.
+pub fn load_scene_dir(hostname: &str) -> Result<PathBuf> {
+    let host_block = load_host_block(hostname)?;
+    let Some(value) = get_either_case(&host_block, "scenes") else {
+        return Ok(PathBuf::from("scenes").join(hostname));
+    };
+    value.as_mapping()
+        .and_then(|block| get_either_case(block, "dir"))
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+        .ok_or_else(|| Error::ConfigInvalid(format!("SCENES for '{}' must be a block with a DIR", hostname)))
+}
+
+// -------------------- Plugins --------------------
+
+/// Load the optional PLUGINS list of operation libraries, e.g.
+/// `PLUGINS: [/home/pi/plugins/libscan_ops.so]` (see plugins; needs the `plugins` feature).
+/// Relative paths are taken from the directory the GUI runs in.
+pub fn load_plugin_paths(hostname: &str) -> Result<Vec<PathBuf>> {
+    let host_block = load_host_block(hostname)?;
+    let Some(value) = get_either_case(&host_block, "plugins") else {
+        return Ok(Vec::new());
+    };
+    let entries = value.as_sequence()
+        .ok_or_else(|| Error::ConfigInvalid(format!("PLUGINS for '{}' must be a list of library paths", hostname)))?;
+    entries.iter()
+        .map(|entry| entry.as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| Error::ConfigInvalid(format!("PLUGINS for '{}' must be a list of library paths", hostname))))
+        .collect()
+}
+
+// -------------------- Status file --------------------
+
+/// Where operations_gui writes its read-only status snapshot
+#[derive(Debug, Clone)]
+pub struct StatusFileSettings {
+    pub path: PathBuf,
+    pub interval: Duration, // Time between rewrites
+}
+
+/// Load the optional STATUS_FILE block, e.g. `STATUS_FILE: { PATH: logs/status.json, INTERVAL: 1.0 }`.
+/// None when the block is absent (no status file).
+pub fn load_status_file(hostname: &str) -> Result<Option<StatusFileSettings>> {
+    let host_block = load_host_block(hostname)?;
+    let Some(block) = get_either_case(&host_block, "status_file").and_then(|v| v.as_mapping()) else {
+        return Ok(None);
+    };
+    let path = get_either_case(block, "path")
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+        .ok_or_else(|| Error::ConfigInvalid(format!("STATUS_FILE for '{}' is missing PATH", hostname)))?;
+    let interval = match get_either_case(block, "interval") {
+        None => Duration::from_secs(1),
+        Some(value) => value.as_f64()
+            .filter(|s| *s > 0.0)
+            .map(Duration::from_secs_f64)
+            .ok_or_else(|| Error::ConfigInvalid(format!("STATUS_FILE.INTERVAL for '{}' must be a positive number of seconds", hostname)))?,
+    };
+    Ok(Some(StatusFileSettings { path, interval }))
+}
+
+// -------------------- Stepper bridge --------------------
+
+/// stepper_gui's socket served over TCP, for operations_gui on another machine
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepperBridgeSettings {
+    pub listen: Option<String>,  // Address stepper_gui listens on, e.g. 0.0.0.0:7878
+    pub connect: Option<String>, // Address operations_gui uses for this instrument, e.g. closet-pc:7878
+    pub token: String,           // Must match on both ends
+}
+
+/// Load the optional STEPPER_BRIDGE block, e.g.
+/// `STEPPER_BRIDGE: { LISTEN: "0.0.0.0:7878", CONNECT: "closet-pc:7878", TOKEN: "..." }`.
+/// None when the block is absent (Unix socket only).
+pub fn load_stepper_bridge(hostname: &str) -> Result<Option<StepperBridgeSettings>> {
+    let host_block = load_host_block(hostname)?;
+    let Some(block) = get_either_case(&host_block, "stepper_bridge").and_then(|v| v.as_mapping()) else {
+        return Ok(None);
+    };
+    let get_str = |key: &str| get_either_case(block, key).and_then(|v| v.as_str()).map(str::to_string);
+    let token = get_str("token")
+        .filter(|t| !t.trim().is_empty() && !t.contains(char::is_whitespace))
+        .ok_or_else(|| Error::ConfigInvalid(format!("STEPPER_BRIDGE for '{}' needs a TOKEN (no spaces)", hostname)))?;
+    let (listen, connect) = (get_str("listen"), get_str("connect"));
+    for (key, address) in [("LISTEN", &listen), ("CONNECT", &connect)] {
+        if let Some(address) = address {
+            if address.rsplit_once(':').and_then(|(_, port)| port.parse::<u16>().ok()).is_none() {
+                return Err(Error::ConfigInvalid(format!("STEPPER_BRIDGE.{} for '{}' must be host:port, got '{}'", key, hostname, address)));
+            }
+        }
+    }
+    Ok(Some(StepperBridgeSettings { listen, connect, token }))
+}
+
+// -------------------- IPC access --------------------
+
+/// One IPC_AUTH client: its token and allow-list (`full`, `read_only` and/or command names)
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpcClientSettings {
+    pub name: String,
+    pub token: String,
+    pub allow: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpcAuthSettings {
+    pub clients: Vec<IpcClientSettings>,
+    pub socket_default: Vec<String>, // Allow-list for Unix socket clients that send no token
+}
+
+/// Load the optional IPC_AUTH block, e.g.
+/// `IPC_AUTH: { SOCKET_DEFAULT: read_only, CLIENTS: [{ NAME: dashboard, TOKEN: "...", ALLOW: read_only }] }`.
+/// ALLOW / SOCKET_DEFAULT take one entry or a list. None when the block is absent (no checks).
+pub fn load_ipc_auth(hostname: &str) -> Result<Option<IpcAuthSettings>> {
+    let host_block = load_host_block(hostname)?;
+    let Some(block) = get_either_case(&host_block, "ipc_auth").and_then(|v| v.as_mapping()) else {
+        return Ok(None);
+    };
+    let entries = |value: Option<&serde_yaml::Value>, what: &str| -> Result<Vec<String>> {
+        match value {
+            None => Ok(Vec::new()),
+            Some(serde_yaml::Value::String(s)) => Ok(vec![s.clone()]),
+            Some(serde_yaml::Value::Sequence(list)) => list
+                .iter()
+                .map(|v| v.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| Error::ConfigInvalid(format!("IPC_AUTH {} for '{}' must be names", what, hostname))),
+            Some(other) => Err(Error::ConfigInvalid(format!("IPC_AUTH {} for '{}' must be a name or list, got {:?}", what, hostname, other))),
+        }
+    };
+    let socket_default = match get_either_case(block, "socket_default") {
+        None => vec!["read_only".to_string()],
+        value => entries(value, "SOCKET_DEFAULT")?,
+    };
+    let mut clients: Vec<IpcClientSettings> = Vec::new();
+    let list = get_either_case(block, "clients").and_then(|v| v.as_sequence()).cloned().unwrap_or_default();
+    for entry in &list {
+        let entry = entry.as_mapping()
+            .ok_or_else(|| Error::ConfigInvalid(format!("IPC_AUTH.CLIENTS entries for '{}' must be mappings", hostname)))?;
+        let get_str = |key: &str| get_either_case(entry, key).and_then(|v| v.as_str()).map(str::to_string);
+        let name = get_str("name")
+            .ok_or_else(|| Error::ConfigInvalid(format!("IPC_AUTH.CLIENTS entry for '{}' is missing NAME", hostname)))?;
+        let token = get_str("token")
+            .filter(|t| !t.trim().is_empty() && !t.contains(char::is_whitespace))
+            .ok_or_else(|| Error::ConfigInvalid(format!("IPC_AUTH client '{}' for '{}' needs a TOKEN (no spaces)", name, hostname)))?;
+        if clients.iter().any(|c| c.token == token) {
+            return Err(Error::ConfigInvalid(format!("IPC_AUTH client '{}' for '{}' reuses another client's TOKEN", name, hostname)));
+        }
+        let allow = entries(get_either_case(entry, "allow"), "ALLOW")?;
+        if allow.is_empty() {
+            return Err(Error::ConfigInvalid(format!("IPC_AUTH client '{}' for '{}' has no ALLOW", name, hostname)));
+        }
+        clients.push(IpcClientSettings { name, token, allow });
+    }
+    Ok(Some(IpcAuthSettings { clients, socket_default }))
+}
+
+// -------------------- Alerts --------------------
+
+/// Event classes that can raise an alert (ALERTS.EVENTS)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertEvent {
+    StepperDisabled, // A fault made an operation disable a stepper
+    OperationFailed,
+    StringBreak, // A sounding channel went silent while the others kept playing
+    SerialLost,  // An Arduino board or the stepper_gui link dropped out
+    IdlePark,    // The inactivity watchdog parked the instrument
+}
+
+impl AlertEvent {
+    pub const ALL: [AlertEvent; 5] = [
+        AlertEvent::StepperDisabled,
+        AlertEvent::OperationFailed,
+        AlertEvent::StringBreak,
+        AlertEvent::SerialLost,
+        AlertEvent::IdlePark,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            AlertEvent::StepperDisabled => "stepper_disabled",
+            AlertEvent::OperationFailed => "operation_failed",
+            AlertEvent::StringBreak => "string_break",
+            AlertEvent::SerialLost => "serial_lost",
+            AlertEvent::IdlePark => "idle_park",
+        }
+    }
+
+    fn from_value(value: &str) -> Result<Self> {
+        Self::ALL.into_iter()
+            .find(|e| e.key() == value)
+            .ok_or_else(|| Error::ConfigInvalid(format!("Unknown ALERTS event '{}' (expected one of stepper_disabled, operation_failed, string_break, serial_lost, idle_park)", value)))
+    }
+}
+
+/// Where alerts are delivered
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertSink {
+    Webhook { url: String }, // JSON POST of {host, event, message, time}
+    Ntfy { url: String },    // Topic URL, e.g. https://ntfy.sh/stringdriver-2
+    Email {
+        smtp_url: String,             // e.g. smtps://smtp.example.org:465
+        from: String,
+        to: Vec<String>,
+        user: Option<String>,
+        password_env: Option<String>, // Environment variable holding the SMTP password
+    },
+    Desktop, // notify-send on this machine
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertSettings {
+    pub events: Vec<AlertEvent>,
+    pub sinks: Vec<AlertSink>,
+    pub min_interval: Duration,        // The same event and message is not re-sent sooner than this
+    pub string_break_silence: Duration, // How long a channel must stay silent to count as a string break
+}
+
+impl Default for AlertSettings {
+    fn default() -> Self {
+        Self {
+            events: Vec::new(),
+            sinks: Vec::new(),
+            min_interval: Duration::from_secs(300),
+            string_break_silence: Duration::from_secs(5),
+        }
+    }
+}
+
+fn parse_alert_sink(entry: &serde_yaml::Value, hostname: &str) -> Result<AlertSink> {
+    let invalid = |msg: &str| Error::ConfigInvalid(format!("ALERTS sink for '{}': {}", hostname, msg));
+    let entry = entry.as_mapping().ok_or_else(|| invalid("entries must be mappings with a TYPE"))?;
+    let get_str = |key: &str| get_either_case(entry, key).and_then(|v| v.as_str()).map(str::to_string);
+    let kind = get_str("type").ok_or_else(|| invalid("missing TYPE"))?;
+    match kind.as_str() {
+        "webhook" => Ok(AlertSink::Webhook { url: get_str("url").ok_or_else(|| invalid("webhook needs a URL"))? }),
+        "ntfy" => Ok(AlertSink::Ntfy { url: get_str("url").ok_or_else(|| invalid("ntfy needs a URL (the topic URL)"))? }),
+        "email" => {
+            let to = match get_either_case(entry, "to") {
+                Some(serde_yaml::Value::String(to)) => vec![to.clone()],
+                Some(serde_yaml::Value::Sequence(list)) => list.iter().filter_map(|v| v.as_str().map(str::to_string)).collect(),
+                _ => Vec::new(),
+            };
+            if to.is_empty() {
+                return Err(invalid("email needs at least one TO address"));
+            }
+            Ok(AlertSink::Email {
+                smtp_url: get_str("smtp_url").ok_or_else(|| invalid("email needs an SMTP_URL"))?,
+                from: get_str("from").ok_or_else(|| invalid("email needs a FROM address"))?,
+                to,
+                user: get_str("user"),
+                password_env: get_str("password_env"),
+            })
+        }
+        "desktop" => Ok(AlertSink::Desktop),
+        other => Err(invalid(&format!("unknown TYPE '{}' (expected webhook, ntfy, email or desktop)", other))),
+    }
+}
+
+/// Load the optional ALERTS block, e.g.
+/// `ALERTS: { EVENTS: [stepper_disabled, serial_lost], SINKS: [{ TYPE: ntfy, URL: https://ntfy.sh/sd2 }] }`.
+/// EVENTS defaults to every event class; without SINKS nothing is sent.
+pub fn load_alert_settings(hostname: &str) -> Result<AlertSettings> {
+    let host_block = load_host_block(hostname)?;
+    let mut settings = AlertSettings::default();
+    let Some(block) = get_either_case(&host_block, "alerts") else {
+        return Ok(settings);
+    };
+    let block = block.as_mapping()
+        .ok_or_else(|| Error::ConfigInvalid(format!("ALERTS for '{}' must be a mapping", hostname)))?;
+    settings.events = match get_either_case(block, "events") {
+        None => AlertEvent::ALL.to_vec(),
+        Some(list) => list.as_sequence()
+            .ok_or_else(|| Error::ConfigInvalid(format!("ALERTS.EVENTS for '{}' must be a list", hostname)))?
+            .iter()
+            .map(|v| AlertEvent::from_value(v.as_str().unwrap_or_default()))
+            .collect::<Result<_>>()?,
+    };
+    if let Some(list) = get_either_case(block, "sinks") {
+        settings.sinks = list.as_sequence()
+            .ok_or_else(|| Error::ConfigInvalid(format!("ALERTS.SINKS for '{}' must be a list", hostname)))?
+            .iter()
+            .map(|entry| parse_alert_sink(entry, hostname))
+            .collect::<Result<_>>()?;
+    }
+    for (key, slot) in [("min_interval", &mut settings.min_interval), ("string_break_silence", &mut settings.string_break_silence)] {
+        let Some(value) = get_either_case(block, key) else { continue; };
+        *slot = value.as_f64()
+            .filter(|s| *s >= 0.0)
+            .map(Duration::from_secs_f64)
+            .ok_or_else(|| Error::ConfigInvalid(format!("ALERTS.{} for '{}' must be a number of seconds", key.to_uppercase(), hostname)))?;
+    }
+    Ok(settings)
+}
+
+// -------------------- Event hooks --------------------
+
+/// Lifecycle events that can run a HOOKS command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HookEvent {
+    OperationStarted,
+    OperationFinished, // Whatever the outcome; the payload carries it
+    StepperDisabled,   // A fault made an operation disable a stepper
+    Estop,             // BREAK or KILL ALL in operations_gui
+    ParkComplete,
+}
+
+impl HookEvent {
+    pub const ALL: [HookEvent; 5] = [
+        HookEvent::OperationStarted,
+        HookEvent::OperationFinished,
+        HookEvent::StepperDisabled,
+        HookEvent::Estop,
+        HookEvent::ParkComplete,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            HookEvent::OperationStarted => "operation_started",
+            HookEvent::OperationFinished => "operation_finished",
+            HookEvent::StepperDisabled => "stepper_disabled",
+            HookEvent::Estop => "estop",
+            HookEvent::ParkComplete => "park_complete",
+        }
+    }
+
+    fn from_value(value: &str) -> Result<Self> {
+        Self::ALL.into_iter()
+            .find(|e| e.key() == value)
+            .ok_or_else(|| Error::ConfigInvalid(format!("Unknown HOOKS event '{}' (expected one of operation_started, operation_finished, stepper_disabled, estop, park_complete)", value)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HookSettings {
+    pub commands: Vec<(HookEvent, String)>, // Shell commands per event, run in YAML order
+    pub timeout: Duration,                  // A hook still running after this is killed
+}
+
+impl Default for HookSettings {
+    fn default() -> Self {
+        Self { commands: Vec::new(), timeout: Duration::from_secs(30) }
+    }
+}
+
+/// Load the optional HOOKS block, e.g.
+/// `HOOKS: { operation_started: "/opt/venue/lights dim", estop: [notify.sh, "lights up"], TIMEOUT: 10 }`.
+/// Each command runs under `sh -c` with the event as JSON on stdin; none run if absent.
+pub fn load_hook_settings(hostname: &str) -> Result<HookSettings> {
+    let host_block = load_host_block(hostname)?;
+    let mut settings = HookSettings::default();
+    let Some(block) = get_either_case(&host_block, "hooks") else {
+        return Ok(settings);
+    };
+    let block = block.as_mapping()
+        .ok_or_else(|| Error::ConfigInvalid(format!("HOOKS for '{}' must be a mapping", hostname)))?;
+    for (key, value) in block {
+        let key = key.as_str().unwrap_or_default().to_lowercase();
+        if key == "timeout" {
+            settings.timeout = value.as_f64()
+                .filter(|s| *s > 0.0)
+                .map(Duration::from_secs_f64)
+                .ok_or_else(|| Error::ConfigInvalid(format!("HOOKS.TIMEOUT for '{}' must be a number of seconds", hostname)))?;
+            continue;
+        }
+        let event = HookEvent::from_value(&key)?;
+        let commands = match value {
+            serde_yaml::Value::String(command) => vec![command.clone()],
+            serde_yaml::Value::Sequence(list) => list.iter()
+                .map(|v| v.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| Error::ConfigInvalid(format!("HOOKS.{} for '{}' must list commands as strings", key, hostname)))?,
+            _ => return Err(Error::ConfigInvalid(format!("HOOKS.{} for '{}' must be a command or a list of commands", key, hostname))),
+        };
+        settings.commands.extend(commands.into_iter().map(|command| (event, command)));
+    }
+    Ok(settings)
+}
+
+// -------------------- Parameter presets --------------------
+
+/// Named set of operations parameters from the optional PRESETS block, e.g.
+/// `PRESETS: { rehearsal: { LAP_REST: 2.0, ADJUSTMENT_LEVEL: 10 } }`.
+/// Unset values leave the current setting alone when the preset is applied.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", deny_unknown_fields)]
+pub struct ParameterPreset {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tune_rest: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x_rest: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z_rest: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lap_rest: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performance_rest: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adjustment_level: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_threshold: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delta_threshold: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z_variance_threshold: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z_up_step: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z_down_step: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x_start: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x_finish: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x_step: Option<i32>,
+    // Per-channel z_adjust thresholds as set in operations_gui
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voice_count_min: Option<Vec<i32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voice_count_max: Option<Vec<i32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amp_sum_min: Option<Vec<i32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amp_sum_max: Option<Vec<i32>>,
+    // Per-string bow wheel speeds (BOW_DRIVE)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bow_speed: Option<Vec<u32>>,
+    // bump_check strategy (BUMP_RETRACT_STEP, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bump: Option<BumpOverrides>,
+}
+
+/// Load the host's presets in the order they appear in string_driver.yaml
+pub fn load_presets(hostname: &str) -> Result<Vec<(String, ParameterPreset)>> {
+    let host_block = load_host_block(hostname)?;
+    let mut presets = Vec::new();
+    let Some(block) = get_either_case(&host_block, "presets").and_then(|v| v.as_mapping()) else {
+        return Ok(presets);
+    };
+    for (name, entry) in block.iter() {
+        let Some(name) = name.as_str() else { continue; };
+        let preset: ParameterPreset = serde_yaml::from_value(entry.clone())
+            .map_err(|e| Error::ConfigInvalid(format!("PRESETS '{}' for '{}': {}", name, hostname, e)))?;
+        if let Some(key) = preset.bump.and_then(|bump| bump.invalid()) {
+            return Err(Error::ConfigInvalid(format!("PRESETS '{}' for '{}': BUMP {} is out of range", name, hostname, key)));
+        }
+        presets.push((name.to_string(), preset));
+    }
+    Ok(presets)
+}
+
+/// Add or replace preset `name` in the host's PRESETS block. The host section is
+/// rewritten through install_host_section, so the previous file is backed up and
+/// its path returned.
+pub fn save_preset(hostname: &str, name: &str, preset: &ParameterPreset) -> Result<PathBuf> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(Error::ConfigInvalid("Preset name must not be empty".to_string()));
+    }
+    let (os_key, mut section) = load_own_host_section(hostname)?;
+    let key = ["PRESETS", "presets"]
+        .iter()
+        .map(|k| serde_yaml::Value::from(*k))
+        .find(|k| section.contains_key(k))
+        .unwrap_or_else(|| serde_yaml::Value::from("PRESETS"));
+    let mut block = section.get(&key).and_then(|v| v.as_mapping()).cloned().unwrap_or_default();
+    block.insert(serde_yaml::Value::from(name), serde_yaml::to_value(preset)?);
+    section.insert(key, serde_yaml::Value::Mapping(block));
+    install_host_section(&os_key, hostname, &section)
+}
+
+// -------------------- Multi-instrument targets --------------------
+
+/// Another instrument operations_gui drives alongside this host's own
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstrumentTarget {
+    pub host: String,                   // Host profile in string_driver.yaml
+    pub stepper_socket: Option<String>, // Defaults to the socket derived from that profile's ARD_PORT
+}
+
+/// Load the optional INSTRUMENTS list, e.g.
+/// `INSTRUMENTS: [{ HOST: stringdriver-2, STEPPER_SOCKET: /tmp/stepper_gui_sd2.sock }]`.
+/// Each HOST must have its own section; this host is not listed.
+pub fn load_instruments(hostname: &str) -> Result<Vec<InstrumentTarget>> {
+    let host_block = load_host_block(hostname)?;
+    let Some(list) = get_either_case(&host_block, "instruments") else {
+        return Ok(Vec::new());
+    };
+    let entries = list.as_sequence()
+        .ok_or_else(|| Error::ConfigInvalid(format!("INSTRUMENTS for '{}' must be a list", hostname)))?;
+    let mut targets: Vec<InstrumentTarget> = Vec::new();
+    for entry in entries {
+        let entry = entry.as_mapping()
+            .ok_or_else(|| Error::ConfigInvalid(format!("INSTRUMENTS entries for '{}' must be mappings with a HOST", hostname)))?;
+        let get_str = |key: &str| get_either_case(entry, key).and_then(|v| v.as_str()).map(str::to_string);
+        let host = get_str("host")
+            .ok_or_else(|| Error::ConfigInvalid(format!("INSTRUMENTS entry for '{}' is missing HOST", hostname)))?;
+        if host == hostname || targets.iter().any(|t| t.host == host) {
+            return Err(Error::ConfigInvalid(format!("INSTRUMENTS for '{}' lists '{}' more than once (this host is always included)", hostname, host)));
+        }
+        targets.push(InstrumentTarget { host, stepper_socket: get_str("stepper_socket") });
+    }
+    Ok(targets)
+}
+
+// -------------------- GUI role lock --------------------
+
+/// Which GUI controls are live: performers only get the safe live controls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Performer,
+    Technician,
+}
+
+impl Role {
+    fn from_value(value: &str) -> Result<Self> {
+        match value {
+            "performer" => Ok(Role::Performer),
+            "technician" => Ok(Role::Technician),
+            other => Err(Error::ConfigInvalid(format!("Unknown ROLE DEFAULT '{}' (expected performer or technician)", other))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoleSettings {
+    pub default: Role,                  // Mode the GUIs start in
+    pub technician_pin: Option<String>, // Required to leave performer mode when set
+}
+
+/// Load the optional ROLE block, e.g. `ROLE: { DEFAULT: performer, TECHNICIAN_PIN: "2468" }`.
+/// Without it the GUIs start unlocked in technician mode, as before.
+pub fn load_role_settings(hostname: &str) -> Result<RoleSettings> {
+    let host_block = load_host_block(hostname)?;
+    let Some(block) = get_either_case(&host_block, "role") else {
+        return Ok(RoleSettings { default: Role::Technician, technician_pin: None });
+    };
+    let block = block.as_mapping()
+        .ok_or_else(|| Error::ConfigInvalid(format!("ROLE for '{}' must be a mapping", hostname)))?;
+    let default = match get_either_case(block, "default").and_then(|v| v.as_str()) {
+        Some(value) => Role::from_value(&value.to_lowercase())?,
+        None => Role::Technician,
+    };
+    // A numeric PIN in YAML reads as a number; accept either spelling
+    let technician_pin = match get_either_case(block, "technician_pin") {
+        None | Some(serde_yaml::Value::Null) => None,
+        Some(serde_yaml::Value::String(pin)) => Some(pin.clone()),
+        Some(serde_yaml::Value::Number(pin)) => Some(pin.to_string()),
+        Some(_) => return Err(Error::ConfigInvalid(format!("ROLE TECHNICIAN_PIN for '{}' must be a string or number", hostname))),
+    };
+    Ok(RoleSettings { default, technician_pin })
+}
+
+// -------------------- GUI refresh rates --------------------
+
+/// How often operations_gui reads the partials slot, recomputes voice counts and
+/// amp sums from it, and repaints; each rate is in Hz
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RefreshRates {
+    pub slot_poll_hz: f32,
+    pub analysis_hz: f32,
+    pub repaint_hz: f32,
+}
+
+impl RefreshRates {
+    /// Desktop hosts: everything at frame rate
+    pub const DESKTOP: Self = Self { slot_poll_hz: 60.0, analysis_hz: 60.0, repaint_hz: 60.0 };
+    /// RaspberryPi hosts: operations sample audio at most every 100 ms, so 10 Hz loses nothing
+    pub const LOW_POWER: Self = Self { slot_poll_hz: 10.0, analysis_hz: 10.0, repaint_hz: 10.0 };
+
+    pub fn interval(hz: f32) -> Duration {
+        Duration::from_secs_f32(1.0 / hz)
+    }
+}
+
+/// Load the optional REFRESH_RATES block, e.g.
+/// `REFRESH_RATES: { SLOT_POLL_HZ: 10, ANALYSIS_HZ: 5, REPAINT_HZ: 15 }`.
+/// Unset rates default by OS section: LOW_POWER under RaspberryPi, DESKTOP elsewhere.
+pub fn load_refresh_rates(hostname: &str) -> Result<RefreshRates> {
+    let (os_key, host_block) = load_host_section(hostname)?;
+    let mut rates = if os_key == "RaspberryPi" { RefreshRates::LOW_POWER } else { RefreshRates::DESKTOP };
+    let Some(value) = host_block.get(&serde_yaml::Value::from("REFRESH_RATES")) else {
+        return Ok(rates);
+    };
+    let block = value.as_mapping()
+        .ok_or_else(|| Error::ConfigInvalid(format!("REFRESH_RATES for '{}' must be a mapping", hostname)))?;
+    for (key, slot) in [("SLOT_POLL_HZ", &mut rates.slot_poll_hz), ("ANALYSIS_HZ", &mut rates.analysis_hz), ("REPAINT_HZ", &mut rates.repaint_hz)] {
+        let Some(v) = get_either_case(block, key) else { continue; };
+        let hz = v.as_f64()
+            .filter(|hz| *hz > 0.0 && *hz <= 240.0)
+            .ok_or_else(|| Error::ConfigInvalid(format!("REFRESH_RATES {} for '{}' must be above 0 and at most 240 Hz", key, hostname)))?;
+        *slot = hz as f32;
+    }
+    Ok(rates)
+}
+
+// -------------------- GPIO config --------------------
+
+#[derive(Debug, Clone)]
+pub struct GpioComponents {
+    pub z_touch_pins: Option<Vec<u32>>,
+    pub x_home_pin: Option<u32>,
+    pub x_away_pin: Option<u32>,
+    pub x_limit_pin: Option<u32>,
+    pub axis_pins: Vec<(String, Option<u32>, Option<u32>)>, // (AXES NAME, HOME_PIN, AWAY_PIN)
+    pub damper_pins: Vec<u32>,   // DAMPERS relay outputs, one per string (OUTPUT: gpio)
+    pub damper_active_low: bool, // Relays engage when their line is driven low
+    pub rotary_encoder_pins: Option<RotaryEncoderPins>,
+    pub distance_sensor_pins: Option<DistanceSensorPins>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RotaryEncoderPins {
+    pub a: u32,
+    pub b: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct DistanceSensorPins {
+    pub trig: u32,
+    pub echo: u32,
+}
+
+/// Bias applied to an input line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpioPull {
+    Up,
+    Down,
+    Off,
+}
+
+/// Electrical settings of one input line (GPIO_LINES)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpioLineConfig {
+    pub active_low: bool,   // Pressed/touching when the line reads low
+    pub debounce: Duration, // A change must hold this long before it is reported
+    pub pull: GpioPull,
+}
+
+impl Default for GpioLineConfig {
+    /// Pulled up, active low, no debounce - how every line was wired before GPIO_LINES
+    fn default() -> Self {
+        Self { active_low: true, debounce: Duration::ZERO, pull: GpioPull::Up }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GpioSettings {
+    pub enabled: bool,
+    pub library: Option<String>,
+    pub max_steps: Option<u32>,
+    pub components: Option<GpioComponents>,
+    pub line_default: GpioLineConfig,
+    pub lines: std::collections::HashMap<u32, GpioLineConfig>, // Per-pin overrides of line_default
+}
+
+/// One GPIO_LINES entry laid over `base`, e.g. `{ ACTIVE: high, PULL: down, DEBOUNCE_MS: 10 }`
+fn parse_gpio_line(value: &serde_yaml::Value, base: GpioLineConfig, what: &str, hostname: &str) -> Result<GpioLineConfig> {
+    let map = value.as_mapping()
+        .ok_or_else(|| Error::ConfigInvalid(format!("GPIO_LINES {} for '{}' must be a mapping", what, hostname)))?;
+    let mut line = base;
+    if let Some(active) = get_either_case(map, "ACTIVE") {
+        line.active_low = match active.as_str().map(|a| a.to_lowercase()).as_deref() {
+            Some("low") => true,
+            Some("high") => false,
+            _ => return Err(Error::ConfigInvalid(format!("GPIO_LINES {} ACTIVE for '{}' must be low or high", what, hostname))),
+        };
+    }
+    if let Some(pull) = get_either_case(map, "PULL") {
+        line.pull = match pull.as_str().map(|p| p.to_lowercase()).as_deref() {
+            Some("up") => GpioPull::Up,
+            Some("down") => GpioPull::Down,
+            Some("off") | Some("none") => GpioPull::Off,
+            _ => return Err(Error::ConfigInvalid(format!("GPIO_LINES {} PULL for '{}' must be up, down or off", what, hostname))),
+        };
+    }
+    if let Some(debounce) = get_either_case(map, "DEBOUNCE_MS") {
+        let ms = debounce.as_u64()
+            .ok_or_else(|| Error::ConfigInvalid(format!("GPIO_LINES {} DEBOUNCE_MS for '{}' must be a whole number of milliseconds", what, hostname)))?;
+        line.debounce = Duration::from_millis(ms);
+    }
+    Ok(line)
+}
+
+/// The optional GPIO_LINES block: DEFAULT for every line plus entries keyed by pin number
+fn parse_gpio_lines(host_block: &serde_yaml::Mapping, hostname: &str) -> Result<(GpioLineConfig, std::collections::HashMap<u32, GpioLineConfig>)> {
+    let Some(block) = host_block.get(&serde_yaml::Value::from("GPIO_LINES")) else {
+        return Ok((GpioLineConfig::default(), std::collections::HashMap::new()));
+    };
+    let map = block.as_mapping()
+        .ok_or_else(|| Error::ConfigInvalid(format!("GPIO_LINES for '{}' must be a mapping", hostname)))?;
+    let line_default = match get_either_case(map, "DEFAULT") {
+        Some(v) => parse_gpio_line(v, GpioLineConfig::default(), "DEFAULT", hostname)?,
+        None => GpioLineConfig::default(),
+    };
+    let mut lines = std::collections::HashMap::new();
+    for (key, value) in map {
+        if key.as_str().is_some_and(|k| k.eq_ignore_ascii_case("DEFAULT")) {
+            continue;
+        }
+        let pin = key.as_u64()
+            .or_else(|| key.as_str().and_then(|k| k.trim().parse().ok()))
+            .ok_or_else(|| Error::ConfigInvalid(format!("GPIO_LINES key {:?} for '{}' must be a pin number or DEFAULT", key, hostname)))?;
+        lines.insert(pin as u32, parse_gpio_line(value, line_default, &format!("pin {}", pin), hostname)?);
+    }
+    Ok((line_default, lines))
+}
+
+/// Load GPIO configuration for a given hostname from string_driver.yaml.
+/// Returns None if GPIO_ENABLED is false or not present.
+/// Fails loudly if GPIO_ENABLED is true but required keys are missing.
+pub fn load_gpio_settings(hostname: &str) -> Result<Option<GpioSettings>> {
+    let host_block = load_host_block(hostname)?;
+    let host_block = &host_block;
+
+    // Check if GPIO is enabled
+    let gpio_enabled = host_block.get(&serde_yaml::Value::from("GPIO_ENABLED"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    if !gpio_enabled {
+        return Ok(None);
+    }
+
+    // GPIO is enabled, so we require GPIO_LIBRARY and GPIO_MAX_STEPS
+    let library = host_block.get(&serde_yaml::Value::from("GPIO_LIBRARY"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    let max_steps = host_block.get(&serde_yaml::Value::from("GPIO_MAX_STEPS"))
+        .and_then(|v| v.as_i64())
+        .map(|v| v as u32);
+
+    // Limit switches of the AXES axes are requested along with GPIO_COMPONENTS
+    let axis_pins: Vec<(String, Option<u32>, Option<u32>)> = parse_axes(host_block, hostname)?
+        .into_iter()
+        .map(|axis| (axis.name, axis.home_pin, axis.away_pin))
+        .collect();
+    // So are the DAMPERS relay lines, as outputs
+    let (damper_pins, damper_active_low) = match parse_dampers(host_block, hostname)?.map(|dampers| dampers.output) {
+        Some(DamperOutput::Gpio { pins, active_low }) => (pins, active_low),
+        _ => (Vec::new(), false),
+    };
+
+    // Parse GPIO_COMPONENTS
+    let components = host_block.get(&serde_yaml::Value::from("GPIO_COMPONENTS"))
+        .and_then(|v| v.as_mapping())
+        .map(|comp_map| {
+            let z_touch_pins = comp_map.get(&serde_yaml::Value::from("Z_TOUCH_PINS"))
+                .and_then(|v| v.as_sequence())
+                .map(|seq| seq.iter().filter_map(|v| v.as_i64().map(|n| n as u32)).collect());
+
+            let x_home_pin = comp_map.get(&serde_yaml::Value::from("X_HOME_PIN"))
+                .and_then(|v| v.as_i64())
+                .map(|n| n as u32);
+
+            let x_away_pin = comp_map.get(&serde_yaml::Value::from("X_AWAY_PIN"))
+                .and_then(|v| v.as_i64())
+                .map(|n| n as u32);
+
+            let x_limit_pin = comp_map.get(&serde_yaml::Value::from("X_LIMIT_PIN"))
+                .and_then(|v| v.as_i64())
+                .map(|n| n as u32);
+
+            let rotary_encoder_pins = comp_map.get(&serde_yaml::Value::from("ROTARY_ENCODER_PINS"))
+                .and_then(|v| v.as_mapping())
+                .and_then(|m| {
+                    let a = m.get(&serde_yaml::Value::from("A"))?.as_i64()? as u32;
+                    let b = m.get(&serde_yaml::Value::from("B"))?.as_i64()? as u32;
+                    Some(RotaryEncoderPins { a, b })
+                });
+
+            let distance_sensor_pins = comp_map.get(&serde_yaml::Value::from("DISTANCE_SENSOR_PINS"))
+                .and_then(|v| v.as_mapping())
+                .and_then(|m| {
+                    let trig = m.get(&serde_yaml::Value::from("TRIG"))?.as_i64()? as u32;
+                    let echo = m.get(&serde_yaml::Value::from("ECHO"))?.as_i64()? as u32;
                     Some(DistanceSensorPins { trig, echo })
                 });
 
@@ -401,6 +3098,9 @@ pub fn load_gpio_settings(hostname: &str) -> Result<Option<GpioSettings>> {
                 x_home_pin,
                 x_away_pin,
                 x_limit_pin,
+                axis_pins,
+                damper_pins,
+                damper_active_low,
                 rotary_encoder_pins,
                 distance_sensor_pins,
             }
@@ -409,14 +3109,18 @@ pub fn load_gpio_settings(hostname: &str) -> Result<Option<GpioSettings>> {
     // If GPIO is enabled, require GPIO_LIBRARY (fail-fast per rules)
     // GPIO_MAX_STEPS is optional - only needed if X-axis stepper hardware is present
     if library.is_none() {
-        return Err(anyhow!("GPIO_ENABLED is true but GPIO_LIBRARY is missing for '{}' in string_driver.yaml", hostname));
+        return Err(Error::ConfigMissing(format!("GPIO_ENABLED is true but GPIO_LIBRARY is missing for '{}' in string_driver.yaml", hostname)));
     }
 
+    let (line_default, lines) = parse_gpio_lines(host_block, hostname)?;
+
     Ok(Some(GpioSettings {
         enabled: true,
         library,
         max_steps,
         components,
+        line_default,
+        lines,
     }))
 }
 
@@ -438,8 +3142,121 @@ impl DbSettings {
         let host = env::var("PG_HOST").or_else(|_| env::var("DB_HOST")).unwrap_or_else(|_| "192.168.1.84".to_string());
         let port = env::var("PG_PORT").or_else(|_| env::var("DB_PORT")).ok().and_then(|s| s.parse().ok()).unwrap_or(5432);
         let user = env::var("PG_USER").or_else(|_| env::var("DB_USER")).unwrap_or_else(|_| "GJW".to_string());
-        let password = env::var("PG_PASSWORD").or_else(|_| env::var("DB_PASSWORD")).map_err(|_| anyhow!("PG_PASSWORD or DB_PASSWORD environment variable required"))?;
+        let password = env::var("PG_PASSWORD").or_else(|_| env::var("DB_PASSWORD")).map_err(|_| Error::ConfigMissing("PG_PASSWORD or DB_PASSWORD environment variable required".to_string()))?;
         let database = env::var("PG_DATABASE").or_else(|_| env::var("DB_NAME")).unwrap_or_else(|_| "String_Driver".to_string());
         Ok(Self { host, port, user, password, database })
     }
 }
+
+// -------------------- Host section export/import --------------------
+
+const OS_SECTIONS: [&str; 3] = ["RaspberryPi", "Ubuntu", "macOS"];
+
+fn load_config_yaml() -> Result<serde_yaml::Value> {
+    let yaml_path = config_path();
+    let file = File::open(&yaml_path)
+        .map_err(|e| Error::ConfigMissing(format!("Missing required string_driver.yaml at {:?}: {}", yaml_path, e)))?;
+    Ok(serde_yaml::from_reader(file)?)
+}
+
+/// string_driver.yaml block for `hostname`, with what it inherits from DEFAULTS and
+/// EXTENDS, together with the OS section it lives in
+pub fn load_host_section(hostname: &str) -> Result<(String, serde_yaml::Mapping)> {
+    resolve_host_block(&load_config_yaml()?, hostname)?
+        .map(|(os_key, block)| (os_key.to_string(), block))
+        .ok_or_else(|| Error::ConfigMissing(format!("No host entry for '{}' in string_driver.yaml", hostname)))
+}
+
+/// `hostname`'s block as written, without what it inherits: the one to change and
+/// write back through install_host_section
+pub fn load_own_host_section(hostname: &str) -> Result<(String, serde_yaml::Mapping)> {
+    let yaml = load_config_yaml()?;
+    find_own_host_block(&yaml, hostname)
+        .map(|(os_key, block)| (os_key.to_string(), block.clone()))
+        .ok_or_else(|| Error::ConfigMissing(format!("No host entry for '{}' in string_driver.yaml", hostname)))
+}
+
+/// Write `section` as the block for `hostname` under the `os_key` section,
+/// replacing the host's existing block there or adding one (and the section, if
+/// the file has none). A host already defined under another OS section is
+/// refused rather than duplicated. The rest of the file, comments included, is
+/// left as it was. A timestamped copy of the old file is kept next to it and its
+/// path returned.
+pub fn install_host_section(os_key: &str, hostname: &str, section: &serde_yaml::Mapping) -> Result<PathBuf> {
+    if !OS_SECTIONS.contains(&os_key) {
+        return Err(Error::ConfigInvalid(format!("Unknown OS section '{}' (expected one of {:?})", os_key, OS_SECTIONS)));
+    }
+    let yaml_path = config_path();
+    let original = std::fs::read_to_string(&yaml_path)
+        .map_err(|e| Error::ConfigMissing(format!("Missing required string_driver.yaml at {:?}: {}", yaml_path, e)))?;
+    let parsed: serde_yaml::Value = serde_yaml::from_str(&original)?;
+    if let Some((other, _)) = find_own_host_block(&parsed, hostname).filter(|(key, _)| *key != os_key) {
+        return Err(Error::ConfigInvalid(format!(
+            "'{}' is already defined under {}; move or remove it there before installing it under {}",
+            hostname, other, os_key
+        )));
+    }
+
+    let mut block = serde_yaml::Mapping::new();
+    block.insert(serde_yaml::Value::from(hostname), serde_yaml::Value::Mapping(section.clone()));
+    let rendered: Vec<String> = serde_yaml::to_string(&block)?
+        .lines()
+        .map(|line| format!("  {}", line))
+        .collect();
+
+    let mut lines: Vec<String> = original.lines().map(str::to_string).collect();
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let is_content = |line: &str| !line.trim().is_empty();
+    let host_line = format!("  {}:", hostname);
+    let os_line = format!("{}:", os_key);
+
+    match lines.iter().position(|l| l.trim_end() == os_line) {
+        Some(os_start) => {
+            // The OS section runs to the next top-level key
+            let os_end = lines[os_start + 1..]
+                .iter()
+                .position(|l| is_content(l) && indent(l) == 0 && !l.starts_with('#'))
+                .map_or(lines.len(), |offset| os_start + 1 + offset);
+            // Only a host key inside it counts: PROFILES entries and other OS
+            // sections may use the same name
+            let existing = lines[os_start + 1..os_end].iter().position(|l| l.trim_end() == host_line);
+            if let Some(offset) = existing {
+                // Existing block: from its key line to the next line indented two spaces or less
+                let start = os_start + 1 + offset;
+                let mut end = lines[start + 1..os_end]
+                    .iter()
+                    .position(|l| is_content(l) && indent(l) <= 2)
+                    .map_or(os_end, |offset| start + 1 + offset);
+                while end > start + 1 && !is_content(&lines[end - 1]) {
+                    end -= 1; // Keep the blank lines separating it from the next block
+                }
+                lines.splice(start..end, rendered);
+            } else {
+                let mut end = os_end;
+                while end > os_start + 1 && (!is_content(&lines[end - 1]) || lines[end - 1].starts_with('#')) {
+                    end -= 1; // Leave comments/blank lines introducing the next top-level section
+                }
+                let mut insert = vec![String::new()];
+                insert.extend(rendered);
+                lines.splice(end..end, insert);
+            }
+        }
+        None => {
+            lines.push(String::new());
+            lines.push(os_line);
+            lines.extend(rendered);
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    serde_yaml::from_str::<serde_yaml::Value>(&updated)
+        .map_err(|e| Error::ConfigInvalid(format!("Refusing to write string_driver.yaml: result does not parse ({})", e)))?;
+
+    let backup = yaml_path.with_extension(format!("yaml.bak-{}", chrono::Local::now().format("%Y%m%d_%H%M%S")));
+    std::fs::copy(&yaml_path, &backup)
+        .map_err(|e| Error::io(format!("Cannot back up string_driver.yaml to {:?}", backup), e))?;
+    std::fs::write(&yaml_path, updated)
+        .map_err(|e| Error::io(format!("Cannot write {:?}", yaml_path), e))?;
+    Ok(backup)
+}
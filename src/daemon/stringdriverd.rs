@@ -0,0 +1,760 @@
+/// Headless String Driver daemon
+///
+/// Run with: cargo run --release --bin stringdriverd --no-default-features
+///
+/// Keeps the instrument automated with no window open. It owns the Arduino
+/// boards through the stepper core (stepper_core) and serves the same socket and
+/// STEPPER_BRIDGE protocol, so operations_gui and master_gui connect to it as
+/// they would to stepper_gui (run one or the other: only one process can own
+/// the boards). GPIO and the partials analysis run through Operations, and
+/// IDLE_PARK parks the instrument after a spell of inactivity. Without a window it
+/// runs operations itself: the AUTOSTART sequence once the main board answers,
+/// then COMMAND_INBOX files, and it keeps STATUS_FILE current. Under systemd
+/// (stringdriverd.service) it reports READY=1 once the boards are connected,
+/// pings the watchdog, and on SIGTERM parks and closes the ports before exiting.
+///
+/// `stringdriverd config dump [--json] [--host NAME]` prints the settings in
+/// effect for a host and where each came from, without touching the hardware.
+
+use clap::{Parser, Subcommand};
+use gethostname::gethostname;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use stringdriver::access::AccessPolicy;
+use stringdriver::audmon_client::{AudmonClient, AudmonConfig};
+use stringdriver::build_info::BuildInfo;
+use stringdriver::command_inbox::{self, InboxCommand, InboxStatus};
+use stringdriver::config_loader::{
+    self, AlertEvent, AutostartStep, BumpOverrides, CommandInboxSettings, IdleParkSettings, ParameterPreset, RefreshRates, RestOverrides, StatusFileSettings,
+};
+use stringdriver::error::{self, Error, Result};
+use stringdriver::lifecycle::{ShutdownFlag, SHUTDOWN_TIMEOUT};
+use stringdriver::logging::LogBuffer;
+use stringdriver::operations::{ChannelBands, OperationOutcome, Operations, StepperOperations};
+use stringdriver::status_snapshot::{self, BuildStatus, HealthStatus, LastOperation, StatusSnapshot};
+use stringdriver::stepper_core::StepperCore;
+use stringdriver::{build_info, logging, profiling, systemd};
+
+/// Client name on the daemon's own stepper commands (audit log, stepper log)
+const CLIENT: &str = "stringdriverd";
+/// How often the main loop wakes when nothing else is due
+const LOOP_INTERVAL: Duration = Duration::from_millis(100);
+/// How often IDLE_PARK looks at the positions
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// WARN and ERROR lines kept in the status file
+const STATUS_RECENT_ERRORS: usize = 20;
+
+#[derive(Parser)]
+#[command(author, version, about = "Headless String Driver daemon", long_about = None)]
+struct Args {
+    #[arg(long)]
+    debug: bool,
+    /// Leave the steppers where they are on SIGTERM (e.g. a restart during maintenance)
+    #[arg(long)]
+    no_park: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Inspect the configuration instead of running the daemon
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print every setting in effect and its source (default, yaml, learned)
+    Dump {
+        /// JSON instead of text
+        #[arg(long)]
+        json: bool,
+        /// Another host's section of string_driver.yaml (default: this machine)
+        #[arg(long)]
+        host: Option<String>,
+    },
+}
+
+/// StepperOperations straight into the in-process stepper core, for Operations' runs
+struct LocalSteppers(Arc<Mutex<StepperCore>>);
+
+impl LocalSteppers {
+    fn command(&self, cmd: &str) -> Result<()> {
+        lock(&self.0).run_command(cmd, CLIENT)
+    }
+}
+
+impl StepperOperations for LocalSteppers {
+    fn rel_move(&mut self, stepper: usize, delta: i32) -> Result<()> {
+        self.command(&format!("rel_move {} {}", stepper, delta))
+    }
+
+    fn abs_move(&mut self, stepper: usize, position: i32) -> Result<()> {
+        self.command(&format!("abs_move {} {}", stepper, position))
+    }
+
+    fn reset(&mut self, stepper: usize, position: i32) -> Result<()> {
+        self.command(&format!("reset {} {}", stepper, position))
+    }
+
+    fn disable(&mut self, stepper: usize) -> Result<()> {
+        self.command(&format!("disable {}", stepper))
+    }
+
+    fn enable(&mut self, stepper: usize) -> Result<()> {
+        self.command(&format!("enable {}", stepper))
+    }
+
+    fn set_speed(&mut self, stepper: usize, speed: i32) -> Result<()> {
+        self.command(&format!("set_speed {} {}", stepper, speed))
+    }
+
+    fn set_bow_speed(&mut self, string_idx: usize, speed: u32) -> Result<()> {
+        self.command(&format!("bow_speed {} {}", string_idx, speed))
+    }
+
+    fn damp(&mut self, string_idx: usize, engaged: bool) -> Result<()> {
+        self.command(&format!("damp {} {}", string_idx, engaged as u8))
+    }
+
+    fn reported_positions(&mut self) -> Result<Option<Vec<i32>>> {
+        Ok(lock(&self.0).reported_positions())
+    }
+}
+
+/// The stepper core, even if a socket thread panicked holding it
+fn lock(core: &Mutex<StepperCore>) -> MutexGuard<'_, StepperCore> {
+    core.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// IDLE_PARK without operations_gui: no stepper has moved for TIMEOUT while a Z
+/// stepper is still down near its string
+struct IdleWatch {
+    settings: IdleParkSettings,
+    last_positions: Option<Vec<i32>>,
+    last_activity: Instant,
+    next_check: Instant,
+}
+
+impl IdleWatch {
+    fn new(settings: IdleParkSettings) -> Self {
+        Self { settings, last_positions: None, last_activity: Instant::now(), next_check: Instant::now() }
+    }
+
+    /// Park when the watch runs out; a park counts as activity
+    fn check(&mut self, operations: &Operations, core: &Arc<Mutex<StepperCore>>) {
+        let now = Instant::now();
+        if now < self.next_check {
+            return;
+        }
+        self.next_check = now + IDLE_CHECK_INTERVAL;
+        let (positions, z_max) = {
+            let mut core = lock(core);
+            (core.reported_positions(), core.z_max())
+        };
+        if positions.is_none() || positions != self.last_positions {
+            self.last_positions = positions;
+            self.last_activity = now;
+            return;
+        }
+        if self.last_activity.elapsed() < self.settings.timeout {
+            return;
+        }
+        self.last_activity = now;
+        let by_index: HashMap<usize, i32> = positions.iter().flatten().copied().enumerate().collect();
+        let near = operations.z_steppers_below_park(&by_index, &z_max_positions(operations, z_max), self.settings.margin);
+        if near.is_empty() {
+            return;
+        }
+        let labels: Vec<String> = near.iter().map(|&idx| operations.stepper_label(idx)).collect();
+        operations.alert(
+            AlertEvent::IdlePark,
+            &format!("Idle for {:.0} min with {} near the strings - parking", self.settings.timeout.as_secs_f32() / 60.0, labels.join(", ")),
+        );
+        park(operations, core, "idle");
+    }
+}
+
+fn z_max_positions(operations: &Operations, z_max: i32) -> HashMap<usize, i32> {
+    operations.get_z_stepper_indices().into_iter().map(|idx| (idx, z_max)).collect()
+}
+
+/// Operations::park on the in-process boards: Z up, X to PARK.X_POS, sensors checked
+fn park(operations: &Operations, core: &Arc<Mutex<StepperCore>>, reason: &str) {
+    let (positions, z_max) = {
+        let mut core = lock(core);
+        (core.reported_positions(), core.z_max())
+    };
+    let Some(mut positions) = positions else {
+        tracing::warn!("Not parking ({}): board positions unavailable", reason);
+        return;
+    };
+    let mut steppers = LocalSteppers(Arc::clone(core));
+    let outcome = operations
+        .begin_operation("park")
+        .and_then(|_| operations.park(&mut steppers, &mut positions, &z_max_positions(operations, z_max), None));
+    operations.end_operation("park", &outcome);
+    match outcome {
+        Ok(summary) => tracing::info!("Parked ({}): {}", reason, summary.replace('\n', "; ")),
+        Err(e) => tracing::error!("Park ({}) failed: {}", reason, e),
+    }
+}
+
+/// A motion operation on its worker thread
+struct RunningOperation {
+    operation: String,
+    exit_flag: Arc<AtomicBool>,
+    worker: JoinHandle<(OperationOutcome, String)>,
+}
+
+/// AUTOSTART: waits for the main board, then runs each step in turn
+struct Autostart {
+    pending: VecDeque<AutostartStep>,
+    current: Option<(AutostartStep, Instant)>,
+    started: bool,
+}
+
+/// COMMAND_INBOX: one command file at a time
+struct Inbox {
+    settings: CommandInboxSettings,
+    access: AccessPolicy,
+    next_poll: Instant,
+    current: Option<InboxRun>,
+}
+
+/// The inbox command whose operation is running
+struct InboxRun {
+    path: PathBuf,
+    operation: String,
+    received_at: String,
+}
+
+/// STATUS_FILE writer
+struct StatusFile {
+    settings: StatusFileSettings,
+    next_write: Instant,
+    failing: bool,
+}
+
+/// What operations_gui does on its own, headless: runs operations on a worker
+/// thread, AUTOSTART, COMMAND_INBOX and STATUS_FILE
+struct Automation {
+    hostname: String,
+    operations: Arc<Operations>,
+    core: Arc<Mutex<StepperCore>>,
+    log_buffer: LogBuffer,
+    bands: ChannelBands,
+    presets: Vec<(String, ParameterPreset)>,
+    running: Option<RunningOperation>,
+    last_operation: Option<(String, OperationOutcome, Instant)>,
+    last_message: String,
+    autostart: Option<Autostart>,
+    inbox: Option<Inbox>,
+    status_file: Option<StatusFile>,
+}
+
+impl Automation {
+    fn new(hostname: &str, operations: Arc<Operations>, core: Arc<Mutex<StepperCore>>, log_buffer: LogBuffer, voice_cap: i32) -> Result<Self> {
+        let autostart = config_loader::load_autostart(hostname)?;
+        let inbox = config_loader::load_command_inbox(hostname)?;
+        let access = command_inbox::access_policy(config_loader::load_ipc_auth(hostname)?.as_ref());
+        let status_file = config_loader::load_status_file(hostname)?;
+        let bands = ChannelBands::defaults(operations.topology.string_num(), voice_cap);
+        Ok(Self {
+            hostname: hostname.to_string(),
+            presets: config_loader::load_presets(hostname)?,
+            operations,
+            core,
+            log_buffer,
+            bands,
+            running: None,
+            last_operation: None,
+            last_message: String::new(),
+            autostart: (!autostart.is_empty()).then(|| Autostart { pending: autostart.into(), current: None, started: false }),
+            inbox: inbox.map(|settings| Inbox { settings, access, next_poll: Instant::now(), current: None }),
+            status_file: status_file.map(|settings| StatusFile { settings, next_write: Instant::now(), failing: false }),
+        })
+    }
+
+    fn is_busy(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// One pass of the main loop
+    fn poll(&mut self) {
+        self.reap();
+        self.advance_autostart();
+        self.poll_inbox();
+        self.write_status_file();
+    }
+
+    /// Start `operation` on the worker thread, with this run's time limit, rests and bump_check strategy
+    fn start(&mut self, operation: &str, time_limit: Option<Duration>, rests: RestOverrides, bump: BumpOverrides) -> std::result::Result<(), String> {
+        if let Some(running) = &self.running {
+            return Err(format!("{} is running", running.operation));
+        }
+        if self.operations.describe_operation(operation).is_none() {
+            return Err(format!("unknown operation '{}'", operation));
+        }
+        self.operations.can_start(operation)?;
+        let (positions, z_max) = {
+            let mut core = lock(&self.core);
+            (core.reported_positions(), core.z_max())
+        };
+        let mut positions = positions.ok_or_else(|| "board positions unavailable".to_string())?;
+        let max_positions = z_max_positions(&self.operations, z_max);
+
+        tracing::info!("Executing {}...", operation);
+        self.operations.claim_operation(operation);
+        self.operations.hook(config_loader::HookEvent::OperationStarted, serde_json::json!({ "operation": operation }));
+        let exit_flag = Arc::new(AtomicBool::new(false));
+        let operations = Arc::clone(&self.operations);
+        let mut steppers = LocalSteppers(Arc::clone(&self.core));
+        let bands = self.bands.clone();
+        let name = operation.to_string();
+        let stop = Arc::clone(&exit_flag);
+        let worker = thread::spawn(move || {
+            let _span = tracing::info_span!("operation", name = %name).entered();
+            if let Err(e) = operations.begin_operation(&name) {
+                return (OperationOutcome::Failed, format!("Error: {}", error::user_message(&e)));
+            }
+            let (progress_tx, progress_rx) = mpsc::channel::<String>();
+            thread::spawn(move || {
+                while let Ok(msg) = progress_rx.recv() {
+                    tracing::info!("{}", msg);
+                }
+            });
+            let result = operations.with_time_budget(&name, time_limit, &stop, || operations.with_rest_overrides(&name, rests, || {
+                operations.with_bump_overrides(bump, || {
+                    operations.run_named(&name, &mut steppers, &mut positions, &max_positions, &bands, Some(&stop), Some(&progress_tx), None)
+                })
+            }));
+            // A run cut off by its time limit may have stopped with a string pressed down
+            let result = match result {
+                Err(Error::OperationTimedOut { operation, budget, partial }) => {
+                    let retracted = operations.retract_z_to_safe_height(&mut steppers, &mut positions, &max_positions);
+                    let partial = [partial, retracted].into_iter().filter(|m| !m.is_empty()).collect::<Vec<_>>().join("\n");
+                    Err(Error::OperationTimedOut { operation, budget, partial })
+                }
+                other => other,
+            };
+            operations.end_operation(&name, &result);
+            let outcome = OperationOutcome::of(&result);
+            let message = match result {
+                Ok(msg) => msg,
+                Err(e) => format!("Error: {}", error::user_message(&e)),
+            };
+            (outcome, message)
+        });
+        self.running = Some(RunningOperation { operation: operation.to_string(), exit_flag, worker });
+        Ok(())
+    }
+
+    /// Collect a finished operation
+    fn reap(&mut self) {
+        if !self.running.as_ref().is_some_and(|running| running.worker.is_finished()) {
+            return;
+        }
+        let Some(running) = self.running.take() else {
+            return;
+        };
+        let (outcome, message) = running.worker.join().unwrap_or_else(|_| (OperationOutcome::Failed, "Error: operation worker panicked".to_string()));
+        self.operations.release_operation(&running.operation);
+        if outcome.succeeded() {
+            tracing::info!("{} {}: {}", running.operation, outcome.name(), message.replace('\n', "; "));
+        } else {
+            tracing::error!("{} {}: {}", running.operation, outcome.name(), message.replace('\n', "; "));
+        }
+        self.last_operation = Some((running.operation, outcome, Instant::now()));
+        self.last_message = message;
+    }
+
+    /// Stop the running operation and wait for it, before the shutdown park
+    fn stop(&mut self) {
+        if let Some(running) = &self.running {
+            tracing::info!("Stopping {}", running.operation);
+            running.exit_flag.store(true, Ordering::Relaxed);
+        }
+        while self.running.is_some() {
+            self.reap();
+            thread::sleep(LOOP_INTERVAL);
+        }
+    }
+
+    /// AUTOSTART: once the main board reports positions, run each step in turn,
+    /// dropping the rest at the first that cannot start, fails or overruns its TIMEOUT
+    fn advance_autostart(&mut self) {
+        let Some(mut autostart) = self.autostart.take() else {
+            return;
+        };
+        if let Some((step, started)) = autostart.current.take() {
+            if let Some(running) = &self.running {
+                if started.elapsed() > step.timeout {
+                    tracing::error!("Autostart: {} exceeded its {:.0}s timeout - stopping it and the sequence", step.operation, step.timeout.as_secs_f32());
+                    running.exit_flag.store(true, Ordering::Relaxed);
+                    return;
+                }
+                autostart.current = Some((step, started));
+                self.autostart = Some(autostart);
+                return;
+            }
+            let succeeded = self.last_operation.as_ref().is_some_and(|(op, outcome, _)| *op == step.operation && outcome.succeeded());
+            if !succeeded {
+                let skipped: Vec<String> = autostart.pending.iter().map(|s| s.operation.clone()).collect();
+                tracing::error!(
+                    "Autostart: {} failed - sequence stopped{}",
+                    step.operation,
+                    if skipped.is_empty() { String::new() } else { format!(" (skipped {})", skipped.join(", ")) }
+                );
+                return;
+            }
+        }
+        if self.is_busy() {
+            self.autostart = Some(autostart);
+            return;
+        }
+        if !autostart.started {
+            if lock(&self.core).reported_positions().is_none() {
+                self.autostart = Some(autostart);
+                return;
+            }
+            autostart.started = true;
+            tracing::info!("Autostart: running {}", autostart.pending.iter().map(|s| s.operation.as_str()).collect::<Vec<_>>().join(", "));
+        }
+        let Some(step) = autostart.pending.pop_front() else {
+            tracing::info!("Autostart: sequence complete");
+            return;
+        };
+        if let Err(reason) = self.start(&step.operation, None, RestOverrides::default(), BumpOverrides::default()) {
+            tracing::error!("Autostart: {} not started: {} - sequence stopped", step.operation, reason);
+            return;
+        }
+        autostart.current = Some((step, Instant::now()));
+        self.autostart = Some(autostart);
+    }
+
+    /// Apply the named preset, bow speeds included
+    fn apply_preset(&mut self, name: &str) -> bool {
+        let Some(preset) = self.presets.iter().find(|(n, _)| n == name).map(|(_, p)| p.clone()) else {
+            return false;
+        };
+        self.apply_parameters(&preset);
+        tracing::info!("Applied preset '{}'", name);
+        true
+    }
+
+    fn apply_parameters(&mut self, preset: &ParameterPreset) {
+        self.operations.apply_parameters(preset);
+        self.bands.apply(preset);
+        if let Some(bow_speed) = &preset.bow_speed {
+            let current = self.operations.get_bow_speeds();
+            let mut steppers = LocalSteppers(Arc::clone(&self.core));
+            for (string_idx, &speed) in bow_speed.iter().enumerate().take(current.len()) {
+                if current[string_idx] != speed {
+                    if let Err(e) = self.operations.set_bow_speed(&mut steppers, string_idx, speed) {
+                        tracing::error!("Bow wheel speed for string {} not set: {}", string_idx, e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// COMMAND_INBOX, as in operations_gui: one file at a time after AUTOSTART,
+    /// its preset and params applied, its result written once the operation finishes
+    fn poll_inbox(&mut self) {
+        let Some(mut inbox) = self.inbox.take() else {
+            return;
+        };
+        if let Some(run) = inbox.current.take() {
+            if self.is_busy() {
+                inbox.current = Some(run);
+            } else {
+                let status = match &self.last_operation {
+                    Some((op, OperationOutcome::Succeeded, _)) if *op == run.operation => InboxStatus::Ok,
+                    Some((op, OperationOutcome::TimedOut, _)) if *op == run.operation => InboxStatus::Timeout,
+                    _ => InboxStatus::Failed,
+                };
+                let message = self.last_message.clone();
+                self.write_inbox_result(&run.path, status, message, run.received_at, None, None);
+            }
+            self.inbox = Some(inbox);
+            return;
+        }
+        if self.is_busy() || self.autostart.is_some() || Instant::now() < inbox.next_poll {
+            self.inbox = Some(inbox);
+            return;
+        }
+        inbox.next_poll = Instant::now() + inbox.settings.poll;
+        let Some(path) = command_inbox::next_command(&inbox.settings.dir) else {
+            self.inbox = Some(inbox);
+            return;
+        };
+        let received_at = chrono::Local::now().to_rfc3339();
+        tracing::info!("Inbox: running {}", path.display());
+        let command = command_inbox::read_command(&path);
+        if let Err(e) = command_inbox::retire(&path) {
+            // A command that cannot be retired would run again on every scan
+            tracing::error!("Inbox: cannot retire {} ({}) - no longer watching {}", path.display(), e, inbox.settings.dir.display());
+            return;
+        }
+        let command = command.and_then(|command| command_inbox::check_access(&inbox.access, &command).map(|_| command));
+        let finished = match command {
+            Ok(command) => self.run_inbox_command(&mut inbox, &path, command, &received_at),
+            Err(e) => Some((InboxStatus::Rejected, e)),
+        };
+        if let Some((status, message)) = finished {
+            self.write_inbox_result(&path, status, message, received_at, None, None);
+        }
+        self.inbox = Some(inbox);
+    }
+
+    /// Carry out an authorized command file; None when its result is already written
+    /// (listings) or waits for the operation it started
+    fn run_inbox_command(&mut self, inbox: &mut Inbox, path: &Path, command: InboxCommand, received_at: &str) -> Option<(InboxStatus, String)> {
+        let mut applied = Vec::new();
+        if let Some(name) = command.preset.as_deref() {
+            if !self.apply_preset(name) {
+                return Some((InboxStatus::Rejected, format!("unknown preset '{}'", name)));
+            }
+            applied.push(format!("Applied preset '{}'", name));
+        }
+        if let Some(params) = command.params {
+            match serde_json::from_value::<ParameterPreset>(params) {
+                Ok(params) => {
+                    if let Some(name) = params.bump.and_then(|bump| bump.invalid()) {
+                        return Some((InboxStatus::Rejected, format!("invalid params: BUMP.{} is out of range", name)));
+                    }
+                    self.apply_parameters(&params);
+                    applied.push("Inbox: parameters applied".to_string());
+                }
+                Err(e) => return Some((InboxStatus::Rejected, format!("invalid params: {}", e))),
+            }
+        }
+        let operation = match command.operation {
+            None => return Some((InboxStatus::Ok, applied.join("\n"))),
+            Some(operation) => operation,
+        };
+        match operation.as_str() {
+            "clear_fault" => Some(if self.operations.clear_fault() {
+                (InboxStatus::Ok, "Fault cleared".to_string())
+            } else {
+                (InboxStatus::Rejected, "instrument is not faulted".to_string())
+            }),
+            "list_operations" => {
+                let operations = serde_json::to_value(self.operations.operation_descriptors()).ok();
+                self.write_inbox_result(path, InboxStatus::Ok, "Operations listed".to_string(), received_at.to_string(), operations, None);
+                None
+            }
+            "effective_config" => {
+                let config = serde_json::to_value(self.operations.effective_config()).ok();
+                self.write_inbox_result(path, InboxStatus::Ok, "Effective settings listed".to_string(), received_at.to_string(), None, config);
+                None
+            }
+            _ => {
+                let time_limit = match command.max_duration {
+                    None => None,
+                    Some(seconds) => match Duration::try_from_secs_f64(seconds).ok().filter(|d| !d.is_zero()) {
+                        Some(limit) => Some(limit),
+                        None => return Some((InboxStatus::Rejected, "max_duration must be a positive number of seconds".to_string())),
+                    },
+                };
+                let rests = command.rests.unwrap_or_default();
+                if let Some(name) = rests.invalid() {
+                    return Some((InboxStatus::Rejected, format!("rests.{} must be a non-negative number of seconds", name)));
+                }
+                let bump = command.bump.unwrap_or_default();
+                if let Some(name) = bump.invalid() {
+                    return Some((InboxStatus::Rejected, format!("bump.{} is out of range", name)));
+                }
+                match self.start(&operation, time_limit, rests, bump) {
+                    Ok(()) => {
+                        inbox.current = Some(InboxRun { path: path.to_path_buf(), operation, received_at: received_at.to_string() });
+                        None
+                    }
+                    Err(reason) => Some((InboxStatus::Rejected, format!("{} not started: {}", operation, reason))),
+                }
+            }
+        }
+    }
+
+    fn write_inbox_result(
+        &self,
+        path: &Path,
+        status: InboxStatus,
+        message: String,
+        received_at: String,
+        operations: Option<serde_json::Value>,
+        config: Option<serde_json::Value>,
+    ) {
+        let result = command_inbox::InboxResult {
+            command: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+            status,
+            message,
+            received_at,
+            finished_at: chrono::Local::now().to_rfc3339(),
+            state: self.operations.instrument_state().0.describe(),
+            operations,
+            config,
+        };
+        match command_inbox::write_result(path, &result) {
+            Ok(()) => tracing::info!("Inbox: {} finished ({:?})", result.command, status),
+            Err(e) => tracing::error!("Inbox: writing the result for {} failed: {}", result.command, e),
+        }
+    }
+
+    /// Rewrite the STATUS_FILE snapshot when its interval has passed
+    fn write_status_file(&mut self) {
+        if self.status_file.as_ref().is_none_or(|file| Instant::now() < file.next_write) {
+            return;
+        }
+        let snapshot = self.status_snapshot();
+        let Some(file) = self.status_file.as_mut() else {
+            return;
+        };
+        file.next_write = Instant::now() + file.settings.interval;
+        match status_snapshot::write(&file.settings.path, &snapshot) {
+            Ok(()) => file.failing = false,
+            Err(e) => {
+                if !file.failing {
+                    tracing::warn!("Writing status file {} failed: {}", file.settings.path.display(), e);
+                }
+                file.failing = true;
+            }
+        }
+    }
+
+    /// Machine state for the STATUS_FILE snapshot; the daemon owns the boards, so
+    /// both build entries are its own
+    fn status_snapshot(&self) -> StatusSnapshot {
+        let (positions, firmware) = {
+            let mut core = lock(&self.core);
+            (core.reported_positions(), core.firmware_protocol())
+        };
+        let build = BuildStatus {
+            operations_gui: BuildInfo::local("stringdriverd"),
+            stepper_gui: Some(BuildInfo { firmware, ..BuildInfo::local("stringdriverd") }),
+        };
+        let snapshot = StatusSnapshot::from_operations(&self.hostname, &self.operations, build);
+        let board = match &positions {
+            Some(_) => HealthStatus { name: "Arduino main".to_string(), level: "ok".to_string(), detail: "connected".to_string() },
+            None => HealthStatus { name: "Arduino main".to_string(), level: "error".to_string(), detail: "not connected".to_string() },
+        };
+        StatusSnapshot {
+            positions: positions.into_iter().flatten().enumerate().collect(),
+            operation: self.running.as_ref().map(|running| running.operation.clone()),
+            last_operation: self.last_operation.as_ref().map(|(name, outcome, at)| LastOperation::new(name, *outcome, *at)),
+            health: vec![board],
+            recent_errors: status_snapshot::recent_errors(&self.log_buffer, STATUS_RECENT_ERRORS),
+            ..snapshot
+        }
+    }
+}
+
+fn run(hostname: &str, args: &Args, log_buffer: LogBuffer) -> std::result::Result<(), String> {
+    // SIGTERM (systemctl stop) and SIGINT both end the main loop below
+    let shutdown = ShutdownFlag::default();
+    let on_signal = shutdown.clone();
+    ctrlc::set_handler(move || on_signal.set()).map_err(|e| format!("Could not install the SIGTERM handler: {}", e))?;
+
+    let refresh_rates = config_loader::load_refresh_rates(hostname).map_err(|e| e.to_string())?;
+    profiling::set_budgets(profiling::Budgets::from_rates(&refresh_rates));
+    let idle_park = config_loader::load_idle_park(hostname).map_err(|e| e.to_string())?;
+
+    let mut core = StepperCore::from_config(hostname, args.debug)?;
+    core.connect_configured();
+    let core = Arc::new(Mutex::new(core));
+    StepperCore::start_socket_listener(Arc::clone(&core));
+
+    // Analysis intake: shared memory partials into Operations, as in operations_gui
+    let mut audmon = AudmonClient::spawn(AudmonConfig {
+        slot_poll: RefreshRates::interval(refresh_rates.slot_poll_hz),
+        ..AudmonConfig::default()
+    });
+    let operations = Arc::new(Operations::for_host(hostname, audmon.take_reader()).map_err(|e| e.to_string())?);
+    let voice_cap = audmon.partials_per_channel() as i32;
+    let mut automation = Automation::new(hostname, Arc::clone(&operations), Arc::clone(&core), log_buffer, voice_cap).map_err(|e| e.to_string())?;
+
+    let connected = lock(&core).reported_positions().is_some();
+    systemd::notify(&format!(
+        "READY=1\nSTATUS={}",
+        if connected { "Boards connected" } else { "Running; main board not connected" }
+    ));
+    tracing::info!("stringdriverd ready on {}", hostname);
+
+    let mut idle = idle_park.map(IdleWatch::new);
+    let watchdog = systemd::watchdog_interval();
+    let analysis_interval = RefreshRates::interval(refresh_rates.analysis_hz);
+    let mut next_analysis = Instant::now();
+    let mut next_watchdog = Instant::now();
+    while !shutdown.is_set() {
+        let now = Instant::now();
+        if now >= next_analysis {
+            next_analysis = now + analysis_interval;
+            operations.update_audio_analysis();
+        }
+        if let Some(interval) = watchdog.filter(|_| now >= next_watchdog) {
+            next_watchdog = now + interval;
+            systemd::notify("WATCHDOG=1");
+        }
+        automation.poll();
+        if let Some(idle) = idle.as_mut().filter(|_| !automation.is_busy()) {
+            idle.check(&operations, &core);
+        }
+        shutdown.sleep(LOOP_INTERVAL);
+    }
+
+    tracing::info!("stringdriverd stopping");
+    automation.stop();
+    if args.no_park {
+        systemd::notify("STOPPING=1\nSTATUS=Disconnecting");
+    } else {
+        systemd::notify("STOPPING=1\nSTATUS=Parking");
+        park(&operations, &core, "shutdown");
+    }
+    lock(&core).disconnect();
+    let stuck = audmon.shutdown(SHUTDOWN_TIMEOUT);
+    if !stuck.is_empty() {
+        tracing::warn!("Background threads still running at exit: {}", stuck.join(", "));
+    }
+    Ok(())
+}
+
+/// `config dump`: Operations loaded without GPIO, so it runs beside a live daemon
+fn config_dump(hostname: &str, json: bool) -> std::result::Result<(), String> {
+    let config = Operations::for_inspection(hostname).map_err(|e| e.to_string())?.effective_config();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?);
+    } else {
+        print!("{}", config.to_text());
+    }
+    Ok(())
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Some(Command::Config { action: ConfigAction::Dump { json, host } }) = &args.command {
+        let hostname = host.clone().unwrap_or_else(|| gethostname().to_string_lossy().to_string());
+        if let Err(e) = config_dump(&hostname, *json) {
+            eprintln!("config dump failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    // Console and log file (LOGGING block) get debug detail with --debug
+    let log_buffer = logging::init("stringdriverd", if args.debug { "debug" } else { "info" });
+    build_info::log_startup("stringdriverd");
+
+    let hostname = gethostname().to_string_lossy().to_string();
+    if let Err(e) = run(&hostname, &args, log_buffer) {
+        tracing::error!("stringdriverd failed: {}", e);
+        systemd::notify(&format!("STATUS=Failed: {}", e));
+        std::process::exit(1);
+    }
+}
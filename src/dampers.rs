@@ -0,0 +1,90 @@
+/// Per-string dampers on relays (DAMPERS)
+///
+/// A damper pressed against a string stops it sounding, so moves that would
+/// otherwise scrape or pluck it (z_calibrate lowering Z onto the touch sensors)
+/// do not leave spurious partials behind. Relays hang off GPIO output lines,
+/// driven by Operations' GPIO board, or off the carriage board's digital
+/// outputs, reached through stepper_gui's `damp <string> <1|0>` command.
+/// Operations keeps which strings are damped; operations listed under DURING
+/// run with every string damped and put the dampers back afterwards.
+
+use crate::config_loader::{DamperOutput, DamperSettings};
+use crate::error::{Error, Result};
+use crate::gpio::GpioBoard;
+use std::sync::Mutex;
+
+#[derive(Debug)]
+pub struct Dampers {
+    pub settings: DamperSettings,
+    engaged: Mutex<Vec<bool>>, // Per string, as last set (all released at start)
+}
+
+impl Dampers {
+    pub fn new(settings: DamperSettings, string_num: usize) -> Self {
+        Self { settings, engaged: Mutex::new(vec![false; string_num]) }
+    }
+
+    /// Which strings are damped
+    pub fn engaged(&self) -> Vec<bool> {
+        self.engaged.lock().map(|e| e.clone()).unwrap_or_default()
+    }
+
+    /// Whether `operation` runs with the strings damped (DURING)
+    pub fn applies_to(&self, operation: &str) -> bool {
+        self.settings.during.iter().any(|op| op == operation)
+    }
+
+    /// Engage (true) or release string `string_idx`'s damper. Relays on the carriage
+    /// board are reached through `send_serial` (StepperOperations::damp).
+    pub fn set(&self, gpio: Option<&GpioBoard>, string_idx: usize, engaged: bool, send_serial: impl FnOnce(usize, bool) -> Result<()>) -> Result<()> {
+        let string_num = self.engaged().len();
+        if string_idx >= string_num {
+            return Err(Error::Other(format!("No damper for string {} ({} strings)", string_idx, string_num)));
+        }
+        match self.settings.output {
+            DamperOutput::Gpio { .. } => {
+                let gpio = gpio.filter(|gpio| gpio.exist)
+                    .ok_or_else(|| Error::GpioUnavailable("DAMPERS uses GPIO relay lines but GPIO is not available".to_string()))?;
+                gpio.set_damper(string_idx, engaged)?;
+            }
+            DamperOutput::Serial { .. } => send_serial(string_idx, engaged)?,
+        }
+        if let Ok(mut states) = self.engaged.lock() {
+            states[string_idx] = engaged;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn settings(output: DamperOutput) -> DamperSettings {
+        DamperSettings { output, settle: Duration::ZERO, during: vec!["z_calibrate".to_string()] }
+    }
+
+    #[test]
+    fn test_serial_dampers_go_through_the_board() {
+        let dampers = Dampers::new(settings(DamperOutput::Serial { command_id: 16 }), 2);
+        let mut sent = Vec::new();
+        dampers.set(None, 1, true, |idx, on| { sent.push((idx, on)); Ok(()) }).unwrap();
+        assert_eq!(dampers.engaged(), vec![false, true]);
+        dampers.set(None, 1, false, |idx, on| { sent.push((idx, on)); Ok(()) }).unwrap();
+        assert_eq!(sent, vec![(1, true), (1, false)]);
+        // No string 2: nothing sent, nothing recorded
+        assert!(dampers.set(None, 2, true, |idx, on| { sent.push((idx, on)); Ok(()) }).is_err());
+        assert_eq!(sent.len(), 2);
+        assert!(dampers.applies_to("z_calibrate") && !dampers.applies_to("x_calibrate"));
+    }
+
+    #[test]
+    fn test_gpio_dampers_need_gpio() {
+        let dampers = Dampers::new(settings(DamperOutput::Gpio { pins: vec![20, 21], active_low: true }), 2);
+        let mut sent = Vec::new();
+        assert!(dampers.set(None, 0, true, |idx, on| { sent.push((idx, on)); Ok(()) }).is_err());
+        assert_eq!(dampers.engaged(), vec![false, false]);
+        assert!(sent.is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+/// Effective configuration with provenance
+///
+/// A value in effect may be a built-in default, come from the host's section of
+/// string_driver.yaml, have been changed since start (GUI, preset, inbox params)
+/// or have been learned by a calibration (height map, pitch curves). Operations::
+/// effective_config() gathers them in one list tagged with that source;
+/// operations_gui adds its per-channel thresholds and shows the list as
+/// "Effective settings", the inbox operation `effective_config` answers with it
+/// and `stringdriverd config dump` prints it.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Default, // Built-in value; the key is not in the host section
+    Yaml,    // The host section of string_driver.yaml
+    Runtime, // Changed since start
+    Learned, // Recorded by a calibration
+}
+
+impl Source {
+    pub fn label(self) -> &'static str {
+        match self {
+            Source::Default => "default",
+            Source::Yaml => "yaml",
+            Source::Runtime => "runtime",
+            Source::Learned => "learned",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EffectiveSetting {
+    pub section: String, // Grouping for display, e.g. "Rests" or "Blocks"
+    pub key: String,     // YAML key where there is one, e.g. "LAP_REST"
+    pub value: String,
+    pub source: Source,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EffectiveConfig {
+    pub host: String,
+    pub config_file: String,
+    pub settings: Vec<EffectiveSetting>,
+}
+
+impl EffectiveConfig {
+    pub fn push(&mut self, section: &str, key: &str, value: impl Into<String>, source: Source) {
+        self.settings.push(EffectiveSetting { section: section.to_string(), key: key.to_string(), value: value.into(), source });
+    }
+
+    pub fn get(&self, key: &str) -> Option<&EffectiveSetting> {
+        self.settings.iter().find(|s| s.key == key)
+    }
+
+    /// One aligned line per setting under its section, for `config dump`
+    pub fn to_text(&self) -> String {
+        let width = self.settings.iter().map(|s| s.key.len()).max().unwrap_or(0);
+        let mut text = format!("# Effective settings for {} ({})\n", self.host, self.config_file);
+        let mut section = None;
+        for setting in &self.settings {
+            if section != Some(&setting.section) {
+                text.push_str(&format!("\n[{}]\n", setting.section));
+                section = Some(&setting.section);
+            }
+            text.push_str(&format!("{:<width$}  {:<8}  {}\n", setting.key, setting.source.label(), setting.value, width = width));
+        }
+        text
+    }
+}
+
+/// Startup values of the settings that can change at run time, so a value that
+/// differs from them now reads as Runtime instead of Default or Yaml
+#[derive(Debug, Clone, Default)]
+pub struct Baseline {
+    yaml_keys: HashSet<String>,
+    startup: HashMap<String, String>,
+}
+
+impl Baseline {
+    /// `yaml_keys`: keys present in the host section; `values`: (key, value) at startup
+    pub fn new(yaml_keys: impl IntoIterator<Item = String>, values: &[(&str, String)]) -> Self {
+        Self {
+            yaml_keys: yaml_keys.into_iter().collect(),
+            startup: values.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+        }
+    }
+
+    /// Exactly as written: the host-level keys are only read in upper case
+    pub fn in_yaml(&self, key: &str) -> bool {
+        self.yaml_keys.contains(key)
+    }
+
+    /// Upper or lower case, as the blocks (X_REGIONS, PARK, ...) are read
+    pub fn in_yaml_either_case(&self, key: &str) -> bool {
+        self.in_yaml(key) || self.in_yaml(&key.to_ascii_lowercase())
+    }
+
+    /// Where `key`'s current `value` came from
+    pub fn source(&self, key: &str, value: &str) -> Source {
+        if self.startup.get(key).is_some_and(|v| v != value) {
+            Source::Runtime
+        } else if self.in_yaml(key) {
+            Source::Yaml
+        } else {
+            Source::Default
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_baseline_tells_runtime_changes_from_yaml_and_defaults() {
+        let baseline = Baseline::new(
+            ["LAP_REST".to_string(), "z_rest".to_string(), "park".to_string()],
+            &[("LAP_REST", "2".to_string()), ("Z_REST", "1".to_string())],
+        );
+        assert_eq!(baseline.source("LAP_REST", "2"), Source::Yaml);
+        // A lower-case Z_REST is not read, so the default is in effect
+        assert_eq!(baseline.source("Z_REST", "1"), Source::Default);
+        assert!(baseline.in_yaml_either_case("PARK") && !baseline.in_yaml("PARK"));
+        assert_eq!(baseline.source("Z_REST", "0.5"), Source::Runtime);
+        assert_eq!(baseline.source("LAP_REST", "3"), Source::Runtime);
+
+        let mut config = EffectiveConfig { host: "h".to_string(), config_file: "string_driver.yaml".to_string(), settings: Vec::new() };
+        config.push("Rests", "LAP_REST", "3", Source::Runtime);
+        config.push("Rests", "Z_REST", "1", Source::Default);
+        let text = config.to_text();
+        assert!(text.contains("[Rests]"));
+        assert!(text.contains("LAP_REST  runtime   3"), "{}", text);
+        assert_eq!(serde_json::to_value(&config).unwrap()["settings"][0]["source"], "runtime");
+    }
+}
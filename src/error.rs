@@ -0,0 +1,120 @@
+/// Error type for the shared modules (config_loader, gpio, operations, arduino_connection)
+///
+/// Binaries keep using anyhow and wrap these with `?`; code that can react to a
+/// particular failure matches on the variant (via `downcast_ref::<Error>()` once
+/// it is inside an anyhow::Error), and the GUIs show `user_message()`.
+
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    /// string_driver.yaml, this host's section or a required key is absent
+    #[error("{0}")]
+    ConfigMissing(String),
+    /// A string_driver.yaml value is present but unusable
+    #[error("{0}")]
+    ConfigInvalid(String),
+    #[error("string_driver.yaml: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    /// The Arduino is connected but did not answer a query in time
+    #[error("Arduino did not answer a {what} query within {}ms", .after.as_millis())]
+    SerialTimeout { what: String, after: Duration },
+    /// The serial port could not be found, opened, written or read
+    #[error("{0}")]
+    Serial(String),
+    /// GPIO is configured but the hardware or driver support is missing
+    #[error("{0}")]
+    GpioUnavailable(String),
+    /// stepper_gui's socket could not be reached or answered unexpectedly
+    #[error("{0}")]
+    StepperLink(String),
+    /// The operation was stopped or refused before it finished
+    #[error("{0}")]
+    OperationAborted(String),
+    /// The operation ran past its MAX_DURATION; `partial` is what it reported before stopping
+    #[error("{operation} stopped after exceeding its {:.0}s time budget{}", .budget.as_secs_f32(), on_next_line(.partial))]
+    OperationTimedOut { operation: String, budget: Duration, partial: String },
+    /// Commanded X moves stopped showing up in the reported positions; Z was raised before stopping
+    #[error("X carriage obstructed near X={at}: {failures} moves in a row were not confirmed{}", on_next_line(.partial))]
+    Obstruction { at: i32, failures: u32, partial: String },
+    /// An X move would have crossed an X_CLEARANCE zone with Z steppers below its Z_MIN
+    #[error("X move from {from} to {to} refused: {low} below the X_CLEARANCE height{}", on_next_line(.partial))]
+    ClearanceViolation { from: i32, to: i32, low: String, partial: String },
+    /// Reading or writing a file failed
+    #[error("{context}: {source}")]
+    Io { context: String, #[source] source: std::io::Error },
+    #[error("{0}")]
+    Other(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+fn on_next_line(text: &str) -> String {
+    if text.is_empty() { String::new() } else { format!("\n{}", text) }
+}
+
+impl Error {
+    pub fn io(context: impl Into<String>, source: std::io::Error) -> Self {
+        Error::Io { context: context.into(), source }
+    }
+
+    /// What the user can do about it, where there is something to do
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Error::ConfigMissing(_) | Error::ConfigInvalid(_) | Error::Yaml(_) => {
+                Some("fix this host's section in string_driver.yaml (or run setup_wizard), then restart")
+            }
+            Error::SerialTimeout { .. } => {
+                Some("the board is not answering: check its USB cable and power, then reset the board or restart stepper_gui")
+            }
+            Error::Serial(_) => Some("check the board is plugged in and ARD_PORT / ARD_USB in string_driver.yaml match it"),
+            Error::GpioUnavailable(_) => {
+                Some("GPIO needs the Raspberry Pi build with the gpiod feature; set GPIO_ENABLED: false on other hosts")
+            }
+            Error::StepperLink(_) => Some("start stepper_gui, or wait for the launcher to restart it"),
+            Error::OperationTimedOut { .. } => {
+                Some("check the sensor wiring; if the run really needs longer, raise MAX_DURATION in the OPERATIONS block or set a time limit for the run")
+            }
+            Error::Obstruction { .. } => Some("clear the X carriage path and check the belt, then run X Home before sweeping again"),
+            Error::ClearanceViolation { .. } => {
+                Some("raise the listed Z steppers (or enable them so they can be raised), or set X_CLEARANCE ACTION: raise")
+            }
+            Error::OperationAborted(_) | Error::Io { .. } | Error::Other(_) => None,
+        }
+    }
+}
+
+/// Text for the GUIs' message panels: the error, plus a hint when the chain holds one of ours
+pub fn user_message(err: &(dyn std::error::Error + 'static)) -> String {
+    let hint = std::iter::successors(Some(err), |e| e.source())
+        .find_map(|e| e.downcast_ref::<Error>())
+        .and_then(Error::hint);
+    match hint {
+        Some(hint) => format!("{}\n  -> {}", err, hint),
+        None => err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_message_adds_hint() {
+        let err = Error::SerialTimeout { what: "positions".to_string(), after: Duration::from_secs(2) };
+        let message = user_message(&err);
+        assert!(message.starts_with("Arduino did not answer a positions query within 2000ms\n"));
+        assert!(message.contains("USB cable"));
+
+        let plain = std::io::Error::other("disk full");
+        assert_eq!(user_message(&plain), "disk full");
+    }
+
+    #[test]
+    fn test_user_message_finds_wrapped_error() {
+        // What the binaries see: a library error carried inside an anyhow::Error
+        let wrapped = anyhow::Error::from(Error::ConfigMissing("No host entry for 'pi' in string_driver.yaml".to_string()));
+        assert!(user_message(wrapped.as_ref()).contains("setup_wizard"));
+    }
+}
@@ -0,0 +1,366 @@
+/// Fault injection for robustness testing (feature `fault-injection`)
+///
+/// Exercises the recovery paths (reconnect, retry, disable, safe abort) before
+/// they are trusted on stage. Each kind of fault strikes at its own probability:
+/// serial frames dropped on the way to or from a board, GPIO reads stalled,
+/// partials frames corrupted before analysis, and StepperOperations calls failing
+/// with a transient link error. Tests install a `FaultInjector` (or wrap a port or
+/// stepper backend in `Faulty`); a bench run of the GUIs reads STRINGDRIVER_FAULTS,
+/// e.g. `serial_drop=0.05,gpio_delay=0.1,gpio_delay_ms=300,stepper_error=0.02,seed=7`.
+/// None of this is compiled into a normal build.
+
+use crate::error::{Error, Result};
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Once, RwLock};
+use std::time::Duration;
+
+/// Environment variable with the fault spec for a bench run
+pub const FAULTS_ENV: &str = "STRINGDRIVER_FAULTS";
+
+static INSTALLED: RwLock<Option<Arc<FaultInjector>>> = RwLock::new(None);
+static FROM_ENV: Once = Once::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    SerialDrop,   // A command written to, or a reply read from, a board is lost
+    GpioDelay,    // A GPIO read stalls for gpio_delay first
+    ShmCorrupt,   // A partials frame from shared memory arrives with garbage amplitudes
+    StepperError, // A StepperOperations call fails with a transient link error
+}
+
+impl Fault {
+    pub const ALL: [Fault; 4] = [Fault::SerialDrop, Fault::GpioDelay, Fault::ShmCorrupt, Fault::StepperError];
+
+    /// Key in a fault spec
+    pub fn key(self) -> &'static str {
+        match self {
+            Fault::SerialDrop => "serial_drop",
+            Fault::GpioDelay => "gpio_delay",
+            Fault::ShmCorrupt => "shm_corrupt",
+            Fault::StepperError => "stepper_error",
+        }
+    }
+}
+
+/// Probability (0..=1) of each fault, per frame, read or call
+#[derive(Debug, Clone, PartialEq)]
+pub struct FaultSettings {
+    pub serial_drop: f64,
+    pub gpio_delay: f64,
+    pub gpio_delay_time: Duration,
+    pub shm_corrupt: f64,
+    pub stepper_error: f64,
+    pub seed: u64,
+}
+
+impl Default for FaultSettings {
+    fn default() -> Self {
+        Self { serial_drop: 0.0, gpio_delay: 0.0, gpio_delay_time: Duration::from_millis(200), shm_corrupt: 0.0, stepper_error: 0.0, seed: 1 }
+    }
+}
+
+impl FaultSettings {
+    /// Parse `key=value` pairs separated by commas (keys as `Fault::key`, plus gpio_delay_ms and seed)
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut settings = Self::default();
+        for pair in spec.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=')
+                .ok_or_else(|| Error::ConfigInvalid(format!("{}: expected key=value, got '{}'", FAULTS_ENV, pair)))?;
+            let (key, value) = (key.trim(), value.trim());
+            let invalid = || Error::ConfigInvalid(format!("{}: bad value '{}' for {}", FAULTS_ENV, value, key));
+            let probability = || value.parse::<f64>().ok().filter(|p| (0.0..=1.0).contains(p)).ok_or_else(invalid);
+            match key {
+                "serial_drop" => settings.serial_drop = probability()?,
+                "gpio_delay" => settings.gpio_delay = probability()?,
+                "shm_corrupt" => settings.shm_corrupt = probability()?,
+                "stepper_error" => settings.stepper_error = probability()?,
+                "gpio_delay_ms" => settings.gpio_delay_time = Duration::from_millis(value.parse().map_err(|_| invalid())?),
+                "seed" => settings.seed = value.parse().map_err(|_| invalid())?,
+                _ => return Err(Error::ConfigInvalid(format!("{}: unknown fault '{}'", FAULTS_ENV, key))),
+            }
+        }
+        Ok(settings)
+    }
+
+    pub fn probability(&self, fault: Fault) -> f64 {
+        match fault {
+            Fault::SerialDrop => self.serial_drop,
+            Fault::GpioDelay => self.gpio_delay,
+            Fault::ShmCorrupt => self.shm_corrupt,
+            Fault::StepperError => self.stepper_error,
+        }
+    }
+}
+
+/// Decides when faults strike (seeded, so a failing run can be replayed) and counts them
+#[derive(Debug)]
+pub struct FaultInjector {
+    pub settings: FaultSettings,
+    rng: Mutex<u64>,
+    injected: [AtomicU64; 4], // Per Fault, in Fault::ALL order
+}
+
+impl FaultInjector {
+    pub fn new(settings: FaultSettings) -> Self {
+        let rng = Mutex::new(settings.seed.max(1));
+        Self { settings, rng, injected: Default::default() }
+    }
+
+    /// Whether `fault` strikes this time
+    pub fn strike(&self, fault: Fault) -> bool {
+        let probability = self.settings.probability(fault);
+        if probability <= 0.0 {
+            return false;
+        }
+        let struck = self.next_unit() < probability;
+        if struck {
+            self.injected[fault as usize].fetch_add(1, Ordering::Relaxed);
+            tracing::debug!("Injected fault: {}", fault.key());
+        }
+        struck
+    }
+
+    /// How many times `fault` has struck
+    pub fn injected(&self, fault: Fault) -> u64 {
+        self.injected[fault as usize].load(Ordering::Relaxed)
+    }
+
+    /// Err with a transient link error when StepperError strikes
+    pub fn stepper_call(&self, what: &str) -> Result<()> {
+        if self.strike(Fault::StepperError) {
+            return Err(Error::StepperLink(format!("injected transient fault on {}", what)));
+        }
+        Ok(())
+    }
+
+    /// Stall before a GPIO read when GpioDelay strikes
+    pub fn gpio_read(&self) {
+        if self.strike(Fault::GpioDelay) {
+            std::thread::sleep(self.settings.gpio_delay_time);
+        }
+    }
+
+    /// Overwrite amplitudes with garbage: random bit patterns (huge, tiny, negative,
+    /// now and then NaN or infinite). Callers scramble a frame when ShmCorrupt strikes.
+    pub fn scramble(&self, partials: &mut [(f32, f32)]) {
+        for partial in partials {
+            partial.1 = f32::from_bits((self.next_unit() * u32::MAX as f64) as u32);
+        }
+    }
+
+    /// Uniform in [0, 1) (xorshift64*)
+    fn next_unit(&self) -> f64 {
+        let mut state = self.rng.lock().unwrap_or_else(|e| e.into_inner());
+        *state ^= *state >> 12;
+        *state ^= *state << 25;
+        *state ^= *state >> 27;
+        (state.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Use `injector` process-wide from now on (None turns injection off)
+pub fn install(injector: Option<Arc<FaultInjector>>) {
+    FROM_ENV.call_once(|| {});
+    *INSTALLED.write().unwrap_or_else(|e| e.into_inner()) = injector;
+}
+
+/// The process-wide injector: the installed one, else STRINGDRIVER_FAULTS on first use
+pub fn active() -> Option<Arc<FaultInjector>> {
+    FROM_ENV.call_once(|| {
+        let Ok(spec) = std::env::var(FAULTS_ENV) else { return };
+        match FaultSettings::parse(&spec) {
+            Ok(settings) => {
+                tracing::warn!("Fault injection on: {}", spec);
+                *INSTALLED.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(FaultInjector::new(settings)));
+            }
+            Err(e) => tracing::error!("Fault injection off: {}", e),
+        }
+    });
+    INSTALLED.read().ok()?.clone()
+}
+
+/// A serial port or stepper backend with faults injected into its traffic
+#[derive(Debug)]
+pub struct Faulty<T> {
+    pub inner: T,
+    pub injector: Arc<FaultInjector>,
+}
+
+impl<T> Faulty<T> {
+    pub fn new(inner: T, injector: Arc<FaultInjector>) -> Self {
+        Self { inner, injector }
+    }
+}
+
+/// `port` behind the active injector, or `port` itself with injection off
+pub fn wrap_port(port: Box<dyn serialport::SerialPort>) -> Box<dyn serialport::SerialPort> {
+    match active() {
+        Some(injector) => Box::new(Faulty::new(port, injector)),
+        None => port,
+    }
+}
+
+/// A dropped reply frame reads as a timeout, its bytes lost
+impl Read for Faulty<Box<dyn serialport::SerialPort>> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read > 0 && self.injector.strike(Fault::SerialDrop) {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "injected fault: reply dropped"));
+        }
+        Ok(read)
+    }
+}
+
+/// A dropped command frame is reported written but never reaches the board
+impl Write for Faulty<Box<dyn serialport::SerialPort>> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !buf.is_empty() && self.injector.strike(Fault::SerialDrop) {
+            return Ok(buf.len());
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl serialport::SerialPort for Faulty<Box<dyn serialport::SerialPort>> {
+    fn name(&self) -> Option<String> {
+        self.inner.name()
+    }
+
+    fn baud_rate(&self) -> serialport::Result<u32> {
+        self.inner.baud_rate()
+    }
+
+    fn data_bits(&self) -> serialport::Result<serialport::DataBits> {
+        self.inner.data_bits()
+    }
+
+    fn flow_control(&self) -> serialport::Result<serialport::FlowControl> {
+        self.inner.flow_control()
+    }
+
+    fn parity(&self) -> serialport::Result<serialport::Parity> {
+        self.inner.parity()
+    }
+
+    fn stop_bits(&self) -> serialport::Result<serialport::StopBits> {
+        self.inner.stop_bits()
+    }
+
+    fn timeout(&self) -> Duration {
+        self.inner.timeout()
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> serialport::Result<()> {
+        self.inner.set_baud_rate(baud_rate)
+    }
+
+    fn set_data_bits(&mut self, data_bits: serialport::DataBits) -> serialport::Result<()> {
+        self.inner.set_data_bits(data_bits)
+    }
+
+    fn set_flow_control(&mut self, flow_control: serialport::FlowControl) -> serialport::Result<()> {
+        self.inner.set_flow_control(flow_control)
+    }
+
+    fn set_parity(&mut self, parity: serialport::Parity) -> serialport::Result<()> {
+        self.inner.set_parity(parity)
+    }
+
+    fn set_stop_bits(&mut self, stop_bits: serialport::StopBits) -> serialport::Result<()> {
+        self.inner.set_stop_bits(stop_bits)
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> serialport::Result<()> {
+        self.inner.set_timeout(timeout)
+    }
+
+    fn write_request_to_send(&mut self, level: bool) -> serialport::Result<()> {
+        self.inner.write_request_to_send(level)
+    }
+
+    fn write_data_terminal_ready(&mut self, level: bool) -> serialport::Result<()> {
+        self.inner.write_data_terminal_ready(level)
+    }
+
+    fn read_clear_to_send(&mut self) -> serialport::Result<bool> {
+        self.inner.read_clear_to_send()
+    }
+
+    fn read_data_set_ready(&mut self) -> serialport::Result<bool> {
+        self.inner.read_data_set_ready()
+    }
+
+    fn read_ring_indicator(&mut self) -> serialport::Result<bool> {
+        self.inner.read_ring_indicator()
+    }
+
+    fn read_carrier_detect(&mut self) -> serialport::Result<bool> {
+        self.inner.read_carrier_detect()
+    }
+
+    fn bytes_to_read(&self) -> serialport::Result<u32> {
+        self.inner.bytes_to_read()
+    }
+
+    fn bytes_to_write(&self) -> serialport::Result<u32> {
+        self.inner.bytes_to_write()
+    }
+
+    fn clear(&self, buffer_to_clear: serialport::ClearBuffer) -> serialport::Result<()> {
+        self.inner.clear(buffer_to_clear)
+    }
+
+    fn try_clone(&self) -> serialport::Result<Box<dyn serialport::SerialPort>> {
+        Ok(Box::new(Faulty::new(self.inner.try_clone()?, Arc::clone(&self.injector))))
+    }
+
+    fn set_break(&self) -> serialport::Result<()> {
+        self.inner.set_break()
+    }
+
+    fn clear_break(&self) -> serialport::Result<()> {
+        self.inner.clear_break()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fault_spec() {
+        let settings = FaultSettings::parse("serial_drop=0.05, gpio_delay=0.1,gpio_delay_ms=300,stepper_error=1,seed=7").unwrap();
+        assert_eq!(settings.serial_drop, 0.05);
+        assert_eq!((settings.gpio_delay, settings.gpio_delay_time), (0.1, Duration::from_millis(300)));
+        assert_eq!((settings.shm_corrupt, settings.stepper_error, settings.seed), (0.0, 1.0, 7));
+        assert_eq!(FaultSettings::parse("").unwrap(), FaultSettings::default());
+        assert!(FaultSettings::parse("serial_drop=1.5").is_err());
+        assert!(FaultSettings::parse("stepper_error").is_err());
+        assert!(FaultSettings::parse("power_cut=0.1").is_err());
+    }
+
+    #[test]
+    fn test_faults_strike_at_their_rate_and_are_counted() {
+        let injector = FaultInjector::new(FaultSettings { stepper_error: 0.25, ..FaultSettings::default() });
+        let failures = (0..4000).filter(|_| injector.stepper_call("rel_move").is_err()).count();
+        assert!((800..1200).contains(&failures), "{}", failures);
+        assert_eq!(injector.injected(Fault::StepperError), failures as u64);
+        // Never strikes at 0
+        assert!((0..1000).all(|_| !injector.strike(Fault::SerialDrop)));
+
+        let mut channel = [(110.0, 1.0), (220.0, 0.5)];
+        injector.scramble(&mut channel);
+        assert_ne!(channel.map(|p| p.1), [1.0, 0.5]);
+        assert_eq!(channel.map(|p| p.0), [110.0, 220.0]);
+
+        // Same seed, same faults
+        let replay = FaultInjector::new(FaultSettings { stepper_error: 0.25, ..FaultSettings::default() });
+        let again = (0..4000).filter(|_| replay.stepper_call("rel_move").is_err()).count();
+        assert_eq!(again, failures);
+    }
+}
@@ -13,19 +13,136 @@ pub type PartialsData = Vec<Vec<(f32, f32)>>;
 // Chucksynth-only: shared constants/types
 pub const DEFAULT_UPDATE_RATE: f32 = 1.0;
 
-/// Read partials from a partials slot without consuming the data (non-destructive clone)
-/// This is the standard pattern used by get_results and should be used by other modules
-/// Returns None if slot is None or lock fails
-/// 
-/// Works with Arc<Mutex<Option<PartialsData>>> type (matches partials_slot::PartialsSlot)
-pub fn read_partials_from_slot(slot: &std::sync::Arc<std::sync::Mutex<Option<PartialsData>>>) -> Option<PartialsData> {
-    if let Ok(slot_guard) = slot.lock() {
-        slot_guard.as_ref().cloned()
-    } else {
-        None
+/// Byte order of the (f32 freq, f32 amp) pairs in the partials file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    Little,
+    Big,
+}
+
+impl ByteOrder {
+    pub const NATIVE: ByteOrder = if cfg!(target_endian = "big") { ByteOrder::Big } else { ByteOrder::Little };
+
+    /// `le` / `be` as written in the control file
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "le" | "little" => Some(ByteOrder::Little),
+            "be" | "big" => Some(ByteOrder::Big),
+            _ => None,
+        }
+    }
+
+    pub fn f32_from(self, bytes: [u8; 4]) -> f32 {
+        match self {
+            ByteOrder::Little => f32::from_le_bytes(bytes),
+            ByteOrder::Big => f32::from_be_bytes(bytes),
+        }
+    }
+
+    fn u32_from(self, bytes: [u8; 4]) -> u32 {
+        match self {
+            ByteOrder::Little => u32::from_le_bytes(bytes),
+            ByteOrder::Big => u32::from_be_bytes(bytes),
+        }
     }
 }
 
+/// First word of a partials file that starts with a header, in the writer's byte order
+pub const PARTIALS_MAGIC: u32 = 0x5344_5046; // "SDPF"
+/// Header: PARTIALS_MAGIC, then the format version (u32, same byte order)
+pub const PARTIALS_HEADER_SIZE: usize = 8;
+
+/// Byte order of a partials file and where its data starts. Writers that declare
+/// `byte_order=` in the control file also start the file with PARTIALS_MAGIC, which
+/// reads back correctly in exactly one byte order; older writers have no header and
+/// wrote in the reader's native order. An error means the file does not match what
+/// the control file declares (e.g. a stale file from another writer), so its
+/// frequencies would be nonsense.
+pub fn partials_format(data: &[u8], control: Option<&AudioControl>) -> Result<(ByteOrder, usize), String> {
+    let declared = control.and_then(|c| c.byte_order);
+    let magic: Option<[u8; 4]> = data.get(..4).and_then(|b| b.try_into().ok());
+    let found = magic.and_then(|m| [ByteOrder::Little, ByteOrder::Big].into_iter().find(|order| order.u32_from(m) == PARTIALS_MAGIC));
+    match (found, declared) {
+        (Some(found), Some(declared)) if found != declared => {
+            Err(format!("partials header is {:?}-endian but the control file declares {:?}", found, declared))
+        }
+        (Some(found), _) => Ok((found, PARTIALS_HEADER_SIZE)),
+        (None, Some(_)) => Err(format!("partials file lacks the header magic {:#010x} the control file promises", PARTIALS_MAGIC)),
+        (None, None) => Ok((ByteOrder::NATIVE, 0)),
+    }
+}
+
+/// What audio_monitor's control file (audio_control next to the partials in shared
+/// memory) says about the frame. The first lines are positional: PID, channels,
+/// partials per channel and, optionally, a frame sequence. Newer audio_monitor builds
+/// follow them with `key=value` lines: `sample_rate=48000`, one
+/// `label.<channel>=<JACK port>` per channel, so a routing change in audmon shows up
+/// here, and `byte_order=le|be` for a partials file with a header (see partials_format).
+/// Older control files carry the counts only; the rest is then None.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AudioControl {
+    pub pid: Option<u32>,
+    pub channels: usize,
+    pub partials_per_channel: usize,
+    pub frame_sequence: Option<u64>,
+    pub sample_rate: Option<u32>,
+    pub labels: Vec<Option<String>>, // Per channel; None where audmon gave no label
+    pub byte_order: Option<ByteOrder>,
+}
+
+impl AudioControl {
+    /// None unless the channel and partial counts are there
+    pub fn parse(content: &str) -> Option<Self> {
+        let lines: Vec<&str> = content.trim().lines().map(str::trim).collect();
+        if lines.len() < 3 {
+            return None;
+        }
+        let mut control = AudioControl {
+            pid: lines[0].parse().ok(),
+            channels: lines[1].parse().ok()?,
+            partials_per_channel: lines[2].parse().ok()?,
+            frame_sequence: lines.get(3).and_then(|l| l.parse().ok()),
+            ..Default::default()
+        };
+        for (key, value) in lines[3..].iter().filter_map(|l| l.split_once('=')) {
+            let (key, value) = (key.trim(), value.trim());
+            if key == "sample_rate" {
+                control.sample_rate = value.parse().ok();
+            } else if key == "byte_order" {
+                control.byte_order = ByteOrder::parse(value);
+            } else if let Some(ch) = key.strip_prefix("label.").and_then(|ch| ch.parse::<usize>().ok()) {
+                if ch >= control.labels.len() {
+                    control.labels.resize(ch + 1, None);
+                }
+                control.labels[ch] = (!value.is_empty()).then(|| value.to_string());
+            }
+        }
+        Some(control)
+    }
+
+    /// audmon's label for a channel (its JACK port), if it sent one
+    pub fn label(&self, ch: usize) -> Option<&str> {
+        self.labels.get(ch).and_then(|l| l.as_deref())
+    }
+}
+
+/// The control file's path: /dev/shm/audio_control on Linux, else /tmp/audio_control
+pub fn control_file_path() -> String {
+    let shm_dir = if cfg!(target_os = "linux") { "/dev/shm" } else { "/tmp" };
+    format!("{}/audio_control", shm_dir)
+}
+
+/// The current control file, None while audio_monitor has not written one
+pub fn read_audio_control() -> Option<AudioControl> {
+    AudioControl::parse(&std::fs::read_to_string(control_file_path()).ok()?)
+}
+
+/// Copy of the newest partials frame if one arrived since the last call
+/// The frame itself stays in the triple buffer; only the synth's owned copy is made here
+pub fn take_new_partials(reader: &mut crate::partials_buffer::PartialsReader) -> Option<PartialsData> {
+    reader.take_new().map(|frame| frame.to_vec())
+}
+
 #[derive(Clone)]
 pub struct ResynthConfig {
     pub gain: f32,
@@ -72,7 +189,7 @@ pub fn start_update_thread_with_sender(
     config: Arc<Mutex<ResynthConfig>>,
     shutdown_flag: Arc<AtomicBool>,
     update_sender: mpsc::Sender<SynthUpdate>,
-    partials_slot: std::sync::Arc<std::sync::Mutex<Option<PartialsData>>>,
+    mut partials_reader: crate::partials_buffer::PartialsReader,
     gui_param_rx: mpsc::Receiver<GuiParameter>,
 ) {
     debug!(target: "get_results", "Starting update thread (event-driven) for FFT data retrieval.");
@@ -140,17 +257,14 @@ pub fn start_update_thread_with_sender(
                 }
             }
 
-            // 2. Check for new partials from the shared slot
+            // 2. Check for new partials from the triple buffer
             let mut new_partials_received_this_cycle = false;
-            if let Some(partials_data) = {
-                let mut slot = partials_slot.lock().unwrap();
-                slot.take() // Take the latest partials, leaving None (destructive for this use case)
-            } {
+            if let Some(partials_data) = take_new_partials(&mut partials_reader) {
                 latest_known_partials = Some(partials_data);
                 new_partials_received_this_cycle = true;
             }
             if new_partials_received_this_cycle {
-                debug!(target: "get_results", "Event: New partials received from slot and updated locally.");
+                debug!(target: "get_results", "Event: New partials received from the triple buffer and updated locally.");
                 immediate_send_triggered_this_cycle = true;
             }
 
@@ -229,4 +343,58 @@ pub fn start_update_thread_with_sender(
         }
         debug!(target: "get_results", "Update thread (event-driven) exiting after {} updates.", update_count);
     });
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_control_file_metadata_with_fallback() {
+        let control = AudioControl::parse("4242\n3\n12\n977\nsample_rate=48000\nlabel.0=system:capture_1\nlabel.2=system:capture_5\n").unwrap();
+        assert_eq!((control.pid, control.channels, control.partials_per_channel), (Some(4242), 3, 12));
+        assert_eq!(control.frame_sequence, Some(977));
+        assert_eq!(control.sample_rate, Some(48000));
+        assert_eq!(control.label(0), Some("system:capture_1"));
+        assert_eq!(control.label(1), None);
+        assert_eq!(control.label(2), Some("system:capture_5"));
+
+        // Older audio_monitor: counts only, no sequence
+        let old = AudioControl::parse("4242\n2\n12").unwrap();
+        assert_eq!((old.channels, old.frame_sequence, old.sample_rate), (2, None, None));
+        assert_eq!(old.label(0), None);
+        // Metadata without a sequence line is not mistaken for one
+        assert_eq!(AudioControl::parse("1\n2\n12\nsample_rate=44100").unwrap().frame_sequence, None);
+        assert!(AudioControl::parse("4242\n2").is_none());
+    }
+
+    #[test]
+    fn test_partials_byte_order_from_header() {
+        let with_header = |order: ByteOrder, freq: f32| {
+            let mut bytes = Vec::new();
+            let (magic, version, freq) = match order {
+                ByteOrder::Little => (PARTIALS_MAGIC.to_le_bytes(), 1u32.to_le_bytes(), freq.to_le_bytes()),
+                ByteOrder::Big => (PARTIALS_MAGIC.to_be_bytes(), 1u32.to_be_bytes(), freq.to_be_bytes()),
+            };
+            bytes.extend(magic);
+            bytes.extend(version);
+            bytes.extend(freq);
+            bytes
+        };
+        let declared = |order| AudioControl { byte_order: Some(order), ..Default::default() };
+
+        // A big-endian writer (or one declaring be) is decoded as such on any reader
+        let big = with_header(ByteOrder::Big, 440.0);
+        assert_eq!(partials_format(&big, Some(&declared(ByteOrder::Big))), Ok((ByteOrder::Big, PARTIALS_HEADER_SIZE)));
+        assert_eq!(ByteOrder::Big.f32_from(big[8..12].try_into().unwrap()), 440.0);
+        let little = with_header(ByteOrder::Little, 440.0);
+        assert_eq!(partials_format(&little, None), Ok((ByteOrder::Little, PARTIALS_HEADER_SIZE)));
+
+        // Mismatches are reported, not decoded
+        assert!(partials_format(&little, Some(&declared(ByteOrder::Big))).is_err());
+        assert!(partials_format(&440.0f32.to_le_bytes(), Some(&declared(ByteOrder::Little))).is_err());
+        // Legacy writer: no header, no declaration
+        assert_eq!(partials_format(&440.0f32.to_ne_bytes(), None), Ok((ByteOrder::NATIVE, 0)));
+        assert_eq!(AudioControl::parse("1\n2\n12\nbyte_order=be").unwrap().byte_order, Some(ByteOrder::Big));
+    }
+}
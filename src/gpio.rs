@@ -6,10 +6,12 @@
 /// Single source of truth: all configuration comes from string_driver.yaml
 /// via config_loader::load_gpio_settings() - no hardcoded fallbacks.
 
-use anyhow::{anyhow, Result};
+use crate::error::{Error, Result};
 use gethostname::gethostname;
-use crate::config_loader::{GpioSettings, GpioComponents};
+use crate::config_loader::{GpioSettings, GpioComponents, GpioLineConfig};
 use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "gpiod")]
 use gpiocdev::chip::Chip;
@@ -18,6 +20,54 @@ use gpiocdev::line::{Bias, Value};
 #[cfg(feature = "gpiod")]
 use gpiocdev::request::Request;
 
+#[cfg(feature = "gpiod")]
+impl From<gpiocdev::Error> for Error {
+    fn from(e: gpiocdev::Error) -> Self {
+        Error::GpioUnavailable(format!("GPIO line access failed: {}", e))
+    }
+}
+
+/// How often a changing line is re-read while it is being debounced
+const DEBOUNCE_SAMPLE: Duration = Duration::from_millis(1);
+
+/// One input line as the GPIO test panel shows it
+#[derive(Debug, Clone)]
+pub struct LineState {
+    pub role: String,
+    pub pin: u32,
+    pub config: GpioLineConfig,
+    pub raw_high: Option<bool>, // Physical level, None when it cannot be read
+    pub active: bool,           // Debounced and with polarity applied
+}
+
+/// Whether a line at `level_high` counts as pressed/touching
+fn is_active(level_high: bool, config: &GpioLineConfig) -> bool {
+    level_high != config.active_low
+}
+
+/// Debounce one read: `level` is accepted when it matches `previous` or every
+/// `sample` over `debounce` agrees with it; otherwise the previous level stands
+/// (`idle` before there is one).
+fn debounce_level(
+    level: bool,
+    previous: Option<bool>,
+    idle: bool,
+    debounce: Duration,
+    mut sample: impl FnMut() -> Result<bool>,
+) -> Result<bool> {
+    if debounce.is_zero() || previous == Some(level) {
+        return Ok(level);
+    }
+    let deadline = Instant::now() + debounce;
+    while Instant::now() < deadline {
+        std::thread::sleep(DEBOUNCE_SAMPLE);
+        if sample()? != level {
+            return Ok(previous.unwrap_or(idle));
+        }
+    }
+    Ok(level)
+}
+
 /// GPIO Board controller
 #[derive(Debug)]
 pub struct GpioBoard {
@@ -30,11 +80,19 @@ pub struct GpioBoard {
     pub x_home_line: Option<u32>,
     pub x_away_line: Option<u32>,
     pub x_limit_button: Option<u32>,
+    pub axis_lines: Vec<(String, Option<u32>, Option<u32>)>, // AXES (name, home, away) lines
+    pub damper_lines: Vec<u32>, // DAMPERS relay outputs, one per string
+    #[cfg(feature = "gpiod")]
+    damper_active_low: bool, // Relays engage when their line is driven low
     
     // Individual line requests (for gpiod)
     #[cfg(feature = "gpiod")]
     line_requests: HashMap<u32, Request>,
     
+    // Polarity, debounce and pull per line (GPIO_LINES) and the last debounced level
+    line_configs: HashMap<u32, GpioLineConfig>,
+    stable_levels: Mutex<HashMap<u32, bool>>,
+    
     // Encoder tracking (software-based since we don't have hardware encoder support yet)
     encoder_steps: i32,
     
@@ -62,25 +120,31 @@ impl GpioBoard {
             // GPIO is enabled - require library (fail-fast per rules)
             // GPIO_MAX_STEPS is optional - only needed if X-axis stepper hardware is present
             let library = settings.library.ok_or_else(|| {
-                anyhow!("GPIO_ENABLED is true but GPIO_LIBRARY is missing for hostname '{}'", hostname)
+                Error::ConfigMissing(format!("GPIO_ENABLED is true but GPIO_LIBRARY is missing for hostname '{}'", hostname))
             })?;
             
             let max_steps = settings.max_steps; // Optional - only needed for X-axis stepper
             
             let components = settings.components.ok_or_else(|| {
-                anyhow!("GPIO_ENABLED is true but GPIO_COMPONENTS is missing for hostname '{}'", hostname)
+                Error::ConfigMissing(format!("GPIO_ENABLED is true but GPIO_COMPONENTS is missing for hostname '{}'", hostname))
             })?;
             
             // Only support gpiod in Rust (gpiozero is Python-specific)
             if library != "gpiod" {
-                return Err(anyhow!(
+                return Err(Error::ConfigInvalid(format!(
                     "GPIO_LIBRARY '{}' is not supported in Rust. Only 'gpiod' is supported.",
                     library
-                ));
+                )));
             }
             
+            // Resolve GPIO_LINES for every pin in use
+            let line_configs = Self::component_pins(&components)
+                .into_iter()
+                .map(|pin| (pin, settings.lines.get(&pin).copied().unwrap_or(settings.line_default)))
+                .collect();
+            
             // Initialize gpiod components
-            Self::init_gpiod(components, max_steps)
+            Self::init_gpiod(components, max_steps, line_configs)
         } else {
             // GPIO not enabled for this host
             Ok(Self::disabled())
@@ -97,8 +161,14 @@ impl GpioBoard {
             x_home_line: None,
             x_away_line: None,
             x_limit_button: None,
+            axis_lines: Vec::new(),
+            damper_lines: Vec::new(),
+            #[cfg(feature = "gpiod")]
+            damper_active_low: false,
             #[cfg(feature = "gpiod")]
             line_requests: HashMap::new(),
+            line_configs: HashMap::new(),
+            stable_levels: Mutex::new(HashMap::new()),
             encoder_steps: 0,
             distance_sensor_enabled: false,
             last_good_distance: 0,
@@ -108,8 +178,9 @@ impl GpioBoard {
     
     /// Initialize GPIO components using libgpiod
     #[cfg(feature = "gpiod")]
-    fn init_gpiod(components: GpioComponents, max_steps: Option<u32>) -> Result<Self> {
-        use gpiocdev::line::{Bias, Value};
+    fn init_gpiod(components: GpioComponents, max_steps: Option<u32>, line_configs: HashMap<u32, GpioLineConfig>) -> Result<Self> {
+        use crate::config_loader::GpioPull;
+        use gpiocdev::line::Bias;
         use gpiocdev::request::Request;
         use std::collections::HashMap;
         
@@ -156,21 +227,45 @@ impl GpioBoard {
             (x_home_line, x_away_line, None)
         };
         
+        // AXES limit switches
+        for pin in components.axis_pins.iter().flat_map(|(_, home, away)| [*home, *away]).flatten() {
+            if !all_pins.contains(&pin) {
+                all_pins.push(pin);
+            }
+        }
+        
         // Request each line individually using the correct gpiocdev API
         let mut line_requests = HashMap::new();
         
         for offset in &all_pins {
+            let bias = match line_configs.get(offset).copied().unwrap_or_default().pull {
+                GpioPull::Up => Bias::PullUp,
+                GpioPull::Down => Bias::PullDown,
+                GpioPull::Off => Bias::Disabled,
+            };
             let request = Request::builder()
                 .on_chip(&chip_path)
                 .with_consumer("StringDriver")
                 .with_line(*offset)
                 .as_input()
-                .with_bias(Bias::PullUp)
+                .with_bias(bias)
                 .request()?;
             
             line_requests.insert(*offset, request);
         }
         
+        // DAMPERS relays are outputs, released until a damper is engaged
+        let released = if components.damper_active_low { Value::Active } else { Value::Inactive };
+        for offset in &components.damper_pins {
+            let request = Request::builder()
+                .on_chip(&chip_path)
+                .with_consumer("StringDriver")
+                .with_line(*offset)
+                .as_output(released)
+                .request()?;
+            line_requests.insert(*offset, request);
+        }
+        
         // Note: Encoder and distance sensor require additional hardware support
         // that would need to be implemented separately (not available in basic gpiod)
         let distance_sensor_enabled = components.distance_sensor_pins.is_some();
@@ -183,7 +278,12 @@ impl GpioBoard {
             x_home_line,
             x_away_line,
             x_limit_button,
+            axis_lines: components.axis_pins.clone(),
+            damper_lines: components.damper_pins.clone(),
+            damper_active_low: components.damper_active_low,
             line_requests,
+            line_configs,
+            stable_levels: Mutex::new(HashMap::new()),
             encoder_steps: 0,
             distance_sensor_enabled,
             last_good_distance: 0,
@@ -192,8 +292,8 @@ impl GpioBoard {
     }
     
     #[cfg(not(feature = "gpiod"))]
-    fn init_gpiod(_components: GpioComponents, _max_steps: Option<u32>) -> Result<Self> {
-        Err(anyhow!("GPIO support not compiled in. Enable 'gpiod' feature."))
+    fn init_gpiod(_components: GpioComponents, _max_steps: Option<u32>, _line_configs: HashMap<u32, GpioLineConfig>) -> Result<Self> {
+        Err(Error::GpioUnavailable("GPIO support not compiled in. Enable 'gpiod' feature.".to_string()))
     }
     
     /// Find a gpiochip that exposes all required pins
@@ -202,25 +302,10 @@ impl GpioBoard {
         {
             use std::fs;
             
-            let required_pins: Vec<u32> = {
-                let mut pins = Vec::new();
-                if let Some(ref z_pins) = components.z_touch_pins {
-                    pins.extend(z_pins);
-                }
-                if let Some(pin) = components.x_home_pin {
-                    pins.push(pin);
-                }
-                if let Some(pin) = components.x_away_pin {
-                    pins.push(pin);
-                }
-                if let Some(pin) = components.x_limit_pin {
-                    pins.push(pin);
-                }
-                pins
-            };
+            let required_pins = Self::component_pins(components);
             
             // Search for gpiochip devices
-            let mut chip_paths: Vec<String> = fs::read_dir("/dev")?
+            let mut chip_paths: Vec<String> = fs::read_dir("/dev").map_err(|e| Error::io("Cannot list /dev", e))?
                 .filter_map(|entry| {
                     let entry = entry.ok()?;
                     let path = entry.path();
@@ -257,15 +342,102 @@ impl GpioBoard {
                 return Ok(first_chip.clone());
             }
             
-            Err(anyhow!("No usable gpiochip device found"))
+            Err(Error::GpioUnavailable("No usable gpiochip device found".to_string()))
         }
         
         #[cfg(not(feature = "gpiod"))]
         {
-            Err(anyhow!("GPIO support not compiled in"))
+            Err(Error::GpioUnavailable("GPIO support not compiled in".to_string()))
         }
     }
     
+    /// Input pins the components use (Z touch, X home, X away, X limit, AXES limits)
+    fn component_pins(components: &GpioComponents) -> Vec<u32> {
+        let mut pins = Vec::new();
+        if let Some(ref z_pins) = components.z_touch_pins {
+            pins.extend(z_pins);
+        }
+        if let Some(pin) = components.x_home_pin {
+            pins.push(pin);
+        }
+        if let Some(pin) = components.x_away_pin {
+            pins.push(pin);
+        }
+        if let Some(pin) = components.x_limit_pin {
+            pins.push(pin);
+        }
+        pins.extend(components.axis_pins.iter().flat_map(|(_, home, away)| [*home, *away]).flatten());
+        pins.extend(&components.damper_pins);
+        pins
+    }
+    
+    fn line_config(&self, pin: u32) -> GpioLineConfig {
+        self.line_configs.get(&pin).copied().unwrap_or_default()
+    }
+    
+    /// Physical level of a line (true = high), None if the line was not requested
+    #[cfg(feature = "gpiod")]
+    fn read_level(&self, pin: u32) -> Result<Option<bool>> {
+        match self.line_requests.get(&pin) {
+            Some(request) => Ok(Some(request.value(pin)? == Value::Active)),
+            None => Ok(None),
+        }
+    }
+    
+    #[cfg(not(feature = "gpiod"))]
+    fn read_level(&self, _pin: u32) -> Result<Option<bool>> {
+        Ok(None)
+    }
+    
+    /// Debounced state of a line with its polarity applied (false if not requested)
+    fn line_active(&self, pin: u32) -> Result<bool> {
+        #[cfg(feature = "fault-injection")]
+        if let Some(faults) = crate::fault_injection::active() {
+            faults.gpio_read();
+        }
+        let Some(level) = self.read_level(pin)? else {
+            return Ok(false);
+        };
+        let config = self.line_config(pin);
+        let previous = self.stable_levels.lock().ok().and_then(|levels| levels.get(&pin).copied());
+        let idle = config.active_low; // Inactive level: high for active-low lines
+        let level = debounce_level(level, previous, idle, config.debounce, || {
+            Ok(self.read_level(pin)?.unwrap_or(level))
+        })?;
+        if let Ok(mut levels) = self.stable_levels.lock() {
+            levels.insert(pin, level);
+        }
+        Ok(is_active(level, &config))
+    }
+    
+    /// Raw and debounced state of every input line, for the GPIO test panel
+    pub fn line_states(&self) -> Vec<LineState> {
+        let mut lines: Vec<(String, u32)> = Vec::new();
+        for (i, pin) in self.z_touch_lines.iter().flatten().enumerate() {
+            lines.push((format!("Z touch {}", i), *pin));
+        }
+        if self.x_limit_button.is_some() {
+            lines.extend(self.x_limit_button.map(|pin| ("X limit".to_string(), pin)));
+        } else {
+            lines.extend(self.x_home_line.map(|pin| ("X home".to_string(), pin)));
+            lines.extend(self.x_away_line.map(|pin| ("X away".to_string(), pin)));
+        }
+        for (name, home, away) in &self.axis_lines {
+            lines.extend(home.map(|pin| (format!("{} home", name), pin)));
+            lines.extend(away.map(|pin| (format!("{} away", name), pin)));
+        }
+        lines
+            .into_iter()
+            .map(|(role, pin)| LineState {
+                role,
+                pin,
+                config: self.line_config(pin),
+                raw_high: self.read_level(pin).ok().flatten(),
+                active: self.line_active(pin).unwrap_or(false),
+            })
+            .collect()
+    }
+    
     /// Check the state of Z-touch sensors
     /// Returns array of bools if button_index is None, single bool if button_index is Some
     pub fn press_check(&self, button_index: Option<usize>) -> Result<Vec<bool>> {
@@ -282,26 +454,15 @@ impl GpioBoard {
                 if let Some(idx) = button_index {
                     if idx < z_pins.len() {
                         let pin = z_pins[idx];
-                        if let Some(request) = self.line_requests.get(&pin) {
-                            // Touch is TRUE when line is LOW (INACTIVE) - pulled up, active low
-                            let value = request.value(pin)?;
-                            results.push(value == Value::Inactive);
-                        } else {
-                            results.push(false);
-                        }
+                        // Touch polarity and debounce come from GPIO_LINES (default: pulled up, active low)
+                        results.push(self.line_active(pin)?);
                     } else {
                         results.push(false);
                     }
                 } else {
                     // Return all Z-touch states
                     for pin in z_pins {
-                        if let Some(request) = self.line_requests.get(pin) {
-                            let value = request.value(*pin)?;
-                            let is_touching = value == Value::Inactive;
-                            results.push(is_touching);
-                        } else {
-                            results.push(false);
-                        }
+                        results.push(self.line_active(*pin)?);
                     }
                 }
                 
@@ -326,11 +487,8 @@ impl GpioBoard {
         #[cfg(feature = "gpiod")]
         {
             if let Some(pin) = self.x_home_line {
-                if let Some(request) = self.line_requests.get(&pin) {
-                    let value = request.value(pin)?;
-                    // Active low: pressed when line is LOW (0)
-                    return Ok(value == Value::Inactive);
-                }
+                // Pressed per the line's GPIO_LINES polarity (default active low)
+                return self.line_active(pin);
             }
         }
         
@@ -346,17 +504,65 @@ impl GpioBoard {
         #[cfg(feature = "gpiod")]
         {
             if let Some(pin) = self.x_away_line {
-                if let Some(request) = self.line_requests.get(&pin) {
-                    let value = request.value(pin)?;
-                    // Active low: pressed when line is LOW (0)
-                    return Ok(value == Value::Inactive);
-                }
+                // Pressed per the line's GPIO_LINES polarity (default active low)
+                return self.line_active(pin);
             }
         }
         
         Ok(false)
     }
     
+    /// Engage (true) or release string `string_idx`'s damper relay
+    pub fn set_damper(&self, string_idx: usize, engaged: bool) -> Result<()> {
+        let Some(&pin) = self.damper_lines.get(string_idx).filter(|_| self.exist) else {
+            return Err(Error::GpioUnavailable(format!("No damper relay line for string {}", string_idx)));
+        };
+        #[cfg(feature = "gpiod")]
+        {
+            let high = engaged != self.damper_active_low;
+            let request = self.line_requests.get(&pin)
+                .ok_or_else(|| Error::GpioUnavailable(format!("Damper line {} was not requested", pin)))?;
+            request.set_value(pin, if high { Value::Active } else { Value::Inactive })?;
+            Ok(())
+        }
+        #[cfg(not(feature = "gpiod"))]
+        {
+            let _ = (pin, engaged);
+            Err(Error::GpioUnavailable("GPIO support not compiled in. Enable 'gpiod' feature.".to_string()))
+        }
+    }
+    
+    /// Home (true) or away limit line of a linear axis: X's from GPIO_COMPONENTS, the rest from AXES
+    fn axis_limit_line(&self, axis: &str, home: bool) -> Option<u32> {
+        if axis == "X" {
+            return if home { self.x_home_line } else { self.x_away_line };
+        }
+        self.axis_lines
+            .iter()
+            .find(|(name, _, _)| name == axis)
+            .and_then(|(_, home_line, away_line)| if home { *home_line } else { *away_line })
+    }
+    
+    pub fn has_home_switch(&self, axis: &str) -> bool {
+        self.axis_limit_line(axis, true).is_some()
+    }
+    
+    /// Check a linear axis's home limit switch
+    pub fn home_check(&self, axis: &str) -> Result<bool> {
+        match self.axis_limit_line(axis, true) {
+            Some(pin) if self.exist => self.line_active(pin),
+            _ => Ok(false),
+        }
+    }
+    
+    /// Check a linear axis's away limit switch
+    pub fn away_check(&self, axis: &str) -> Result<bool> {
+        match self.axis_limit_line(axis, false) {
+            Some(pin) if self.exist => self.line_active(pin),
+            _ => Ok(false),
+        }
+    }
+    
     /// Get encoder step count (software tracking)
     /// Note: Real hardware encoder would require additional implementation
     pub fn get_encoder_steps(&self) -> i32 {
@@ -413,4 +619,22 @@ mod tests {
         let gpio = GpioBoard::disabled();
         assert!(!gpio.exist);
     }
+    
+    #[test]
+    fn test_line_polarity_and_debounce() {
+        let active_high = GpioLineConfig { active_low: false, ..GpioLineConfig::default() };
+        assert!(is_active(false, &GpioLineConfig::default()));
+        assert!(is_active(true, &active_high));
+        
+        let debounce = Duration::from_millis(5);
+        // A level that holds through the window is accepted
+        assert!(debounce_level(true, Some(false), true, debounce, || Ok(true)).unwrap());
+        // A bounce keeps the previous level, or the idle level before there is one
+        let mut bounces = [true, false].into_iter().cycle();
+        assert!(!debounce_level(true, Some(false), true, debounce, || Ok(bounces.next().unwrap())).unwrap());
+        assert!(debounce_level(false, None, true, debounce, || Ok(true)).unwrap());
+        // Unchanged levels and zero debounce are not re-sampled
+        assert!(debounce_level(true, None, false, Duration::ZERO, || panic!("sampled")).unwrap());
+        assert!(debounce_level(true, Some(true), false, debounce, || panic!("sampled")).unwrap());
+    }
 }
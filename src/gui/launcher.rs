@@ -1,286 +1,281 @@
 /// Launcher for String Driver application
 /// 
-/// Two modes:
-/// 1. Master GUI mode (default): Launches master_gui via master_gui.sh
-///    - master_gui embeds audmon, stepper_gui, and operations_gui
-///    - Single unified interface
+/// Starts the components listed under LAUNCH in string_driver.yaml for this host,
+/// in order: builds stale release binaries, spawns each command, waits for its
+/// readiness check (socket, file or TCP port), then supervises components that
+/// have a restart policy.
 /// 
-/// 2. Separate mode (--separate flag): Launches components separately
-///    - audio_monitor (audmon) via audmon.sh
-///    - Waits for shared memory to have results
-///    - stepper_gui
-///    - operations_gui
+/// Without a LAUNCH section the built-in lists are used:
+/// 1. Master GUI mode (default): master_gui via master_gui.sh
+///    - master_gui embeds audmon, stepper_gui, and operations_gui
+/// 2. Separate mode (--separate flag): audio_monitor (audmon.sh), then
+///    stepper_gui, then operations_gui
 /// 
 /// Run with: 
 ///   cargo run --bin launcher --release              # Master GUI mode
 ///   cargo run --bin launcher --release -- --separate  # Separate mode
+/// 
+/// Stopping (see src/shutdown.rs; kill_all.sh runs --stop):
+///   launcher --stop [NAME...]      # SIGTERM, then SIGKILL after 2 s; every component without names
+///   launcher --restart NAME...     # stop the named components and start them again
+///   add --dry-run to list what would be stopped, --kill to skip the grace period
 
-use std::process::{Command, Stdio};
+use stringdriver::{config_loader, health, logging, readiness, shutdown};
+
+use std::process::{Child, Command, Stdio};
 use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use gethostname::gethostname;
 use serde_yaml;
+use config_loader::{LaunchComponent, ReadinessCheck, RestartPolicy};
+
+/// Time a component gets before an immediate exit counts as a startup failure
+const STARTUP_CHECK: Duration = Duration::from_millis(500);
+/// Pause before restarting a component that exited
+const RESTART_DELAY: Duration = Duration::from_secs(2);
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     let separate_mode = args.iter().any(|a| a == "--separate");
+    let stop = args.iter().any(|a| a == "--stop");
+    let restart = args.iter().any(|a| a == "--restart");
     
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("String Driver Launcher");
-    if separate_mode {
-        println!("Mode: Separate components");
-    } else {
-        println!("Mode: Master GUI (unified)");
-    }
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
     
-    if separate_mode {
-        launch_separate_mode()
-    } else {
-        launch_master_gui_mode()
-    }
-}
-
-fn launch_master_gui_mode() {
     // Get project root directory
     let project_root = match env::var("CARGO_MANIFEST_DIR") {
-        Ok(dir) => std::path::PathBuf::from(dir),
+        Ok(dir) => PathBuf::from(dir),
         Err(_) => {
             eprintln!("ERROR: Could not determine project root");
             std::process::exit(1);
         }
     };
     
-    let release_dir = project_root.join("target/release");
-    
-    // Check if GPIO is enabled for this host from YAML
-    let gpio_enabled = check_gpio_enabled(&project_root);
-    println!("GPIO enabled for this host: {}", gpio_enabled);
-    
-    // Check if master_gui binary needs rebuilding
-    let master_gui_binary = release_dir.join("master_gui");
-    let needs_build = check_binary_needs_build(&project_root, &master_gui_binary);
-    
-    if needs_build {
-        println!("\nBuilding master_gui release binary...");
-        println!("  (Cargo build output will appear below)\n");
-        std::io::stdout().flush().ok();
-        
-        let mut build_args = vec!["build", "--release", "--bin", "master_gui"];
-        if gpio_enabled {
-            build_args.push("--features");
-            build_args.push("gpiod");
-        }
-        
-        let build_status = Command::new("cargo")
-            .args(&build_args)
-            .current_dir(&project_root)
-            .stdout(Stdio::inherit())
-            .stderr(Stdio::inherit())
-            .status();
-        
-        match build_status {
-            Ok(status) if status.success() => {
-                println!("\n✓ master_gui release binary built successfully");
-            }
-            Ok(status) => {
-                eprintln!("\n✗ Build failed with exit code: {:?}", status.code());
-                std::process::exit(1);
-            }
-            Err(e) => {
-                eprintln!("\n✗ Failed to run cargo build: {}", e);
-                std::process::exit(1);
-            }
-        }
-    } else {
-        println!("\n✓ master_gui release binary is up-to-date");
-    }
-    
-    // Launch master_gui via master_gui.sh script (maintains persistence)
-    println!("\nLaunching master_gui via master_gui.sh...");
-    let master_gui_script = project_root.join("master_gui.sh");
-    if !master_gui_script.exists() {
-        eprintln!("✗ master_gui.sh not found at {}", master_gui_script.display());
+    let hostname = gethostname().to_string_lossy().to_string();
+    if let Err(e) = config_loader::load_host_section(&hostname) {
+        eprintln!("ERROR: {}", e);
+        eprintln!("This looks like a new instrument. Set it up with: cargo run --bin setup_wizard");
         std::process::exit(1);
     }
-    
-    match Command::new("bash")
-        .arg(&master_gui_script)
-        .current_dir(&project_root)
-        .spawn() {
+    if stop || restart {
+        let mode = if args.iter().any(|a| a == "--dry-run") {
+            shutdown::Mode::DryRun
+        } else if args.iter().any(|a| a == "--kill") {
+            shutdown::Mode::Kill
+        } else {
+            shutdown::Mode::Graceful(shutdown::DEFAULT_GRACE)
+        };
+        let names: Vec<String> = args.iter().skip(1).filter(|a| !a.starts_with("--")).cloned().collect();
+        std::process::exit(stop_components(&project_root, &hostname, &names, restart, mode));
+    }
+    let components = match config_loader::load_launch_settings(&hostname) {
+        Ok(list) if !list.is_empty() => {
+            println!("Mode: LAUNCH list from string_driver.yaml ({} components)", list.len());
+            if separate_mode {
+                println!("  (--separate ignored: LAUNCH section takes precedence)");
+            }
+            list
+        }
         Ok(_) => {
-            println!("✓ master_gui launched via master_gui.sh");
+            if separate_mode {
+                println!("Mode: Separate components");
+            } else {
+                println!("Mode: Master GUI (unified)");
+            }
+            config_loader::default_launch_components(separate_mode, &project_root)
         }
         Err(e) => {
-            eprintln!("✗ Failed to launch master_gui.sh: {}", e);
-            std::process::exit(1);
-        }
-    }
-    
-    // Wait for master_gui to be ready (check status file)
-    println!("\nWaiting for master_gui to initialize...");
-    let status_file = project_root.join(".master_gui_status");
-    let ready = wait_for_master_gui_ready(&status_file);
-    if !ready {
-        eprintln!("⚠ Warning: Timeout waiting for master_gui to be ready");
-        eprintln!("  master_gui may still be starting up");
-        eprintln!("  Status file: {}", status_file.display());
-    } else {
-        println!("✓ master_gui is ready");
-    }
-    
-    println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("Master GUI launched!");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("\nLauncher exiting (master_gui will continue running)");
-}
-
-fn launch_separate_mode() {
-    // Get project root directory
-    let project_root = match env::var("CARGO_MANIFEST_DIR") {
-        Ok(dir) => std::path::PathBuf::from(dir),
-        Err(_) => {
-            eprintln!("ERROR: Could not determine project root");
+            eprintln!("ERROR: Invalid LAUNCH section for host '{}': {}", hostname, e);
             std::process::exit(1);
         }
     };
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
     
-    let release_dir = project_root.join("target/release");
+    // Launch history goes to today's launcher log (LOGGING block in string_driver.yaml)
+    let mut launch_log = logging::open_log_file("launcher");
+    record_launch(&mut launch_log, &format!(
+        "Launcher started: {}",
+        components.iter().map(|c| c.name.as_str()).collect::<Vec<_>>().join(", ")
+    ));
     
-    // Launch audmon via audmon.sh script (maintains persistence for JACK audio)
-    println!("Launching audio_monitor (audmon) via audmon.sh...");
-    let audmon_path = project_root.parent()
-        .map(|p| p.join("audmon"))
-        .unwrap_or_else(|| std::path::PathBuf::from("../audmon"));
+    build_components(&project_root, &components);
     
-    // Clone audmon if local clone doesn't exist
-    if !audmon_path.exists() {
-        println!("Local audmon clone not found, cloning repository...");
-        let parent_dir = project_root.parent().unwrap_or(&project_root);
-        let clone_status = Command::new("git")
-            .args(&["clone", "git@github.com:gwild/audmon.git", "audmon"])
-            .current_dir(parent_dir)
-            .status();
-        
-        match clone_status {
-            Ok(status) if status.success() => {
-                println!("✓ audmon cloned successfully");
-            }
-            Ok(status) => {
-                eprintln!("✗ git clone failed with exit code: {:?}", status.code());
-                std::process::exit(1);
-            }
+    let mut supervised = Vec::with_capacity(components.len());
+    for component in components {
+        let child = match start_component(&project_root, &component, &mut launch_log) {
+            Ok(child) => child,
             Err(e) => {
-                eprintln!("✗ Failed to run git clone: {}", e);
+                eprintln!("✗ {}", e);
                 std::process::exit(1);
             }
-        }
+        };
+        wait_until_ready(&component, &mut launch_log);
+        supervised.push(Supervised { component, child: Some(child), stopped: false, restarts: 0 });
     }
     
-    // Check if audmon release binary exists and is up-to-date
-    let audmon_release_dir = audmon_path.join("target/release");
-    let audmon_binary = audmon_release_dir.join("audio_monitor");
-    let needs_build = check_binary_needs_build(&audmon_path, &audmon_binary);
+    println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("All applications launched!");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     
-    if needs_build {
-        println!("Building audmon release binary...");
-        let build_status = Command::new("cargo")
-            .args(&["build", "--release", "--bin", "audio_monitor"])
-            .current_dir(&audmon_path)
-            .stdout(Stdio::inherit())
-            .stderr(Stdio::inherit())
-            .status();
-        
-        match build_status {
-            Ok(status) if status.success() => {
-                println!("✓ audmon release binary built successfully");
-            }
-            Ok(status) => {
-                eprintln!("✗ audmon build failed with exit code: {:?}", status.code());
-                std::process::exit(1);
-            }
+    if supervised.iter().all(|s| s.component.restart == RestartPolicy::Never) {
+        report_status(false, &mut supervised);
+        println!("\nLauncher exiting (applications will continue running)");
+        return;
+    }
+    supervise(&project_root, supervised, &mut launch_log);
+}
+
+/// --stop / --restart: stop the named components (every one for a bare --stop), then
+/// start the restarted ones again, unsupervised. Returns the exit code.
+fn stop_components(project_root: &Path, hostname: &str, names: &[String], restart: bool, mode: shutdown::Mode) -> i32 {
+    let registry = match shutdown::registry(hostname, project_root) {
+        Ok(registry) => registry,
+        Err(e) => {
+            eprintln!("ERROR: Invalid LAUNCH section for host '{}': {}", hostname, e);
+            return 1;
+        }
+    };
+    let targets = if names.is_empty() {
+        if restart {
+            let known: Vec<&str> = registry.iter().map(|t| t.name.as_str()).collect();
+            eprintln!("ERROR: --restart needs the components to restart ({})", known.join(", "));
+            return 1;
+        }
+        registry
+    } else {
+        match shutdown::select(&registry, names) {
+            Ok(targets) => targets,
             Err(e) => {
-                eprintln!("✗ Failed to run cargo build for audmon: {}", e);
-                std::process::exit(1);
+                eprintln!("ERROR: {}", e);
+                return 1;
             }
         }
-    } else {
-        println!("✓ audmon release binary is up-to-date");
-    }
+    };
     
-    let audmon_script = audmon_path.join("audmon.sh");
-    if !audmon_script.exists() {
-        eprintln!("✗ audmon.sh not found at {}", audmon_script.display());
-        std::process::exit(1);
+    let dry_run = mode == shutdown::Mode::DryRun;
+    println!("{}: {}", if dry_run { "Dry run, would stop" } else { "Stopping" },
+        targets.iter().map(|t| t.name.as_str()).collect::<Vec<_>>().join(", "));
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    let report = shutdown::terminate(&targets, mode);
+    for line in report.lines() {
+        println!("  {}", line);
+    }
+    let mut launch_log = logging::open_log_file("launcher");
+    if !dry_run {
+        record_launch(&mut launch_log, &format!(
+            "Stopped {} ({} processes, {} still running)",
+            report.targets.join(", "), report.processes.len(), report.survivors().count()
+        ));
+    }
+    if report.survivors().count() > 0 {
+        return 1;
+    }
+    if !restart {
+        return 0;
     }
     
-    match Command::new("bash")
-        .arg(&audmon_script)
-        .current_dir(&audmon_path)
-        .spawn() {
-        Ok(_) => {
-            println!("✓ audio_monitor launched via audmon.sh");
-        }
+    let components = match shutdown::registry_components(hostname, project_root) {
+        Ok(list) => list,
         Err(e) => {
-            eprintln!("✗ Failed to launch audmon.sh: {}", e);
-            std::process::exit(1);
+            eprintln!("ERROR: Invalid LAUNCH section for host '{}': {}", hostname, e);
+            return 1;
         }
+    };
+    let restarted: Vec<LaunchComponent> = components.into_iter().filter(|c| names.contains(&c.name)).collect();
+    if dry_run {
+        for component in &restarted {
+            println!("  would start {}: {}", component.name, component.command.join(" "));
+        }
+        return 0;
     }
-    
-    // Wait for audmon to start writing to shared memory
-    println!("\nWaiting for audio_monitor to initialize and write to shared memory...");
-    let shm_path = get_shared_memory_path();
-    println!("  Checking shared memory at: {}", shm_path);
-    let shm_ready = wait_for_shared_memory();
-    if !shm_ready {
-        eprintln!("⚠ Warning: Timeout waiting for shared memory to have results");
-        eprintln!("  audio_monitor may not be running correctly");
-        eprintln!("  Shared memory path: {}", shm_path);
-        if Path::new(&shm_path).exists() {
-            eprintln!("  File exists but may not have valid data yet");
-        } else {
-            eprintln!("  File does not exist");
+    build_components(project_root, &restarted);
+    for component in &restarted {
+        if let Err(e) = start_component(project_root, component, &mut launch_log) {
+            eprintln!("✗ {}", e);
+            return 1;
         }
-        eprintln!("  Continuing anyway to launch stepper_gui and operations_gui...");
-    } else {
-        println!("✓ Shared memory verified - audio_monitor is running");
+        wait_until_ready(component, &mut launch_log);
     }
+    0
+}
+
+/// Append a timestamped line to the launcher log (no-op if it could not be opened)
+fn record_launch(log: &mut Option<logging::RotatingFile>, message: &str) {
+    if let Some(f) = log.as_mut() {
+        let _ = writeln!(f, "{} {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), message);
+    }
+}
+
+fn component_dir(project_root: &Path, component: &LaunchComponent) -> PathBuf {
+    match &component.working_dir {
+        Some(dir) => project_root.join(dir),
+        None => project_root.to_path_buf(),
+    }
+}
+
+/// Substitute {stepper_socket} and {shm} in readiness paths
+fn expand_placeholders(value: &str) -> String {
+    let mut out = value.replace("{shm}", &get_shared_memory_path());
+    if out.contains("{stepper_socket}") {
+        let socket = get_stepper_socket_path().unwrap_or_default();
+        out = out.replace("{stepper_socket}", &socket);
+    }
+    out
+}
+
+/// Rebuild stale release binaries, one cargo invocation per working directory.
+/// The project's own binaries get the gpiod feature when GPIO is enabled for this host.
+fn build_components(project_root: &Path, components: &[LaunchComponent]) {
+    let gpio_enabled = check_gpio_enabled();
+    println!("GPIO enabled for this host: {}", gpio_enabled);
     
-    // Check if GPIO is enabled for this host from YAML
-    let gpio_enabled = check_gpio_enabled(&project_root);
-    println!("\nGPIO enabled for this host: {}", gpio_enabled);
-    
-    // Check if binaries need rebuilding
-    let stepper_gui_binary = release_dir.join("stepper_gui");
-    let operations_gui_binary = release_dir.join("operations_gui");
-    let stepper_needs_build = check_binary_needs_build(&project_root, &stepper_gui_binary);
-    let operations_needs_build = check_binary_needs_build(&project_root, &operations_gui_binary);
-    
-    if stepper_needs_build || operations_needs_build {
-        println!("\nBuilding release binaries...");
-        if stepper_needs_build {
-            println!("  stepper_gui needs rebuild");
+    let mut builds: Vec<(PathBuf, Vec<&str>)> = Vec::new();
+    for component in components {
+        let Some(bin) = component.build_bin.as_deref() else { continue; };
+        let dir = component_dir(project_root, component);
+        if !dir.exists() {
+            eprintln!("✗ Working directory for {} not found: {}", component.name, dir.display());
+            std::process::exit(1);
         }
-        if operations_needs_build {
-            println!("  operations_gui needs rebuild");
+        let binary = dir.join("target/release").join(bin);
+        if !check_binary_needs_build(&dir, &binary) {
+            continue;
         }
+        println!("  {} needs rebuild", bin);
+        match builds.iter_mut().find(|(d, _)| *d == dir) {
+            Some((_, bins)) => bins.push(bin),
+            None => builds.push((dir, vec![bin])),
+        }
+    }
+    
+    if builds.is_empty() {
+        println!("✓ Release binaries are up-to-date");
+        return;
+    }
+    
+    for (dir, bins) in builds {
+        println!("\nBuilding release binaries in {}...", dir.display());
         println!("  (Cargo build output will appear below)\n");
         std::io::stdout().flush().ok();
         
         let mut build_args = vec!["build", "--release"];
-        if gpio_enabled {
+        if gpio_enabled && dir == project_root {
             build_args.push("--features");
             build_args.push("gpiod");
         }
-        build_args.push("--bin");
-        build_args.push("stepper_gui");
-        build_args.push("--bin");
-        build_args.push("operations_gui");
+        for bin in bins {
+            build_args.push("--bin");
+            build_args.push(bin);
+        }
         
         let build_status = Command::new("cargo")
             .args(&build_args)
-            .current_dir(&project_root)
+            .current_dir(&dir)
             .stdout(Stdio::inherit())
             .stderr(Stdio::inherit())
             .status();
@@ -298,98 +293,198 @@ fn launch_separate_mode() {
                 std::process::exit(1);
             }
         }
-    } else {
-        println!("\n✓ Release binaries are up-to-date");
-    }
-    
-    // Launch stepper_gui
-    println!("\nLaunching stepper_gui...");
-    let stepper_gui = release_dir.join("stepper_gui");
-    
-    // Check if binary exists
-    if !stepper_gui.exists() {
-        eprintln!("✗ stepper_gui binary not found at: {}", stepper_gui.display());
-        std::process::exit(1);
     }
+}
+
+/// Spawn one component; fails if it cannot start or exits with an error straight away
+fn start_component(project_root: &Path, component: &LaunchComponent, launch_log: &mut Option<logging::RotatingFile>) -> anyhow::Result<Child> {
+    println!("\nLaunching {}...", component.name);
+    let dir = component_dir(project_root, component);
+    
+    // Relative program paths (e.g. target/release/stepper_gui) are relative to the working dir
+    let program = Path::new(&component.command[0]);
+    let program = if program.is_relative() && program.components().count() > 1 {
+        dir.join(program)
+    } else {
+        program.to_path_buf()
+    };
     
-    match Command::new(&stepper_gui)
+    let mut child = match Command::new(&program)
+        .args(&component.command[1..])
+        .current_dir(&dir)
         .spawn() {
-        Ok(child) => {
-            println!("✓ stepper_gui launched (PID: {})", child.id());
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("  Command: {}", component.command.join(" "));
+            eprintln!("  Working directory: {}", dir.display());
+            record_launch(launch_log, &format!("Failed to launch {}: {}", component.name, e));
+            return Err(anyhow::anyhow!("Failed to launch {}: {}", component.name, e));
         }
+    };
+    println!("✓ {} launched (PID: {})", component.name, child.id());
+    record_launch(launch_log, &format!("{} launched (PID: {})", component.name, child.id()));
+    
+    // Give it a moment to start and check if it's still running
+    std::thread::sleep(STARTUP_CHECK);
+    match child.try_wait() {
+        Ok(Some(status)) if !status.success() => {
+            eprintln!("  This usually indicates a startup error - check stderr output above");
+            record_launch(launch_log, &format!("{} exited immediately: {:?}", component.name, status));
+            return Err(anyhow::anyhow!("{} exited immediately with status: {:?}", component.name, status));
+        }
+        Ok(_) => {}
         Err(e) => {
-            eprintln!("✗ Failed to launch stepper_gui: {}", e);
-            std::process::exit(1);
+            eprintln!("  Warning: Could not check {} status: {}", component.name, e);
         }
     }
-    
-    // Wait for stepper_gui socket to be ready before launching operations_gui
-    println!("\nWaiting for stepper_gui socket to be ready...");
-    let socket_ready = wait_for_stepper_socket(&project_root);
-    if !socket_ready {
-        eprintln!("⚠ Warning: Timeout waiting for stepper_gui socket");
-        eprintln!("  stepper_gui may not be running correctly");
-        eprintln!("  Continuing anyway to launch operations_gui...");
-    } else {
-        println!("✓ stepper_gui socket verified - ready for operations_gui");
-    }
-    
-    // Launch operations_gui
-    println!("\nLaunching operations_gui...");
-    let operations_gui = release_dir.join("operations_gui");
-    
-    // Check if binary exists
-    if !operations_gui.exists() {
-        eprintln!("✗ operations_gui binary not found at: {}", operations_gui.display());
-        eprintln!("  Expected path: {}", operations_gui.display());
-        eprintln!("  Release directory exists: {}", release_dir.exists());
-        if release_dir.exists() {
-            eprintln!("  Files in release directory:");
-            if let Ok(entries) = std::fs::read_dir(&release_dir) {
-                for entry in entries.flatten() {
-                    if let Ok(name) = entry.file_name().into_string() {
-                        eprintln!("    - {}", name);
-                    }
-                }
-            }
+    Ok(child)
+}
+
+fn check_ready(check: &ReadinessCheck) -> bool {
+    match check {
+        ReadinessCheck::Socket(path) => readiness::socket_ready(Path::new(&expand_placeholders(path))),
+        ReadinessCheck::File { path, contains } => {
+            readiness::file_ready(Path::new(&expand_placeholders(path)), contains.as_deref())
         }
-        std::process::exit(1);
+        ReadinessCheck::Port(port) => readiness::port_ready(*port),
+    }
+}
+
+fn describe_check(check: &ReadinessCheck) -> String {
+    match check {
+        ReadinessCheck::Socket(path) => format!("socket {}", expand_placeholders(path)),
+        ReadinessCheck::File { path, contains: Some(text) } => format!("file {} containing '{}'", expand_placeholders(path), text),
+        ReadinessCheck::File { path, contains: None } => format!("file {}", expand_placeholders(path)),
+        ReadinessCheck::Port(port) => format!("TCP port {}", port),
     }
+}
+
+/// Poll the component's readiness check (event-driven polling).
+/// A timeout is reported but does not stop the launch.
+fn wait_until_ready(component: &LaunchComponent, launch_log: &mut Option<logging::RotatingFile>) {
+    let Some(check) = component.ready.as_ref() else { return; };
+    
+    println!("Waiting for {} ({})...", component.name, describe_check(check));
+    let options = readiness::WaitOptions::with_timeout(Duration::from_secs_f32(component.ready_timeout.max(0.0)));
+    let ready = readiness::poll_until(options, || check_ready(check), |polls| {
+        if polls % 5 == 0 {
+            print!(".");
+            std::io::stdout().flush().ok();
+        }
+    });
+    if !ready {
+        eprintln!("⚠ Warning: Timeout waiting for {} to be ready", component.name);
+        eprintln!("  {} may still be starting up - continuing", component.name);
+        record_launch(launch_log, &format!("Timeout waiting for {} to be ready", component.name));
+        return;
+    }
+    println!("✓ {} is ready", component.name);
+    record_launch(launch_log, &format!("{} ready", component.name));
+}
+
+/// A launched component and its current process (None while waiting to restart)
+struct Supervised {
+    component: LaunchComponent,
+    child: Option<Child>,
+    stopped: bool, // Exited and not to be restarted
+    restarts: u32,
+}
+
+/// Watch launched components and restart them according to their policy.
+/// Returns once nothing is left to supervise; Ctrl-C stops supervision and leaves
+/// the components running.
+fn supervise(project_root: &Path, mut supervised: Vec<Supervised>, launch_log: &mut Option<logging::RotatingFile>) {
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_handler = Arc::clone(&stop);
+    if let Err(e) = ctrlc::set_handler(move || stop_handler.store(true, Ordering::Relaxed)) {
+        eprintln!("Warning: Could not install Ctrl-C handler: {}", e);
+    }
+    println!("\nSupervising components (Ctrl-C to stop supervising; applications keep running)");
     
-    match Command::new(&operations_gui)
-        .spawn() {
-        Ok(mut child) => {
-            println!("✓ operations_gui launched (PID: {})", child.id());
-            // Give it a moment to start and check if it's still running
-            std::thread::sleep(std::time::Duration::from_millis(500));
-            match child.try_wait() {
-                Ok(Some(status)) => {
-                    eprintln!("✗ operations_gui exited immediately with status: {:?}", status);
-                    eprintln!("  This usually indicates a startup error - check stderr output above");
-                    std::process::exit(1);
-                }
-                Ok(None) => {
-                    println!("  operations_gui is still running");
-                }
+    while !stop.load(Ordering::Relaxed) {
+        report_status(true, &mut supervised);
+        for entry in supervised.iter_mut() {
+            if entry.stopped {
+                continue;
+            }
+            let Some(child) = entry.child.as_mut() else {
+                // Pending restart (an earlier attempt failed)
+                restart_component(project_root, entry, launch_log);
+                continue;
+            };
+            let status = match child.try_wait() {
+                Ok(Some(status)) => status,
+                Ok(None) => continue,
                 Err(e) => {
-                    eprintln!("  Warning: Could not check operations_gui status: {}", e);
+                    eprintln!("  Warning: Could not check {} status: {}", entry.component.name, e);
+                    continue;
                 }
+            };
+            let restart = match entry.component.restart {
+                RestartPolicy::Always => true,
+                RestartPolicy::OnFailure => !status.success(),
+                RestartPolicy::Never => false,
+            };
+            println!("{} exited with status: {:?}", entry.component.name, status);
+            record_launch(launch_log, &format!("{} exited: {:?}", entry.component.name, status));
+            entry.child = None;
+            if restart {
+                restart_component(project_root, entry, launch_log);
+            } else {
+                entry.stopped = true;
             }
         }
-        Err(e) => {
-            eprintln!("✗ Failed to launch operations_gui: {}", e);
-            eprintln!("  Binary path: {}", operations_gui.display());
-            eprintln!("  Error details: {:?}", e);
-            std::process::exit(1);
+        if supervised.iter().all(|s| s.stopped) {
+            report_status(false, &mut supervised);
+            println!("\nAll supervised components have stopped");
+            return;
         }
+        std::thread::sleep(Duration::from_secs(1));
     }
-    
-    println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("All applications launched!");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    report_status(false, &mut supervised);
     println!("\nLauncher exiting (applications will continue running)");
 }
 
+/// Publish component states for the GUIs' health panel
+fn report_status(supervising: bool, supervised: &mut [Supervised]) {
+    let components: Vec<health::ComponentStatus> = supervised
+        .iter_mut()
+        .map(|entry| {
+            let (state, pid) = match entry.child.as_mut() {
+                Some(child) => match child.try_wait() {
+                    Ok(None) => ("running", Some(child.id())),
+                    _ => ("exited", Some(child.id())),
+                },
+                None if entry.stopped => ("stopped", None),
+                None => ("restarting", None),
+            };
+            health::ComponentStatus {
+                name: entry.component.name.clone(),
+                state: state.to_string(),
+                pid,
+                restarts: entry.restarts,
+            }
+        })
+        .collect();
+    if let Err(e) = health::write_supervisor_status(supervising, &components) {
+        eprintln!("  Warning: Could not write launcher status: {}", e);
+    }
+}
+
+fn restart_component(project_root: &Path, entry: &mut Supervised, launch_log: &mut Option<logging::RotatingFile>) {
+    std::thread::sleep(RESTART_DELAY);
+    entry.restarts += 1;
+    println!("Restarting {} (restart #{})", entry.component.name, entry.restarts);
+    match start_component(project_root, &entry.component, launch_log) {
+        Ok(child) => {
+            entry.child = Some(child);
+            wait_until_ready(&entry.component, launch_log);
+        }
+        // Left pending; retried on the next supervision pass
+        Err(e) => eprintln!("✗ {}", e),
+    }
+}
+
 /// Get shared memory path for partials data
 fn get_shared_memory_path() -> String {
     let shm_dir = if cfg!(target_os = "linux") {
@@ -402,148 +497,14 @@ fn get_shared_memory_path() -> String {
     format!("{}/audio_peaks", shm_dir)
 }
 
-/// Check if shared memory file exists and has been created by audio_monitor
-/// audio_monitor creates the file when it starts, so if it exists with reasonable size, it's ready
-fn check_shared_memory_has_data() -> bool {
-    let shm_path = get_shared_memory_path();
-    
-    // Check if file exists
-    if !Path::new(&shm_path).exists() {
-        return false;
-    }
-    
-    // Check file size - audio_monitor creates a file of a specific size (typically 4MB for partials)
-    // If file exists and has reasonable size (> 0 bytes), audio_monitor is running
-    if let Ok(metadata) = std::fs::metadata(&shm_path) {
-        let size = metadata.len();
-        // File exists and has some size - audio_monitor is running
-        // Don't require valid audio data since there might not be audio input yet
-        if size > 0 {
-            return true;
-        }
-    }
-    
-    false
-}
-
-/// Wait for shared memory to have results (event-driven polling)
-/// Returns true if shared memory has data, false if timeout
-fn wait_for_shared_memory() -> bool {
-    const MAX_ATTEMPTS: u32 = 60; // 60 attempts
-    const POLL_INTERVAL_MS: u64 = 500; // Check every 500ms
-    
-    for attempt in 1..=MAX_ATTEMPTS {
-        if check_shared_memory_has_data() {
-            return true;
-        }
-        
-        if attempt < MAX_ATTEMPTS {
-            std::thread::sleep(std::time::Duration::from_millis(POLL_INTERVAL_MS));
-            if attempt % 10 == 0 {
-                print!(".");
-                std::io::stdout().flush().ok();
-            }
-        }
-    }
-    
-    false
-}
-
-/// Wait for master_gui status file to show "ready" (event-driven polling)
-/// Returns true if status is "ready", false if timeout
-fn wait_for_master_gui_ready(status_file: &std::path::Path) -> bool {
-    const MAX_ATTEMPTS: u32 = 60; // 60 attempts
-    const POLL_INTERVAL_MS: u64 = 500; // Check every 500ms
-    
-    for attempt in 1..=MAX_ATTEMPTS {
-        if let Ok(content) = std::fs::read_to_string(status_file) {
-            let status = content.trim();
-            if status == "ready" {
-                return true;
-            }
-        }
-        
-        if attempt < MAX_ATTEMPTS {
-            std::thread::sleep(std::time::Duration::from_millis(POLL_INTERVAL_MS));
-            if attempt % 10 == 0 {
-                print!(".");
-                std::io::stdout().flush().ok();
-            }
-        }
-    }
-    
-    false
-}
-
 /// Get socket path for stepper_gui based on Arduino port
-fn get_stepper_socket_path(project_root: &std::path::Path) -> Option<String> {
-    let yaml_path = project_root.join("string_driver.yaml");
-    let file = match std::fs::File::open(&yaml_path) {
-        Ok(f) => f,
-        Err(_) => return None,
-    };
-    
-    let yaml: serde_yaml::Value = match serde_yaml::from_reader(file) {
-        Ok(y) => y,
-        Err(_) => return None,
-    };
-    
+fn get_stepper_socket_path() -> Option<String> {
     let hostname = gethostname().to_string_lossy().to_string();
-    
-    // Search across known OS sections to find a host block matching hostname
-    for os_key in ["RaspberryPi", "Ubuntu", "macOS"].iter() {
-        if let Some(os_map) = yaml.get(*os_key).and_then(|v| v.as_mapping()) {
-            for (k, v) in os_map.iter() {
-                if k.as_str() == Some(&hostname) {
-                    if let Some(host_block) = v.as_mapping() {
-                        // Get ARD_PORT
-                        if let Some(ard_port) = host_block.get(&serde_yaml::Value::from("ARD_PORT")) {
-                            if let Some(port_str) = ard_port.as_str() {
-                                // Generate socket path same way as stepper_gui.rs
-                                let port_id = port_str.replace("/", "_").replace("\\", "_");
-                                return Some(format!("/tmp/stepper_gui_{}.sock", port_id));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-    
-    None
-}
-
-/// Wait for stepper_gui socket to exist (event-driven polling)
-/// Returns true if socket exists, false if timeout
-fn wait_for_stepper_socket(project_root: &std::path::Path) -> bool {
-    let socket_path = match get_stepper_socket_path(project_root) {
-        Some(path) => path,
-        None => {
-            eprintln!("  Could not determine socket path from config");
-            return false;
-        }
-    };
-    
-    println!("  Checking socket at: {}", socket_path);
-    
-    const MAX_ATTEMPTS: u32 = 30; // 30 attempts
-    const POLL_INTERVAL_MS: u64 = 200; // Check every 200ms
-    
-    for attempt in 1..=MAX_ATTEMPTS {
-        if Path::new(&socket_path).exists() {
-            return true;
-        }
-        
-        if attempt < MAX_ATTEMPTS {
-            std::thread::sleep(std::time::Duration::from_millis(POLL_INTERVAL_MS));
-            if attempt % 5 == 0 {
-                print!(".");
-                std::io::stdout().flush().ok();
-            }
-        }
-    }
-    
-    false
+    let (_, host_block) = config_loader::load_host_section(&hostname).ok()?;
+    let port_str = host_block.get(&serde_yaml::Value::from("ARD_PORT"))?.as_str()?;
+    // Generate socket path same way as the stepper core (stepper_core.rs)
+    let port_id = port_str.replace("/", "_").replace("\\", "_");
+    Some(format!("/tmp/stepper_gui_{}.sock", port_id))
 }
 
 /// Check if a binary needs a fresh release build
@@ -637,38 +598,11 @@ fn check_dir_newer_than(dir_path: &std::path::Path, threshold: std::time::System
 }
 
 /// Check if GPIO is enabled for the current hostname from YAML config
-fn check_gpio_enabled(project_root: &std::path::Path) -> bool {
-    let yaml_path = project_root.join("string_driver.yaml");
-    let file = match std::fs::File::open(&yaml_path) {
-        Ok(f) => f,
-        Err(_) => return false,
-    };
-    
-    let yaml: serde_yaml::Value = match serde_yaml::from_reader(file) {
-        Ok(y) => y,
-        Err(_) => return false,
-    };
-    
+fn check_gpio_enabled() -> bool {
     let hostname = gethostname().to_string_lossy().to_string();
-    
-    // Search across known OS sections to find a host block matching hostname
-    for os_key in ["RaspberryPi", "Ubuntu", "macOS"].iter() {
-        if let Some(os_map) = yaml.get(*os_key).and_then(|v| v.as_mapping()) {
-            for (k, v) in os_map.iter() {
-                if k.as_str() == Some(&hostname) {
-                    if let Some(host_block) = v.as_mapping() {
-                        // Check GPIO_ENABLED
-                        if let Some(gpio_enabled) = host_block.get(&serde_yaml::Value::from("GPIO_ENABLED")) {
-                            if let Some(enabled) = gpio_enabled.as_bool() {
-                                return enabled;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-    
-    false
+    config_loader::load_host_section(&hostname)
+        .ok()
+        .and_then(|(_, host_block)| host_block.get(&serde_yaml::Value::from("GPIO_ENABLED")).and_then(|v| v.as_bool()))
+        .unwrap_or(false)
 }
 
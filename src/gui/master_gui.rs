@@ -5,21 +5,11 @@
 /// - Center panel: Audio Monitor status/info (audmon runs as separate process)
 /// - Right panel: Operations Control (600px default, resizable 400-800px)
 
-#[path = "../config_loader.rs"]
-mod config_loader;
-#[path = "../gpio.rs"]
-mod gpio;
-#[path = "../operations.rs"]
-mod operations;
-#[path = "../get_results.rs"]
-mod get_results;
-#[path = "../machine_state_logger.rs"]
-mod machine_state_logger;
+use stringdriver::stepper_core::StepperCore;
+use stringdriver::transport::Transport;
+use stringdriver::{arduino_connection, audmon_client, bow_drive, build_info, config_loader, error, gui_state, i18n, logging, profiling, role, tension};
 
-// Include the GUI structs as modules so we can use them
-// We'll include just the struct definitions and impl blocks we need
-#[path = "stepper_gui.rs"]
-mod stepper_gui_mod;
+// Include the operations GUI as a module so we can use its struct
 #[path = "operations_gui.rs"]
 mod operations_gui_mod;
 
@@ -27,7 +17,6 @@ use eframe::egui;
 use std::time::{Duration, Instant};
 use anyhow::Result;
 use gethostname::gethostname;
-use std::fs::File;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, RwLock, mpsc};
 use std::collections::VecDeque;
@@ -44,9 +33,10 @@ use audio_monitor::plot::SpectrumApp;
 use audio_monitor::{DEFAULT_BUFFER_SIZE, DEFAULT_NUM_PARTIALS};
 
 pub struct MasterGUI {
-    stepper_gui: Option<stepper_gui_mod::StepperGUI>,
+    stepper_gui: Option<StepperCore>,
     operations_gui: Option<operations_gui_mod::OperationsGUI>,
     audmon_gui: Option<MyApp>,
+    gui_state: gui_state::GuiState, // Window, panels and operations_gui's selection across launches
 }
 
 impl MasterGUI {
@@ -65,26 +55,34 @@ impl MasterGUI {
         }
     }
     
-    /// Read control file directly (since read_control_file is private)
-    fn read_control_file_direct(control_path: &str) -> Option<(usize, usize)> {
-        let content = std::fs::read_to_string(control_path).ok()?;
-        let lines: Vec<&str> = content.trim().split('\n').collect();
-        if lines.len() >= 3 {
-            // Format: PID\nnum_channels\nnum_partials
-            let num_channels = lines[1].parse::<usize>().ok()?;
-            let num_partials = lines[2].parse::<usize>().ok()?;
-            Some((num_channels, num_partials))
-        } else {
-            None
-        }
-    }
-    
-    pub fn new() -> Result<Self> {
+    pub fn new(log_buffer: logging::LogBuffer) -> Result<Self> {
         // Initialize stepper_gui (optional - only if Arduino is configured)
-        let stepper_gui = Self::init_stepper_gui().ok();
+        // Both panels show the same process-wide log buffer
+        let mut stepper_gui = Self::init_stepper_gui().ok();
+        if let Some(stepper) = stepper_gui.as_mut() {
+            stepper.attach_log_buffer(Arc::clone(&log_buffer));
+        }
         
         // Initialize operations_gui
-        let operations_gui = operations_gui_mod::OperationsGUI::new().ok();
+        let mut operations_gui = operations_gui_mod::OperationsGUI::new().ok();
+        if let Some(ops) = operations_gui.as_mut() {
+            ops.attach_log_buffer(log_buffer);
+        }
+
+        // One performer / technician switch for both panels
+        let role_lock = role::RoleLock::for_host(&gethostname().to_string_lossy());
+        if let Some(stepper) = stepper_gui.as_mut() {
+            stepper.attach_role_lock(role_lock.clone());
+        }
+        if let Some(ops) = operations_gui.as_mut() {
+            ops.attach_role_lock(role_lock);
+        }
+
+        // Window, panels and the operations selection as the last session left them
+        let gui_state = gui_state::GuiState::load(&gethostname().to_string_lossy(), "master_gui");
+        if let Some(ops) = operations_gui.as_mut() {
+            ops.restore_selection(&gui_state.state);
+        }
         
         // Initialize audmon_gui - try to create MyApp instance
         let audmon_gui = match Self::init_audmon_gui() {
@@ -101,10 +99,11 @@ impl MasterGUI {
             stepper_gui,
             operations_gui,
             audmon_gui,
+            gui_state,
         })
     }
     
-    fn init_stepper_gui() -> Result<stepper_gui_mod::StepperGUI> {
+    fn init_stepper_gui() -> Result<StepperCore> {
         use clap::Parser;
         
         #[derive(Parser)]
@@ -114,12 +113,6 @@ impl MasterGUI {
         }
         
         let args = Args::parse();
-        let mut debug_file: Option<File> = None;
-        if args.debug {
-            if let Ok(file) = File::create("/home/gregory/Documents/string_driver/rust_driver/run_output.log") {
-                debug_file = Some(file);
-            }
-        }
 
         let hostname = gethostname().to_string_lossy().to_string();
         let settings = config_loader::load_arduino_settings(&hostname)?;
@@ -145,6 +138,8 @@ impl MasterGUI {
         let tuner_first_index = settings.tuner_first_index;
         let ard_t_port = settings.ard_t_port.clone();
         let firmware = settings.firmware;
+        let ard_t_firmware = settings.ard_t_firmware;
+        let encoder_slip_threshold = settings.encoder_slip_threshold;
         let x_max_pos = settings.x_max_pos;
         
         let ops_settings = config_loader::load_operations_settings(&hostname)
@@ -163,17 +158,26 @@ impl MasterGUI {
                 x_start: Some(100),
                 x_finish: Some(100),
                 x_step: Some(10),
+                bump_retract_step: None,
+                bump_clear_readings: None,
+                bump_settle_rest: None,
+                bump_final_margin: None,
+                adaptive_z_step: false,
+                z_max_step: None,
+                performance_rest: None,
+                performance_bump_interval: None,
+                performance_use_controller: false,
+                partials_stale_limit: None,
+                voice_floor_relative: false,
+                voice_floor: None,
+                voice_floors: Vec::new(),
+                channel_map: None,
             });
         let z_up_step = ops_settings.z_up_step.unwrap_or(2);
         let z_down_step = ops_settings.z_down_step.unwrap_or(-2);
         let x_step = ops_settings.x_step.unwrap_or(10);
 
-        // Use firmware directly - both modules use the same enum from config_loader.rs
-        // We'll use unsafe transmute since the compiler sees them as different types
-        // but they're actually the same enum from the same source file
-        use std::mem;
-        
-        let mut stepper = stepper_gui_mod::StepperGUI::new(
+        let mut stepper = StepperCore::new(
             port,
             num_steppers,
             string_num,
@@ -183,15 +187,46 @@ impl MasterGUI {
             ard_t_port,
             tuner_num_for_gui,
             args.debug,
-            debug_file,
             z_up_step,
             z_down_step,
-            // Transmute firmware enum - both are the same enum from config_loader.rs
-            unsafe { mem::transmute(firmware) },
+            firmware,
             x_max_pos,
             x_step,
+            ard_t_firmware,
+            encoder_slip_threshold,
         );
         
+        // ARD_USB / ARD_T_USB: find the boards by USB identity rather than device path
+        let usb_match = |u: &config_loader::UsbIdSettings| arduino_connection::UsbMatch {
+            vid: u.vid,
+            pid: u.pid,
+            serial_number: u.serial_number.clone(),
+        };
+        stepper.set_usb_matches(settings.usb.as_ref().map(usb_match), settings.ard_t_usb.as_ref().map(usb_match));
+        // ARD_TRANSPORT / ARD_T_TRANSPORT: serial device or BLE UART
+        stepper.set_transports(Transport::from_settings(&settings.transport), Transport::from_settings(&settings.ard_t_transport));
+        stepper.set_reply_checksums(settings.checksum, settings.ard_t_checksum);
+        stepper.set_reply_timeouts(settings.reply_timeouts.positions, settings.reply_timeouts.encoders);
+        stepper.set_tuner_steps(settings.tuner_steps.coarse, settings.tuner_steps.fine);
+        stepper.set_jog_rate(settings.jog_rate_hz);
+        // MOTION_PARAMS: pushed to the boards on every connect
+        if let Some(params) = settings.motion_params {
+            stepper.set_motion_params(params);
+        }
+        // AXES: linear axes besides X
+        stepper.set_axes(settings.axes.clone());
+        // BOW_DRIVE: per-string bow wheel speeds
+        let bow_settings = config_loader::load_bow_drive_settings(&hostname)?;
+        stepper.set_bow_drive(bow_settings.map(|bow| bow_drive::BowDrive::new(bow, string_num)));
+        stepper.set_tuner_guard(settings.tuner_safety.as_ref().map(|safety| tension::TunerGuard::new(safety, string_num)));
+        let damper_settings = config_loader::load_damper_settings(&hostname)?;
+        stepper.set_damper_command(damper_settings.as_ref().and_then(|dampers| dampers.serial_command()));
+        stepper.set_string_info(
+            settings.strings.iter().map(|s| s.name.clone()).collect(),
+            settings.strings.iter().map(|s| s.color).collect(),
+        );
+        stepper.enable_position_model();
+        
         // Auto-connect on startup
         stepper.connect();
         
@@ -215,9 +250,19 @@ impl MasterGUI {
 }
 
 impl eframe::App for MasterGUI {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        // Stop operations_gui's background threads with the window
+        if let Some(ops) = self.operations_gui.as_mut() {
+            ops.shutdown();
+        }
+        self.gui_state.save();
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Request regular repaints
-        ctx.request_repaint_after(Duration::from_millis(16));
+        let frame_started = Instant::now(); // All three panels count towards the frame budget
+        // Request regular repaints, at operations_gui's REFRESH_RATES REPAINT_HZ when it is up
+        let repaint = self.operations_gui.as_ref().map_or(Duration::from_millis(16), |ops| ops.repaint_interval());
+        ctx.request_repaint_after(repaint);
         
         // Left panel: Stepper Control
         egui::SidePanel::left("stepper_panel")
@@ -251,11 +296,7 @@ impl eframe::App for MasterGUI {
                         ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                         return;
                     }
-                    ctx.request_repaint_after(Duration::from_millis(16));
-                    ops.poll_operation_result();
-                    let partials = get_results::read_partials_from_slot(&ops.partials_slot);
-                    ops.operations.read().unwrap().update_audio_analysis_with_partials(partials);
-                    ops.reconcile_voice_count_cap();
+                    ops.tick();
                     
                     ops.render_ui(ui, ctx);
                 } else {
@@ -268,22 +309,9 @@ impl eframe::App for MasterGUI {
         // Center panel: Audio Monitor GUI (full audmon interface)
         egui::CentralPanel::default().show(ctx, |ui| {
             if let Some(ref mut audmon_gui) = self.audmon_gui {
-                // Update partials from shared memory before rendering
-                let shm_dir = if cfg!(target_os = "linux") {
-                    "/dev/shm"
-                } else if cfg!(target_os = "macos") {
-                    "/tmp"
-                } else {
-                    "/tmp"
-                };
-                let control_path = format!("{}/audio_control", shm_dir);
-                
-                // Read partials from shared memory and update MyApp
-                if let Some((num_channels, num_partials)) = Self::read_control_file_direct(&control_path) {
-                    if let Some(partials) = operations::Operations::read_partials_from_shared_memory(
-                        num_channels,
-                        num_partials
-                    ) {
+                // Read partials from shared memory and update MyApp before rendering
+                if let Some((channels, partials_per_channel)) = audmon_client::control_layout() {
+                    if let Some(partials) = audmon_client::read_partials(channels, partials_per_channel) {
                         audmon_gui.update_from_partials(partials);
                     }
                 }
@@ -310,32 +338,51 @@ impl eframe::App for MasterGUI {
         if let Some(ref mut audmon_gui) = self.audmon_gui {
             audmon_gui.render_crosstalk_trainer(ctx);
         }
+        profiling::record(profiling::Probe::Frame, frame_started.elapsed());
+        profiling::show_overlay(ctx);
+        if let Some(ops) = self.operations_gui.as_ref() {
+            ops.note_selection(&mut self.gui_state.state);
+        }
+        self.gui_state.capture(ctx);
     }
 }
 
 fn main() {
     println!("Master GUI starting...");
-    env_logger::init();
+    let debug = std::env::args().any(|a| a == "--debug");
+    let log_buffer = logging::init("master_gui", if debug { "debug" } else { "info" });
+    build_info::log_startup("master_gui");
+    i18n::init_for_host(&gethostname().to_string_lossy());
+    if let Ok(rates) = config_loader::load_refresh_rates(&gethostname().to_string_lossy()) {
+        profiling::set_budgets(profiling::Budgets::from_rates(&rates));
+    }
+    #[cfg(feature = "plugins")]
+    operations_gui_mod::load_plugins(&gethostname().to_string_lossy());
     
-    let gui = match MasterGUI::new() {
+    let gui = match MasterGUI::new(log_buffer) {
         Ok(gui) => gui,
         Err(e) => {
-            eprintln!("Failed to create MasterGUI: {}", e);
+            eprintln!("Failed to create MasterGUI: {}", error::user_message(e.as_ref()));
             std::process::exit(1);
         }
     };
     
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_title("String Driver - Master Control")
-            .with_inner_size([1800.0, 1000.0]), // Wide window for three panels
+        viewport: gui.gui_state.viewport(
+            egui::ViewportBuilder::default().with_title("String Driver - Master Control"),
+            None,
+            [1800.0, 1000.0], // Wide window for three panels
+        ),
         ..Default::default()
     };
     
     if let Err(e) = eframe::run_native(
         "String Driver - Master Control",
         options,
-        Box::new(|_cc| Box::new(gui)),
+        Box::new(|cc| {
+            gui.gui_state.install(&cc.egui_ctx);
+            Box::new(gui)
+        }),
     ) {
         eprintln!("GUI error: {}", e);
     }
@@ -2,116 +2,237 @@
 /// 
 /// Run with: cargo run --bin operations_gui
 
-#[path = "../config_loader.rs"]
-mod config_loader;
-#[path = "../gpio.rs"]
-mod gpio;
-#[path = "../operations.rs"]
-mod operations;
-#[path = "../get_results.rs"]
-mod get_results;
-#[path = "../machine_state_logger.rs"]
-mod machine_state_logger;
+use stringdriver::{access, arbitration, audio_trigger, audmon_client, build_info, bump_log, command_inbox, config_loader, effective_config, error, gui_state, health, i18n, lifecycle, logging, machine_state_logger, operations, plugins, profile, profiling, readiness, role, scene, score, shared_state, shutdown, stage_view, state_diff, status_snapshot, stepper_link, timeline, topology};
+#[cfg(feature = "fault-injection")]
+use stringdriver::fault_injection;
 
 use eframe::egui;
 use anyhow::Result;
 use std::collections::HashSet;
-use std::sync::{Arc, Mutex, RwLock, atomic::{AtomicBool, AtomicUsize}};
+use std::sync::{Arc, Mutex, RwLock, atomic::AtomicBool};
 use std::sync::mpsc::{self, Receiver, TryRecvError};
 use std::thread;
 use std::time::{Duration, Instant};
-use std::os::unix::net::UnixStream;
+use stepper_link::LinkStream;
+use lifecycle::{BackgroundThreads, ShutdownFlag};
+use audmon_client::{rescale_voice_count, AnalysisHandle, AudmonClient, AudmonConfig, LayoutChange};
 use std::process::Command;
 use uuid::Uuid;
 use chrono::Utc;
 use log::warn;
+use arbitration::{Admission, Category, Priority};
+use i18n::{tr, trf};
 
-/// Type alias for partials slot (matches partials_slot::PartialsSlot pattern)
-/// Using get_results::PartialsData type
-type PartialsSlot = Arc<Mutex<Option<get_results::PartialsData>>>;
+/// How long a command waits for stepper_gui's socket to appear on first connect
+const STEPPER_SOCKET_WAIT: Duration = Duration::from_secs(5);
+/// How long a command waits for stepper_gui to come back after the link drops (e.g. a restart)
+const STEPPER_RECONNECT_WAIT: Duration = Duration::from_secs(15);
+/// Commands that may be held while the link is down and sent once it returns.
+/// Moves are never queued - a late move is worse than a failed one - and neither
+/// is enable, which must not take effect without the user seeing it succeed.
+const QUEUEABLE_COMMANDS: &[&str] = &["set_speed", "disable"];
+const MAX_QUEUED_COMMANDS: usize = 32;
+/// Operations that establish positions from the hardware and so may run
+/// while stepper_gui's startup check reports a mismatch
+const REHOME_OPERATIONS: &[&str] = &["z_home", "x_home", "z_calibrate", "x_calibrate"];
+/// How long a quiet subscription read waits before looking at the shutdown flag
+const SUBSCRIPTION_POLL: Duration = Duration::from_millis(250);
+/// How often the health panel re-reads its inputs
+const HEALTH_REFRESH: Duration = Duration::from_millis(500);
+/// WARN/ERROR log lines kept in the status file
+const STATUS_RECENT_ERRORS: usize = 20;
+/// A channel below this fraction of its amp sum min counts as silent (string_break alerts)
+const STRING_BREAK_SILENT_FRACTION: f32 = 0.1;
+/// Health entries that do not hold up AUTOSTART: the sequence's own results and DB logging
+const AUTOSTART_IGNORED_HEALTH: &[&str] = &["Last operation", "DB logger"];
+
+/// Board connection state reported by stepper_gui
+#[derive(Debug, Clone, Copy)]
+struct StepperStatus {
+    main_connected: bool,
+    tuner_connected: Option<bool>, // None when there is no separate tuner board
+    slipping: usize,               // Steppers flagged for encoder slippage
+    main_stale: bool,              // Main board not answering position queries
+    tuner_stale: bool,
+    startup_mismatch: bool,        // Positions differ from the last session until re-homed or accepted
+}
 
 /// Arduino stepper operations implementation using simple Unix socket text commands
-/// Sends commands like "rel_move 2 2\n" to stepper_gui's Unix socket listener
+/// Sends commands like "rel_move 2 2\n" to stepper_gui's Unix socket listener,
+/// or to its TCP bridge when the socket path is a `host:port` (see stepper_link)
 struct ArduinoStepperOps {
     socket_path: String,
-    stream: Option<UnixStream>,
+    stream: Option<LinkStream>,
     connected_once: bool,
+    source: Option<String>, // Operation name tagged onto commands for stepper_gui's audit log
+    link_down: Arc<AtomicBool>, // Set while stepper_gui is unreachable (drives the GUI banner)
+    queue_when_down: Arc<AtomicBool>, // Hold QUEUEABLE_COMMANDS during an outage instead of failing
+    queued: std::collections::VecDeque<String>,
 }
 
 impl ArduinoStepperOps {
+    /// Socket path stepper_gui derives from its ARD_PORT
     fn socket_path_for_port(port_path: &str) -> String {
         let port_id = port_path.replace("/", "_").replace("\\", "_");
         format!("/tmp/stepper_gui_{}.sock", port_id)
     }
 
-    fn new(port_path: &str) -> Self {
-        // Generate socket path the same way as stepper_gui.rs
-        let socket_path = Self::socket_path_for_port(port_path);
-        println!("Initializing shared stepper socket target at {}", socket_path);
+    /// Target an explicit socket (e.g. one forwarded from another machine)
+    fn with_socket_path(socket_path: String) -> Self {
+        tracing::info!("Initializing shared stepper socket target at {}", socket_path);
         Self {
             socket_path,
             stream: None,
             connected_once: false,
+            source: None,
+            link_down: Arc::new(AtomicBool::new(false)),
+            queue_when_down: Arc::new(AtomicBool::new(true)),
+            queued: std::collections::VecDeque::new(),
         }
     }
 
+    fn link_down_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.link_down)
+    }
+
+    fn queue_when_down_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.queue_when_down)
+    }
+
+    /// Tag subsequent commands with the operation issuing them (None for manual moves)
+    fn set_source(&mut self, source: Option<&str>) {
+        self.source = source.map(|s| s.to_string());
+    }
+
     fn socket_path(&self) -> String {
         self.socket_path.clone()
     }
     
-    fn ensure_stream(&mut self) -> Result<&mut UnixStream> {
+    fn ensure_stream(&mut self) -> Result<&mut LinkStream> {
         if self.stream.is_none() {
-            if self.connected_once {
-                println!(
+            let wait = if self.connected_once {
+                tracing::warn!(
                     "Stepper socket connection dropped; attempting reconnect to {}",
                     self.socket_path
                 );
+                STEPPER_RECONNECT_WAIT
             } else {
-                println!("Connecting to stepper socket {}", self.socket_path);
-            }
-            let stream = UnixStream::connect(&self.socket_path)
-                .map_err(|e| anyhow::anyhow!("Failed to connect to stepper_gui socket at {}: {}", self.socket_path, e))?;
-            println!(
+                tracing::info!("Connecting to stepper socket {}", self.socket_path);
+                STEPPER_SOCKET_WAIT
+            };
+            // The socket file can reappear before stepper_gui accepts, so retry the connect too
+            let mut stream = None;
+            let mut last_error = None;
+            readiness::wait_until(readiness::WaitOptions::with_timeout(wait), || {
+                match stepper_link::connect(&self.socket_path) {
+                    Ok(s) => {
+                        stream = Some(s);
+                        true
+                    }
+                    Err(e) => {
+                        last_error = Some(e);
+                        false
+                    }
+                }
+            });
+            let Some(stream) = stream else {
+                self.link_down.store(true, std::sync::atomic::Ordering::Relaxed);
+                return Err(anyhow::anyhow!(
+                    "Failed to connect to stepper_gui socket at {} within {:?}: {}",
+                    self.socket_path,
+                    wait,
+                    last_error.map(|e| e.to_string()).unwrap_or_else(|| "no response".to_string())
+                ));
+            };
+            tracing::info!(
                 "Stepper socket {} connection {}",
                 self.socket_path,
                 if self.connected_once { "re-established" } else { "established" }
             );
             self.stream = Some(stream);
             self.connected_once = true;
+            self.link_down.store(false, std::sync::atomic::Ordering::Relaxed);
+            self.flush_queued();
         }
         Ok(self.stream.as_mut().unwrap())
     }
+
+    /// Send commands held during an outage, oldest first
+    fn flush_queued(&mut self) {
+        use std::io::Write;
+        let Some(stream) = self.stream.as_mut() else { return; };
+        while let Some(line) = self.queued.pop_front() {
+            if let Err(e) = stream.write_all(line.as_bytes()).and_then(|_| stream.flush()) {
+                tracing::warn!("Failed to send queued command '{}': {}", line.trim(), e);
+                self.queued.push_front(line);
+                self.stream = None;
+                return;
+            }
+            tracing::info!("Sent queued command: {}", line.trim());
+        }
+    }
+
+    /// Hold a non-motion command for the next reconnect; false if it must fail instead
+    fn queue_if_allowed(&mut self, cmd: &str, line: &str) -> bool {
+        let name = cmd.split_whitespace().next().unwrap_or("");
+        if !self.queue_when_down.load(std::sync::atomic::Ordering::Relaxed) || !QUEUEABLE_COMMANDS.contains(&name) {
+            return false;
+        }
+        if self.queued.len() >= MAX_QUEUED_COMMANDS {
+            tracing::warn!("Command queue full; dropping oldest queued command");
+            self.queued.pop_front();
+        }
+        tracing::warn!("Stepper link down; queued '{}' until it reconnects", cmd);
+        self.queued.push_back(line.to_string());
+        true
+    }
     /// Send a text command to stepper_gui via Unix socket
     fn send_command(&mut self, cmd: &str) -> Result<()> {
         use std::io::Write;
         
-        let cmd_with_newline = format!("{}
-", cmd);
-        println!("Stepper IPC command: {}", cmd);
-        match self.ensure_stream() {
+        let cmd_with_newline = match self.source.as_deref() {
+            Some(source) => format!("{} src={}\n", cmd, source),
+            None => format!("{}\n", cmd),
+        };
+        tracing::debug!("Stepper IPC command: {}", cmd);
+        let result = match self.ensure_stream() {
             Ok(stream) => {
                 if let Err(e) = stream.write_all(cmd_with_newline.as_bytes()) {
-                    println!(
+                    tracing::warn!(
                         "Stepper socket write failed ({}). Resetting connection to {}",
                         e, self.socket_path
                     );
-                    // Connection probably dropped; try once more by reconnecting.
+                    // Connection probably dropped (e.g. stepper_gui restarting); reconnect and retry once.
                     self.stream = None;
-                    let stream = self.ensure_stream()?;
-                    stream.write_all(cmd_with_newline.as_bytes())
-                        .map_err(|e| anyhow::anyhow!("Failed to write command to socket: {}", e))?;
-                    stream.flush()
-                        .map_err(|e| anyhow::anyhow!("Failed to flush socket: {}", e))?;
-                    Ok(())
+                    self.link_down.store(true, std::sync::atomic::Ordering::Relaxed);
+                    self.ensure_stream().and_then(|stream| {
+                        stream.write_all(cmd_with_newline.as_bytes())
+                            .map_err(|e| anyhow::anyhow!("Failed to write command to socket: {}", e))?;
+                        stream.flush()
+                            .map_err(|e| anyhow::anyhow!("Failed to flush socket: {}", e))
+                    })
                 } else {
                     stream.flush()
                         .map_err(|e| anyhow::anyhow!("Failed to flush socket: {}", e))
                 }
             }
             Err(e) => Err(e),
+        };
+        match result {
+            Err(_) if self.queue_if_allowed(cmd, &cmd_with_newline) => Ok(()),
+            other => other,
         }
     }
     
+    /// send_command for the operations layer, which sees socket failures as StepperLink errors
+    fn send_for_operation(&mut self, cmd: &str) -> error::Result<()> {
+        #[cfg(feature = "fault-injection")]
+        if let Some(faults) = fault_injection::active() {
+            faults.stepper_call(cmd)?;
+        }
+        self.send_command(cmd).map_err(|e| error::Error::StepperLink(e.to_string()))
+    }
+
     /// Read current positions from stepper_gui (not implemented - positions tracked locally)
     /// For now, we'll track positions locally as we move steppers
     fn _get_positions(&self) -> Result<Vec<i32>> {
@@ -122,9 +243,8 @@ impl ArduinoStepperOps {
 
     fn fetch_x_step_from_socket(socket_path: &str) -> Result<i32> {
         use std::io::{BufRead, BufReader, Write};
-        use std::os::unix::net::UnixStream;
 
-        let mut stream = UnixStream::connect(socket_path)
+        let mut stream = stepper_link::connect(socket_path)
             .map_err(|e| anyhow::anyhow!("Failed to connect to stepper_gui socket at {}: {}", socket_path, e))?;
         stream
             .write_all(b"get_x_step\n")
@@ -145,11 +265,103 @@ impl ArduinoStepperOps {
             .map_err(|e| anyhow::anyhow!("Failed to parse x_step response '{}': {}", response.trim(), e))
     }
 
+    /// Ask stepper_gui for board connection state ("get_status")
+    fn fetch_status_from_socket(socket_path: &str) -> Result<StepperStatus> {
+        use std::io::{BufRead, BufReader, Write};
+
+        let mut stream = stepper_link::connect(socket_path)
+            .map_err(|e| anyhow::anyhow!("Failed to connect to stepper_gui socket at {}: {}", socket_path, e))?;
+        stream
+            .write_all(b"get_status\n")
+            .and_then(|_| stream.flush())
+            .map_err(|e| anyhow::anyhow!("Failed to request status: {}", e))?;
+
+        let mut reader = BufReader::new(stream);
+        let mut response = String::new();
+        reader
+            .read_line(&mut response)
+            .map_err(|e| anyhow::anyhow!("Failed to read status response: {}", e))?;
+        let mut tokens = response.split_whitespace();
+        if tokens.next() != Some("status") {
+            return Err(anyhow::anyhow!("Unexpected status response '{}'", response.trim()));
+        }
+        let mut status = StepperStatus { main_connected: false, tuner_connected: None, slipping: 0, main_stale: false, tuner_stale: false, startup_mismatch: false };
+        for token in tokens {
+            match token.split_once('=') {
+                Some(("main", v)) => status.main_connected = v == "up",
+                Some(("tuner", "none")) => status.tuner_connected = None,
+                Some(("tuner", v)) => status.tuner_connected = Some(v == "up"),
+                Some(("slipping", v)) => status.slipping = v.parse().unwrap_or(0),
+                Some(("positions", v)) => status.main_stale = v == "stale",
+                Some(("tuner_positions", v)) => status.tuner_stale = v == "stale",
+                Some(("startup", v)) => status.startup_mismatch = v == "mismatch",
+                _ => {}
+            }
+        }
+        Ok(status)
+    }
+
+    /// Swap hello lines with stepper_gui (see build_info.rs)
+    fn fetch_hello_from_socket(socket_path: &str) -> Result<build_info::BuildInfo> {
+        use std::io::{BufRead, BufReader, Write};
+
+        let mut stream = stepper_link::connect(socket_path)
+            .map_err(|e| anyhow::anyhow!("Failed to connect to stepper_gui socket at {}: {}", socket_path, e))?;
+        let hello = build_info::BuildInfo::local("operations_gui").hello_line();
+        stream
+            .write_all(format!("{}\n", hello).as_bytes())
+            .and_then(|_| stream.flush())
+            .map_err(|e| anyhow::anyhow!("Failed to send hello: {}", e))?;
+
+        let mut reader = BufReader::new(stream);
+        let mut response = String::new();
+        reader
+            .read_line(&mut response)
+            .map_err(|e| anyhow::anyhow!("Failed to read hello response: {}", e))?;
+        build_info::BuildInfo::parse_hello(&response)
+            .ok_or_else(|| anyhow::anyhow!("Unexpected hello response '{}'", response.trim()))
+    }
+
+    /// Poll stepper_gui's board status for the health panel (None while unreachable).
+    /// Greets it once per connection so a restarted stepper_gui with another build is noticed.
+    fn spawn_status_poller(
+        threads: &mut BackgroundThreads,
+        socket_path: String,
+        status: Arc<Mutex<Option<StepperStatus>>>,
+        build: Arc<Mutex<Option<build_info::BuildInfo>>>,
+    ) {
+        threads.spawn("stepper status poller", move |shutdown| while !shutdown.is_set() {
+            let fresh = Self::fetch_status_from_socket(&socket_path).ok();
+            let known = build.lock().map(|b| b.is_some()).unwrap_or(true);
+            if fresh.is_none() {
+                if let Ok(mut slot) = build.lock() {
+                    *slot = None;
+                }
+            } else if !known {
+                match Self::fetch_hello_from_socket(&socket_path) {
+                    Ok(peer) => {
+                        tracing::info!("stepper_gui is {}", peer.describe());
+                        for problem in build_info::BuildInfo::local("operations_gui").mismatches(&peer) {
+                            tracing::warn!("{}", problem);
+                        }
+                        if let Ok(mut slot) = build.lock() {
+                            *slot = Some(peer);
+                        }
+                    }
+                    Err(e) => tracing::warn!("Version handshake with stepper_gui failed: {}", e),
+                }
+            }
+            if let Ok(mut slot) = status.lock() {
+                *slot = fresh;
+            }
+            shutdown.sleep(Duration::from_secs(2));
+        });
+    }
+
     fn fetch_positions_from_socket(socket_path: &str) -> Result<Vec<i32>> {
         use std::io::{BufRead, BufReader, Write};
-        use std::os::unix::net::UnixStream;
 
-        let mut stream = UnixStream::connect(socket_path)
+        let mut stream = stepper_link::connect(socket_path)
             .map_err(|e| anyhow::anyhow!("Failed to connect to stepper_gui socket at {}: {}", socket_path, e))?;
         stream
             .write_all(b"get_positions\n")
@@ -169,6 +381,124 @@ impl ArduinoStepperOps {
         Self::parse_positions_response(&response)
     }
 
+    /// Keep `positions` in sync from stepper_gui's pushed updates ("subscribe positions").
+    /// Reconnects in the background; `live` is true while a subscription is open and
+    /// `link_down` is raised when an open subscription drops. stepper_gui answers a new
+    /// subscription with the current positions, so a reconnect also refreshes them.
+    fn spawn_position_subscriber(
+        threads: &mut BackgroundThreads,
+        socket_path: String,
+        positions: Arc<Mutex<std::collections::HashMap<usize, i32>>>,
+        live: Arc<AtomicBool>,
+        link_down: Arc<AtomicBool>,
+    ) {
+        threads.spawn("position subscriber", move |shutdown| {
+            use std::io::Write;
+            // Long waits back off to one check every few seconds
+            let wait = readiness::WaitOptions {
+                timeout: Duration::from_secs(60),
+                max_interval: Duration::from_secs(2),
+                ..readiness::WaitOptions::default()
+            };
+            while !shutdown.is_set() {
+                if !readiness::wait_until(wait, || shutdown.is_set() || stepper_link::target_ready(&socket_path)) || shutdown.is_set() {
+                    continue;
+                }
+                if let Ok(mut stream) = stepper_link::connect(&socket_path) {
+                    if stream.write_all(b"subscribe positions\n").and_then(|_| stream.flush()).is_ok() {
+                        live.store(true, std::sync::atomic::Ordering::Relaxed);
+                        link_down.store(false, std::sync::atomic::Ordering::Relaxed);
+                        Self::follow_subscription(stream, &shutdown, |line| {
+                            if let Ok(fresh) = Self::parse_positions_response(line) {
+                                if let Ok(mut map) = positions.lock() {
+                                    for (idx, pos) in fresh.iter().enumerate() {
+                                        map.insert(idx, *pos);
+                                    }
+                                }
+                            }
+                        });
+                        live.store(false, std::sync::atomic::Ordering::Relaxed);
+                        if shutdown.is_set() {
+                            break;
+                        }
+                        link_down.store(true, std::sync::atomic::Ordering::Relaxed);
+                        tracing::warn!("Position subscription to {} dropped; resubscribing", socket_path);
+                    }
+                }
+                shutdown.sleep(Duration::from_secs(2));
+            }
+        });
+    }
+
+    /// Keep `shared` up to date from stepper_gui's pushed shared state ("subscribe shared"),
+    /// reconnecting in the background like the position subscription
+    fn spawn_shared_subscriber(threads: &mut BackgroundThreads, socket_path: String, shared: Arc<Mutex<Option<shared_state::SharedState>>>) {
+        threads.spawn("shared state subscriber", move |shutdown| {
+            use std::io::Write;
+            let wait = readiness::WaitOptions {
+                timeout: Duration::from_secs(60),
+                max_interval: Duration::from_secs(2),
+                ..readiness::WaitOptions::default()
+            };
+            while !shutdown.is_set() {
+                if !readiness::wait_until(wait, || shutdown.is_set() || stepper_link::target_ready(&socket_path)) || shutdown.is_set() {
+                    continue;
+                }
+                if let Ok(mut stream) = stepper_link::connect(&socket_path) {
+                    if stream.write_all(b"subscribe shared\n").and_then(|_| stream.flush()).is_ok() {
+                        Self::follow_subscription(stream, &shutdown, |line| match shared_state::SharedState::parse_line(line) {
+                            Ok(state) => {
+                                if let Ok(mut slot) = shared.lock() {
+                                    *slot = Some(state);
+                                }
+                            }
+                            Err(e) => tracing::warn!("Ignoring shared state update: {}", e),
+                        });
+                        if shutdown.is_set() {
+                            break;
+                        }
+                        tracing::warn!("Shared state subscription to {} dropped; resubscribing", socket_path);
+                    }
+                }
+                shutdown.sleep(Duration::from_secs(2));
+            }
+        });
+    }
+
+    /// Hand each pushed line (without its newline) to `on_line` until the subscription
+    /// drops or shutdown is requested; reads time out so a quiet link still sees the flag
+    fn follow_subscription(stream: LinkStream, shutdown: &ShutdownFlag, mut on_line: impl FnMut(&str)) {
+        use std::io::{BufRead, BufReader, ErrorKind};
+        if stream.set_read_timeout(Some(SUBSCRIPTION_POLL)).is_err() {
+            return;
+        }
+        let mut reader = BufReader::new(stream);
+        // Bytes of a line survive a timeout part way through it
+        let mut line = Vec::new();
+        while !shutdown.is_set() {
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) => break,
+                Ok(_) => {
+                    on_line(String::from_utf8_lossy(&line).trim_end_matches(['\r', '\n']));
+                    line.clear();
+                }
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+                Err(_) => break,
+            }
+        }
+    }
+
+    /// Send one command on a connection of its own, so it need not wait for a running operation
+    fn send_oneshot(socket_path: &str, cmd: &str) -> Result<()> {
+        use std::io::Write;
+        let mut stream = stepper_link::connect(socket_path)
+            .map_err(|e| anyhow::anyhow!("Failed to connect to stepper_gui socket at {}: {}", socket_path, e))?;
+        stream
+            .write_all(format!("{}\n", cmd).as_bytes())
+            .and_then(|_| stream.flush())
+            .map_err(|e| anyhow::anyhow!("Failed to send '{}': {}", cmd, e))
+    }
+
     fn parse_positions_response(response: &str) -> Result<Vec<i32>> {
         let mut tokens = response.trim().split_whitespace();
         match tokens.next() {
@@ -206,6 +536,14 @@ impl ArduinoStepperOps {
                 }
                 Ok(positions)
             }
+            // stepper_gui can't read the board: "error timeout <ms>" or "error port <message>"
+            Some("error") => match tokens.next() {
+                Some("timeout") => {
+                    let ms = tokens.next().and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+                    Err(error::Error::SerialTimeout { what: "positions".to_string(), after: Duration::from_millis(ms) }.into())
+                }
+                _ => Err(anyhow::anyhow!("stepper_gui cannot read positions: {}", response.trim())),
+            },
             Some(other) => Err(anyhow::anyhow!("Unexpected positions response '{}'", other)),
             None => Err(anyhow::anyhow!("Empty positions response")),
         }
@@ -213,30 +551,53 @@ impl ArduinoStepperOps {
 }
 
 impl operations::StepperOperations for ArduinoStepperOps {
-    fn rel_move(&mut self, stepper: usize, delta: i32) -> Result<()> {
-        self.send_command(&format!("rel_move {} {}", stepper, delta))
+    fn rel_move(&mut self, stepper: usize, delta: i32) -> error::Result<()> {
+        self.send_for_operation(&format!("rel_move {} {}", stepper, delta))
     }
     
-    fn abs_move(&mut self, stepper: usize, position: i32) -> Result<()> {
-        self.send_command(&format!("abs_move {} {}", stepper, position))
+    fn abs_move(&mut self, stepper: usize, position: i32) -> error::Result<()> {
+        self.send_for_operation(&format!("abs_move {} {}", stepper, position))
     }
     
-    fn reset(&mut self, stepper: usize, position: i32) -> Result<()> {
-        self.send_command(&format!("reset {} {}", stepper, position))
+    fn reset(&mut self, stepper: usize, position: i32) -> error::Result<()> {
+        self.send_for_operation(&format!("reset {} {}", stepper, position))
     }
     
-    fn disable(&mut self, _stepper: usize) -> Result<()> {
-        // Disable is handled by setting enable state in operations, not a direct Arduino command
-        Ok(())
+    fn disable(&mut self, stepper: usize) -> error::Result<()> {
+        // stepper_gui locks the stepper out of moves and cuts its driver current
+        self.send_for_operation(&format!("disable {}", stepper))
+    }
+    
+    fn enable(&mut self, stepper: usize) -> error::Result<()> {
+        self.send_for_operation(&format!("enable {}", stepper))
+    }
+    
+    fn set_speed(&mut self, stepper: usize, speed: i32) -> error::Result<()> {
+        self.send_for_operation(&format!("set_speed {} {}", stepper, speed))
+    }
+    
+    fn set_bow_speed(&mut self, string_idx: usize, speed: u32) -> error::Result<()> {
+        self.send_for_operation(&format!("bow_speed {} {}", string_idx, speed))
+    }
+    
+    fn damp(&mut self, string_idx: usize, engaged: bool) -> error::Result<()> {
+        self.send_for_operation(&format!("damp {} {}", string_idx, engaged as u8))
+    }
+    
+    fn reported_positions(&mut self) -> error::Result<Option<Vec<i32>>> {
+        Self::fetch_positions_from_socket(&self.socket_path)
+            .map(Some)
+            .map_err(|e| error::Error::StepperLink(e.to_string()))
     }
 }
 
 /// Operations GUI state
 pub struct OperationsGUI {
+    hostname: String, // Host profile this instrument was configured from
     pub operations: Arc<RwLock<operations::Operations>>,
     message: String,
-    pub partials_slot: PartialsSlot,
-    partials_per_channel: Arc<AtomicUsize>,
+    audmon: AnalysisHandle, // Partials slot reader (a silent feed for another machine's instrument)
+    layout_changes: std::sync::mpsc::Receiver<LayoutChange>, // audmon reconfigured mid-session
     voice_count_cap_cache: i32,
     selected_operation: String,
     arduino_ops: Option<Arc<Mutex<ArduinoStepperOps>>>,
@@ -247,6 +608,10 @@ pub struct OperationsGUI {
     voice_count_max_logger: Option<Arc<Mutex<Vec<i32>>>>,
     amp_sum_min: Vec<i32>,      // Per-channel minimum amplitude sum
     amp_sum_max: Vec<i32>,      // Per-channel maximum amplitude sum
+    height_target: (i32, i32),  // Steps above contact the Apply button turns into amp_sum thresholds
+    height_target_pending: bool, // HEIGHT_CALIBRATION.TARGET_STEPS waits for audio channels
+    height_target_bands: Vec<Option<(i32, i32)>>, // amp_sum bands the height target last set, per channel
+    effective_changed_only: bool, // Effective settings lists only what differs from the defaults
     // Track stepper positions locally (updated as we move steppers)
     stepper_positions: Arc<Mutex<std::collections::HashMap<usize, i32>>>,
     // Exit flag to signal operations to stop
@@ -254,15 +619,418 @@ pub struct OperationsGUI {
     // Operation lock to prevent concurrent execution
     pub operation_running: Arc<AtomicBool>,
     operation_task: Option<OperationTask>,
+    probe_tasks: Vec<OperationTask>, // Read-only operations, which run beside the motion one
     repeat_enabled: bool,
     repeat_pending: Option<(String, Instant)>,
+    time_limit: Option<f32>,                 // Seconds; replaces MAX_DURATION for runs started here (None = configured)
+    next_run_time_limit: Option<Duration>,   // One-off limit for the next run (an inbox command's max_duration)
+    rest_overrides: config_loader::RestOverrides,       // Rests set for runs started here (unset = YAML OPERATIONS, then globals)
+    next_run_rests: Option<config_loader::RestOverrides>, // One-off rests for the next run (an inbox command's or score cue's)
+    next_run_bump: Option<config_loader::BumpOverrides>,  // One-off bump_check strategy for the next run (an inbox command's)
     // Machine state logging
     logging_enabled: bool,
     logger: Option<machine_state_logger::MachineStateLoggingContext>,
+    state_diff: Option<StateDiffPanel>, // None without DB settings
+    bumps: BumpPanel,                   // bump_check's pressed sensors against the audio level
+    // Lines captured by the tracing GUI layer, shown in the Log panel
+    log_buffer: logging::LogBuffer,
+    // stepper_gui link state, shared with ArduinoStepperOps (None without an Arduino)
+    stepper_link_down: Option<Arc<AtomicBool>>,
+    stepper_queue_when_down: Option<Arc<AtomicBool>>,
+    // Health panel inputs
+    stepper_status: Arc<Mutex<Option<StepperStatus>>>,
+    stepper_build: Arc<Mutex<Option<build_info::BuildInfo>>>, // stepper_gui's answer to our hello
+    last_operation: Option<(String, operations::OperationOutcome, Instant)>,  // (operation, outcome, finished)
+    last_probe: Option<(String, operations::OperationOutcome)>, // Kept apart, as probes finish during other operations
+    health_cache: Option<(Instant, Vec<health::SubsystemHealth>)>,
+    // Named parameter sets from the host's PRESETS block
+    presets: Vec<(String, config_loader::ParameterPreset)>,
+    active_preset: Option<String>,
+    preset_name: String,
+    role_lock: role::RoleLock, // Performer / technician mode (shared with stepper_gui in master_gui)
+    autostart: Option<Autostart>, // Host's AUTOSTART sequence until it finishes or stops
+    inbox: Option<CommandInbox>,  // COMMAND_INBOX watcher (None without the block)
+    scores: Option<Scores>,       // SCORES watcher and transport (None without the block)
+    scenes: Scenes,               // Saved scenes for the Scenes panel
+    status_file: Option<StatusFile>, // STATUS_FILE writer (None without the block)
+    alert_watch: AlertWatch,          // Previous states for serial_lost / string_break alerts
+    idle_park: Option<IdlePark>,      // IDLE_PARK inactivity watchdog (None without the block)
+    shared_sync: Option<SharedSync>, // Step sizes and lockout shared with stepper_gui (None without an Arduino)
+    background: BackgroundThreads, // Partials reader, stepper_gui subscriptions/poller and logger; stopped on exit or Drop
+    refresh_rates: config_loader::RefreshRates, // REFRESH_RATES: slot polling, analysis and repaint, each on its own clock
+    next_analysis: Instant, // When tick() next recomputes voice counts / amp sums
+    stage_view: Option<stage_view::StageView>, // Some while the full-screen stage view is shown (F11)
+    gui_state: Option<gui_state::GuiState>, // Window and panels across launches (None when another GUI owns the window)
+    processes: Vec<(shutdown::Target, bool)>, // Processes panel: the launch registry and what is ticked
+    process_stop: Option<Receiver<shutdown::Report>>, // Stop selected, running on its own thread
+}
+
+/// stepper_gui's shared state as last pushed, and as last applied here
+struct SharedSync {
+    socket_path: String,
+    latest: Arc<Mutex<Option<shared_state::SharedState>>>,
+    applied: Option<shared_state::SharedState>,
 }
 
 struct OperationTask {
     receiver: Receiver<OperationResult>,
+    operation: String,
+    started_at: chrono::DateTime<Utc>, // For the operations table (pre/post diffs in What Changed)
+}
+
+/// "What Changed" panel: two machine state snapshots (two times, or either side of a
+/// logged operation) read and compared on a worker thread so the UI never waits on the DB
+struct StateDiffPanel {
+    db_settings: config_loader::DbSettings,
+    by_operation: bool,
+    from: String, // "now", "-10m", "-2h" or local "YYYY-MM-DD HH:MM[:SS]"
+    to: String,
+    operations: Vec<machine_state_logger::OperationEvent>, // Newest first
+    selected: Option<usize>,
+    diff: Option<std::result::Result<state_diff::SnapshotDiff, String>>,
+    reply: Arc<Mutex<Option<StateDiffReply>>>, // Filled by the worker, taken by render
+    loading: bool,
+}
+
+enum StateDiffReply {
+    Operations(std::result::Result<Vec<machine_state_logger::OperationEvent>, String>),
+    Diff(std::result::Result<state_diff::SnapshotDiff, String>),
+}
+
+/// Recent operations listed for pre/post diffs
+const STATE_DIFF_OPERATIONS: i64 = 30;
+
+impl StateDiffPanel {
+    fn new(db_settings: config_loader::DbSettings) -> Self {
+        Self {
+            db_settings,
+            by_operation: true,
+            from: "-10m".to_string(),
+            to: "now".to_string(),
+            operations: Vec::new(),
+            selected: None,
+            diff: None,
+            reply: Arc::new(Mutex::new(None)),
+            loading: false,
+        }
+    }
+
+    /// Run `query` against a fresh DB connection on a worker thread
+    fn spawn(&mut self, query: impl FnOnce(&mut state_diff::StateHistory) -> StateDiffReply + Send + 'static) {
+        self.loading = true;
+        let db_settings = self.db_settings.clone();
+        let reply = Arc::clone(&self.reply);
+        thread::spawn(move || {
+            let answer = match state_diff::StateHistory::connect(&db_settings) {
+                Ok(mut history) => query(&mut history),
+                Err(e) => StateDiffReply::Diff(Err(e.to_string())),
+            };
+            if let Ok(mut slot) = reply.lock() {
+                *slot = Some(answer);
+            }
+        });
+    }
+
+    fn load_operations(&mut self, host: &str) {
+        let host = host.to_string();
+        self.spawn(move |history| {
+            StateDiffReply::Operations(history.recent_operations(&host, STATE_DIFF_OPERATIONS).map_err(|e| e.to_string()))
+        });
+    }
+
+    fn compare(&mut self, host: &str) {
+        if self.by_operation {
+            let Some(operation) = self.selected.and_then(|idx| self.operations.get(idx)).cloned() else {
+                self.diff = Some(Err("Pick an operation first".to_string()));
+                return;
+            };
+            self.spawn(move |history| StateDiffReply::Diff(history.diff_operation(&operation).map_err(|e| e.to_string())));
+        } else {
+            let now = Utc::now();
+            let (Some(from), Some(to)) = (state_diff::parse_time(&self.from, now), state_diff::parse_time(&self.to, now)) else {
+                self.diff = Some(Err("Times are \"now\", \"-10m\" / \"-2h\" or \"YYYY-MM-DD HH:MM\"".to_string()));
+                return;
+            };
+            let host = host.to_string();
+            self.spawn(move |history| StateDiffReply::Diff(history.diff_times(&host, from, to).map_err(|e| e.to_string())));
+        }
+    }
+
+    fn poll(&mut self) {
+        let Some(reply) = self.reply.lock().ok().and_then(|mut slot| slot.take()) else { return };
+        self.loading = false;
+        match reply {
+            StateDiffReply::Operations(Ok(operations)) => {
+                self.operations = operations;
+                self.selected = (!self.operations.is_empty()).then_some(0);
+            }
+            StateDiffReply::Operations(Err(e)) => self.diff = Some(Err(e)),
+            StateDiffReply::Diff(diff) => self.diff = Some(diff),
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, host: &str) {
+        self.poll();
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.by_operation, true, tr("Before / after an operation"));
+            ui.radio_value(&mut self.by_operation, false, tr("Between two times"));
+        });
+        if self.by_operation {
+            ui.horizontal(|ui| {
+                let label = |op: &machine_state_logger::OperationEvent| {
+                    format!("{} {} ({})", op.recorded_at.with_timezone(&chrono::Local).format("%m-%d %H:%M:%S"), op.operation_type, op.operation_status)
+                };
+                let selected_text = self.selected.and_then(|idx| self.operations.get(idx)).map_or("none loaded".to_string(), label);
+                egui::ComboBox::from_id_source("state_diff_operation")
+                    .selected_text(selected_text)
+                    .width(260.0)
+                    .show_ui(ui, |ui| {
+                        for (idx, op) in self.operations.iter().enumerate() {
+                            ui.selectable_value(&mut self.selected, Some(idx), label(op));
+                        }
+                    });
+                if ui.add_enabled(!self.loading, egui::Button::new(tr("Load"))).on_hover_text(tr("List this host's recent operations from the DB")).clicked() {
+                    self.load_operations(host);
+                }
+            });
+        } else {
+            ui.horizontal(|ui| {
+                ui.label(tr("From"));
+                ui.add(egui::TextEdit::singleline(&mut self.from).desired_width(140.0));
+                ui.label(tr("to"));
+                ui.add(egui::TextEdit::singleline(&mut self.to).desired_width(140.0));
+            })
+            .response
+            .on_hover_text(tr("\"now\", \"-10m\", \"-2h\" or local \"YYYY-MM-DD HH:MM\""));
+        }
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!self.loading, egui::Button::new(tr("Compare"))).clicked() {
+                self.compare(host);
+            }
+            if self.loading {
+                ui.spinner();
+            }
+            if let Some(Ok(diff)) = &self.diff {
+                if ui.button(tr("Copy")).clicked() {
+                    let text = diff.summary_lines().join("\n");
+                    ui.output_mut(|o| o.copied_text = text);
+                }
+            }
+        });
+        match &self.diff {
+            None => {}
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::from_rgb(220, 50, 50), e);
+            }
+            Some(Ok(diff)) => Self::show_diff(ui, diff),
+        }
+    }
+
+    fn show_diff(ui: &mut egui::Ui, diff: &state_diff::SnapshotDiff) {
+        let lines = diff.summary_lines();
+        ui.label(&lines[0]);
+        if diff.is_empty() {
+            ui.label(tr("Nothing changed"));
+            return;
+        }
+        let value = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+        if !diff.steppers.is_empty() {
+            ui.strong("Steppers");
+            egui::Grid::new("state_diff_steppers").striped(true).show(ui, |ui| {
+                for header in ["Stepper", "Before", "After", "Moved", "Enabled"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for stepper in &diff.steppers {
+                    ui.label(stepper.role.clone().map_or(stepper.index.to_string(), |r| format!("{} ({})", stepper.index, r)));
+                    ui.label(value(stepper.position.0.map(|p| p.to_string())));
+                    ui.label(value(stepper.position.1.map(|p| p.to_string())));
+                    ui.label(stepper.moved_by().filter(|&d| d != 0).map_or(String::new(), |d| format!("{:+}", d)));
+                    if stepper.enabled.0 != stepper.enabled.1 {
+                        let state = |e: Option<bool>| value(e.map(|e| if e { "on" } else { "off" }.to_string()));
+                        ui.colored_label(egui::Color32::from_rgb(255, 140, 0), format!("{} -> {}", state(stepper.enabled.0), state(stepper.enabled.1)));
+                    } else {
+                        ui.label("");
+                    }
+                    ui.end_row();
+                }
+            });
+        }
+        if !diff.settings.is_empty() {
+            ui.strong("Settings");
+            egui::Grid::new("state_diff_settings").striped(true).show(ui, |ui| {
+                for setting in &diff.settings {
+                    ui.label(&setting.name);
+                    ui.label(format!("{} -> {}", setting.from, setting.to));
+                    ui.end_row();
+                }
+            });
+        }
+        if !diff.channels.is_empty() {
+            ui.strong("Analysis");
+            egui::Grid::new("state_diff_channels").striped(true).show(ui, |ui| {
+                for header in ["Channel", "Voices", "Amp sum"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for shift in &diff.channels {
+                    ui.label((shift.channel + 1).to_string());
+                    ui.label(format!("{} -> {}", value(shift.voice_count.0.map(|v| v.to_string())), value(shift.voice_count.1.map(|v| v.to_string()))));
+                    ui.label(format!(
+                        "{} -> {}",
+                        value(shift.amp_sum.0.map(|a| format!("{:.2}", a))),
+                        value(shift.amp_sum.1.map(|a| format!("{:.2}", a)))
+                    ));
+                    ui.end_row();
+                }
+            });
+        }
+    }
+}
+
+/// "Bumps" panel: the touch sensors bump_check found pressed, per string, against
+/// the channel's level, from this session or read back from the bump_events table
+struct BumpPanel {
+    session: Vec<bump_log::BumpEvent>, // Oldest first, at most BUMP_SESSION_EVENTS
+    db_settings: Option<config_loader::DbSettings>, // None without the Postgres backend
+    from_db: bool,
+    since: String, // As in What Changed: "-24h", "-30m" or local "YYYY-MM-DD HH:MM"
+    loaded: Option<BumpReply>,
+    reply: Arc<Mutex<Option<BumpReply>>>, // Filled by the worker, taken by show
+    loading: bool,
+}
+
+type BumpReply = std::result::Result<Vec<bump_log::BumpEvent>, String>;
+
+/// Bumps kept for the session view
+const BUMP_SESSION_EVENTS: usize = 500;
+/// Latest bumps listed under the per-string table
+const BUMP_RECENT_EVENTS: usize = 10;
+
+impl BumpPanel {
+    fn new(db_settings: Option<config_loader::DbSettings>) -> Self {
+        Self {
+            session: Vec::new(),
+            db_settings,
+            from_db: false,
+            since: "-24h".to_string(),
+            loaded: None,
+            reply: Arc::new(Mutex::new(None)),
+            loading: false,
+        }
+    }
+
+    fn push(&mut self, event: bump_log::BumpEvent) {
+        if self.session.len() >= BUMP_SESSION_EVENTS {
+            self.session.remove(0);
+        }
+        self.session.push(event);
+    }
+
+    /// Read the host's bumps since `since` on a worker thread
+    fn load(&mut self, host: &str) {
+        let Some(db_settings) = self.db_settings.clone() else { return };
+        let Some(since) = state_diff::parse_time(&self.since, Utc::now()) else {
+            self.loaded = Some(Err("Times are \"-24h\", \"-30m\" or \"YYYY-MM-DD HH:MM\"".to_string()));
+            return;
+        };
+        self.loading = true;
+        let host = host.to_string();
+        let reply = Arc::clone(&self.reply);
+        thread::spawn(move || {
+            let answer = state_diff::StateHistory::connect(&db_settings)
+                .and_then(|mut history| history.bump_events(&host, since))
+                .map_err(|e| e.to_string());
+            if let Ok(mut slot) = reply.lock() {
+                *slot = Some(answer);
+            }
+        });
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, host: &str) {
+        if let Some(answer) = self.reply.lock().ok().and_then(|mut slot| slot.take()) {
+            self.loading = false;
+            self.loaded = Some(answer);
+        }
+        if self.db_settings.is_some() {
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.from_db, false, tr("This session"));
+                ui.radio_value(&mut self.from_db, true, tr("History since"));
+                ui.add_enabled(self.from_db, egui::TextEdit::singleline(&mut self.since).desired_width(140.0));
+                if ui.add_enabled(self.from_db && !self.loading, egui::Button::new(tr("Load"))).clicked() {
+                    self.load(host);
+                }
+                if self.loading {
+                    ui.spinner();
+                }
+            });
+        }
+        let events: &[bump_log::BumpEvent] = if self.from_db {
+            match &self.loaded {
+                Some(Ok(events)) => events,
+                Some(Err(e)) => {
+                    ui.colored_label(egui::Color32::from_rgb(220, 50, 50), e);
+                    return;
+                }
+                None => &[],
+            }
+        } else {
+            &self.session
+        };
+        if events.is_empty() {
+            ui.label(tr("No bumps recorded"));
+            return;
+        }
+        if ui.button(tr("Copy")).clicked() {
+            let text = bump_log::report_lines(events).join("\n");
+            ui.output_mut(|o| o.copied_text = text);
+        }
+        ui.label(trf("A level at least {0}% below the seconds before counts as contact", &[&format!("{:.0}", bump_log::DIP_RATIO * 100.0)]));
+        egui::Grid::new("bump_strings").striped(true).show(ui, |ui| {
+            for header in ["String", "Bumps", "Contact", "Noise", "Unknown", "Mean dip", "z_down_step"] {
+                ui.strong(header);
+            }
+            ui.end_row();
+            for stats in bump_log::correlate(events) {
+                ui.label(stats.label());
+                ui.label(stats.bumps.to_string());
+                ui.label(stats.contact.to_string());
+                ui.label(stats.noise.to_string());
+                ui.label(stats.unknown.to_string());
+                ui.label(stats.mean_dip.map_or("-".to_string(), |d| format!("{:.0}%", d * 100.0)));
+                let step = stats.z_down_step.to_string();
+                let advice = stats.advice();
+                match stats.suggested_z_down_step {
+                    Some(suggested) => ui.colored_label(egui::Color32::from_rgb(255, 140, 0), format!("{} -> {}", step, suggested)),
+                    None => ui.label(step),
+                }
+                .on_hover_text(advice);
+                ui.end_row();
+            }
+        });
+        ui.strong(tr("Latest"));
+        egui::Grid::new("bump_events").striped(true).show(ui, |ui| {
+            for header in ["Time", "Stepper", "X", "Z", "Amp sum", "Before", "Verdict"] {
+                ui.strong(header);
+            }
+            ui.end_row();
+            let value = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+            for event in events.iter().rev().take(BUMP_RECENT_EVENTS) {
+                ui.label(event.recorded_at.with_timezone(&chrono::Local).format("%m-%d %H:%M:%S").to_string());
+                ui.label(event.stepper.to_string());
+                ui.label(value(event.x_position.map(|x| x.to_string())));
+                ui.label(event.z_position.to_string());
+                ui.label(value(event.amp_sum.map(|a| format!("{:.2}", a))));
+                ui.label(value(event.amp_baseline.map(|a| format!("{:.2}", a))));
+                let verdict = event.verdict();
+                match verdict {
+                    bump_log::Verdict::Contact => ui.colored_label(egui::Color32::from_rgb(255, 140, 0), verdict.name()),
+                    _ => ui.label(verdict.name()),
+                };
+                ui.end_row();
+            }
+        });
+    }
 }
 
 struct OperationResult {
@@ -270,65 +1038,137 @@ struct OperationResult {
     message: String,
     updated_positions: std::collections::HashMap<usize, i32>,
     is_progress: bool, // If true, this is a progress update (append immediately), if false, it's the final result
+    outcome: operations::OperationOutcome, // Outcome of the final result (always Succeeded for progress updates)
+}
+
+/// STATUS_FILE settings and write schedule
+struct StatusFile {
+    settings: config_loader::StatusFileSettings,
+    next_write: Instant,
+    failing: bool, // Last write failed (warned once until it works again)
+}
+
+/// What the alert checks saw last tick, so alerts fire on changes only
+#[derive(Default)]
+struct AlertWatch {
+    link_down: bool,
+    main_connected: Option<bool>,
+    tuner_connected: Option<bool>,
+    sounded: Vec<bool>,                 // Per channel: has sounded since its last string_break alert
+    silent_since: Vec<Option<Instant>>, // Per channel: when it went silent while others sound
+}
+
+/// IDLE_PARK watchdog: parks once nothing has happened for the timeout
+struct IdlePark {
+    settings: config_loader::IdleParkSettings,
+    last_activity: Instant, // Last operation, GUI input or stepper movement
+    last_positions: std::collections::HashMap<usize, i32>, // Moves made elsewhere (stepper_gui) count as activity
+}
+
+/// COMMAND_INBOX watcher and the command file being run
+struct CommandInbox {
+    settings: config_loader::CommandInboxSettings,
+    access: access::AccessPolicy, // IPC_AUTH: command files must carry a token allowing their operation
+    next_poll: Instant,
+    current: Option<InboxRun>,
+}
+
+struct InboxRun {
+    path: std::path::PathBuf,
+    operation: String,
+    received_at: String,
+    log_start: usize, // Message log length when the operation started
+}
+
+/// SCORES watcher and the transport of the score being played
+struct Scores {
+    settings: config_loader::ScoreSettings,
+    next_scan: Instant,
+    seen: std::collections::HashMap<std::path::PathBuf, std::time::SystemTime>, // Score files as last read (modified time)
+    loaded: Vec<(std::path::PathBuf, score::Score)>, // Scores that parsed and name only known operations and presets
+    transport: Option<(std::path::PathBuf, score::Transport)>,
+    midi: Option<Receiver<score::MidiMessage>>, // MIDI_CLOCK messages from the reader thread
+}
+
+/// Saved scenes (see scene) and the Scenes panel's name field
+struct Scenes {
+    dir: std::path::PathBuf,
+    saved: Vec<scene::Scene>,
+    name: String,
+}
+
+/// Progress through the host's AUTOSTART sequence
+struct Autostart {
+    pending: std::collections::VecDeque<config_loader::AutostartStep>,
+    total: usize,
+    current: Option<(config_loader::AutostartStep, Instant)>, // Running step and when it started
+    started: bool,          // Readiness passed and the first step was launched
+    next_check: Instant,    // Next readiness check while waiting
+    waiting_reported: bool, // The blockers have been logged once
 }
 
 impl OperationsGUI {
-    /// Create a new OperationsGUI instance
+    /// Create a new OperationsGUI instance for this machine's instrument
     pub fn new() -> Result<Self> {
-        // Create a partials slot for shared memory updates
-        let partials_slot: PartialsSlot = Arc::new(Mutex::new(None));
-        let partials_per_channel = Arc::new(AtomicUsize::new(12));
-        
-        // Get config to know how many channels to read and Arduino port
         let hostname = gethostname::gethostname().to_string_lossy().to_string();
+        Self::for_instrument(&hostname, None)
+    }
+
+    /// Create an OperationsGUI driving `hostname`'s instrument. `stepper_socket`
+    /// replaces the socket derived from its ARD_PORT, e.g. a stepper_gui socket
+    /// forwarded from another machine or a `host:port` bridge address. Only this machine's own instrument is fed
+    /// from the local audio analysis; the others see no partials, so audio-driven
+    /// moves stop on the staleness interlock instead of following the wrong strings.
+    pub fn for_instrument(hostname: &str, stepper_socket: Option<&str>) -> Result<Self> {
+        // Get config to know how many channels to read and Arduino port
+        let hostname = hostname.to_string();
+        let is_local = hostname == gethostname::gethostname().to_string_lossy();
         let ard_settings = config_loader::load_arduino_settings(&hostname)?;
         let _string_num = ard_settings.string_num; // Not used - we use actual channel count instead
         let port_path = ard_settings.port.clone();
+        let presets = config_loader::load_presets(&hostname)?;
+        let autostart_steps = config_loader::load_autostart(&hostname)?;
+        let inbox_settings = config_loader::load_command_inbox(&hostname)?;
+        let score_settings = config_loader::load_score_settings(&hostname)?;
+        let scene_dir = config_loader::load_scene_dir(&hostname)?;
+        let status_file = config_loader::load_status_file(&hostname)?;
+        let idle_park = config_loader::load_idle_park(&hostname)?;
+        let refresh_rates = config_loader::load_refresh_rates(&hostname)?;
+        let ipc_auth = config_loader::load_ipc_auth(&hostname)?;
+        let inbox_access = command_inbox::access_policy(ipc_auth.as_ref());
         
-        // Create operations with the partials slot (wrap in Arc<Mutex> for sharing with logging thread)
-        let operations = Arc::new(RwLock::new(operations::Operations::new_with_partials_slot(Some(Arc::clone(&partials_slot)))?));
+        // audmon's partials reach Operations' analysis through the slot reader's triple buffer
+        let mut audmon = if is_local {
+            AudmonClient::spawn(AudmonConfig { slot_poll: config_loader::RefreshRates::interval(refresh_rates.slot_poll_hz), ..AudmonConfig::default() })
+        } else {
+            AudmonClient::silent()
+        };
         
-        // Create Arduino stepper operations client (connects via IPC to stepper_gui's connection)
-        // Only create if Arduino port is configured
-        let arduino_ops = port_path.as_ref()
-            .map(|p| Arc::new(Mutex::new(ArduinoStepperOps::new(p))))
-            .map(Some)
-            .unwrap_or(None);
+        // Create operations reading the partials feed (wrap in Arc<RwLock> for sharing with logging thread)
+        let operations = Arc::new(RwLock::new(operations::Operations::for_host(&hostname, audmon.take_reader())?));
         
-        // Spawn a thread to periodically update the partials slot from shared memory
-        let partials_slot_thread = Arc::clone(&partials_slot);
-        let partials_detected_for_thread = Arc::clone(&partials_per_channel);
-        thread::spawn(move || {
-            loop {
-                let partial_hint = std::cmp::max(
-                    1,
-                    partials_detected_for_thread.load(std::sync::atomic::Ordering::Relaxed),
-                );
-                // Read from shared memory and update the slot
-                // Use large number to read all available channels (not limited by string_num)
-                // The function will read actual_channels_written from control file and limit to that
-                const LARGE_CHANNEL_HINT: usize = 100; // Large enough to read all available channels
-                if let Some(partials) = operations::Operations::read_partials_from_shared_memory(
-                    LARGE_CHANNEL_HINT,
-                    partial_hint,
-                ) {
-                    if let Ok(mut slot) = partials_slot_thread.lock() {
-                        *slot = Some(partials.clone());
-                    }
-                    let observed = partials
-                        .iter()
-                        .map(|channel| channel.len())
-                        .max()
-                        .unwrap_or(0);
-                    if observed > 0 {
-                        partials_detected_for_thread
-                            .store(observed, std::sync::atomic::Ordering::Relaxed);
-                    }
-                }
-                // Update at ~60 Hz to match GUI frame rate
-                thread::sleep(Duration::from_millis(16));
+        // Create Arduino stepper operations client (connects via IPC to stepper_gui's connection)
+        // Only create if Arduino port (or an explicit socket) is configured
+        // STEPPER_BRIDGE.CONNECT reaches a stepper_gui on another machine over TCP
+        let bridge = config_loader::load_stepper_bridge(&hostname)?;
+        let stepper_socket = stepper_socket
+            .map(str::to_string)
+            .or_else(|| bridge.as_ref().and_then(|b| b.connect.clone()));
+        let stepper_target = stepper_socket.or_else(|| port_path.as_deref().map(ArduinoStepperOps::socket_path_for_port));
+        if let Some(target) = stepper_target.as_deref() {
+            // IPC_AUTH: present this GUI's own token (client "operations_gui"); a bridge also takes its TOKEN
+            let own_token = ipc_auth.as_ref().and_then(|a| a.clients.iter().find(|c| c.name == "operations_gui"));
+            match (own_token, bridge.as_ref()) {
+                (Some(client), _) => stepper_link::register_token(target, &client.token),
+                (None, Some(bridge)) if stepper_link::is_bridge(target) => stepper_link::register_token(target, &bridge.token),
+                _ => {}
             }
-        });
+        }
+        let arduino_ops = stepper_target.map(ArduinoStepperOps::with_socket_path)
+        .map(|ops| Arc::new(Mutex::new(ops)));
+        
+        // Every loop below runs on `background` and stops with this GUI (see lifecycle)
+        let mut background = BackgroundThreads::new();
         
         // Initialize thresholds with defaults
         // Get actual channel count from operations (will be 0 initially, will grow when audio data arrives)
@@ -336,13 +1176,14 @@ impl OperationsGUI {
             let ops = operations.read().unwrap();
             ops.get_voice_count().len().max(ops.get_amp_sum().len())
         };
-        let voice_count_cap = std::cmp::max(1, partials_per_channel.load(std::sync::atomic::Ordering::Relaxed) as i32);
+        let voice_count_cap = std::cmp::max(1, audmon.partials_per_channel() as i32);
         let voice_count_min_default = std::cmp::min(2, voice_count_cap);
         // Initialize with actual channel count (will be 0 if no audio yet, will resize dynamically)
         let voice_count_min = vec![voice_count_min_default; initial_channel_count];
         let voice_count_max = vec![voice_count_cap; initial_channel_count];
         let amp_sum_min = vec![20; initial_channel_count];
         let amp_sum_max = vec![250; initial_channel_count];
+        let configured_height_target = operations.read().unwrap().height_settings.target;
         let stepper_positions: Arc<Mutex<std::collections::HashMap<usize, i32>>> = Arc::new(Mutex::new(std::collections::HashMap::new()));
         {
             let enabled_snapshot = operations.read().unwrap().get_all_stepper_enabled();
@@ -353,6 +1194,31 @@ impl OperationsGUI {
             }
         }
         
+        // Receive pushed position updates from stepper_gui instead of polling for them
+        let positions_live = Arc::new(AtomicBool::new(false));
+        let stepper_status = Arc::new(Mutex::new(None));
+        let stepper_build = Arc::new(Mutex::new(None));
+        let mut stepper_link_down = None;
+        let mut stepper_queue_when_down = None;
+        let mut shared_sync = None;
+        if let Some(arduino_ops_ref) = arduino_ops.as_ref() {
+            if let Ok(ops_guard) = arduino_ops_ref.lock() {
+                let latest = Arc::new(Mutex::new(None));
+                ArduinoStepperOps::spawn_shared_subscriber(&mut background, ops_guard.socket_path(), Arc::clone(&latest));
+                shared_sync = Some(SharedSync { socket_path: ops_guard.socket_path(), latest, applied: None });
+                ArduinoStepperOps::spawn_position_subscriber(
+                    &mut background,
+                    ops_guard.socket_path(),
+                    Arc::clone(&stepper_positions),
+                    Arc::clone(&positions_live),
+                    ops_guard.link_down_flag(),
+                );
+                stepper_link_down = Some(ops_guard.link_down_flag());
+                stepper_queue_when_down = Some(ops_guard.queue_when_down_flag());
+                ArduinoStepperOps::spawn_status_poller(&mut background, ops_guard.socket_path(), Arc::clone(&stepper_status), Arc::clone(&stepper_build));
+            }
+        }
+        
         let stepper_roles_metadata = Arc::new({
             let ops_guard = operations.read().unwrap();
             let total_steppers = ard_settings.num_steppers.unwrap_or(0);
@@ -361,14 +1227,22 @@ impl OperationsGUI {
 
         // Initialize machine state logging (non-blocking, optional functionality)
         // If database configuration is missing, logging is disabled (not a fallback - logging is optional)
-        let logger: Option<machine_state_logger::MachineStateLoggingContext> = 
-            match crate::config_loader::DbSettings::from_env() {
-                Ok(db_config) => Some(machine_state_logger::MachineStateLoggingContext::new_nonblocking(db_config)),
-                Err(e) => {
-                    warn!(target: "operations_gui", "Machine state logging unavailable: {}. Set DB_PASSWORD or PG_PASSWORD environment variable.", e);
-                    None
-                }
-            };
+        // LOGGING.MACHINE_STATE picks Postgres (the default), InfluxDB line protocol or both
+        let machine_state_log = config_loader::load_logging_settings(&hostname)?.machine_state;
+        let db_settings = config_loader::DbSettings::from_env();
+        let mut sinks = Vec::new();
+        if machine_state_log.postgres {
+            match &db_settings {
+                Ok(db_config) => sinks.push(machine_state_logger::SinkConfig::Postgres(db_config.clone())),
+                Err(e) => warn!(target: "operations_gui", "Machine state logging unavailable: {}. Set DB_PASSWORD or PG_PASSWORD environment variable.", e),
+            }
+        }
+        if let Some(target) = machine_state_log.influx {
+            sinks.push(machine_state_logger::SinkConfig::Influx(target));
+        }
+        let logger: Option<machine_state_logger::MachineStateLoggingContext> =
+            (!sinks.is_empty()).then(|| machine_state_logger::MachineStateLoggingContext::with_sinks(sinks));
+        let history_db = db_settings.ok().filter(|_| machine_state_log.postgres);
         let mut voice_count_min_logger_arc: Option<Arc<Mutex<Vec<i32>>>> = None;
         let mut voice_count_max_logger_arc: Option<Arc<Mutex<Vec<i32>>>> = None;
         
@@ -397,21 +1271,23 @@ impl OperationsGUI {
             } else {
                 None
             };
-            thread::spawn(move || {
+            let positions_live_for_logger = Arc::clone(&positions_live);
+            let stepper_build_for_logger = Arc::clone(&stepper_build);
+            background.spawn("machine state logger", move |shutdown| {
                 use std::time::Instant;
                 let mut last_log = Instant::now();
                 const LOG_INTERVAL: Duration = Duration::from_secs(1); // 1Hz
-                loop {
-                    thread::sleep(Duration::from_millis(100));
+                while shutdown.sleep(Duration::from_millis(100)) {
                     if Instant::now().duration_since(last_log) >= LOG_INTERVAL {
                         if logger_clone.is_enabled() {
                             // Fetch positions directly from stepper_gui (1Hz is slow enough that socket I/O overhead is negligible)
                             let mut all_positions = vec![0i32; total_steppers];
                             let mut all_enabled = vec![false; total_steppers];
                             
-                            // Fetch fresh positions directly from socket
-                            if let Some(ref socket_path) = socket_path_for_logger {
-                                if std::path::Path::new(socket_path).exists() {
+                            // Fetch fresh positions directly from socket, unless pushed updates are already live
+                            let subscribed = positions_live_for_logger.load(std::sync::atomic::Ordering::Relaxed);
+                            if let Some(socket_path) = socket_path_for_logger.as_ref().filter(|_| !subscribed) {
+                                if stepper_link::target_ready(socket_path) {
                                     if let Ok(fresh_positions) = ArduinoStepperOps::fetch_positions_from_socket(socket_path) {
                                         // Update positions array and also update cached map
                                         for (idx, &pos) in fresh_positions.iter().enumerate() {
@@ -475,6 +1351,11 @@ impl OperationsGUI {
                                     amp_sum_min: amp_min.clone(),
                                     amp_sum_max: amp_max.clone(),
                                     stepper_roles: (*stepper_roles_clone_for_logger).clone(),
+                                    channel_strings: (0..ops.get_voice_count().len()).map(|ch_idx| ops.string_for_channel(ch_idx)).collect(),
+                                    build_info: build_info::summary(
+                                        &build_info::BuildInfo::local("operations_gui"),
+                                        stepper_build_for_logger.lock().ok().and_then(|b| b.clone()).as_ref(),
+                                    ),
                                 };
                                 logger_clone.insert_machine_state(&snapshot);
                             }
@@ -485,14 +1366,36 @@ impl OperationsGUI {
             });
         }
         
+        let role_lock = role::RoleLock::for_host(&hostname);
+
+        // SCORES: MIDI_CLOCK is read on its own thread and handed over at each tick
+        let scores = score_settings.map(|settings| {
+            let midi = settings.midi_clock.clone().map(|device| {
+                let (tx, rx) = mpsc::channel();
+                background.spawn("score midi clock", move |shutdown| {
+                    if let Err(e) = score::follow_midi_clock(&device, |wait| shutdown.sleep(wait), |message| { let _ = tx.send(message); }) {
+                        tracing::warn!("SCORES: cannot follow the MIDI clock on {}: {}", device.display(), e);
+                    }
+                });
+                rx
+            });
+            Scores { settings, next_scan: Instant::now(), seen: Default::default(), loaded: Vec::new(), transport: None, midi }
+        });
+        let (saved_scenes, scene_errors) = scene::load_scenes(&scene_dir);
+        for error in scene_errors {
+            tracing::warn!("Scenes: {}", error);
+        }
+
         Ok(Self {
+            hostname,
             operations,
             message: String::new(),
             exit_flag: Arc::new(AtomicBool::new(false)),
             operation_running: Arc::new(AtomicBool::new(false)),
             operation_task: None,
-            partials_slot,
-            partials_per_channel: Arc::clone(&partials_per_channel),
+            probe_tasks: Vec::new(),
+            layout_changes: audmon.subscribe(),
+            audmon,
             voice_count_cap_cache: voice_count_cap,
             selected_operation: "None".to_string(),
             arduino_ops,
@@ -502,14 +1405,1584 @@ impl OperationsGUI {
             voice_count_max_logger: voice_count_max_logger_arc,
             amp_sum_min,
             amp_sum_max,
+            height_target: configured_height_target.unwrap_or((2, 4)),
+            height_target_pending: configured_height_target.is_some(),
+            height_target_bands: Vec::new(),
+            effective_changed_only: false,
             stepper_positions: Arc::clone(&stepper_positions),
             repeat_enabled: false,
+            time_limit: None,
+            next_run_time_limit: None,
+            rest_overrides: config_loader::RestOverrides::default(),
+            next_run_rests: None,
+            next_run_bump: None,
             repeat_pending: None,
             logging_enabled: logger.is_some(),
             logger,
+            // What Changed and the Bumps history read the Postgres tables
+            state_diff: history_db.clone().map(StateDiffPanel::new),
+            bumps: BumpPanel::new(history_db),
+            log_buffer: logging::new_buffer(),
+            stepper_link_down,
+            stepper_queue_when_down,
+            stepper_status,
+            stepper_build,
+            last_operation: None,
+            last_probe: None,
+            health_cache: None,
+            presets,
+            active_preset: None,
+            preset_name: String::new(),
+            role_lock,
+            autostart: (!autostart_steps.is_empty()).then(|| Autostart {
+                total: autostart_steps.len(),
+                pending: autostart_steps.into(),
+                current: None,
+                started: false,
+                next_check: Instant::now(),
+                waiting_reported: false,
+            }),
+            inbox: inbox_settings.map(|settings| CommandInbox { settings, access: inbox_access, next_poll: Instant::now(), current: None }),
+            scores,
+            scenes: Scenes { dir: scene_dir, saved: saved_scenes, name: String::new() },
+            status_file: status_file.map(|settings| StatusFile { settings, next_write: Instant::now(), failing: false }),
+            alert_watch: AlertWatch::default(),
+            idle_park: idle_park.map(|settings| IdlePark { settings, last_activity: Instant::now(), last_positions: Default::default() }),
+            shared_sync,
+            background,
+            refresh_rates,
+            next_analysis: Instant::now(),
+            stage_view: None,
+            gui_state: None,
+            processes: Vec::new(),
+            process_stop: None,
         })
     }
+
+    /// Stop the background threads and any running operation; the GUI is closing
+    /// (also done on Drop, so a GUI rebuilt in its place leaves nothing behind)
+    pub fn shutdown(&mut self) {
+        self.exit_flag.store(true, std::sync::atomic::Ordering::Relaxed);
+        let mut stuck = self.background.shutdown(lifecycle::SHUTDOWN_TIMEOUT);
+        stuck.extend(self.audmon.shutdown(lifecycle::SHUTDOWN_TIMEOUT));
+        if !stuck.is_empty() {
+            tracing::warn!("{}: background threads still running after shutdown: {}", self.hostname, stuck.join(", "));
+        }
+    }
+
+    /// Operations' effective settings plus the per-channel thresholds kept here: learned
+    /// while they still hold the bands the height target set, runtime once changed
+    fn effective_config(&self) -> effective_config::EffectiveConfig {
+        use effective_config::Source;
+        let mut config = self.operations.read().unwrap().effective_config();
+        let cap = self.voice_count_cap_cache.max(1);
+        let columns: [(&str, &Vec<i32>, i32); 4] = [
+            ("VOICE_COUNT_MIN", &self.voice_count_min, cap.min(2)),
+            ("VOICE_COUNT_MAX", &self.voice_count_max, cap),
+            ("AMP_SUM_MIN", &self.amp_sum_min, 20),
+            ("AMP_SUM_MAX", &self.amp_sum_max, 250),
+        ];
+        for (key, values, default) in columns {
+            for (ch_idx, &value) in values.iter().enumerate() {
+                let learned = self.height_target_bands.get(ch_idx).copied().flatten().is_some_and(|(min, max)| match key {
+                    "AMP_SUM_MIN" => value == min,
+                    "AMP_SUM_MAX" => value == max,
+                    _ => false,
+                });
+                let source = if learned {
+                    Source::Learned
+                } else if value == default {
+                    Source::Default
+                } else {
+                    Source::Runtime
+                };
+                config.push("Thresholds", &format!("{} {}", key, self.channel_label(ch_idx)), value.to_string(), source);
+            }
+        }
+        config
+    }
+
+    fn current_thresholds(&self) -> profile::ProfileThresholds {
+        profile::ProfileThresholds {
+            voice_count_min: self.voice_count_min.clone(),
+            voice_count_max: self.voice_count_max.clone(),
+            amp_sum_min: self.amp_sum_min.clone(),
+            amp_sum_max: self.amp_sum_max.clone(),
+        }
+    }
+
+    /// Form for the parameters `op` reads; changes apply as a preset naming just that key,
+    /// or for a custom operation's own keys go to the plugin parameters
+    fn render_operation_params(&mut self, ui: &mut egui::Ui, op: &operations::OperationDescriptor) {
+        let current = serde_json::to_value(self.capture_preset()).unwrap_or_default();
+        gui_state::collapsing(ui, "operations.parameters", trf("{0} parameters", &[&tr(&op.label)]), |ui| {
+            egui::Grid::new("operation_params").num_columns(3).show(ui, |ui| {
+                for param in op.params {
+                    ui.label(param.key);
+                    let preset_key = current.get(param.key).is_some();
+                    let mut value = current.get(param.key).and_then(|v| v.as_f64()).or_else(|| plugins::param_value(param.key)).or(param.default).unwrap_or(param.min);
+                    let mut drag = egui::DragValue::new(&mut value).clamp_range(param.min..=param.max);
+                    drag = if param.integer { drag.fixed_decimals(0).speed(1.0) } else { drag.speed(0.1) };
+                    let default = param.default.map_or_else(|| tr("derived"), |d| d.to_string());
+                    let changed = ui.add(drag).on_hover_text(trf("{0}..{1}, default {2}", &[&param.min, &param.max, &default])).changed();
+                    ui.label(tr(param.description));
+                    ui.end_row();
+                    if changed && !preset_key {
+                        plugins::set_param_value(param.key, if param.integer { value.round() } else { value });
+                        self.append_message(&format!("{} set to {}", param.key, value));
+                    } else if changed {
+                        let json = if param.integer { serde_json::json!(value.round() as i64) } else { serde_json::json!(value) };
+                        match serde_json::from_value::<config_loader::ParameterPreset>(serde_json::json!({ param.key: json })) {
+                            Ok(preset) => {
+                                self.apply_parameters(preset);
+                                self.active_preset = None;
+                                self.append_message(&format!("{} set to {}", param.key, json));
+                            }
+                            Err(e) => self.append_message(&format!("ERROR: {}: {}", param.key, e)),
+                        }
+                    }
+                }
+            });
+        });
+    }
+
+    /// Every preset-able parameter as currently set
+    fn capture_preset(&self) -> config_loader::ParameterPreset {
+        let ops = self.operations.read().unwrap();
+        config_loader::ParameterPreset {
+            tune_rest: Some(ops.get_tune_rest()),
+            x_rest: Some(ops.get_x_rest()),
+            z_rest: Some(ops.get_z_rest()),
+            lap_rest: Some(ops.get_lap_rest()),
+            performance_rest: Some(ops.get_performance_rest()),
+            adjustment_level: Some(ops.get_adjustment_level()),
+            retry_threshold: Some(ops.get_retry_threshold()),
+            delta_threshold: Some(ops.get_delta_threshold()),
+            z_variance_threshold: Some(ops.get_z_variance_threshold()),
+            z_up_step: Some(ops.get_z_up_step()),
+            z_down_step: Some(ops.get_z_down_step()),
+            x_start: Some(ops.get_x_start()),
+            x_finish: Some(ops.get_x_finish()),
+            x_step: Some(ops.get_x_step()),
+            voice_count_min: Some(self.voice_count_min.clone()),
+            voice_count_max: Some(self.voice_count_max.clone()),
+            amp_sum_min: Some(self.amp_sum_min.clone()),
+            amp_sum_max: Some(self.amp_sum_max.clone()),
+            bow_speed: ops.bow_drive.is_some().then(|| ops.get_bow_speeds()),
+            bump: Some(ops.get_bump_strategy().overrides()),
+        }
+    }
+
+    /// Apply the named preset; values it leaves unset keep their current setting
+    fn apply_preset(&mut self, name: &str) {
+        let Some(preset) = self.presets.iter().find(|(n, _)| n == name).map(|(_, p)| p.clone()) else {
+            return;
+        };
+        self.apply_parameters(preset);
+        self.active_preset = Some(name.to_string());
+        self.append_message(&format!("Applied preset '{}'", name));
+    }
+
+    /// Set every parameter `preset` names
+    fn apply_parameters(&mut self, preset: config_loader::ParameterPreset) {
+        let shares_steps = preset.z_up_step.is_some() || preset.z_down_step.is_some() || preset.x_step.is_some();
+        self.operations.read().unwrap().apply_parameters(&preset);
+        // Channel lists only cover the channels they name, so a preset saved with
+        // fewer channels leaves the rest alone
+        let thresholds = [
+            (&mut self.voice_count_min, preset.voice_count_min),
+            (&mut self.voice_count_max, preset.voice_count_max),
+            (&mut self.amp_sum_min, preset.amp_sum_min),
+            (&mut self.amp_sum_max, preset.amp_sum_max),
+        ];
+        for (current, saved) in thresholds {
+            if let Some(saved) = saved {
+                for (dst, src) in current.iter_mut().zip(saved) {
+                    *dst = src;
+                }
+            }
+        }
+        self.publish_voice_thresholds_to_logger();
+        if shares_steps {
+            self.publish_step_sizes();
+        }
+        // Only the strings the list names, and only wheels whose speed changes
+        if let Some(bow_speed) = preset.bow_speed {
+            let current = self.operations.read().unwrap().get_bow_speeds();
+            for (string_idx, speed) in bow_speed.into_iter().enumerate().take(current.len()) {
+                if current[string_idx] != speed {
+                    self.set_bow_speed(string_idx, speed);
+                }
+            }
+        }
+    }
+
+    /// Save the current parameters under the name in the preset field
+    fn save_preset(&mut self) {
+        let name = self.preset_name.trim().to_string();
+        let hostname = self.hostname.clone();
+        let preset = self.capture_preset();
+        match config_loader::save_preset(&hostname, &name, &preset) {
+            Ok(backup) => {
+                match self.presets.iter_mut().find(|(n, _)| *n == name) {
+                    Some(entry) => entry.1 = preset,
+                    None => self.presets.push((name.clone(), preset)),
+                }
+                self.active_preset = Some(name.clone());
+                self.preset_name.clear();
+                self.append_message(&format!("Saved preset '{}' to string_driver.yaml (previous file saved to {})", name, backup.display()));
+            }
+            Err(e) => self.append_message(&format!("ERROR: Saving preset failed: {}", error::user_message(&e))),
+        }
+    }
+
+    fn export_profile(&mut self) {
+        let hostname = self.hostname.clone();
+        let Some(path) = rfd::FileDialog::new()
+            .set_file_name(format!("{}-profile.json", hostname))
+            .add_filter("Instrument profile", &["json"])
+            .save_file()
+        else {
+            return;
+        };
+        let result = profile::build_profile(&hostname, Some(self.current_thresholds()))
+            .and_then(|p| profile::write_profile(&p, &path).map(|_| p));
+        match result {
+            Ok(p) => self.append_message(&format!(
+                "Exported profile for '{}' ({} scene(s), {} response map(s){}) to {}",
+                hostname, p.scenes.len(), p.response_maps.len(), if p.height_map.is_some() { ", height map" } else { "" }, path.display()
+            )),
+            Err(e) => self.append_message(&format!("ERROR: Profile export failed: {}", error::user_message(&e))),
+        }
+    }
+
+    /// Install a profile as this host and apply its thresholds right away
+    fn import_profile(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Instrument profile", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        let hostname = self.hostname.clone();
+        let imported = profile::read_profile(&path)
+            .and_then(|p| profile::import_profile(&p, &hostname).map(|summary| (p, summary)));
+        match imported {
+            Ok((p, summary)) => {
+                if let Some(t) = p.thresholds {
+                    self.voice_count_min = t.voice_count_min;
+                    self.voice_count_max = t.voice_count_max;
+                    self.amp_sum_min = t.amp_sum_min;
+                    self.amp_sum_max = t.amp_sum_max;
+                    self.publish_voice_thresholds_to_logger();
+                    self.append_message("Applied thresholds from profile");
+                }
+                self.append_message(&summary);
+            }
+            Err(e) => self.append_message(&format!("ERROR: Profile import failed: {}", error::user_message(&e))),
+        }
+    }
+
+    /// Show the lines captured by the process-wide tracing subscriber (see logging::init)
+    pub fn attach_log_buffer(&mut self, buffer: logging::LogBuffer) {
+        self.log_buffer = buffer;
+    }
+
+    /// Selected operation and Repeat as the last session left them; an operation
+    /// this host no longer offers stays unselected
+    pub fn restore_selection(&mut self, state: &gui_state::AppState) {
+        let offered = state.selected_operation.as_ref()
+            .filter(|operation| self.operations.read().unwrap().describe_operation(operation).is_some());
+        if let Some(operation) = offered {
+            self.selected_operation = operation.clone();
+        }
+        self.repeat_enabled = state.repeat;
+    }
+
+    pub fn note_selection(&self, state: &mut gui_state::AppState) {
+        state.selected_operation = (self.selected_operation != "None").then(|| self.selected_operation.clone());
+        state.repeat = self.repeat_enabled;
+    }
+
+    /// Own the window's saved state (standalone operations_gui), restoring the selection from it
+    pub fn attach_gui_state(&mut self, gui_state: gui_state::GuiState) {
+        self.restore_selection(&gui_state.state);
+        self.gui_state = Some(gui_state);
+    }
+
+    fn capture_gui_state(&mut self, ctx: &egui::Context) {
+        if let Some(mut gui_state) = self.gui_state.take() {
+            self.note_selection(&mut gui_state.state);
+            gui_state.capture(ctx);
+            self.gui_state = Some(gui_state);
+        }
+    }
+
+    fn save_gui_state(&mut self) {
+        if let Some(gui_state) = self.gui_state.as_mut() {
+            gui_state.save();
+        }
+    }
+
+    /// Share a performer / technician mode switch with other GUIs
+    pub fn attach_role_lock(&mut self, role_lock: role::RoleLock) {
+        self.role_lock = role_lock;
+    }
+
+    pub fn role_lock(&self) -> role::RoleLock {
+        self.role_lock.clone()
+    }
     
+    /// Current state of every subsystem for the health panel
+    fn collect_health(&self) -> Vec<health::SubsystemHealth> {
+        let mut entries = Vec::new();
+
+        if self.arduino_ops.is_some() {
+            let status = self.stepper_status.lock().ok().and_then(|s| *s);
+            entries.push(health::arduino_health("Arduino main", status.map(|s| s.main_connected)));
+            match status {
+                Some(StepperStatus { tuner_connected: Some(connected), .. }) => {
+                    entries.push(health::arduino_health("Arduino tuner", Some(connected)));
+                }
+                Some(_) => {}
+                None => entries.push(health::arduino_health("Arduino tuner", None)),
+            }
+            if let Some(slipping) = status.map(|s| s.slipping).filter(|&n| n > 0) {
+                entries.push(health::SubsystemHealth::new(
+                    "Encoders",
+                    health::HealthLevel::Warn,
+                    format!("{} stepper(s) slipping", slipping),
+                ));
+            }
+            let stale: Vec<&str> = status.iter()
+                .flat_map(|s| [(s.main_stale, "main"), (s.tuner_stale, "tuner")])
+                .filter_map(|(stale, board)| stale.then_some(board))
+                .collect();
+            if !stale.is_empty() {
+                entries.push(health::SubsystemHealth::new(
+                    "Positions",
+                    health::HealthLevel::Error,
+                    format!("{} board not answering position queries", stale.join(" and ")),
+                ));
+            }
+            if status.is_some_and(|s| s.startup_mismatch) {
+                entries.push(health::SubsystemHealth::new(
+                    "Startup check",
+                    health::HealthLevel::Error,
+                    "positions differ from the last session - re-home (see stepper_gui)",
+                ));
+            }
+        } else {
+            entries.push(health::SubsystemHealth::new("Arduino", health::HealthLevel::Unknown, "not configured"));
+        }
+
+        let gpio = self.operations.read().ok().and_then(|ops| ops.gpio.as_ref().map(|g| g.exist));
+        entries.push(health::gpio_health(gpio));
+        if gpio == Some(true) {
+            if let Ok(ops) = self.operations.read() {
+                let label = |sensor: usize| ops.sensor_label(sensor);
+                let faults: Vec<String> = ops.sensor_faults().iter().map(|(s, f)| format!("{}: {}", label(*s), f.describe())).collect();
+                let quarantined: Vec<String> = ops.quarantined_sensors().into_iter().map(label).collect();
+                entries.push(health::sensor_health(&faults, &quarantined));
+            }
+        }
+
+        let shm_age = self.operations.read().ok().and_then(|ops| ops.partials_age());
+        entries.push(health::shm_health(shm_age));
+
+        entries.push(match self.logger.as_ref() {
+            Some(logger) => {
+                let status = logger.status();
+                health::db_health(true, status.connected, status.enabled, status.backlog, status.errors + status.dropped)
+            }
+            None => health::db_health(false, false, false, 0, 0),
+        });
+
+        entries.extend(health::supervisor_health());
+
+        entries.push(health::operation_health(
+            self.last_operation.as_ref().map(|(op, outcome, at)| (op.as_str(), outcome.succeeded(), at.elapsed())),
+        ));
+        entries
+    }
+
+    /// Host profile this instrument was configured from
+    pub fn hostname(&self) -> &str {
+        &self.hostname
+    }
+
+    /// True while an operation runs or its final result is still being collected
+    pub fn is_busy(&self) -> bool {
+        self.operation_running.load(std::sync::atomic::Ordering::Relaxed) || self.operation_task.is_some()
+    }
+
+    /// Outcome of the most recently finished operation: (operation, succeeded)
+    pub fn last_result(&self) -> Option<(&str, bool)> {
+        self.last_outcome().map(|(op, outcome)| (op, outcome.succeeded()))
+    }
+
+    /// As last_result, telling a timeout from other failures
+    pub fn last_outcome(&self) -> Option<(&str, operations::OperationOutcome)> {
+        self.last_operation.as_ref().map(|(op, outcome, _)| (op.as_str(), *outcome))
+    }
+
+    /// Start `operation` on behalf of a broadcast; false if it could not start
+    pub fn run_operation(&mut self, operation: &str) -> bool {
+        if self.is_busy() && !self.is_probe(operation) {
+            self.append_message(&format!("Broadcast {} skipped - an operation is already running", operation));
+            return false;
+        }
+        self.append_message(&format!("Broadcast: running {}", operation));
+        self.start_operation(operation.to_string(), Priority::User);
+        self.has_started(operation)
+    }
+
+    /// A read-only operation, which may run while another operation runs
+    fn is_probe(&self, operation: &str) -> bool {
+        self.operations.read().unwrap().operation_category(operation) == Category::Probe
+    }
+
+    /// `operation` is running, as the motion operation or a probe
+    fn has_started(&self, operation: &str) -> bool {
+        self.operation_task.iter().chain(&self.probe_tasks).any(|task| task.operation == operation)
+    }
+
+    /// Ask the running operation to stop at its next check point (the BREAK button)
+    pub fn request_break(&mut self) {
+        self.exit_flag.store(true, std::sync::atomic::Ordering::Relaxed);
+        self.append_message("Break requested - operation will stop at next check point");
+        let dropped = self.operations.read().unwrap().drop_queued_operations(Priority::Estop);
+        if !dropped.is_empty() {
+            let names: Vec<&str> = dropped.iter().map(|request| request.operation.as_str()).collect();
+            self.append_message(&format!("Queued operations dropped: {}", names.join(", ")));
+        }
+        self.fire_estop("break");
+        if self.autostart.take().is_some() {
+            self.append_message("Autostart: sequence stopped by BREAK");
+        }
+        // Someone is there to play: the trigger does not start the operation again
+        let ops = self.operations.read().unwrap();
+        if ops.audio_trigger_state().is_some_and(|state| matches!(state, audio_trigger::TriggerState::Running { .. })) {
+            ops.arm_audio_trigger(false);
+            drop(ops);
+            self.append_message("Audio trigger: disarmed by BREAK");
+        }
+    }
+
+    /// Switch between the full controls and the full-screen stage view
+    fn toggle_stage_view(&mut self, ctx: &egui::Context) {
+        self.stage_view = match self.stage_view.take() {
+            Some(_) => None,
+            None => Some(stage_view::StageView::default()),
+        };
+        ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(self.stage_view.is_some()));
+    }
+
+    /// Each string's status dot, the X position and STOP (BREAK)
+    fn render_stage_view(&mut self, ui: &mut egui::Ui) {
+        let now = Instant::now();
+        let positions = self.stepper_positions.lock().map(|p| p.clone()).unwrap_or_default();
+        let operations = Arc::clone(&self.operations);
+        let ops = operations.read().unwrap();
+        let enabled = ops.get_all_stepper_enabled();
+        let amp_sum = ops.get_amp_sum();
+        let Some(view) = self.stage_view.as_mut() else { return; };
+        let strings: Vec<stage_view::StringStage> = ops.topology.strings().iter().map(|string| {
+            let z: Vec<usize> = string.z_pair.into_iter().flat_map(|(inner, outer)| [inner, outer]).collect();
+            let fault = z.iter().any(|idx| {
+                enabled.get(idx) == Some(&false) || ops.topology.sensor_for(*idx).is_some_and(|sensor| ops.sensor_quarantined(sensor))
+            });
+            let moved: Vec<bool> = z.iter()
+                .filter_map(|idx| positions.get(idx).map(|&pos| view.moved_recently(*idx, pos, now)))
+                .collect();
+            let level = string.channel.and_then(|ch| {
+                let band = (*self.amp_sum_min.get(ch)? as f32, *self.amp_sum_max.get(ch)? as f32);
+                Some((*amp_sum.get(ch)?, band))
+            });
+            let label = ops.strings.get(string.string)
+                .and_then(|info| info.name.clone())
+                .unwrap_or_else(|| string.string.to_string());
+            stage_view::StringStage { label, status: stage_view::classify(fault, moved.contains(&true), level) }
+        }).collect();
+        let x_position = ops.x_step_index().and_then(|x| positions.get(&x).copied());
+        let activity = match self.operation_task.as_ref() {
+            Some(task) => trf("{0} running", &[&task.operation]),
+            None => ops.instrument_state().0.describe(),
+        };
+        drop(ops);
+        if stage_view::render_stage_view(ui, &strings, x_position, &activity) {
+            self.request_break();
+        }
+    }
+
+    /// Run the estop HOOKS: `source` is the button (break, park or kill_all)
+    fn fire_estop(&self, source: &str) {
+        let operation = self.operation_task.as_ref().map(|task| task.operation.clone());
+        self.operations.read().unwrap().hook(config_loader::HookEvent::Estop, serde_json::json!({ "source": source, "operation": operation }));
+    }
+
+    /// Run COMMAND_INBOX files one at a time (after any AUTOSTART sequence), applying
+    /// their preset and params, starting their operation and writing the result file
+    /// once it finishes. Operations go through start_operation, so the role lock applies.
+    fn poll_inbox(&mut self) {
+        let Some(mut inbox) = self.inbox.take() else {
+            return;
+        };
+        if let Some(run) = inbox.current.take() {
+            if self.is_busy() || self.has_started(&run.operation) {
+                inbox.current = Some(run);
+            } else {
+                let probe = self.last_probe.as_ref().map(|(op, outcome)| (op.as_str(), *outcome));
+                let status = match self.last_outcome().filter(|(op, _)| *op == run.operation).or(probe) {
+                    Some((op, operations::OperationOutcome::Succeeded)) if op == run.operation => command_inbox::InboxStatus::Ok,
+                    Some((op, operations::OperationOutcome::TimedOut)) if op == run.operation => command_inbox::InboxStatus::Timeout,
+                    _ => command_inbox::InboxStatus::Failed,
+                };
+                let message = self.message.get(run.log_start..).unwrap_or_default().trim().to_string();
+                self.finish_inbox_command(&run.path, status, message, run.received_at);
+            }
+            self.inbox = Some(inbox);
+            return;
+        }
+        if self.is_busy() || self.autostart.is_some() || Instant::now() < inbox.next_poll {
+            self.inbox = Some(inbox);
+            return;
+        }
+        inbox.next_poll = Instant::now() + inbox.settings.poll;
+        let Some(path) = command_inbox::next_command(&inbox.settings.dir) else {
+            self.inbox = Some(inbox);
+            return;
+        };
+
+        let received_at = chrono::Local::now().to_rfc3339();
+        let log_start = self.message.len();
+        self.append_message(&format!("Inbox: running {}", path.display()));
+        let command = command_inbox::read_command(&path);
+        if let Err(e) = command_inbox::retire(&path) {
+            // A command that cannot be retired would run again on every scan
+            self.append_message(&format!("ERROR: Inbox: cannot retire {} ({}) - no longer watching {}", path.display(), e, inbox.settings.dir.display()));
+            return;
+        }
+        let command = match command.and_then(|command| command_inbox::check_access(&inbox.access, &command).map(|_| command)) {
+            Ok(command) => command,
+            Err(e) => {
+                self.finish_inbox_command(&path, command_inbox::InboxStatus::Rejected, e, received_at);
+                self.inbox = Some(inbox);
+                return;
+            }
+        };
+        if let Some(name) = command.preset.as_deref() {
+            if !self.presets.iter().any(|(n, _)| n == name) {
+                self.finish_inbox_command(&path, command_inbox::InboxStatus::Rejected, format!("unknown preset '{}'", name), received_at);
+                self.inbox = Some(inbox);
+                return;
+            }
+            self.apply_preset(name);
+        }
+        if let Some(params) = command.params {
+            match serde_json::from_value::<config_loader::ParameterPreset>(params) {
+                Ok(params) => {
+                    if let Some(name) = params.bump.and_then(|bump| bump.invalid()) {
+                        self.finish_inbox_command(&path, command_inbox::InboxStatus::Rejected, format!("invalid params: BUMP.{} is out of range", name), received_at);
+                        self.inbox = Some(inbox);
+                        return;
+                    }
+                    self.apply_parameters(params);
+                    self.active_preset = None;
+                    self.append_message("Inbox: parameters applied");
+                }
+                Err(e) => {
+                    self.finish_inbox_command(&path, command_inbox::InboxStatus::Rejected, format!("invalid params: {}", e), received_at);
+                    self.inbox = Some(inbox);
+                    return;
+                }
+            }
+        }
+
+        match command.operation {
+            None => {
+                let message = self.message.get(log_start..).unwrap_or_default().trim().to_string();
+                self.finish_inbox_command(&path, command_inbox::InboxStatus::Ok, message, received_at);
+            }
+            Some(operation) if operation == "clear_fault" => {
+                let cleared = self.operations.read().unwrap().clear_fault();
+                let (status, message) = if cleared {
+                    (command_inbox::InboxStatus::Ok, "Fault cleared".to_string())
+                } else {
+                    (command_inbox::InboxStatus::Rejected, "instrument is not faulted".to_string())
+                };
+                self.append_message(&format!("Inbox: {}", message));
+                self.finish_inbox_command(&path, status, message, received_at);
+            }
+            Some(operation) if operation == "list_operations" => {
+                let operations = serde_json::to_value(self.operations.read().unwrap().operation_descriptors()).ok();
+                self.append_message("Inbox: operations listed");
+                self.write_inbox_result(&path, command_inbox::InboxStatus::Ok, "Operations listed".to_string(), received_at, operations, None);
+            }
+            Some(operation) if operation == "effective_config" => {
+                let config = serde_json::to_value(self.effective_config()).ok();
+                self.append_message("Inbox: effective settings listed");
+                self.write_inbox_result(&path, command_inbox::InboxStatus::Ok, "Effective settings listed".to_string(), received_at, None, config);
+            }
+            Some(operation) if self.operations.read().unwrap().describe_operation(&operation).is_none() => {
+                let message = format!("unknown operation '{}' (list_operations names them)", operation);
+                self.finish_inbox_command(&path, command_inbox::InboxStatus::Rejected, message, received_at);
+            }
+            Some(operation) => {
+                if let Some(seconds) = command.max_duration {
+                    let Some(limit) = Duration::try_from_secs_f64(seconds).ok().filter(|d| !d.is_zero()) else {
+                        self.finish_inbox_command(&path, command_inbox::InboxStatus::Rejected, "max_duration must be a positive number of seconds".to_string(), received_at);
+                        self.inbox = Some(inbox);
+                        return;
+                    };
+                    self.next_run_time_limit = Some(limit);
+                }
+                if let Some(rests) = command.rests {
+                    if let Some(name) = rests.invalid() {
+                        self.finish_inbox_command(&path, command_inbox::InboxStatus::Rejected, format!("rests.{} must be a non-negative number of seconds", name), received_at);
+                        self.inbox = Some(inbox);
+                        return;
+                    }
+                    self.next_run_rests = Some(rests);
+                }
+                if let Some(bump) = command.bump {
+                    if let Some(name) = bump.invalid() {
+                        self.finish_inbox_command(&path, command_inbox::InboxStatus::Rejected, format!("bump.{} is out of range", name), received_at);
+                        self.inbox = Some(inbox);
+                        return;
+                    }
+                    self.next_run_bump = Some(bump);
+                }
+                self.start_operation(operation.clone(), Priority::User);
+                // Not left over for a later run if this one did not start
+                self.next_run_time_limit = None;
+                self.next_run_rests = None;
+                self.next_run_bump = None;
+                if self.has_started(&operation) {
+                    inbox.current = Some(InboxRun { path, operation, received_at, log_start });
+                } else {
+                    let message = self.message.get(log_start..).unwrap_or_default().trim().to_string();
+                    self.finish_inbox_command(&path, command_inbox::InboxStatus::Rejected, message, received_at);
+                }
+            }
+        }
+        self.inbox = Some(inbox);
+    }
+
+    /// Pick up new or changed SCORES files, follow the MIDI clock and carry out the
+    /// cues the transport hands out
+    fn poll_scores(&mut self) {
+        let Some(mut scores) = self.scores.take() else {
+            return;
+        };
+        let now = Instant::now();
+        let mut notes = Vec::new();
+        if now >= scores.next_scan {
+            scores.next_scan = now + scores.settings.poll;
+            let files = score::score_files(&scores.settings.dir);
+            scores.loaded.retain(|(path, _)| files.contains(path));
+            scores.seen.retain(|path, _| files.contains(path));
+            for path in files {
+                let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+                if modified.is_none() || scores.seen.get(&path) == modified.as_ref() {
+                    continue;
+                }
+                scores.seen.insert(path.clone(), modified.unwrap_or(std::time::UNIX_EPOCH));
+                scores.loaded.retain(|(p, _)| *p != path);
+                match score::Score::load(&path).and_then(|s| self.check_score(&s).map(|_| s)) {
+                    Ok(loaded) => {
+                        notes.push(format!("Score: loaded '{}' ({} events, {:.0} s)", loaded.title, loaded.events.len(), loaded.length()));
+                        let idle = scores.transport.as_ref().is_none_or(|(_, t)| t.state() != score::TransportState::Playing);
+                        if scores.settings.autoplay && idle {
+                            notes.push(format!("Score: playing '{}'", loaded.title));
+                            let mut transport = score::Transport::new(loaded.clone());
+                            transport.play(now);
+                            scores.transport = Some((path.clone(), transport));
+                        }
+                        scores.loaded.push((path, loaded));
+                    }
+                    Err(e) => notes.push(format!("ERROR: Score: {}", e)),
+                }
+            }
+            scores.loaded.sort_by(|a, b| a.0.cmp(&b.0));
+        }
+
+        let mut cues = Vec::new();
+        if let Some((_, transport)) = scores.transport.as_mut() {
+            while let Some(message) = scores.midi.as_ref().and_then(|rx| rx.try_recv().ok()) {
+                cues.extend(transport.follow(message, now));
+            }
+            cues.extend(transport.poll(now));
+        }
+        self.scores = Some(scores);
+        for note in notes {
+            self.append_message(&note);
+        }
+        for cue in cues {
+            self.play_score_cue(cue);
+        }
+    }
+
+    /// A score may only name this host's operations and presets
+    fn check_score(&self, score: &score::Score) -> std::result::Result<(), String> {
+        let (operations, presets) = score.references();
+        let ops = self.operations.read().unwrap();
+        if let Some(unknown) = operations.iter().find(|op| ops.describe_operation(op).is_none()) {
+            return Err(format!("'{}' names unknown operation '{}'", score.title, unknown));
+        }
+        if let Some(unknown) = presets.iter().find(|name| !self.presets.iter().any(|(n, _)| n == *name)) {
+            return Err(format!("'{}' names unknown preset '{}'", score.title, unknown));
+        }
+        Ok(())
+    }
+
+    /// Carry out one score cue. Moves need the stepper link, which a running operation
+    /// holds, and an operation cannot start while another runs: such cues are skipped
+    /// (and logged) rather than held back, so the piece keeps its time.
+    fn play_score_cue(&mut self, cue: score::ScoreAction) {
+        self.note_activity();
+        match cue {
+            score::ScoreAction::Operation(operation, rests) => {
+                if self.is_busy() && !self.is_probe(&operation) {
+                    self.append_message(&format!("Score: {} skipped - an operation is already running", operation));
+                    return;
+                }
+                self.append_message(&format!("Score: running {}", operation));
+                self.next_run_rests = Some(rests);
+                self.start_operation(operation, Priority::Scheduled);
+                self.next_run_rests = None;
+            }
+            score::ScoreAction::Preset(name) => self.apply_preset(&name),
+            score::ScoreAction::Stop => {
+                if self.is_busy() {
+                    self.exit_flag.store(true, std::sync::atomic::Ordering::Relaxed);
+                    self.append_message("Score: stopping the running operation");
+                }
+            }
+            cue => match self.score_move(&cue) {
+                Ok(note) if !note.is_empty() => self.append_message(&format!("Score: {}", note)),
+                Ok(_) => {}
+                Err(e) => self.append_message(&format!("Score: {:?} skipped: {}", cue, e)),
+            },
+        }
+    }
+
+    /// X, Z and tuner cues, through stepper_gui
+    fn score_move(&mut self, cue: &score::ScoreAction) -> std::result::Result<String, String> {
+        self.with_stepper_link(|ops, client, positions, max_positions| match cue {
+            score::ScoreAction::MoveX(x) => ops.move_x_to(client, positions, *x),
+            score::ScoreAction::MoveZ(target) => ops.move_string_z(client, positions, max_positions, target.string, target.position).map(|_| String::new()),
+            score::ScoreAction::Tune(change) => ops.tune_string(client, positions, change.string, change.steps).map(|_| String::new()),
+            _ => Ok(String::new()),
+        })
+    }
+
+    /// Run `f` on the stepper link with the positions as known here, and keep the ones
+    /// it changed. Refused rather than waited for while an operation holds the link.
+    fn with_stepper_link<R>(
+        &mut self,
+        f: impl FnOnce(&operations::Operations, &mut ArduinoStepperOps, &mut [i32], &std::collections::HashMap<usize, i32>) -> error::Result<R>,
+    ) -> std::result::Result<R, String> {
+        let Some(arduino_ops) = self.arduino_ops.as_ref() else {
+            return Err("no stepper_gui link".to_string());
+        };
+        // A running operation holds the link; taking it here would stall the GUI
+        let Ok(mut client) = arduino_ops.try_lock() else {
+            return Err("the stepper link is busy with an operation".to_string());
+        };
+        let ops = self.operations.read().unwrap();
+        let known = self.stepper_positions.lock().map(|p| p.clone()).unwrap_or_default();
+        let z_indices = ops.get_z_stepper_indices();
+        let highest = known.keys().copied().chain(z_indices.iter().copied()).chain(ops.x_step_index()).chain(ops.tuner_indices()).max();
+        let mut positions = vec![0; highest.map_or(0, |idx| idx + 1)];
+        for (&idx, &pos) in &known {
+            positions[idx] = pos;
+        }
+        let result = f(&ops, &mut client, &mut positions, &z_max_positions(&z_indices));
+        drop(ops);
+        drop(client);
+        if let Ok(mut map) = self.stepper_positions.lock() {
+            for (idx, &pos) in positions.iter().enumerate() {
+                if known.get(&idx).copied().unwrap_or(0) != pos {
+                    map.insert(idx, pos);
+                }
+            }
+        }
+        result.map_err(|e| error::user_message(&e))
+    }
+
+    /// Save the instrument as it stands under the name in the Scenes field
+    fn save_scene(&mut self) {
+        let name = self.scenes.name.trim().to_string();
+        let positions = self.stepper_positions.lock().map(|p| p.clone()).unwrap_or_default();
+        let scene = self.operations.read().unwrap().capture_scene(&name, &positions, self.capture_preset());
+        match scene.save(&self.scenes.dir) {
+            Ok(path) => {
+                self.scenes.saved.retain(|s| s.name != name);
+                self.scenes.saved.push(scene);
+                self.scenes.saved.sort_by(|a, b| a.name.cmp(&b.name));
+                self.scenes.name.clear();
+                self.append_message(&format!("Saved scene '{}' to {}", name, path.display()));
+            }
+            Err(e) => self.append_message(&format!("ERROR: Saving scene failed: {}", e)),
+        }
+    }
+
+    /// Drive the instrument to scene `name`, then apply its parameters. Like a score
+    /// cue it needs the stepper link, so not while an operation runs.
+    fn recall_scene(&mut self, name: &str) {
+        self.note_activity();
+        let Some(scene) = self.scenes.saved.iter().find(|s| s.name == name).cloned() else {
+            return;
+        };
+        if self.is_busy() {
+            self.append_message(&format!("Scene '{}' not recalled - an operation is running", name));
+            return;
+        }
+        match self.with_stepper_link(|ops, client, positions, max_positions| ops.recall_scene(client, positions, max_positions, &scene)) {
+            Ok(notes) => {
+                for note in notes {
+                    self.append_message(&format!("Scene: {}", note));
+                }
+                self.apply_parameters(scene.params);
+                self.active_preset = None;
+                self.append_message(&format!("Recalled scene '{}'", name));
+            }
+            Err(e) => self.append_message(&format!("ERROR: Scene '{}' not recalled: {}", name, e)),
+        }
+    }
+
+    /// Read the scenes directory again, for scenes copied in from elsewhere
+    fn reload_scenes(&mut self) {
+        let (saved, errors) = scene::load_scenes(&self.scenes.dir);
+        self.scenes.saved = saved;
+        for error in errors {
+            self.append_message(&format!("ERROR: Scenes: {}", error));
+        }
+        self.append_message(&format!("{} scene(s) in {}", self.scenes.saved.len(), self.scenes.dir.display()));
+    }
+
+    /// Scenes panel: recall a saved scene; in technician mode also save and delete
+    fn render_scenes(&mut self, ui: &mut egui::Ui) {
+        let technician = self.role_lock.is_technician();
+        let busy = self.is_busy();
+        let mut recall = None;
+        let mut delete = None;
+        if self.scenes.saved.is_empty() {
+            ui.label(trf("No scenes in {0}", &[&self.scenes.dir.display()]));
+        }
+        egui::Grid::new("scenes_grid").striped(true).show(ui, |ui| {
+            for scene in &self.scenes.saved {
+                ui.label(&scene.name);
+                ui.label(egui::RichText::new(&scene.saved_at).weak());
+                if ui.add_enabled(!busy, egui::Button::new(tr("Recall")))
+                    .on_hover_text(tr("Move Z, X and the tuners to the scene and apply its parameters"))
+                    .clicked()
+                {
+                    recall = Some(scene.name.clone());
+                }
+                if technician && ui.small_button(tr("Delete")).clicked() {
+                    delete = Some(scene.name.clone());
+                }
+                ui.end_row();
+            }
+        });
+        if technician {
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.scenes.name).hint_text(tr("scene name")).desired_width(140.0));
+                let can_save = !self.scenes.name.trim().is_empty();
+                if ui.add_enabled(can_save, egui::Button::new(tr("Save Scene")))
+                    .on_hover_text(tr("Store the stepper, tuner and X positions and the parameters under this name (replaces a scene of the same name)"))
+                    .clicked()
+                {
+                    self.save_scene();
+                }
+                if ui.button(tr("Reload")).clicked() {
+                    self.reload_scenes();
+                }
+            });
+        }
+        if let Some(name) = recall {
+            self.recall_scene(&name);
+        }
+        if let Some(name) = delete {
+            match scene::delete_scene(&self.scenes.dir, &name) {
+                Ok(()) => {
+                    self.scenes.saved.retain(|s| s.name != name);
+                    self.append_message(&format!("Deleted scene '{}'", name));
+                }
+                Err(e) => self.append_message(&format!("ERROR: {}", e)),
+            }
+        }
+    }
+
+    /// Effective settings panel: each setting's value and source, by section
+    fn render_effective_settings(&mut self, ui: &mut egui::Ui) {
+        use effective_config::Source;
+        let config = self.effective_config();
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.effective_changed_only, tr("Changed from defaults only"));
+            ui.label(egui::RichText::new(&config.config_file).weak());
+        });
+        let changed_only = self.effective_changed_only;
+        egui::ScrollArea::vertical().id_source("effective_settings").max_height(300.0).show(ui, |ui| {
+            egui::Grid::new("effective_settings_grid").striped(true).show(ui, |ui| {
+                let mut section = None;
+                for setting in config.settings.iter().filter(|s| !changed_only || s.source != Source::Default) {
+                    if section != Some(&setting.section) {
+                        ui.strong(tr(&setting.section));
+                        ui.end_row();
+                        section = Some(&setting.section);
+                    }
+                    ui.label(&setting.key);
+                    ui.label(&setting.value);
+                    let color = match setting.source {
+                        Source::Default => egui::Color32::GRAY,
+                        Source::Yaml => egui::Color32::from_rgb(80, 140, 220),
+                        Source::Runtime => egui::Color32::from_rgb(255, 140, 0),
+                        Source::Learned => egui::Color32::from_rgb(40, 170, 60),
+                    };
+                    ui.colored_label(color, tr(setting.source.label()));
+                    ui.end_row();
+                }
+            });
+        });
+    }
+
+    /// Score panel: choose a loaded score, play / pause / stop it and seek
+    /// Per-run rest overrides: a ticked rest replaces the global (and YAML OPERATIONS) one
+    fn render_run_rests(&mut self, ui: &mut egui::Ui) {
+        let globals = {
+            let ops = self.operations.read().unwrap();
+            [ops.get_tune_rest(), ops.get_x_rest(), ops.get_z_rest(), ops.get_lap_rest()]
+        };
+        let rests = &mut self.rest_overrides;
+        let rows = [
+            (tr("Tune rest"), &mut rests.tune_rest),
+            (tr("X rest"), &mut rests.x_rest),
+            (tr("Z rest"), &mut rests.z_rest),
+            (tr("Lap rest"), &mut rests.lap_rest),
+        ];
+        for ((label, rest), global) in rows.into_iter().zip(globals) {
+            ui.horizontal(|ui| {
+                let mut set = rest.is_some();
+                if ui.checkbox(&mut set, label).changed() {
+                    *rest = set.then_some(global);
+                }
+                if let Some(value) = rest.as_mut() {
+                    ui.add(egui::DragValue::new(value).speed(0.1).clamp_range(0.0..=100.0).suffix(" s"));
+                }
+            });
+        }
+    }
+
+    fn render_scores(&mut self, ui: &mut egui::Ui) {
+        let Some(scores) = self.scores.as_mut() else {
+            return;
+        };
+        let now = Instant::now();
+        let mut notes = Vec::new();
+        let mut chase = Vec::new();
+        if scores.loaded.is_empty() {
+            ui.label(trf("No scores in {0}", &[&scores.settings.dir.display()]));
+            return;
+        }
+        let current = scores.transport.as_ref().map(|(path, t)| (path.clone(), t.score.title.clone()));
+        egui::ComboBox::from_id_source("score_select")
+            .selected_text(current.as_ref().map_or("None", |(_, title)| title.as_str()))
+            .show_ui(ui, |ui| {
+                for (path, loaded) in &scores.loaded {
+                    let selected = current.as_ref().is_some_and(|(p, _)| p == path);
+                    if ui.selectable_label(selected, &loaded.title).clicked() && !selected {
+                        scores.transport = Some((path.clone(), score::Transport::new(loaded.clone())));
+                    }
+                }
+            });
+        let Some((_, transport)) = scores.transport.as_mut() else {
+            return;
+        };
+        let state = transport.state();
+        ui.horizontal(|ui| {
+            if ui.add_enabled(state != score::TransportState::Playing, egui::Button::new(tr("Play"))).clicked() {
+                transport.play(now);
+                notes.push(format!("Score: playing '{}'", transport.score.title));
+            }
+            if ui.add_enabled(state == score::TransportState::Playing, egui::Button::new(tr("Pause"))).clicked() {
+                transport.pause(now);
+                notes.push(format!("Score: '{}' paused", transport.score.title));
+            }
+            if ui.add_enabled(state != score::TransportState::Stopped, egui::Button::new(tr("Stop"))).clicked() {
+                transport.stop();
+                notes.push(format!("Score: '{}' stopped", transport.score.title));
+            }
+            let mut position = transport.position(now);
+            let slider = egui::Slider::new(&mut position, 0.0..=transport.score.length()).suffix(" s");
+            if ui.add(slider).drag_stopped() {
+                chase = transport.seek(position, now);
+                notes.push(format!("Score: '{}' at {:.1} s", transport.score.title, position));
+            }
+            if let Some((bar, beat)) = transport.score.bar_beat(position) {
+                ui.label(trf("bar {0} beat {1}", &[&bar, &format!("{:.1}", beat)]));
+            }
+            if scores.midi.is_some() {
+                ui.label(tr("following MIDI clock"));
+            }
+        });
+        for note in notes {
+            self.append_message(&note);
+        }
+        for cue in chase {
+            self.play_score_cue(cue);
+        }
+    }
+
+    fn finish_inbox_command(&mut self, path: &std::path::Path, status: command_inbox::InboxStatus, message: String, received_at: String) {
+        self.write_inbox_result(path, status, message, received_at, None, None);
+    }
+
+    fn write_inbox_result(
+        &mut self,
+        path: &std::path::Path,
+        status: command_inbox::InboxStatus,
+        message: String,
+        received_at: String,
+        operations: Option<serde_json::Value>,
+        config: Option<serde_json::Value>,
+    ) {
+        let result = command_inbox::InboxResult {
+            command: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+            status,
+            message,
+            received_at,
+            finished_at: chrono::Local::now().to_rfc3339(),
+            state: self.operations.read().unwrap().instrument_state().0.describe(),
+            operations,
+            config,
+        };
+        match command_inbox::write_result(path, &result) {
+            Ok(()) => self.append_message(&format!("Inbox: {} finished ({:?})", result.command, status)),
+            Err(e) => self.append_message(&format!("ERROR: Inbox: writing the result for {} failed: {}", result.command, e)),
+        }
+    }
+
+    /// Machine state for the STATUS_FILE snapshot
+    fn status_snapshot(&self) -> status_snapshot::StatusSnapshot {
+        let build = status_snapshot::BuildStatus {
+            operations_gui: build_info::BuildInfo::local("operations_gui"),
+            stepper_gui: self.stepper_build.lock().ok().and_then(|b| b.clone()),
+        };
+        let snapshot = status_snapshot::StatusSnapshot::from_operations(&self.hostname, &self.operations.read().unwrap(), build);
+        let health = self.collect_health()
+            .into_iter()
+            .map(|e| status_snapshot::HealthStatus {
+                name: e.name,
+                level: format!("{:?}", e.level).to_lowercase(),
+                detail: e.detail,
+            })
+            .collect();
+        status_snapshot::StatusSnapshot {
+            positions: self.stepper_positions.lock().map(|p| p.iter().map(|(&k, &v)| (k, v)).collect()).unwrap_or_default(),
+            operation: self.operation_task.as_ref().map(|task| task.operation.clone()),
+            last_operation: self.last_operation.as_ref().map(|(name, outcome, at)| status_snapshot::LastOperation::new(name, *outcome, *at)),
+            health,
+            recent_errors: status_snapshot::recent_errors(&self.log_buffer, STATUS_RECENT_ERRORS),
+            ..snapshot
+        }
+    }
+
+    /// Rewrite the STATUS_FILE snapshot when its interval has passed
+    fn write_status_file(&mut self) {
+        if self.status_file.as_ref().is_none_or(|file| Instant::now() < file.next_write) {
+            return;
+        }
+        let snapshot = self.status_snapshot();
+        let Some(file) = self.status_file.as_mut() else {
+            return;
+        };
+        file.next_write = Instant::now() + file.settings.interval;
+        match status_snapshot::write(&file.settings.path, &snapshot) {
+            Ok(()) => file.failing = false,
+            Err(e) => {
+                if !file.failing {
+                    tracing::warn!("Writing status file {} failed: {}", file.settings.path.display(), e);
+                }
+                file.failing = true;
+            }
+        }
+    }
+
+    /// Subsystems in error that AUTOSTART waits on
+    fn autostart_blockers(&self) -> Vec<String> {
+        self.collect_health()
+            .into_iter()
+            .filter(|e| e.level == health::HealthLevel::Error && !AUTOSTART_IGNORED_HEALTH.contains(&e.name.as_str()))
+            .map(|e| format!("{} ({})", e.name, e.detail))
+            .collect()
+    }
+
+    /// Drive the AUTOSTART sequence: wait until the health checks pass, then run each
+    /// step in turn, dropping the rest at the first that cannot start, fails or overruns
+    /// its TIMEOUT. Steps run whatever the role lock says - the sequence comes from YAML.
+    fn advance_autostart(&mut self) {
+        let Some(mut autostart) = self.autostart.take() else {
+            return;
+        };
+
+        if let Some((step, started)) = autostart.current.take() {
+            if self.is_busy() {
+                if started.elapsed() > step.timeout {
+                    self.append_message(&format!(
+                        "Autostart: {} exceeded its {:.0}s timeout - stopping it and the sequence",
+                        step.operation,
+                        step.timeout.as_secs_f32()
+                    ));
+                    self.request_break();
+                    return;
+                }
+                autostart.current = Some((step, started));
+                self.autostart = Some(autostart);
+                return;
+            }
+            match self.last_result() {
+                Some((op, true)) if op == step.operation => {}
+                _ => {
+                    let skipped: Vec<String> = autostart.pending.iter().map(|s| s.operation.clone()).collect();
+                    self.append_message(&format!(
+                        "Autostart: {} failed - sequence stopped{}",
+                        step.operation,
+                        if skipped.is_empty() { String::new() } else { format!(" (skipped {})", skipped.join(", ")) }
+                    ));
+                    return;
+                }
+            }
+        }
+
+        // Wait for anything the user started by hand
+        if self.is_busy() {
+            self.autostart = Some(autostart);
+            return;
+        }
+        if !autostart.started {
+            if Instant::now() < autostart.next_check {
+                self.autostart = Some(autostart);
+                return;
+            }
+            autostart.next_check = Instant::now() + HEALTH_REFRESH;
+            let blockers = self.autostart_blockers();
+            if !blockers.is_empty() {
+                if !autostart.waiting_reported {
+                    autostart.waiting_reported = true;
+                    self.append_message(&format!("Autostart: waiting for {}", blockers.join(", ")));
+                }
+                self.autostart = Some(autostart);
+                return;
+            }
+            autostart.started = true;
+        }
+
+        let Some(step) = autostart.pending.pop_front() else {
+            self.append_message("Autostart: sequence complete");
+            return;
+        };
+        self.append_message(&format!(
+            "Autostart: step {}/{} - {}",
+            autostart.total - autostart.pending.len(),
+            autostart.total,
+            step.operation
+        ));
+        self.launch_operation(step.operation.clone(), Priority::Scheduled);
+        if !self.has_started(&step.operation) {
+            self.append_message(&format!("Autostart: {} could not start - sequence stopped", step.operation));
+            return;
+        }
+        autostart.current = Some((step, Instant::now()));
+        self.autostart = Some(autostart);
+    }
+
+    /// Per-frame housekeeping: collect operation results and refresh the audio analysis
+    pub fn tick(&mut self) {
+        self.poll_operation_result();
+        self.advance_autostart();
+        self.poll_inbox();
+        self.poll_scores();
+        self.write_status_file();
+        self.watch_alerts();
+        self.follow_shared_state();
+        self.watch_idle();
+        self.collect_bump_events();
+        self.poll_process_stop();
+        // Analysis runs at ANALYSIS_HZ however often the GUI repaints
+        let now = Instant::now();
+        if now >= self.next_analysis {
+            self.next_analysis = now + config_loader::RefreshRates::interval(self.refresh_rates.analysis_hz);
+            let ops = self.operations.read().unwrap();
+            ops.update_audio_analysis();
+            ops.observe_tuner_pitch(&self.stepper_positions.lock().unwrap());
+            drop(ops);
+            self.reconcile_voice_count_cap();
+            self.watch_audio_trigger();
+        }
+    }
+
+    /// Time between repaints (REFRESH_RATES REPAINT_HZ); input still repaints at once
+    pub fn repaint_interval(&self) -> Duration {
+        config_loader::RefreshRates::interval(self.refresh_rates.repaint_hz)
+    }
+
+    /// Enable or disable a stepper from the GUI: stepper_gui locks it out (and cuts its
+    /// current) and the enabled map follows; an enable that cannot be sent changes nothing
+    fn toggle_stepper_enabled(&mut self, stepper_idx: usize, enabled: bool) {
+        let operations = Arc::clone(&self.operations);
+        let ops = operations.read().unwrap();
+        let label = ops.stepper_label(stepper_idx);
+        let result = match self.arduino_ops.as_ref() {
+            None => {
+                ops.set_stepper_enabled(stepper_idx, enabled);
+                Ok(())
+            }
+            // A running operation holds the link; taking it here would stall the GUI
+            Some(arduino_ops) => match arduino_ops.try_lock() {
+                Ok(mut client) => ops.apply_stepper_enabled(&mut *client, stepper_idx, enabled).map_err(|e| error::user_message(&e)),
+                Err(_) => Err("the stepper link is busy with an operation - try again when it finishes".to_string()),
+            },
+        };
+        drop(ops);
+        let action = if enabled { "enabled" } else { "disabled" };
+        match result {
+            Ok(()) => self.append_message(&format!("{} {}", label, action)),
+            Err(e) => self.append_message(&format!("Error: {} not {}: {}", label, action, e)),
+        }
+    }
+
+    /// Set a string's bow wheel speed from the GUI or a preset, through stepper_gui
+    fn set_bow_speed(&mut self, string_idx: usize, speed: u32) {
+        let operations = Arc::clone(&self.operations);
+        let ops = operations.read().unwrap();
+        let label = ops.string_label(string_idx);
+        let result = match self.arduino_ops.as_ref() {
+            None => Err("no stepper_gui link".to_string()),
+            // A running operation holds the link; taking it here would stall the GUI
+            Some(arduino_ops) => match arduino_ops.try_lock() {
+                Ok(mut client) => ops.set_bow_speed(&mut *client, string_idx, speed).map_err(|e| error::user_message(&e)),
+                Err(_) => Err("the stepper link is busy with an operation - try again when it finishes".to_string()),
+            },
+        };
+        drop(ops);
+        match result {
+            Ok(sent) => self.append_message(&format!("{} bow wheel speed set to {}", label, sent)),
+            Err(e) => self.append_message(&format!("Error: {} bow wheel speed not set: {}", label, e)),
+        }
+    }
+
+    /// A string row's Z steps: ticked, bump_check, z_calibrate and z_adjust use the
+    /// string's own up / down step instead of the global ones
+    fn show_string_z_steps(&mut self, ui: &mut egui::Ui, string_idx: usize) {
+        let ops = self.operations.read().unwrap();
+        let (up, down) = ops.get_string_z_steps(string_idx);
+        let mut own = up.is_some() || down.is_some();
+        let mut up_step = up.unwrap_or_else(|| ops.get_z_up_step());
+        let mut down_step = down.unwrap_or_else(|| ops.get_z_down_step());
+        drop(ops);
+        let mut changed = ui.checkbox(&mut own, tr("Own Z steps"))
+            .on_hover_text(tr("Use this string's Z up / down steps instead of the global ones"))
+            .changed();
+        changed |= ui.add_enabled(own, egui::DragValue::new(&mut up_step).clamp_range(2..=20)).changed();
+        changed |= ui.add_enabled(own, egui::DragValue::new(&mut down_step).clamp_range(-20..=-2)).changed();
+        if !changed {
+            return;
+        }
+        let operations = Arc::clone(&self.operations);
+        let ops = operations.read().unwrap();
+        let label = ops.string_label(string_idx);
+        if own {
+            ops.set_string_z_steps(string_idx, Some(up_step), Some(down_step));
+            drop(ops);
+            self.append_message(&format!("{} Z steps set to +{} / {}", label, up_step, down_step));
+        } else {
+            ops.set_string_z_steps(string_idx, None, None);
+            drop(ops);
+            self.append_message(&format!("{} back to the global Z steps", label));
+        }
+    }
+
+    /// Engage or release one string's damper, or every string's when `string_idx` is None
+    fn set_damper(&mut self, string_idx: Option<usize>, engaged: bool) {
+        let operations = Arc::clone(&self.operations);
+        let ops = operations.read().unwrap();
+        let target = match string_idx {
+            Some(idx) => format!("{} damper", ops.string_label(idx)),
+            None => "All dampers".to_string(),
+        };
+        let result = match self.arduino_ops.as_ref() {
+            None => Err("no stepper_gui link".to_string()),
+            // A running operation holds the link; taking it here would stall the GUI
+            Some(arduino_ops) => match arduino_ops.try_lock() {
+                Ok(mut client) => match string_idx {
+                    Some(idx) => ops.damp(&mut *client, idx, engaged),
+                    None => ops.damp_all(&mut *client, engaged),
+                }
+                .map_err(|e| error::user_message(&e)),
+                Err(_) => Err("the stepper link is busy with an operation - try again when it finishes".to_string()),
+            },
+        };
+        drop(ops);
+        let state = if engaged { "engaged" } else { "released" };
+        match result {
+            Ok(()) => self.append_message(&format!("{} {}", target, state)),
+            Err(e) => self.append_message(&format!("Error: {} not {}: {}", target, state, e)),
+        }
+    }
+
+    /// Apply what stepper_gui reports for the shared state (step sizes, lockout). Only
+    /// values that changed since its previous report are applied, so an edit made here
+    /// is not undone by a report sent before stepper_gui saw it.
+    fn follow_shared_state(&mut self) {
+        let Some(sync) = self.shared_sync.as_mut() else {
+            return;
+        };
+        let Some(latest) = sync.latest.lock().ok().and_then(|s| s.clone()) else {
+            return;
+        };
+        if sync.applied.as_ref() == Some(&latest) {
+            return;
+        }
+        let previous = sync.applied.replace(latest.clone());
+        let operations = Arc::clone(&self.operations);
+        let ops = operations.read().unwrap();
+        let mut messages = Vec::new();
+        let changed = |value: fn(&shared_state::SharedState) -> i32| previous.as_ref().map_or(true, |p| value(p) != value(&latest));
+        if changed(|s| s.z_up_step) && ops.get_z_up_step() != latest.z_up_step {
+            ops.set_z_up_step(latest.z_up_step);
+            messages.push(format!("Z up step set to {} in stepper_gui", latest.z_up_step));
+        }
+        if changed(|s| s.z_down_step) && ops.get_z_down_step() != latest.z_down_step {
+            ops.set_z_down_step(latest.z_down_step);
+            messages.push(format!("Z down step set to {} in stepper_gui", latest.z_down_step));
+        }
+        if changed(|s| s.x_step) && ops.get_x_step() != latest.x_step {
+            ops.set_x_step(latest.x_step);
+            messages.push(format!("X step set to {} in stepper_gui", latest.x_step));
+        }
+        let previous_disabled = previous.map(|p| p.disabled).unwrap_or_default();
+        for &idx in latest.disabled.difference(&previous_disabled) {
+            if ops.get_stepper_enabled(idx) {
+                ops.set_stepper_enabled(idx, false);
+                messages.push(format!("{} disabled in stepper_gui", ops.stepper_label(idx)));
+            }
+        }
+        for &idx in previous_disabled.difference(&latest.disabled) {
+            if !ops.get_stepper_enabled(idx) {
+                ops.set_stepper_enabled(idx, true);
+                messages.push(format!("{} enabled in stepper_gui", ops.stepper_label(idx)));
+            }
+        }
+        drop(ops);
+        for message in messages {
+            self.append_message(&message);
+        }
+    }
+
+    /// Send this GUI's step sizes to stepper_gui, which passes them on to its subscribers
+    fn publish_step_sizes(&mut self) {
+        let Some(sync) = self.shared_sync.as_ref() else {
+            return;
+        };
+        let cmd = {
+            let ops = self.operations.read().unwrap();
+            format!("set_shared z_up_step={} z_down_step={} x_step={}", ops.get_z_up_step(), ops.get_z_down_step(), ops.get_x_step())
+        };
+        if let Err(e) = ArduinoStepperOps::send_oneshot(&sync.socket_path, &cmd) {
+            self.append_message(&format!("Warning: step sizes not shared with stepper_gui: {}", e));
+        }
+    }
+
+    /// Raise serial_lost when the stepper link or a board drops out, and string_break
+    /// when a channel that was sounding goes quiet while the other strings keep playing
+    fn watch_alerts(&mut self) {
+        let operations = Arc::clone(&self.operations);
+        let ops = operations.read().unwrap();
+        if ops.alert_enabled(config_loader::AlertEvent::SerialLost) {
+            let link_down = self.stepper_link_down.as_ref().is_some_and(|flag| flag.load(std::sync::atomic::Ordering::Relaxed));
+            if link_down && !self.alert_watch.link_down {
+                ops.alert(config_loader::AlertEvent::SerialLost, "Lost the link to stepper_gui");
+            }
+            self.alert_watch.link_down = link_down;
+            let status = self.stepper_status.lock().ok().and_then(|s| *s);
+            let boards = [
+                ("main", &mut self.alert_watch.main_connected, status.map(|s| s.main_connected)),
+                ("tuner", &mut self.alert_watch.tuner_connected, status.and_then(|s| s.tuner_connected)),
+            ];
+            for (board, was, now) in boards {
+                if *was == Some(true) && now == Some(false) {
+                    ops.alert(config_loader::AlertEvent::SerialLost, &format!("Arduino {} board disconnected", board));
+                }
+                if now.is_some() {
+                    *was = now;
+                }
+            }
+        }
+
+        if ops.alert_enabled(config_loader::AlertEvent::StringBreak) {
+            let amp_sum = ops.get_amp_sum();
+            let mask = ops.get_channel_mask();
+            let silence = ops.string_break_silence();
+            let amp_min = |ch: usize| self.amp_sum_min.get(ch).copied().unwrap_or(20).max(1) as f32;
+            let sounding: Vec<bool> = amp_sum.iter().enumerate().map(|(ch, &amp)| amp >= amp_min(ch)).collect();
+            let watch = &mut self.alert_watch;
+            watch.sounded.resize(amp_sum.len(), false);
+            watch.silent_since.resize(amp_sum.len(), None);
+            for (ch, &amp) in amp_sum.iter().enumerate() {
+                if sounding[ch] {
+                    watch.sounded[ch] = true;
+                    watch.silent_since[ch] = None;
+                    continue;
+                }
+                let silent = amp < amp_min(ch) * STRING_BREAK_SILENT_FRACTION;
+                let others_sounding = sounding.iter().enumerate().any(|(other, &s)| other != ch && s);
+                let watched = mask.excluded_reason(ch).is_none() && !ops.is_channel_paused(ch);
+                if !(silent && others_sounding && watched && watch.sounded[ch]) {
+                    watch.silent_since[ch] = None;
+                    continue;
+                }
+                let since = *watch.silent_since[ch].get_or_insert_with(Instant::now);
+                if since.elapsed() >= silence {
+                    let string = ops.string_for_channel(ch).map(|idx| ops.string_label(idx)).unwrap_or_else(|| "no string".to_string());
+                    ops.alert(
+                        config_loader::AlertEvent::StringBreak,
+                        &format!("Possible string break: channel {} ({}) silent for {:.0}s while other strings sound", ch, string, silence.as_secs_f32()),
+                    );
+                    // Once per break: the channel has to sound again to re-arm
+                    watch.sounded[ch] = false;
+                    watch.silent_since[ch] = None;
+                }
+            }
+        }
+    }
+
+    /// Reset the IDLE_PARK timer
+    fn note_activity(&mut self) {
+        if let Some(idle) = self.idle_park.as_mut() {
+            idle.last_activity = Instant::now();
+        }
+    }
+
+    /// Park when no operation has run, nobody has touched the GUI and no stepper has
+    /// moved for IDLE_PARK.MINUTES while a Z stepper is still down near its string
+    fn watch_idle(&mut self) {
+        if self.arduino_ops.is_none() {
+            return;
+        }
+        let busy = self.is_busy();
+        let positions = self.stepper_positions.lock().map(|p| p.clone()).unwrap_or_default();
+        let Some(idle) = self.idle_park.as_mut() else {
+            return;
+        };
+        if busy || positions != idle.last_positions {
+            idle.last_positions = positions;
+            idle.last_activity = Instant::now();
+            return;
+        }
+        if idle.last_activity.elapsed() < idle.settings.timeout {
+            return;
+        }
+        idle.last_activity = Instant::now();
+        let (timeout, margin) = (idle.settings.timeout, idle.settings.margin);
+        let near = {
+            let ops = self.operations.read().unwrap();
+            let near = ops.z_steppers_below_park(&positions, &z_max_positions(&ops.get_z_stepper_indices()), margin);
+            if !near.is_empty() {
+                let labels: Vec<String> = near.iter().map(|&idx| ops.stepper_label(idx)).collect();
+                ops.alert(
+                    config_loader::AlertEvent::IdlePark,
+                    &format!("Idle for {:.0} min with {} near the strings - parking", timeout.as_secs_f32() / 60.0, labels.join(", ")),
+                );
+            }
+            near
+        };
+        if near.is_empty() {
+            return;
+        }
+        self.append_message(&format!(
+            "Idle for {:.0} min with {} Z stepper(s) near the strings - parking",
+            timeout.as_secs_f32() / 60.0,
+            near.len()
+        ));
+        self.launch_operation("park".to_string(), Priority::Scheduled);
+    }
+
+    /// AUDIO_TRIGGER: start its operation once the instrument has sounded for SECONDS,
+    /// stop it and park after SILENCE_MINUTES of silence, then wait armed again
+    fn watch_audio_trigger(&mut self) {
+        let Some(settings) = self.operations.read().unwrap().audio_trigger_settings() else {
+            return;
+        };
+        let running = self.operation_task.as_ref().is_some_and(|task| task.operation == settings.operation);
+        let action = self.operations.read().unwrap().observe_audio_trigger(running, self.is_busy());
+        match action {
+            Some(audio_trigger::TriggerAction::Start) => {
+                self.append_message(&format!(
+                    "Audio trigger: sound above {} for {:.0}s - starting {}",
+                    settings.threshold,
+                    settings.sound_for.as_secs_f32(),
+                    settings.operation
+                ));
+                self.launch_operation(settings.operation, Priority::Scheduled);
+            }
+            Some(audio_trigger::TriggerAction::Stop) => {
+                self.append_message(&format!(
+                    "Audio trigger: silent for {:.0} min - stopping {} and parking",
+                    settings.silence_for.as_secs_f32() / 60.0,
+                    settings.operation
+                ));
+                // Park waits in the queue until the operation has stopped
+                self.exit_flag.store(true, std::sync::atomic::Ordering::Relaxed);
+                self.launch_operation("park".to_string(), Priority::Scheduled);
+            }
+            Some(audio_trigger::TriggerAction::Ended) => {
+                self.append_message(&format!("Audio trigger: {} ended - armed again", settings.operation));
+            }
+            None => {}
+        }
+    }
+
+    /// Audio trigger panel: state, settings and Arm / Disarm
+    fn render_audio_trigger(&mut self, ui: &mut egui::Ui) {
+        let ops = self.operations.read().unwrap();
+        let (Some(settings), Some(state), Some(status)) = (ops.audio_trigger_settings(), ops.audio_trigger_state(), ops.describe_audio_trigger()) else {
+            return;
+        };
+        drop(ops);
+        ui.label(trf(
+            "Starts {0} after {1}s above {2} on any channel, stops it and parks after {3} min of silence",
+            &[&settings.operation, &format!("{:.0}", settings.sound_for.as_secs_f32()), &settings.threshold, &format!("{:.0}", settings.silence_for.as_secs_f32() / 60.0)],
+        ));
+        ui.horizontal(|ui| {
+            ui.label(trf("State: {0}", &[&status]));
+            let armed = state != audio_trigger::TriggerState::Disarmed;
+            let (label, hover) = if armed {
+                ("Disarm", "Stop listening; an operation it started keeps running")
+            } else {
+                ("Arm", "Start the operation when the instrument is played")
+            };
+            if ui.button(tr(label)).on_hover_text(tr(hover)).clicked() {
+                self.operations.read().unwrap().arm_audio_trigger(!armed);
+                self.append_message(if armed { "Audio trigger disarmed" } else { "Audio trigger armed" });
+            }
+        });
+    }
+
+    /// Meter label for an audio channel: "Ch 2", or "Ch 2 (A2)" when its string has a NAME
+    fn channel_label(&self, ch_idx: usize) -> String {
+        let ops = self.operations.read().unwrap();
+        let name = ops.string_for_channel(ch_idx)
+            .and_then(|string_idx| ops.strings.get(string_idx))
+            .and_then(|info| info.name.as_deref());
+        match name {
+            Some(name) => format!("Ch {} ({})", ch_idx, name),
+            None => format!("Ch {}", ch_idx),
+        }
+    }
+
     /// Append message
     fn append_message(&mut self, msg: &str) {
         if !self.message.is_empty() {
@@ -518,17 +2991,15 @@ impl OperationsGUI {
         self.message.push_str(msg);
     }
     
-    fn sync_voice_threshold_caps(&mut self, new_cap: i32) {
-        let cap = std::cmp::max(1, new_cap);
+    /// Carry the voice count thresholds from `old_cap` partials per channel over to
+    /// `new_cap` (see rescale_voice_count), keeping min <= max
+    fn rescale_voice_thresholds(&mut self, old_cap: i32, new_cap: i32) {
+        let (old_cap, new_cap) = (old_cap.max(1) as usize, new_cap.max(1) as usize);
         for max_val in self.voice_count_max.iter_mut() {
-            if *max_val > cap {
-                *max_val = cap;
-            }
+            *max_val = rescale_voice_count(*max_val, old_cap, new_cap);
         }
         for (idx, min_val) in self.voice_count_min.iter_mut().enumerate() {
-            if *min_val > cap {
-                *min_val = cap;
-            }
+            *min_val = rescale_voice_count(*min_val, old_cap, new_cap);
             if let Some(current_max) = self.voice_count_max.get(idx) {
                 if *min_val > *current_max {
                     *min_val = *current_max;
@@ -537,6 +3008,66 @@ impl OperationsGUI {
         }
     }
     
+    /// Set each channel's amp_sum min/max to what its height curve shows for the
+    /// height target; channels without a curve keep their thresholds
+    fn apply_height_target(&mut self, channels: usize) {
+        let (low, high) = self.height_target;
+        let bands = self.operations.read().unwrap().height_target_thresholds(low, high, channels);
+        if bands.iter().all(Option::is_none) {
+            self.append_message("No height map for these channels yet - run Height Calibrate first");
+            return;
+        }
+        self.amp_sum_min.resize(self.amp_sum_min.len().max(channels), 20);
+        self.amp_sum_max.resize(self.amp_sum_max.len().max(channels), 250);
+        let mut missing = Vec::new();
+        for (ch_idx, band) in bands.into_iter().enumerate() {
+            match band {
+                Some((min, max)) => {
+                    self.amp_sum_min[ch_idx] = min.floor() as i32;
+                    self.amp_sum_max[ch_idx] = max.ceil() as i32;
+                }
+                None => missing.push(self.channel_label(ch_idx)),
+            }
+        }
+        self.height_target_bands = (0..channels)
+            .map(|ch_idx| Some((*self.amp_sum_min.get(ch_idx)?, *self.amp_sum_max.get(ch_idx)?)))
+            .collect();
+        self.append_message(&format!("Amp sum thresholds set for {}-{} steps above contact", low, high));
+        if !missing.is_empty() {
+            self.append_message(&format!("No height curve for {} - thresholds unchanged", missing.join(", ")));
+        }
+    }
+
+    /// Bumps bump_check recorded: to the machine state backends and the Bumps panel
+    fn collect_bump_events(&mut self) {
+        let events = self.operations.read().unwrap().take_bump_events();
+        for event in events {
+            if let Some(logger) = self.logger.as_ref() {
+                logger.insert_bump_event(&event);
+            }
+            self.bumps.push(event);
+        }
+    }
+
+    /// A finished operation into the operations table, for What Changed's pre/post diffs
+    fn log_operation_event(&self, result: &OperationResult, started_at: chrono::DateTime<Utc>) {
+        let Some(logger) = self.logger.as_ref() else { return };
+        let mut positions: Vec<(usize, i32)> = result.updated_positions.iter().map(|(&idx, &pos)| (idx, pos)).collect();
+        positions.sort_unstable();
+        logger.insert_operation(&machine_state_logger::OperationEvent {
+            operation_id: Uuid::new_v4(),
+            state_id: None,
+            host: self.hostname.clone(),
+            recorded_at: Utc::now(),
+            operation_type: result.operation.clone(),
+            operation_status: result.outcome.name().to_string(),
+            message: result.message.clone(),
+            stepper_indices: positions.iter().map(|&(idx, _)| idx).collect(),
+            final_positions: positions.iter().map(|&(_, pos)| pos).collect(),
+            started_at: Some(started_at),
+        });
+    }
+
     fn publish_voice_thresholds_to_logger(&self) {
         if self.voice_count_min_logger.is_none() && self.voice_count_max_logger.is_none() {
             return;
@@ -555,24 +3086,39 @@ impl OperationsGUI {
         }
     }
     
+    /// Follow audmon's partials per channel: report layout changes and rescale the
+    /// voice thresholds to the new cap (also when the first frame differs from the hint)
     pub fn reconcile_voice_count_cap(&mut self) {
-        let detected = self.partials_per_channel.load(std::sync::atomic::Ordering::Relaxed) as i32;
+        let changes: Vec<LayoutChange> = self.layout_changes.try_iter().collect();
+        for change in changes {
+            self.append_message(&format!(
+                "audmon now writes {} channels x {} partials (was {} x {})",
+                change.to.channels, change.to.partials_per_channel, change.from.channels, change.from.partials_per_channel
+            ));
+        }
+        let detected = self.audmon.partials_per_channel() as i32;
         if detected <= 0 {
             return;
         }
         if detected != self.voice_count_cap_cache {
-            self.voice_count_cap_cache = detected;
-            self.sync_voice_threshold_caps(detected);
+            let old_cap = std::mem::replace(&mut self.voice_count_cap_cache, detected);
+            self.rescale_voice_thresholds(old_cap, detected);
             self.publish_voice_thresholds_to_logger();
+            tracing::info!("{}: voice count thresholds rescaled from {} to {} partials per channel", self.hostname, old_cap, detected);
         }
     }
     
     pub fn poll_operation_result(&mut self) {
+        self.poll_probe_results();
         let mut should_clear = false;
         let mut schedule_repeat_op: Option<String> = None;
         if let Some(task) = self.operation_task.as_mut() {
+            let started_at = task.started_at;
             match task.receiver.try_recv() {
                 Ok(result) => {
+                    if !result.is_progress {
+                        self.log_operation_event(&result, started_at);
+                    }
                     for (idx, pos) in result.updated_positions {
                         if let Ok(mut positions) = self.stepper_positions.lock() {
                             positions.insert(idx, pos);
@@ -583,6 +3129,19 @@ impl OperationsGUI {
                     // If this is a progress message, just append it and continue
                     // If it's the final result, mark operation as complete
                     if !result.is_progress {
+                        self.last_operation = Some((result.operation.clone(), result.outcome, Instant::now()));
+                        self.operations.read().unwrap().hook(config_loader::HookEvent::OperationFinished, serde_json::json!({
+                            "operation": result.operation,
+                            "outcome": result.outcome.name(),
+                            "message": result.message.trim(),
+                            "duration_s": (Utc::now() - started_at).num_milliseconds() as f64 / 1000.0,
+                        }));
+                        if !result.outcome.succeeded() {
+                            self.operations.read().unwrap().alert(
+                                config_loader::AlertEvent::OperationFailed,
+                                &format!("{} failed: {}", result.operation, result.message.trim()),
+                            );
+                        }
                         self.operation_running.store(false, std::sync::atomic::Ordering::Relaxed);
                         // Reset exit flag when operation completes (unless it's a kill_all shutdown)
                         // This allows break button to work without closing the window
@@ -596,6 +3155,17 @@ impl OperationsGUI {
                 Err(TryRecvError::Empty) => {}
                 Err(TryRecvError::Disconnected) => {
                     self.append_message("Operation worker disconnected unexpectedly");
+                    self.operations.read().unwrap().alert(
+                        config_loader::AlertEvent::OperationFailed,
+                        &format!("{} failed: operation worker disconnected unexpectedly", self.selected_operation),
+                    );
+                    self.last_operation = Some((self.selected_operation.clone(), operations::OperationOutcome::Failed, Instant::now()));
+                    self.operations.read().unwrap().hook(config_loader::HookEvent::OperationFinished, serde_json::json!({
+                        "operation": self.selected_operation,
+                        "outcome": operations::OperationOutcome::Failed.name(),
+                        "message": "operation worker disconnected unexpectedly",
+                        "duration_s": (Utc::now() - started_at).num_milliseconds() as f64 / 1000.0,
+                    }));
                     self.operation_running.store(false, std::sync::atomic::Ordering::Relaxed);
                     // Reset exit flag when operation completes
                     self.exit_flag.store(false, std::sync::atomic::Ordering::Relaxed);
@@ -605,12 +3175,23 @@ impl OperationsGUI {
         }
 
         if should_clear {
-            self.operation_task = None;
+            if let Some(task) = self.operation_task.take() {
+                self.operations.read().unwrap().release_operation(&task.operation);
+            }
+            let next = self.operations.read().unwrap().next_queued_operation();
+            if let Some(next) = next {
+                self.append_message(&format!("Starting queued {} ({})", next.operation, next.priority.name()));
+                self.start_operation(next.operation, next.priority);
+            }
         }
 
         if let Some(op) = schedule_repeat_op {
             if self.repeat_enabled {
-                let lap_rest = self.operations.read().unwrap().get_lap_rest().max(0.0);
+                let lap_rest = self.operations.read().unwrap()
+                    .effective_rests(&op, self.rest_overrides)
+                    .lap_rest
+                    .unwrap_or(0.0)
+                    .max(0.0);
                 let wait = if lap_rest <= 0.0 {
                     Duration::from_secs(0)
                 } else {
@@ -631,26 +3212,26 @@ impl OperationsGUI {
 
 
     /// Execute the selected operation
+    /// Execute the selected operation: at once when nothing is in its way, else queued
+    /// behind the running one (see arbitration)
     fn execute_operation(&mut self) {
-        if self.operation_running.load(std::sync::atomic::Ordering::Relaxed) {
-            self.append_message("Operation already running - please wait");
-            return;
-        }
-
         self.poll_operation_result();
 
-        if self.operation_task.is_some() {
-            self.append_message("Operation still completing - please wait");
-            return;
-        }
-
         let selected_operation = self.selected_operation.clone();
         if selected_operation == "None" {
             self.append_message("No operation selected");
             return;
         }
 
-        self.start_operation(selected_operation);
+        self.start_operation(selected_operation, Priority::User);
+    }
+
+    /// The PARK button: parks now, or stops the running operation and parks next
+    fn park_now(&mut self) {
+        self.repeat_pending = None;
+        self.selected_operation = "park".to_string();
+        let priority = if self.is_busy() { Priority::Estop } else { Priority::User };
+        self.start_operation("park".to_string(), priority);
     }
 
     fn try_start_scheduled_repeat(&mut self) {
@@ -664,14 +3245,103 @@ impl OperationsGUI {
             if Instant::now() >= deadline {
                 self.repeat_pending = None;
                 self.append_message(&format!("Repeat interval elapsed - re-running {}", op_name));
-                self.start_operation(op_name);
+                self.start_operation(op_name, Priority::Scheduled);
+            }
+        }
+    }
+
+    fn start_operation(&mut self, operation: String, priority: Priority) {
+        // Performer mode only runs performance_mode and park (also stops repeats and broadcasts of anything else)
+        let performer_operation = self.operations.read().unwrap().describe_operation(&operation).is_some_and(|op| op.performer);
+        if !self.role_lock.is_technician() && !performer_operation {
+            self.append_message(&format!("{} is locked in performer mode - switch to technician mode to run it", operation));
+            return;
+        }
+        self.launch_operation(operation, priority);
+    }
+
+    /// Run a read-only operation on its own thread, beside any motion operation
+    fn launch_probe(&mut self, operation: String) {
+        self.append_message(&format!("Executing {} (read-only)...", operation));
+        self.operations.read().unwrap().claim_operation(&operation);
+        let operations = Arc::clone(&self.operations);
+        let (tx, rx) = mpsc::channel();
+        self.probe_tasks.push(OperationTask { receiver: rx, operation: operation.clone(), started_at: Utc::now() });
+        thread::spawn(move || {
+            let _span = tracing::info_span!("probe", name = %operation).entered();
+            let result = operations.read().map_err(|_| error::Error::Other("Operations lock poisoned".to_string())).and_then(|ops| ops.run_probe(&operation));
+            let outcome = operations::OperationOutcome::of(&result);
+            let message = match result {
+                Ok(msg) => msg,
+                Err(e) => format!("Error: {}", error::user_message(&e)),
+            };
+            let _ = tx.send(OperationResult { operation, message, updated_positions: std::collections::HashMap::new(), is_progress: false, outcome });
+        });
+    }
+
+    /// Collect finished probes; they leave the instrument state alone
+    fn poll_probe_results(&mut self) {
+        let mut finished = Vec::new();
+        self.probe_tasks.retain(|task| match task.receiver.try_recv() {
+            Ok(result) => {
+                finished.push(result);
+                false
+            }
+            Err(TryRecvError::Empty) => true,
+            Err(TryRecvError::Disconnected) => {
+                finished.push(OperationResult {
+                    operation: task.operation.clone(),
+                    message: "Operation worker disconnected unexpectedly".to_string(),
+                    updated_positions: std::collections::HashMap::new(),
+                    is_progress: false,
+                    outcome: operations::OperationOutcome::Failed,
+                });
+                false
             }
+        });
+        for result in finished {
+            self.operations.read().unwrap().release_operation(&result.operation);
+            self.append_message(&result.message);
+            self.last_probe = Some((result.operation, result.outcome));
         }
     }
 
-    fn start_operation(&mut self, operation: String) {
+    /// Start `operation` on a worker thread (no role check - see start_operation), or
+    /// queue it at `priority` behind the running motion operation
+    fn launch_operation(&mut self, operation: String, priority: Priority) {
+        let admission = self.operations.read().unwrap().request_operation(&operation, priority);
+        match admission {
+            Admission::Start => {}
+            Admission::Queued(position) => {
+                let running = self.operation_task.as_ref().map_or_else(String::new, |task| task.operation.clone());
+                let priority = Priority::for_operation(&operation, priority);
+                self.append_message(&format!("{} queued ({}, #{}) until {} finishes", operation, priority.name(), position, running));
+                // An estop does not wait for the running operation to finish by itself
+                if priority == Priority::Estop {
+                    self.exit_flag.store(true, std::sync::atomic::Ordering::Relaxed);
+                    self.fire_estop("park");
+                }
+                return;
+            }
+            Admission::Interject => {
+                let running = self.operation_task.as_ref().map_or_else(String::new, |task| task.operation.clone());
+                self.append_message(&format!("{} will run during the pause of {}", operation, running));
+                return;
+            }
+            Admission::Refused(reason) => {
+                self.append_message(&format!("Error: {} not started: {}", operation, reason));
+                return;
+            }
+        }
+        if self.is_probe(&operation) {
+            self.launch_probe(operation);
+            return;
+        }
         // Reset exit flag when starting a new operation
         self.exit_flag.store(false, std::sync::atomic::Ordering::Relaxed);
+        let time_limit = self.next_run_time_limit.take().or(self.time_limit.map(Duration::from_secs_f32));
+        let rest_overrides = self.next_run_rests.take().unwrap_or_default().or(self.rest_overrides);
+        let bump_overrides = self.next_run_bump.take().unwrap_or_default();
         
         let arduino_ops = match self.arduino_ops.as_ref() {
             Some(ops) => Arc::clone(ops),
@@ -687,27 +3357,39 @@ impl OperationsGUI {
             return;
         }
 
-        match operation.as_str() {
-            "z_calibrate" => self.append_message("Executing Z Calibrate..."),
-            "z_adjust" => self.append_message("Executing Z Adjust..."),
-            "bump_check" => self.append_message("Executing Bump Check..."),
-            "right_left_move" => self.append_message("Executing Right Left Move..."),
-            "left_right_move" => self.append_message("Executing Left Right Move..."),
-            "x_home" => self.append_message("Executing X Home..."),
-            "x_away" => self.append_message("Executing X Away..."),
-            "x_calibrate" => self.append_message("Executing X Calibrate..."),
-            _ => {
+        // Until the steppers are re-homed (or the positions accepted) only homing may run
+        let startup_mismatch = self.stepper_status.lock().ok().and_then(|s| *s).is_some_and(|s| s.startup_mismatch);
+        if startup_mismatch && !REHOME_OPERATIONS.contains(&operation.as_str()) {
+            self.append_message(&format!(
+                "Error: {} not started: stepper_gui reports positions that differ from the last session. Run {} or accept the positions in stepper_gui",
+                operation,
+                REHOME_OPERATIONS.join(" / ")
+            ));
+            self.last_operation = Some((operation.clone(), operations::OperationOutcome::Failed, Instant::now()));
+            return;
+        }
+
+        // Instrument state guards: not while busy, sweeps only once calibrated, recovery only while faulted
+        let allowed = self.operations.read().map_err(|_| "operations lock poisoned".to_string()).and_then(|ops| ops.can_start(&operation));
+        if let Err(reason) = allowed {
+            self.append_message(&format!("Error: {} not started: {}", operation, reason));
+            self.last_operation = Some((operation.clone(), operations::OperationOutcome::Failed, Instant::now()));
+            return;
+        }
+
+        match self.operations.read().ok().and_then(|ops| ops.describe_operation(&operation)) {
+            Some(op) if op.runs_until_break => self.append_message(&format!("Executing {} (press BREAK to stop)...", op.label)),
+            Some(op) => self.append_message(&format!("Executing {}...", op.label)),
+            None => {
                 self.append_message("No operation selected");
                 return;
             }
         }
 
-        // Get all stepper indices including X stepper for position tracking
+        // Get all stepper indices including X and the other axes for position tracking
         let ops_guard = self.operations.read().unwrap();
         let mut all_indices = z_indices.clone();
-        if let Some(x_idx) = ops_guard.x_step_index() {
-            all_indices.push(x_idx);
-        }
+        all_indices.extend(ops_guard.axes().iter().map(|axis| axis.step_index));
         drop(ops_guard);
         
         // Fetch current positions from stepper_gui before starting operation to ensure accuracy
@@ -716,12 +3398,12 @@ impl OperationsGUI {
             .map(|map| map.clone())
             .unwrap_or_default();
         
-        // Try to fetch fresh positions from stepper_gui socket before starting operation
-        if let Some(ref arduino_ops) = self.arduino_ops {
-            if let Ok(ops_guard) = arduino_ops.lock() {
-                let socket_path = ops_guard.socket_path();
-                drop(ops_guard);
-                if let Ok(fresh_positions) = ArduinoStepperOps::fetch_positions_from_socket(&socket_path) {
+        // Fetch fresh positions from stepper_gui before starting the operation
+        if let Ok(ops_guard) = arduino_ops.lock() {
+            let socket_path = ops_guard.socket_path();
+            drop(ops_guard);
+            match ArduinoStepperOps::fetch_positions_from_socket(&socket_path) {
+                Ok(fresh_positions) => {
                     // Update snapshot with fresh positions
                     for (idx, pos) in fresh_positions.iter().enumerate() {
                         positions_snapshot.insert(idx, *pos);
@@ -733,6 +3415,13 @@ impl OperationsGUI {
                         }
                     }
                 }
+                // The board isn't answering: starting from stale positions would move steppers blind
+                Err(e) if matches!(e.downcast_ref::<error::Error>(), Some(error::Error::SerialTimeout { .. })) => {
+                    self.append_message(&format!("Error: {} not started: {}", operation, error::user_message(e.as_ref())));
+                    self.last_operation = Some((operation.clone(), operations::OperationOutcome::Failed, Instant::now()));
+                    return;
+                }
+                Err(_) => {}
             }
         }
         
@@ -745,15 +3434,14 @@ impl OperationsGUI {
                 positions[idx] = positions_snapshot.get(&idx).copied().unwrap_or(0);
             }
         }
-        let mut max_positions = std::collections::HashMap::new();
-        for &idx in &z_indices {
-            max_positions.insert(idx, 100);
-        }
+        let max_positions = z_max_positions(&z_indices);
 
-        let min_thresholds: Vec<f32> = self.amp_sum_min.iter().map(|&v| v as f32).collect();
-        let max_thresholds: Vec<f32> = self.amp_sum_max.iter().map(|&v| v as f32).collect();
-        let min_voices: Vec<usize> = self.voice_count_min.iter().map(|&v| v.max(0) as usize).collect();
-        let max_voices: Vec<usize> = self.voice_count_max.iter().map(|&v| v.max(0) as usize).collect();
+        let bands = operations::ChannelBands {
+            amp_sum_min: self.amp_sum_min.clone(),
+            amp_sum_max: self.amp_sum_max.clone(),
+            voice_count_min: self.voice_count_min.clone(),
+            voice_count_max: self.voice_count_max.clone(),
+        };
 
         let operations = Arc::clone(&self.operations);
         let exit_flag = Arc::clone(&self.exit_flag);
@@ -761,12 +3449,15 @@ impl OperationsGUI {
         let operation_label = operation.clone();
 
         let (tx, rx) = mpsc::channel();
-        self.operation_task = Some(OperationTask { receiver: rx });
+        self.operations.read().unwrap().claim_operation(&operation);
+        self.operation_task = Some(OperationTask { receiver: rx, operation: operation.clone(), started_at: Utc::now() });
         self.operation_running.store(true, std::sync::atomic::Ordering::Relaxed);
+        self.operations.read().unwrap().hook(config_loader::HookEvent::OperationStarted, serde_json::json!({ "operation": operation }));
 
         thread::spawn(move || {
             let mut local_positions = positions;
             let op_name = operation_label;
+            let _span = tracing::info_span!("operation", name = %op_name).entered();
             let operation_result = {
                 let mut stepper_client = match arduino_ops.lock() {
                     Ok(guard) => guard,
@@ -776,6 +3467,7 @@ impl OperationsGUI {
                             message: "Error: Arduino client lock poisoned".to_string(),
                             updated_positions: std::collections::HashMap::new(),
                             is_progress: false,
+                            outcome: operations::OperationOutcome::Failed,
                         });
                         return;
                     }
@@ -790,116 +3482,71 @@ impl OperationsGUI {
                             message: "Error: Operations lock poisoned".to_string(),
                             updated_positions: std::collections::HashMap::new(),
                             is_progress: false,
+                            outcome: operations::OperationOutcome::Failed,
                         });
                         return;
                     }
                 };
 
-                match op_name.as_str() {
-                    "z_calibrate" => ops_guard.z_calibrate(&mut *stepper_client, &mut local_positions, &max_positions, Some(&exit_flag)),
-                    "z_adjust" => ops_guard.z_adjust(
-                        &mut *stepper_client,
-                        &mut local_positions,
-                        &max_positions,
-                        &min_thresholds,
-                        &max_thresholds,
-                        &min_voices,
-                        &max_voices,
-                        Some(&exit_flag),
-                    ),
-                    "bump_check" => ops_guard.bump_check(
-                        None,
-                        &mut local_positions,
-                        &max_positions,
-                        &mut *stepper_client,
-                        Some(&exit_flag),
-                    ),
-                    "right_left_move" => {
-                        // Sync x_step from stepper_gui before operation
-                        if let Ok(x_step) = ArduinoStepperOps::fetch_x_step_from_socket(&socket_path) {
-                            ops_guard.set_x_step(x_step);
-                        }
-                        // Create progress message channel for real-time updates
-                        let (progress_tx, progress_rx) = std::sync::mpsc::channel();
-                        let tx_clone = tx.clone();
-                        let op_name_clone = op_name.clone();
-                        // Spawn thread to forward progress messages
-                        std::thread::spawn(move || {
-                            while let Ok(msg) = progress_rx.recv() {
-                                let _ = tx_clone.send(OperationResult {
-                                    operation: op_name_clone.clone(),
-                                    message: msg,
-                                    updated_positions: std::collections::HashMap::new(),
-                                    is_progress: true,
-                                });
-                            }
-                        });
-                        ops_guard.right_left_move(
-                        &mut *stepper_client,
-                        &mut local_positions,
-                        &max_positions,
-                        &min_thresholds,
-                        &max_thresholds,
-                        &min_voices,
-                        &max_voices,
-                        Some(&exit_flag),
-                        Some(&progress_tx),
-                        )
-                    },
-                    "left_right_move" => {
-                        // Sync x_step from stepper_gui before operation
-                        if let Ok(x_step) = ArduinoStepperOps::fetch_x_step_from_socket(&socket_path) {
-                            ops_guard.set_x_step(x_step);
-                        }
-                        // Create progress message channel for real-time updates
-                        let (progress_tx, progress_rx) = std::sync::mpsc::channel();
-                        let tx_clone = tx.clone();
-                        let op_name_clone = op_name.clone();
-                        // Spawn thread to forward progress messages
-                        std::thread::spawn(move || {
-                            while let Ok(msg) = progress_rx.recv() {
-                                let _ = tx_clone.send(OperationResult {
-                                    operation: op_name_clone.clone(),
-                                    message: msg,
-                                    updated_positions: std::collections::HashMap::new(),
-                                    is_progress: true,
-                                });
-                            }
-                        });
-                        ops_guard.left_right_move(
-                        &mut *stepper_client,
-                        &mut local_positions,
-                        &max_positions,
-                        &min_thresholds,
-                        &max_thresholds,
-                        &min_voices,
-                        &max_voices,
-                        Some(&exit_flag),
-                        Some(&progress_tx),
-                        )
-                    },
-                    "x_home" => ops_guard.x_home(
-                        &mut *stepper_client,
-                        &mut local_positions,
-                        Some(&exit_flag),
-                        Some(&socket_path),
-                    ),
-                    "x_away" => ops_guard.x_away(
-                        &mut *stepper_client,
-                        &mut local_positions,
-                        Some(&exit_flag),
-                        Some(&socket_path),
-                    ),
-                    "x_calibrate" => ops_guard.x_calibrate(
-                        &mut *stepper_client,
-                        &mut local_positions,
-                        Some(&exit_flag),
-                        Some(&socket_path),
-                    ),
-                    _ => Err(anyhow::anyhow!("Unsupported operation")),
+                if let Err(e) = ops_guard.begin_operation(&op_name) {
+                    let _ = tx.send(OperationResult {
+                        operation: op_name.clone(),
+                        message: format!("Error: {}", e),
+                        updated_positions: std::collections::HashMap::new(),
+                        is_progress: false,
+                        outcome: operations::OperationOutcome::Failed,
+                    });
+                    return;
+                }
+                // Tag commands so stepper_gui's audit log shows which operation moved what
+                stepper_client.set_source(Some(&op_name));
+                // Sync x_step from stepper_gui before a sweep
+                if matches!(op_name.as_str(), "right_left_move" | "left_right_move" | "ping_pong_move") {
+                    if let Ok(x_step) = ArduinoStepperOps::fetch_x_step_from_socket(&socket_path) {
+                        ops_guard.set_x_step(x_step);
+                    }
                 }
+                // Forward progress messages (sweeps, z_hold, performance_mode, ...) as they come
+                let (progress_tx, progress_rx) = std::sync::mpsc::channel();
+                let tx_clone = tx.clone();
+                let op_name_clone = op_name.clone();
+                std::thread::spawn(move || {
+                    while let Ok(msg) = progress_rx.recv() {
+                        let _ = tx_clone.send(OperationResult {
+                            operation: op_name_clone.clone(),
+                            message: msg,
+                            updated_positions: std::collections::HashMap::new(),
+                            is_progress: true,
+                            outcome: operations::OperationOutcome::Succeeded,
+                        });
+                    }
+                });
+                // Per-operation rests and run time limit (YAML OPERATIONS block, or this run's) and this run's bump_check strategy apply for the whole run
+                let operation_result = ops_guard.with_time_budget(&op_name, time_limit, &exit_flag, || ops_guard.with_rest_overrides(&op_name, rest_overrides, || ops_guard.with_bump_overrides(bump_overrides, || ops_guard.run_named(
+                    &op_name,
+                    &mut *stepper_client,
+                    &mut local_positions,
+                    &max_positions,
+                    &bands,
+                    Some(&exit_flag),
+                    Some(&progress_tx),
+                    Some(&socket_path),
+                ))));
+                // A run cut off by its time limit may have stopped with a string pressed down
+                let operation_result = match operation_result {
+                    Err(error::Error::OperationTimedOut { operation, budget, partial }) => {
+                        let retracted = ops_guard.retract_z_to_safe_height(&mut *stepper_client, &mut local_positions, &max_positions);
+                        let partial = [partial, retracted].into_iter().filter(|m| !m.is_empty()).collect::<Vec<_>>().join("\n");
+                        Err(error::Error::OperationTimedOut { operation, budget, partial })
+                    }
+                    other => other,
+                };
+                ops_guard.end_operation(&op_name, &operation_result);
+                stepper_client.set_source(None);
+                operation_result
             };
 
+            let outcome = operations::OperationOutcome::of(&operation_result);
             let message = match op_name.as_str() {
                 "bump_check" => match operation_result {
                     Ok(msg) => {
@@ -909,21 +3556,19 @@ impl OperationsGUI {
                             msg
                         }
                     }
-                    Err(e) => format!("Bump check error: {}", e),
+                    Err(e) => format!("Bump check error: {}", error::user_message(&e)),
                 },
                 _ => match operation_result {
                     Ok(msg) => msg,
-                    Err(e) => format!("Error: {}", e),
+                    Err(e) => format!("Error: {}", error::user_message(&e)),
                 },
             };
 
             let mut updated_positions = std::collections::HashMap::new();
-            // Update positions for all steppers (Z and X)
+            // Update positions for all steppers (Z, X and the other axes)
             let ops_guard_for_update = operations.read().unwrap();
             let mut all_indices_for_update = z_indices_clone.clone();
-            if let Some(x_idx) = ops_guard_for_update.x_step_index() {
-                all_indices_for_update.push(x_idx);
-            }
+            all_indices_for_update.extend(ops_guard_for_update.axes().iter().map(|axis| axis.step_index));
             drop(ops_guard_for_update);
             
             for &idx in &all_indices_for_update {
@@ -932,12 +3577,20 @@ impl OperationsGUI {
                 }
             }
 
-            let _ = tx.send(OperationResult { operation: op_name, message, updated_positions, is_progress: false });
+            let _ = tx.send(OperationResult { operation: op_name, message, updated_positions, is_progress: false, outcome });
         });
     }
 
 
-    /// Kill all processes and close GUI
+    /// This machine's launch registry (its LAUNCH list or the built-in ones), from the
+    /// project directory the GUI runs in
+    fn launch_registry() -> error::Result<Vec<shutdown::Target>> {
+        let hostname = gethostname::gethostname().to_string_lossy().to_string();
+        shutdown::registry(&hostname, &std::env::current_dir().unwrap_or_default())
+    }
+
+    /// EXIT: stop every String Driver process (SIGTERM, then SIGKILL after the grace
+    /// period; this process is spared) and close the GUI
     fn kill_all(&mut self) {
         self.append_message("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
         self.append_message("KILL ALL triggered - shutting down everything...");
@@ -945,257 +3598,606 @@ impl OperationsGUI {
         
         // Set exit flag to stop any running operations
         self.exit_flag.store(true, std::sync::atomic::Ordering::Relaxed);
+        self.fire_estop("kill_all");
         
-        // Run kill script
-        let script_path = std::env::current_dir()
-            .unwrap_or_default()
-            .join("kill_all.sh");
-        
-        if script_path.exists() {
-            match Command::new("bash")
-                .arg(&script_path)
-                .output()
-            {
-                Ok(output) => {
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    if !stdout.is_empty() {
-                        self.append_message(&stdout);
-                    }
-                    if !stderr.is_empty() {
-                        self.append_message(&format!("Errors: {}", stderr));
-                    }
-                }
-                Err(e) => {
-                    self.append_message(&format!("Failed to run kill script: {}", e));
+        match Self::launch_registry() {
+            Ok(targets) => {
+                let report = shutdown::terminate(&targets, shutdown::Mode::Graceful(shutdown::DEFAULT_GRACE));
+                for line in report.lines() {
+                    tracing::info!("EXIT: {}", line);
+                    self.append_message(&line);
                 }
             }
-        } else {
-            self.append_message(&format!("Kill script not found at: {}", script_path.display()));
-            // Fallback: try pkill directly with SIGKILL (-9) - matching exact patterns from launcher
-            // stepper_gui and operations_gui are launched directly by launcher
-            let _ = Command::new("pkill")
-                .args(&["-9", "-f", "stepper_gui"])
-                .output();
-            let _ = Command::new("pkill")
-                .args(&["-9", "-f", "operations_gui"])
-                .output();
-            // audio_monitor is launched by persist script at target/release/audio_monitor
-            let _ = Command::new("pkill")
-                .args(&["-9", "-f", "target/release/audio_monitor"])
-                .output();
-            let _ = Command::new("pkill")
-                .args(&["-9", "-f", "audio_monitor"])
-                .output();
-            // persist script runs in xterm "Persist Monitor"
-            let _ = Command::new("pkill")
-                .args(&["-9", "-f", "Persist Monitor"])
-                .output();
-            let _ = Command::new("pkill")
-                .args(&["-9", "-f", "audmon.sh"])
-                .output();
-            // qjackctl is checked by persist, may be launched separately
-            let _ = Command::new("pkill")
-                .args(&["-9", "-f", "qjackctl"])
-                .output();
-            let _ = Command::new("pkill")
-                .args(&["-9", "qjackctl"])
-                .output();
-            // launcher
-            let _ = Command::new("pkill")
-                .args(&["-9", "-f", "launcher"])
-                .output();
-            self.append_message("Sent kill signals directly");
+            Err(e) => self.append_message(&format!("Could not read the launch registry ({}); closing this GUI only", e)),
         }
         
         // Close this window by exiting process
-        // Give a moment for kill script to run, then exit
         thread::spawn(move || {
             thread::sleep(Duration::from_millis(500));
             std::process::exit(0);
         });
     }
+
+    /// Processes panel: the launch registry's components, with a dry run, Stop and
+    /// Restart (through `launcher --restart`) for the ticked ones
+    fn render_processes(&mut self, ui: &mut egui::Ui) {
+        if self.processes.is_empty() {
+            match Self::launch_registry() {
+                Ok(targets) => self.processes = targets.into_iter().map(|t| (t, false)).collect(),
+                Err(e) => {
+                    ui.label(trf("Launch registry unavailable: {0}", &[&e]));
+                    return;
+                }
+            }
+        }
+        ui.horizontal_wrapped(|ui| {
+            for (target, ticked) in self.processes.iter_mut() {
+                ui.checkbox(ticked, &target.name).on_hover_text(target.patterns.join(", "));
+            }
+        });
+        let selected: Vec<shutdown::Target> = self.processes.iter().filter(|(_, ticked)| *ticked).map(|(t, _)| t.clone()).collect();
+        let idle = !selected.is_empty() && self.process_stop.is_none();
+        let (mut dry_run, mut stop, mut restart) = (false, false, false);
+        ui.horizontal(|ui| {
+            dry_run = ui.add_enabled(idle, egui::Button::new(tr("Dry run"))).on_hover_text(tr("List the processes Stop would end")).clicked();
+            stop = ui.add_enabled(idle, egui::Button::new(tr("Stop selected"))).on_hover_text(tr("SIGTERM, then SIGKILL after 2 s")).clicked();
+            restart = ui.add_enabled(idle, egui::Button::new(tr("Restart selected"))).on_hover_text(tr("Stop, then start again through the launcher")).clicked();
+        });
+        if dry_run {
+            for line in shutdown::terminate(&selected, shutdown::Mode::DryRun).lines() {
+                self.append_message(&line);
+            }
+        } else if stop {
+            let (tx, rx) = mpsc::channel();
+            self.process_stop = Some(rx);
+            thread::spawn(move || {
+                let _ = tx.send(shutdown::terminate(&selected, shutdown::Mode::Graceful(shutdown::DEFAULT_GRACE)));
+            });
+        } else if restart {
+            self.restart_components(&selected);
+        }
+    }
+
+    /// Hand the components to `launcher --restart`, which stops them, starts them
+    /// again and waits for them to be ready
+    fn restart_components(&mut self, targets: &[shutdown::Target]) {
+        let names: Vec<&str> = targets.iter().map(|t| t.name.as_str()).collect();
+        let project_root = std::env::current_dir().unwrap_or_default();
+        let launcher = std::env::current_exe()
+            .map(|exe| exe.with_file_name("launcher"))
+            .unwrap_or_else(|_| project_root.join("target/release/launcher"));
+        match Command::new(&launcher).arg("--restart").args(&names).env("CARGO_MANIFEST_DIR", &project_root).spawn() {
+            Ok(child) => self.append_message(&format!("Restarting {} (launcher pid {})", names.join(", "), child.id())),
+            Err(e) => self.append_message(&format!("Could not run {}: {}", launcher.display(), e)),
+        }
+    }
+
+    /// Report a finished Stop selected
+    fn poll_process_stop(&mut self) {
+        let Some(rx) = self.process_stop.as_ref() else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(report) => {
+                self.process_stop = None;
+                for line in report.lines() {
+                    self.append_message(&line);
+                }
+            }
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => self.process_stop = None,
+        }
+    }
 }
 
 impl OperationsGUI {
     /// Render the UI content (can be called from panels or standalone)
     pub fn render_ui(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
-        ui.heading("Operations Control");
+        // Clicks and key presses anywhere in the window hold off IDLE_PARK
+        if ctx.input(|i| i.pointer.any_pressed() || i.events.iter().any(|e| matches!(e, egui::Event::Key { .. } | egui::Event::Text(_)))) {
+            self.note_activity();
+        }
+        let escape = self.stage_view.is_some() && ctx.input(|i| i.key_pressed(egui::Key::Escape));
+        if escape || ctx.input(|i| i.key_pressed(egui::Key::F11)) {
+            self.toggle_stage_view(ctx);
+        }
+        if self.stage_view.is_some() {
+            self.render_stage_view(ui);
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.heading(tr("Operations Control"));
+            if ui.button(tr("Stage view")).on_hover_text(tr("Full-screen string status and STOP, readable from across the stage (F11)")).clicked() {
+                self.toggle_stage_view(ctx);
+            }
+        });
+            // Performer mode: presets, audio thresholds, channel pauses and performance_mode only
+            self.role_lock.show(ui);
+            let technician = self.role_lock.is_technician();
+
+            // Instrument state (operations refuse to start when the state does not allow them)
+            let (state, calibrated, since) = self.operations.read().unwrap().instrument_state();
+            ui.horizontal(|ui| {
+                let color = match state.name() {
+                    "faulted" => egui::Color32::from_rgb(220, 50, 50),
+                    "uninitialized" | "paused" => egui::Color32::from_rgb(220, 160, 0),
+                    _ if state.is_busy() => egui::Color32::from_rgb(80, 140, 220),
+                    _ => egui::Color32::from_rgb(0, 170, 0),
+                };
+                ui.label(tr("State:"));
+                ui.colored_label(color, egui::RichText::new(state.describe()).strong());
+                ui.label(format!("for {:.0}s", since.elapsed().as_secs_f32()));
+                if !calibrated {
+                    ui.label(tr("(not calibrated)"));
+                }
+                if state.name() == "faulted"
+                    && ui.add_enabled(technician, egui::Button::new(tr("Clear Fault"))).clicked()
+                    && self.operations.read().unwrap().clear_fault()
+                {
+                    self.append_message("Fault cleared");
+                }
+            });
+
+            // stepper_gui link banner (set while its socket is unreachable, cleared on reconnect)
+            let link_down = self.stepper_link_down.as_ref()
+                .map(|flag| flag.load(std::sync::atomic::Ordering::Relaxed))
+                .unwrap_or(false);
+            if link_down {
+                egui::Frame::default()
+                    .fill(egui::Color32::from_rgb(180, 30, 30))
+                    .inner_margin(egui::Margin::same(6.0))
+                    .show(ui, |ui| {
+                        ui.colored_label(egui::Color32::WHITE, egui::RichText::new(tr("⚠ Stepper link down - reconnecting to stepper_gui...")).strong());
+                    });
+            }
+            if let Some(flag) = self.stepper_queue_when_down.as_ref() {
+                let mut queue = flag.load(std::sync::atomic::Ordering::Relaxed);
+                if ui.checkbox(&mut queue, tr("Queue speed/disable commands while link is down")).changed() {
+                    flag.store(queue, std::sync::atomic::Ordering::Relaxed);
+                }
+            }
+
+            // Some inputs are files on disk, so refresh a couple of times a second rather than every frame
+            if self.health_cache.as_ref().map_or(true, |(at, _)| at.elapsed() >= HEALTH_REFRESH) {
+                self.health_cache = Some((Instant::now(), self.collect_health()));
+            }
+            if let Some((_, entries)) = self.health_cache.as_ref() {
+                health::render_health_panel(ui, entries);
+            }
             
             // Machine state logging + exit controls
             ui.horizontal(|ui| {
-                ui.label("Machine State Logging:");
+                ui.label(tr("Machine State Logging:"));
                 if let Some(ref logger) = self.logger {
                     let mut enabled = logger.is_enabled();
-                    if ui.checkbox(&mut enabled, "Enabled").changed() {
+                    if ui.add_enabled(technician, egui::Checkbox::new(&mut enabled, "Enabled")).changed() {
                         logger.set_enabled(enabled);
                         self.logging_enabled = enabled;
                         self.append_message(&format!("Machine state logging {}", if enabled { "enabled" } else { "disabled" }));
                     }
                 } else {
-                    ui.label("(Database not configured)");
+                    ui.label(tr("(Database not configured)"));
                 }
 
-                ui.add_space(16.0);
-                // EXIT button with red background - use Frame with fill
-                let exit_response = egui::Frame::default()
-                    .fill(egui::Color32::from_rgb(220, 32, 32))
-                    .inner_margin(egui::Margin::same(6.0))
-                    .show(ui, |ui| {
-                        ui.add(egui::Button::new(egui::RichText::new("EXIT").strong()))
+                if technician {
+                    ui.add_space(16.0);
+                    // EXIT button with red background - use Frame with fill
+                    let exit_response = egui::Frame::default()
+                        .fill(egui::Color32::from_rgb(220, 32, 32))
+                        .inner_margin(egui::Margin::same(6.0))
+                        .show(ui, |ui| {
+                            ui.add(egui::Button::new(egui::RichText::new(tr("EXIT")).strong()))
+                        });
+                    if exit_response.inner.clicked() {
+                        self.kill_all();
+                    }
+                }
+            });
+            
+            // Instrument profile: this host's YAML section, thresholds and response maps in one file
+            if technician {
+                ui.horizontal(|ui| {
+                    ui.label(tr("Instrument Profile:"));
+                    if ui.button(tr("Export...")).clicked() {
+                        self.export_profile();
+                    }
+                    if ui.button(tr("Import...")).clicked() {
+                        self.import_profile();
+                    }
+                });
+            }
+
+            // Parameter presets: quick switch between named sets from string_driver.yaml
+            ui.horizontal(|ui| {
+                ui.label(tr("Preset:"));
+                let mut chosen = None;
+                egui::ComboBox::from_id_source("preset_select")
+                    .selected_text(self.active_preset.as_deref().unwrap_or("(none)"))
+                    .show_ui(ui, |ui| {
+                        for (name, _) in &self.presets {
+                            let selected = self.active_preset.as_deref() == Some(name.as_str());
+                            if ui.selectable_label(selected, name).clicked() {
+                                chosen = Some(name.clone());
+                            }
+                        }
                     });
-                if exit_response.inner.clicked() {
-                    self.kill_all();
+                if let Some(name) = chosen {
+                    self.apply_preset(&name);
+                }
+
+                ui.add(egui::TextEdit::singleline(&mut self.preset_name).hint_text("preset name").desired_width(140.0));
+                let can_save = !self.preset_name.trim().is_empty();
+                if ui.add_enabled(can_save && technician, egui::Button::new(tr("Save Preset")))
+                    .on_hover_text(tr("Store the current parameters under this name (replaces a preset of the same name)"))
+                    .clicked()
+                {
+                    self.save_preset();
                 }
             });
             
             ui.separator();
             
             // Adjustment parameters
-            ui.heading("Adjustment Parameters");
+            // Tuning parameters are technician-only; presets above still switch them
+            ui.add_enabled_ui(technician, |ui| {
+                ui.heading(tr("Adjustment Parameters"));
             
-            ui.horizontal(|ui| {
-                let current_enabled = self.operations.read().unwrap().get_bump_check_enable();
-                let mut bump_enabled = current_enabled;
-                if ui.checkbox(&mut bump_enabled, "Bump check enabled").changed() {
-                    self.operations.read().unwrap().set_bump_check_enable(bump_enabled);
-                    self.append_message(&format!("Bump check {}", if bump_enabled { "enabled" } else { "disabled" }));
-                    if !bump_enabled {
-                        self.repeat_pending = None;
+                ui.horizontal(|ui| {
+                    let current_enabled = self.operations.read().unwrap().get_bump_check_enable();
+                    let mut bump_enabled = current_enabled;
+                    if ui.checkbox(&mut bump_enabled, tr("Bump check enabled")).changed() {
+                        self.operations.read().unwrap().set_bump_check_enable(bump_enabled);
+                        self.append_message(&format!("Bump check {}", if bump_enabled { "enabled" } else { "disabled" }));
+                        if !bump_enabled {
+                            self.repeat_pending = None;
+                        }
                     }
-                }
-            });
+                });
             
-            // Row 1: X Start, X Finish, Adjustment Level
-            ui.horizontal(|ui| {
-                ui.label("X Start:");
-                let mut x_start = self.operations.read().unwrap().get_x_start();
-                let mut drag = egui::DragValue::new(&mut x_start);
-                drag = drag.clamp_range(-10000..=10000);
-                if ui.add(drag).changed() {
-                    self.operations.read().unwrap().set_x_start(x_start);
-                    self.append_message(&format!("X start set to {}", x_start));
-                }
+                // Row 1: X Start, X Finish, Adjustment Level
+                ui.horizontal(|ui| {
+                    ui.label(tr("X Start:"));
+                    let mut x_start = self.operations.read().unwrap().get_x_start();
+                    let mut drag = egui::DragValue::new(&mut x_start);
+                    drag = drag.clamp_range(-10000..=10000);
+                    if ui.add(drag).changed() {
+                        self.operations.read().unwrap().set_x_start(x_start);
+                        self.append_message(&format!("X start set to {}", x_start));
+                    }
                 
-                ui.label("X Finish:");
-                let mut x_finish = self.operations.read().unwrap().get_x_finish();
-                let mut drag = egui::DragValue::new(&mut x_finish);
-                drag = drag.clamp_range(-10000..=10000);
-                if ui.add(drag).changed() {
-                    self.operations.read().unwrap().set_x_finish(x_finish);
-                    self.append_message(&format!("X finish set to {}", x_finish));
-                }
+                    ui.label(tr("X Finish:"));
+                    let mut x_finish = self.operations.read().unwrap().get_x_finish();
+                    let mut drag = egui::DragValue::new(&mut x_finish);
+                    drag = drag.clamp_range(-10000..=10000);
+                    if ui.add(drag).changed() {
+                        self.operations.read().unwrap().set_x_finish(x_finish);
+                        self.append_message(&format!("X finish set to {}", x_finish));
+                    }
                 
-                ui.label("Adjustment Level:");
-                let mut adjustment_level = self.operations.read().unwrap().get_adjustment_level();
-                let mut drag = egui::DragValue::new(&mut adjustment_level);
-                drag = drag.clamp_range(1..=100);
-                if ui.add(drag).changed() {
-                    self.operations.read().unwrap().set_adjustment_level(adjustment_level);
-                    self.append_message(&format!("Adjustment level set to {}", adjustment_level));
-                }
-            });
+                    ui.label(tr("Sweep Laps:"));
+                    let mut sweep_laps = self.operations.read().unwrap().get_sweep_laps();
+                    let mut drag = egui::DragValue::new(&mut sweep_laps);
+                    drag = drag.clamp_range(0..=1000);
+                    if ui.add(drag).on_hover_text(tr("Laps per sweep; 0 = one lap, or until stopped for Ping Pong Move")).changed() {
+                        self.operations.read().unwrap().set_sweep_laps(sweep_laps);
+                        self.append_message(&format!("Sweep laps set to {}", sweep_laps));
+                    }
+                
+                    ui.label(tr("Converge Below:"));
+                    let mut converge_below = self.operations.read().unwrap().get_sweep_converge_below();
+                    let mut drag = egui::DragValue::new(&mut converge_below);
+                    drag = drag.clamp_range(0..=10000);
+                    if ui.add(drag).on_hover_text(tr("Stop a multi-lap sweep after a lap with fewer Z moves than this; 0 = off")).changed() {
+                        self.operations.read().unwrap().set_sweep_converge_below(converge_below);
+                        self.append_message(&format!("Sweep convergence set to {} Z moves per lap", converge_below));
+                    }
+                
+                    ui.label(tr("Adjustment Level:"));
+                    let mut adjustment_level = self.operations.read().unwrap().get_adjustment_level();
+                    let mut drag = egui::DragValue::new(&mut adjustment_level);
+                    drag = drag.clamp_range(1..=100);
+                    if ui.add(drag).changed() {
+                        self.operations.read().unwrap().set_adjustment_level(adjustment_level);
+                        self.append_message(&format!("Adjustment level set to {}", adjustment_level));
+                    }
+                });
             
-            // Row 2: Retry Threshold, Delta Threshold, Z Variance Threshold
-            ui.horizontal(|ui| {
-                ui.label("Retry Threshold:");
-                let mut retry_threshold = self.operations.read().unwrap().get_retry_threshold();
-                let mut drag = egui::DragValue::new(&mut retry_threshold);
-                drag = drag.clamp_range(1..=1000);
-                if ui.add(drag).changed() {
-                    self.operations.read().unwrap().set_retry_threshold(retry_threshold);
-                    self.append_message(&format!("Retry threshold set to {}", retry_threshold));
-                }
+                // Row 2: Retry Threshold, Delta Threshold, Z Variance Threshold
+                ui.horizontal(|ui| {
+                    ui.label(tr("Retry Threshold:"));
+                    let mut retry_threshold = self.operations.read().unwrap().get_retry_threshold();
+                    let mut drag = egui::DragValue::new(&mut retry_threshold);
+                    drag = drag.clamp_range(1..=1000);
+                    if ui.add(drag).changed() {
+                        self.operations.read().unwrap().set_retry_threshold(retry_threshold);
+                        self.append_message(&format!("Retry threshold set to {}", retry_threshold));
+                    }
                 
-                ui.label("Delta Threshold:");
-                let mut delta_threshold = self.operations.read().unwrap().get_delta_threshold();
-                let mut drag = egui::DragValue::new(&mut delta_threshold);
-                drag = drag.clamp_range(1..=1000);
-                if ui.add(drag).changed() {
-                    self.operations.read().unwrap().set_delta_threshold(delta_threshold);
-                    self.append_message(&format!("Delta threshold set to {}", delta_threshold));
-                }
+                    ui.label(tr("Delta Threshold:"));
+                    let mut delta_threshold = self.operations.read().unwrap().get_delta_threshold();
+                    let mut drag = egui::DragValue::new(&mut delta_threshold);
+                    drag = drag.clamp_range(1..=1000);
+                    if ui.add(drag).changed() {
+                        self.operations.read().unwrap().set_delta_threshold(delta_threshold);
+                        self.append_message(&format!("Delta threshold set to {}", delta_threshold));
+                    }
                 
-                ui.label("Z Variance Threshold:");
-                let mut z_variance_threshold = self.operations.read().unwrap().get_z_variance_threshold();
-                let mut drag = egui::DragValue::new(&mut z_variance_threshold);
-                drag = drag.clamp_range(1..=1000);
-                if ui.add(drag).changed() {
-                    self.operations.read().unwrap().set_z_variance_threshold(z_variance_threshold);
-                    self.append_message(&format!("Z variance threshold set to {}", z_variance_threshold));
+                    ui.label(tr("Z Variance Threshold:"));
+                    let mut z_variance_threshold = self.operations.read().unwrap().get_z_variance_threshold();
+                    let mut drag = egui::DragValue::new(&mut z_variance_threshold);
+                    drag = drag.clamp_range(1..=1000);
+                    if ui.add(drag).changed() {
+                        self.operations.read().unwrap().set_z_variance_threshold(z_variance_threshold);
+                        self.append_message(&format!("Z variance threshold set to {}", z_variance_threshold));
+                    }
+                });
+
+                // Step sizes, shared with stepper_gui
+                ui.horizontal(|ui| {
+                    let mut steps_changed = false;
+                    ui.label(tr("Z Up Step:"));
+                    let mut z_up_step = self.operations.read().unwrap().get_z_up_step();
+                    if ui.add(egui::DragValue::new(&mut z_up_step).clamp_range(2..=10)).changed() {
+                        self.operations.read().unwrap().set_z_up_step(z_up_step);
+                        steps_changed = true;
+                    }
+                    ui.label(tr("Z Down Step:"));
+                    let mut z_down_step = self.operations.read().unwrap().get_z_down_step();
+                    if ui.add(egui::DragValue::new(&mut z_down_step).clamp_range(-10..=-2)).changed() {
+                        self.operations.read().unwrap().set_z_down_step(z_down_step);
+                        steps_changed = true;
+                    }
+                    ui.label(tr("X Step:"));
+                    let mut x_step = self.operations.read().unwrap().get_x_step();
+                    if ui.add(egui::DragValue::new(&mut x_step).clamp_range(1..=1000)).changed() {
+                        self.operations.read().unwrap().set_x_step(x_step);
+                        steps_changed = true;
+                    }
+                    if steps_changed {
+                        self.publish_step_sizes();
+                    }
+                });
+
+                // Row 3: Adaptive Z step sizing
+                ui.horizontal(|ui| {
+                    let mut adaptive = self.operations.read().unwrap().get_adaptive_z_step();
+                    if ui.checkbox(&mut adaptive, tr("Adaptive Z step")).changed() {
+                        self.operations.read().unwrap().set_adaptive_z_step(adaptive);
+                        self.append_message(&format!("Adaptive Z step {}", if adaptive { "enabled" } else { "disabled" }));
+                    }
+
+                    ui.label(tr("Z Max Step:"));
+                    let mut z_max_step = self.operations.read().unwrap().get_z_max_step();
+                    let mut drag = egui::DragValue::new(&mut z_max_step);
+                    drag = drag.clamp_range(1..=200);
+                    if ui.add_enabled(adaptive, drag).changed() {
+                        self.operations.read().unwrap().set_z_max_step(z_max_step);
+                        self.append_message(&format!("Z max step set to {}", z_max_step));
+                    }
+
+                    ui.label(tr("Stale Limit (s):"));
+                    let mut stale_limit = self.operations.read().unwrap().get_partials_stale_limit();
+                    let mut drag = egui::DragValue::new(&mut stale_limit).speed(0.1);
+                    drag = drag.clamp_range(0.0..=60.0);
+                    if ui.add(drag).on_hover_text(tr("Audio-driven moves stop when partials are older than this (0 = off)")).changed() {
+                        self.operations.read().unwrap().set_partials_stale_limit(stale_limit);
+                        self.append_message(&format!("Partials stale limit set to {:.1}s", stale_limit));
+                    }
+                });
+
+                // Row 4: Bow wheels (BOW_DRIVE)
+                let bow_limit = self.operations.read().unwrap().bow_drive.as_ref().map(|bow| bow.max_speed);
+                if let Some(max_speed) = bow_limit {
+                    ui.horizontal(|ui| {
+                        let mut modulate = self.operations.read().unwrap().get_bow_modulate();
+                        if ui.checkbox(&mut modulate, tr("Bow speed first"))
+                            .on_hover_text(tr("z_adjust changes the wheel speed when only the amplitude is out of range, and moves Z once the wheel is at its limit"))
+                            .changed()
+                        {
+                            self.operations.read().unwrap().set_bow_modulate(modulate);
+                            self.append_message(&format!("z_adjust bow speed modulation {}", if modulate { "enabled" } else { "disabled" }));
+                        }
+                    });
+                    let speeds = self.operations.read().unwrap().get_bow_speeds();
+                    for (string_idx, current) in speeds.into_iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(trf("{0} bow speed:", &[&self.operations.read().unwrap().string_label(string_idx)]));
+                            let mut speed = current;
+                            let slider = ui.add(egui::Slider::new(&mut speed, 0..=max_speed));
+                            // Send on release (or a typed value), not every frame of a drag
+                            if (slider.drag_stopped() || (slider.changed() && !slider.dragged())) && speed != current {
+                                self.set_bow_speed(string_idx, speed);
+                            }
+                        });
+                    }
+                }
+
+                // Row 5: Dampers (DAMPERS)
+                let dampers = self.operations.read().unwrap().get_dampers();
+                if !dampers.is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Dampers:"));
+                        for (string_idx, engaged) in dampers.iter().copied().enumerate() {
+                            let label = self.operations.read().unwrap().string_label(string_idx);
+                            if ui.selectable_label(engaged, label).on_hover_text(tr("Toggle this string's damper")).clicked() {
+                                self.set_damper(Some(string_idx), !engaged);
+                            }
+                        }
+                        if ui.button(tr("Damp all")).clicked() {
+                            self.set_damper(None, true);
+                        }
+                        if ui.button(tr("Release all")).clicked() {
+                            self.set_damper(None, false);
+                        }
+                    });
                 }
-            });
             
-            ui.separator();
+                ui.separator();
             
-            // Rest timing values
-            ui.heading("Timing");
+                // Rest timing values
+                ui.heading(tr("Timing"));
             
-            // Row: Tune Rest, X Rest, Lap Rest
-            ui.horizontal(|ui| {
-                ui.label("Tune Rest:");
-                let mut tune_rest = self.operations.read().unwrap().get_tune_rest();
-                let mut drag = egui::DragValue::new(&mut tune_rest).speed(0.1);
-                drag = drag.clamp_range(0.0..=100.0);
-                if ui.add(drag).changed() {
-                    self.operations.read().unwrap().set_tune_rest(tune_rest);
-                    self.append_message(&format!("Tune rest set to {:.2}", tune_rest));
-                }
+                // Row: Tune Rest, X Rest, Lap Rest
+                ui.horizontal(|ui| {
+                    ui.label(tr("Tune Rest:"));
+                    let mut tune_rest = self.operations.read().unwrap().get_tune_rest();
+                    let mut drag = egui::DragValue::new(&mut tune_rest).speed(0.1);
+                    drag = drag.clamp_range(0.0..=100.0);
+                    if ui.add(drag).changed() {
+                        self.operations.read().unwrap().set_tune_rest(tune_rest);
+                        self.append_message(&format!("Tune rest set to {:.2}", tune_rest));
+                    }
                 
-                ui.label("X Rest:");
-                let mut x_rest = self.operations.read().unwrap().get_x_rest();
-                let mut drag = egui::DragValue::new(&mut x_rest).speed(0.1);
-                drag = drag.clamp_range(0.0..=100.0);
-                if ui.add(drag).changed() {
-                    self.operations.read().unwrap().set_x_rest(x_rest);
-                    self.append_message(&format!("X rest set to {:.2}", x_rest));
-                }
+                    ui.label(tr("X Rest:"));
+                    let mut x_rest = self.operations.read().unwrap().get_x_rest();
+                    let mut drag = egui::DragValue::new(&mut x_rest).speed(0.1);
+                    drag = drag.clamp_range(0.0..=100.0);
+                    if ui.add(drag).changed() {
+                        self.operations.read().unwrap().set_x_rest(x_rest);
+                        self.append_message(&format!("X rest set to {:.2}", x_rest));
+                    }
                 
-                ui.label("Lap Rest:");
-                let mut lap_rest = self.operations.read().unwrap().get_lap_rest();
-                let mut drag = egui::DragValue::new(&mut lap_rest).speed(0.1);
-                drag = drag.clamp_range(0.0..=100.0);
-                if ui.add(drag).changed() {
-                    self.operations.read().unwrap().set_lap_rest(lap_rest);
-                    self.append_message(&format!("Lap rest set to {:.2}", lap_rest));
-                }
-            });
+                    ui.label(tr("Lap Rest:"));
+                    let mut lap_rest = self.operations.read().unwrap().get_lap_rest();
+                    let mut drag = egui::DragValue::new(&mut lap_rest).speed(0.1);
+                    drag = drag.clamp_range(0.0..=100.0);
+                    if ui.add(drag).changed() {
+                        self.operations.read().unwrap().set_lap_rest(lap_rest);
+                        self.append_message(&format!("Lap rest set to {:.2}", lap_rest));
+                    }
+                });
             
-            ui.horizontal(|ui| {
-                ui.label("Z Rest:");
-                let mut z_rest = self.operations.read().unwrap().get_z_rest();
-                let mut drag = egui::DragValue::new(&mut z_rest).speed(0.1);
-                drag = drag.clamp_range(0.0..=100.0);
-                if ui.add(drag).changed() {
-                    self.operations.read().unwrap().set_z_rest(z_rest);
-                    self.append_message(&format!("Z rest set to {:.2}", z_rest));
+                // Rest of every other linear axis (AXES), e.g. Y Rest
+                let other_axes: Vec<String> = self.operations.read().unwrap().axes().iter().map(|axis| axis.name.clone()).filter(|name| name != "X").collect();
+                if !other_axes.is_empty() {
+                    ui.horizontal(|ui| {
+                        for axis in other_axes {
+                            ui.label(trf("{0} Rest:", &[&axis]));
+                            let mut rest = self.operations.read().unwrap().get_axis_rest(&axis);
+                            let mut drag = egui::DragValue::new(&mut rest).speed(0.1);
+                            drag = drag.clamp_range(0.0..=100.0);
+                            if ui.add(drag).changed() {
+                                self.operations.read().unwrap().set_axis_rest(&axis, rest);
+                                self.append_message(&format!("{} rest set to {:.2}", axis, rest));
+                            }
+                        }
+                    });
                 }
-            });
             
+                ui.horizontal(|ui| {
+                    ui.label(tr("Z Rest:"));
+                    let mut z_rest = self.operations.read().unwrap().get_z_rest();
+                    let mut drag = egui::DragValue::new(&mut z_rest).speed(0.1);
+                    drag = drag.clamp_range(0.0..=100.0);
+                    if ui.add(drag).changed() {
+                        self.operations.read().unwrap().set_z_rest(z_rest);
+                        self.append_message(&format!("Z rest set to {:.2}", z_rest));
+                    }
+                });
+
+                // Row: bump_check strategy (applies to subsequent runs)
+                ui.horizontal(|ui| {
+                    let mut strategy = self.operations.read().unwrap().get_bump_strategy();
+                    let mut changed = false;
+
+                    let mut follow_z_up = strategy.retract_step.is_none();
+                    if ui.checkbox(&mut follow_z_up, tr("Retract = Z up step")).changed() {
+                        strategy.retract_step = if follow_z_up {
+                            None
+                        } else {
+                            Some(self.operations.read().unwrap().get_z_up_step().max(1))
+                        };
+                        changed = true;
+                    }
+                    if let Some(mut retract_step) = strategy.retract_step {
+                        ui.label(tr("Bump Retract:"));
+                        if ui.add(egui::DragValue::new(&mut retract_step).clamp_range(1..=100)).changed() {
+                            strategy.retract_step = Some(retract_step);
+                            changed = true;
+                        }
+                    }
+
+                    ui.label(tr("Clear Readings:"));
+                    if ui.add(egui::DragValue::new(&mut strategy.clear_readings).clamp_range(1..=20)).changed() {
+                        changed = true;
+                    }
+
+                    ui.label(tr("Settle Rest:"));
+                    if ui.add(egui::DragValue::new(&mut strategy.settle_rest).speed(0.1).clamp_range(0.0..=10.0)).changed() {
+                        changed = true;
+                    }
+
+                    ui.label(tr("Final Margin:"));
+                    if ui.add(egui::DragValue::new(&mut strategy.final_margin).clamp_range(0..=100)).changed() {
+                        changed = true;
+                    }
+
+                    if changed {
+                        self.operations.read().unwrap().set_bump_strategy(strategy);
+                        self.append_message(&format!(
+                            "Bump strategy: retract {}, {} clear reading(s), settle {:.2}s, margin {}",
+                            strategy.retract_step.map_or("z_up_step".to_string(), |s| s.to_string()),
+                            strategy.clear_readings,
+                            strategy.settle_rest,
+                            strategy.final_margin
+                        ));
+                    }
+                });
+            });
+
             ui.separator();
             
             // Audio analysis display
-            ui.heading("Audio Analysis");
+            ui.heading(tr("Audio Analysis"));
+            if let Some(rate) = self.operations.read().unwrap().audio_sample_rate() {
+                ui.label(trf("audmon sample rate: {0} Hz", &[&rate]));
+            }
             
             let voice_count = self.operations.read().unwrap().get_voice_count();
             let amp_sum = self.operations.read().unwrap().get_amp_sum();
             
             // Show message if no audio channels available yet
             if voice_count.is_empty() && amp_sum.is_empty() {
-                ui.label("Waiting for audio data... (audio_monitor may not be running)");
+                ui.label(tr("Waiting for audio data... (audio_monitor may not be running)"));
             } else {
+                // Mute/solo: which channels' analysis z_adjust listens to (bump_check still covers all)
+                for (label, is_solo) in [("Mute:", false), ("Solo:", true)] {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(label);
+                        for ch_idx in 0..voice_count.len().max(amp_sum.len()) {
+                            let ops = self.operations.read().unwrap();
+                            let mut on = if is_solo { ops.is_channel_soloed(ch_idx) } else { ops.is_channel_muted(ch_idx) };
+                            if ui.checkbox(&mut on, format!("Ch {}", ch_idx)).changed() {
+                                if is_solo {
+                                    ops.set_channel_soloed(ch_idx, on);
+                                } else {
+                                    ops.set_channel_muted(ch_idx, on);
+                                }
+                                drop(ops);
+                                let action = match (is_solo, on) {
+                                    (false, true) => "muted",
+                                    (false, false) => "unmuted",
+                                    (true, true) => "soloed",
+                                    (true, false) => "unsoloed",
+                                };
+                                self.append_message(&format!("Channel {} {}", ch_idx, action));
+                            }
+                        }
+                    });
+                }
+                
                 // Voice count display with horizontal meters and thresholds
             let voice_cap = self.voice_count_cap_cache.max(1);
             ui.horizontal(|ui| {
-                ui.label(format!("Voice Count (per channel, max {}):", voice_cap));
+                ui.label(trf("Voice Count (per channel, max {0}):", &[&voice_cap]));
                 ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
-                    ui.label("Thresholds");
+                    ui.label(tr("Thresholds"));
                 });
             });
             
             // Global Voice Count thresholds (sets all channels at once)
             ui.horizontal(|ui| {
-                ui.label("Global Voice Count:");
+                ui.label(tr("Global Voice Count:"));
                 // Get actual channel count from voice_count array (not string_num)
                 let actual_channel_count = {
                     let ops = self.operations.read().unwrap();
@@ -1217,7 +4219,7 @@ impl OperationsGUI {
                 let mut global_min = current_min;
                 let mut global_max = current_max;
                 
-                ui.label("min");
+                ui.label(tr("min"));
                 if ui.add(egui::DragValue::new(&mut global_min).clamp_range(0..=voice_cap)).changed() {
                     // Update all channels (resize to actual channel count)
                     self.voice_count_min.resize(actual_channel_count, global_min);
@@ -1236,7 +4238,7 @@ impl OperationsGUI {
                     self.append_message(&format!("Global voice count min set to {} for all channels", global_min));
                 }
                 
-                ui.label("max");
+                ui.label(tr("max"));
                 // Clamp max to be at least min, but don't change min
                 let max_clamp_min = global_min.max(0);
                 if ui.add(egui::DragValue::new(&mut global_max).clamp_range(max_clamp_min..=voice_cap)).changed() {
@@ -1250,6 +4252,25 @@ impl OperationsGUI {
                 }
             });
             
+            // Voice floor: partials at or below it don't count as voices
+            let voice_floor = self.operations.read().unwrap().get_voice_floor();
+            ui.horizontal(|ui| {
+                ui.label(tr("Voice Floor:"));
+                let mut relative = voice_floor.relative;
+                if ui.checkbox(&mut relative, tr("Relative to channel max")).changed() {
+                    self.operations.read().unwrap().set_voice_floor_relative(relative);
+                    self.append_message(&format!("Voice floor is now {}", if relative { "relative to channel max" } else { "an absolute amplitude" }));
+                }
+                
+                ui.label(tr("all"));
+                let mut global_floor = voice_floor.default;
+                let range = if relative { 0.0..=1.0 } else { 0.0..=f32::MAX };
+                if ui.add(egui::DragValue::new(&mut global_floor).speed(0.001).clamp_range(range)).changed() {
+                    self.operations.read().unwrap().set_voice_floor_all(global_floor);
+                    self.append_message(&format!("Voice floor set to {:.3} for all channels", global_floor));
+                }
+            });
+            
             let mut thresholds_changed = false;
             for (ch_idx, count) in voice_count.iter().enumerate() {
                 ui.horizontal(|ui| {
@@ -1262,8 +4283,11 @@ impl OperationsGUI {
                         self.voice_count_min.resize(ch_idx + 1, min_default);
                     }
                     
-                    // Left column: Channel label and meter
-                    ui.label(format!("Ch {}:", ch_idx));
+                    // Left column: Channel label (and audmon's JACK port for it) and meter
+                    ui.label(format!("{}:", self.channel_label(ch_idx)));
+                    if let Some(source) = self.operations.read().unwrap().audio_channel_source(ch_idx) {
+                        ui.label(egui::RichText::new(source).weak());
+                    }
                     let count_val = *count as i32;
                     let min_threshold = self.voice_count_min[ch_idx];
                     let max_threshold = self.voice_count_max[ch_idx];
@@ -1294,11 +4318,18 @@ impl OperationsGUI {
                         let mut max_val = self.voice_count_max[ch_idx];
                         let mut min_val = self.voice_count_min[ch_idx];
                         
-                        ui.label("min");
+                        ui.label(tr("min"));
                         ui.add(egui::DragValue::new(&mut min_val).clamp_range(0..=voice_cap));
-                        ui.label("max");
+                        ui.label(tr("max"));
                         ui.add(egui::DragValue::new(&mut max_val).clamp_range(0..=voice_cap));
                         
+                        ui.label(tr("floor"));
+                        let mut floor_val = voice_floor.floor(ch_idx);
+                        let range = if voice_floor.relative { 0.0..=1.0 } else { 0.0..=f32::MAX };
+                        if ui.add(egui::DragValue::new(&mut floor_val).speed(0.001).clamp_range(range)).changed() {
+                            self.operations.read().unwrap().set_voice_floor(ch_idx, floor_val);
+                        }
+                        
                         if max_val != self.voice_count_max[ch_idx] {
                             self.voice_count_max[ch_idx] = max_val;
                             thresholds_changed = true;
@@ -1322,15 +4353,15 @@ impl OperationsGUI {
             
             // Amp sum display with horizontal meters and thresholds
             ui.horizontal(|ui| {
-                ui.label("Amplitude Sum (per channel):");
+                ui.label(tr("Amplitude Sum (per channel):"));
                 ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
-                    ui.label("Thresholds");
+                    ui.label(tr("Thresholds"));
                 });
             });
             
             // Global Amp Sum thresholds (sets all channels at once)
             ui.horizontal(|ui| {
-                ui.label("Global Amp Sum:");
+                ui.label(tr("Global Amp Sum:"));
                 // Get actual channel count from amp_sum array (not string_num)
                 let actual_channel_count = {
                     let ops = self.operations.read().unwrap();
@@ -1352,7 +4383,7 @@ impl OperationsGUI {
                 let mut global_min = current_min;
                 let mut global_max = current_max;
                 
-                ui.label("min");
+                ui.label(tr("min"));
                 if ui.add(egui::DragValue::new(&mut global_min).clamp_range(0..=i32::MAX)).changed() {
                     // Update all channels (resize to actual channel count)
                     self.amp_sum_min.resize(actual_channel_count, global_min);
@@ -1370,7 +4401,7 @@ impl OperationsGUI {
                     self.append_message(&format!("Global amp sum min set to {} for all channels", global_min));
                 }
                 
-                ui.label("max");
+                ui.label(tr("max"));
                 // Clamp max to be at least min, but don't change min
                 let max_clamp_min = global_min.max(0);
                 if ui.add(egui::DragValue::new(&mut global_max).clamp_range(max_clamp_min..=i32::MAX)).changed() {
@@ -1383,6 +4414,31 @@ impl OperationsGUI {
                 }
             });
             
+            // Height target: amp_sum thresholds from the height_calibrate curves
+            if self.height_target_pending && !amp_sum.is_empty() {
+                self.height_target_pending = false;
+                self.apply_height_target(amp_sum.len());
+            }
+            ui.horizontal(|ui| {
+                ui.label(tr("Height Target:"));
+                let (mut low, mut high) = self.height_target;
+                ui.add(egui::DragValue::new(&mut low).clamp_range(0..=i32::MAX));
+                ui.label(tr("to"));
+                ui.add(egui::DragValue::new(&mut high).clamp_range(low..=i32::MAX));
+                ui.label(tr("steps above contact"));
+                self.height_target = (low, high.max(low));
+                let height_map = self.operations.read().unwrap().get_height_map();
+                let apply = ui.add_enabled(height_map.is_some(), egui::Button::new(tr("Apply")))
+                    .on_hover_text(tr("Set each channel's amp sum min/max from its height curve"))
+                    .on_disabled_hover_text("No height map yet - run Height Calibrate");
+                if apply.clicked() {
+                    self.apply_height_target(amp_sum.len());
+                }
+                if let Some(map) = height_map {
+                    ui.label(format!("(height map {})", map.recorded_at.get(..16).unwrap_or(&map.recorded_at)));
+                }
+            });
+            
             for (ch_idx, sum) in amp_sum.iter().enumerate() {
                 ui.horizontal(|ui| {
                     // Ensure we have enough elements in the vectors
@@ -1393,8 +4449,11 @@ impl OperationsGUI {
                         self.amp_sum_min.resize(ch_idx + 1, 20);
                     }
                     
-                    // Left column: Channel label and meter
-                    ui.label(format!("Ch {}:", ch_idx));
+                    // Left column: Channel label (and audmon's JACK port for it) and meter
+                    ui.label(format!("{}:", self.channel_label(ch_idx)));
+                    if let Some(source) = self.operations.read().unwrap().audio_channel_source(ch_idx) {
+                        ui.label(egui::RichText::new(source).weak());
+                    }
                     let sum_val = *sum;
                     let min_threshold = self.amp_sum_min[ch_idx] as f32;
                     let max_threshold = self.amp_sum_max[ch_idx] as f32;
@@ -1424,9 +4483,9 @@ impl OperationsGUI {
                         let mut max_val = self.amp_sum_max[ch_idx];
                         let mut min_val = self.amp_sum_min[ch_idx];
                         
-                        ui.label("min");
+                        ui.label(tr("min"));
                         ui.add(egui::DragValue::new(&mut min_val).clamp_range(0..=i32::MAX));
-                        ui.label("max");
+                        ui.label(tr("max"));
                         ui.add(egui::DragValue::new(&mut max_val).clamp_range(0..=i32::MAX));
                         
                         if max_val != self.amp_sum_max[ch_idx] {
@@ -1438,152 +4497,282 @@ impl OperationsGUI {
                     });
                 });
             }
+            
+            // Why the last sweep pass at an X position failed, string by string
+            let evaluation = self.operations.read().unwrap().get_last_evaluation();
+            gui_state::collapsing(ui, "operations.last_pass_evaluation", tr("Last Pass Evaluation"), |ui| {
+                let Some(evaluation) = evaluation else {
+                    ui.label(tr("No sweep pass evaluated yet"));
+                    return;
+                };
+                ui.label(format!(
+                    "X={}, attempt {}, {:.0}s ago: {}{}",
+                    evaluation.x,
+                    evaluation.attempt,
+                    evaluation.at.elapsed().as_secs_f32(),
+                    if evaluation.passed() { "passed" } else { "failed" },
+                    if evaluation.bump_check_passed { "" } else { " (bump_check failed)" }
+                ));
+                egui::Grid::new("last_pass_evaluation").striped(true).show(ui, |ui| {
+                    for header in ["String", "Amp", "Amp band", "Voices", "Voice band", "Failed"] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+                    for channel in &evaluation.channels {
+                        ui.label(self.channel_label(channel.channel));
+                        ui.label(format!("{:.1}", channel.amp_sum));
+                        ui.label(format!("{:.0}-{:.0}", channel.amp_band.0, channel.amp_band.1));
+                        ui.label(channel.voices.to_string());
+                        ui.label(format!("{}-{}", channel.voice_band.0, channel.voice_band.1));
+                        if let Some(reason) = channel.excluded {
+                            ui.weak(reason);
+                        } else if channel.failures.is_empty() {
+                            ui.colored_label(egui::Color32::from_rgb(0, 200, 0), "ok");
+                        } else {
+                            let failed: Vec<&str> = channel.failures.iter().map(|f| f.name()).collect();
+                            ui.colored_label(egui::Color32::from_rgb(255, 0, 0), failed.join(", "));
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
             } // End of else block for when audio data is available
             
             ui.separator();
             
-            // Stepper enable/disable checkboxes
-            ui.heading("Stepper Enable/Disable");
-            ui.label("(Controls which steppers participate in operations/bump_check)");
-
-            let (z_indices, bump_status, num_pairs, z_first, x_step_index, tuner_indices) = {
-                let ops_guard = self.operations.read().unwrap();
-                (
-                    ops_guard.get_z_stepper_indices(),
-                    ops_guard.get_bump_status(),
-                    ops_guard.string_num,
-                    ops_guard.z_first_index,
-                    ops_guard.x_step_index(),
-                    ops_guard.tuner_indices(),
-                )
-            };
-
-            if let Some(x_idx) = x_step_index {
-                ui.horizontal(|ui| {
-                    let mut enabled = self.operations.read().unwrap().get_stepper_enabled(x_idx);
-                    if ui.checkbox(&mut enabled, format!("Stepper {} (X)", x_idx)).changed() {
-                        self.operations.read().unwrap().set_stepper_enabled(x_idx, enabled);
-                        self.append_message(&format!("Stepper {} {}", x_idx, if enabled { "enabled" } else { "disabled" }));
+            // Performance mode settings (re-read every cycle, so they apply while running)
+            ui.heading(tr("Performance Mode"));
+            ui.horizontal(|ui| {
+                ui.label(tr("Cycle Rest:"));
+                let mut performance_rest = self.operations.read().unwrap().get_performance_rest();
+                let mut drag = egui::DragValue::new(&mut performance_rest).speed(0.1);
+                drag = drag.clamp_range(0.0..=60.0);
+                if ui.add(drag).changed() {
+                    self.operations.read().unwrap().set_performance_rest(performance_rest);
+                    self.append_message(&format!("Performance cycle rest set to {:.2}", performance_rest));
+                }
+                
+                ui.label(tr("Bump Check Every:"));
+                let mut bump_interval = self.operations.read().unwrap().get_performance_bump_interval();
+                let mut drag = egui::DragValue::new(&mut bump_interval);
+                drag = drag.clamp_range(1..=100);
+                if ui.add(drag).changed() {
+                    self.operations.read().unwrap().set_performance_bump_interval(bump_interval);
+                    self.append_message(&format!("Performance bump_check interval set to {} cycle(s)", bump_interval));
+                }
+                
+                let has_controller = self.operations.read().unwrap().z_controller_settings.is_some();
+                let mut use_controller = self.operations.read().unwrap().get_performance_use_controller();
+                if ui.add_enabled(has_controller, egui::Checkbox::new(&mut use_controller, "Use PID controller")).changed() {
+                    self.operations.read().unwrap().set_performance_use_controller(use_controller);
+                    self.append_message(&format!("Performance mode will use {}", if use_controller { "PID controller" } else { "z_adjust" }));
+                }
+            });
+            
+            let channel_count = self.operations.read().unwrap().get_amp_sum().len();
+            if channel_count > 0 {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(tr("Pause:"));
+                    for ch_idx in 0..channel_count {
+                        let mut paused = self.operations.read().unwrap().is_channel_paused(ch_idx);
+                        if ui.checkbox(&mut paused, format!("Ch {}", ch_idx)).changed() {
+                            self.operations.read().unwrap().set_channel_paused(ch_idx, paused);
+                            self.append_message(&format!("Channel {} {}", ch_idx, if paused { "paused" } else { "resumed" }));
+                        }
                     }
                 });
             }
+            
+            ui.separator();
+            
+            // Stepper enable/disable checkboxes
+            ui.add_enabled_ui(technician, |ui| {
+                ui.heading(tr("Stepper Enable/Disable"));
+                ui.label(tr("(Controls which steppers participate in operations/bump_check)"));
+
+                let (topology, bump_status) = {
+                    let ops_guard = self.operations.read().unwrap();
+                    (ops_guard.topology.clone(), ops_guard.get_bump_status())
+                };
+                let tuner_indices = topology.mainboard_tuner_indices();
+
+                if let Some(x_idx) = topology.x_index() {
+                    ui.horizontal(|ui| {
+                        let mut enabled = self.operations.read().unwrap().get_stepper_enabled(x_idx);
+                        if ui.checkbox(&mut enabled, format!("Stepper {} (X)", x_idx)).changed() {
+                            self.toggle_stepper_enabled(x_idx, enabled);
+                        }
+                    });
+                }
 
-            if !tuner_indices.is_empty() {
-                ui.label("Tuners:");
-                for (t_idx, step_idx) in tuner_indices.iter().enumerate() {
-                    let mut enabled = self.operations.read().unwrap().get_stepper_enabled(*step_idx);
-                    if ui.checkbox(&mut enabled, format!("Stepper {} (T{})", step_idx, t_idx)).changed() {
-                        self.operations.read().unwrap().set_stepper_enabled(*step_idx, enabled);
-                        self.append_message(&format!("Stepper {} {}", step_idx, if enabled { "enabled" } else { "disabled" }));
+                if !tuner_indices.is_empty() {
+                    ui.label(tr("Tuners:"));
+                    for (t_idx, step_idx) in tuner_indices.iter().enumerate() {
+                        let mut enabled = self.operations.read().unwrap().get_stepper_enabled(*step_idx);
+                        let name = self.operations.read().unwrap().string_label(t_idx);
+                        if ui.checkbox(&mut enabled, format!("Stepper {} (T{}) {}", step_idx, t_idx, name)).changed() {
+                            self.toggle_stepper_enabled(*step_idx, enabled);
+                        }
                     }
                 }
-            }
 
-            let bump_map: std::collections::HashMap<usize, bool> = bump_status.iter().cloned().collect();
+                let bump_map: std::collections::HashMap<usize, bool> = bump_status.iter().cloned().collect();
             
-            // Arrange steppers in pairs matching stepper_gui layout:
-            // Left column: "out" stepper (odd index, Stepper2)
-            // Right column: "in" stepper (even index, Stepper1)
+                // Arrange steppers in pairs matching stepper_gui layout:
+                // Left column: "out" stepper (odd index, Stepper2)
+                // Right column: "in" stepper (even index, Stepper1)
             
-            for row in 0..num_pairs {
-                let left_idx = z_first + (row * 2) + 1;  // "out" stepper (odd)
-                let right_idx = z_first + (row * 2);     // "in" stepper (even)
-                
-                // Check if indices are valid
-                if !z_indices.contains(&left_idx) || !z_indices.contains(&right_idx) {
-                    continue;
-                }
+                for string in topology.strings() {
+                    let row = string.string;
+                    let Some((right_idx, left_idx)) = string.z_pair else { continue };  // ("in", "out")
                 
-                ui.horizontal(|ui| {
-                    // Left column: "out" stepper (Stepper2)
-                    ui.vertical(|ui| {
-                        let mut enabled = self.operations.read().unwrap().get_stepper_enabled(left_idx);
-                        let is_bumping = bump_map.get(&left_idx).copied().unwrap_or(false);
+                    ui.horizontal(|ui| {
+                        // Left column: "out" stepper (Stepper2)
+                        ui.vertical(|ui| {
+                            let mut enabled = self.operations.read().unwrap().get_stepper_enabled(left_idx);
+                            let is_bumping = bump_map.get(&left_idx).copied().unwrap_or(false);
                         
-                        let label = format!("Stepper {} (Z{})", 
-                            left_idx, 
-                            left_idx - z_first,
-                        );
+                            let label = format!("Stepper {} (Z{}) {}", 
+                                left_idx, 
+                                topology.sensor_for(left_idx).unwrap_or(0),
+                                self.operations.read().unwrap().string_label(row),
+                            );
                         
-                        ui.horizontal(|ui| {
-                            if ui.checkbox(&mut enabled, &label).changed() {
-                                self.operations.read().unwrap().set_stepper_enabled(left_idx, enabled);
-                                self.append_message(&format!("Stepper {} {}", left_idx, if enabled { "enabled" } else { "disabled" }));
-                            }
+                            ui.horizontal(|ui| {
+                                if ui.checkbox(&mut enabled, &label).changed() {
+                                    self.toggle_stepper_enabled(left_idx, enabled);
+                                }
                             
-                            let dot_color = if is_bumping {
-                                egui::Color32::from_rgb(220, 0, 0)
-                            } else {
-                                egui::Color32::from_gray(120)
-                            };
-                            let (rect, _) = ui.allocate_exact_size(egui::Vec2::new(14.0, 14.0), egui::Sense::hover());
-                            ui.painter().circle_filled(rect.center(), 5.0, dot_color);
+                                let dot_color = if is_bumping {
+                                    egui::Color32::from_rgb(220, 0, 0)
+                                } else {
+                                    egui::Color32::from_gray(120)
+                                };
+                                let (rect, _) = ui.allocate_exact_size(egui::Vec2::new(14.0, 14.0), egui::Sense::hover());
+                                ui.painter().circle_filled(rect.center(), 5.0, dot_color);
+                            });
                         });
-                    });
                     
-                    // Right column: "in" stepper (Stepper1)
-                    ui.vertical(|ui| {
-                        let mut enabled = self.operations.read().unwrap().get_stepper_enabled(right_idx);
-                        let is_bumping = bump_map.get(&right_idx).copied().unwrap_or(false);
+                        // Right column: "in" stepper (Stepper1)
+                        ui.vertical(|ui| {
+                            let mut enabled = self.operations.read().unwrap().get_stepper_enabled(right_idx);
+                            let is_bumping = bump_map.get(&right_idx).copied().unwrap_or(false);
                         
-                        let label = format!("Stepper {} (Z{})", 
-                            right_idx, 
-                            right_idx - z_first,
-                        );
+                            let label = format!("Stepper {} (Z{}) {}", 
+                                right_idx, 
+                                topology.sensor_for(right_idx).unwrap_or(0),
+                                self.operations.read().unwrap().string_label(row),
+                            );
                         
-                        ui.horizontal(|ui| {
-                            if ui.checkbox(&mut enabled, &label).changed() {
-                                self.operations.read().unwrap().set_stepper_enabled(right_idx, enabled);
-                                self.append_message(&format!("Stepper {} {}", right_idx, if enabled { "enabled" } else { "disabled" }));
-                            }
+                            ui.horizontal(|ui| {
+                                if ui.checkbox(&mut enabled, &label).changed() {
+                                    self.toggle_stepper_enabled(right_idx, enabled);
+                                }
                             
-                            let dot_color = if is_bumping {
-                                egui::Color32::from_rgb(220, 0, 0)
-                            } else {
-                                egui::Color32::from_gray(120)
-                            };
-                            let (rect, _) = ui.allocate_exact_size(egui::Vec2::new(14.0, 14.0), egui::Sense::hover());
-                            ui.painter().circle_filled(rect.center(), 5.0, dot_color);
+                                let dot_color = if is_bumping {
+                                    egui::Color32::from_rgb(220, 0, 0)
+                                } else {
+                                    egui::Color32::from_gray(120)
+                                };
+                                let (rect, _) = ui.allocate_exact_size(egui::Vec2::new(14.0, 14.0), egui::Sense::hover());
+                                ui.painter().circle_filled(rect.center(), 5.0, dot_color);
+                            });
+                        });
+
+                        ui.vertical(|ui| {
+                            ui.horizontal(|ui| self.show_string_z_steps(ui, row));
                         });
                     });
-                });
-            }
+                }
+
+                // Suspect touch sensors: quarantine treats a sensor as absent instead of disabling its stepper
+                let (faults, quarantined) = {
+                    let ops_guard = self.operations.read().unwrap();
+                    (ops_guard.sensor_faults(), ops_guard.quarantined_sensors())
+                };
+                let mut suspects: Vec<usize> = faults.iter().map(|(s, _)| *s).chain(quarantined.iter().copied()).collect();
+                suspects.sort_unstable();
+                suspects.dedup();
+                for sensor in suspects {
+                    let label = self.operations.read().unwrap().sensor_label(sensor);
+                    ui.horizontal(|ui| {
+                        let mut is_quarantined = quarantined.contains(&sensor);
+                        if ui.checkbox(&mut is_quarantined, format!("Quarantine sensor of {}", label))
+                            .on_hover_text(tr("Treat the sensor as absent: no bump protection, skipped by calibration and homing"))
+                            .changed()
+                        {
+                            self.operations.read().unwrap().set_sensor_quarantined(sensor, is_quarantined);
+                            self.append_message(&format!(
+                                "Touch sensor of {} {}",
+                                label,
+                                if is_quarantined { "quarantined" } else { "back in service" }
+                            ));
+                        }
+                        if let Some((_, fault)) = faults.iter().find(|(s, _)| *s == sensor) {
+                            ui.colored_label(egui::Color32::from_rgb(230, 170, 0), fault.describe());
+                        }
+                    });
+                }
+            });
             
             ui.separator();
             
             // Operations dropdown menu
-            ui.heading("Operations");
+            ui.heading(tr("Operations"));
             // Row: Select Operation, Repeat, Execute, BREAK
             ui.horizontal(|ui| {
-                ui.label("Select Operation:");
+                ui.label(tr("Select Operation:"));
+                let (descriptors, missing): (Vec<_>, Vec<_>) = {
+                    let ops = self.operations.read().unwrap();
+                    ops.operation_descriptors().into_iter()
+                        .filter(|op| technician || op.performer)
+                        .map(|op| { let missing = ops.missing_hardware(&op); (op, missing) })
+                        .unzip()
+                };
                 egui::ComboBox::from_id_source("operation_select")
                     .selected_text(&self.selected_operation)
                     .show_ui(ui, |ui| {
                         ui.selectable_value(&mut self.selected_operation, "None".to_string(), "None");
-                        ui.selectable_value(&mut self.selected_operation, "z_calibrate".to_string(), "Z Calibrate");
-                        ui.selectable_value(&mut self.selected_operation, "z_adjust".to_string(), "Z Adjust");
-                        ui.selectable_value(&mut self.selected_operation, "bump_check".to_string(), "Bump Check");
-                        ui.selectable_value(&mut self.selected_operation, "right_left_move".to_string(), "Right Left Move");
-                        ui.selectable_value(&mut self.selected_operation, "left_right_move".to_string(), "Left Right Move");
-                        ui.selectable_value(&mut self.selected_operation, "x_home".to_string(), "X Home");
-                        ui.selectable_value(&mut self.selected_operation, "x_away".to_string(), "X Away");
-                        ui.selectable_value(&mut self.selected_operation, "x_calibrate".to_string(), "X Calibrate");
+                        for (op, missing) in descriptors.iter().zip(&missing) {
+                            let needs: Vec<&str> = missing.iter().map(|requirement| requirement.describe()).collect();
+                            ui.add_enabled_ui(missing.is_empty(), |ui| {
+                                ui.selectable_value(&mut self.selected_operation, op.name.to_string(), tr(&op.label))
+                                    .on_hover_text(tr(op.description))
+                                    .on_disabled_hover_text(trf("Not available on this host: needs {0}", &[&needs.join(", ")]));
+                            });
+                        }
                     });
                 
                 let mut repeat_flag = self.repeat_enabled;
-                if ui.checkbox(&mut repeat_flag, "Repeat").changed() {
+                if ui.checkbox(&mut repeat_flag, tr("Repeat")).changed() {
                     self.repeat_enabled = repeat_flag;
                     if !repeat_flag {
                         self.repeat_pending = None;
                     }
                 }
+
+                let mut limited = self.time_limit.is_some();
+                if ui.checkbox(&mut limited, tr("Time limit"))
+                    .on_hover_text(tr("Stop the run after this many seconds (retracting Z) instead of its configured MAX_DURATION"))
+                    .changed()
+                {
+                    let configured = self.operations.read().unwrap().time_budget(&self.selected_operation);
+                    self.time_limit = limited.then(|| configured.map_or(600.0, |d| d.as_secs_f32()));
+                }
+                if let Some(limit) = self.time_limit.as_mut() {
+                    ui.add(egui::DragValue::new(limit).clamp_range(1.0..=86400.0).suffix(" s"));
+                }
+                let rests_label = if self.rest_overrides.is_empty() { tr("Run rests") } else { tr("Run rests (set)") };
+                ui.menu_button(rests_label, |ui| self.render_run_rests(ui))
+                    .response
+                    .on_hover_text(tr("Rests for runs started here, in place of the operation's OPERATIONS rests and the globals"));
                 
                 // Execute button with green background - use Frame with fill
                 let execute_response = egui::Frame::default()
                     .fill(egui::Color32::from_rgb(0, 150, 0))
                     .inner_margin(egui::Margin::same(6.0))
                     .show(ui, |ui| {
-                        ui.add(egui::Button::new("Execute"))
+                        ui.add(egui::Button::new(tr("Execute")))
                     });
                 if execute_response.inner.clicked() {
                     self.repeat_pending = None;
@@ -1596,23 +4785,146 @@ impl OperationsGUI {
                     .fill(egui::Color32::from_rgb(255, 165, 0))
                     .inner_margin(egui::Margin::same(6.0))
                     .show(ui, |ui| {
-                        ui.add_enabled(operation_running, egui::Button::new(egui::RichText::new("BREAK").strong()))
+                        ui.add_enabled(operation_running, egui::Button::new(egui::RichText::new(tr("BREAK")).strong()))
                     });
                 if break_response.inner.clicked() {
-                    self.exit_flag.store(true, std::sync::atomic::Ordering::Relaxed);
-                    self.append_message("Break requested - operation will stop at next check point");
+                    self.request_break();
+                }
+                
+                // PARK button: one click to make the machine safe for transport or maintenance
+                // While an operation runs it is stopped and park goes next
+                let park_response = ui.button(tr("PARK"))
+                    .on_hover_text(tr("Raise Z to the PARK position, move X to park and check no string is touching"));
+                if park_response.clicked() {
+                    self.park_now();
                 }
             });
             
+            // Motion requests waiting for the running operation, next first
+            let queued = self.operations.read().unwrap().queued_operations();
+            if !queued.is_empty() {
+                let names: Vec<String> = queued.iter().map(|request| format!("{} ({})", request.operation, request.priority.name())).collect();
+                ui.horizontal(|ui| {
+                    ui.label(trf("Queued: {0}", &[&names.join(", ")]));
+                    if ui.small_button(tr("Clear queue")).on_hover_text(tr("A park requested with PARK stays queued")).clicked() {
+                        self.operations.read().unwrap().drop_queued_operations(Priority::Estop);
+                        self.append_message("Queue cleared");
+                    }
+                });
+            }
+            
+            // Parameters of the selected operation, from its descriptor
+            let selected = self.operations.read().unwrap().describe_operation(&self.selected_operation);
+            if let Some(op) = selected.filter(|op| technician && !op.params.is_empty()) {
+                self.render_operation_params(ui, &op);
+            }
+            if self.scores.is_some() {
+                gui_state::collapsing(ui, "operations.score", tr("Score"), |ui| self.render_scores(ui));
+            }
+            gui_state::collapsing(ui, "operations.scenes", tr("Scenes"), |ui| self.render_scenes(ui));
+            if self.operations.read().unwrap().audio_trigger_settings().is_some() {
+                gui_state::collapsing(ui, "operations.audio_trigger", tr("Audio trigger"), |ui| self.render_audio_trigger(ui));
+            }
+            if technician {
+                gui_state::collapsing(ui, "operations.processes", tr("Processes"), |ui| self.render_processes(ui));
+            }
+            let tuner_safety = self.operations.read().unwrap().tuner_safety_report();
+            if !tuner_safety.is_empty() {
+                // Pitch, MAX_PITCH and tension trend from the pitch-vs-tuner curves
+                gui_state::collapsing(ui, "operations.tuner_safety", tr("Tuner safety"), |ui| {
+                    for line in tuner_safety {
+                        ui.label(line);
+                    }
+                });
+            }
+            
+            // Every setting in effect and where it came from (default, yaml, runtime, learned)
+            gui_state::collapsing(ui, "operations.effective_settings", tr("Effective settings"), |ui| self.render_effective_settings(ui));
+            
             ui.separator();
             
+            // GPIO test panel: raw level vs debounced state of every input line
+            gui_state::collapsing(ui, "operations.gpio_lines", tr("GPIO Lines"), |ui| {
+                let lines = self.operations.read().ok()
+                    .and_then(|ops| ops.gpio.as_ref().filter(|g| g.exist).map(|g| g.line_states()));
+                let Some(lines) = lines else {
+                    ui.label(tr("GPIO not available on this host"));
+                    return;
+                };
+                egui::Grid::new("gpio_lines").striped(true).show(ui, |ui| {
+                    for header in ["Line", "Pin", "Raw", "State", "Active", "Pull", "Debounce"] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+                    for line in &lines {
+                        ui.label(&line.role);
+                        ui.label(line.pin.to_string());
+                        ui.label(match line.raw_high {
+                            Some(true) => "high",
+                            Some(false) => "low",
+                            None => "?",
+                        });
+                        if line.active {
+                            ui.colored_label(egui::Color32::from_rgb(255, 140, 0), "ACTIVE");
+                        } else {
+                            ui.label(tr("idle"));
+                        }
+                        ui.label(if line.config.active_low { "low" } else { "high" });
+                        ui.label(format!("{:?}", line.config.pull).to_lowercase());
+                        ui.label(format!("{} ms", line.config.debounce.as_millis()));
+                        ui.end_row();
+                    }
+                });
+            });
+            
+            // Machine state DB: what moved / changed between two snapshots
+            let hostname = self.hostname.clone();
+            if let Some(panel) = self.state_diff.as_mut() {
+                gui_state::collapsing(ui, "operations.what_changed", tr("What Changed"), |ui| panel.show(ui, &hostname));
+            }
+            let bumps = &mut self.bumps;
+            gui_state::collapsing(ui, "operations.bumps", tr("Bumps"), |ui| bumps.show(ui, &hostname));
+            
             // Display messages (debug log style)
-            ui.collapsing("Messages", |ui| {
+            gui_state::collapsing(ui, "operations.about", tr("About"), |ui| {
+                let own = build_info::BuildInfo::local("operations_gui");
+                ui.label(own.describe());
+                match self.stepper_build.lock().ok().and_then(|b| b.clone()) {
+                    Some(peer) => {
+                        ui.label(peer.describe());
+                        for problem in own.mismatches(&peer) {
+                            ui.colored_label(egui::Color32::from_rgb(255, 140, 0), problem);
+                        }
+                    }
+                    None => {
+                        ui.label(tr("stepper_gui: not reached yet"));
+                    }
+                }
+                let rates = self.refresh_rates;
+                ui.label(format!(
+                    "Refresh: slot {} Hz, analysis {} Hz, repaint {} Hz",
+                    rates.slot_poll_hz, rates.analysis_hz, rates.repaint_hz
+                ));
+            });
+            
+            // Phases of the current or latest operation, from its tracing spans
+            gui_state::collapsing(ui, "operations.timeline", tr("Timeline"), |ui| {
+                let log = logging::phase_buffer();
+                let timeline = log.lock().ok().and_then(|log| timeline::Timeline::from_log(&log, Instant::now()));
+                match timeline {
+                    Some(timeline) => timeline::render_timeline(ui, &timeline),
+                    None => {
+                        ui.label(tr("No operation has run yet"));
+                    }
+                }
+            });
+            
+            gui_state::collapsing(ui, "operations.messages", tr("Messages"), |ui| {
                 ui.horizontal(|ui| {
-                    if ui.button("Clear").clicked() {
+                    if ui.button(tr("Clear")).clicked() {
                         self.message.clear();
                     }
-                    if ui.button("Copy").clicked() {
+                    if ui.button(tr("Copy")).clicked() {
                         let log = self.message.clone();
                         ui.output_mut(|o| o.copied_text = log);
                     }
@@ -1630,10 +4942,41 @@ impl OperationsGUI {
                         );
                     });
             });
+
+            // Structured log (operation/IPC spans, set RUST_LOG for console detail)
+            gui_state::collapsing(ui, "operations.log", tr("Log"), |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Clear")).clicked() {
+                        logging::clear_buffer(&self.log_buffer);
+                    }
+                    if ui.button(tr("Copy")).clicked() {
+                        let log = logging::buffer_text(&self.log_buffer);
+                        ui.output_mut(|o| o.copied_text = log);
+                    }
+                });
+                let mut log_text = logging::buffer_text(&self.log_buffer);
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .auto_shrink([false; 2])
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut log_text)
+                                .desired_width(f32::INFINITY)
+                                .interactive(true)
+                                .code_editor()
+                        );
+                    });
+            });
     }
 }
 
 impl eframe::App for OperationsGUI {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.save_gui_state();
+        self.shutdown();
+    }
+
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         // Check exit flag and close window if set (but only if no operation is running)
         // This ensures BREAK button only stops operations, not the GUI
@@ -1645,23 +4988,193 @@ impl eframe::App for OperationsGUI {
             return;
         }
         
-        // Request continuous repaints for smooth meter updates
-        ctx.request_repaint_after(Duration::from_millis(16)); // ~60 Hz update rate
-        
-        // Poll for any finished background operations before rendering
-        self.poll_operation_result();
-        
-        // Update audio analysis from partials slot using get_results module
-        let partials = get_results::read_partials_from_slot(&self.partials_slot);
-        self.operations.read().unwrap().update_audio_analysis_with_partials(partials);
-        self.reconcile_voice_count_cap();
+        // Request continuous repaints for meter updates (REFRESH_RATES REPAINT_HZ)
+        ctx.request_repaint_after(self.repaint_interval());
         
-        egui::CentralPanel::default().show(ctx, |ui| {
-            self.render_ui(ui, ctx);
+        // Poll for finished background operations and refresh audio analysis before rendering
+        profiling::time(profiling::Probe::Frame, || {
+            self.tick();
+
+            egui::CentralPanel::default().show(ctx, |ui| {
+                self.render_ui(ui, ctx);
+            });
+        });
+        profiling::show_overlay(ctx);
+        self.capture_gui_state(ctx);
+    }
+}
+
+/// Operations offered for broadcast (descriptor `broadcast`); never z_hold or
+/// performance_mode, which run until BREAK and would stall the sequence
+fn broadcast_operations() -> Vec<operations::OperationDescriptor> {
+    operations::operation_catalog(&["X"]).into_iter().filter(|op| op.broadcast).collect()
+}
+
+/// An operation run on each instrument in turn
+struct Broadcast {
+    operation: String,
+    pending: std::collections::VecDeque<usize>,
+    current: Option<usize>,
+    outcomes: Vec<(String, bool)>, // (instrument, succeeded)
+}
+
+/// Several instruments (this host plus its INSTRUMENTS list), one tab each
+pub struct InstrumentTabs {
+    instruments: Vec<OperationsGUI>,
+    selected: usize,
+    broadcast_operation: String,
+    broadcast: Option<Broadcast>,
+    broadcast_summary: String,
+}
+
+impl InstrumentTabs {
+    pub fn new(instruments: Vec<OperationsGUI>) -> Self {
+        Self {
+            instruments,
+            selected: 0,
+            broadcast_operation: broadcast_operations()[0].name.to_string(),
+            broadcast: None,
+            broadcast_summary: String::new(),
+        }
+    }
+
+    fn start_broadcast(&mut self) {
+        self.broadcast_summary.clear();
+        self.broadcast = Some(Broadcast {
+            operation: self.broadcast_operation.clone(),
+            pending: (0..self.instruments.len()).collect(),
+            current: None,
+            outcomes: Vec::new(),
+        });
+    }
+
+    /// Drop the instruments not yet started and break the one running
+    fn stop_broadcast(&mut self) {
+        let Some(broadcast) = self.broadcast.as_mut() else { return; };
+        broadcast.pending.clear();
+        if let Some(idx) = broadcast.current {
+            self.instruments[idx].request_break();
+        }
+    }
+
+    /// Start the next instrument once the current one has finished
+    fn advance_broadcast(&mut self) {
+        let Some(broadcast) = self.broadcast.as_mut() else { return; };
+        if let Some(idx) = broadcast.current {
+            let gui = &self.instruments[idx];
+            if gui.is_busy() {
+                return;
+            }
+            let succeeded = gui.last_result() == Some((broadcast.operation.as_str(), true));
+            broadcast.outcomes.push((gui.hostname().to_string(), succeeded));
+            broadcast.current = None;
+        }
+        while let Some(idx) = broadcast.pending.pop_front() {
+            if self.instruments[idx].run_operation(&broadcast.operation) {
+                broadcast.current = Some(idx);
+                return;
+            }
+            broadcast.outcomes.push((self.instruments[idx].hostname().to_string(), false));
+        }
+        let outcomes: Vec<String> = broadcast.outcomes
+            .iter()
+            .map(|(host, ok)| format!("{} {}", host, if *ok { "ok" } else { "FAILED" }))
+            .collect();
+        self.broadcast_summary = format!("{}: {}", broadcast.operation, outcomes.join(", "));
+        self.broadcast = None;
+    }
+
+    fn render_tabs(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            for (idx, gui) in self.instruments.iter().enumerate() {
+                let label = if gui.is_busy() { format!("● {}", gui.hostname()) } else { gui.hostname().to_string() };
+                if ui.selectable_label(self.selected == idx, label).clicked() {
+                    self.selected = idx;
+                }
+            }
+        });
+        // Broadcasts run calibration-type operations, so performers don't get them
+        if !self.instruments[0].role_lock.is_technician() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label(tr("Broadcast:"));
+            let running = self.broadcast.is_some();
+            let choices = broadcast_operations();
+            let selected_label = choices
+                .iter()
+                .find(|op| op.name == self.broadcast_operation)
+                .map_or(String::new(), |op| tr(&op.label));
+            ui.add_enabled_ui(!running, |ui| {
+                egui::ComboBox::from_id_source("broadcast_select")
+                    .selected_text(selected_label)
+                    .show_ui(ui, |ui| {
+                        for op in &choices {
+                            ui.selectable_value(&mut self.broadcast_operation, op.name.to_string(), tr(&op.label));
+                        }
+                    });
+            });
+            if running {
+                if ui.button(tr("Stop Broadcast")).clicked() {
+                    self.stop_broadcast();
+                }
+                if let Some(broadcast) = &self.broadcast {
+                    let current = broadcast.current.map_or("", |idx| self.instruments[idx].hostname());
+                    ui.label(trf("running on {0} ({1} left)", &[&current, &broadcast.pending.len()]));
+                }
+            } else {
+                if ui.button(tr("Run on all")).on_hover_text(tr("Run the operation on each instrument in turn")).clicked() {
+                    self.start_broadcast();
+                }
+                ui.label(&self.broadcast_summary);
+            }
+        });
+    }
+}
+
+impl eframe::App for InstrumentTabs {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        for gui in self.instruments.iter_mut() {
+            gui.save_gui_state();
+            gui.shutdown();
+        }
+    }
+
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Same exit handling as a single OperationsGUI: close once EXIT's flag is set and nothing runs
+        if self.instruments.iter().any(|gui| {
+            gui.exit_flag.load(std::sync::atomic::Ordering::Relaxed)
+                && !gui.operation_running.load(std::sync::atomic::Ordering::Relaxed)
+        }) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            return;
+        }
+        ctx.request_repaint_after(self.instruments[self.selected].repaint_interval());
+
+        profiling::time(profiling::Probe::Frame, || {
+            for gui in self.instruments.iter_mut() {
+                gui.tick();
+            }
+            self.advance_broadcast();
+
+            egui::TopBottomPanel::top("instrument_tabs").show(ctx, |ui| {
+                self.render_tabs(ui);
+            });
+            egui::CentralPanel::default().show(ctx, |ui| {
+                self.instruments[self.selected].render_ui(ui, ctx);
+            });
         });
+        profiling::show_overlay(ctx);
+        // This host's instrument (the first tab) holds the window's saved state
+        self.instruments[0].capture_gui_state(ctx);
     }
 }
 
+/// Upper Z limit passed to operations (every Z stepper tops out at 100)
+fn z_max_positions(z_indices: &[usize]) -> std::collections::HashMap<usize, i32> {
+    z_indices.iter().map(|&idx| (idx, 100)).collect()
+}
+
 fn derive_stepper_roles(ops: &operations::Operations, total_steppers: usize) -> Vec<machine_state_logger::StepperRoleEntry> {
     let mut roles = Vec::new();
     let mut seen = HashSet::new();
@@ -1676,19 +5189,14 @@ fn derive_stepper_roles(ops: &operations::Operations, total_steppers: usize) ->
         }
     };
 
-    for string_idx in 0..ops.string_num {
-        let z_in_idx = ops.z_first_index + (string_idx * 2);
-        let z_out_idx = z_in_idx + 1;
-        push_entry(z_in_idx, "z_in", Some(string_idx));
-        push_entry(z_out_idx, "z_out", Some(string_idx));
-    }
-
-    if let Some(x_idx) = ops.x_step_index() {
-        push_entry(x_idx, "x_axis", None);
-    }
-
-    for (t_idx, step_idx) in ops.tuner_indices().iter().enumerate() {
-        push_entry(*step_idx, "tuner", Some(t_idx));
+    for idx in ops.topology.main_indices() {
+        match ops.topology.role_of(topology::Bank::Main, idx) {
+            Some(topology::StepperRole::Z { string, side: topology::ZSide::In }) => push_entry(idx, "z_in", Some(string)),
+            Some(topology::StepperRole::Z { string, side: topology::ZSide::Out }) => push_entry(idx, "z_out", Some(string)),
+            Some(topology::StepperRole::X) => push_entry(idx, "x_axis", None),
+            Some(topology::StepperRole::Tuner { string }) => push_entry(idx, "tuner", Some(string)),
+            None => {}
+        }
     }
 
     for idx in 0..total_steppers {
@@ -1705,46 +5213,129 @@ fn derive_stepper_roles(ops: &operations::Operations, total_steppers: usize) ->
     roles
 }
 
+#[derive(clap::Parser)]
+#[command(author, version, about = "String Driver operations GUI", long_about = None)]
+struct Args {
+    /// Print this host's operations (name, needed hardware, parameters) as JSON and exit
+    #[arg(long)]
+    list_operations: bool,
+}
+
+/// The host's operations without starting Operations: its axes come from the ARDUINO settings
+fn host_operation_catalog(hostname: &str) -> Vec<operations::OperationDescriptor> {
+    let axes: Vec<String> = match config_loader::load_arduino_settings(hostname) {
+        Ok(settings) => settings.x_step_index.map(|_| "X".to_string()).into_iter().chain(settings.axes.into_iter().map(|axis| axis.name)).collect(),
+        Err(_) => vec!["X".to_string()],
+    };
+    operations::operation_catalog(&axes.iter().map(String::as_str).collect::<Vec<_>>())
+}
+
+/// Register the operations of the host's PLUGINS libraries, before anything lists operations
+#[cfg(feature = "plugins")]
+pub fn load_plugins(hostname: &str) {
+    match config_loader::load_plugin_paths(hostname) {
+        Ok(paths) => {
+            for line in plugins::load_libraries(&paths) {
+                eprintln!("{}", line);
+            }
+        }
+        Err(e) => eprintln!("✗ {}", e),
+    }
+}
+
 fn main() {
+    use clap::{CommandFactory, FromArgMatches};
+    #[cfg(feature = "plugins")]
+    load_plugins(&gethostname::gethostname().to_string_lossy());
+    let catalog = host_operation_catalog(&gethostname::gethostname().to_string_lossy());
+    let matches = Args::command().after_help(operations::operations_help(&catalog)).get_matches();
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if args.list_operations {
+        match serde_json::to_string_pretty(&catalog) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("✗ {}", e),
+        }
+        return;
+    }
+
     println!("Operations GUI starting...");
-    env_logger::init();
+    let log_buffer = logging::init("operations_gui", "info");
+    build_info::log_startup("operations_gui");
+    i18n::init_for_host(&gethostname::gethostname().to_string_lossy());
     
     println!("Creating OperationsGUI instance...");
     let gui_result = OperationsGUI::new();
-    let gui = match gui_result {
+    let mut gui = match gui_result {
         Ok(gui) => {
             println!("✓ OperationsGUI created successfully");
             gui
         }
         Err(e) => {
-            eprintln!("✗ Failed to create OperationsGUI: {}", e);
+            eprintln!("✗ Failed to create OperationsGUI: {}", error::user_message(e.as_ref()));
             eprintln!("Error details: {:?}", e);
             std::process::exit(1);
         }
     };
     
+    gui.attach_log_buffer(log_buffer.clone());
+    if let Ok(rates) = config_loader::load_refresh_rates(gui.hostname()) {
+        profiling::set_budgets(profiling::Budgets::from_rates(&rates));
+    }
+
+    // Other instruments from this host's INSTRUMENTS list get a tab each
+    let targets = match config_loader::load_instruments(gui.hostname()) {
+        Ok(targets) => targets,
+        Err(e) => {
+            eprintln!("✗ Ignoring INSTRUMENTS: {}", error::user_message(&e));
+            Vec::new()
+        }
+    };
+    let role_lock = gui.role_lock();
+    let mut instruments = vec![gui];
+    for target in targets {
+        match OperationsGUI::for_instrument(&target.host, target.stepper_socket.as_deref()) {
+            Ok(mut other) => {
+                println!("✓ Added instrument '{}'", target.host);
+                other.attach_log_buffer(log_buffer.clone());
+                other.attach_role_lock(role_lock.clone());
+                instruments.push(other);
+            }
+            Err(e) => eprintln!("✗ Skipping instrument '{}': {}", target.host, error::user_message(e.as_ref())),
+        }
+    }
+
     println!("Initializing GUI window...");
-    // Position in top right: assume screen width ~1920, window width 430
-    // Position at x = screen_width - window_width - margin
+    // Where the window was left last time; the first launch puts it top right:
+    // assume screen width ~1920, window width 430, 20px margin from the right edge
     let window_width = 430.0;
     let screen_width = 1920.0; // Default, will be adjusted by window manager if needed
-    let top_right_x = screen_width - window_width - 20.0; // 20px margin from right edge
+    let top_right_x = screen_width - window_width - 20.0;
+    let gui_state = gui_state::GuiState::load(instruments[0].hostname(), "operations_gui");
     
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_title("Operations Control")
-            .with_inner_size([window_width, 1200.0])
-            .with_position(egui::pos2(top_right_x, 0.0)), // Top right
+        viewport: gui_state.viewport(
+            egui::ViewportBuilder::default().with_title("Operations Control"),
+            Some(egui::pos2(top_right_x, 0.0)),
+            [window_width, 1200.0],
+        ),
         ..Default::default()
     };
+    instruments[0].attach_gui_state(gui_state);
     
     println!("Starting eframe::run_native...");
     if let Err(e) = eframe::run_native(
         "Operations Control",
         options,
-        Box::new(|_cc| {
+        Box::new(move |cc| -> Box<dyn eframe::App> {
             println!("✓ GUI window created, entering event loop");
-            Box::new(gui)
+            if let Some(gui_state) = &instruments[0].gui_state {
+                gui_state.install(&cc.egui_ctx);
+            }
+            if instruments.len() == 1 {
+                Box::new(instruments.remove(0))
+            } else {
+                Box::new(InstrumentTabs::new(instruments))
+            }
         }),
     ) {
         eprintln!("✗ GUI error: {}", e);
@@ -1755,3 +5346,50 @@ fn main() {
     println!("Operations GUI exiting");
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stringdriver::loopback_serial::{FirmwareProtocol, LoopbackBoard};
+    use stringdriver::plugins::{Operation, OperationContext, OperationDescriptor, PluginResult, Report};
+
+    /// Lift string 0's Z pair a few steps
+    struct Lift;
+
+    impl Operation for Lift {
+        fn descriptor(&self) -> OperationDescriptor {
+            OperationDescriptor::new("gui_lift_string_0", "Lift", "Lift string 0's Z pair", &[], &[])
+        }
+
+        fn run(&self, ctx: &mut OperationContext<'_>) -> PluginResult<Report> {
+            let (left, right) = ctx.z_pair_for_channel(0).ok_or("no Z pair for channel 0")?;
+            ctx.rel_move(left, 5)?;
+            ctx.rel_move(right, 5)?;
+            Ok(Report::from(format!("lifted {} and {}", left, right)))
+        }
+    }
+
+    #[test]
+    fn test_registered_operation_runs_from_the_gui() {
+        let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/closed-loop.yaml");
+        std::env::set_var(config_loader::CONFIG_ENV, &fixture);
+        plugins::register(Arc::new(Lift)).unwrap();
+        let ops = operations::Operations::for_host("sim-bench", None).unwrap();
+        assert!(ops.operation_descriptors().iter().any(|op| op.name == "gui_lift_string_0"));
+        assert!(host_operation_catalog("sim-bench").iter().any(|op| op.name == "gui_lift_string_0"));
+
+        let protocol = FirmwareProtocol {
+            positions_id: 5, encoders_id: None, amove_id: 0, rmove_id: 1, set_stepper_id: 2,
+            enable_id: None, value_bytes: 2, checksum: false, version: None,
+        };
+        let mut board = LoopbackBoard::new(protocol, 5);
+        let mut positions = board.positions();
+        let max_positions = z_max_positions(&ops.get_z_stepper_indices());
+        let bands = operations::ChannelBands::defaults(1, 4);
+        let message = ops.run_named("gui_lift_string_0", &mut board, &mut positions, &max_positions, &bands, None, None, None).unwrap();
+        assert_eq!(message, "lifted 1 and 2");
+        assert_eq!(board.positions(), [0, 5, 5, 0, 0]);
+        assert!(ops.run_named("no_such_operation", &mut board, &mut positions, &max_positions, &bands, None, None, None).is_err());
+        std::env::remove_var(config_loader::CONFIG_ENV);
+    }
+}
@@ -0,0 +1,371 @@
+/// First-run setup for a new host
+///
+/// Interactive terminal wizard for a machine that has no block in
+/// string_driver.yaml yet: lists serial ports, asks for the stepper layout,
+/// looks for GPIO chips, writes the new host section (keeping a backup of the
+/// YAML) and then checks the result by loading it back through config_loader
+/// and opening the configured boards.
+///
+/// Run with:
+///   cargo run --bin setup_wizard
+///   cargo run --bin setup_wizard -- --hostname stringdriver-4   # prepare a block for another machine
+///   cargo run --bin setup_wizard -- import-legacy surfer.ini [--hostname h] [--os Ubuntu] [--dry-run] [--yes]
+///
+/// `import-legacy` merges surfer.py's old settings (.ini or pickle) into the
+/// host block instead, listing every change and every setting it left out.
+
+use stringdriver::{config_loader, legacy_config};
+
+use anyhow::{anyhow, Result};
+use gethostname::gethostname;
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::time::Duration;
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("\nERROR: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let args: Vec<String> = env::args().collect();
+    let hostname = args.iter()
+        .position(|a| a == "--hostname")
+        .and_then(|i| args.get(i + 1).cloned())
+        .unwrap_or_else(|| gethostname().to_string_lossy().to_string());
+    if args.get(1).map(String::as_str) == Some("import-legacy") {
+        return import_legacy(&args[2..], &hostname);
+    }
+
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("String Driver setup for host '{}'", hostname);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+
+    if config_loader::load_own_host_section(&hostname).is_ok()
+        && !ask_yes_no(&format!("'{}' already has a block in string_driver.yaml. Replace it?", hostname), false)?
+    {
+        println!("Nothing changed.");
+        return Ok(());
+    }
+
+    let os_section = ask_choice("OS section", &["RaspberryPi", "Ubuntu", "macOS"], detect_os_section())?;
+    let mut section = serde_yaml::Mapping::new();
+    let mut set = |key: &str, value: serde_yaml::Value| {
+        section.insert(serde_yaml::Value::from(key), value);
+    };
+    set("SHMEM_PATH", serde_yaml::Value::from(if os_section == "macOS" { "/tmp" } else { "/dev/shm" }));
+    set("CONTROL_FILE", serde_yaml::Value::from(if os_section == "macOS" { "/tmp/audio_control" } else { "/dev/shm/audio_control" }));
+
+    // Serial boards
+    let ports = list_serial_ports();
+    let string_num = ask_usize("Number of strings", Some(6))?;
+    set("STRING_NUM", serde_yaml::Value::from(string_num as u64));
+    match pick_port("Carriage (main) Arduino port", &ports)? {
+        Some(port) => {
+            let firmware = ask_choice("Carriage firmware", &["string_driver_v1", "string_driver_v2", "string_driver_v3"], "string_driver_v2")?;
+            let num_steppers = ask_usize("Steppers reported by the carriage firmware (ARD_NUM_STEPPERS)", Some(1 + string_num * 2))?;
+            let x_step_index = ask_optional_usize("X stepper index (blank if none)", Some(0))?;
+            let z_first_index = ask_usize("First Z stepper index", Some(x_step_index.map_or(0, |x| x + 1)))?;
+            if z_first_index + string_num * 2 > num_steppers {
+                println!("  Warning: {} Z steppers from index {} do not fit in {} steppers", string_num * 2, z_first_index, num_steppers);
+            }
+            set("ARDUINO_FIRMWARE", serde_yaml::Value::from(firmware));
+            set("ARD_PORT", serde_yaml::Value::from(port));
+            set("ARD_NUM_STEPPERS", serde_yaml::Value::from(num_steppers as u64));
+            set("Z_FIRST_INDEX", serde_yaml::Value::from(z_first_index as u64));
+            if let Some(x) = x_step_index {
+                set("X_STEP_INDEX", serde_yaml::Value::from(x as u64));
+                set("X_MAX_POS", serde_yaml::Value::from(ask_usize("X travel in steps (X_MAX_POS)", Some(1500))? as u64));
+            }
+        }
+        None => {
+            set("ARD_PORT", serde_yaml::Value::Null);
+            set("ARD_NUM_STEPPERS", serde_yaml::Value::Null);
+        }
+    }
+    if let Some(port) = pick_port("Tuner Arduino port (blank if no separate tuner board)", &ports)? {
+        let tuners = ask_usize("Tuner steppers on that board (ARD_T_NUM_STEPPERS)", Some(string_num))?;
+        set("ARD_T_PORT", serde_yaml::Value::from(port));
+        set("ARD_T_NUM_STEPPERS", serde_yaml::Value::from(tuners as u64));
+        set("TUNER_FIRST_INDEX", serde_yaml::Value::from(0u64));
+    }
+    set("z_up_step", serde_yaml::Value::from(2));
+    set("z_down_step", serde_yaml::Value::from(-2));
+
+    // GPIO touch sensors
+    let chips = list_gpio_chips();
+    if chips.is_empty() {
+        println!("\nNo GPIO chips found (/dev/gpiochip*) - GPIO disabled.");
+        set("GPIO_ENABLED", serde_yaml::Value::from(false));
+    } else {
+        println!("\nGPIO chips: {}", chips.join(", "));
+        if ask_yes_no("Use GPIO touch sensors (bump_check, z_calibrate)?", true)? {
+            let pins = ask_pins(&format!("Z touch pins, {} comma-separated BCM numbers (in, out per string)", string_num * 2), string_num * 2)?;
+            let mut components = serde_yaml::Mapping::new();
+            components.insert(
+                serde_yaml::Value::from("Z_TOUCH_PINS"),
+                serde_yaml::Value::Sequence(pins.into_iter().map(|p| serde_yaml::Value::from(p as u64)).collect()),
+            );
+            set("GPIO_ENABLED", serde_yaml::Value::from(true));
+            set("GPIO_LIBRARY", serde_yaml::Value::from("gpiod"));
+            set("GPIO_COMPONENTS", serde_yaml::Value::Mapping(components));
+        } else {
+            set("GPIO_ENABLED", serde_yaml::Value::from(false));
+        }
+    }
+
+    println!("\nNew block for '{}' under {}:\n", hostname, os_section);
+    let mut preview = serde_yaml::Mapping::new();
+    preview.insert(serde_yaml::Value::from(hostname.as_str()), serde_yaml::Value::Mapping(section.clone()));
+    for line in serde_yaml::to_string(&preview)?.lines() {
+        println!("  {}", line);
+    }
+    if !ask_yes_no("\nWrite this to string_driver.yaml?", true)? {
+        println!("Nothing changed.");
+        return Ok(());
+    }
+    let backup = config_loader::install_host_section(&os_section, &hostname, &section)?;
+    println!("Written (previous file saved to {})", backup.display());
+
+    verify(&hostname)
+}
+
+/// Load the new block back the way the GUIs will and open the configured boards
+fn verify(hostname: &str) -> Result<()> {
+    println!("\nChecking the new configuration...");
+    let ard = config_loader::load_arduino_settings(hostname)?;
+    println!("  ✓ Arduino settings: {} string(s), port {}", ard.string_num, ard.port.as_deref().unwrap_or("none"));
+    config_loader::load_operations_settings(hostname)?;
+    println!("  ✓ Operations settings");
+    match config_loader::load_gpio_settings(hostname)? {
+        Some(gpio) => println!("  ✓ GPIO settings ({} touch pin(s))", gpio.components.and_then(|c| c.z_touch_pins).map_or(0, |p| p.len())),
+        None => println!("  ✓ GPIO disabled"),
+    }
+
+    let mut failed = false;
+    for (role, port) in [("carriage", ard.port.as_deref()), ("tuner", ard.ard_t_port.as_deref())] {
+        let Some(port) = port else { continue };
+        match serialport::new(port, 115200).timeout(Duration::from_millis(500)).open() {
+            Ok(_) => println!("  ✓ {} board: opened {}", role, port),
+            Err(e) => {
+                println!("  ✗ {} board: cannot open {}: {}", role, port, e);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        return Err(anyhow!("Configuration written, but not every board could be opened"));
+    }
+    println!("\nDone. Start everything with: cargo run --bin launcher --release");
+    Ok(())
+}
+
+/// `import-legacy <file>`: surfer.py settings into the host block, showing every change first
+fn import_legacy(args: &[String], hostname: &str) -> Result<()> {
+    let flag = |name: &str| args.iter().any(|a| a == name);
+    let option = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1).cloned());
+    let takes_value = |i: usize| i > 0 && matches!(args[i - 1].as_str(), "--hostname" | "--os");
+    let Some(file) = args.iter().enumerate().find(|(i, a)| !a.starts_with("--") && !takes_value(*i)).map(|(_, a)| a) else {
+        return Err(anyhow!("Usage: setup_wizard import-legacy <surfer.ini | settings.pkl> [--hostname h] [--os RaspberryPi|Ubuntu|macOS] [--dry-run] [--yes]"));
+    };
+
+    let pairs = legacy_config::read_legacy_file(Path::new(file))?;
+    let import = legacy_config::map_legacy(&pairs);
+    println!("Read {} setting(s) from {}", pairs.len(), file);
+    for (legacy, key) in &import.mapped {
+        println!("  {} -> {}", legacy, key);
+    }
+    if !import.unmapped.is_empty() {
+        println!("\nNot imported:");
+        for entry in &import.unmapped {
+            println!("  {}", entry);
+        }
+    }
+    if import.settings.is_empty() {
+        println!("\nNothing to import.");
+        return Ok(());
+    }
+
+    let (os_section, mut section) = match config_loader::load_own_host_section(hostname) {
+        Ok(existing) => existing,
+        Err(_) => {
+            let os_section = option("--os").unwrap_or_else(|| detect_os_section().to_string());
+            println!("\n'{}' has no block in string_driver.yaml: creating one under {}.", hostname, os_section);
+            println!("Run setup_wizard without import-legacy afterwards for the ports and stepper layout.");
+            (os_section, serde_yaml::Mapping::new())
+        }
+    };
+    let changes = legacy_config::merge_into(&mut section, &import);
+    if changes.is_empty() {
+        println!("\n'{}' already has these values. Nothing changed.", hostname);
+        return Ok(());
+    }
+    println!("\nChanges to '{}' under {}:", hostname, os_section);
+    for change in &changes {
+        println!("  {}", change);
+    }
+    if flag("--dry-run") {
+        println!("\nDry run: string_driver.yaml not written.");
+        return Ok(());
+    }
+    if !flag("--yes") && !ask_yes_no("\nWrite these changes to string_driver.yaml?", true)? {
+        println!("Nothing changed.");
+        return Ok(());
+    }
+    let backup = config_loader::install_host_section(&os_section, hostname, &section)?;
+    println!("Written (previous file saved to {})", backup.display());
+    config_loader::load_operations_settings(hostname)?;
+    println!("  ✓ Operations settings load with the imported values");
+    Ok(())
+}
+
+fn detect_os_section() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "macOS"
+    } else if std::fs::read_to_string("/proc/device-tree/model").map_or(false, |m| m.contains("Raspberry Pi")) {
+        "RaspberryPi"
+    } else {
+        "Ubuntu"
+    }
+}
+
+/// Serial ports with a short description (USB product/serial when known)
+fn list_serial_ports() -> Vec<String> {
+    let ports = serialport::available_ports().unwrap_or_default();
+    if ports.is_empty() {
+        println!("No serial ports found.");
+    } else {
+        println!("Serial ports:");
+    }
+    ports
+        .into_iter()
+        .enumerate()
+        .map(|(i, port)| {
+            let detail = match &port.port_type {
+                serialport::SerialPortType::UsbPort(usb) => format!(
+                    "USB {:04x}:{:04x} {} {}",
+                    usb.vid,
+                    usb.pid,
+                    usb.product.as_deref().unwrap_or(""),
+                    usb.serial_number.as_deref().map(|s| format!("(serial {})", s)).unwrap_or_default()
+                ),
+                other => format!("{:?}", other),
+            };
+            println!("  [{}] {}  {}", i + 1, port.port_name, detail.trim());
+            port.port_name
+        })
+        .collect()
+}
+
+fn list_gpio_chips() -> Vec<String> {
+    let mut chips: Vec<String> = std::fs::read_dir("/dev")
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| name.starts_with("gpiochip"))
+                .collect()
+        })
+        .unwrap_or_default();
+    chips.sort();
+    chips
+}
+
+// -------------------- Prompts --------------------
+
+fn prompt(question: &str) -> Result<String> {
+    print!("{}: ", question);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    if io::stdin().lock().read_line(&mut line)? == 0 {
+        return Err(anyhow!("Input closed"));
+    }
+    Ok(line.trim().to_string())
+}
+
+fn ask_yes_no(question: &str, default: bool) -> Result<bool> {
+    loop {
+        let answer = prompt(&format!("{} [{}]", question, if default { "Y/n" } else { "y/N" }))?;
+        match answer.to_lowercase().as_str() {
+            "" => return Ok(default),
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => println!("  Please answer y or n"),
+        }
+    }
+}
+
+fn ask_choice(question: &str, options: &[&str], default: &str) -> Result<String> {
+    loop {
+        let answer = prompt(&format!("{} ({}) [{}]", question, options.join(" / "), default))?;
+        if answer.is_empty() {
+            return Ok(default.to_string());
+        }
+        if options.contains(&answer.as_str()) {
+            return Ok(answer);
+        }
+        println!("  Expected one of: {}", options.join(", "));
+    }
+}
+
+fn ask_optional_usize(question: &str, default: Option<usize>) -> Result<Option<usize>> {
+    loop {
+        let shown = default.map(|d| d.to_string()).unwrap_or_default();
+        let answer = prompt(&format!("{} [{}]", question, shown))?;
+        if answer.is_empty() {
+            return Ok(default);
+        }
+        if answer == "-" || answer.eq_ignore_ascii_case("none") {
+            return Ok(None);
+        }
+        match answer.parse() {
+            Ok(n) => return Ok(Some(n)),
+            Err(_) => println!("  Enter a number, or 'none'"),
+        }
+    }
+}
+
+fn ask_usize(question: &str, default: Option<usize>) -> Result<usize> {
+    loop {
+        if let Some(n) = ask_optional_usize(question, default)? {
+            return Ok(n);
+        }
+        println!("  A value is required");
+    }
+}
+
+/// Port by list number or path; blank for none
+fn pick_port(question: &str, ports: &[String]) -> Result<Option<String>> {
+    loop {
+        let answer = prompt(&format!("{} (number, path, or blank)", question))?;
+        if answer.is_empty() {
+            return Ok(None);
+        }
+        if let Ok(n) = answer.parse::<usize>() {
+            if let Some(port) = n.checked_sub(1).and_then(|i| ports.get(i)) {
+                return Ok(Some(port.clone()));
+            }
+            println!("  No port [{}]", n);
+            continue;
+        }
+        if !Path::new(&answer).exists() {
+            println!("  Note: {} does not exist right now", answer);
+        }
+        return Ok(Some(answer));
+    }
+}
+
+fn ask_pins(question: &str, expected: usize) -> Result<Vec<u32>> {
+    loop {
+        let answer = prompt(question)?;
+        let pins: Result<Vec<u32>, _> = answer.split(',').map(|p| p.trim().parse::<u32>()).collect();
+        match pins {
+            Ok(pins) if pins.len() == expected => return Ok(pins),
+            Ok(pins) => println!("  Got {} pin(s), expected {}", pins.len(), expected),
+            Err(_) => println!("  Use comma-separated numbers, e.g. 8, 17, 18, 27"),
+        }
+    }
+}
@@ -0,0 +1,235 @@
+/// GUI state kept across launches, per host
+///
+/// Window position and size, which collapsible panels are open and, in
+/// operations_gui, the selected operation and Repeat are saved as JSON in
+/// `gui_state_<host>.json` under the config dir ($XDG_CONFIG_HOME/stringdriver,
+/// else ~/.config/stringdriver) and restored at the next start. stepper_gui,
+/// operations_gui and master_gui each keep their own entry in the host's file.
+/// Panels opt in through `collapsing`, keyed by a name that does not change with
+/// the language.
+
+#[cfg(feature = "gui")]
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "gui")]
+use std::time::{Duration, Instant};
+
+/// Changes are written at most this often while running, and on exit
+#[cfg(feature = "gui")]
+const SAVE_INTERVAL: Duration = Duration::from_secs(2);
+/// Smaller windows (minimised, mid-resize) are not remembered
+#[cfg(feature = "gui")]
+const MIN_WINDOW_SIDE: f32 = 100.0;
+
+/// Outer position and inner size, in points
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// What one GUI restores at startup
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AppState {
+    #[serde(default)]
+    pub window: Option<WindowGeometry>,
+    #[serde(default)]
+    pub sections: BTreeMap<String, bool>, // Panel key -> open
+    #[serde(default)]
+    pub selected_operation: Option<String>,
+    #[serde(default)]
+    pub repeat: bool,
+}
+
+/// The host's file: one entry per GUI
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StateFile {
+    #[serde(default)]
+    apps: BTreeMap<String, AppState>,
+}
+
+/// $XDG_CONFIG_HOME/stringdriver, else ~/.config/stringdriver, else the project root
+pub fn state_dir() -> PathBuf {
+    let non_empty = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+    non_empty("XDG_CONFIG_HOME")
+        .or_else(|| non_empty("HOME").map(|home| home.join(".config")))
+        .map(|dir| dir.join("stringdriver"))
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")))
+}
+
+pub fn state_path(hostname: &str) -> PathBuf {
+    state_dir().join(format!("gui_state_{}.json", hostname))
+}
+
+fn read_file(path: &Path) -> StateFile {
+    let Ok(json) = std::fs::read_to_string(path) else {
+        return StateFile::default();
+    };
+    serde_json::from_str(&json).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring unreadable GUI state {}: {}", path.display(), e);
+        StateFile::default()
+    })
+}
+
+/// One GUI's saved state and where it goes
+#[derive(Debug)]
+pub struct GuiState {
+    path: PathBuf,
+    app: String,
+    pub state: AppState, // As of the last capture
+    saved: AppState,
+    #[cfg(feature = "gui")]
+    next_save: Instant,
+}
+
+impl GuiState {
+    /// `app`'s entry in the host's file (empty when there is none yet)
+    pub fn load(hostname: &str, app: &str) -> Self {
+        Self::at(state_path(hostname), app)
+    }
+
+    pub fn at(path: PathBuf, app: &str) -> Self {
+        let state = read_file(&path).apps.remove(app).unwrap_or_default();
+        Self {
+            path,
+            app: app.to_string(),
+            saved: state.clone(),
+            state,
+            #[cfg(feature = "gui")]
+            next_save: Instant::now() + SAVE_INTERVAL,
+        }
+    }
+
+    /// Write the entry if it changed since the last save, keeping the other GUIs' entries
+    pub fn save(&mut self) {
+        if self.state == self.saved {
+            return;
+        }
+        let mut file = read_file(&self.path);
+        file.apps.insert(self.app.clone(), self.state.clone());
+        let written = self.path.parent().map_or(Ok(()), std::fs::create_dir_all).and_then(|_| {
+            let json = serde_json::to_string_pretty(&file).map_err(std::io::Error::other)?;
+            let tmp = self.path.with_extension("json.tmp");
+            std::fs::write(&tmp, json)?;
+            std::fs::rename(&tmp, &self.path)
+        });
+        match written {
+            Ok(()) => self.saved = self.state.clone(),
+            // Not retried until the state changes again
+            Err(e) => {
+                tracing::warn!("Could not save GUI state to {}: {}", self.path.display(), e);
+                self.saved = self.state.clone();
+            }
+        }
+    }
+
+    /// The saved window geometry, or `default_position` with `default_size`
+    #[cfg(feature = "gui")]
+    pub fn viewport(&self, builder: egui::ViewportBuilder, default_position: Option<egui::Pos2>, default_size: [f32; 2]) -> egui::ViewportBuilder {
+        match self.state.window {
+            Some(window) => builder.with_position(egui::pos2(window.x, window.y)).with_inner_size([window.width, window.height]),
+            None => {
+                let builder = builder.with_inner_size(default_size);
+                match default_position {
+                    Some(position) => builder.with_position(position),
+                    None => builder,
+                }
+            }
+        }
+    }
+
+    /// Hand the saved panel states to `collapsing`; call once with the creation context
+    #[cfg(feature = "gui")]
+    pub fn install(&self, ctx: &egui::Context) {
+        let sections = Sections(self.state.sections.clone());
+        ctx.data_mut(|d| d.insert_temp(sections_id(), sections));
+    }
+
+    /// Take the window geometry and panel states from this frame, saving now and then
+    #[cfg(feature = "gui")]
+    pub fn capture(&mut self, ctx: &egui::Context) {
+        let (outer, inner, minimized) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (viewport.outer_rect, viewport.inner_rect, viewport.minimized.unwrap_or(false))
+        });
+        if let (Some(outer), Some(inner), false) = (outer, inner, minimized) {
+            if inner.width() >= MIN_WINDOW_SIDE && inner.height() >= MIN_WINDOW_SIDE {
+                self.state.window = Some(WindowGeometry { x: outer.min.x, y: outer.min.y, width: inner.width(), height: inner.height() });
+            }
+        }
+        if let Some(sections) = ctx.data(|d| d.get_temp::<Sections>(sections_id())) {
+            self.state.sections = sections.0;
+        }
+        let now = Instant::now();
+        if now >= self.next_save {
+            self.next_save = now + SAVE_INTERVAL;
+            self.save();
+        }
+    }
+}
+
+/// Panel open states shared through the egui context
+#[cfg(feature = "gui")]
+#[derive(Clone, Default)]
+struct Sections(BTreeMap<String, bool>);
+
+#[cfg(feature = "gui")]
+fn sections_id() -> egui::Id {
+    egui::Id::new("gui_state_sections")
+}
+
+/// `ui.collapsing` that opens as it was left last time; `key` names the panel in the file
+#[cfg(feature = "gui")]
+pub fn collapsing<R>(
+    ui: &mut egui::Ui,
+    key: &str,
+    heading: impl Into<egui::WidgetText>,
+    add_contents: impl FnOnce(&mut egui::Ui) -> R,
+) -> egui::CollapsingResponse<R> {
+    let saved = ui.ctx().data(|d| d.get_temp::<Sections>(sections_id()).and_then(|s| s.0.get(key).copied()));
+    let response = egui::CollapsingHeader::new(heading)
+        .id_source(key)
+        .default_open(saved.unwrap_or(false))
+        .show(ui, add_contents);
+    let open = response.openness > 0.5;
+    if saved != Some(open) {
+        ui.ctx().data_mut(|d| {
+            d.get_temp_mut_or_default::<Sections>(sections_id()).0.insert(key.to_string(), open);
+        });
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_each_gui_keeps_its_own_entry() {
+        let path = std::env::temp_dir().join(format!("stringdriver-gui-state-{}.json", std::process::id()));
+        let mut operations = GuiState::at(path.clone(), "operations_gui");
+        assert_eq!(operations.state, AppState::default());
+        operations.state.window = Some(WindowGeometry { x: 1470.0, y: 0.0, width: 430.0, height: 1200.0 });
+        operations.state.sections.insert("messages".to_string(), true);
+        operations.state.selected_operation = Some("z_adjust".to_string());
+        operations.state.repeat = true;
+        operations.save();
+
+        let mut stepper = GuiState::at(path.clone(), "stepper_gui");
+        stepper.state.sections.insert("about".to_string(), false);
+        stepper.save();
+
+        let restored = GuiState::at(path.clone(), "operations_gui");
+        assert_eq!(restored.state, operations.state);
+        assert_eq!(GuiState::at(path.clone(), "stepper_gui").state.sections.get("about"), Some(&false));
+
+        // A damaged file starts over rather than failing the GUI
+        std::fs::write(&path, "{not json").unwrap();
+        assert_eq!(GuiState::at(path.clone(), "operations_gui").state, AppState::default());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,249 @@
+/// Subsystem health summary
+///
+/// One red/yellow/green line per subsystem (Arduino boards, GPIO, shared memory,
+/// DB logger, launcher-supervised components, last operation), rendered by
+/// operations_gui and therefore also inside master_gui. The launcher reports its
+/// component states through a small status file in the project root.
+
+#[cfg(feature = "gui")]
+use eframe::egui;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// Partials frames older than this count as stale
+const SHM_WARN_AGE: Duration = Duration::from_secs(1);
+const SHM_ERROR_AGE: Duration = Duration::from_secs(5);
+/// Queued DB writes above this count as a backlog
+const DB_BACKLOG_WARN: usize = 50;
+/// A supervising launcher rewrites its status file every second or so
+const SUPERVISOR_STALE_AGE: Duration = Duration::from_secs(10);
+
+const SUPERVISOR_STATUS_FILE: &str = ".launcher_status";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthLevel {
+    Ok,
+    Warn,
+    Error,
+    Unknown, // Not configured / nothing reported yet
+}
+
+#[cfg(feature = "gui")]
+impl HealthLevel {
+    fn color(self) -> egui::Color32 {
+        match self {
+            HealthLevel::Ok => egui::Color32::from_rgb(40, 170, 60),
+            HealthLevel::Warn => egui::Color32::from_rgb(230, 170, 0),
+            HealthLevel::Error => egui::Color32::from_rgb(220, 40, 40),
+            HealthLevel::Unknown => egui::Color32::GRAY,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SubsystemHealth {
+    pub name: String,
+    pub level: HealthLevel,
+    pub detail: String,
+}
+
+impl SubsystemHealth {
+    pub fn new(name: &str, level: HealthLevel, detail: impl Into<String>) -> Self {
+        Self { name: name.to_string(), level, detail: detail.into() }
+    }
+}
+
+/// Worst level across entries (Unknown only if everything is unknown)
+pub fn overall_level(entries: &[SubsystemHealth]) -> HealthLevel {
+    let levels: Vec<HealthLevel> = entries.iter().map(|e| e.level).collect();
+    if levels.contains(&HealthLevel::Error) {
+        HealthLevel::Error
+    } else if levels.contains(&HealthLevel::Warn) {
+        HealthLevel::Warn
+    } else if levels.contains(&HealthLevel::Ok) {
+        HealthLevel::Ok
+    } else {
+        HealthLevel::Unknown
+    }
+}
+
+/// Collapsible "Health" panel; the header dot shows the worst subsystem
+#[cfg(feature = "gui")]
+pub fn render_health_panel(ui: &mut egui::Ui, entries: &[SubsystemHealth]) {
+    let overall = overall_level(entries);
+    let header = egui::RichText::new("● Health").color(overall.color());
+    egui::CollapsingHeader::new(header)
+        .id_source("health_panel")
+        .default_open(true)
+        .show(ui, |ui| {
+            egui::Grid::new("health_grid").num_columns(3).spacing([8.0, 2.0]).show(ui, |ui| {
+                for entry in entries {
+                    ui.colored_label(entry.level.color(), "●");
+                    ui.label(&entry.name);
+                    ui.label(&entry.detail);
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+// -------------------- Per-subsystem classification --------------------
+
+/// `connected`: None when stepper_gui could not be asked
+pub fn arduino_health(name: &str, connected: Option<bool>) -> SubsystemHealth {
+    match connected {
+        Some(true) => SubsystemHealth::new(name, HealthLevel::Ok, "connected"),
+        Some(false) => SubsystemHealth::new(name, HealthLevel::Error, "disconnected"),
+        None => SubsystemHealth::new(name, HealthLevel::Error, "stepper_gui unreachable"),
+    }
+}
+
+/// `gpio`: None when GPIO is not configured for this host, else whether the hardware was found
+pub fn gpio_health(gpio: Option<bool>) -> SubsystemHealth {
+    match gpio {
+        Some(true) => SubsystemHealth::new("GPIO", HealthLevel::Ok, "available"),
+        Some(false) => SubsystemHealth::new("GPIO", HealthLevel::Error, "configured but not available"),
+        None => SubsystemHealth::new("GPIO", HealthLevel::Unknown, "not configured"),
+    }
+}
+
+/// `age`: time since the last new partials frame (None if none seen yet)
+pub fn shm_health(age: Option<Duration>) -> SubsystemHealth {
+    match age {
+        None => SubsystemHealth::new("Shared memory", HealthLevel::Error, "no partials received"),
+        Some(age) if age >= SHM_ERROR_AGE => {
+            SubsystemHealth::new("Shared memory", HealthLevel::Error, format!("last frame {:.0}s ago", age.as_secs_f32()))
+        }
+        Some(age) if age >= SHM_WARN_AGE => {
+            SubsystemHealth::new("Shared memory", HealthLevel::Warn, format!("last frame {:.1}s ago", age.as_secs_f32()))
+        }
+        Some(age) => SubsystemHealth::new("Shared memory", HealthLevel::Ok, format!("last frame {} ms ago", age.as_millis())),
+    }
+}
+
+/// DB logger state as reported by MachineStateLoggingContext::status()
+pub fn db_health(configured: bool, connected: bool, enabled: bool, backlog: usize, errors: usize) -> SubsystemHealth {
+    let name = "DB logger";
+    if !configured {
+        return SubsystemHealth::new(name, HealthLevel::Unknown, "not configured");
+    }
+    if !connected {
+        return SubsystemHealth::new(name, HealthLevel::Error, "not connected");
+    }
+    let detail = format!("backlog {}, errors {}", backlog, errors);
+    if !enabled {
+        SubsystemHealth::new(name, HealthLevel::Warn, format!("disabled ({})", detail))
+    } else if backlog >= DB_BACKLOG_WARN || errors > 0 {
+        SubsystemHealth::new(name, HealthLevel::Warn, detail)
+    } else {
+        SubsystemHealth::new(name, HealthLevel::Ok, detail)
+    }
+}
+
+/// `last`: (operation, succeeded, time since it finished)
+pub fn operation_health(last: Option<(&str, bool, Duration)>) -> SubsystemHealth {
+    let name = "Last operation";
+    match last {
+        None => SubsystemHealth::new(name, HealthLevel::Unknown, "none run yet"),
+        Some((op, true, ago)) => SubsystemHealth::new(name, HealthLevel::Ok, format!("{} ok ({:.0}s ago)", op, ago.as_secs_f32())),
+        Some((op, false, ago)) => SubsystemHealth::new(name, HealthLevel::Error, format!("{} failed ({:.0}s ago)", op, ago.as_secs_f32())),
+    }
+}
+
+/// `faults`: "<sensor>: <why>" per suspect touch sensor; `quarantined`: sensors treated as absent
+pub fn sensor_health(faults: &[String], quarantined: &[String]) -> SubsystemHealth {
+    let name = "Touch sensors";
+    let mut detail = faults.join("; ");
+    if !quarantined.is_empty() {
+        if !detail.is_empty() {
+            detail.push_str("; ");
+        }
+        detail.push_str(&format!("quarantined: {}", quarantined.join(", ")));
+    }
+    if detail.is_empty() {
+        SubsystemHealth::new(name, HealthLevel::Ok, "no stuck or silent sensors")
+    } else {
+        SubsystemHealth::new(name, HealthLevel::Warn, detail)
+    }
+}
+
+// -------------------- Launcher supervisor status --------------------
+
+/// One launched component as last reported by the launcher
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentStatus {
+    pub name: String,
+    pub state: String, // running, restarting, stopped
+    pub pid: Option<u32>,
+    pub restarts: u32,
+}
+
+fn supervisor_status_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(SUPERVISOR_STATUS_FILE)
+}
+
+/// Written by the launcher: "supervising <bool>" then "<name> <state> <pid|-> <restarts>" per line
+pub fn write_supervisor_status(supervising: bool, components: &[ComponentStatus]) -> std::io::Result<()> {
+    let path = supervisor_status_path();
+    let tmp = path.with_extension("tmp");
+    {
+        let mut f = fs::File::create(&tmp)?;
+        writeln!(f, "supervising {}", supervising)?;
+        for c in components {
+            let pid = c.pid.map(|p| p.to_string()).unwrap_or_else(|| "-".to_string());
+            writeln!(f, "{} {} {} {}", c.name, c.state, pid, c.restarts)?;
+        }
+    }
+    // Rename so readers never see a half-written file
+    fs::rename(tmp, path)
+}
+
+fn read_supervisor_status() -> Option<(bool, Duration, Vec<ComponentStatus>)> {
+    let path = supervisor_status_path();
+    let age = fs::metadata(&path).ok()?.modified().ok()
+        .and_then(|m| SystemTime::now().duration_since(m).ok())
+        .unwrap_or_default();
+    let content = fs::read_to_string(&path).ok()?;
+    let mut lines = content.lines();
+    let supervising = lines.next()?.strip_prefix("supervising ")? == "true";
+    let components = lines
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some(ComponentStatus {
+                name: parts.next()?.to_string(),
+                state: parts.next()?.to_string(),
+                pid: parts.next()?.parse().ok(),
+                restarts: parts.next()?.parse().ok()?,
+            })
+        })
+        .collect();
+    Some((supervising, age, components))
+}
+
+/// One entry per component the launcher reported (or a single Unknown entry)
+pub fn supervisor_health() -> Vec<SubsystemHealth> {
+    let Some((supervising, age, components)) = read_supervisor_status() else {
+        return vec![SubsystemHealth::new("Launcher", HealthLevel::Unknown, "no launcher status")];
+    };
+    if !supervising {
+        let names: Vec<&str> = components.iter().map(|c| c.name.as_str()).collect();
+        return vec![SubsystemHealth::new("Launcher", HealthLevel::Unknown, format!("launched {} (not supervised)", names.join(", ")))];
+    }
+    if age > SUPERVISOR_STALE_AGE {
+        return vec![SubsystemHealth::new("Launcher", HealthLevel::Warn, format!("no update for {:.0}s", age.as_secs_f32()))];
+    }
+    components
+        .iter()
+        .map(|c| {
+            let level = match c.state.as_str() {
+                "running" if c.restarts == 0 => HealthLevel::Ok,
+                "running" | "restarting" => HealthLevel::Warn,
+                _ => HealthLevel::Error,
+            };
+            let pid = c.pid.map(|p| format!(" (PID {})", p)).unwrap_or_default();
+            SubsystemHealth::new(&c.name, level, format!("{}{}, {} restart(s)", c.state, pid, c.restarts))
+        })
+        .collect()
+}
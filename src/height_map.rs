@@ -0,0 +1,105 @@
+/// Amplitude vs. height above contact, per string, from height_calibrate
+///
+/// height_calibrate starts every enabled Z stepper at its touched zero (after
+/// z_calibrate / z_home) and steps them upward together, recording the averaged
+/// amp_sum of each channel at each height. The curves are saved as JSON
+/// (HEIGHT_CALIBRATION.FILE) and loaded on the next start, so a target can be
+/// given as "2-4 steps above contact" and turned into each channel's amp_sum
+/// min/max instead of tuning raw amplitudes string by string.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// One recorded height: steps above contact and the averaged amp_sum there
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HeightPoint {
+    pub steps: i32,
+    pub amp_sum: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HeightMap {
+    pub host: String,
+    pub recorded_at: String,
+    pub curves: BTreeMap<usize, Vec<HeightPoint>>, // Channel -> points by ascending height
+}
+
+impl HeightMap {
+    /// amp_sum expected `steps` above contact, interpolated between recorded heights
+    /// and held at the ends outside them
+    pub fn amp_at(&self, channel: usize, steps: f32) -> Option<f32> {
+        let curve = self.curves.get(&channel).filter(|c| !c.is_empty())?;
+        let first = curve[0];
+        let last = curve[curve.len() - 1];
+        if steps <= first.steps as f32 {
+            return Some(first.amp_sum);
+        }
+        if steps >= last.steps as f32 {
+            return Some(last.amp_sum);
+        }
+        curve.windows(2).find(|w| steps <= w[1].steps as f32).map(|w| {
+            let span = (w[1].steps - w[0].steps) as f32;
+            let t = if span > 0.0 { (steps - w[0].steps as f32) / span } else { 0.0 };
+            w[0].amp_sum + t * (w[1].amp_sum - w[0].amp_sum)
+        })
+    }
+
+    /// amp_sum (min, max) seen while the string sits between `low` and `high` steps
+    /// above contact; the curve need not be monotonic, so recorded points inside the
+    /// band count as well as its ends
+    pub fn amp_band(&self, channel: usize, low: i32, high: i32) -> Option<(f32, f32)> {
+        let (low, high) = (low.min(high), low.max(high));
+        let ends = [self.amp_at(channel, low as f32)?, self.amp_at(channel, high as f32)?];
+        let inside = self.curves.get(&channel)?.iter().filter(|p| (low..=high).contains(&p.steps)).map(|p| p.amp_sum);
+        let values: Vec<f32> = ends.into_iter().chain(inside).collect();
+        let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        Some((min, max))
+    }
+}
+
+/// Read a saved map; None when there is none yet or it cannot be used
+pub fn load(path: &Path) -> Option<HeightMap> {
+    let json = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&json) {
+        Ok(map) => Some(map),
+        Err(e) => {
+            tracing::warn!("Ignoring unreadable height map {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Write the map through a temporary file so a crash never leaves half a file
+pub fn save(path: &Path, map: &HeightMap) -> std::io::Result<()> {
+    let json = serde_json::to_string_pretty(map).map_err(std::io::Error::other)?;
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_band_to_amplitude_band() {
+        let point = |steps, amp_sum| HeightPoint { steps, amp_sum };
+        let mut map = HeightMap::default();
+        map.curves.insert(0, vec![point(0, 200.0), point(2, 120.0), point(4, 60.0), point(6, 20.0)]);
+
+        assert_eq!(map.amp_at(0, 3.0), Some(90.0));
+        assert_eq!(map.amp_at(0, 10.0), Some(20.0));
+        assert_eq!(map.amp_band(0, 2, 4), Some((60.0, 120.0)));
+        assert_eq!(map.amp_band(0, 5, 1), Some((40.0, 160.0)));
+        assert_eq!(map.amp_band(1, 2, 4), None);
+
+        // A bump inside the band widens it
+        map.curves.insert(1, vec![point(0, 50.0), point(2, 80.0), point(4, 40.0)]);
+        assert_eq!(map.amp_band(1, 1, 3), Some((60.0, 80.0)));
+    }
+}
@@ -0,0 +1,135 @@
+/// User-defined shell commands on lifecycle events (HOOKS)
+///
+/// A low-effort integration point for venue tooling: each HOOKS command for an
+/// event runs under `sh -c` with the event as one line of JSON on stdin, e.g.
+/// `{"host":"stringdriver-2","event":"operation_finished","time":"...","operation":"z_adjust","outcome":"ok"}`.
+/// Hooks run on a short-lived thread, one after another in YAML order, so a slow
+/// script never holds up an operation; one still running after HOOKS.TIMEOUT is killed.
+
+use crate::config_loader::{HookEvent, HookSettings};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How often a running hook is checked against the timeout
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug)]
+pub struct Hooks {
+    host: String,
+    settings: HookSettings,
+}
+
+impl Hooks {
+    pub fn new(host: &str, settings: HookSettings) -> Self {
+        Self { host: host.to_string(), settings }
+    }
+
+    /// True when some command runs on `event`
+    pub fn wants(&self, event: HookEvent) -> bool {
+        self.settings.commands.iter().any(|(e, _)| *e == event)
+    }
+
+    /// Run every command for `event` in the background; `details` (a JSON object)
+    /// is added to the payload next to host, event and time
+    pub fn fire(&self, event: HookEvent, details: serde_json::Value) {
+        let commands: Vec<String> = self.settings.commands.iter()
+            .filter(|(e, _)| *e == event)
+            .map(|(_, command)| command.clone())
+            .collect();
+        if commands.is_empty() {
+            return;
+        }
+        let input = payload(&self.host, event, details);
+        let timeout = self.settings.timeout;
+        std::thread::spawn(move || {
+            for command in &commands {
+                if let Err(e) = run_hook(command, &input, timeout) {
+                    tracing::warn!("Hook '{}' for {} failed: {}", command, event.key(), e);
+                }
+            }
+        });
+    }
+}
+
+/// The JSON line a hook reads on stdin
+fn payload(host: &str, event: HookEvent, details: serde_json::Value) -> String {
+    let mut fields = serde_json::Map::new();
+    fields.insert("host".to_string(), host.into());
+    fields.insert("event".to_string(), event.key().into());
+    fields.insert("time".to_string(), chrono::Local::now().to_rfc3339().into());
+    if let serde_json::Value::Object(details) = details {
+        fields.extend(details);
+    }
+    format!("{}\n", serde_json::Value::Object(fields))
+}
+
+/// Run one hook command with `input` on stdin, killing it after `timeout`; Err carries its stderr
+fn run_hook(command: &str, input: &str, timeout: Duration) -> Result<(), String> {
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("cannot run sh: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores stdin may exit before reading it; that is not a failure
+        let _ = stdin.write_all(input.as_bytes());
+    }
+    let started = Instant::now();
+    loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(_) => break,
+            None if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("still running after {:.1}s - killed", timeout.as_secs_f64()));
+            }
+            None => std::thread::sleep(POLL_INTERVAL),
+        }
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} ({})", String::from_utf8_lossy(&output.stderr).trim(), output.status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_carries_event_and_details() {
+        let line = payload("stringdriver-2", HookEvent::OperationFinished, serde_json::json!({ "operation": "z_adjust", "outcome": "ok" }));
+        assert!(line.ends_with('\n'));
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["host"], "stringdriver-2");
+        assert_eq!(value["event"], "operation_finished");
+        assert_eq!((value["operation"].as_str(), value["outcome"].as_str()), (Some("z_adjust"), Some("ok")));
+        assert!(value["time"].is_string());
+
+        let hooks = Hooks::new("stringdriver-2", HookSettings {
+            commands: vec![(HookEvent::Estop, "true".to_string())],
+            ..HookSettings::default()
+        });
+        assert!(hooks.wants(HookEvent::Estop) && !hooks.wants(HookEvent::ParkComplete));
+    }
+
+    #[test]
+    fn test_hook_reads_stdin_and_is_killed_after_timeout() {
+        let out = std::env::temp_dir().join(format!("stringdriver-hook-{}.json", std::process::id()));
+        let command = format!("cat > '{}'", out.display());
+        run_hook(&command, "{\"event\":\"estop\"}\n", Duration::from_secs(5)).unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "{\"event\":\"estop\"}\n");
+        let _ = std::fs::remove_file(&out);
+
+        assert!(run_hook("exit 3", "", Duration::from_secs(5)).is_err());
+        let started = Instant::now();
+        let err = run_hook("sleep 5", "", Duration::from_millis(200)).unwrap_err();
+        assert!(err.contains("killed"), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+}
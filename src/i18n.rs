@@ -0,0 +1,134 @@
+/// GUI translations (LANGUAGE)
+///
+/// A translation file is `<LOCALES_DIR>/<language>.yaml`, mapping the English
+/// text shown in stepper_gui and operations_gui to the venue's language, e.g.
+/// `Connect: Verbinden` or `"{0} bow speed:": "{0} Bogengeschwindigkeit:"`.
+/// The English text is the key, so text without an entry simply stays English
+/// and a file covering only the buttons a technician needs is already useful.
+/// Placeholders {0}, {1}, ... are filled in by `trf` and may be reordered.
+/// Files are read at startup: dropping one in (or editing it) needs a restart,
+/// not a rebuild. STRINGDRIVER_LANG overrides the host's LANGUAGE.
+
+use crate::config_loader::{load_locale_settings, LocaleSettings};
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Environment variable that overrides LANGUAGE (en for English)
+pub const LANG_ENV: &str = "STRINGDRIVER_LANG";
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+/// One language's translations
+#[derive(Debug, Default)]
+pub struct Catalog {
+    pub language: String,
+    entries: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// Parse a translation file's contents (English text -> translation)
+    pub fn parse(language: &str, yaml: &str) -> Result<Self> {
+        let entries: HashMap<String, String> = serde_yaml::from_str::<Option<HashMap<String, String>>>(yaml)
+            .map_err(|e| Error::ConfigInvalid(format!("Translations for '{}': {}", language, e)))?
+            .unwrap_or_default();
+        Ok(Self { language: language.to_string(), entries })
+    }
+
+    pub fn load(language: &str, dir: &Path) -> Result<Self> {
+        let path = dir.join(format!("{}.yaml", language));
+        let yaml = std::fs::read_to_string(&path).map_err(|e| Error::io(format!("Reading translations {}", path.display()), e))?;
+        Self::parse(language, &yaml)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// `text` in this language, or `text` itself without an entry
+    pub fn translate<'a>(&'a self, text: &'a str) -> &'a str {
+        self.entries.get(text).map(String::as_str).filter(|t| !t.is_empty()).unwrap_or(text)
+    }
+}
+
+/// Load the translations for `settings` (STRINGDRIVER_LANG first) and use them from
+/// now on. Only the first call in a process has an effect; returns the catalog in use.
+pub fn init(settings: &LocaleSettings) -> Result<Option<&'static Catalog>> {
+    let language = match std::env::var(LANG_ENV) {
+        Ok(lang) if !lang.trim().is_empty() => Some(lang.trim().to_lowercase()).filter(|lang| lang != "en"),
+        _ => settings.language.clone(),
+    };
+    let Some(language) = language else {
+        return Ok(CATALOG.get());
+    };
+    let catalog = Catalog::load(&language, &settings.dir)?;
+    Ok(Some(CATALOG.get_or_init(|| catalog)))
+}
+
+/// `init` from the host's settings; a missing or broken translation file leaves the GUIs in English
+pub fn init_for_host(hostname: &str) {
+    match load_locale_settings(hostname).and_then(|settings| init(&settings)) {
+        Ok(Some(catalog)) => tracing::info!("GUI language {} ({} translations)", catalog.language, catalog.len()),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("GUI stays in English: {}", e),
+    }
+}
+
+/// `text` in the GUI language
+pub fn tr(text: &str) -> String {
+    match CATALOG.get() {
+        Some(catalog) => catalog.translate(text).to_string(),
+        None => text.to_string(),
+    }
+}
+
+/// `template` in the GUI language with {0}, {1}, ... replaced by `args`
+pub fn trf(template: &str, args: &[&dyn Display]) -> String {
+    fill(&tr(template), args)
+}
+
+fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut text = template.to_string();
+    for (i, arg) in args.iter().enumerate() {
+        text = text.replace(&format!("{{{}}}", i), &arg.to_string());
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_falls_back_to_english() {
+        let catalog = Catalog::parse("de", "Connect: Verbinden\n\"{0} bow speed:\": \"Bogen {0}:\"\nStop all: \"\"\n").unwrap();
+        assert_eq!(catalog.len(), 3);
+        assert_eq!(catalog.translate("Connect"), "Verbinden");
+        assert_eq!(catalog.translate("Disconnect"), "Disconnect");
+        // An empty translation is not used
+        assert_eq!(catalog.translate("Stop all"), "Stop all");
+        assert_eq!(fill(catalog.translate("{0} bow speed:"), &[&"String 1"]), "Bogen String 1:");
+        assert!(Catalog::parse("de", "").unwrap().is_empty());
+        assert!(Catalog::parse("de", "- not a map").is_err());
+    }
+
+    #[test]
+    fn test_shipped_translations_load() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("locales");
+        let catalog = Catalog::load("de", &dir).unwrap();
+        assert_eq!(catalog.translate("Stop all"), "Alle stoppen");
+        assert!(Catalog::load("xx", &dir).is_err());
+    }
+
+    #[test]
+    fn test_placeholders_can_be_reordered() {
+        assert_eq!(fill("{1} <- {0}", &[&"a", &2]), "2 <- a");
+        assert_eq!(fill("no placeholders", &[&"a"]), "no placeholders");
+    }
+}
@@ -0,0 +1,228 @@
+/// Instrument state machine - what the whole instrument is doing, in one place
+///
+/// Operations owns one StateMachine. Every operation asks it before starting
+/// (`can_start`), then moves it through `begin` and `end`; sweeps that wait on
+/// stale audio mark it Paused. Guards keep sweeps from running before the Z
+/// reference is established and confine a Faulted instrument to recovery
+/// operations until the fault is cleared or a recovery succeeds.
+
+use std::time::Instant;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstrumentState {
+    Uninitialized,     // Z reference not established since start
+    Homing(String),    // Homing or calibration operation running
+    Calibrated,        // Homing/calibration just succeeded
+    Idle,              // Calibrated, nothing running
+    Operating(String), // Any other operation running
+    Paused(String),    // Operation holding for fresh audio data
+    Faulted(String),   // Last operation failed; only recovery operations may run
+    Parked,            // Park succeeded, strings clear
+}
+
+impl InstrumentState {
+    /// Short lowercase name for status outputs
+    pub fn name(&self) -> &'static str {
+        match self {
+            InstrumentState::Uninitialized => "uninitialized",
+            InstrumentState::Homing(_) => "homing",
+            InstrumentState::Calibrated => "calibrated",
+            InstrumentState::Idle => "idle",
+            InstrumentState::Operating(_) => "operating",
+            InstrumentState::Paused(_) => "paused",
+            InstrumentState::Faulted(_) => "faulted",
+            InstrumentState::Parked => "parked",
+        }
+    }
+
+    /// The running operation or fault reason, if the state has one
+    pub fn detail(&self) -> Option<&str> {
+        match self {
+            InstrumentState::Homing(s) | InstrumentState::Operating(s) | InstrumentState::Paused(s) | InstrumentState::Faulted(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self.detail() {
+            Some(detail) => format!("{} ({})", self.name(), detail),
+            None => self.name().to_string(),
+        }
+    }
+
+    /// An operation is running (possibly paused)
+    pub fn is_busy(&self) -> bool {
+        matches!(self, InstrumentState::Homing(_) | InstrumentState::Operating(_) | InstrumentState::Paused(_))
+    }
+}
+
+/// Operations that run in the Homing state
+pub const HOMING_OPERATIONS: &[&str] = &["z_calibrate", "z_home", "x_home"];
+/// Operations that establish the Z reference when they succeed
+pub const CALIBRATING_OPERATIONS: &[&str] = &["z_calibrate", "z_home"];
+/// Operations refused until the Z reference is established
+pub const NEEDS_CALIBRATION: &[&str] = &["right_left_move", "left_right_move", "ping_pong_move", "height_calibrate"];
+/// Operations allowed while Faulted
+pub const FAULT_RECOVERY_OPERATIONS: &[&str] = &["bump_check", "z_calibrate", "z_home", "x_home", "park"];
+
+#[derive(Debug)]
+pub struct StateMachine {
+    state: InstrumentState,
+    calibrated: bool,
+    since: Instant,
+}
+
+impl Default for StateMachine {
+    fn default() -> Self {
+        Self { state: InstrumentState::Uninitialized, calibrated: false, since: Instant::now() }
+    }
+}
+
+impl StateMachine {
+    pub fn state(&self) -> &InstrumentState {
+        &self.state
+    }
+
+    pub fn calibrated(&self) -> bool {
+        self.calibrated
+    }
+
+    /// When the current state was entered
+    pub fn since(&self) -> Instant {
+        self.since
+    }
+
+    /// Whether `operation` may start now; Err says why not
+    pub fn can_start(&self, operation: &str) -> Result<(), String> {
+        match &self.state {
+            state if state.is_busy() => Err(format!("instrument is {}", state.describe())),
+            InstrumentState::Faulted(reason) if !FAULT_RECOVERY_OPERATIONS.contains(&operation) => Err(format!(
+                "instrument is faulted ({}) - run {} or clear the fault",
+                reason,
+                FAULT_RECOVERY_OPERATIONS.join(" / ")
+            )),
+            _ if NEEDS_CALIBRATION.contains(&operation) && !self.calibrated => {
+                Err(format!("instrument is not calibrated - run {} first", CALIBRATING_OPERATIONS.join(" or ")))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn begin(&mut self, operation: &str) -> Result<(), String> {
+        self.can_start(operation)?;
+        self.enter_running(operation);
+        Ok(())
+    }
+
+    /// The running operation is holding (e.g. for fresh audio)
+    pub fn pause(&mut self) {
+        if let InstrumentState::Operating(op) | InstrumentState::Homing(op) = &self.state {
+            let op = op.clone();
+            self.set(InstrumentState::Paused(op));
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if let InstrumentState::Paused(op) = &self.state {
+            let op = op.clone();
+            self.enter_running(&op);
+        }
+    }
+
+    fn enter_running(&mut self, operation: &str) {
+        if HOMING_OPERATIONS.contains(&operation) {
+            self.set(InstrumentState::Homing(operation.to_string()));
+        } else {
+            self.set(InstrumentState::Operating(operation.to_string()));
+        }
+    }
+
+    /// `operation` finished; Err carries the failure reason
+    pub fn end(&mut self, operation: &str, outcome: Result<(), String>) {
+        match outcome {
+            Err(reason) => {
+                if CALIBRATING_OPERATIONS.contains(&operation) {
+                    self.calibrated = false;
+                }
+                self.set(InstrumentState::Faulted(format!("{}: {}", operation, reason)));
+            }
+            Ok(()) if CALIBRATING_OPERATIONS.contains(&operation) => {
+                self.calibrated = true;
+                self.set(InstrumentState::Calibrated);
+            }
+            Ok(()) if operation == "park" => self.set(InstrumentState::Parked),
+            Ok(()) => {
+                let resting = self.resting();
+                self.set(resting);
+            }
+        }
+    }
+
+    /// The running operation was stopped (BREAK) - not a fault, but nothing was established
+    pub fn stop(&mut self) {
+        if self.state.is_busy() {
+            let resting = self.resting();
+            self.set(resting);
+        }
+    }
+
+    /// Leave Faulted without running a recovery; false if not faulted
+    pub fn clear_fault(&mut self) -> bool {
+        if !matches!(self.state, InstrumentState::Faulted(_)) {
+            return false;
+        }
+        let resting = self.resting();
+        self.set(resting);
+        true
+    }
+
+    fn resting(&self) -> InstrumentState {
+        if self.calibrated {
+            InstrumentState::Idle
+        } else {
+            InstrumentState::Uninitialized
+        }
+    }
+
+    fn set(&mut self, next: InstrumentState) {
+        if next != self.state {
+            tracing::info!("Instrument state: {} -> {}", self.state.describe(), next.describe());
+            self.state = next;
+            self.since = Instant::now();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transitions_are_guarded() {
+        let mut machine = StateMachine::default();
+        assert!(machine.can_start("ping_pong_move").is_err()); // Not calibrated
+        machine.begin("z_calibrate").unwrap();
+        assert_eq!(machine.state(), &InstrumentState::Homing("z_calibrate".to_string()));
+        assert!(machine.can_start("bump_check").is_err()); // Busy
+        machine.end("z_calibrate", Ok(()));
+        assert_eq!(machine.state(), &InstrumentState::Calibrated);
+
+        machine.begin("right_left_move").unwrap();
+        machine.pause();
+        assert_eq!(machine.state().name(), "paused");
+        machine.resume();
+        machine.stop();
+        assert_eq!(machine.state(), &InstrumentState::Idle);
+        machine.begin("right_left_move").unwrap();
+        machine.end("right_left_move", Err("X move not confirmed".to_string()));
+        assert_eq!(machine.state().name(), "faulted");
+        assert!(machine.can_start("z_adjust").is_err());
+        assert!(machine.can_start("park").is_ok());
+
+        assert!(machine.clear_fault());
+        assert_eq!(machine.state(), &InstrumentState::Idle);
+        machine.begin("park").unwrap();
+        machine.end("park", Ok(()));
+        assert_eq!(machine.state(), &InstrumentState::Parked);
+    }
+}
@@ -0,0 +1,570 @@
+/// Import of surfer.py's legacy settings into a string_driver.yaml host block
+///
+/// surfer.py kept its tuned parameters in a configparser .ini file or a pickled
+/// dict / config object. Both are read here into flat `name -> value` pairs
+/// (nested dicts and object attributes become dotted names such as `ins.z_rest`),
+/// the names are mapped onto the keys config_loader reads, and setup_wizard's
+/// `import-legacy` subcommand merges the result into the host block. Names with
+/// no equivalent are reported rather than guessed at.
+
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A setting as surfer.py stored it
+#[derive(Debug, Clone, PartialEq)]
+pub enum LegacyValue {
+    None,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    List(Vec<LegacyValue>),               // Lists, tuples and sets
+    Dict(Vec<(String, LegacyValue)>),     // Dicts and pickled objects' attributes
+}
+
+impl std::fmt::Display for LegacyValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LegacyValue::None => write!(f, "None"),
+            LegacyValue::Bool(b) => write!(f, "{}", if *b { "True" } else { "False" }),
+            LegacyValue::Int(n) => write!(f, "{}", n),
+            LegacyValue::Float(x) => write!(f, "{}", x),
+            LegacyValue::Str(s) => write!(f, "'{}'", s),
+            LegacyValue::List(items) => {
+                write!(f, "[{}]", items.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", "))
+            }
+            LegacyValue::Dict(entries) => write!(f, "{{{} entries}}", entries.len()),
+        }
+    }
+}
+
+/// What a string_driver.yaml key holds
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Int,
+    Float,
+    Bool,
+    Str,
+}
+
+/// surfer.py names (lower case, last part of a dotted name) and the YAML key each becomes
+const KEY_MAP: &[(&str, &str, Kind)] = &[
+    ("up_step", "Z_UP_STEP", Kind::Int),
+    ("z_up_step", "Z_UP_STEP", Kind::Int),
+    ("down_step", "Z_DOWN_STEP", Kind::Int),
+    ("z_down_step", "Z_DOWN_STEP", Kind::Int),
+    ("z_rest", "Z_REST", Kind::Float),
+    ("x_rest", "X_REST", Kind::Float),
+    ("tune_rest", "TUNE_REST", Kind::Float),
+    ("lap_rest", "LAP_REST", Kind::Float),
+    ("adjustment_level", "ADJUSTMENT_LEVEL", Kind::Int),
+    ("retry_threshold", "RETRY_THRESHOLD", Kind::Int),
+    ("delta_threshold", "DELTA_THRESHOLD", Kind::Int),
+    ("z_variance_threshold", "Z_VARIANCE_THRESHOLD", Kind::Int),
+    ("x_start", "X_START", Kind::Int),
+    ("x_finish", "X_FINISH", Kind::Int),
+    ("x_step", "X_STEP", Kind::Int),
+    ("bump_check", "BUMP_CHECK_ENABLE", Kind::Bool),
+    ("bump_check_enable", "BUMP_CHECK_ENABLE", Kind::Bool),
+    ("voice_floor", "VOICE_FLOOR", Kind::Float),
+    ("string_num", "STRING_NUM", Kind::Int),
+    ("num_strings", "STRING_NUM", Kind::Int),
+    ("x_max_pos", "X_MAX_POS", Kind::Int),
+    ("port", "ARD_PORT", Kind::Str),
+    ("ard_port", "ARD_PORT", Kind::Str),
+    ("arduino_port", "ARD_PORT", Kind::Str),
+    ("num_steppers", "ARD_NUM_STEPPERS", Kind::Int),
+    ("x_step_index", "X_STEP_INDEX", Kind::Int),
+    ("z_first_index", "Z_FIRST_INDEX", Kind::Int),
+    ("tuner_first_index", "TUNER_FIRST_INDEX", Kind::Int),
+];
+
+/// Legacy settings translated for a host block
+#[derive(Debug, Clone, Default)]
+pub struct LegacyImport {
+    pub settings: serde_yaml::Mapping,  // YAML key -> value; a later legacy name for the same key wins
+    pub mapped: Vec<(String, String)>,  // (legacy name, YAML key)
+    pub unmapped: Vec<String>,          // Legacy names left out, with the reason
+}
+
+/// Read an .ini or pickle file (by extension, else by its first bytes) into flat pairs
+pub fn read_legacy_file(path: &Path) -> Result<Vec<(String, LegacyValue)>> {
+    let data = std::fs::read(path).map_err(|e| Error::io(format!("Cannot read legacy settings {}", path.display()), e))?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    let pickled = match extension.as_str() {
+        "ini" | "cfg" | "conf" => false,
+        "pkl" | "pickle" | "p" => true,
+        _ => data.first() == Some(&0x80) || std::str::from_utf8(&data).is_err() || data.starts_with(b"(d") || data.starts_with(b"c"),
+    };
+    if pickled {
+        parse_pickle(&data)
+    } else {
+        Ok(parse_ini(&String::from_utf8_lossy(&data)))
+    }
+}
+
+/// configparser text: `[section]` headers, `name = value` or `name: value` lines, `#`/`;`
+/// comments. Values are read as Python literals where they look like one.
+pub fn parse_ini(text: &str) -> Vec<(String, LegacyValue)> {
+    let mut section = String::new();
+    let mut pairs = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_string();
+            continue;
+        }
+        let Some(split) = line.find(['=', ':']) else { continue };
+        let (name, value) = (line[..split].trim(), line[split + 1..].trim());
+        let name = if section.is_empty() || section.eq_ignore_ascii_case("DEFAULT") {
+            name.to_string()
+        } else {
+            format!("{}.{}", section, name)
+        };
+        pairs.push((name, ini_value(value)));
+    }
+    pairs
+}
+
+fn ini_value(text: &str) -> LegacyValue {
+    let unquoted = text.strip_prefix('\'').and_then(|t| t.strip_suffix('\''))
+        .or_else(|| text.strip_prefix('"').and_then(|t| t.strip_suffix('"')));
+    if let Some(s) = unquoted {
+        return LegacyValue::Str(s.to_string());
+    }
+    if let Some(inner) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']'))
+        .or_else(|| text.strip_prefix('(').and_then(|t| t.strip_suffix(')')))
+    {
+        return LegacyValue::List(inner.split(',').map(str::trim).filter(|s| !s.is_empty()).map(ini_value).collect());
+    }
+    match text.to_lowercase().as_str() {
+        "none" => return LegacyValue::None,
+        "true" | "yes" | "on" => return LegacyValue::Bool(true),
+        "false" | "no" | "off" => return LegacyValue::Bool(false),
+        _ => {}
+    }
+    if let Ok(n) = text.parse::<i64>() {
+        LegacyValue::Int(n)
+    } else if let Ok(x) = text.parse::<f64>() {
+        LegacyValue::Float(x)
+    } else {
+        LegacyValue::Str(text.to_string())
+    }
+}
+
+/// Pickle stack entries
+#[derive(Debug, Clone)]
+enum Item {
+    Value(LegacyValue),
+    Mark,
+    Class(String), // GLOBAL / STACK_GLOBAL reference, instantiated as an attribute dict
+}
+
+/// Decode a pickle (protocols 0 to 5) of plain data and config objects. Objects come
+/// back as the dict of their attributes. A memoized container is read as it was when
+/// first stored, which is all surfer.py's flat settings need.
+pub fn parse_pickle(data: &[u8]) -> Result<Vec<(String, LegacyValue)>> {
+    let mut reader = PickleReader { data, pos: 0 };
+    let mut stack: Vec<Item> = Vec::new();
+    let mut memo: HashMap<u64, Item> = HashMap::new();
+    let invalid = |pos: usize, msg: &str| Error::ConfigInvalid(format!("Legacy pickle at byte {}: {}", pos, msg));
+
+    fn pop_value(stack: &mut Vec<Item>, pos: usize) -> Result<LegacyValue> {
+        match stack.pop() {
+            Some(Item::Value(v)) => Ok(v),
+            Some(Item::Class(name)) => Ok(LegacyValue::Str(name)),
+            _ => Err(Error::ConfigInvalid(format!("Legacy pickle at byte {}: value expected on the stack", pos))),
+        }
+    }
+    fn pop_mark(stack: &mut Vec<Item>, pos: usize) -> Result<Vec<LegacyValue>> {
+        let mark = stack.iter().rposition(|item| matches!(item, Item::Mark))
+            .ok_or_else(|| Error::ConfigInvalid(format!("Legacy pickle at byte {}: MARK missing", pos)))?;
+        let items = stack.split_off(mark + 1);
+        stack.pop();
+        Ok(items.into_iter().map(|item| match item {
+            Item::Value(v) => v,
+            Item::Class(name) => LegacyValue::Str(name),
+            Item::Mark => LegacyValue::None,
+        }).collect())
+    }
+    fn set_items(stack: &mut [Item], items: Vec<LegacyValue>, pos: usize) -> Result<()> {
+        let Some(Item::Value(LegacyValue::Dict(dict))) = stack.last_mut() else {
+            return Err(Error::ConfigInvalid(format!("Legacy pickle at byte {}: SETITEM without a dict", pos)));
+        };
+        let mut items = items.into_iter();
+        while let (Some(key), Some(value)) = (items.next(), items.next()) {
+            let key = match key {
+                LegacyValue::Str(s) => s,
+                other => other.to_string(),
+            };
+            dict.push((key, value));
+        }
+        Ok(())
+    }
+    fn append(stack: &mut [Item], items: Vec<LegacyValue>, pos: usize) -> Result<()> {
+        match stack.last_mut() {
+            Some(Item::Value(LegacyValue::List(list))) => {
+                list.extend(items);
+                Ok(())
+            }
+            _ => Err(Error::ConfigInvalid(format!("Legacy pickle at byte {}: APPEND without a list", pos))),
+        }
+    }
+
+    loop {
+        let at = reader.pos;
+        let op = reader.byte()?;
+        match op {
+            0x80 => { reader.take(1)?; } // PROTO
+            0x95 => { reader.take(8)?; } // FRAME
+            b'.' => break,
+            b'(' => stack.push(Item::Mark),
+            b'N' => stack.push(Item::Value(LegacyValue::None)),
+            0x88 => stack.push(Item::Value(LegacyValue::Bool(true))),
+            0x89 => stack.push(Item::Value(LegacyValue::Bool(false))),
+            b'I' => {
+                let line = reader.line()?;
+                let value = match line.as_str() {
+                    "01" => LegacyValue::Bool(true),
+                    "00" => LegacyValue::Bool(false),
+                    n => LegacyValue::Int(n.parse().map_err(|_| invalid(at, "bad INT"))?),
+                };
+                stack.push(Item::Value(value));
+            }
+            b'L' => {
+                let line = reader.line()?;
+                let n = line.trim_end_matches('L').parse().map_err(|_| invalid(at, "LONG out of range"))?;
+                stack.push(Item::Value(LegacyValue::Int(n)));
+            }
+            b'F' => {
+                let x = reader.line()?.parse().map_err(|_| invalid(at, "bad FLOAT"))?;
+                stack.push(Item::Value(LegacyValue::Float(x)));
+            }
+            b'J' => {
+                let n = i32::from_le_bytes(reader.take(4)?.try_into().unwrap());
+                stack.push(Item::Value(LegacyValue::Int(n as i64)));
+            }
+            b'K' => {
+                let n = reader.byte()?;
+                stack.push(Item::Value(LegacyValue::Int(n as i64)));
+            }
+            b'M' => {
+                let n = u16::from_le_bytes(reader.take(2)?.try_into().unwrap());
+                stack.push(Item::Value(LegacyValue::Int(n as i64)));
+            }
+            0x8a => {
+                // LONG1: little-endian two's complement
+                let len = reader.byte()? as usize;
+                let bytes = reader.take(len)?;
+                if len > 8 {
+                    return Err(invalid(at, "LONG1 out of range"));
+                }
+                let negative = bytes.last().is_some_and(|b| b & 0x80 != 0);
+                let mut buf = [if negative { 0xff } else { 0 }; 8];
+                buf[..len].copy_from_slice(bytes);
+                stack.push(Item::Value(LegacyValue::Int(i64::from_le_bytes(buf))));
+            }
+            b'G' => {
+                let x = f64::from_be_bytes(reader.take(8)?.try_into().unwrap());
+                stack.push(Item::Value(LegacyValue::Float(x)));
+            }
+            b'S' => {
+                let line = reader.line()?;
+                stack.push(Item::Value(LegacyValue::Str(unquote_repr(&line))));
+            }
+            b'V' => {
+                let line = reader.line()?;
+                stack.push(Item::Value(LegacyValue::Str(line)));
+            }
+            b'X' | b'T' | b'B' => {
+                let len = u32::from_le_bytes(reader.take(4)?.try_into().unwrap()) as usize;
+                let s = String::from_utf8_lossy(reader.take(len)?).into_owned();
+                stack.push(Item::Value(LegacyValue::Str(s)));
+            }
+            0x8c | b'U' | b'C' => {
+                let len = reader.byte()? as usize;
+                let s = String::from_utf8_lossy(reader.take(len)?).into_owned();
+                stack.push(Item::Value(LegacyValue::Str(s)));
+            }
+            0x8d | 0x8e => {
+                let len = u64::from_le_bytes(reader.take(8)?.try_into().unwrap()) as usize;
+                let s = String::from_utf8_lossy(reader.take(len)?).into_owned();
+                stack.push(Item::Value(LegacyValue::Str(s)));
+            }
+            b'}' => stack.push(Item::Value(LegacyValue::Dict(Vec::new()))),
+            b']' | b')' | 0x8f => stack.push(Item::Value(LegacyValue::List(Vec::new()))),
+            b'd' => {
+                let items = pop_mark(&mut stack, at)?;
+                stack.push(Item::Value(LegacyValue::Dict(Vec::new())));
+                set_items(&mut stack, items, at)?;
+            }
+            b'l' | b't' | 0x91 => {
+                let items = pop_mark(&mut stack, at)?;
+                stack.push(Item::Value(LegacyValue::List(items)));
+            }
+            0x85..=0x87 => {
+                let count = (op - 0x84) as usize;
+                if stack.len() < count {
+                    return Err(invalid(at, "TUPLE past the bottom of the stack"));
+                }
+                let mut items = Vec::with_capacity(count);
+                for _ in 0..count {
+                    items.push(pop_value(&mut stack, at)?);
+                }
+                items.reverse();
+                stack.push(Item::Value(LegacyValue::List(items)));
+            }
+            b's' => {
+                let value = pop_value(&mut stack, at)?;
+                let key = pop_value(&mut stack, at)?;
+                set_items(&mut stack, vec![key, value], at)?;
+            }
+            b'u' => {
+                let items = pop_mark(&mut stack, at)?;
+                set_items(&mut stack, items, at)?;
+            }
+            b'a' => {
+                let value = pop_value(&mut stack, at)?;
+                append(&mut stack, vec![value], at)?;
+            }
+            b'e' | 0x90 => {
+                let items = pop_mark(&mut stack, at)?;
+                append(&mut stack, items, at)?;
+            }
+            b'p' => {
+                let id = reader.line()?.parse().map_err(|_| invalid(at, "bad PUT"))?;
+                memo.insert(id, stack.last().cloned().ok_or_else(|| invalid(at, "PUT on an empty stack"))?);
+            }
+            b'q' | b'r' | 0x94 => {
+                let id = match op {
+                    b'q' => reader.byte()? as u64,
+                    b'r' => u32::from_le_bytes(reader.take(4)?.try_into().unwrap()) as u64,
+                    _ => memo.len() as u64,
+                };
+                memo.insert(id, stack.last().cloned().ok_or_else(|| invalid(at, "memo store on an empty stack"))?);
+            }
+            b'g' | b'h' | b'j' => {
+                let id = match op {
+                    b'g' => reader.line()?.parse().map_err(|_| invalid(at, "bad GET"))?,
+                    b'h' => reader.byte()? as u64,
+                    _ => u32::from_le_bytes(reader.take(4)?.try_into().unwrap()) as u64,
+                };
+                stack.push(memo.get(&id).cloned().ok_or_else(|| invalid(at, "memo entry missing"))?);
+            }
+            b'c' => {
+                let module = reader.line()?;
+                let name = reader.line()?;
+                stack.push(Item::Class(format!("{}.{}", module, name)));
+            }
+            0x93 => {
+                let name = pop_value(&mut stack, at)?;
+                let module = pop_value(&mut stack, at)?;
+                stack.push(Item::Class(format!("{}.{}", module, name).replace('\'', "")));
+            }
+            b'i' => {
+                // INST: class on the next two lines, constructor args since MARK
+                reader.line()?;
+                reader.line()?;
+                pop_mark(&mut stack, at)?;
+                stack.push(Item::Value(LegacyValue::Dict(Vec::new())));
+            }
+            b'o' => {
+                pop_mark(&mut stack, at)?;
+                stack.push(Item::Value(LegacyValue::Dict(Vec::new())));
+            }
+            b'R' | 0x81 | 0x92 => {
+                // REDUCE / NEWOBJ / NEWOBJ_EX: the object's state arrives with BUILD
+                let args = if op == 0x92 { 2 } else { 1 };
+                for _ in 0..args + 1 {
+                    stack.pop().ok_or_else(|| invalid(at, "object construction past the bottom of the stack"))?;
+                }
+                stack.push(Item::Value(LegacyValue::Dict(Vec::new())));
+            }
+            b'b' => {
+                let state = match pop_value(&mut stack, at)? {
+                    // (dict, slots) state: the dict holds the attributes
+                    LegacyValue::List(parts) => parts.into_iter().find(|p| matches!(p, LegacyValue::Dict(_))),
+                    state => Some(state),
+                };
+                if let (Some(LegacyValue::Dict(attributes)), Some(Item::Value(LegacyValue::Dict(object)))) = (state, stack.last_mut()) {
+                    object.extend(attributes);
+                }
+            }
+            b'0' => {
+                stack.pop();
+            }
+            b'1' => {
+                pop_mark(&mut stack, at)?;
+            }
+            b'2' => {
+                let top = stack.last().cloned().ok_or_else(|| invalid(at, "DUP on an empty stack"))?;
+                stack.push(top);
+            }
+            other => return Err(invalid(at, &format!("unsupported opcode 0x{:02x}", other))),
+        }
+    }
+    let root = pop_value(&mut stack, reader.pos)?;
+    let mut pairs = Vec::new();
+    flatten(String::new(), root, &mut pairs);
+    Ok(pairs)
+}
+
+struct PickleReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> PickleReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.data.len())
+            .ok_or_else(|| Error::ConfigInvalid(format!("Legacy pickle truncated at byte {}", self.pos)))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn line(&mut self) -> Result<String> {
+        let rest = &self.data[self.pos..];
+        let len = rest.iter().position(|&b| b == b'\n')
+            .ok_or_else(|| Error::ConfigInvalid(format!("Legacy pickle truncated at byte {}", self.pos)))?;
+        let line = String::from_utf8_lossy(&rest[..len]).into_owned();
+        self.pos += len + 1;
+        Ok(line)
+    }
+}
+
+/// Protocol 0 STRING argument: a quoted Python repr
+fn unquote_repr(text: &str) -> String {
+    let inner = text.get(1..text.len().saturating_sub(1)).unwrap_or("");
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                out.extend(u8::from_str_radix(&hex, 16).ok().map(char::from));
+            }
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+/// Nested dicts (and objects) become dotted names
+fn flatten(prefix: String, value: LegacyValue, pairs: &mut Vec<(String, LegacyValue)>) {
+    match value {
+        LegacyValue::Dict(entries) => {
+            for (key, value) in entries {
+                let name = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+                flatten(name, value, pairs);
+            }
+        }
+        value => pairs.push((prefix, value)),
+    }
+}
+
+fn convert(value: &LegacyValue, kind: Kind) -> Option<serde_yaml::Value> {
+    match (kind, value) {
+        (Kind::Int, LegacyValue::Int(n)) => Some(serde_yaml::Value::from(*n)),
+        (Kind::Int, LegacyValue::Float(x)) if x.fract() == 0.0 => Some(serde_yaml::Value::from(*x as i64)),
+        (Kind::Float, LegacyValue::Int(n)) => Some(serde_yaml::Value::from(*n as f64)),
+        (Kind::Float, LegacyValue::Float(x)) => Some(serde_yaml::Value::from(*x)),
+        (Kind::Bool, LegacyValue::Bool(b)) => Some(serde_yaml::Value::from(*b)),
+        (Kind::Bool, LegacyValue::Int(n @ (0 | 1))) => Some(serde_yaml::Value::from(*n == 1)),
+        (Kind::Str, LegacyValue::Str(s)) => Some(serde_yaml::Value::from(s.as_str())),
+        _ => None,
+    }
+}
+
+/// Map legacy names onto string_driver.yaml keys
+pub fn map_legacy(pairs: &[(String, LegacyValue)]) -> LegacyImport {
+    let mut import = LegacyImport::default();
+    for (name, value) in pairs {
+        let short = name.rsplit('.').next().unwrap_or(name).to_lowercase();
+        let Some(&(_, key, kind)) = KEY_MAP.iter().find(|(legacy, _, _)| *legacy == short) else {
+            import.unmapped.push(format!("{} = {} (no string_driver.yaml equivalent)", name, value));
+            continue;
+        };
+        match convert(value, kind) {
+            Some(converted) => {
+                import.settings.insert(serde_yaml::Value::from(key), converted);
+                import.mapped.push((name.clone(), key.to_string()));
+            }
+            None => import.unmapped.push(format!("{} = {} (not a valid {} value)", name, value, key)),
+        }
+    }
+    import
+}
+
+/// Merge the imported settings into a host block; returns one line per key added or changed
+pub fn merge_into(section: &mut serde_yaml::Mapping, import: &LegacyImport) -> Vec<String> {
+    let show = |v: &serde_yaml::Value| serde_yaml::to_string(v).map(|s| s.trim().to_string()).unwrap_or_default();
+    let mut changes = Vec::new();
+    for (key, value) in &import.settings {
+        let name = key.as_str().unwrap_or_default();
+        match section.insert(key.clone(), value.clone()) {
+            None => changes.push(format!("{}: {} (new)", name, show(value))),
+            Some(old) if old != *value => changes.push(format!("{}: {} -> {}", name, show(&old), show(value))),
+            Some(_) => {}
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ini_and_pickles_map_to_yaml_keys() {
+        let ini = "[surfer]\ndown_step = -2\nz_rest: 0.5\n; tuned 2019\nbump_check = yes\nwindow = big\nx_start = 1e3\n";
+        let import = map_legacy(&parse_ini(ini));
+        assert_eq!(import.settings.get("Z_DOWN_STEP"), Some(&serde_yaml::Value::from(-2)));
+        assert_eq!(import.settings.get("Z_REST"), Some(&serde_yaml::Value::from(0.5)));
+        assert_eq!(import.settings.get("BUMP_CHECK_ENABLE"), Some(&serde_yaml::Value::from(true)));
+        assert_eq!(import.settings.get("X_START"), Some(&serde_yaml::Value::from(1000)));
+        assert_eq!(import.unmapped, vec!["surfer.window = 'big' (no string_driver.yaml equivalent)".to_string()]);
+
+        // pickle.dumps({...}, protocol=2)
+        let dict = b"\x80\x02}q\x00(X\t\x00\x00\x00down_stepq\x01J\xfe\xff\xff\xffX\x06\x00\x00\x00z_restq\x02G?\xe0\x00\x00\x00\x00\x00\x00X\x07\x00\x00\x00x_startq\x03J\xa0\x86\x01\x00X\x04\x00\x00\x00lapsq\x04]q\x05(K\x01K\x02eX\x06\x00\x00\x00colourq\x06X\x03\x00\x00\x00redq\x07X\n\x00\x00\x00bump_checkq\x08\x88X\x06\x00\x00\x00x_restq\tNu.";
+        let pairs = parse_pickle(dict).unwrap();
+        assert_eq!(pairs[0], ("down_step".to_string(), LegacyValue::Int(-2)));
+        assert_eq!(pairs[3], ("laps".to_string(), LegacyValue::List(vec![LegacyValue::Int(1), LegacyValue::Int(2)])));
+        let import = map_legacy(&pairs);
+        assert_eq!(import.settings.get("X_START"), Some(&serde_yaml::Value::from(100000)));
+        assert!(import.unmapped.iter().any(|u| u.starts_with("x_rest = None (not a valid X_REST value)")));
+
+        // A surfer.Config object with an Ins attribute, protocols 0 and 4
+        let object_v0 = b"ccopy_reg\n_reconstructor\np0\n(csurfer\nConfig\np1\nc__builtin__\nobject\np2\nNtp3\nRp4\n(dp5\nVins\np6\ng0\n(csurfer\nIns\np7\ng2\nNtp8\nRp9\n(dp10\nVz_rest\np11\nF0.5\nsVdown_step\np12\nI-2\nsVbump_check\np13\nI01\nsbsVport\np14\nV/dev/ttyACM0\np15\nsVwindow\np16\nVbig\np17\nsb.";
+        let object_v4 = b"\x80\x04\x95\x8b\x00\x00\x00\x00\x00\x00\x00\x8c\x06surfer\x94\x8c\x06Config\x94\x93\x94)\x81\x94}\x94(\x8c\x03ins\x94h\x00\x8c\x03Ins\x94\x93\x94)\x81\x94}\x94(\x8c\x06z_rest\x94G?\xe0\x00\x00\x00\x00\x00\x00\x8c\tdown_step\x94J\xfe\xff\xff\xff\x8c\nbump_check\x94\x88ub\x8c\x04port\x94\x8c\x0c/dev/ttyACM0\x94\x8c\x06window\x94\x8c\x03big\x94ub.";
+        for data in [&object_v0[..], &object_v4[..]] {
+            let pairs = parse_pickle(data).unwrap();
+            let names: Vec<&str> = pairs.iter().map(|(name, _)| name.as_str()).collect();
+            assert_eq!(names, vec!["ins.z_rest", "ins.down_step", "ins.bump_check", "port", "window"]);
+            let import = map_legacy(&pairs);
+            assert_eq!(import.mapped.len(), 4);
+            assert_eq!(import.settings.get("ARD_PORT"), Some(&serde_yaml::Value::from("/dev/ttyACM0")));
+        }
+
+        let mut section = serde_yaml::Mapping::new();
+        section.insert(serde_yaml::Value::from("Z_REST"), serde_yaml::Value::from(0.5));
+        section.insert(serde_yaml::Value::from("ARD_PORT"), serde_yaml::Value::from("/dev/ttyUSB0"));
+        let changes = merge_into(&mut section, &map_legacy(&parse_pickle(object_v4).unwrap()));
+        assert_eq!(changes, vec!["Z_DOWN_STEP: -2 (new)", "BUMP_CHECK_ENABLE: true (new)", "ARD_PORT: /dev/ttyUSB0 -> /dev/ttyACM0"]);
+    }
+}
@@ -0,0 +1,63 @@
+/// Core String Driver library: config, operations and the stepper / audio plumbing
+///
+/// Everything here builds without eframe/egui (`--no-default-features`), for a
+/// headless daemon or an armv7 cross-build. Every binary in src/gui/ and
+/// src/daemon/ uses these modules through `use stringdriver::...`, so they all
+/// share one set of types; the GUI binaries need the `gui` feature (on by
+/// default), behind which health and role keep their egui rendering.
+
+pub mod access;
+pub mod alerting;
+pub mod arbitration;
+pub mod arduino_connection;
+pub mod audio_sim;
+pub mod audio_trigger;
+pub mod audmon_client;
+pub mod audit_log;
+pub mod bow_drive;
+pub mod bump_log;
+pub mod build_info;
+pub mod command_inbox;
+pub mod config_loader;
+pub mod dampers;
+pub mod effective_config;
+pub mod error;
+#[cfg(feature = "fault-injection")]
+pub mod fault_injection;
+pub mod get_results;
+pub mod gpio;
+pub mod gui_state;
+pub mod health;
+pub mod hooks;
+pub mod i18n;
+pub mod height_map;
+pub mod instrument_state;
+pub mod legacy_config;
+pub mod lifecycle;
+pub mod logging;
+pub mod loopback_serial;
+pub mod machine_state_logger;
+pub mod operations;
+pub mod partials_buffer;
+pub mod plugins;
+pub mod position_model;
+pub mod profile;
+pub mod profiling;
+pub mod readiness;
+pub mod role;
+pub mod scene;
+pub mod score;
+pub mod sensor_health;
+pub mod shared_state;
+pub mod shutdown;
+pub mod stage_view;
+pub mod state_diff;
+pub mod status_snapshot;
+pub mod stepper_core;
+pub mod stepper_link;
+pub mod systemd;
+pub mod tension;
+pub mod timeline;
+pub mod topology;
+pub mod transport;
+pub mod z_controller;
@@ -0,0 +1,147 @@
+/// Background threads owned by a GUI, stopped when it goes away
+///
+/// operations_gui runs loops behind its window: the partials-slot reader, the
+/// stepper_gui position/shared-state subscriptions and status poller, and the
+/// 1 Hz machine-state logger. Each is spawned through BackgroundThreads and
+/// watches the shared ShutdownFlag between iterations (sleeping with
+/// `ShutdownFlag::sleep`, which wakes early). `shutdown` - on window exit and on
+/// Drop - raises the flag and joins the threads, so closing the window or
+/// rebuilding the GUI leaves nothing running.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How often a sleeping thread looks at the flag
+const SHUTDOWN_POLL: Duration = Duration::from_millis(20);
+/// How long Drop waits for the threads before leaving stragglers detached
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownFlag(Arc<AtomicBool>);
+
+impl ShutdownFlag {
+    pub fn is_set(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn set(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Sleep for `duration`, cut short by shutdown; false once shutdown is requested
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let deadline = Instant::now() + duration;
+        while !self.is_set() {
+            let now = Instant::now();
+            if now >= deadline {
+                return true;
+            }
+            thread::sleep(SHUTDOWN_POLL.min(deadline - now));
+        }
+        false
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BackgroundThreads {
+    shutdown_flag: ShutdownFlag,
+    threads: Vec<(String, JoinHandle<()>)>,
+}
+
+impl BackgroundThreads {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn shutdown_flag(&self) -> ShutdownFlag {
+        self.shutdown_flag.clone()
+    }
+
+    /// Start a named thread; `body` gets the flag and should return once it is set
+    pub fn spawn(&mut self, name: &str, body: impl FnOnce(ShutdownFlag) + Send + 'static) {
+        let flag = self.shutdown_flag.clone();
+        match thread::Builder::new().name(name.to_string()).spawn(move || body(flag)) {
+            Ok(handle) => self.threads.push((name.to_string(), handle)),
+            Err(e) => tracing::error!("Failed to start {} thread: {}", name, e),
+        }
+    }
+
+    /// Threads that have not returned yet
+    pub fn running(&self) -> Vec<&str> {
+        self.threads.iter().filter(|(_, h)| !h.is_finished()).map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Raise the flag and join every thread that returns within `timeout`;
+    /// the names of any still running are returned and they are left detached
+    pub fn shutdown(&mut self, timeout: Duration) -> Vec<String> {
+        self.shutdown_flag.set();
+        let deadline = Instant::now() + timeout;
+        while self.threads.iter().any(|(_, h)| !h.is_finished()) && Instant::now() < deadline {
+            thread::sleep(SHUTDOWN_POLL);
+        }
+        let mut stuck = Vec::new();
+        for (name, handle) in self.threads.drain(..) {
+            if !handle.is_finished() {
+                stuck.push(name);
+            } else if handle.join().is_err() {
+                tracing::warn!("Background thread {} panicked", name);
+            }
+        }
+        stuck
+    }
+}
+
+impl Drop for BackgroundThreads {
+    fn drop(&mut self) {
+        if self.threads.is_empty() {
+            return;
+        }
+        let stuck = self.shutdown(SHUTDOWN_TIMEOUT);
+        if !stuck.is_empty() {
+            tracing::warn!("Background threads still running after shutdown: {}", stuck.join(", "));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_threads_stop_on_shutdown_and_drop() {
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let mut threads = BackgroundThreads::new();
+        let counter = Arc::clone(&ticks);
+        threads.spawn("ticker", move |shutdown| {
+            while shutdown.sleep(Duration::from_millis(5)) {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        // A long sleep is cut short rather than holding up shutdown
+        threads.spawn("sleeper", |shutdown| {
+            shutdown.sleep(Duration::from_secs(3600));
+        });
+        assert_eq!(threads.running(), vec!["ticker", "sleeper"]);
+        thread::sleep(Duration::from_millis(30));
+
+        let started = Instant::now();
+        assert!(threads.shutdown(Duration::from_secs(2)).is_empty());
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(threads.running().is_empty());
+        let stopped_at = ticks.load(Ordering::Relaxed);
+        assert!(stopped_at > 0);
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(ticks.load(Ordering::Relaxed), stopped_at);
+
+        // Dropping the owner stops its threads as well
+        let flag = {
+            let mut owner = BackgroundThreads::new();
+            owner.spawn("idle", |shutdown| while shutdown.sleep(Duration::from_millis(5)) {});
+            owner.shutdown_flag()
+        };
+        assert!(flag.is_set());
+    }
+}
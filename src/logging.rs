@@ -0,0 +1,365 @@
+/// Structured logging setup shared by the GUI binaries
+///
+/// Installs a tracing subscriber with three outputs:
+/// - console, filtered by RUST_LOG (else the caller's default); JSON lines when STRINGDRIVER_LOG_JSON=1
+/// - a per-host, per-day log file under the LOGGING directory, rotated by size
+/// - an in-memory buffer (debug and above) that the GUI log panels display
+///
+/// `log` crate records from the shared modules are bridged into tracing, so
+/// they show up in both outputs with the enclosing operation/IPC spans.
+///
+/// The spans themselves are kept too: every span opened under the latest
+/// "operation" span, with its fields and when it opened and closed, goes to the
+/// process-wide PhaseLog that the operation timeline is drawn from (see timeline).
+
+use crate::error::{Error, Result};
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+use crate::config_loader::{load_logging_settings, LoggingSettings};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+
+/// Lines kept for the GUI log panels
+const GUI_LOG_CAPACITY: usize = 2000;
+/// Phases kept for the operation timeline; older ones are dropped past this
+/// (a 3-hour sweep opens a dwell span per X position and more inside each)
+const PHASE_CAPACITY: usize = 20_000;
+
+/// Formatted log lines shared with the GUI panels
+pub type LogBuffer = Arc<Mutex<VecDeque<String>>>;
+
+pub fn new_buffer() -> LogBuffer {
+    Arc::new(Mutex::new(VecDeque::with_capacity(GUI_LOG_CAPACITY)))
+}
+
+/// One span of an operation: the operation itself, a lap, a dwell at an X position,
+/// a calibration or a bump_check
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseRecord {
+    pub seq: u64, // Increasing: phases are kept in opening order
+    pub name: &'static str,
+    pub fields: Vec<(&'static str, String)>,
+    pub depth: usize, // 0: the operation span
+    pub start: Instant,
+    pub end: Option<Instant>, // None while open
+}
+
+impl PhaseRecord {
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.iter().find(|(field, _)| *field == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// The spans of the latest operation
+#[derive(Debug, Clone, Default)]
+pub struct PhaseLog {
+    pub operation: Option<PhaseRecord>,
+    pub phases: VecDeque<PhaseRecord>, // Spans under it, oldest first
+    pub dropped: usize,                // Older phases dropped at PHASE_CAPACITY
+}
+
+/// Process-wide, like the subscriber that fills it
+pub type PhaseBuffer = Arc<Mutex<PhaseLog>>;
+
+static PHASES: OnceLock<PhaseBuffer> = OnceLock::new();
+
+/// The PhaseLog kept by the subscriber `init` installs
+pub fn phase_buffer() -> PhaseBuffer {
+    Arc::clone(PHASES.get_or_init(|| Arc::new(Mutex::new(PhaseLog::default()))))
+}
+
+/// Whole buffer as newline-separated text (for display or copying)
+pub fn buffer_text(buffer: &LogBuffer) -> String {
+    buffer.lock()
+        .map(|lines| lines.iter().cloned().collect::<Vec<_>>().join("\n"))
+        .unwrap_or_default()
+}
+
+pub fn clear_buffer(buffer: &LogBuffer) {
+    if let Ok(mut lines) = buffer.lock() {
+        lines.clear();
+    }
+}
+
+/// Host directory for log files (<LOGGING.DIR>/<hostname>), created if missing
+pub fn log_dir(settings: &LoggingSettings) -> Result<PathBuf> {
+    let hostname = gethostname::gethostname().to_string_lossy().to_string();
+    let dir = settings.dir.join(hostname);
+    fs::create_dir_all(&dir)
+        .map_err(|e| Error::io(format!("Cannot create log directory {:?}", dir), e))?;
+    Ok(dir)
+}
+
+/// Today's log file for `component`, e.g. logs/<host>/stepper_gui-2024-05-01.log
+pub fn log_file_path(settings: &LoggingSettings, component: &str) -> Result<PathBuf> {
+    let day = chrono::Local::now().format("%Y-%m-%d");
+    Ok(log_dir(settings)?.join(format!("{}-{}.log", component, day)))
+}
+
+/// Open today's size-capped log file for `component` using this host's LOGGING settings.
+/// Problems are reported on stderr and leave file logging off.
+pub fn open_log_file(component: &str) -> Option<RotatingFile> {
+    let hostname = gethostname::gethostname().to_string_lossy().to_string();
+    let settings = load_logging_settings(&hostname).unwrap_or_else(|e| {
+        eprintln!("Warning: Could not load LOGGING settings: {}. Using defaults.", e);
+        LoggingSettings::default()
+    });
+    match log_file_path(&settings, component).and_then(|path| RotatingFile::open(path, settings.max_file_bytes, settings.keep_files)) {
+        Ok(file) => Some(file),
+        Err(e) => {
+            eprintln!("File logging disabled: {}", e);
+            None
+        }
+    }
+}
+
+/// Install the global subscriber and return the buffer feeding the GUI panels.
+/// `component` names the log file; `default_filter` (e.g. "info", "debug") applies to
+/// the console and file when RUST_LOG is unset.
+/// Safe to call more than once; only the first call installs anything.
+pub fn init(component: &str, default_filter: &str) -> LogBuffer {
+    let buffer = new_buffer();
+
+    let env_filter = || EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter));
+    let console_filter = env_filter();
+    let json = matches!(std::env::var("STRINGDRIVER_LOG_JSON").as_deref(), Ok("1") | Ok("true"));
+    let console = if json {
+        fmt::layer().json().with_current_span(true).with_span_list(true).boxed()
+    } else {
+        fmt::layer().boxed()
+    };
+    let file = open_log_file(component).map(|file| {
+        fmt::layer()
+            .with_ansi(false)
+            .with_writer(Mutex::new(file))
+            .with_filter(env_filter())
+    });
+    let gui = GuiLogLayer { buffer: Arc::clone(&buffer) }.with_filter(LevelFilter::DEBUG);
+    let phases = PhaseLayer::new(phase_buffer());
+
+    if let Err(e) = tracing_subscriber::registry()
+        .with(console.with_filter(console_filter))
+        .with(file)
+        .with(gui)
+        .with(phases)
+        .try_init()
+    {
+        eprintln!("Logging already initialized: {}", e);
+    }
+    buffer
+}
+
+/// Layer that renders events as "HH:MM:SS.mmm LEVEL [span:span] message key=value"
+struct GuiLogLayer {
+    buffer: LogBuffer,
+}
+
+impl<S> Layer<S> for GuiLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut line = format!(
+            "{} {:<5}",
+            chrono::Local::now().format("%H:%M:%S%.3f"),
+            event.metadata().level().to_string()
+        );
+        if let Some(scope) = ctx.event_scope(event) {
+            let names: Vec<&str> = scope.from_root().map(|span| span.name()).collect();
+            if !names.is_empty() {
+                let _ = write!(line, " [{}]", names.join(":"));
+            }
+        }
+        let mut visitor = LineVisitor::default();
+        event.record(&mut visitor);
+        let _ = write!(line, " {}{}", visitor.message, visitor.fields);
+
+        if let Ok(mut lines) = self.buffer.lock() {
+            if lines.len() >= GUI_LOG_CAPACITY {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+    }
+}
+
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LineVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// Layer that records the spans of the latest "operation" span into a PhaseLog.
+/// A second operation opened while one is still open (a probe beside a sweep has
+/// its own span name, but a plugin may not) is left out.
+pub struct PhaseLayer {
+    log: PhaseBuffer,
+    next_seq: AtomicU64,
+}
+
+impl PhaseLayer {
+    /// `init` installs one filling phase_buffer(); tests can record into their own
+    pub fn new(log: PhaseBuffer) -> Self {
+        Self { log, next_seq: AtomicU64::new(0) }
+    }
+}
+
+/// The PhaseRecord a span was recorded as
+struct PhaseSeq(u64);
+
+impl<S> Layer<S> for PhaseLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let Ok(mut log) = self.log.lock() else {
+            return;
+        };
+        let is_operation = span.name() == "operation" && span.parent().is_none();
+        let depth = span.scope().count() - 1;
+        if is_operation {
+            if log.operation.as_ref().is_some_and(|op| op.end.is_none()) {
+                return;
+            }
+        } else {
+            // Only spans inside the operation being recorded
+            let root_seq = span.scope().from_root().next().and_then(|root| root.extensions().get::<PhaseSeq>().map(|seq| seq.0));
+            if root_seq.is_none() || root_seq != log.operation.as_ref().map(|op| op.seq) {
+                return;
+            }
+        }
+        let mut fields = FieldVisitor::default();
+        attrs.record(&mut fields);
+        let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+        let record = PhaseRecord { seq, name: span.name(), fields: fields.0, depth, start: Instant::now(), end: None };
+        if is_operation {
+            *log = PhaseLog { operation: Some(record), ..PhaseLog::default() };
+        } else {
+            if log.phases.len() >= PHASE_CAPACITY {
+                log.phases.pop_front();
+                log.dropped += 1;
+            }
+            log.phases.push_back(record);
+        }
+        span.extensions_mut().insert(PhaseSeq(seq));
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(seq) = ctx.span(&id).and_then(|span| span.extensions().get::<PhaseSeq>().map(|seq| seq.0)) else {
+            return;
+        };
+        let Ok(mut log) = self.log.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        if let Some(operation) = log.operation.as_mut().filter(|op| op.seq == seq) {
+            operation.end = Some(now);
+        } else if let Ok(idx) = log.phases.binary_search_by_key(&seq, |phase| phase.seq) {
+            log.phases[idx].end = Some(now);
+        }
+    }
+}
+
+/// Span fields as (name, value) pairs
+#[derive(Default)]
+struct FieldVisitor(Vec<(&'static str, String)>);
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push((field.name(), value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push((field.name(), format!("{:?}", value)));
+    }
+}
+
+/// Append-only file that rotates to <name>.1 .. <name>.N once it reaches `max_bytes`
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    keep: usize,
+}
+
+impl RotatingFile {
+    pub fn open(path: PathBuf, max_bytes: u64, keep: usize) -> Result<Self> {
+        let file = Self::open_append(&path).map_err(|e| Error::io(format!("Cannot open log file {:?}", path), e))?;
+        let written = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Ok(Self { path, file, written, max_bytes, keep })
+    }
+
+    fn open_append(path: &Path) -> io::Result<File> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.keep == 0 {
+            // Nothing kept: start the file over
+            self.file = File::create(&self.path)?;
+        } else {
+            for i in (1..self.keep).rev() {
+                let _ = fs::rename(self.rotated(i), self.rotated(i + 1));
+            }
+            fs::rename(&self.path, self.rotated(1))?;
+            self.file = Self::open_append(&self.path)?;
+        }
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl io::Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let n = self.file.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
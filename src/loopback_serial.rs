@@ -0,0 +1,443 @@
+/// Loopback serial port emulating the String_Driver firmware
+///
+/// Stands in for a board in tests: CmdMessenger commands written to the port are
+/// parsed the way the firmware parses them and applied to an in-memory stepper
+/// model (amove, rmove, set_stepper, set_enable), and position/encoder/version queries are answered
+/// with the escaped binary frames the firmware sends. The `LoopbackBoard` handle
+/// shared with the test inspects the model and injects faults: replies delivered
+/// in short reads with timeouts in between, debug prints around a reply,
+/// corrupted frames, and the board dropping off the bus and coming back.
+
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Command ids and argument width of one firmware variant
+#[derive(Debug, Clone, Copy)]
+pub struct FirmwareProtocol {
+    pub positions_id: u8,
+    pub encoders_id: Option<u8>,
+    pub amove_id: u8,
+    pub rmove_id: u8,
+    pub set_stepper_id: u8,
+    pub enable_id: Option<u8>, // set_enable <stepper> <1|0>; moves to a cut driver are ignored
+    pub value_bytes: usize, // 2 = AVR int (v1/v2), 4 = long (v3)
+    pub checksum: bool,     // Append a CRC-8 argument to replies
+    pub version: Option<(u8, i32)>, // Version query id and the protocol it answers (None = older firmware, no reply)
+}
+
+/// One command as the firmware decoded it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceivedCommand {
+    pub id: u8,
+    pub args: Vec<i32>,
+}
+
+#[derive(Debug)]
+struct BoardState {
+    protocol: FirmwareProtocol,
+    positions: Vec<i32>,
+    driver_enabled: Vec<bool>,
+    received: Vec<u8>,       // Bytes of a command not yet terminated
+    commands: Vec<ReceivedCommand>,
+    reply: VecDeque<u8>,     // Reply bytes waiting to be read
+    max_read: Option<usize>, // Deliver replies at most this many bytes per read
+    stall_reads: bool,       // Time out once between partial reads
+    stalled: bool,
+    corrupt_replies: VecDeque<Vec<u8>>, // Sent instead of the next replies
+    debug_print: Vec<u8>,    // Serial.print output emitted ahead of the next reply
+    plugged_in: bool,
+    generation: u32,         // Bumped on unplug; ports from older generations stay dead
+    opens: usize,
+}
+
+/// Test-side handle to an emulated board
+#[derive(Debug, Clone)]
+pub struct LoopbackBoard {
+    state: Arc<Mutex<BoardState>>,
+}
+
+impl LoopbackBoard {
+    pub fn new(protocol: FirmwareProtocol, num_steppers: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(BoardState {
+                protocol,
+                positions: vec![0; num_steppers],
+                driver_enabled: vec![true; num_steppers],
+                received: Vec::new(),
+                commands: Vec::new(),
+                reply: VecDeque::new(),
+                max_read: None,
+                stall_reads: false,
+                stalled: false,
+                corrupt_replies: VecDeque::new(),
+                debug_print: Vec::new(),
+                plugged_in: true,
+                generation: 0,
+                opens: 0,
+            })),
+        }
+    }
+
+    /// Open a port onto the board, failing like serialport does when it is unplugged
+    pub fn open(&self) -> serialport::Result<Box<dyn serialport::SerialPort>> {
+        let mut state = self.state.lock().unwrap();
+        if !state.plugged_in {
+            return Err(serialport::Error::new(serialport::ErrorKind::NoDevice, "Device not found"));
+        }
+        state.opens += 1;
+        Ok(Box::new(LoopbackPort {
+            state: Arc::clone(&self.state),
+            generation: state.generation,
+            timeout: Duration::from_millis(10),
+        }))
+    }
+
+    pub fn positions(&self) -> Vec<i32> {
+        self.state.lock().unwrap().positions.clone()
+    }
+
+    pub fn set_positions(&self, positions: &[i32]) {
+        self.state.lock().unwrap().positions = positions.to_vec();
+    }
+
+    /// Whether a stepper's driver is energized (set_enable)
+    pub fn driver_enabled(&self, stepper: usize) -> bool {
+        self.state.lock().unwrap().driver_enabled.get(stepper).copied().unwrap_or(false)
+    }
+
+    /// Commands received so far, queries included
+    pub fn commands(&self) -> Vec<ReceivedCommand> {
+        self.state.lock().unwrap().commands.clone()
+    }
+
+    pub fn open_count(&self) -> usize {
+        self.state.lock().unwrap().opens
+    }
+
+    /// Hand out replies `max_read` bytes at a time, optionally timing out between pieces
+    pub fn split_replies(&self, max_read: usize, stall_between: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.max_read = Some(max_read.max(1));
+        state.stall_reads = stall_between;
+    }
+
+    /// Answer the next query with `bytes` verbatim
+    pub fn corrupt_next_reply(&self, bytes: &[u8]) {
+        self.state.lock().unwrap().corrupt_replies.push_back(bytes.to_vec());
+    }
+
+    /// Emit `text` just before the next reply, as a stray Serial.print would
+    pub fn print_before_next_reply(&self, text: &str) {
+        self.state.lock().unwrap().debug_print.extend_from_slice(text.as_bytes());
+    }
+
+    /// Drop off the bus: open ports fail with broken pipe and new opens fail
+    pub fn unplug(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.plugged_in = false;
+        state.generation += 1;
+        state.received.clear();
+        state.reply.clear();
+    }
+
+    pub fn plug_in(&self) {
+        self.state.lock().unwrap().plugged_in = true;
+    }
+}
+
+impl BoardState {
+    /// Split complete commands off the receive buffer and act on them
+    fn process_received(&mut self) {
+        while let Some(end) = unescaped_position(&self.received, b';') {
+            let frame: Vec<u8> = self.received.drain(..=end).collect();
+            if let Some(command) = self.parse_command(&frame[..end]) {
+                self.apply(&command);
+                self.commands.push(command);
+            }
+        }
+    }
+
+    /// "<id>,<arg>,<arg>" with '/' escapes; the firmware ignores ids it cannot parse
+    fn parse_command(&self, frame: &[u8]) -> Option<ReceivedCommand> {
+        let mut fields: Vec<Vec<u8>> = vec![Vec::new()];
+        let mut bytes = frame.iter();
+        while let Some(&b) = bytes.next() {
+            match b {
+                b'/' => fields.last_mut()?.push(*bytes.next()?),
+                b',' => fields.push(Vec::new()),
+                _ => fields.last_mut()?.push(b),
+            }
+        }
+        let id = std::str::from_utf8(&fields[0]).ok()?.trim().parse().ok()?;
+        // readBinArg<int> reads only the first sizeof(int) bytes of each argument
+        let width = self.protocol.value_bytes;
+        let args = fields[1..]
+            .iter()
+            .map(|field| {
+                let mut raw = [0u8; 4];
+                for (dst, src) in raw.iter_mut().zip(field.iter().take(width)) {
+                    *dst = *src;
+                }
+                match width {
+                    4 => i32::from_le_bytes(raw),
+                    _ => i16::from_le_bytes([raw[0], raw[1]]) as i32,
+                }
+            })
+            .collect();
+        Some(ReceivedCommand { id, args })
+    }
+
+    fn apply(&mut self, command: &ReceivedCommand) {
+        let protocol = self.protocol;
+        let target = |args: &[i32]| -> Option<(usize, i32)> {
+            let stepper = usize::try_from(*args.first()?).ok()?;
+            Some((stepper, *args.get(1)?))
+        };
+        if command.id == protocol.positions_id {
+            let values = self.positions.clone();
+            self.queue_reply(protocol.positions_id, &values, protocol.value_bytes);
+        } else if Some(command.id) == protocol.encoders_id {
+            let values = self.positions.clone();
+            self.queue_reply(command.id, &values, 4);
+        } else if let Some((version_id, version)) = protocol.version.filter(|(id, _)| *id == command.id) {
+            self.queue_reply(version_id, &[version], 4);
+        } else if Some(command.id) == protocol.enable_id {
+            if let Some((stepper, on)) = target(&command.args) {
+                if let Some(enabled) = self.driver_enabled.get_mut(stepper) {
+                    *enabled = on != 0;
+                }
+            }
+        } else if command.id == protocol.set_stepper_id {
+            if let Some((stepper, value)) = target(&command.args) {
+                if let Some(position) = self.positions.get_mut(stepper) {
+                    *position = value;
+                }
+            }
+        } else if command.id == protocol.amove_id || command.id == protocol.rmove_id {
+            if let Some((stepper, value)) = target(&command.args) {
+                let energized = self.driver_enabled.get(stepper).copied().unwrap_or(false);
+                if let Some(position) = self.positions.get_mut(stepper).filter(|_| energized) {
+                    if command.id == protocol.amove_id {
+                        *position = value;
+                    } else {
+                        *position += value;
+                    }
+                }
+            }
+        }
+    }
+
+    /// sendCmdStart(id); sendCmdBinArg(v) per value; sendCmdEnd()
+    fn queue_reply(&mut self, id: u8, values: &[i32], width: usize) {
+        let debug_print = std::mem::take(&mut self.debug_print);
+        self.reply.extend(debug_print);
+        if let Some(corrupt) = self.corrupt_replies.pop_front() {
+            self.reply.extend(corrupt);
+            return;
+        }
+        let mut frame = id.to_string().into_bytes();
+        let mut args: Vec<Vec<u8>> = values.iter().map(|v| v.to_le_bytes()[..width].to_vec()).collect();
+        if self.protocol.checksum {
+            args.push(vec![crc8(&args.concat())]);
+        }
+        for arg in args {
+            frame.push(b',');
+            for b in arg {
+                if matches!(b, b',' | b';' | b'/' | 0) {
+                    frame.push(b'/');
+                }
+                frame.push(b);
+            }
+        }
+        frame.push(b';');
+        self.reply.extend(frame);
+    }
+}
+
+/// CRC-8, polynomial 0x07
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |crc, &b| {
+        (0..8).fold(crc ^ b, |c, _| if c & 0x80 != 0 { (c << 1) ^ 0x07 } else { c << 1 })
+    })
+}
+
+fn unescaped_position(buffer: &[u8], target: u8) -> Option<usize> {
+    let mut escaped = false;
+    for (i, &b) in buffer.iter().enumerate() {
+        if escaped {
+            escaped = false;
+        } else if b == b'/' {
+            escaped = true;
+        } else if b == target {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Port end of the loopback, as handed to the code under test
+#[derive(Debug)]
+pub struct LoopbackPort {
+    state: Arc<Mutex<BoardState>>,
+    generation: u32,
+    timeout: Duration,
+}
+
+impl LoopbackPort {
+    fn live_state(&self) -> io::Result<std::sync::MutexGuard<'_, BoardState>> {
+        let state = self.state.lock().unwrap();
+        if !state.plugged_in || state.generation != self.generation {
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe, "Broken pipe"));
+        }
+        Ok(state)
+    }
+}
+
+impl Read for LoopbackPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut state = self.live_state()?;
+        if state.reply.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "Operation timed out"));
+        }
+        if state.stall_reads {
+            state.stalled = !state.stalled;
+            if !state.stalled {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "Operation timed out"));
+            }
+        }
+        let limit = state.max_read.unwrap_or(usize::MAX).min(buf.len());
+        let mut n = 0;
+        while n < limit {
+            let Some(b) = state.reply.pop_front() else { break };
+            buf[n] = b;
+            n += 1;
+        }
+        Ok(n)
+    }
+}
+
+impl Write for LoopbackPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.live_state()?;
+        state.received.extend_from_slice(buf);
+        state.process_received();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.live_state().map(|_| ())
+    }
+}
+
+impl serialport::SerialPort for LoopbackPort {
+    fn name(&self) -> Option<String> {
+        Some("loopback".to_string())
+    }
+
+    fn baud_rate(&self) -> serialport::Result<u32> {
+        Ok(115200)
+    }
+
+    fn data_bits(&self) -> serialport::Result<serialport::DataBits> {
+        Ok(serialport::DataBits::Eight)
+    }
+
+    fn flow_control(&self) -> serialport::Result<serialport::FlowControl> {
+        Ok(serialport::FlowControl::None)
+    }
+
+    fn parity(&self) -> serialport::Result<serialport::Parity> {
+        Ok(serialport::Parity::None)
+    }
+
+    fn stop_bits(&self) -> serialport::Result<serialport::StopBits> {
+        Ok(serialport::StopBits::One)
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn set_baud_rate(&mut self, _baud_rate: u32) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn set_data_bits(&mut self, _data_bits: serialport::DataBits) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn set_flow_control(&mut self, _flow_control: serialport::FlowControl) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn set_parity(&mut self, _parity: serialport::Parity) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn set_stop_bits(&mut self, _stop_bits: serialport::StopBits) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> serialport::Result<()> {
+        self.timeout = timeout;
+        Ok(())
+    }
+
+    fn write_request_to_send(&mut self, _level: bool) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn write_data_terminal_ready(&mut self, _level: bool) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn read_clear_to_send(&mut self) -> serialport::Result<bool> {
+        Ok(true)
+    }
+
+    fn read_data_set_ready(&mut self) -> serialport::Result<bool> {
+        Ok(true)
+    }
+
+    fn read_ring_indicator(&mut self) -> serialport::Result<bool> {
+        Ok(false)
+    }
+
+    fn read_carrier_detect(&mut self) -> serialport::Result<bool> {
+        Ok(true)
+    }
+
+    fn bytes_to_read(&self) -> serialport::Result<u32> {
+        Ok(self.live_state()?.reply.len() as u32)
+    }
+
+    fn bytes_to_write(&self) -> serialport::Result<u32> {
+        Ok(0)
+    }
+
+    fn clear(&self, buffer_to_clear: serialport::ClearBuffer) -> serialport::Result<()> {
+        let mut state = self.live_state()?;
+        if matches!(buffer_to_clear, serialport::ClearBuffer::Input | serialport::ClearBuffer::All) {
+            state.reply.clear();
+        }
+        Ok(())
+    }
+
+    fn try_clone(&self) -> serialport::Result<Box<dyn serialport::SerialPort>> {
+        Ok(Box::new(LoopbackPort {
+            state: Arc::clone(&self.state),
+            generation: self.generation,
+            timeout: self.timeout,
+        }))
+    }
+
+    fn set_break(&self) -> serialport::Result<()> {
+        Ok(())
+    }
+
+    fn clear_break(&self) -> serialport::Result<()> {
+        Ok(())
+    }
+}
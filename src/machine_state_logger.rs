@@ -3,27 +3,40 @@
 /// Non-blocking, event-driven logging at 1Hz
 /// Uses existing position arrays (does NOT query Arduino - avoids blocking)
 /// Links to audmon's controls_id for concurrent time-series correlation
+///
+/// LOGGING.MACHINE_STATE picks the backends: the Postgres machine_state,
+/// operations and bump_events tables, InfluxDB line protocol (POSTed to a write
+/// endpoint or appended to a file), or both. One writer thread feeds them all.
 
+use std::fmt::Write as _;
+use std::io::Write as _;
 use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::mpsc::{self, SyncSender, Receiver};
 use std::thread;
 use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use log::{error, info, warn, debug};
 use postgres::{Client, NoTls, Statement};
 use uuid::Uuid;
 
-use crate::config_loader::DbSettings;
+use crate::alerting::CurlConfig;
+use crate::bump_log::BumpEvent;
+use crate::config_loader::{DbSettings, InfluxTarget};
+use crate::error::{Error, Result};
 
 const DB_BUFFER_FULL_MSG: &str = "DB write buffer is full.";
+/// Measurement names start with this
+const INFLUX_PREFIX: &str = "stringdriver";
+/// Seconds curl may spend on one line protocol POST
+const INFLUX_POST_TIMEOUT_SECS: &str = "10";
 
 // Event-driven database write commands
 enum DbWriteCommand {
-    InsertMachineState(MachineStateSnapshot),
-    InsertOperation(OperationEvent),
+    MachineState(MachineStateSnapshot),
+    Operation(OperationEvent),
+    BumpEvent(BumpEvent),
 }
 
 #[derive(Clone)]
@@ -59,6 +72,8 @@ pub struct MachineStateSnapshot {
     pub amp_sum_min: Vec<i32>,
     pub amp_sum_max: Vec<i32>,
     pub stepper_roles: Vec<StepperRoleEntry>,
+    pub channel_strings: Vec<Option<usize>>, // String heard on each channel (line protocol tags; not in Postgres)
+    pub build_info: String, // Versions, git hashes and protocols of the GUIs and firmware
 }
 
 #[derive(Clone)]
@@ -72,6 +87,7 @@ pub struct OperationEvent {
     pub message: String,
     pub stepper_indices: Vec<usize>,
     pub final_positions: Vec<i32>,
+    pub started_at: Option<DateTime<Utc>>, // recorded_at is when it finished
 }
 
 #[derive(Clone)]
@@ -81,10 +97,16 @@ pub struct StepperRoleEntry {
     pub string_index: Option<usize>,
 }
 
+/// A Postgres failure as Error::Other, saying what was being done
+fn db_error(context: &'static str) -> impl FnOnce(postgres::Error) -> Error {
+    move |e| Error::Other(format!("{}: {}", context, e))
+}
+
 pub struct MachineStateLogger {
     client: Client,
     insert_state_stmt: Statement,
     insert_operation_stmt: Statement,
+    insert_bump_stmt: Statement,
     stepper_role_table_ready: bool,
 }
 
@@ -101,22 +123,48 @@ impl MachineStateLogger {
         eprintln!("  Database: {}", db_config.database);
         eprintln!("============================================================");
         let mut client = Client::connect(&connection_str, NoTls)
-            .context("Failed to connect to machine state database")?;
+            .map_err(db_error("Failed to connect to machine state database"))?;
 
         // Verify connection is actually working with a test query (no fallbacks - fail-fast if DB is unreachable)
         client.query("SELECT 1", &[])
-            .context("Database connection test query failed - connection is not working")?;
+            .map_err(db_error("Database connection test query failed - connection is not working"))?;
         eprintln!("✓ Machine state database connection verified (test query succeeded)");
 
+        // Databases created before build_info existed
+        client.batch_execute("ALTER TABLE machine_state ADD COLUMN IF NOT EXISTS build_info TEXT")
+            .map_err(db_error("Failed to add build_info column to machine_state."))?;
+        client.batch_execute("ALTER TABLE operations ADD COLUMN IF NOT EXISTS started_at TIMESTAMP WITH TIME ZONE")
+            .map_err(db_error("Failed to add started_at column to operations."))?;
         let insert_state_stmt = client
-            .prepare("INSERT INTO machine_state (state_id, controls_id, host, recorded_at, stepper_positions, stepper_enabled, bump_check_enable, z_up_step, z_down_step, tune_rest, x_rest, z_rest, lap_rest, adjustment_level, retry_threshold, delta_threshold, z_variance_threshold, voice_count, amp_sum, voice_count_min, voice_count_max, amp_sum_min, amp_sum_max) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23)")
-            .context("Failed to prepare machine state SQL statement.")?;
+            .prepare("INSERT INTO machine_state (state_id, controls_id, host, recorded_at, stepper_positions, stepper_enabled, bump_check_enable, z_up_step, z_down_step, tune_rest, x_rest, z_rest, lap_rest, adjustment_level, retry_threshold, delta_threshold, z_variance_threshold, voice_count, amp_sum, voice_count_min, voice_count_max, amp_sum_min, amp_sum_max, build_info) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24)")
+            .map_err(db_error("Failed to prepare machine state SQL statement."))?;
 
         let insert_operation_stmt = client
-            .prepare("INSERT INTO operations (operation_id, state_id, host, recorded_at, operation_type, operation_status, message, stepper_indices, final_positions) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)")
-            .context("Failed to prepare operations SQL statement.")?;
+            .prepare("INSERT INTO operations (operation_id, state_id, host, recorded_at, operation_type, operation_status, message, stepper_indices, final_positions, started_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)")
+            .map_err(db_error("Failed to prepare operations SQL statement."))?;
+
+        client.batch_execute(
+            "
+            CREATE TABLE IF NOT EXISTS bump_events (
+                host TEXT NOT NULL,
+                recorded_at TIMESTAMP WITH TIME ZONE NOT NULL,
+                stepper_index INTEGER NOT NULL,
+                string_index INTEGER,
+                channel_index INTEGER,
+                x_position INTEGER,
+                z_position INTEGER NOT NULL,
+                z_down_step INTEGER NOT NULL,
+                amp_sum REAL,
+                amp_baseline REAL
+            );
+            CREATE INDEX IF NOT EXISTS bump_events_host_time ON bump_events (host, recorded_at);
+            "
+        ).map_err(db_error("Failed to create bump_events table"))?;
+        let insert_bump_stmt = client
+            .prepare("INSERT INTO bump_events (host, recorded_at, stepper_index, string_index, channel_index, x_position, z_position, z_down_step, amp_sum, amp_baseline) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)")
+            .map_err(db_error("Failed to prepare bump_events SQL statement."))?;
 
-        Ok(Self { client, insert_state_stmt, insert_operation_stmt, stepper_role_table_ready: false })
+        Ok(Self { client, insert_state_stmt, insert_operation_stmt, insert_bump_stmt, stepper_role_table_ready: false })
     }
 
     fn insert_machine_state(&mut self, snapshot: &MachineStateSnapshot) -> Result<()> {
@@ -133,7 +181,8 @@ impl MachineStateLogger {
             &(snapshot.adjustment_level as i32), &(snapshot.retry_threshold as i32), &(snapshot.delta_threshold as i32), &(snapshot.z_variance_threshold as i32),
             &snapshot.voice_count.iter().map(|&x| x as i32).collect::<Vec<i32>>(), &snapshot.amp_sum,
             &snapshot.voice_count_min, &snapshot.voice_count_max, &snapshot.amp_sum_min.iter().map(|&x| x as i32).collect::<Vec<i32>>(), &snapshot.amp_sum_max.iter().map(|&x| x as i32).collect::<Vec<i32>>(),
-        ]).context("Failed to insert machine state record.")?;
+            &snapshot.build_info,
+        ]).map_err(db_error("Failed to insert machine state record."))?;
         info!(target: "machine_state_logger", "Inserted machine state: id={}", snapshot.state_id);
         Ok(())
     }
@@ -152,7 +201,7 @@ impl MachineStateLogger {
                 PRIMARY KEY(host, stepper_index)
             );
             "
-        ).context("Failed to create host_config_stepper_roles table")?;
+        ).map_err(db_error("Failed to create host_config_stepper_roles table"))?;
         self.stepper_role_table_ready = true;
         Ok(())
     }
@@ -170,7 +219,7 @@ impl MachineStateLogger {
                 DO UPDATE SET role = EXCLUDED.role, string_index = EXCLUDED.string_index
                 ",
                 &[&host, &stepper_index, &entry.role, &string_index]
-            ).context("Failed to upsert host_config_stepper_roles")?;
+            ).map_err(db_error("Failed to upsert host_config_stepper_roles"))?;
         }
         Ok(())
     }
@@ -184,10 +233,332 @@ impl MachineStateLogger {
             &event.recorded_at,
             &event.operation_type, &event.operation_status, &event.message,
             &stepper_indices_array, &event.final_positions,
-        ]).context("Failed to insert operation record.")?;
+            &event.started_at,
+        ]).map_err(db_error("Failed to insert operation record."))?;
         info!(target: "machine_state_logger", "Inserted operation: id={}, type={}", event.operation_id, event.operation_type);
         Ok(())
     }
+
+    fn insert_bump_event(&mut self, event: &BumpEvent) -> Result<()> {
+        let index = |idx: Option<usize>| idx.map(|i| i as i32);
+        self.client.execute(&self.insert_bump_stmt, &[
+            &event.host,
+            &event.recorded_at,
+            &(event.stepper as i32), &index(event.string), &index(event.channel),
+            &event.x_position, &event.z_position, &event.z_down_step,
+            &event.amp_sum, &event.amp_baseline,
+        ]).map_err(db_error("Failed to insert bump event."))?;
+        info!(target: "machine_state_logger", "Inserted bump event: stepper={}", event.stepper);
+        Ok(())
+    }
+}
+
+/// A backend the writer thread hands snapshots, operations and bumps to
+trait StateSink: Send {
+    fn insert_machine_state(&mut self, snapshot: &MachineStateSnapshot) -> Result<()>;
+    fn insert_operation(&mut self, event: &OperationEvent) -> Result<()>;
+    fn insert_bump_event(&mut self, event: &BumpEvent) -> Result<()>;
+}
+
+impl StateSink for MachineStateLogger {
+    fn insert_machine_state(&mut self, snapshot: &MachineStateSnapshot) -> Result<()> {
+        MachineStateLogger::insert_machine_state(self, snapshot)
+    }
+
+    fn insert_operation(&mut self, event: &OperationEvent) -> Result<()> {
+        MachineStateLogger::insert_operation(self, event)
+    }
+
+    fn insert_bump_event(&mut self, event: &BumpEvent) -> Result<()> {
+        MachineStateLogger::insert_bump_event(self, event)
+    }
+}
+
+/// A backend to open on the writer thread
+#[derive(Debug, Clone)]
+pub enum SinkConfig {
+    Postgres(DbSettings),
+    Influx(InfluxTarget),
+}
+
+impl SinkConfig {
+    fn open(&self) -> Result<Box<dyn StateSink>> {
+        match self {
+            SinkConfig::Postgres(db_config) => Ok(Box::new(MachineStateLogger::new(db_config)?)),
+            SinkConfig::Influx(target) => Ok(Box::new(InfluxLogger::new(target.clone())?)),
+        }
+    }
+}
+
+/// InfluxDB line protocol, for an Influx / Grafana monitoring stack.
+///
+/// Each snapshot becomes one `stringdriver_machine_state` point (settings), one
+/// `stringdriver_stepper` point per stepper (position, enabled; tagged stepper,
+/// role and string) and one `stringdriver_channel` point per channel (voice
+/// count, amp sum and their thresholds; tagged channel and string). Each
+/// operation becomes a `stringdriver_operation` point (tagged operation and
+/// status) and a `stringdriver_operation_stepper` point per stepper it moved.
+/// Each bump becomes a `stringdriver_bump` point (positions, amp sum against
+/// its baseline, dip and verdict; tagged stepper, string and channel).
+/// Every point is tagged with the host and stamped in nanoseconds.
+pub struct InfluxLogger {
+    target: InfluxTarget,
+    roles: Vec<StepperRoleEntry>, // As of the last snapshot, to tag operation steppers with their string
+}
+
+impl InfluxLogger {
+    pub fn new(target: InfluxTarget) -> Result<Self> {
+        if let InfluxTarget::File(path) = &target {
+            // Fail at startup, not on the first write
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).map_err(|e| Error::io(format!("Cannot create {}", dir.display()), e))?;
+            }
+            std::fs::OpenOptions::new().create(true).append(true).open(path)
+                .map_err(|e| Error::io(format!("Cannot open {} for line protocol", path.display()), e))?;
+        }
+        info!(target: "machine_state_logger", "Machine state line protocol to {:?}", target);
+        Ok(Self { target, roles: Vec::new() })
+    }
+
+    fn write(&self, lines: &str) -> Result<()> {
+        match &self.target {
+            InfluxTarget::File(path) => {
+                let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)
+                    .map_err(|e| Error::io(format!("Cannot open {}", path.display()), e))?;
+                file.write_all(lines.as_bytes()).map_err(|e| Error::io(format!("Cannot write {}", path.display()), e))
+            }
+            InfluxTarget::Http { url, token_env } => {
+                let mut cmd = std::process::Command::new("curl");
+                cmd.args(["-sS", "--fail", "--max-time", INFLUX_POST_TIMEOUT_SECS, "-H", "Content-Type: text/plain; charset=utf-8"]);
+                // The token goes in a config file, not curl's command line; kept until curl is done
+                let _credentials = match token_env.as_ref().and_then(|var| std::env::var(var).ok()) {
+                    Some(token) => {
+                        let config = CurlConfig::write(&[("header", &format!("Authorization: Token {}", token))])
+                            .map_err(|e| Error::io("Cannot write curl credentials", e))?;
+                        cmd.arg("-K").arg(config.path());
+                        Some(config)
+                    }
+                    None => None,
+                };
+                let mut child = cmd.args(["--data-binary", "@-", url])
+                    .stdin(std::process::Stdio::piped())
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::piped())
+                    .spawn()
+                    .map_err(|e| Error::io("Cannot run curl", e))?;
+                if let Some(mut stdin) = child.stdin.take() {
+                    stdin.write_all(lines.as_bytes()).map_err(|e| Error::io("Cannot pass line protocol to curl", e))?;
+                }
+                let output = child.wait_with_output().map_err(|e| Error::io("curl did not finish", e))?;
+                if !output.status.success() {
+                    return Err(Error::Other(format!(
+                        "POST to {} failed: {} ({})",
+                        url,
+                        String::from_utf8_lossy(&output.stderr).trim(),
+                        output.status
+                    )));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl StateSink for InfluxLogger {
+    fn insert_machine_state(&mut self, snapshot: &MachineStateSnapshot) -> Result<()> {
+        self.roles = snapshot.stepper_roles.clone();
+        self.write(&snapshot_lines(snapshot))?;
+        debug!(target: "machine_state_logger", "Wrote machine state line protocol: id={}", snapshot.state_id);
+        Ok(())
+    }
+
+    fn insert_operation(&mut self, event: &OperationEvent) -> Result<()> {
+        self.write(&operation_lines(event, &self.roles))?;
+        info!(target: "machine_state_logger", "Wrote operation line protocol: id={}, type={}", event.operation_id, event.operation_type);
+        Ok(())
+    }
+
+    fn insert_bump_event(&mut self, event: &BumpEvent) -> Result<()> {
+        self.write(&bump_lines(event))?;
+        info!(target: "machine_state_logger", "Wrote bump line protocol: stepper={}", event.stepper);
+        Ok(())
+    }
+}
+
+/// Tag keys and values, and measurement names: commas, equals signs and spaces escaped
+fn escape_tag(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            ',' | '=' | ' ' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' | '\r' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A string field value, quoted
+fn quote_field(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+/// One point: `measurement,tags fields timestamp`; nothing when there are no fields
+fn push_point(out: &mut String, measurement: &str, tags: &[(&str, String)], fields: &[(&str, String)], at: &DateTime<Utc>) {
+    if fields.is_empty() {
+        return;
+    }
+    let _ = write!(out, "{}_{}", INFLUX_PREFIX, measurement);
+    for (key, value) in tags {
+        let _ = write!(out, ",{}={}", key, escape_tag(value));
+    }
+    let fields: Vec<String> = fields.iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+    let _ = writeln!(out, " {} {}", fields.join(","), at.timestamp_nanos_opt().unwrap_or(0));
+}
+
+fn int(value: impl std::fmt::Display) -> String {
+    format!("{}i", value)
+}
+
+fn snapshot_lines(snapshot: &MachineStateSnapshot) -> String {
+    let mut out = String::new();
+    let at = &snapshot.recorded_at;
+    let host = ("host", snapshot.host.clone());
+    let mut fields = vec![
+        ("state_id", quote_field(&snapshot.state_id.to_string())),
+        ("bump_check_enable", snapshot.bump_check_enable.to_string()),
+        ("z_up_step", int(snapshot.z_up_step)),
+        ("z_down_step", int(snapshot.z_down_step)),
+        ("tune_rest", snapshot.tune_rest.to_string()),
+        ("x_rest", snapshot.x_rest.to_string()),
+        ("z_rest", snapshot.z_rest.to_string()),
+        ("lap_rest", snapshot.lap_rest.to_string()),
+        ("adjustment_level", int(snapshot.adjustment_level)),
+        ("retry_threshold", int(snapshot.retry_threshold)),
+        ("delta_threshold", int(snapshot.delta_threshold)),
+        ("z_variance_threshold", int(snapshot.z_variance_threshold)),
+    ];
+    if let Some(controls_id) = snapshot.controls_id {
+        fields.push(("controls_id", quote_field(&controls_id.to_string())));
+    }
+    push_point(&mut out, "machine_state", std::slice::from_ref(&host), &fields, at);
+
+    for (idx, position) in snapshot.stepper_positions.iter().enumerate() {
+        let mut tags = vec![host.clone(), ("stepper", idx.to_string())];
+        if let Some(role) = snapshot.stepper_roles.iter().find(|r| r.stepper_index == idx) {
+            tags.push(("role", role.role.clone()));
+            if let Some(string_idx) = role.string_index {
+                tags.push(("string", string_idx.to_string()));
+            }
+        }
+        let mut fields = vec![("position", int(position))];
+        if let Some(enabled) = snapshot.stepper_enabled.get(idx) {
+            fields.push(("enabled", enabled.to_string()));
+        }
+        push_point(&mut out, "stepper", &tags, &fields, at);
+    }
+
+    let channels = snapshot.voice_count.len().max(snapshot.amp_sum.len());
+    for ch_idx in 0..channels {
+        let mut tags = vec![host.clone(), ("channel", ch_idx.to_string())];
+        if let Some(string_idx) = snapshot.channel_strings.get(ch_idx).copied().flatten() {
+            tags.push(("string", string_idx.to_string()));
+        }
+        let mut fields = Vec::new();
+        let ints = [
+            ("voice_count", &snapshot.voice_count),
+            ("voice_count_min", &snapshot.voice_count_min),
+            ("voice_count_max", &snapshot.voice_count_max),
+            ("amp_sum_min", &snapshot.amp_sum_min),
+            ("amp_sum_max", &snapshot.amp_sum_max),
+        ];
+        for (key, values) in ints {
+            if let Some(value) = values.get(ch_idx) {
+                fields.push((key, int(value)));
+            }
+        }
+        // NaN and infinity are not valid field values
+        if let Some(amp_sum) = snapshot.amp_sum.get(ch_idx).filter(|a| a.is_finite()) {
+            fields.push(("amp_sum", amp_sum.to_string()));
+        }
+        push_point(&mut out, "channel", &tags, &fields, at);
+    }
+    out
+}
+
+fn operation_lines(event: &OperationEvent, roles: &[StepperRoleEntry]) -> String {
+    let mut out = String::new();
+    let at = &event.recorded_at;
+    let host = ("host", event.host.clone());
+    let operation = ("operation", event.operation_type.clone());
+    let mut fields = vec![
+        ("operation_id", quote_field(&event.operation_id.to_string())),
+        ("message", quote_field(&event.message)),
+        ("steppers_moved", int(event.stepper_indices.len())),
+    ];
+    if let Some(started_at) = event.started_at {
+        let seconds = (event.recorded_at - started_at).num_milliseconds() as f64 / 1000.0;
+        fields.push(("duration_s", seconds.to_string()));
+    }
+    if let Some(state_id) = event.state_id {
+        fields.push(("state_id", quote_field(&state_id.to_string())));
+    }
+    push_point(&mut out, "operation", &[host.clone(), operation.clone(), ("status", event.operation_status.clone())], &fields, at);
+
+    for (idx, position) in event.stepper_indices.iter().zip(&event.final_positions) {
+        let mut tags = vec![host.clone(), operation.clone(), ("stepper", idx.to_string())];
+        if let Some(string_idx) = roles.iter().find(|r| r.stepper_index == *idx).and_then(|r| r.string_index) {
+            tags.push(("string", string_idx.to_string()));
+        }
+        push_point(&mut out, "operation_stepper", &tags, &[("final_position", int(position))], at);
+    }
+    out
+}
+
+fn bump_lines(event: &BumpEvent) -> String {
+    let mut out = String::new();
+    let mut tags = vec![("host", event.host.clone()), ("stepper", event.stepper.to_string())];
+    if let Some(string_idx) = event.string {
+        tags.push(("string", string_idx.to_string()));
+    }
+    if let Some(ch_idx) = event.channel {
+        tags.push(("channel", ch_idx.to_string()));
+    }
+    let mut fields = vec![("z_position", int(event.z_position)), ("z_down_step", int(event.z_down_step))];
+    if let Some(x_position) = event.x_position {
+        fields.push(("x_position", int(x_position)));
+    }
+    let floats = [("amp_sum", event.amp_sum), ("amp_baseline", event.amp_baseline), ("dip", event.dip())];
+    for (key, value) in floats {
+        if let Some(value) = value.filter(|v| v.is_finite()) {
+            fields.push((key, value.to_string()));
+        }
+    }
+    fields.push(("verdict", quote_field(event.verdict().name())));
+    push_point(&mut out, "bump", &tags, &fields, &event.recorded_at);
+    out
+}
+
+/// Writer-thread counters reported in the health panel
+#[derive(Default)]
+struct DbWriterStats {
+    connected: AtomicBool,
+    backlog: AtomicUsize, // Queued but not yet written
+    errors: AtomicUsize,
+    dropped: AtomicUsize, // Rejected because the queue was full
+}
+
+/// Snapshot of the DB logger for status displays
+#[derive(Debug, Clone, Copy)]
+pub struct DbLoggerStatus {
+    pub connected: bool,
+    pub enabled: bool,
+    pub backlog: usize,
+    pub errors: usize,
+    pub dropped: usize,
 }
 
 /// Logging context - non-blocking, event-driven
@@ -195,91 +566,114 @@ impl MachineStateLogger {
 pub struct MachineStateLoggingContext {
     write_tx: Arc<Mutex<Option<SyncSender<DbWriteCommand>>>>,
     enabled: Arc<AtomicBool>,
+    stats: Arc<DbWriterStats>,
 }
 
 impl MachineStateLoggingContext {
     pub fn new(db_config: &DbSettings) -> Result<Self> {
         let logger = MachineStateLogger::new(db_config)?;
         let (write_tx, write_rx) = mpsc::sync_channel(100);
+        let stats = Arc::new(DbWriterStats::default());
+        stats.connected.store(true, Ordering::Relaxed);
+        let stats_clone = Arc::clone(&stats);
         thread::spawn(move || {
-            Self::db_writer_thread(logger, write_rx);
+            Self::db_writer_thread(vec![Box::new(logger)], write_rx, stats_clone);
         });
         Ok(Self {
             write_tx: Arc::new(Mutex::new(Some(write_tx))),
             enabled: Arc::new(AtomicBool::new(true)),
+            stats,
         })
     }
 
     pub fn new_nonblocking(db_config: DbSettings) -> Self {
+        Self::with_sinks(vec![SinkConfig::Postgres(db_config)])
+    }
+
+    /// Open `sinks` in the background; logging starts once at least one of them is open
+    pub fn with_sinks(sinks: Vec<SinkConfig>) -> Self {
         let write_tx = Arc::new(Mutex::new(None));
         let enabled = Arc::new(AtomicBool::new(false));
+        let stats = Arc::new(DbWriterStats::default());
         let write_tx_clone = Arc::clone(&write_tx);
         let enabled_clone = Arc::clone(&enabled);
+        let stats_clone = Arc::clone(&stats);
         thread::spawn(move || {
-            match MachineStateLogger::new(&db_config) {
-                Ok(logger) => {
-                    let (tx, rx) = mpsc::sync_channel(100);
-                    *write_tx_clone.lock().unwrap() = Some(tx);
-                    enabled_clone.store(true, Ordering::Relaxed);
-                    Self::db_writer_thread(logger, rx);
-                }
-                Err(e) => warn!(target: "machine_state_logger", "Background DB connection failed: {}", e),
+            let opened: Vec<Box<dyn StateSink>> = sinks
+                .iter()
+                .filter_map(|sink| match sink.open() {
+                    Ok(opened) => Some(opened),
+                    Err(e) => {
+                        warn!(target: "machine_state_logger", "Machine state backend unavailable: {}", e);
+                        None
+                    }
+                })
+                .collect();
+            if opened.is_empty() {
+                return;
             }
+            let (tx, rx) = mpsc::sync_channel(100);
+            *write_tx_clone.lock().unwrap() = Some(tx);
+            stats_clone.connected.store(true, Ordering::Relaxed);
+            enabled_clone.store(true, Ordering::Relaxed);
+            Self::db_writer_thread(opened, rx, stats_clone);
         });
-        Self { write_tx, enabled }
+        Self { write_tx, enabled, stats }
     }
 
-    fn db_writer_thread(mut logger: MachineStateLogger, write_rx: Receiver<DbWriteCommand>) {
+    fn db_writer_thread(mut sinks: Vec<Box<dyn StateSink>>, write_rx: Receiver<DbWriteCommand>, stats: Arc<DbWriterStats>) {
         info!(target: "machine_state_db_writer", "DB writer thread is active.");
         let mut commands_processed = 0;
         let mut errors = 0;
-        loop {
-            match write_rx.recv() {
-                Ok(DbWriteCommand::InsertMachineState(snapshot)) => {
-                    commands_processed += 1;
-                    if let Err(e) = logger.insert_machine_state(&snapshot) {
-                        errors += 1;
-                        error!(target: "machine_state_db_writer", "Failed to insert: {:#}", e);
-                    }
-                }
-                Ok(DbWriteCommand::InsertOperation(event)) => {
-                    commands_processed += 1;
-                    if let Err(e) = logger.insert_operation(&event) {
-                        errors += 1;
-                        error!(target: "machine_state_db_writer", "Failed to insert: {:#}", e);
-                    }
+        while let Ok(command) = write_rx.recv() {
+            commands_processed += 1;
+            stats.backlog.fetch_sub(1, Ordering::Relaxed);
+            // Each backend gets every record; one failing does not hold up the others
+            for sink in sinks.iter_mut() {
+                let written = match &command {
+                    DbWriteCommand::MachineState(snapshot) => sink.insert_machine_state(snapshot),
+                    DbWriteCommand::Operation(event) => sink.insert_operation(event),
+                    DbWriteCommand::BumpEvent(event) => sink.insert_bump_event(event),
+                };
+                if let Err(e) = written {
+                    errors += 1;
+                    stats.errors.fetch_add(1, Ordering::Relaxed);
+                    error!(target: "machine_state_db_writer", "Failed to insert: {}", e);
                 }
-                Err(_) => break,
             }
         }
+        stats.connected.store(false, Ordering::Relaxed);
         info!(target: "machine_state_db_writer", "DB writer stopped. Processed: {}, Errors: {}", commands_processed, errors);
     }
 
     pub fn insert_machine_state(&self, snapshot: &MachineStateSnapshot) {
-        if !self.enabled.load(Ordering::Relaxed) { return; }
-        if let Ok(guard) = self.write_tx.lock() {
-            if let Some(tx) = guard.as_ref() {
-                match tx.try_send(DbWriteCommand::InsertMachineState(snapshot.clone())) {
-                    Ok(_) => {},
-                    Err(std::sync::mpsc::TrySendError::Full(_)) => {
-                        warn!(target: "machine_state_logger", "{}", DB_BUFFER_FULL_MSG);
-                    }
-                    Err(_) => {},
-                }
-            }
-        }
+        self.send(DbWriteCommand::MachineState(snapshot.clone()));
     }
 
     pub fn insert_operation(&self, event: &OperationEvent) {
+        self.send(DbWriteCommand::Operation(event.clone()));
+    }
+
+    pub fn insert_bump_event(&self, event: &BumpEvent) {
+        self.send(DbWriteCommand::BumpEvent(event.clone()));
+    }
+
+    fn send(&self, command: DbWriteCommand) {
         if !self.enabled.load(Ordering::Relaxed) { return; }
         if let Ok(guard) = self.write_tx.lock() {
             if let Some(tx) = guard.as_ref() {
-                match tx.try_send(DbWriteCommand::InsertOperation(event.clone())) {
+                // Count before sending so the writer never decrements below zero
+                self.stats.backlog.fetch_add(1, Ordering::Relaxed);
+                match tx.try_send(command) {
                     Ok(_) => {},
                     Err(std::sync::mpsc::TrySendError::Full(_)) => {
-                        warn!(target: "machine_state_logger", "DB write buffer is full.");
+                        self.stats.backlog.fetch_sub(1, Ordering::Relaxed);
+                        self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                        warn!(target: "machine_state_logger", "{}", DB_BUFFER_FULL_MSG);
+                    }
+                    Err(_) => {
+                        self.stats.backlog.fetch_sub(1, Ordering::Relaxed);
                     }
-                    Err(_) => {},
                 }
             }
         }
@@ -292,5 +686,95 @@ impl MachineStateLoggingContext {
     pub fn is_enabled(&self) -> bool {
         self.enabled.load(Ordering::Relaxed)
     }
+
+    pub fn status(&self) -> DbLoggerStatus {
+        DbLoggerStatus {
+            connected: self.stats.connected.load(Ordering::Relaxed),
+            enabled: self.is_enabled(),
+            backlog: self.stats.backlog.load(Ordering::Relaxed),
+            errors: self.stats.errors.load(Ordering::Relaxed),
+            dropped: self.stats.dropped.load(Ordering::Relaxed),
+        }
+    }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_protocol_tags_host_string_and_stepper() {
+        let at = DateTime::parse_from_rfc3339("2026-03-01T20:00:00Z").unwrap().with_timezone(&Utc);
+        let snapshot = MachineStateSnapshot {
+            state_id: Uuid::nil(),
+            controls_id: None,
+            host: "stringdriver 2".to_string(),
+            recorded_at: at,
+            stepper_positions: vec![1200, 40],
+            stepper_enabled: vec![true, false],
+            bump_check_enable: true,
+            z_up_step: 2,
+            z_down_step: -2,
+            tune_rest: 1.0,
+            x_rest: 1.0,
+            z_rest: 0.5,
+            lap_rest: 4.0,
+            adjustment_level: 4,
+            retry_threshold: 50,
+            delta_threshold: 50,
+            z_variance_threshold: 50,
+            voice_count: vec![3],
+            amp_sum: vec![f32::NAN],
+            voice_count_min: vec![2],
+            voice_count_max: vec![8],
+            amp_sum_min: vec![20],
+            amp_sum_max: vec![250],
+            stepper_roles: vec![
+                StepperRoleEntry { stepper_index: 0, role: "x_axis".to_string(), string_index: None },
+                StepperRoleEntry { stepper_index: 1, role: "z_in".to_string(), string_index: Some(0) },
+            ],
+            channel_strings: vec![Some(0)],
+            build_info: String::new(),
+        };
+        let lines = snapshot_lines(&snapshot);
+        let lines: Vec<&str> = lines.lines().collect();
+        assert_eq!(lines.len(), 4, "{:?}", lines);
+        assert!(lines[0].starts_with("stringdriver_machine_state,host=stringdriver\\ 2 state_id=\"00000000-0000-0000-0000-000000000000\",bump_check_enable=true,z_up_step=2i,"));
+        assert!(lines[0].ends_with(" 1772395200000000000"));
+        assert_eq!(lines[2], "stringdriver_stepper,host=stringdriver\\ 2,stepper=1,role=z_in,string=0 position=40i,enabled=false 1772395200000000000");
+        // A NaN amp sum is left out rather than breaking the batch
+        assert_eq!(lines[3], "stringdriver_channel,host=stringdriver\\ 2,channel=0,string=0 voice_count=3i,voice_count_min=2i,voice_count_max=8i,amp_sum_min=20i,amp_sum_max=250i 1772395200000000000");
+
+        let event = OperationEvent {
+            operation_id: Uuid::nil(),
+            state_id: None,
+            host: "sd2".to_string(),
+            recorded_at: at,
+            operation_type: "z_adjust".to_string(),
+            operation_status: "completed".to_string(),
+            message: "Adjusted \"string 0\"\ndone".to_string(),
+            stepper_indices: vec![1],
+            final_positions: vec![44],
+            started_at: Some(at - chrono::Duration::milliseconds(12500)),
+        };
+        let lines = operation_lines(&event, &snapshot.stepper_roles);
+        let lines: Vec<&str> = lines.lines().collect();
+        assert_eq!(lines[0], "stringdriver_operation,host=sd2,operation=z_adjust,status=completed operation_id=\"00000000-0000-0000-0000-000000000000\",message=\"Adjusted \\\"string 0\\\"\\ndone\",steppers_moved=1i,duration_s=12.5 1772395200000000000");
+        assert_eq!(lines[1], "stringdriver_operation_stepper,host=sd2,operation=z_adjust,stepper=1,string=0 final_position=44i 1772395200000000000");
+
+        let bump = BumpEvent {
+            recorded_at: at,
+            host: "sd2".to_string(),
+            stepper: 1,
+            string: Some(0),
+            channel: Some(0),
+            x_position: Some(1200),
+            z_position: -6,
+            z_down_step: -2,
+            amp_sum: Some(30.0),
+            amp_baseline: Some(120.0),
+        };
+        assert_eq!(bump_lines(&bump), "stringdriver_bump,host=sd2,stepper=1,string=0,channel=0 z_position=-6i,z_down_step=-2i,x_position=1200i,amp_sum=30,amp_baseline=120,dip=0.75,verdict=\"contact\" 1772395200000000000\n");
+    }
+}
@@ -3,44 +3,82 @@
 /// Single source of truth: all configuration comes from string_driver.yaml
 /// via config_loader - no hardcoded fallbacks.
 
-use anyhow::{anyhow, Result};
+use crate::error::{Error, Result};
+use crate::get_results::{read_audio_control, AudioControl};
 use gethostname::gethostname;
-use crate::config_loader::{load_operations_settings, load_arduino_settings, load_gpio_settings, mainboard_tuner_indices};
+use crate::alerting::Alerter;
+use crate::hooks::Hooks;
+use crate::instrument_state::{InstrumentState, StateMachine};
+use crate::arbitration::{Admission, Arbiter, Category, Priority, Request};
+use crate::sensor_health::{SensorFault, SensorHealth};
+use crate::bump_log::{AmpHistory, BumpEvent};
+use crate::audio_trigger::{AudioTrigger, TriggerAction, TriggerState};
+use crate::height_map::{HeightMap, HeightPoint};
+use crate::scene::Scene;
+use crate::config_loader::{load_alert_settings, load_audio_trigger, load_hook_settings, load_operations_settings, validate_channel_map, load_arduino_settings, load_gpio_settings, load_z_controller_settings, load_response_map_settings, load_height_calibration_settings, load_rest_overrides, load_park_settings, load_sensor_health_settings, load_time_budgets, load_x_clearance, load_x_regions, load_x_verify_settings, load_z_home_settings, load_bow_drive_settings, load_damper_settings, BowDriveSettings, mainboard_tuner_indices, string_label, AlertEvent, HookEvent, ClearanceAction, HeightCalibrationSettings, ParkSettings, ResponseMapSettings, RestOverrides, BumpOverrides, SensorHealthSettings, StringInfo, TimeBudgets, XClearance, XRegion, XVerifySettings, AudioTriggerSettings, ParameterPreset, ZControllerSettings, ZHomeSettings};
 use crate::gpio;
-use std::collections::{HashMap, HashSet};
+use crate::dampers::Dampers;
+use crate::effective_config::{Baseline, EffectiveConfig, Source};
+use crate::z_controller::{ControllerMetric, ZController};
+use crate::partials_buffer::{PartialsFrame, PartialsReader};
+use crate::topology::{Bank, StepperRole, Topology, TunerLayout};
+use crate::plugins;
+use crate::tension::{self, TunerGuard};
+use crate::audmon_client::{self, Freshness};
+use crate::profiling::{self, Probe};
+pub use crate::plugins::{OperationDescriptor, OperationParam, Requirement};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::{Arc, Mutex};
-use std::fs::OpenOptions;
-use std::time::Duration;
-use memmap2::Mmap;
-
-/// Type alias for partials data: Vec<Vec<(f32, f32)>> where each inner Vec is a channel's partials (freq, amp)
-type PartialsData = Vec<Vec<(f32, f32)>>;
-
-/// Type alias for partials slot (matches partials_slot::PartialsSlot)
-type PartialsSlot = Arc<Mutex<Option<PartialsData>>>;
-
-/// Calculate voice count per channel from partials data
-/// Returns Vec<usize> where each element is the count of non-zero amplitudes for that channel
-fn calculate_voice_count(partials: &PartialsData) -> Vec<usize> {
-    partials.iter()
-        .map(|channel_partials| {
-            channel_partials.iter()
-                .filter(|&&(_, amp)| amp > 0.0)
-                .count()
-        })
-        .collect()
+use std::sync::atomic::{AtomicU64, AtomicUsize};
+use std::time::{Duration, Instant};
+
+/// Bumps kept for the logger; the oldest go first when nothing takes them
+const MAX_PENDING_BUMPS: usize = 256;
+
+/// Amplitude a partial must exceed to count as a voice, so the FFT noise
+/// floor doesn't read as a full set of voices.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VoiceFloor {
+    /// Floors are fractions of the channel's loudest partial rather than absolute amplitudes
+    pub relative: bool,
+    /// Floor for channels without their own entry (0.0 = any non-zero amplitude counts)
+    pub default: f32,
+    /// Per-channel floors (channel index -> floor)
+    pub channels: HashMap<usize, f32>,
 }
 
-/// Calculate amplitude sum per channel from partials data
-/// Returns Vec<f32> where each element is the sum of amplitudes for that channel
-fn calculate_amp_sum(partials: &PartialsData) -> Vec<f32> {
-    partials.iter()
-        .map(|channel_partials| {
-            channel_partials.iter()
-                .map(|&(_, amp)| amp)
-                .sum()
-        })
-        .collect()
+impl VoiceFloor {
+    /// Configured floor for a channel (absolute amplitude or fraction of channel max)
+    pub fn floor(&self, ch_idx: usize) -> f32 {
+        self.channels.get(&ch_idx).copied().unwrap_or(self.default)
+    }
+    
+    /// Amplitude a partial in this channel must exceed
+    fn threshold(&self, ch_idx: usize, channel_partials: &[(f32, f32)]) -> f32 {
+        let floor = self.floor(ch_idx).max(0.0);
+        if self.relative {
+            let channel_max = channel_partials.iter().map(|&(_, amp)| amp).fold(0.0, f32::max);
+            floor * channel_max
+        } else {
+            floor
+        }
+    }
+}
+
+/// Voice count of one channel: amplitudes above that channel's voice floor
+fn calculate_voice_count(ch_idx: usize, channel_partials: &[(f32, f32)], voice_floor: &VoiceFloor) -> usize {
+    let threshold = voice_floor.threshold(ch_idx, channel_partials);
+    channel_partials.iter()
+        .filter(|&&(_, amp)| amp > threshold)
+        .count()
+}
+
+/// Amplitude sum of one channel
+fn calculate_amp_sum(channel_partials: &[(f32, f32)]) -> f32 {
+    channel_partials.iter()
+        .map(|&(_, amp)| amp)
+        .sum()
 }
 
 /// Calculate delta (difference) in amplitude sum between previous and current values per channel
@@ -56,15 +94,728 @@ fn calculate_amp_delta(previous: &[f32], current: &[f32]) -> Vec<f32> {
         .collect()
 }
 
+/// Lowest Z position the calibration and homing moves go to
+pub(crate) const Z_MIN_POS: i32 = 0;
+
+/// Whether a Z stepper may be sent to `target`: Z_MIN_POS up to its max position
+fn z_within_limits(max_positions: &HashMap<usize, i32>, stepper: usize, target: i32) -> bool {
+    target >= Z_MIN_POS && max_positions.get(&stepper).map_or(true, |&max| target <= max)
+}
+
+/// Z_MAX_STEP when the YAML leaves it out: five times the z_up_step
+fn default_z_max_step(z_up_step: i32) -> i32 {
+    z_up_step.abs() * 5
+}
+
+/// Scale a base Z step by how far a channel is outside its band.
+/// `error_ratio` is the overshoot divided by the band width (0 = at the edge).
+/// Result keeps the sign of `base_step` and its magnitude is clamped to
+/// [|base_step|, max_step].
+fn scale_z_step(base_step: i32, error_ratio: f32, max_step: i32) -> i32 {
+    let base = base_step.abs();
+    if base == 0 {
+        return 0;
+    }
+    let max = max_step.max(base);
+    let scaled = ((base as f32) * (1.0 + error_ratio.max(0.0))).round() as i32;
+    scaled.clamp(base, max) * base_step.signum()
+}
+
+/// Normalised distance of `value` outside [min, max]; 0.0 when inside the band.
+fn band_error_ratio(value: f32, min: f32, max: f32) -> f32 {
+    let width = (max - min).max(1.0);
+    if value > max {
+        (value - max) / width
+    } else if value < min {
+        (min - value) / width
+    } else {
+        0.0
+    }
+}
+
+/// Per-channel values with an X_REGIONS override laid over them: one region value
+/// applies to every channel, a list replaces channel by channel.
+fn overlay_thresholds<T: Copy>(base: &[T], region: &[T], channels: usize) -> Vec<T> {
+    match region {
+        [] => base.to_vec(),
+        [all] => vec![*all; base.len().max(channels)],
+        per_channel => (0..base.len().max(per_channel.len()))
+            .map(|ch| per_channel.get(ch).copied().unwrap_or_else(|| base[ch]))
+            .collect(),
+    }
+}
+
+/// Sweep pass criteria per channel
+struct PassThresholds {
+    min_amp: Vec<f32>,
+    max_amp: Vec<f32>,
+    min_voices: Vec<usize>,
+    max_voices: Vec<usize>,
+}
+
+/// Which way x_sweep moves the carriage on each lap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SweepDirection {
+    RightLeft, // x_start -> x_finish
+    LeftRight, // x_finish -> x_start
+    PingPong,  // Alternating, starting x_start -> x_finish
+}
+
+impl SweepDirection {
+    /// Operation name used in messages
+    pub fn name(self) -> &'static str {
+        match self {
+            SweepDirection::RightLeft => "right_left_move",
+            SweepDirection::LeftRight => "left_right_move",
+            SweepDirection::PingPong => "ping_pong_move",
+        }
+    }
+
+    /// (from, to) X positions of lap `lap` (0-based)
+    fn leg(self, lap: u32, x_start: i32, x_finish: i32) -> (i32, i32) {
+        match (self, lap % 2) {
+            (SweepDirection::RightLeft, _) | (SweepDirection::PingPong, 0) => (x_start, x_finish),
+            _ => (x_finish, x_start),
+        }
+    }
+}
+
+/// Seek operations every linear axis has: `<axis>_home`, `<axis>_away`, `<axis>_calibrate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AxisSeek {
+    Home,      // To the home switch, which is position 0
+    Away,      // To the away switch or the max position
+    Calibrate, // To the nearer switch and back
+}
+
+impl AxisSeek {
+    pub const ALL: [AxisSeek; 3] = [AxisSeek::Home, AxisSeek::Away, AxisSeek::Calibrate];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AxisSeek::Home => "Home",
+            AxisSeek::Away => "Away",
+            AxisSeek::Calibrate => "Calibrate",
+        }
+    }
+
+    /// Operation name for `axis`, e.g. `y_home`
+    pub fn operation(self, axis: &str) -> String {
+        format!("{}_{}", axis.to_lowercase(), self.label().to_lowercase())
+    }
+}
+
+const fn param(key: &'static str, description: &'static str, min: f64, max: f64, default: f64, integer: bool) -> OperationParam {
+    OperationParam::new(key, description, min, max, default, integer)
+}
+
+const Z_UP_STEP: OperationParam = param("Z_UP_STEP", "Z steps away from the string per move", 2.0, 10.0, 2.0, true);
+const Z_DOWN_STEP: OperationParam = param("Z_DOWN_STEP", "Z steps towards the string per move", -10.0, -2.0, -2.0, true);
+const Z_REST: OperationParam = param("Z_REST", "Seconds to wait after each Z move", 0.0, 100.0, 1.0, false);
+const X_REST: OperationParam = param("X_REST", "Seconds to wait after each X move", 0.0, 100.0, 5.0, false);
+const LAP_REST: OperationParam = param("LAP_REST", "Seconds to wait between passes or laps", 0.0, 100.0, 4.0, false);
+const X_START: OperationParam = param("X_START", "X position where a sweep starts", -10000.0, 10000.0, 100.0, true);
+const X_FINISH: OperationParam = OperationParam {
+    key: "X_FINISH",
+    description: "X position where a sweep ends (default X_MAX_POS - 100)",
+    min: -10000.0,
+    max: 10000.0,
+    default: None,
+    integer: true,
+};
+const PERFORMANCE_REST: OperationParam = param("PERFORMANCE_REST", "Seconds between performance_mode cycles", 0.0, 60.0, 1.0, false);
+const X_STEP: OperationParam = param("X_STEP", "X steps between sweep positions", 1.0, 1000.0, 10.0, true);
+const SWEEP_PARAMS: &[OperationParam] = &[
+    X_START,
+    X_FINISH,
+    X_STEP,
+    param("ADJUSTMENT_LEVEL", "Passing z_adjust runs needed at a position before moving on", 1.0, 100.0, 4.0, true),
+    param("RETRY_THRESHOLD", "z_adjust runs at one position before recalibrating", 1.0, 1000.0, 50.0, true),
+    param("DELTA_THRESHOLD", "Z travel at one position before recalibrating", 1.0, 1000.0, 50.0, true),
+    param("Z_VARIANCE_THRESHOLD", "Spread between Z positions before recalibrating", 1.0, 1000.0, 50.0, true),
+    Z_UP_STEP,
+    Z_DOWN_STEP,
+    X_REST,
+    Z_REST,
+    LAP_REST,
+];
+
+const fn operation(
+    name: &'static str,
+    label: &'static str,
+    description: &'static str,
+    requires: &'static [Requirement],
+    params: &'static [OperationParam],
+) -> OperationDescriptor {
+    OperationDescriptor::new(name, label, description, requires, params)
+}
+
+/// Every operation with a fixed name, in menu order; the axis seeks follow per axis
+/// (`operation_catalog`), park comes last
+pub const OPERATIONS: &[OperationDescriptor] = &[
+    operation("z_calibrate", "Z Calibrate", "Lower each Z pair until its touch sensor trips, then back off", &[Requirement::Gpio], &[Z_DOWN_STEP, Z_REST]).broadcast(),
+    operation("z_home", "Z Home", "Calibrate Z, then z_adjust every string into range", &[Requirement::Gpio], &[Z_UP_STEP, Z_DOWN_STEP, Z_REST]).broadcast(),
+    operation("z_adjust", "Z Adjust", "Move each Z pair until its string's amplitude and voice count are in range", &[], &[Z_UP_STEP, Z_DOWN_STEP, Z_REST, LAP_REST]).broadcast(),
+    operation("z_hold", "Z Hold (PID)", "Hold each string on its setpoint with the Z_CONTROLLER loop", &[], &[]).until_break(),
+    operation("performance_mode", "Performance Mode", "z_adjust every cycle with bump checks, for the length of a performance", &[], &[PERFORMANCE_REST, Z_UP_STEP, Z_DOWN_STEP])
+        .performer()
+        .until_break(),
+    operation("response_map", "Response Map", "Record amplitude against Z depth at each X position", &[Requirement::XAxis], &[X_START, X_FINISH, X_STEP, X_REST, Z_REST]).broadcast(),
+    operation("height_calibrate", "Height Calibrate", "Sweep X and record the settled Z height of every string along it", &[Requirement::XAxis], SWEEP_PARAMS).broadcast(),
+    operation("bump_check", "Bump Check", "Raise any Z stepper whose touch sensor is pressed until it clears", &[Requirement::Gpio], &[Z_UP_STEP, Z_REST]).broadcast(),
+    operation("bump_probe", "Bump Probe", "Read every Z touch sensor and report which are pressed, without moving", &[Requirement::Gpio], &[]).read_only(),
+    operation("right_left_move", "Right Left Move", "Sweep from X_START to X_FINISH, adjusting Z at each position", &[Requirement::XAxis], SWEEP_PARAMS),
+    operation("left_right_move", "Left Right Move", "Sweep from X_FINISH to X_START, adjusting Z at each position", &[Requirement::XAxis], SWEEP_PARAMS),
+    operation("ping_pong_move", "Ping Pong Move", "Sweep back and forth between X_START and X_FINISH, adjusting Z", &[Requirement::XAxis], SWEEP_PARAMS),
+    operation("park", "Park", "Raise Z to the PARK position, move X to park and check no string is touching", &[], &[Z_REST, X_REST]).performer().broadcast(),
+];
+
+/// The seek operation of one axis (`x_home`, `y_calibrate`, ...)
+fn axis_seek_descriptor(axis: &str, seek: AxisSeek) -> OperationDescriptor {
+    let is_x = axis == "X";
+    let description = match seek {
+        AxisSeek::Home => "Move the axis to its home switch and set position 0",
+        AxisSeek::Away => "Move the axis to its away switch or max position",
+        AxisSeek::Calibrate => "Seek the nearer limit switch and come back",
+    };
+    OperationDescriptor {
+        name: Cow::Owned(seek.operation(axis)),
+        label: Cow::Owned(format!("{} {}", axis, seek.label())),
+        description,
+        requires: if is_x { &[Requirement::Gpio, Requirement::XAxis] } else { &[Requirement::Gpio] },
+        params: if is_x { &[X_REST] } else { &[] },
+        performer: false,
+        runs_until_break: false,
+        // x_home and x_calibrate re-home the carriage, so they go out to every instrument
+        broadcast: is_x && seek != AxisSeek::Away,
+        read_only: false,
+    }
+}
+
+/// All operations for a host with these linear axes (X, then the AXES names),
+/// then the registered custom operations (see plugins)
+pub fn operation_catalog(axes: &[&str]) -> Vec<OperationDescriptor> {
+    let (park, fixed) = OPERATIONS.split_last().expect("OPERATIONS is not empty");
+    let seeks = axes.iter().flat_map(|axis| AxisSeek::ALL.map(|seek| axis_seek_descriptor(axis, seek)));
+    let custom = plugins::registered().into_iter().map(|op| op.descriptor());
+    fixed.iter().cloned().chain(seeks).chain(custom).chain(std::iter::once(park.clone())).collect()
+}
+
+/// Plain-text listing of `catalog`, as shown by `--help`
+pub fn operations_help(catalog: &[OperationDescriptor]) -> String {
+    let mut text = String::from("Operations:\n");
+    for op in catalog {
+        text.push_str(&format!("  {:<18} {}\n", op.name, op.description));
+        if !op.requires.is_empty() {
+            let requires: Vec<&str> = op.requires.iter().map(|r| r.describe()).collect();
+            text.push_str(&format!("  {:<18} needs {}\n", "", requires.join(", ")));
+        }
+        for p in op.params {
+            let default = p.default.map_or("derived".to_string(), |d| d.to_string());
+            text.push_str(&format!("  {:<18} {} ({}..{}, default {}): {}\n", "", p.key, p.min, p.max, default, p.description));
+        }
+    }
+    text
+}
+
+/// The instrument as a custom operation sees it during `Operations::run_plugin`
+struct PluginHost<'a, T: StepperOperations> {
+    ops: &'a Operations,
+    stepper_ops: &'a mut T,
+    positions: &'a mut [i32],
+    max_positions: &'a HashMap<usize, i32>,
+    exit_flag: Option<&'a Arc<std::sync::atomic::AtomicBool>>,
+}
+
+impl<T: StepperOperations> plugins::OperationHost for PluginHost<'_, T> {
+    fn positions(&self) -> Vec<i32> {
+        self.positions.to_vec()
+    }
+
+    fn x_index(&self) -> Option<usize> {
+        self.ops.topology.x_index()
+    }
+
+    fn z_pair_for_channel(&self, channel: usize) -> Option<(usize, usize)> {
+        self.ops.z_pair_for_channel(channel)
+    }
+
+    fn rel_move(&mut self, stepper: usize, delta: i32) -> plugins::PluginResult<()> {
+        let current = *self.positions.get(stepper).ok_or_else(|| format!("no stepper {}", stepper))?;
+        self.abs_move(stepper, current + delta)
+    }
+
+    fn abs_move(&mut self, stepper: usize, position: i32) -> plugins::PluginResult<()> {
+        let Some(current) = self.positions.get(stepper).copied() else {
+            return Err(format!("no stepper {}", stepper));
+        };
+        if let Some(&max) = self.max_positions.get(&stepper) {
+            if position > max {
+                return Err(format!("{} cannot move to {} (max {})", self.ops.stepper_label(stepper), position, max));
+            }
+        }
+        if let Some(StepperRole::Tuner { string }) = self.ops.topology.role_of(Bank::Main, stepper) {
+            self.ops.check_tuner_move(string, current, position).map_err(|e| e.to_string())?;
+        }
+        let delta = position - current;
+        let result = if Some(stepper) == self.x_index() {
+            self.ops.rel_move_x(self.stepper_ops, stepper, delta)
+        } else {
+            self.ops.rel_move_z(self.stepper_ops, stepper, delta)
+        };
+        result.map_err(|e| e.to_string())?;
+        self.positions[stepper] = position;
+        Ok(())
+    }
+
+    fn amp_sum(&self) -> Vec<f32> {
+        self.ops.get_amp_sum()
+    }
+
+    fn voice_count(&self) -> Vec<usize> {
+        self.ops.get_voice_count()
+    }
+
+    fn bump_check(&mut self) -> plugins::PluginResult<String> {
+        self.ops.bump_check(None, self.positions, self.max_positions, self.stepper_ops, self.exit_flag).map(|report| report.message).map_err(|e| e.to_string())
+    }
+
+    fn rest_z(&self) {
+        self.ops.rest_z()
+    }
+
+    fn rest_x(&self) {
+        self.ops.rest_x()
+    }
+
+    fn parameter(&self, key: &str) -> Option<f64> {
+        self.ops.parameter(key)
+    }
+
+    fn stop_requested(&self) -> bool {
+        self.exit_flag.is_some_and(|exit| exit.load(std::sync::atomic::Ordering::Relaxed))
+    }
+}
+
+/// A linear axis with a home switch at 0 and an away switch at its max position:
+/// X from the X_* keys, or an AXES entry (e.g. Y tilting the bow wheel)
+#[derive(Debug, Clone)]
+pub struct LinearAxis {
+    pub name: String,
+    pub step_index: usize,
+    pub max_pos: Option<i32>, // 0 = dummy stepper, seeks skipped
+    rest: Arc<Mutex<f32>>,    // Seconds after each move; X's is x_rest
+}
+
+impl LinearAxis {
+    fn is_x(&self) -> bool {
+        self.name == "X"
+    }
+
+    /// Where the max position is configured, for messages
+    fn max_pos_key(&self) -> String {
+        if self.is_x() { "X_MAX_POS".to_string() } else { format!("AXES {} MAX_POS", self.name) }
+    }
+}
+
+/// Pass criteria (per channel, from the GUI) and lap limit for x_sweep
+#[derive(Debug, Clone, Copy)]
+pub struct SweepParams<'a> {
+    pub min_thresholds: &'a [f32],
+    pub max_thresholds: &'a [f32],
+    pub min_voices: &'a [usize],
+    pub max_voices: &'a [usize],
+    pub laps: u32, // 0 = one lap for RightLeft/LeftRight, until stopped for PingPong
+    pub converge_below: u32, // Stop after a lap with fewer Z moves than this (0 = off)
+}
+
+/// Per-channel bands the band-driven operations (z_adjust, the sweeps, z_hold,
+/// performance_mode) work to: amp sums and voice counts, from the GUI rows or a preset
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelBands {
+    pub amp_sum_min: Vec<i32>,
+    pub amp_sum_max: Vec<i32>,
+    pub voice_count_min: Vec<i32>,
+    pub voice_count_max: Vec<i32>,
+}
+
+impl ChannelBands {
+    /// Amp sums 20-250, voices from 2 (or the cap) up to the analysis' voice cap
+    pub fn defaults(channels: usize, voice_cap: i32) -> Self {
+        let voice_cap = voice_cap.max(1);
+        Self {
+            amp_sum_min: vec![20; channels],
+            amp_sum_max: vec![250; channels],
+            voice_count_min: vec![voice_cap.min(2); channels],
+            voice_count_max: vec![voice_cap; channels],
+        }
+    }
+
+    /// Take the channel lists `preset` names; a list saved with fewer channels
+    /// leaves the rest alone
+    pub fn apply(&mut self, preset: &ParameterPreset) {
+        let lists = [
+            (&mut self.voice_count_min, &preset.voice_count_min),
+            (&mut self.voice_count_max, &preset.voice_count_max),
+            (&mut self.amp_sum_min, &preset.amp_sum_min),
+            (&mut self.amp_sum_max, &preset.amp_sum_max),
+        ];
+        for (current, saved) in lists {
+            if let Some(saved) = saved {
+                for (dst, &src) in current.iter_mut().zip(saved) {
+                    *dst = src;
+                }
+            }
+        }
+    }
+}
+
+/// What one lap of x_sweep did
+#[derive(Debug, Clone, Default)]
+pub struct LapReport {
+    pub from: i32,
+    pub to: i32,
+    pub positions: u32,    // X positions that met the adjustment level
+    pub attempts: u32,     // z_adjust + bump_check passes over the whole lap
+    pub calibrations: u32, // z_calibrate runs (retry or Z variance threshold)
+    pub z_moves: u64,      // Z stepper moves of any kind (adjust, bump, calibrate)
+    pub elapsed: Duration,
+    pub cancelled: bool,
+}
+
+impl LapReport {
+    /// Attempts needed per X position - falls as the instrument converges
+    pub fn avg_attempts(&self) -> f32 {
+        self.attempts as f32 / self.positions.max(1) as f32
+    }
+
+    pub fn summary(&self, lap: u32) -> String {
+        format!(
+            "Lap {} (X {} -> {}){}: {} positions, {} Z moves, {:.1} attempts per X, {} calibrations in {:.1}s",
+            lap, self.from, self.to, if self.cancelled { " cancelled" } else { "" },
+            self.positions, self.z_moves, self.avg_attempts(), self.calibrations, self.elapsed.as_secs_f32()
+        )
+    }
+}
+
+/// Lap reports of the running or last x_sweep, for the status file
+#[derive(Debug, Clone)]
+pub struct SweepReport {
+    pub direction: SweepDirection,
+    pub laps: Vec<LapReport>,
+    pub converged: bool, // Stopped early on SweepParams::converge_below
+}
+
+/// How a finished operation ended, for the status file and inbox results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationOutcome {
+    Succeeded,
+    Failed,
+    TimedOut, // Stopped by its time budget; Z retracted
+}
+
+impl OperationOutcome {
+    pub fn of<T>(result: &Result<T>) -> Self {
+        match result {
+            Ok(_) => OperationOutcome::Succeeded,
+            Err(Error::OperationTimedOut { .. }) => OperationOutcome::TimedOut,
+            Err(_) => OperationOutcome::Failed,
+        }
+    }
+
+    pub fn succeeded(self) -> bool {
+        self == OperationOutcome::Succeeded
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            OperationOutcome::Succeeded => "succeeded",
+            OperationOutcome::Failed => "failed",
+            OperationOutcome::TimedOut => "timeout",
+        }
+    }
+}
+
+/// A pass criterion a channel missed, in z_adjust's order of precedence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CriterionFailure {
+    VoicesHigh,
+    VoicesLow,
+    AmpHigh,
+    AmpLow,
+}
+
+impl CriterionFailure {
+    pub fn name(self) -> &'static str {
+        match self {
+            CriterionFailure::VoicesHigh => "voices > max",
+            CriterionFailure::VoicesLow => "voices < min",
+            CriterionFailure::AmpHigh => "amp > max",
+            CriterionFailure::AmpLow => "amp < min",
+        }
+    }
+}
+
+/// One channel's values against its bands at the last pass check
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelEvaluation {
+    pub channel: usize,
+    pub amp_sum: f32,
+    pub amp_band: (f32, f32),
+    pub voices: usize,
+    pub voice_band: (usize, usize),
+    pub failures: Vec<CriterionFailure>, // Empty when the channel passed
+    pub excluded: Option<&'static str>,  // Muted / not soloed: not part of the pass
+}
+
+impl ChannelEvaluation {
+    pub fn passed(&self) -> bool {
+        self.excluded.is_some() || self.failures.is_empty()
+    }
+}
+
+/// The last x_sweep pass check: why the pass at an X position failed (or that it passed)
+#[derive(Debug, Clone)]
+pub struct PassEvaluation {
+    pub x: i32,
+    pub attempt: i32,
+    pub bump_check_passed: bool,
+    pub channels: Vec<ChannelEvaluation>,
+    pub at: Instant,
+}
+
+impl PassEvaluation {
+    pub fn passed(&self) -> bool {
+        self.bump_check_passed && self.channels.iter().all(ChannelEvaluation::passed)
+    }
+}
+
+/// Check every channel against its amp_sum and voice_count bands; missing
+/// thresholds use the same defaults as z_adjust
+fn evaluate_channels(
+    amp_sums: &[f32],
+    voice_counts: &[usize],
+    min_thresholds: &[f32],
+    max_thresholds: &[f32],
+    min_voices: &[usize],
+    max_voices: &[usize],
+    voice_cap: usize,
+    channel_mask: &ChannelMask,
+) -> Vec<ChannelEvaluation> {
+    let num_channels = amp_sums.len().min(voice_counts.len());
+    (0..num_channels)
+        .map(|ch_idx| {
+            let amp_sum = amp_sums[ch_idx];
+            let voices = voice_counts[ch_idx];
+            let amp_band = (min_thresholds.get(ch_idx).copied().unwrap_or(20.0), max_thresholds.get(ch_idx).copied().unwrap_or(100.0));
+            let voice_band = (min_voices.get(ch_idx).copied().unwrap_or(0), max_voices.get(ch_idx).copied().unwrap_or(voice_cap));
+            let failures = [
+                (voices > voice_band.1, CriterionFailure::VoicesHigh),
+                (voices < voice_band.0, CriterionFailure::VoicesLow),
+                (amp_sum > amp_band.1, CriterionFailure::AmpHigh),
+                (amp_sum < amp_band.0, CriterionFailure::AmpLow),
+            ]
+            .into_iter()
+            .filter_map(|(failed, failure)| failed.then_some(failure))
+            .collect();
+            ChannelEvaluation { channel: ch_idx, amp_sum, amp_band, voices, voice_band, failures, excluded: channel_mask.excluded_reason(ch_idx) }
+        })
+        .collect()
+}
+
+/// Per-channel mute/solo for audio-driven operations. A muted channel's analysis
+/// is ignored by z_adjust (no stepping, not part of the pass criteria); when any
+/// channel is soloed, only soloed channels are considered. Unlike disabling the
+/// steppers, bump_check keeps protecting every string.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChannelMask {
+    pub muted: HashSet<usize>,
+    pub soloed: HashSet<usize>,
+}
+
+impl ChannelMask {
+    /// Why the channel is left out, or None if it takes part
+    pub fn excluded_reason(&self, ch_idx: usize) -> Option<&'static str> {
+        if self.muted.contains(&ch_idx) {
+            Some("muted")
+        } else if !self.soloed.is_empty() && !self.soloed.contains(&ch_idx) {
+            Some("not soloed")
+        } else {
+            None
+        }
+    }
+    
+    pub fn includes(&self, ch_idx: usize) -> bool {
+        self.excluded_reason(ch_idx).is_none()
+    }
+}
+
 /// Stepper enable state tracking (index -> enabled)
 type StepperEnabled = Arc<Mutex<HashMap<usize, bool>>>;
 
+/// Per-string Z steps (up, down); None uses the global step
+type StringZSteps = Arc<Mutex<Vec<(Option<i32>, Option<i32>)>>>;
+
+/// Strategy used by bump_check to back a Z-stepper off the string.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BumpCheckStrategy {
+    /// Retract increment per move; None follows the current z_up_step
+    pub retract_step: Option<i32>,
+    /// Consecutive clear sensor readings required before declaring the stepper cleared
+    pub clear_readings: u32,
+    /// Seconds to wait after each move (and between readings) before reading the sensor
+    pub settle_rest: f32,
+    /// Extra retract applied once cleared (0 = none)
+    pub final_margin: i32,
+}
+
+impl Default for BumpCheckStrategy {
+    fn default() -> Self {
+        Self {
+            retract_step: None,
+            clear_readings: 1,
+            settle_rest: 0.0,
+            final_margin: 0,
+        }
+    }
+}
+
+impl BumpCheckStrategy {
+    /// This strategy with the values `overrides` sets replaced
+    pub fn with_overrides(self, overrides: BumpOverrides) -> Self {
+        Self {
+            retract_step: overrides.retract_step.or(self.retract_step),
+            clear_readings: overrides.clear_readings.unwrap_or(self.clear_readings),
+            settle_rest: overrides.settle_rest.unwrap_or(self.settle_rest),
+            final_margin: overrides.final_margin.unwrap_or(self.final_margin),
+        }
+    }
+
+    /// Every value of this strategy, as a preset's BUMP
+    pub fn overrides(&self) -> BumpOverrides {
+        BumpOverrides {
+            retract_step: self.retract_step,
+            clear_readings: Some(self.clear_readings),
+            settle_rest: Some(self.settle_rest),
+            final_margin: Some(self.final_margin),
+        }
+    }
+}
+
+/// What a bump_check pass found, ordered by severity; a pass over several
+/// steppers reports the worst of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BumpOutcome {
+    /// No sensor pressed, or the check did not run (no GPIO, disabled)
+    Clear,
+    /// A touch sensor could not be read
+    SensorError,
+    /// A stepper was touching and has been retracted clear
+    Cleared,
+    /// A stepper stayed touching; its faulty sensor was quarantined instead of disabling it
+    Quarantined,
+    /// A stepper stayed touching and was disabled
+    Disabled,
+}
+
+/// Result of a bump_check pass: the outcome to act on and the messages to show
+#[derive(Debug, Clone, PartialEq)]
+pub struct BumpReport {
+    pub outcome: BumpOutcome,
+    pub message: String,
+}
+
+impl BumpReport {
+    fn clear(message: impl Into<String>) -> Self {
+        Self { outcome: BumpOutcome::Clear, message: message.into() }
+    }
+
+    /// Some Z stepper was moved or disabled, so Z-dependent state is stale
+    pub fn z_moved(&self) -> bool {
+        self.outcome >= BumpOutcome::Cleared
+    }
+
+    /// Nothing was touching and every sensor could be read
+    pub fn passed(&self) -> bool {
+        self.outcome == BumpOutcome::Clear
+    }
+}
+
+/// Outcome of stepping a Z stepper toward its touch sensor
+#[derive(Debug)]
+enum SensorApproach {
+    Touched,
+    BottomedOut,
+    Cancelled,
+    GpioError(String),
+}
+
 /// Trait for stepper operations - allows bump_check to work with different implementations
 pub trait StepperOperations {
     fn rel_move(&mut self, stepper: usize, delta: i32) -> Result<()>;
     fn abs_move(&mut self, stepper: usize, position: i32) -> Result<()>;
     fn reset(&mut self, stepper: usize, position: i32) -> Result<()>;
+    /// Cut the stepper's driver current (where the firmware can) and lock it out of moves
     fn disable(&mut self, stepper: usize) -> Result<()>;
+    /// Restore driver current and lift the lockout
+    fn enable(&mut self, stepper: usize) -> Result<()>;
+    /// Change a stepper's speed; implementations without speed control keep this no-op
+    fn set_speed(&mut self, _stepper: usize, _speed: i32) -> Result<()> {
+        Ok(())
+    }
+    /// Change a string's bow wheel speed (BOW_DRIVE); implementations without wheels keep this no-op
+    fn set_bow_speed(&mut self, _string_idx: usize, _speed: u32) -> Result<()> {
+        Ok(())
+    }
+    /// Engage or release a string's damper on the carriage board (DAMPERS); implementations without dampers keep this no-op
+    fn damp(&mut self, _string_idx: usize, _engaged: bool) -> Result<()> {
+        Ok(())
+    }
+    /// Positions as the board reports them; None when the implementation cannot read them
+    fn reported_positions(&mut self) -> Result<Option<Vec<i32>>> {
+        Ok(None)
+    }
+}
+
+/// Transient link errors injected ahead of every call (see fault_injection)
+#[cfg(feature = "fault-injection")]
+impl<T: StepperOperations> StepperOperations for crate::fault_injection::Faulty<T> {
+    fn rel_move(&mut self, stepper: usize, delta: i32) -> Result<()> {
+        self.injector.stepper_call("rel_move")?;
+        self.inner.rel_move(stepper, delta)
+    }
+    fn abs_move(&mut self, stepper: usize, position: i32) -> Result<()> {
+        self.injector.stepper_call("abs_move")?;
+        self.inner.abs_move(stepper, position)
+    }
+    fn reset(&mut self, stepper: usize, position: i32) -> Result<()> {
+        self.injector.stepper_call("reset")?;
+        self.inner.reset(stepper, position)
+    }
+    fn disable(&mut self, stepper: usize) -> Result<()> {
+        self.injector.stepper_call("disable")?;
+        self.inner.disable(stepper)
+    }
+    fn enable(&mut self, stepper: usize) -> Result<()> {
+        self.injector.stepper_call("enable")?;
+        self.inner.enable(stepper)
+    }
+    fn set_speed(&mut self, stepper: usize, speed: i32) -> Result<()> {
+        self.injector.stepper_call("set_speed")?;
+        self.inner.set_speed(stepper, speed)
+    }
+    fn set_bow_speed(&mut self, string_idx: usize, speed: u32) -> Result<()> {
+        self.injector.stepper_call("set_bow_speed")?;
+        self.inner.set_bow_speed(string_idx, speed)
+    }
+    fn damp(&mut self, string_idx: usize, engaged: bool) -> Result<()> {
+        self.injector.stepper_call("damp")?;
+        self.inner.damp(string_idx, engaged)
+    }
+    fn reported_positions(&mut self) -> Result<Option<Vec<i32>>> {
+        self.injector.stepper_call("reported_positions")?;
+        self.inner.reported_positions()
+    }
 }
 
 /// Operations context for bump checking and recovery
@@ -74,6 +825,7 @@ pub struct Operations {
     bump_check_enable: Arc<Mutex<bool>>,
     z_up_step: Arc<Mutex<i32>>,
     z_down_step: Arc<Mutex<i32>>,
+    string_z_steps: StringZSteps,
     tune_rest: Arc<Mutex<f32>>,
     x_rest: Arc<Mutex<f32>>,
     z_rest: Arc<Mutex<f32>>,
@@ -85,31 +837,97 @@ pub struct Operations {
     x_start: Arc<Mutex<i32>>,
     x_finish: Arc<Mutex<i32>>,
     x_step: Arc<Mutex<i32>>,
-    pub z_first_index: usize,
-    pub string_num: usize,
-    pub x_step_index: Option<usize>,
+    sweep_laps: Arc<Mutex<u32>>, // x_sweep lap limit (see SweepParams::laps)
+    sweep_converge_below: Arc<Mutex<u32>>, // x_sweep convergence (see SweepParams::converge_below)
+    last_sweep: Arc<Mutex<Option<SweepReport>>>,
+    last_evaluation: Arc<Mutex<Option<PassEvaluation>>>, // Latest x_sweep pass check, for threshold debugging
+    z_moves: AtomicU64, // Z moves since start, for per-lap counts
+    bump_strategy: Arc<Mutex<BumpCheckStrategy>>,
+    adaptive_z_step: Arc<Mutex<bool>>,
+    z_max_step: Arc<Mutex<i32>>,
+    pub z_controller_settings: Option<ZControllerSettings>,
+    performance_rest: Arc<Mutex<f32>>,
+    performance_bump_interval: Arc<Mutex<u32>>,
+    performance_use_controller: Arc<Mutex<bool>>,
+    paused_channels: Arc<Mutex<HashSet<usize>>>,
+    channel_mask: Arc<Mutex<ChannelMask>>, // Mute/solo for audio-driven operations
+    voice_floor: Arc<Mutex<VoiceFloor>>,   // Amplitude floor used by voice counting
+    pub response_map_settings: ResponseMapSettings,
+    pub height_settings: HeightCalibrationSettings,
+    height_map: Arc<Mutex<Option<HeightMap>>>, // Latest height_calibrate curves
+    pub z_home_settings: ZHomeSettings,
+    pub x_verify_settings: XVerifySettings,
+    pub x_regions: Vec<XRegion>, // Sweep thresholds by X position (X_REGIONS)
+    pub x_clearance: XClearance, // Minimum Z heights along the X travel (X_CLEARANCE)
+    pub park_settings: ParkSettings,
+    rest_overrides: HashMap<String, RestOverrides>, // Per-operation rests from YAML
+    active_rests: Arc<Mutex<RestOverrides>>,         // Overrides for the running operation
+    active_bump: Arc<Mutex<BumpOverrides>>,          // bump_check overrides for the running operation
+    time_budgets: TimeBudgets,                       // OPERATIONS MAX_DURATION limits from YAML
+    alerter: Alerter,                                // ALERTS sinks for critical events
+    hooks: Hooks,                                    // HOOKS shell commands on lifecycle events
+    sensor_health: Mutex<SensorHealth>,              // Stuck/dead touch sensors and quarantine
+    state: Mutex<StateMachine>,                      // Instrument-level state (see instrument_state)
+    arbiter: Mutex<Arbiter>,                         // Which operations run now and which wait (see arbitration)
+    amp_history: Mutex<AmpHistory>,                  // Recent amp sums, for the level at a bump against before it
+    bump_events: Mutex<VecDeque<BumpEvent>>,         // Recorded by bump_check, taken by the logger (see bump_log)
+    audio_trigger: Mutex<Option<AudioTrigger>>,      // AUDIO_TRIGGER: start on sound, stop on silence
+    pub sensor_health_settings: SensorHealthSettings,
+    pub topology: Topology, // Each string's Z pair, tuner and channel (CHANNEL_MAP), and X
+    pub strings: Vec<StringInfo>, // STRINGS metadata for labels
     pub x_max_pos: Option<i32>,
-    pub tuner_indices: Vec<usize>,
+    axes: Vec<LinearAxis>, // X (when configured) then the AXES axes
+    pub bow_drive: Option<BowDriveSettings>, // Bow wheels (BOW_DRIVE), None without
+    bow_speeds: Arc<Mutex<Vec<u32>>>,        // Wheel speed per string as last sent from here
+    bow_modulate: Arc<Mutex<bool>>,          // z_adjust changes wheel speed for amplitude before moving Z
+    pub dampers: Option<Dampers>,            // Per-string damper relays (DAMPERS), None without
     pub stepper_enabled: StepperEnabled,
     pub gpio: Option<crate::gpio::GpioBoard>,
     arduino_connected: bool,
     // Audio analysis arrays
     voice_count: Arc<Mutex<Vec<usize>>>, // Per-channel voice count
     amp_sum: Arc<Mutex<Vec<f32>>>, // Per-channel amplitude sum
-    partials_slot: Option<PartialsSlot>, // Reference to shared partials slot
+    pitch: Arc<Mutex<Vec<Option<f32>>>>, // Per-channel fundamental, None when silent
+    partials_per_channel: AtomicUsize, // In the last analysed frame: the voice cap where no threshold sets one
+    tuner_guard: Option<Mutex<TunerGuard>>, // TUNER_SAFETY pitch curves and limits (None = tuner moves unchecked)
+    partials_feed: Option<Mutex<PartialsReader>>, // Frames from the GUI's partials reader thread (None = read shared memory here)
+    scratch_frame: Mutex<PartialsFrame>, // Shared memory read without a feed lands here
+    partials_freshness: Arc<Mutex<Freshness>>,
+    audio_control: Mutex<Option<AudioControl>>, // Control file as of the last analysed frame (channel labels, sample rate)
+    partials_stale_limit: Arc<Mutex<f32>>, // Seconds without a new frame before audio-driven moves stop (0 = off)
+    config_baseline: Baseline, // Tunable values at start and the host section's keys, for effective_config
 }
 
 impl Operations {
     /// Create a new Operations instance from configuration.
     /// Loads config from string_driver.yaml for the current hostname.
     pub fn new() -> Result<Self> {
-        Self::new_with_partials_slot(None)
+        Self::new_with_partials_feed(None)
     }
     
-    /// Create a new Operations instance with optional partials slot.
+    /// Create a new Operations instance reading frames from an optional partials feed.
     /// Loads config from string_driver.yaml for the current hostname.
-    pub fn new_with_partials_slot(partials_slot: Option<PartialsSlot>) -> Result<Self> {
+    pub fn new_with_partials_feed(partials_feed: Option<PartialsReader>) -> Result<Self> {
         let hostname = gethostname().to_string_lossy().to_string();
+        Self::for_host(&hostname, partials_feed)
+    }
+
+    /// Create an Operations instance for `hostname`'s section of string_driver.yaml.
+    /// GPIO is only opened for this machine's own instrument; another host's
+    /// touch sensors and limit switch are not wired here.
+    pub fn for_host(hostname: &str, partials_feed: Option<PartialsReader>) -> Result<Self> {
+        Self::load(hostname, partials_feed, true)
+    }
+
+    /// `hostname`'s configuration without opening GPIO, for reading it back
+    /// (`stringdriverd config dump`) while the instrument runs elsewhere
+    pub fn for_inspection(hostname: &str) -> Result<Self> {
+        Self::load(hostname, None, false)
+    }
+
+    fn load(hostname: &str, partials_feed: Option<PartialsReader>, open_gpio: bool) -> Result<Self> {
+        let hostname = hostname.to_string();
+        let is_local = open_gpio && hostname == gethostname().to_string_lossy();
         
         // Load operations settings (single source of truth)
         let ops_settings = load_operations_settings(&hostname)?;
@@ -121,11 +939,12 @@ impl Operations {
         // Z_FIRST_INDEX is only required when Arduino is connected
         let z_first_index = if arduino_connected {
             ard_settings.z_first_index
-                .ok_or_else(|| anyhow!("Z_FIRST_INDEX missing for '{}' in string_driver.yaml (required when Arduino is configured)", hostname))?
+                .ok_or_else(|| Error::ConfigMissing(format!("Z_FIRST_INDEX missing for '{}' in string_driver.yaml (required when Arduino is configured)", hostname)))?
         } else {
             0 // Dummy value when no Arduino - won't be used
         };
         let string_num = ard_settings.string_num;
+        let strings = ard_settings.strings.clone();
         
         // Load z_up_step from operations settings (from YAML - default to 2 if not specified)
         let z_up_step = ops_settings.z_up_step
@@ -146,14 +965,98 @@ impl Operations {
         let delta_threshold = ops_settings.delta_threshold.unwrap_or(50);
         let z_variance_threshold = ops_settings.z_variance_threshold.unwrap_or(50);
         
+        // Load adaptive step sizing for z_adjust (from YAML - default off, max 5x z_up_step)
+        let adaptive_z_step = ops_settings.adaptive_z_step;
+        let z_max_step = ops_settings.z_max_step.unwrap_or_else(|| default_z_max_step(z_up_step));
+        
+        // Load optional closed-loop Z controller config (Z_CONTROLLER block)
+        let z_controller_settings = load_z_controller_settings(&hostname)?;
+        
+        // Load performance mode parameters (from YAML - defaults: 1s cycle, bump_check every cycle)
+        let performance_rest = ops_settings.performance_rest.unwrap_or(1.0);
+        let performance_bump_interval = ops_settings.performance_bump_interval.unwrap_or(1).max(1);
+        let performance_use_controller = ops_settings.performance_use_controller && z_controller_settings.is_some();
+        
+        // Load voice counting amplitude floor (from YAML - default counts any non-zero amplitude)
+        let voice_floor = VoiceFloor {
+            relative: ops_settings.voice_floor_relative,
+            default: ops_settings.voice_floor.unwrap_or(0.0).max(0.0),
+            channels: ops_settings.voice_floors.iter().copied().enumerate().collect(),
+        };
+        
+        // Load channel -> string mapping (from YAML - default channel i drives string i)
+        let channel_map = match ops_settings.channel_map {
+            Some(map) => {
+                validate_channel_map(&map, string_num)
+                    .map_err(|e| Error::ConfigInvalid(format!("Invalid CHANNEL_MAP for '{}': {}", hostname, e)))?;
+                map
+            }
+            None => (0..string_num).collect(),
+        };
+        
+        // Load partials staleness limit (from YAML - default 2s, 0 disables the interlock)
+        let partials_stale_limit = ops_settings.partials_stale_limit.unwrap_or(2.0).max(0.0);
+        
+        // Load response_map sweep grid (RESPONSE_MAP block, defaults if absent)
+        let response_map_settings = load_response_map_settings(&hostname)?;
+        
+        // Load height_calibrate heights (HEIGHT_CALIBRATION block) and the curves it last recorded
+        let height_settings = load_height_calibration_settings(&hostname)?;
+        let height_map = crate::height_map::load(&height_settings.file);
+        
+        // Load z_home homing profile (Z_HOME block, defaults if absent)
+        let z_home_settings = load_z_home_settings(&hostname)?;
+        
+        // Load sweep X move verification (X_VERIFY block, defaults if absent)
+        let x_verify_settings = load_x_verify_settings(&hostname)?;
+        
+        // Load per-X-region sweep thresholds (X_REGIONS, none if absent)
+        let x_regions = load_x_regions(&hostname)?;
+        
+        // Load the carriage / Z clearance zones (X_CLEARANCE, X moves unchecked if absent)
+        let x_clearance = load_x_clearance(&hostname)?;
+        
+        // Load the park position (PARK block, defaults if absent)
+        let park_settings = load_park_settings(&hostname)?;
+        
+        // Load the bow wheels (BOW_DRIVE block, none if absent); they start stopped
+        let bow_drive = load_bow_drive_settings(&hostname)?;
+        let bow_modulate = bow_drive.as_ref().map_or(false, |bow| bow.modulate);
+        
+        // Load the damper relays (DAMPERS block, none if absent); all released at start
+        let dampers = load_damper_settings(&hostname)?.map(|settings| Dampers::new(settings, string_num));
+        
+        // Load per-operation rest overrides (OPERATIONS block, optional)
+        let rest_overrides = load_rest_overrides(&hostname)?;
+        let time_budgets = load_time_budgets(&hostname)?;
+        
+        // Load alert sinks and event classes (ALERTS block, nothing sent if absent)
+        let alerter = Alerter::new(&hostname, load_alert_settings(&hostname)?);
+        // Load the lifecycle event hooks (HOOKS block, nothing run if absent)
+        let hooks = Hooks::new(&hostname, load_hook_settings(&hostname)?);
+        let sensor_health_settings = load_sensor_health_settings(&hostname)?;
+        // Load the audio-triggered start (AUDIO_TRIGGER block, never armed if absent)
+        let audio_trigger = load_audio_trigger(&hostname)?.map(AudioTrigger::new);
+        
         // Load GPIO if available (required for z_calibration and bump_check)
         let gpio_settings = load_gpio_settings(&hostname)?;
         // Get GPIO_MAX_STEPS for default X range calculation before moving gpio_settings
         let gpio_max_steps = gpio_settings.as_ref().and_then(|gs| gs.max_steps).map(|v| v as i32);
-        let gpio = gpio_settings.map(|_| crate::gpio::GpioBoard::new()).transpose()?;
+        let gpio = gpio_settings.filter(|_| is_local).map(|_| crate::gpio::GpioBoard::new()).transpose()?;
         
         let x_step_index = ard_settings.x_step_index;
         let x_max_pos = ard_settings.x_max_pos;
+        let x_rest = Arc::new(Mutex::new(x_rest));
+        let axes: Vec<LinearAxis> = x_step_index
+            .map(|step_index| LinearAxis { name: "X".to_string(), step_index, max_pos: x_max_pos, rest: x_rest.clone() })
+            .into_iter()
+            .chain(ard_settings.axes.iter().map(|axis| LinearAxis {
+                name: axis.name.clone(),
+                step_index: axis.step_index,
+                max_pos: Some(axis.max_pos),
+                rest: Arc::new(Mutex::new(axis.rest.unwrap_or(5.0))),
+            }))
+            .collect();
         
         // Load X movement parameters from operations settings (from YAML - defaults)
         // Default x_start = 100, x_finish = X_MAX_POS - 100
@@ -170,31 +1073,52 @@ impl Operations {
         let x_start = ops_settings.x_start.unwrap_or(100);
         let x_finish = ops_settings.x_finish.unwrap_or(default_x_finish);
         let x_step = ops_settings.x_step.unwrap_or(10);
-        let tuner_indices = mainboard_tuner_indices(&ard_settings);
+        // Carriage board tuners only: Operations does not drive the tuner board
+        let topology = Topology::new(
+            string_num,
+            Some(z_first_index),
+            x_step_index,
+            TunerLayout::Main(mainboard_tuner_indices(&ard_settings)),
+            Some(&channel_map),
+        );
+        
+        // Load bump_check strategy from operations settings (from YAML - defaults match legacy behaviour)
+        let default_strategy = BumpCheckStrategy::default();
+        let bump_strategy = BumpCheckStrategy {
+            retract_step: ops_settings.bump_retract_step,
+            clear_readings: ops_settings.bump_clear_readings.unwrap_or(default_strategy.clear_readings).max(1),
+            settle_rest: ops_settings.bump_settle_rest.unwrap_or(default_strategy.settle_rest),
+            final_margin: ops_settings.bump_final_margin.unwrap_or(default_strategy.final_margin),
+        };
         
         // Initialize stepper enabled states (all enabled by default)
         // Only initialize if Arduino is connected
         let mut stepper_enabled = HashMap::new();
         if arduino_connected {
-            for i in 0..(string_num * 2) {
-                let stepper_idx = z_first_index + i;
+            for stepper_idx in topology.main_indices().into_iter().chain(axes.iter().map(|axis| axis.step_index)) {
                 stepper_enabled.insert(stepper_idx, true);
             }
-            if let Some(x_idx) = x_step_index {
-                stepper_enabled.insert(x_idx, true);
-            }
-            for idx in &tuner_indices {
-                stepper_enabled.insert(*idx, true);
-            }
         }
         
-        Ok(Self {
+        let mut yaml_keys: Vec<String> = crate::config_loader::load_host_section(&hostname)
+            .map(|(_, section)| section.keys().filter_map(|k| k.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+        let mut string_z_steps = vec![(None, None); string_num];
+        for (slot, info) in string_z_steps.iter_mut().zip(&strings) {
+            *slot = (info.z_up_step, info.z_down_step);
+        }
+        // Set inside STRINGS, reported as one setting
+        if string_z_steps.iter().any(|&(up, down)| up.is_some() || down.is_some()) {
+            yaml_keys.push("STRING_Z_STEPS".to_string());
+        }
+        let mut ops = Self {
             hostname,
             bump_check_enable: Arc::new(Mutex::new(ops_settings.bump_check_enable)),
             z_up_step: Arc::new(Mutex::new(z_up_step)),
             z_down_step: Arc::new(Mutex::new(z_down_step)),
+            string_z_steps: Arc::new(Mutex::new(string_z_steps)),
             tune_rest: Arc::new(Mutex::new(tune_rest)),
-            x_rest: Arc::new(Mutex::new(x_rest)),
+            x_rest,
             z_rest: Arc::new(Mutex::new(z_rest)),
             lap_rest: Arc::new(Mutex::new(lap_rest)),
             adjustment_level: Arc::new(Mutex::new(adjustment_level)),
@@ -204,30 +1128,82 @@ impl Operations {
             x_start: Arc::new(Mutex::new(x_start)),
             x_finish: Arc::new(Mutex::new(x_finish)),
             x_step: Arc::new(Mutex::new(x_step)),
-            z_first_index,
-            string_num,
-            x_step_index,
+            sweep_laps: Arc::new(Mutex::new(0)),
+            sweep_converge_below: Arc::new(Mutex::new(0)),
+            last_sweep: Arc::new(Mutex::new(None)),
+            last_evaluation: Arc::new(Mutex::new(None)),
+            z_moves: AtomicU64::new(0),
+            bump_strategy: Arc::new(Mutex::new(bump_strategy)),
+            adaptive_z_step: Arc::new(Mutex::new(adaptive_z_step)),
+            z_max_step: Arc::new(Mutex::new(z_max_step)),
+            z_controller_settings,
+            performance_rest: Arc::new(Mutex::new(performance_rest)),
+            performance_bump_interval: Arc::new(Mutex::new(performance_bump_interval)),
+            performance_use_controller: Arc::new(Mutex::new(performance_use_controller)),
+            paused_channels: Arc::new(Mutex::new(HashSet::new())),
+            channel_mask: Arc::new(Mutex::new(ChannelMask::default())),
+            voice_floor: Arc::new(Mutex::new(voice_floor)),
+            response_map_settings,
+            height_settings,
+            height_map: Arc::new(Mutex::new(height_map)),
+            z_home_settings,
+            x_verify_settings,
+            x_regions,
+            x_clearance,
+            park_settings,
+            rest_overrides,
+            active_rests: Arc::new(Mutex::new(RestOverrides::default())),
+            active_bump: Arc::new(Mutex::new(BumpOverrides::default())),
+            time_budgets,
+            alerter,
+            hooks,
+            sensor_health: Mutex::new(SensorHealth::new(&sensor_health_settings.quarantine)),
+            state: Mutex::new(StateMachine::default()),
+            arbiter: Mutex::new(Arbiter::default()),
+            amp_history: Mutex::new(AmpHistory::default()),
+            bump_events: Mutex::new(VecDeque::new()),
+            audio_trigger: Mutex::new(audio_trigger),
+            sensor_health_settings,
+            topology,
+            strings,
             x_max_pos,
-            tuner_indices,
+            axes,
+            bow_drive,
+            bow_speeds: Arc::new(Mutex::new(vec![0; string_num])),
+            bow_modulate: Arc::new(Mutex::new(bow_modulate)),
+            dampers,
             stepper_enabled: Arc::new(Mutex::new(stepper_enabled)),
             gpio,
             arduino_connected,
             voice_count: {
                 // Try to initialize with channel count from control file if available
-                let initial_size = Self::read_control_file()
+                let initial_size = audmon_client::control_layout()
                     .map(|(ch, _)| ch)
                     .unwrap_or(0);
                 Arc::new(Mutex::new(vec![0; initial_size]))
             },
             amp_sum: {
                 // Try to initialize with channel count from control file if available
-                let initial_size = Self::read_control_file()
+                let initial_size = audmon_client::control_layout()
                     .map(|(ch, _)| ch)
                     .unwrap_or(0);
                 Arc::new(Mutex::new(vec![0.0; initial_size]))
             },
-            partials_slot,
-        })
+            pitch: Arc::new(Mutex::new(Vec::new())),
+            partials_per_channel: AtomicUsize::new(
+                audmon_client::control_layout().map_or(audmon_client::DEFAULT_PARTIALS_PER_CHANNEL, |(_, partials)| partials).max(1),
+            ),
+            tuner_guard: ard_settings.tuner_safety.as_ref().map(|settings| Mutex::new(TunerGuard::new(settings, string_num))),
+            partials_feed: partials_feed.map(Mutex::new),
+            scratch_frame: Mutex::new(PartialsFrame::default()),
+            partials_freshness: Arc::new(Mutex::new(Freshness::default())),
+            audio_control: Mutex::new(None),
+            partials_stale_limit: Arc::new(Mutex::new(partials_stale_limit)),
+            config_baseline: Baseline::default(),
+        };
+        let startup: Vec<(&str, String)> = ops.tunable_settings().into_iter().map(|(_, key, value)| (key, value)).collect();
+        ops.config_baseline = Baseline::new(yaml_keys, &startup);
+        Ok(ops)
     }
     
     /// Set bump_check_enable state
@@ -272,615 +1248,2160 @@ impl Operations {
             .unwrap_or(-2)
     }
     
-    pub fn x_step_index(&self) -> Option<usize> {
-        self.x_step_index
+    /// A string's own (z_up_step, z_down_step); None where it uses the global one
+    pub fn get_string_z_steps(&self, string_idx: usize) -> (Option<i32>, Option<i32>) {
+        self.string_z_steps.lock()
+            .ok()
+            .and_then(|steps| steps.get(string_idx).copied())
+            .unwrap_or((None, None))
     }
     
-    pub fn tuner_indices(&self) -> Vec<usize> {
-        self.tuner_indices.clone()
+    /// Give a string its own Z steps (None: back to the global one). An up step
+    /// that is not positive or a down step that is not negative is ignored.
+    pub fn set_string_z_steps(&self, string_idx: usize, up: Option<i32>, down: Option<i32>) {
+        if let Ok(mut steps) = self.string_z_steps.lock() {
+            if let Some(slot) = steps.get_mut(string_idx) {
+                *slot = (up.filter(|&u| u > 0), down.filter(|&d| d < 0));
+            }
+        }
     }
     
-    /// Set tune_rest value
-    pub fn set_tune_rest(&self, rest: f32) {
-        if let Ok(mut rest_val) = self.tune_rest.lock() {
-            *rest_val = rest;
+    /// z_up_step for a string's Z pair: its own, else the global one
+    pub fn z_up_step_for(&self, string_idx: Option<usize>) -> i32 {
+        string_idx.and_then(|s| self.get_string_z_steps(s).0).unwrap_or_else(|| self.get_z_up_step())
+    }
+    
+    /// z_down_step for a string's Z pair: its own, else the global one
+    pub fn z_down_step_for(&self, string_idx: Option<usize>) -> i32 {
+        string_idx.and_then(|s| self.get_string_z_steps(s).1).unwrap_or_else(|| self.get_z_down_step())
+    }
+    
+    /// The string a Z stepper presses on
+    pub fn string_of_z_stepper(&self, stepper_idx: usize) -> Option<usize> {
+        match self.topology.role_of(Bank::Main, stepper_idx) {
+            Some(StepperRole::Z { string, .. }) => Some(string),
+            _ => None,
         }
     }
     
-    /// Get tune_rest value
-    pub fn get_tune_rest(&self) -> f32 {
-        self.tune_rest.lock()
-            .map(|r| *r)
-            .unwrap_or(10.0)
+    /// Set the bump_check strategy used by subsequent runs
+    pub fn set_bump_strategy(&self, strategy: BumpCheckStrategy) {
+        if let Ok(mut strategy_val) = self.bump_strategy.lock() {
+            *strategy_val = strategy;
+        }
     }
     
-    /// Set x_rest value
-    pub fn set_x_rest(&self, rest: f32) {
-        if let Ok(mut rest_val) = self.x_rest.lock() {
-            *rest_val = rest;
+    /// Get the bump_check strategy
+    pub fn get_bump_strategy(&self) -> BumpCheckStrategy {
+        self.bump_strategy.lock()
+            .map(|s| *s)
+            .unwrap_or_default()
+    }
+    
+    /// Set whether z_adjust scales its step by the size of the error
+    pub fn set_adaptive_z_step(&self, enabled: bool) {
+        if let Ok(mut enable) = self.adaptive_z_step.lock() {
+            *enable = enabled;
         }
     }
     
-    /// Get x_rest value
-    pub fn get_x_rest(&self) -> f32 {
-        self.x_rest.lock()
-            .map(|r| *r)
-            .unwrap_or(10.0)
+    /// Get whether z_adjust scales its step by the size of the error
+    pub fn get_adaptive_z_step(&self) -> bool {
+        self.adaptive_z_step.lock()
+            .map(|e| *e)
+            .unwrap_or(false)
     }
     
-    /// Set z_rest value
-    pub fn set_z_rest(&self, rest: f32) {
-        if let Ok(mut rest_val) = self.z_rest.lock() {
-            *rest_val = rest;
+    /// Set the largest Z move (absolute steps) adaptive z_adjust may issue
+    pub fn set_z_max_step(&self, step: i32) {
+        if let Ok(mut step_val) = self.z_max_step.lock() {
+            *step_val = step;
         }
     }
     
-    /// Get z_rest value
-    pub fn get_z_rest(&self) -> f32 {
-        self.z_rest.lock()
-            .map(|r| *r)
-            .unwrap_or(5.0)
+    /// Get the largest Z move (absolute steps) adaptive z_adjust may issue
+    pub fn get_z_max_step(&self) -> i32 {
+        self.z_max_step.lock()
+            .map(|s| *s)
+            .unwrap_or_else(|_| default_z_max_step(self.get_z_up_step()))
     }
 
-    fn sleep_for(seconds: f32) {
-        if seconds > 0.0 {
-            std::thread::sleep(Duration::from_secs_f32(seconds));
+    /// Set string `string_idx`'s bow wheel speed (capped at MAX_SPEED) through stepper_gui;
+    /// returns the speed sent
+    pub fn set_bow_speed<T: StepperOperations>(&self, stepper_ops: &mut T, string_idx: usize, speed: u32) -> Result<u32> {
+        let Some(bow) = &self.bow_drive else {
+            return Err(Error::ConfigMissing(format!("No BOW_DRIVE configured for '{}'", self.hostname)));
+        };
+        if string_idx >= self.topology.string_num() {
+            return Err(Error::Other(format!("No bow wheel for string {}", string_idx)));
+        }
+        let speed = speed.min(bow.max_speed);
+        stepper_ops.set_bow_speed(string_idx, speed)?;
+        if let Ok(mut speeds) = self.bow_speeds.lock() {
+            if let Some(slot) = speeds.get_mut(string_idx) {
+                *slot = speed;
+            }
         }
+        Ok(speed)
     }
 
-    fn rest_z(&self) {
-        Self::sleep_for(self.get_z_rest());
+    /// Bow wheel speed per string as last sent from here (0 = stopped)
+    pub fn get_bow_speeds(&self) -> Vec<u32> {
+        self.bow_speeds.lock()
+            .map(|s| s.clone())
+            .unwrap_or_default()
     }
 
-    fn rest_x(&self) {
-        Self::sleep_for(self.get_x_rest());
+    /// Set whether z_adjust changes wheel speed for amplitude before moving Z
+    pub fn set_bow_modulate(&self, enabled: bool) {
+        if let Ok(mut enable) = self.bow_modulate.lock() {
+            *enable = enabled && self.bow_drive.is_some();
+        }
     }
 
-    fn rest_tune(&self) {
-        Self::sleep_for(self.get_tune_rest());
+    /// Get whether z_adjust changes wheel speed for amplitude before moving Z
+    pub fn get_bow_modulate(&self) -> bool {
+        self.bow_modulate.lock()
+            .map(|e| *e)
+            .unwrap_or(false)
     }
 
-    fn rest_lap(&self) {
-        Self::sleep_for(self.get_lap_rest());
+    /// Engage (true) or release string `string_idx`'s damper
+    pub fn damp<T: StepperOperations>(&self, stepper_ops: &mut T, string_idx: usize, engaged: bool) -> Result<()> {
+        let dampers = self.dampers.as_ref()
+            .ok_or_else(|| Error::ConfigMissing(format!("No DAMPERS configured for '{}'", self.hostname)))?;
+        dampers.set(self.gpio.as_ref(), string_idx, engaged, |idx, on| stepper_ops.damp(idx, on))
     }
 
-    fn rel_move_z_with_rest<T: StepperOperations>(&self, stepper_ops: &mut T, stepper: usize, delta: i32, rest: bool) -> Result<()> {
-        stepper_ops.rel_move(stepper, delta)?;
-        if rest {
-            self.rest_z();
+    /// Engage or release every damper, then wait SETTLE for the strings to go quiet
+    pub fn damp_all<T: StepperOperations>(&self, stepper_ops: &mut T, engaged: bool) -> Result<()> {
+        self.set_dampers(stepper_ops, &vec![engaged; self.topology.string_num()])
+    }
+
+    /// Which strings are damped (empty without DAMPERS)
+    pub fn get_dampers(&self) -> Vec<bool> {
+        self.dampers.as_ref().map(|dampers| dampers.engaged()).unwrap_or_default()
+    }
+
+    /// Move X straight to `target` (within 0..X_MAX_POS) for a score cue, raising Z
+    /// out of the way first where X_CLEARANCE asks; returns what the clearance did
+    pub fn move_x_to<T: StepperOperations>(&self, stepper_ops: &mut T, positions: &mut [i32], target: i32) -> Result<String> {
+        let x_step_index = self.x_step_index().ok_or_else(|| Error::ConfigMissing("X stepper not configured".to_string()))?;
+        if !self.get_stepper_enabled(x_step_index) {
+            return Err(Error::Other(format!("{} is disabled", self.stepper_label(x_step_index))));
+        }
+        let target = match self.x_max_pos {
+            Some(max) if max > 0 => target.clamp(0, max),
+            _ => target.max(0),
+        };
+        let from = positions.get(x_step_index).copied().unwrap_or(0);
+        let mut messages = Vec::new();
+        self.clear_x_path(stepper_ops, positions, from, target, &mut messages)?;
+        stepper_ops.abs_move(x_step_index, target)?;
+        if let Some(x) = positions.get_mut(x_step_index) {
+            *x = target;
+        }
+        Ok(messages.join("\n"))
+    }
+
+    /// Move both enabled Z steppers of string `string_idx` to `position` (held to
+    /// Z_MIN_POS and their max positions) for a score cue; a bump_check follows a
+    /// move down
+    pub fn move_string_z<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        string_idx: usize,
+        position: i32,
+    ) -> Result<()> {
+        let (inner, outer) = self.topology.z_pair(string_idx)
+            .ok_or_else(|| Error::Other(format!("No Z pair for string {}", string_idx)))?;
+        let mut lowered = false;
+        for stepper in [inner, outer] {
+            if !self.get_stepper_enabled(stepper) {
+                continue;
+            }
+            let target = max_positions.get(&stepper).map_or(position, |&max| position.min(max)).max(Z_MIN_POS);
+            if !z_within_limits(max_positions, stepper, target) {
+                return Err(Error::Other(format!("{} cannot go to {}: outside its limits", self.stepper_label(stepper), position)));
+            }
+            let current = positions.get(stepper).copied().unwrap_or(0);
+            stepper_ops.abs_move(stepper, target)?;
+            self.z_moves.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            if let Some(slot) = positions.get_mut(stepper) {
+                *slot = target;
+            }
+            lowered |= target < current;
+        }
+        // A Z stepper sent down may have landed on its string
+        if lowered {
+            self.bump_check(None, positions, max_positions, stepper_ops, None)?;
         }
         Ok(())
     }
 
-    fn rel_move_z<T: StepperOperations>(&self, stepper_ops: &mut T, stepper: usize, delta: i32) -> Result<()> {
-        self.rel_move_z_with_rest(stepper_ops, stepper, delta, true)
+    /// Turn string `string_idx`'s tuner by `steps` for a score cue; only a tuner on the
+    /// carriage board can be reached through stepper_gui
+    pub fn tune_string<T: StepperOperations>(&self, stepper_ops: &mut T, positions: &mut [i32], string_idx: usize, steps: i32) -> Result<()> {
+        let tuner = self.topology.string(string_idx).and_then(|s| s.tuner)
+            .ok_or_else(|| Error::Other(format!("No tuner for {}", self.string_label(string_idx))))?;
+        if tuner.bank != Bank::Main {
+            return Err(Error::Other(format!("{}'s tuner is on the tuner board, out of stepper_gui's reach", self.string_label(string_idx))));
+        }
+        let current = positions.get(tuner.index).copied().unwrap_or(0);
+        self.check_tuner_move(string_idx, current, current + steps)?;
+        stepper_ops.rel_move(tuner.index, steps)?;
+        if let Some(slot) = positions.get_mut(tuner.index) {
+            *slot += steps;
+        }
+        Ok(())
     }
 
-    fn rel_move_z_no_rest<T: StepperOperations>(&self, stepper_ops: &mut T, stepper: usize, delta: i32) -> Result<()> {
-        self.rel_move_z_with_rest(stepper_ops, stepper, delta, false)
+    /// The instrument as it stands, as scene `name`: each Z stepper's position, each
+    /// carriage board tuner's by string, X, and `params`
+    pub fn capture_scene(&self, name: &str, positions: &HashMap<usize, i32>, params: ParameterPreset) -> Scene {
+        let z = self.get_z_stepper_indices().into_iter()
+            .filter_map(|idx| positions.get(&idx).map(|&pos| (idx, pos)))
+            .collect();
+        let tuners = self.topology.strings().iter()
+            .filter_map(|string| {
+                let tuner = string.tuner.filter(|t| t.bank == Bank::Main)?;
+                positions.get(&tuner.index).map(|&pos| (string.string, pos))
+            })
+            .collect();
+        Scene {
+            name: name.trim().to_string(),
+            host: self.hostname.clone(),
+            saved_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            x: self.x_step_index().and_then(|idx| positions.get(&idx).copied()),
+            z,
+            tuners,
+            params,
+        }
     }
 
-    fn rel_move_x<T: StepperOperations>(&self, stepper_ops: &mut T, stepper: usize, delta: i32) -> Result<()> {
-        stepper_ops.rel_move(stepper, delta)?;
-        self.rest_x();
-        Ok(())
+    /// Drive the instrument back to `scene` (the order of the moves is in scene.rs).
+    /// It is checked before anything moves: its Z steppers and tuners must exist here,
+    /// X must be enabled if it moves and every tuner move must stay under MAX_PITCH.
+    /// Z targets are held to Z_MIN_POS and their max positions, X keeps to X_MAX_POS
+    /// and X_CLEARANCE, and disabled Z steppers and tuners stay where they are. A
+    /// bump_check follows the Z moves down. The scene's parameters are left to the
+    /// caller. Returns what was done.
+    pub fn recall_scene<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        scene: &Scene,
+    ) -> Result<Vec<String>> {
+        let z_indices = self.get_z_stepper_indices();
+        if let Some(stepper) = scene.z.keys().find(|idx| !z_indices.contains(idx)) {
+            return Err(Error::Other(format!("Scene '{}' sets stepper {}, which is not a Z stepper here", scene.name, stepper)));
+        }
+        let x_index = self.x_step_index();
+        let x_moves = scene.x.is_some_and(|x| x_index.and_then(|idx| positions.get(idx)) != Some(&x));
+        if x_moves && !x_index.is_some_and(|idx| self.get_stepper_enabled(idx)) {
+            return Err(Error::Other(format!("Scene '{}' moves X, which is disabled or not configured", scene.name)));
+        }
+        let mut tuner_moves = Vec::new();
+        for (&string_idx, &target) in &scene.tuners {
+            let tuner = self.topology.string(string_idx).and_then(|s| s.tuner)
+                .filter(|t| t.bank == Bank::Main)
+                .ok_or_else(|| Error::Other(format!("Scene '{}' tunes {}, which has no tuner on the carriage board", scene.name, self.string_label(string_idx))))?;
+            let current = positions.get(tuner.index).copied().unwrap_or(0);
+            if target != current && self.get_stepper_enabled(tuner.index) {
+                self.check_tuner_move(string_idx, current, target)?;
+                tuner_moves.push((string_idx, tuner.index, target - current));
+            }
+        }
+
+        let mut messages = Vec::new();
+        let mut lowered = false;
+        for up in [true, false] {
+            for (stepper, target) in scene.z_moves(positions, max_positions, up) {
+                if !self.get_stepper_enabled(stepper) {
+                    messages.push(format!("{} is disabled, left at {}", self.stepper_label(stepper), positions.get(stepper).copied().unwrap_or(0)));
+                    continue;
+                }
+                if !z_within_limits(max_positions, stepper, target) {
+                    messages.push(format!("{} not moved: {} is outside its limits", self.stepper_label(stepper), target));
+                    continue;
+                }
+                stepper_ops.abs_move(stepper, target)?;
+                self.z_moves.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                if let Some(slot) = positions.get_mut(stepper) {
+                    *slot = target;
+                }
+                lowered |= !up;
+            }
+            // X between the Z steppers going up and those going down
+            if let Some(x) = scene.x.filter(|_| up && x_moves) {
+                let cleared = self.move_x_to(stepper_ops, positions, x)?;
+                messages.extend(cleared.lines().map(str::to_string));
+            }
+        }
+        // A Z stepper sent down may have landed on its string
+        if lowered {
+            let report = self.bump_check(None, positions, max_positions, stepper_ops, None)?;
+            if !report.passed() {
+                messages.extend(report.message.lines().filter(|line| !line.trim().is_empty()).map(str::to_string));
+            }
+        }
+        for (string_idx, tuner_index, steps) in tuner_moves {
+            stepper_ops.rel_move(tuner_index, steps)?;
+            if let Some(slot) = positions.get_mut(tuner_index) {
+                *slot += steps;
+            }
+            messages.push(format!("Turned {}'s tuner by {}", self.string_label(string_idx), steps));
+        }
+        Ok(messages)
     }
 
-    fn rel_move_tune<T: StepperOperations>(&self, stepper_ops: &mut T, stepper: usize, delta: i32) -> Result<()> {
-        stepper_ops.rel_move(stepper, delta)?;
-        self.rest_tune();
+    /// Refuse a tuner move the string's pitch curve says passes its MAX_PITCH (see
+    /// tension.rs). The pitch heard now is logged against `from` first, so the curve
+    /// includes where the move starts.
+    pub fn check_tuner_move(&self, string_idx: usize, from: i32, to: i32) -> Result<()> {
+        let Some(guard) = &self.tuner_guard else { return Ok(()) };
+        let mut guard = guard.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(pitch) = self.string_pitch(string_idx) {
+            if let Err(e) = guard.record(string_idx, from, pitch) {
+                tracing::warn!("Could not log the pitch curve of {}: {}", self.string_label(string_idx), e);
+            }
+        }
+        guard.check(string_idx, from, to).map_err(|e| Error::Other(format!("{}: {}", self.string_label(string_idx), e)))
+    }
+
+    /// Log each carriage board tuner's pitch once it has settled at a new position, so
+    /// tuning by hand in stepper_gui builds the curves too
+    pub fn observe_tuner_pitch(&self, positions: &HashMap<usize, i32>) {
+        let Some(guard) = &self.tuner_guard else { return };
+        let mut guard = guard.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        for string in self.topology.strings() {
+            let Some(tuner) = string.tuner.filter(|t| t.bank == Bank::Main) else { continue };
+            let (Some(&position), Some(pitch)) = (positions.get(&tuner.index), self.string_pitch(string.string)) else { continue };
+            if let Err(e) = guard.observe(string.string, position, pitch, now) {
+                tracing::warn!("Could not log the pitch curve of {}: {}", self.string_label(string.string), e);
+            }
+        }
+    }
+
+    /// Per string with a MAX_PITCH or a curve: its last pitch, limit and tension trend
+    pub fn tuner_safety_report(&self) -> Vec<String> {
+        let Some(guard) = &self.tuner_guard else { return Vec::new() };
+        let guard = guard.lock().unwrap_or_else(|e| e.into_inner());
+        (0..self.topology.strings().len())
+            .filter_map(|string| guard.describe(string).map(|text| format!("{}: {}", self.string_label(string), text)))
+            .collect()
+    }
+
+    /// Put each string's damper in `states`, then wait SETTLE if any changed
+    fn set_dampers<T: StepperOperations>(&self, stepper_ops: &mut T, states: &[bool]) -> Result<()> {
+        let dampers = self.dampers.as_ref()
+            .ok_or_else(|| Error::ConfigMissing(format!("No DAMPERS configured for '{}'", self.hostname)))?;
+        let current = dampers.engaged();
+        let mut changed = false;
+        for (string_idx, (&engaged, &was)) in states.iter().zip(&current).enumerate() {
+            if engaged != was {
+                dampers.set(self.gpio.as_ref(), string_idx, engaged, |idx, on| stepper_ops.damp(idx, on))?;
+                changed = true;
+            }
+        }
+        if changed {
+            std::thread::sleep(dampers.settings.settle);
+        }
         Ok(())
     }
-    
-    /// Set lap_rest value
-    pub fn set_lap_rest(&self, rest: f32) {
-        if let Ok(mut rest_val) = self.lap_rest.lock() {
+
+    /// Run `f` with every string damped when DAMPERS lists `operation` under DURING,
+    /// then put the dampers back as they were (also when `f` fails)
+    pub fn with_dampers<T, F>(&self, operation: &str, stepper_ops: &mut T, f: F) -> Result<String>
+    where
+        T: StepperOperations,
+        F: FnOnce(&mut T) -> Result<String>,
+    {
+        let Some(dampers) = self.dampers.as_ref().filter(|dampers| dampers.applies_to(operation)) else {
+            return f(stepper_ops);
+        };
+        let previous = dampers.engaged();
+        self.damp_all(stepper_ops, true)?;
+        let result = f(stepper_ops);
+        let restored = self.set_dampers(stepper_ops, &previous);
+        match (result, restored) {
+            (Ok(msg), Ok(())) => Ok(format!("Strings damped for {}\n{}\nDampers restored", operation, msg)),
+            (Ok(msg), Err(e)) => Err(Error::Other(format!("{}\nDampers not restored after {}: {}", msg, operation, e))),
+            (Err(e), _) => Err(e),
+        }
+    }
+
+    /// Set minimum seconds per performance mode cycle
+    pub fn set_performance_rest(&self, rest: f32) {
+        if let Ok(mut rest_val) = self.performance_rest.lock() {
             *rest_val = rest;
         }
     }
     
-    /// Get lap_rest value
-    pub fn get_lap_rest(&self) -> f32 {
-        self.lap_rest.lock()
+    /// Get minimum seconds per performance mode cycle
+    pub fn get_performance_rest(&self) -> f32 {
+        self.performance_rest.lock()
             .map(|r| *r)
-            .unwrap_or(4.0)
+            .unwrap_or(1.0)
     }
     
-    /// Set adjustment_level value
-    pub fn set_adjustment_level(&self, level: i32) {
-        if let Ok(mut level_val) = self.adjustment_level.lock() {
-            *level_val = level;
+    /// Set how many performance mode cycles run between bump_checks
+    pub fn set_performance_bump_interval(&self, interval: u32) {
+        if let Ok(mut interval_val) = self.performance_bump_interval.lock() {
+            *interval_val = interval.max(1);
         }
     }
     
-    /// Get adjustment_level value
-    pub fn get_adjustment_level(&self) -> i32 {
-        self.adjustment_level.lock()
-            .map(|l| *l)
-            .unwrap_or(4)
+    /// Get how many performance mode cycles run between bump_checks
+    pub fn get_performance_bump_interval(&self) -> u32 {
+        self.performance_bump_interval.lock()
+            .map(|i| *i)
+            .unwrap_or(1)
     }
     
-    /// Set retry_threshold value
-    pub fn set_retry_threshold(&self, threshold: i32) {
-        if let Ok(mut thresh) = self.retry_threshold.lock() {
-            *thresh = threshold;
+    /// Set whether performance mode uses the Z_CONTROLLER PID loops instead of z_adjust
+    pub fn set_performance_use_controller(&self, enabled: bool) {
+        if let Ok(mut enable) = self.performance_use_controller.lock() {
+            *enable = enabled;
         }
     }
     
-    /// Get retry_threshold value
-    pub fn get_retry_threshold(&self) -> i32 {
-        self.retry_threshold.lock()
-            .map(|t| *t)
-            .unwrap_or(50)
+    /// Get whether performance mode uses the Z_CONTROLLER PID loops instead of z_adjust
+    pub fn get_performance_use_controller(&self) -> bool {
+        self.performance_use_controller.lock()
+            .map(|e| *e)
+            .unwrap_or(false)
     }
     
-    /// Set delta_threshold value
-    pub fn set_delta_threshold(&self, threshold: i32) {
-        if let Ok(mut thresh) = self.delta_threshold.lock() {
-            *thresh = threshold;
+    /// Pause or resume a channel in performance mode
+    pub fn set_channel_paused(&self, ch_idx: usize, paused: bool) {
+        if let Ok(mut set) = self.paused_channels.lock() {
+            if paused {
+                set.insert(ch_idx);
+            } else {
+                set.remove(&ch_idx);
+            }
         }
     }
     
-    /// Get delta_threshold value
-    pub fn get_delta_threshold(&self) -> i32 {
-        self.delta_threshold.lock()
-            .map(|t| *t)
-            .unwrap_or(50)
+    /// Check whether a channel is paused in performance mode
+    pub fn is_channel_paused(&self, ch_idx: usize) -> bool {
+        self.paused_channels.lock()
+            .map(|set| set.contains(&ch_idx))
+            .unwrap_or(false)
     }
     
-    /// Set z_variance_threshold value
-    pub fn set_z_variance_threshold(&self, threshold: i32) {
-        if let Ok(mut thresh) = self.z_variance_threshold.lock() {
-            *thresh = threshold;
+    /// Get all channels paused in performance mode
+    pub fn get_paused_channels(&self) -> HashSet<usize> {
+        self.paused_channels.lock()
+            .map(|set| set.clone())
+            .unwrap_or_default()
+    }
+    
+    /// Mute or unmute a channel's analysis for audio-driven operations
+    pub fn set_channel_muted(&self, ch_idx: usize, muted: bool) {
+        if let Ok(mut mask) = self.channel_mask.lock() {
+            if muted {
+                mask.muted.insert(ch_idx);
+            } else {
+                mask.muted.remove(&ch_idx);
+            }
         }
     }
     
-    /// Get z_variance_threshold value
-    pub fn get_z_variance_threshold(&self) -> i32 {
-        self.z_variance_threshold.lock()
-            .map(|t| *t)
-            .unwrap_or(50)
+    pub fn is_channel_muted(&self, ch_idx: usize) -> bool {
+        self.channel_mask.lock()
+            .map(|mask| mask.muted.contains(&ch_idx))
+            .unwrap_or(false)
     }
     
-    /// Set x_start value
-    pub fn set_x_start(&self, start: i32) {
-        if let Ok(mut val) = self.x_start.lock() {
-            *val = start;
+    /// Solo or unsolo a channel; while any channel is soloed only soloed channels are adjusted
+    pub fn set_channel_soloed(&self, ch_idx: usize, soloed: bool) {
+        if let Ok(mut mask) = self.channel_mask.lock() {
+            if soloed {
+                mask.soloed.insert(ch_idx);
+            } else {
+                mask.soloed.remove(&ch_idx);
+            }
         }
     }
     
-    /// Get x_start value
-    pub fn get_x_start(&self) -> i32 {
-        self.x_start.lock()
-            .map(|s| *s)
-            .unwrap_or(0)
+    pub fn is_channel_soloed(&self, ch_idx: usize) -> bool {
+        self.channel_mask.lock()
+            .map(|mask| mask.soloed.contains(&ch_idx))
+            .unwrap_or(false)
     }
     
-    /// Set x_finish value
-    pub fn set_x_finish(&self, finish: i32) {
-        if let Ok(mut val) = self.x_finish.lock() {
-            *val = finish;
-        }
+    /// Get the current mute/solo state (clone)
+    pub fn get_channel_mask(&self) -> ChannelMask {
+        self.channel_mask.lock()
+            .map(|mask| mask.clone())
+            .unwrap_or_default()
     }
     
-    /// Get x_finish value
-    pub fn get_x_finish(&self) -> i32 {
-        self.x_finish.lock()
-            .map(|f| *f)
-            .unwrap_or(100)
+    /// Get the voice counting floor (clone)
+    pub fn get_voice_floor(&self) -> VoiceFloor {
+        self.voice_floor.lock()
+            .map(|f| f.clone())
+            .unwrap_or_default()
     }
     
-    /// Set x_step value
-    pub fn set_x_step(&self, step: i32) {
-        if let Ok(mut val) = self.x_step.lock() {
-            *val = step;
+    /// Switch voice floors between absolute amplitudes and fractions of the channel max
+    pub fn set_voice_floor_relative(&self, relative: bool) {
+        if let Ok(mut floor) = self.voice_floor.lock() {
+            floor.relative = relative;
         }
     }
     
-    /// Get x_step value
-    pub fn get_x_step(&self) -> i32 {
-        self.x_step.lock()
-            .map(|s| *s)
-            .unwrap_or(10)
+    /// Set the floor for every channel (clears per-channel floors)
+    pub fn set_voice_floor_all(&self, value: f32) {
+        if let Ok(mut floor) = self.voice_floor.lock() {
+            floor.default = value.max(0.0);
+            floor.channels.clear();
+        }
     }
     
-    /// Get Z stepper indices based on configuration
-    pub fn get_z_stepper_indices(&self) -> Vec<usize> {
-        let mut indices = Vec::new();
-        for i in 0..(self.string_num * 2) {
-            let idx = self.z_first_index + i;
-            indices.push(idx);
+    /// Set the floor for one channel
+    pub fn set_voice_floor(&self, ch_idx: usize, value: f32) {
+        if let Ok(mut floor) = self.voice_floor.lock() {
+            floor.channels.insert(ch_idx, value.max(0.0));
         }
-        indices
     }
     
-    /// Set stepper enable state
-    pub fn set_stepper_enabled(&self, stepper_idx: usize, enabled: bool) {
-        if let Ok(mut enabled_map) = self.stepper_enabled.lock() {
-            enabled_map.insert(stepper_idx, enabled);
-        }
+    /// String (Z pair) driven by an audio channel's analysis; None for channels beyond STRING_NUM
+    pub fn string_for_channel(&self, ch_idx: usize) -> Option<usize> {
+        self.topology.string_for_channel(ch_idx)
     }
     
-    /// Get stepper enable state
-    pub fn get_stepper_enabled(&self, stepper_idx: usize) -> bool {
-        self.stepper_enabled.lock()
-            .map(|map| map.get(&stepper_idx).copied().unwrap_or(false))
-            .unwrap_or(false)
+    /// Audio channel whose analysis drives a string
+    pub fn channel_for_string(&self, string_idx: usize) -> Option<usize> {
+        self.topology.channel_for_string(string_idx)
     }
     
-    /// Get all stepper enabled states (clone of internal map)
-    pub fn get_all_stepper_enabled(&self) -> HashMap<usize, bool> {
-        self.stepper_enabled.lock()
-            .map(|map| map.clone())
-            .unwrap_or_default()
+    /// (z_in, z_out) stepper indices for an audio channel, via CHANNEL_MAP
+    pub fn z_pair_for_channel(&self, ch_idx: usize) -> Option<(usize, usize)> {
+        self.topology.z_pair_for_channel(ch_idx)
     }
     
-    /// Get shared memory path for partials data
-    /// Returns the path to the shared memory file where audio_streaming writes partials
-    pub fn get_shared_memory_path() -> String {
-        // Determine shared memory directory based on platform
-        let shm_dir = if cfg!(target_os = "linux") {
-            "/dev/shm"
-        } else if cfg!(target_os = "macos") {
-            "/tmp"
-        } else {
-            "/tmp"
-        };
-        format!("{}/audio_peaks", shm_dir)
+    /// Send an alert to the ALERTS sinks if `event` is enabled there
+    pub fn alert(&self, event: AlertEvent, message: &str) {
+        self.alerter.raise(event, message);
     }
     
-    /// Get control file path for audio monitor metadata
-    /// Returns the path to the control file that contains channel count and partials info
-    fn get_control_file_path() -> String {
-        // Determine shared memory directory based on platform (same as shared memory)
-        let shm_dir = if cfg!(target_os = "linux") {
-            "/dev/shm"
-        } else if cfg!(target_os = "macos") {
-            "/tmp"
-        } else {
-            "/tmp"
-        };
-        format!("{}/audio_control", shm_dir)
-    }
-    
-    /// Read actual channel count and partials per channel from control file
-    /// Returns (num_channels, num_partials_per_channel) if file exists and is readable
-    /// Returns None if file doesn't exist or can't be read
-    fn read_control_file() -> Option<(usize, usize)> {
-        let control_path = Self::get_control_file_path();
-        let content = std::fs::read_to_string(&control_path).ok()?;
-        let lines: Vec<&str> = content.trim().split('\n').collect();
-        if lines.len() >= 3 {
-            // Format: PID\nnum_channels\nnum_partials
-            let num_channels = lines[1].parse::<usize>().ok()?;
-            let num_partials = lines[2].parse::<usize>().ok()?;
-            Some((num_channels, num_partials))
-        } else {
-            None
-        }
+    /// Run the HOOKS commands for `event` with `details` (a JSON object) in the payload
+    pub fn hook(&self, event: HookEvent, details: serde_json::Value) {
+        self.hooks.fire(event, details);
     }
     
-    /// Read partials data from shared memory file
-    /// Returns None if file doesn't exist or can't be read
-    /// num_channels: maximum number of channels to read (will read actual_channels_written from control file if available)
-    /// num_partials_per_channel: number of partials per channel (hint, will be overridden by control file if available)
-    pub fn read_partials_from_shared_memory(num_channels: usize, mut num_partials_per_channel: usize) -> Option<PartialsData> {
-        let shm_path = Self::get_shared_memory_path();
-        
-        // Try to open and read the shared memory file
-        let file = OpenOptions::new().read(true).open(&shm_path).ok()?;
-        let mmap = unsafe { Mmap::map(&file).ok()? };
-        
-        // Deserialize bytes: each partial is (f32 freq, f32 amp) = 8 bytes
-        // Format: channel 0 partials, channel 1 partials, etc.
-        // Each channel has exactly num_partials_per_channel partials
-        const PARTIAL_SIZE: usize = 8; // 2 * f32 = 8 bytes
-        
-        // Read control file to get actual channel count and partials per channel written by audio_monitor
-        let (actual_channels_written, actual_partials_per_channel) = match Self::read_control_file() {
-            Some((ch, ppc)) => (ch, ppc),
-            None => {
-                // Fallback: try to detect from file size if control file not available
-                if num_channels > 0 {
-                    let total_entries = mmap.len() / PARTIAL_SIZE;
-                    let detected = total_entries / num_channels;
-                    if detected > 0 {
-                        (num_channels, detected) // Assume num_channels is correct if no control file
-                    } else {
-                        (num_channels, num_partials_per_channel) // Use hint
-                    }
-                } else {
-                    (num_channels, num_partials_per_channel) // Use hint
-                }
-            }
-        };
-        
-        // Use actual values from control file (or detected values)
-        num_partials_per_channel = actual_partials_per_channel;
-        
-        if num_partials_per_channel == 0 {
-            // Fallback to default of 12 if still zero
-            num_partials_per_channel = 12;
-        }
-        
-        let channel_size = num_partials_per_channel * PARTIAL_SIZE;
-        
-        // Read min(actual_channels_written, num_channels) channels
-        // This respects the caller's request while not reading beyond what was written
-        let channels_to_read = actual_channels_written.min(num_channels);
-        
-        let mut partials = Vec::new();
-        let mut offset = 0;
-        
-        // Read exactly channels_to_read channels
-        for _ in 0..channels_to_read {
-            if offset + channel_size > mmap.len() {
-                break; // Not enough data
-            }
-            
-            let mut channel_data = Vec::new();
-            
-            // Read exactly num_partials_per_channel partials for this channel
-            for _ in 0..num_partials_per_channel {
-                if offset + PARTIAL_SIZE > mmap.len() {
-                    break;
-                }
-                
-                let freq_bytes = &mmap[offset..offset + 4];
-                let amp_bytes = &mmap[offset + 4..offset + 8];
-                
-                let freq = f32::from_ne_bytes([freq_bytes[0], freq_bytes[1], freq_bytes[2], freq_bytes[3]]);
-                let amp = f32::from_ne_bytes([amp_bytes[0], amp_bytes[1], amp_bytes[2], amp_bytes[3]]);
-                
-                channel_data.push((freq, amp));
-                offset += PARTIAL_SIZE;
+    /// A fault disabled `stepper_idx`: alert (ALERTS) and run the stepper_disabled hooks
+    fn report_disabled(&self, stepper_idx: usize, message: &str) {
+        self.alert(AlertEvent::StepperDisabled, message);
+        self.hook(HookEvent::StepperDisabled, serde_json::json!({
+            "stepper": stepper_idx,
+            "label": self.stepper_label(stepper_idx),
+            "message": message,
+        }));
+    }
+    
+    /// True when alerts for `event` are configured
+    pub fn alert_enabled(&self, event: AlertEvent) -> bool {
+        self.alerter.wants(event)
+    }
+    
+    /// How long a channel must stay silent before it is reported as a string break
+    pub fn string_break_silence(&self) -> Duration {
+        self.alerter.settings().string_break_silence
+    }
+    
+    /// "String 4 (A2)", from STRINGS
+    pub fn string_label(&self, string_idx: usize) -> String {
+        string_label(&self.strings, string_idx)
+    }
+    
+    /// Name a stepper by what it moves, e.g. "String 4 (A2) outer Z (stepper 7)"
+    pub fn stepper_label(&self, stepper_idx: usize) -> String {
+        match self.topology.role_of(Bank::Main, stepper_idx) {
+            Some(StepperRole::Z { string, side }) if self.arduino_connected => {
+                format!("{} {} Z (stepper {})", self.string_label(string), side.name(), stepper_idx)
             }
-            
-            partials.push(channel_data);
-        }
-        
-        if partials.is_empty() {
-            None
-        } else {
-            Some(partials)
+            Some(StepperRole::X) => format!("X carriage (stepper {})", stepper_idx),
+            Some(StepperRole::Tuner { string }) => format!("{} tuner (stepper {})", self.string_label(string), stepper_idx),
+            _ => match self.axes.iter().find(|axis| axis.step_index == stepper_idx) {
+                Some(axis) => format!("{} axis (stepper {})", axis.name, stepper_idx),
+                None => format!("Stepper {}", stepper_idx),
+            },
         }
     }
     
-    /// Update voice_count and amp_sum from partials data in the shared slot
-    /// Caller should use get_results::read_partials_from_slot() to read from slot
-    /// If partials_slot is None, reads from shared memory file as fallback
-    pub fn update_audio_analysis_with_partials(&self, partials: Option<PartialsData>) {
-        if let Some(partials) = partials {
-            // Use actual number of channels from audio data (not limited by string_num)
-            let num_channels = partials.len();
-            
-            // Use get_results functions for calculations
-            let voice_counts = calculate_voice_count(&partials);
-            let amp_sums = calculate_amp_sum(&partials);
-            
-            // Update voice_count - resize to actual channel count, update all channels
-            if let Ok(mut voice_count) = self.voice_count.lock() {
-                // Resize to actual channel count (not string_num)
-                if voice_count.len() < num_channels {
-                    voice_count.resize(num_channels, 0);
-                }
-                // Update all channels that have data
-                for ch_idx in 0..num_channels {
-                    if ch_idx < voice_counts.len() && ch_idx < voice_count.len() {
-                        voice_count[ch_idx] = voice_counts[ch_idx];
-                    }
-                }
-            }
-            
-            // Update amp_sum - resize to actual channel count, update all channels
-            if let Ok(mut amp_sum) = self.amp_sum.lock() {
-                // Resize to actual channel count (not string_num)
-                if amp_sum.len() < num_channels {
-                    amp_sum.resize(num_channels, 0.0);
-                }
-                // Update all channels that have data
-                for ch_idx in 0..num_channels {
-                    if ch_idx < amp_sums.len() && ch_idx < amp_sum.len() {
-                        amp_sum[ch_idx] = amp_sums[ch_idx];
-                    }
-                }
-            }
+    /// Name a touch sensor by the Z stepper it protects
+    pub fn sensor_label(&self, sensor: usize) -> String {
+        match self.topology.stepper_for_sensor(sensor) {
+            Some(stepper_idx) => self.stepper_label(stepper_idx),
+            None => format!("Touch sensor {}", sensor),
         }
     }
     
-    /// Update voice_count and amp_sum from partials data in the shared slot
-    /// DEPRECATED: Use update_audio_analysis_with_partials() with get_results::read_partials_from_slot()
-    /// This method duplicates logic and should not be used - kept for backward compatibility only
-    pub fn update_audio_analysis(&self) {
-        // Caller should use: get_results::read_partials_from_slot(&slot) then update_audio_analysis_with_partials()
-        // This fallback is only for cases where slot is not available and shared memory must be used
-        let partials = if self.partials_slot.is_some() {
-            // If slot exists, caller should use get_results::read_partials_from_slot() instead
-            None  // Force caller to use proper pattern
-        } else {
-            // Only fallback to shared memory if no slot available
-            // Get actual channel count from control file, or use a large number to read all available channels
-            const DEFAULT_NUM_PARTIALS: usize = 12;
-            let num_channels_hint = Self::read_control_file()
-                .map(|(ch, _)| ch)
-                .unwrap_or(100); // Use large number to read all available channels if control file not available
-            Self::read_partials_from_shared_memory(num_channels_hint, DEFAULT_NUM_PARTIALS)
-        };
-        self.update_audio_analysis_with_partials(partials);
+    /// GPIO touch sensor of a Z stepper
+    fn sensor_index(&self, stepper_idx: usize) -> usize {
+        self.topology.sensor_for(stepper_idx).unwrap_or(0)
     }
     
-    /// Get reference to partials slot (for use with get_results::read_partials_from_slot)
-    pub fn partials_slot(&self) -> Option<&PartialsSlot> {
-        self.partials_slot.as_ref()
+    pub fn x_step_index(&self) -> Option<usize> {
+        self.topology.x_index()
     }
     
-    /// Get voice_count array (clone)
-    pub fn get_voice_count(&self) -> Vec<usize> {
-        self.voice_count.lock()
-            .map(|vc| vc.clone())
-            .unwrap_or_default()
+    pub fn tuner_indices(&self) -> Vec<usize> {
+        self.topology.mainboard_tuner_indices()
     }
     
-    /// Get amp_sum array (clone)
-    pub fn get_amp_sum(&self) -> Vec<f32> {
-        self.amp_sum.lock()
-            .map(|asum| asum.clone())
-            .unwrap_or_default()
+    /// Linear axes: X when configured, then the AXES entries
+    pub fn axes(&self) -> &[LinearAxis] {
+        &self.axes
     }
     
-    /// Get bump status for all Z steppers
-    /// Returns Vec<(stepper_index, is_bumping)>
-    pub fn get_bump_status(&self) -> Vec<(usize, bool)> {
-        let mut status = Vec::new();
-        
-        if let Some(ref gpio) = self.gpio {
-            if !gpio.exist {
-                return status;
-            }
-            
-            let z_indices = self.get_z_stepper_indices();
-            for &stepper_idx in &z_indices {
-                let gpio_index = stepper_idx.saturating_sub(self.z_first_index);
-                match gpio.press_check(Some(gpio_index)) {
-                    Ok(states) => {
-                        let is_bumping = states.get(0).copied().unwrap_or(false);
-                        status.push((stepper_idx, is_bumping));
-                    }
-                    Err(_) => {
-                        status.push((stepper_idx, false));
-                    }
-                }
-            }
-        }
-        
-        status
+    fn linear_axis(&self, name: &str) -> Result<&LinearAxis> {
+        self.axes.iter()
+            .find(|axis| axis.name == name)
+            .ok_or_else(|| Error::ConfigMissing(format!("{} stepper not configured", name)))
     }
     
-    /// Perform bump check on Z-steppers.
-    ///
-    /// For each enabled Z-stepper (or the specified index):
-    /// 1. Poll the touch sensor; if not bumping, do nothing.
-    /// 2. If bumping, issue repeated upward moves of `z_up_step`, resting `z_rest` between moves,
-    ///    until the sensor clears or the reported position reaches `max_pos`.
-    /// 3. When the sensor clears, reset the controller position to `z_up_step` (no hardware motion).
-    /// 4. If the sensor never clears and the stepper is already at/above `max_pos`, disable it.
-    pub fn bump_check<T: StepperOperations>(
+    /// Axis and seek an operation name stands for, e.g. `y_home`
+    pub fn axis_seek_for(&self, operation: &str) -> Option<(String, AxisSeek)> {
+        self.axes.iter().find_map(|axis| {
+            AxisSeek::ALL.into_iter().find(|seek| seek.operation(&axis.name) == operation).map(|seek| (axis.name.clone(), seek))
+        })
+    }
+    
+    /// Operations on this host: the fixed ones plus the seeks of its axes
+    pub fn operation_descriptors(&self) -> Vec<OperationDescriptor> {
+        let axes: Vec<&str> = self.axes.iter().map(|axis| axis.name.as_str()).collect();
+        operation_catalog(&axes)
+    }
+
+    pub fn describe_operation(&self, operation: &str) -> Option<OperationDescriptor> {
+        self.operation_descriptors().into_iter().find(|op| op.name == operation)
+    }
+
+    /// What `operation` needs that this host does not have
+    pub fn missing_hardware(&self, operation: &OperationDescriptor) -> Vec<Requirement> {
+        operation.requires.iter().copied().filter(|requirement| match requirement {
+            Requirement::Gpio => !self.gpio.as_ref().is_some_and(|gpio| gpio.exist),
+            Requirement::XAxis => !self.axes.iter().any(|axis| axis.is_x() && axis.max_pos != Some(0)),
+        }).collect()
+    }
+
+    /// Current value of a PRESETS scalar key, e.g. `Z_UP_STEP`
+    pub fn parameter(&self, key: &str) -> Option<f64> {
+        Some(match key {
+            "TUNE_REST" => self.get_tune_rest() as f64,
+            "X_REST" => self.get_x_rest() as f64,
+            "Z_REST" => self.get_z_rest() as f64,
+            "LAP_REST" => self.get_lap_rest() as f64,
+            "PERFORMANCE_REST" => self.get_performance_rest() as f64,
+            "ADJUSTMENT_LEVEL" => self.get_adjustment_level() as f64,
+            "RETRY_THRESHOLD" => self.get_retry_threshold() as f64,
+            "DELTA_THRESHOLD" => self.get_delta_threshold() as f64,
+            "Z_VARIANCE_THRESHOLD" => self.get_z_variance_threshold() as f64,
+            "Z_UP_STEP" => self.get_z_up_step() as f64,
+            "Z_DOWN_STEP" => self.get_z_down_step() as f64,
+            "X_START" => self.get_x_start() as f64,
+            "X_FINISH" => self.get_x_finish() as f64,
+            "X_STEP" => self.get_x_step() as f64,
+            _ => return None,
+        })
+    }
+
+    /// Run a registered custom operation (see plugins) on this instrument
+    pub fn run_plugin<T: StepperOperations>(
         &self,
-        stepper_index: Option<usize>,
+        operation: &dyn plugins::Operation,
+        stepper_ops: &mut T,
         positions: &mut [i32],
         max_positions: &HashMap<usize, i32>,
-        stepper_ops: &mut T,
         exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
     ) -> Result<String> {
-        let gpio = self.gpio.as_ref().ok_or_else(|| anyhow!("GPIO not initialized"))?;
-        if !gpio.exist {
-            return Ok("\nno GPIO".to_string());
+        let mut host = PluginHost { ops: self, stepper_ops, positions, max_positions, exit_flag };
+        let result = operation.run(&mut plugins::OperationContext::new(&mut host, operation.descriptor()));
+        if exit_flag.is_some_and(|exit| exit.load(std::sync::atomic::Ordering::Relaxed)) {
+            return Err(Error::OperationAborted(result.map(|report| report.message()).unwrap_or_else(|e| e)));
         }
+        result.map(|report| report.message()).map_err(Error::Other)
+    }
 
-        if !self.get_bump_check_enable() {
-            return Ok("bump_check disabled - skipping".to_string());
-        }
+    /// Set every scalar parameter `preset` names (rests, thresholds, steps, X
+    /// range, bump_check strategy); the channel bands go through
+    /// ChannelBands::apply and bow speeds through set_bow_speed
+    pub fn apply_parameters(&self, preset: &ParameterPreset) {
+        if let Some(v) = preset.tune_rest { self.set_tune_rest(v); }
+        if let Some(v) = preset.x_rest { self.set_x_rest(v); }
+        if let Some(v) = preset.z_rest { self.set_z_rest(v); }
+        if let Some(v) = preset.lap_rest { self.set_lap_rest(v); }
+        if let Some(v) = preset.performance_rest { self.set_performance_rest(v); }
+        if let Some(v) = preset.adjustment_level { self.set_adjustment_level(v); }
+        if let Some(v) = preset.retry_threshold { self.set_retry_threshold(v); }
+        if let Some(v) = preset.delta_threshold { self.set_delta_threshold(v); }
+        if let Some(v) = preset.z_variance_threshold { self.set_z_variance_threshold(v); }
+        if let Some(v) = preset.z_up_step { self.set_z_up_step(v); }
+        if let Some(v) = preset.z_down_step { self.set_z_down_step(v); }
+        if let Some(v) = preset.x_start { self.set_x_start(v); }
+        if let Some(v) = preset.x_finish { self.set_x_finish(v); }
+        if let Some(v) = preset.x_step { self.set_x_step(v); }
+        if let Some(v) = preset.bump { self.set_bump_strategy(self.get_bump_strategy().with_overrides(v)); }
+    }
 
-        let z_up_step = self.get_z_up_step();
-        if z_up_step <= 0 {
-            return Err(anyhow!(
-                "Invalid z_up_step {} for bump_check: value must be positive to move away from the string",
-                z_up_step
-            ));
+    /// Run a motion operation by its menu name: the built-in ones, every axis's
+    /// home / away / calibrate, then registered custom operations. Shared by
+    /// operations_gui and stringdriverd; the caller brackets it with
+    /// begin_operation / end_operation.
+    pub fn run_named<T: StepperOperations>(
+        &self,
+        operation: &str,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        bands: &ChannelBands,
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        progress_sender: Option<&std::sync::mpsc::Sender<String>>,
+        socket_path: Option<&str>,
+    ) -> Result<String> {
+        let min_thresholds: Vec<f32> = bands.amp_sum_min.iter().map(|&v| v as f32).collect();
+        let max_thresholds: Vec<f32> = bands.amp_sum_max.iter().map(|&v| v as f32).collect();
+        let min_voices: Vec<usize> = bands.voice_count_min.iter().map(|&v| v.max(0) as usize).collect();
+        let max_voices: Vec<usize> = bands.voice_count_max.iter().map(|&v| v.max(0) as usize).collect();
+        // z_hold regulates to the middle of each channel's band
+        let hold_on_voices = self.z_controller_settings.as_ref().is_some_and(|c| c.metric == "voice_count");
+        let (lows, highs) = if hold_on_voices {
+            (&bands.voice_count_min, &bands.voice_count_max)
+        } else {
+            (&bands.amp_sum_min, &bands.amp_sum_max)
+        };
+        let hold_setpoints: Vec<f32> = lows.iter().zip(highs).map(|(&lo, &hi)| (lo + hi) as f32 / 2.0).collect();
+
+        match operation {
+            "z_calibrate" => self.z_calibrate(stepper_ops, positions, max_positions, exit_flag),
+            "z_home" => self.z_home(stepper_ops, positions, max_positions, exit_flag),
+            "z_adjust" => self.z_adjust(
+                stepper_ops,
+                positions,
+                max_positions,
+                &min_thresholds,
+                &max_thresholds,
+                &min_voices,
+                &max_voices,
+                exit_flag,
+            ),
+            "bump_check" => self.bump_check(None, positions, max_positions, stepper_ops, exit_flag).map(|report| report.message),
+            "right_left_move" | "left_right_move" | "ping_pong_move" => {
+                let direction = match operation {
+                    "right_left_move" => SweepDirection::RightLeft,
+                    "left_right_move" => SweepDirection::LeftRight,
+                    _ => SweepDirection::PingPong,
+                };
+                let params = SweepParams {
+                    min_thresholds: &min_thresholds,
+                    max_thresholds: &max_thresholds,
+                    min_voices: &min_voices,
+                    max_voices: &max_voices,
+                    laps: self.get_sweep_laps(),
+                    converge_below: self.get_sweep_converge_below(),
+                };
+                self.x_sweep(stepper_ops, positions, max_positions, direction, &params, exit_flag, progress_sender)
+            }
+            "z_hold" => self.z_hold(stepper_ops, positions, max_positions, &hold_setpoints, exit_flag, progress_sender),
+            "performance_mode" => self.performance_mode(
+                stepper_ops,
+                positions,
+                max_positions,
+                &min_thresholds,
+                &max_thresholds,
+                &min_voices,
+                &max_voices,
+                &hold_setpoints,
+                exit_flag,
+                progress_sender,
+            ),
+            "response_map" => self.response_map(stepper_ops, positions, max_positions, exit_flag, progress_sender),
+            "height_calibrate" => self.height_calibrate(stepper_ops, positions, max_positions, exit_flag, progress_sender),
+            "park" => self.park(stepper_ops, positions, max_positions, exit_flag),
+            // x_home / x_away / x_calibrate and the same for every AXES axis
+            other => match self.axis_seek_for(other) {
+                Some((axis, seek)) => self.run_axis_seek(&axis, seek, stepper_ops, positions, exit_flag, socket_path),
+                None => match plugins::find(other) {
+                    Some(custom) => self.run_plugin(&*custom, stepper_ops, positions, max_positions, exit_flag),
+                    None => Err(Error::Other("Unsupported operation".to_string())),
+                },
+            },
         }
+    }
 
-        // Get all Z-stepper indices
-        let mut all_z_indices = Vec::new();
-        for i in 0..(self.string_num * 2) {
-            let idx = self.z_first_index + i;
-            all_z_indices.push(idx);
+    /// Set an axis's rest after each move (X's is x_rest)
+    pub fn set_axis_rest(&self, name: &str, rest: f32) {
+        if let Some(mut rest_val) = self.axes.iter().find(|axis| axis.name == name).and_then(|axis| axis.rest.lock().ok()) {
+            *rest_val = rest;
         }
-        
-        if all_z_indices.is_empty() {
-            return Ok(String::new());
+    }
+    
+    /// Get an axis's rest after each move
+    pub fn get_axis_rest(&self, name: &str) -> f32 {
+        self.axes.iter()
+            .find(|axis| axis.name == name)
+            .and_then(|axis| axis.rest.lock().ok().map(|r| *r))
+            .unwrap_or(10.0)
+    }
+    
+    /// Set tune_rest value
+    pub fn set_tune_rest(&self, rest: f32) {
+        if let Ok(mut rest_val) = self.tune_rest.lock() {
+            *rest_val = rest;
         }
+    }
+    
+    /// Get tune_rest value
+    pub fn get_tune_rest(&self) -> f32 {
+        self.tune_rest.lock()
+            .map(|r| *r)
+            .unwrap_or(10.0)
+    }
+    
+    /// Set x_rest value
+    pub fn set_x_rest(&self, rest: f32) {
+        if let Ok(mut rest_val) = self.x_rest.lock() {
+            *rest_val = rest;
+        }
+    }
+    
+    /// Get x_rest value
+    pub fn get_x_rest(&self) -> f32 {
+        self.x_rest.lock()
+            .map(|r| *r)
+            .unwrap_or(10.0)
+    }
+    
+    /// Set z_rest value
+    pub fn set_z_rest(&self, rest: f32) {
+        if let Ok(mut rest_val) = self.z_rest.lock() {
+            *rest_val = rest;
+        }
+    }
+    
+    /// Get z_rest value
+    pub fn get_z_rest(&self) -> f32 {
+        self.z_rest.lock()
+            .map(|r| *r)
+            .unwrap_or(5.0)
+    }
 
-        // Build the list of steppers to probe: either all, or one specified
-        let steppers_to_check = if let Some(spec_idx) = stepper_index {
-            let idx_0_based = if spec_idx > 0 { spec_idx - 1 } else { spec_idx };
-            if idx_0_based < all_z_indices.len() {
-                vec![all_z_indices[idx_0_based]]
-            } else {
-                return Ok(format!("\nInvalid stepper index: {}", spec_idx));
-            }
-        } else {
-            all_z_indices.clone()
-        };
-
-        let enabled_states = self.get_all_stepper_enabled();
-        const MAX_MOVE_ITERATIONS: u32 = 50;
-        let mut messages = Vec::new();
-
-        for &stepper_idx in &steppers_to_check {
+    fn sleep_for(seconds: f32) {
+        if seconds > 0.0 {
+            std::thread::sleep(Duration::from_secs_f32(seconds));
+        }
+    }
+    
+    /// Sleep in short slices so long rests still react to the exit flag
+    fn sleep_interruptible(seconds: f32, exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>) {
+        let deadline = std::time::Instant::now() + Duration::from_secs_f32(seconds.max(0.0));
+        while std::time::Instant::now() < deadline {
             if let Some(exit) = exit_flag {
                 if exit.load(std::sync::atomic::Ordering::Relaxed) {
-                    return Ok(messages.join("\n"));
+                    return;
                 }
             }
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            std::thread::sleep(remaining.min(Duration::from_millis(100)));
+        }
+    }
 
-            let enabled = enabled_states.get(&stepper_idx).copied().unwrap_or(false);
-            if !enabled {
-                continue;
-            }
+    /// Rest values an operation would use: call overrides, then the YAML
+    /// OPERATIONS entry for `operation`, then the globals.
+    pub fn effective_rests(&self, operation: &str, call_overrides: RestOverrides) -> RestOverrides {
+        let yaml = self.rest_overrides.get(operation).copied().unwrap_or_default();
+        call_overrides.or(yaml).or(RestOverrides {
+            tune_rest: Some(self.get_tune_rest()),
+            x_rest: Some(self.get_x_rest()),
+            z_rest: Some(self.get_z_rest()),
+            lap_rest: Some(self.get_lap_rest()),
+        })
+    }
+    
+    /// Run `f` with `operation`'s rest overrides in effect: the run's own (GUI, inbox
+    /// command, score cue), then the YAML OPERATIONS entry. A rest neither overrides is
+    /// read from the globals at each rest, so edits made mid-run still apply. Nested
+    /// operations inherit the outer operation's overrides, which are restored afterwards.
+    pub fn with_rest_overrides<F>(&self, operation: &str, call_overrides: RestOverrides, f: F) -> Result<String>
+    where
+        F: FnOnce() -> Result<String>,
+    {
+        let yaml = self.rest_overrides.get(operation).copied().unwrap_or_default();
+        let overrides = call_overrides.or(yaml);
+        if !overrides.is_empty() {
+            tracing::info!(
+                operation,
+                tune_rest = ?overrides.tune_rest,
+                x_rest = ?overrides.x_rest,
+                z_rest = ?overrides.z_rest,
+                lap_rest = ?overrides.lap_rest,
+                "rest overrides"
+            );
+        }
+        let previous = self.active_rests.lock()
+            .map(|mut active| std::mem::replace(&mut *active, overrides))
+            .unwrap_or_default();
+        let result = f();
+        if let Ok(mut active) = self.active_rests.lock() {
+            *active = previous;
+        }
+        result
+    }
+    
+    /// Run time limit for `operation` (YAML OPERATIONS MAX_DURATION), if any
+    pub fn time_budget(&self, operation: &str) -> Option<Duration> {
+        self.time_budgets.budget(operation)
+    }
 
-            let gpio_index = stepper_idx.saturating_sub(self.z_first_index);
-            let max_pos = max_positions.get(&stepper_idx).copied().unwrap_or(100);
-            
-            // Check initial bump state
-            let initial_bumping = match gpio.press_check(Some(gpio_index)) {
-                Ok(states) => states.get(0).copied().unwrap_or(false),
-                Err(e) => {
-                    messages.push(format!("GPIO error for stepper {}: {}", stepper_idx, e));
-                    continue; // Skip this stepper on GPIO error
+    /// Run `f` under `operation`'s time budget, or under `limit` when the run sets its
+    /// own. When the budget runs out `exit_flag` is raised so the operation stops at its
+    /// next check, and the result becomes an OperationTimedOut error carrying whatever it
+    /// reported (see retract_z_to_safe_height for the cleanup). Without a budget `f` just runs.
+    pub fn with_time_budget<F>(&self, operation: &str, limit: Option<Duration>, exit_flag: &Arc<std::sync::atomic::AtomicBool>, f: F) -> Result<String>
+    where
+        F: FnOnce() -> Result<String>,
+    {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        let Some(budget) = limit.or_else(|| self.time_budget(operation)) else {
+            return f();
+        };
+        let expired = Arc::new(AtomicBool::new(false));
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+        let watchdog = {
+            let exit_flag = Arc::clone(exit_flag);
+            let expired = Arc::clone(&expired);
+            std::thread::spawn(move || {
+                // Disconnected means f finished within the budget
+                if done_rx.recv_timeout(budget) == Err(std::sync::mpsc::RecvTimeoutError::Timeout) {
+                    expired.store(true, Ordering::Relaxed);
+                    exit_flag.store(true, Ordering::Relaxed);
                 }
-            };
+            })
+        };
+        let result = f();
+        drop(done_tx);
+        let _ = watchdog.join();
+        if !expired.load(Ordering::Relaxed) {
+            return result;
+        }
+        let partial = match result {
+            Ok(msg) => msg,
+            Err(e) => e.to_string(),
+        };
+        Err(Error::OperationTimedOut { operation: operation.to_string(), budget, partial })
+    }
 
-            // If not bumping, skip this stepper
-            if !initial_bumping {
-                continue;
-            }
+    /// Run `f` with the run's own bump_check overrides (inbox command, GUI) over the
+    /// BUMP_* strategy; the strategy itself is untouched, so edits made mid-run still
+    /// apply to the values the run does not override. Restored afterwards.
+    pub fn with_bump_overrides<F>(&self, call_overrides: BumpOverrides, f: F) -> Result<String>
+    where
+        F: FnOnce() -> Result<String>,
+    {
+        if !call_overrides.is_empty() {
+            tracing::info!(
+                retract_step = ?call_overrides.retract_step,
+                clear_readings = ?call_overrides.clear_readings,
+                settle_rest = ?call_overrides.settle_rest,
+                final_margin = ?call_overrides.final_margin,
+                "bump_check overrides"
+            );
+        }
+        let previous = self.active_bump.lock()
+            .map(|mut active| std::mem::replace(&mut *active, call_overrides))
+            .unwrap_or_default();
+        let result = f();
+        if let Ok(mut active) = self.active_bump.lock() {
+            *active = previous;
+        }
+        result
+    }
 
-            // Stepper is bumping - move it up until cleared
-            let mut cleared = false;
-            let mut iterations = 0u32;
+    /// The bump_check strategy in effect for the running operation
+    pub fn run_bump_strategy(&self) -> BumpCheckStrategy {
+        let overrides = self.active_bump.lock().map(|b| *b).unwrap_or_default();
+        self.get_bump_strategy().with_overrides(overrides)
+    }
 
-            loop {
-                if let Some(exit) = exit_flag {
-                    if exit.load(std::sync::atomic::Ordering::Relaxed) {
-                        return Ok(messages.join("\n"));
-                    }
-                }
+    fn active_rests(&self) -> RestOverrides {
+        self.active_rests.lock().map(|r| *r).unwrap_or_default()
+    }
 
-                let current_pos = positions.get(stepper_idx).copied().unwrap_or(0);
-                if current_pos >= max_pos {
-                    stepper_ops.disable(stepper_idx)?;
-                    messages.push(format!(
-                        "\nCRITICAL: DISABLING stepper {}. Reason: Bumping at max_pos {}.",
-                        stepper_idx, max_pos
-                    ));
-                    break;
-                }
+    fn rest_z(&self) {
+        Self::sleep_for(self.active_rests().z_rest.unwrap_or_else(|| self.get_z_rest()));
+    }
 
-                let remaining = max_pos - current_pos;
-                let move_delta = remaining.min(z_up_step);
-                self.rel_move_z_no_rest(stepper_ops, stepper_idx, move_delta)?;
-                // Position is updated by refresh_positions() - Arduino is source of truth
+    fn rest_x(&self) {
+        Self::sleep_for(self.active_rests().x_rest.unwrap_or_else(|| self.get_x_rest()));
+    }
 
-                // Check if still bumping after move
-                let still_bumping = match gpio.press_check(Some(gpio_index)) {
-                    Ok(states) => states.get(0).copied().unwrap_or(false),
-                    Err(e) => {
-                        messages.push(format!("GPIO error for stepper {}: {}", stepper_idx, e));
-                        false // Assume cleared on error
-                    }
-                };
+    /// X honours the running operation's X_REST override; other axes use their own rest
+    fn rest_axis(&self, axis: &LinearAxis) {
+        if axis.is_x() {
+            self.rest_x();
+        } else {
+            Self::sleep_for(axis.rest.lock().map(|r| *r).unwrap_or(1.0));
+        }
+    }
+
+    fn rest_tune(&self) {
+        Self::sleep_for(self.active_rests().tune_rest.unwrap_or_else(|| self.get_tune_rest()));
+    }
+
+    fn rest_lap(&self) {
+        Self::sleep_for(self.active_rests().lap_rest.unwrap_or_else(|| self.get_lap_rest()));
+    }
+
+    fn rel_move_z_with_rest<T: StepperOperations>(&self, stepper_ops: &mut T, stepper: usize, delta: i32, rest: bool) -> Result<()> {
+        stepper_ops.rel_move(stepper, delta)?;
+        self.z_moves.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        if rest {
+            self.rest_z();
+        }
+        Ok(())
+    }
+
+    fn rel_move_z<T: StepperOperations>(&self, stepper_ops: &mut T, stepper: usize, delta: i32) -> Result<()> {
+        self.rel_move_z_with_rest(stepper_ops, stepper, delta, true)
+    }
+
+    fn rel_move_z_no_rest<T: StepperOperations>(&self, stepper_ops: &mut T, stepper: usize, delta: i32) -> Result<()> {
+        self.rel_move_z_with_rest(stepper_ops, stepper, delta, false)
+    }
+
+    fn rel_move_x<T: StepperOperations>(&self, stepper_ops: &mut T, stepper: usize, delta: i32) -> Result<()> {
+        stepper_ops.rel_move(stepper, delta)?;
+        self.rest_x();
+        Ok(())
+    }
+
+    fn rel_move_axis<T: StepperOperations>(&self, axis: &LinearAxis, stepper_ops: &mut T, delta: i32) -> Result<()> {
+        stepper_ops.rel_move(axis.step_index, delta)?;
+        self.rest_axis(axis);
+        Ok(())
+    }
+
+    /// Only the X carriage crosses the Z hardware (X_CLEARANCE)
+    fn clear_axis_path<T: StepperOperations>(&self, axis: &LinearAxis, stepper_ops: &mut T, positions: &mut [i32], from: i32, to: i32, messages: &mut Vec<String>) -> Result<()> {
+        if axis.is_x() {
+            self.clear_x_path(stepper_ops, positions, from, to, messages)?;
+        }
+        Ok(())
+    }
+
+    /// Pick up an x_step changed in stepper_gui while X seeks
+    fn sync_axis_step(&self, axis: &LinearAxis, socket_path: Option<&str>) {
+        if let (true, Some(socket)) = (axis.is_x(), socket_path) {
+            if let Ok(x_step) = Self::fetch_x_step_from_socket(socket) {
+                self.set_x_step(x_step);
+            }
+        }
+    }
+
+    /// Move X by `delta` and wait up to X_VERIFY.TIMEOUT_MS for the board to report it,
+    /// copying the reported X into `positions`. Returns false when the move never showed
+    /// up; true also when the implementation cannot report positions (nothing to check).
+    fn verified_rel_move_x<T: StepperOperations>(&self, stepper_ops: &mut T, positions: &mut [i32], stepper: usize, delta: i32) -> Result<bool> {
+        let settings = self.x_verify_settings;
+        let before = match stepper_ops.reported_positions() {
+            Ok(Some(reported)) => reported.get(stepper).copied(),
+            _ => None,
+        };
+        self.rel_move_x(stepper_ops, stepper, delta)?;
+        let Some(before) = before else {
+            return Ok(true);
+        };
+        let target = before + delta;
+        let deadline = Instant::now() + settings.timeout;
+        loop {
+            if let Ok(Some(reported)) = stepper_ops.reported_positions() {
+                if let Some(&pos) = reported.get(stepper) {
+                    if let Some(slot) = positions.get_mut(stepper) {
+                        *slot = pos;
+                    }
+                    if (pos - target).abs() <= settings.tolerance {
+                        return Ok(true);
+                    }
+                }
+            }
+            if Instant::now() >= deadline {
+                return Ok(false);
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    /// Check X_CLEARANCE before an X move from `from` to `to`. Z steppers below the Z_MIN of
+    /// a zone on the path are raised to it first (noted in `messages`); with ACTION refuse, or
+    /// when a low stepper is disabled and can't be raised, the move is refused with a
+    /// ClearanceViolation instead of driving the carriage into the Z hardware.
+    fn clear_x_path<T: StepperOperations>(&self, stepper_ops: &mut T, positions: &mut [i32], from: i32, to: i32, messages: &mut Vec<String>) -> Result<()> {
+        if self.x_clearance.zones.is_empty() {
+            return Ok(());
+        }
+        let z_indices = self.get_z_stepper_indices();
+        // Z heights as the board reports them where it can: not every Z move updates `positions`
+        if let Ok(Some(reported)) = stepper_ops.reported_positions() {
+            for &idx in &z_indices {
+                if let (Some(&pos), Some(slot)) = (reported.get(idx), positions.get_mut(idx)) {
+                    *slot = pos;
+                }
+            }
+        }
+        let low: Vec<(usize, i32, i32)> = z_indices
+            .into_iter()
+            .filter_map(|idx| {
+                let required = self.x_clearance.required_z(from, to, self.topology.sensor_for(idx)? / 2)?;
+                let pos = positions.get(idx).copied()?;
+                (pos < required).then_some((idx, pos, required))
+            })
+            .collect();
+        if low.is_empty() {
+            return Ok(());
+        }
+        let enabled_states = self.get_all_stepper_enabled();
+        let refuse = self.x_clearance.action == ClearanceAction::Refuse
+            || low.iter().any(|(idx, _, _)| !enabled_states.get(idx).copied().unwrap_or(false));
+        if refuse {
+            let low = low
+                .iter()
+                .map(|&(idx, pos, required)| format!("{} at {} (needs {})", self.stepper_label(idx), pos, required))
+                .collect::<Vec<_>>()
+                .join(", ");
+            tracing::warn!("X move {} -> {} refused: {} below X_CLEARANCE", from, to, low);
+            return Err(Error::ClearanceViolation { from, to, low, partial: messages.join("\n") });
+        }
+        for (idx, pos, required) in low {
+            stepper_ops.abs_move(idx, required)?;
+            if let Some(slot) = positions.get_mut(idx) {
+                *slot = required;
+            }
+            messages.push(format!("Raised {} from {} to {} for X clearance ({} -> {})", self.stepper_label(idx), pos, required, from, to));
+        }
+        self.rest_z();
+        Ok(())
+    }
+
+    /// Sweep X step with obstruction detection: counts unconfirmed moves in `failures`
+    /// and, once X_VERIFY.MAX_FAILURES have failed in a row, raises every enabled Z
+    /// stepper to its max position and returns an Obstruction error.
+    fn sweep_step_x<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        x_step_index: usize,
+        delta: i32,
+        failures: &mut u32,
+        messages: &mut Vec<String>,
+    ) -> Result<()> {
+        let from = positions.get(x_step_index).copied().unwrap_or(0);
+        self.clear_x_path(stepper_ops, positions, from, from + delta, messages)?;
+        if self.verified_rel_move_x(stepper_ops, positions, x_step_index, delta)? {
+            *failures = 0;
+            return Ok(());
+        }
+        *failures += 1;
+        let max_failures = self.x_verify_settings.max_failures;
+        messages.push(format!("X move of {} from X={} not confirmed ({} of {})", delta, from, failures, max_failures));
+        if *failures < max_failures {
+            return Ok(());
+        }
+        
+        tracing::warn!("X carriage obstructed near X={}; raising Z steppers", from);
+        let enabled_states = self.get_all_stepper_enabled();
+        for stepper_idx in self.get_z_stepper_indices() {
+            if !enabled_states.get(&stepper_idx).copied().unwrap_or(false) {
+                continue;
+            }
+            let safe_pos = max_positions.get(&stepper_idx).copied().unwrap_or(100);
+            match stepper_ops.abs_move(stepper_idx, safe_pos) {
+                Ok(()) => {
+                    if let Some(slot) = positions.get_mut(stepper_idx) {
+                        *slot = safe_pos;
+                    }
+                    messages.push(format!("Raised {} to {}", self.stepper_label(stepper_idx), safe_pos));
+                }
+                Err(e) => messages.push(format!("Failed to raise {}: {}", self.stepper_label(stepper_idx), e)),
+            }
+        }
+        self.rest_z();
+        Err(Error::Obstruction { at: from, failures: *failures, partial: messages.join("\n") })
+    }
+
+    fn rel_move_tune<T: StepperOperations>(&self, stepper_ops: &mut T, stepper: usize, delta: i32) -> Result<()> {
+        stepper_ops.rel_move(stepper, delta)?;
+        self.rest_tune();
+        Ok(())
+    }
+    
+    /// Set lap_rest value
+    pub fn set_lap_rest(&self, rest: f32) {
+        if let Ok(mut rest_val) = self.lap_rest.lock() {
+            *rest_val = rest;
+        }
+    }
+    
+    /// Get lap_rest value
+    pub fn get_lap_rest(&self) -> f32 {
+        self.lap_rest.lock()
+            .map(|r| *r)
+            .unwrap_or(4.0)
+    }
+    
+    /// Set adjustment_level value
+    pub fn set_adjustment_level(&self, level: i32) {
+        if let Ok(mut level_val) = self.adjustment_level.lock() {
+            *level_val = level;
+        }
+    }
+    
+    /// Get adjustment_level value
+    pub fn get_adjustment_level(&self) -> i32 {
+        self.adjustment_level.lock()
+            .map(|l| *l)
+            .unwrap_or(4)
+    }
+    
+    /// Set retry_threshold value
+    pub fn set_retry_threshold(&self, threshold: i32) {
+        if let Ok(mut thresh) = self.retry_threshold.lock() {
+            *thresh = threshold;
+        }
+    }
+    
+    /// Get retry_threshold value
+    pub fn get_retry_threshold(&self) -> i32 {
+        self.retry_threshold.lock()
+            .map(|t| *t)
+            .unwrap_or(50)
+    }
+    
+    /// Set delta_threshold value
+    pub fn set_delta_threshold(&self, threshold: i32) {
+        if let Ok(mut thresh) = self.delta_threshold.lock() {
+            *thresh = threshold;
+        }
+    }
+    
+    /// Get delta_threshold value
+    pub fn get_delta_threshold(&self) -> i32 {
+        self.delta_threshold.lock()
+            .map(|t| *t)
+            .unwrap_or(50)
+    }
+    
+    /// Set z_variance_threshold value
+    pub fn set_z_variance_threshold(&self, threshold: i32) {
+        if let Ok(mut thresh) = self.z_variance_threshold.lock() {
+            *thresh = threshold;
+        }
+    }
+    
+    /// Get z_variance_threshold value
+    pub fn get_z_variance_threshold(&self) -> i32 {
+        self.z_variance_threshold.lock()
+            .map(|t| *t)
+            .unwrap_or(50)
+    }
+    
+    /// Set x_start value
+    pub fn set_x_start(&self, start: i32) {
+        if let Ok(mut val) = self.x_start.lock() {
+            *val = start;
+        }
+    }
+    
+    /// Get x_start value
+    pub fn get_x_start(&self) -> i32 {
+        self.x_start.lock()
+            .map(|s| *s)
+            .unwrap_or(0)
+    }
+    
+    /// Set x_finish value
+    pub fn set_x_finish(&self, finish: i32) {
+        if let Ok(mut val) = self.x_finish.lock() {
+            *val = finish;
+        }
+    }
+    
+    /// Get x_finish value
+    pub fn get_x_finish(&self) -> i32 {
+        self.x_finish.lock()
+            .map(|f| *f)
+            .unwrap_or(100)
+    }
+    
+    /// Set x_step value
+    pub fn set_x_step(&self, step: i32) {
+        if let Ok(mut val) = self.x_step.lock() {
+            *val = step;
+        }
+    }
+    
+    /// Get x_step value
+    pub fn get_x_step(&self) -> i32 {
+        self.x_step.lock()
+            .map(|s| *s)
+            .unwrap_or(10)
+    }
+    
+    /// Set the x_sweep lap limit (0 = one lap, or until stopped for ping-pong)
+    pub fn set_sweep_laps(&self, laps: u32) {
+        if let Ok(mut val) = self.sweep_laps.lock() {
+            *val = laps;
+        }
+    }
+    
+    /// Get the x_sweep lap limit
+    pub fn get_sweep_laps(&self) -> u32 {
+        self.sweep_laps.lock()
+            .map(|l| *l)
+            .unwrap_or(0)
+    }
+    
+    /// Set the x_sweep convergence threshold (Z moves per lap, 0 = off)
+    pub fn set_sweep_converge_below(&self, moves: u32) {
+        if let Ok(mut val) = self.sweep_converge_below.lock() {
+            *val = moves;
+        }
+    }
+    
+    /// Get the x_sweep convergence threshold
+    pub fn get_sweep_converge_below(&self) -> u32 {
+        self.sweep_converge_below.lock()
+            .map(|m| *m)
+            .unwrap_or(0)
+    }
+    
+    /// Lap reports of the running or last x_sweep
+    pub fn get_last_evaluation(&self) -> Option<PassEvaluation> {
+        self.last_evaluation.lock().ok().and_then(|e| e.clone())
+    }
+    
+    pub fn get_last_sweep(&self) -> Option<SweepReport> {
+        self.last_sweep.lock()
+            .ok()
+            .and_then(|s| s.clone())
+    }
+    
+    /// Get Z stepper indices based on configuration
+    pub fn get_z_stepper_indices(&self) -> Vec<usize> {
+        self.topology.z_indices()
+    }
+    
+    /// Enable or disable a stepper on the hardware and in the enabled map together.
+    /// Enabling is recorded only once the command went out; disabling always removes
+    /// the stepper from operations, even when cutting its current failed.
+    pub fn apply_stepper_enabled<T: StepperOperations + ?Sized>(&self, stepper_ops: &mut T, stepper_idx: usize, enabled: bool) -> Result<()> {
+        if enabled {
+            stepper_ops.enable(stepper_idx)?;
+            self.set_stepper_enabled(stepper_idx, true);
+            Ok(())
+        } else {
+            self.set_stepper_enabled(stepper_idx, false);
+            stepper_ops.disable(stepper_idx)
+        }
+    }
+    
+    /// Set stepper enable state (map only - see apply_stepper_enabled)
+    pub fn set_stepper_enabled(&self, stepper_idx: usize, enabled: bool) {
+        if let Ok(mut enabled_map) = self.stepper_enabled.lock() {
+            enabled_map.insert(stepper_idx, enabled);
+        }
+    }
+    
+    /// Get stepper enable state
+    pub fn get_stepper_enabled(&self, stepper_idx: usize) -> bool {
+        self.stepper_enabled.lock()
+            .map(|map| map.get(&stepper_idx).copied().unwrap_or(false))
+            .unwrap_or(false)
+    }
+    
+    /// Get all stepper enabled states (clone of internal map)
+    pub fn get_all_stepper_enabled(&self) -> HashMap<usize, bool> {
+        self.stepper_enabled.lock()
+            .map(|map| map.clone())
+            .unwrap_or_default()
+    }
+    
+    /// Update voice_count and amp_sum from the newest partials frame: from the partials
+    /// feed when there is one (skipped when no new frame arrived), else read straight
+    /// from shared memory. Updates that found a frame are timed for the Analysis probe.
+    pub fn update_audio_analysis(&self) {
+        let started = Instant::now();
+        if let Some(feed) = &self.partials_feed {
+            let mut feed = feed.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(mut frame) = feed.take_new() {
+                Self::inject_frame_faults(&mut frame);
+                self.analyse_frame(&frame);
+                profiling::record(Probe::Analysis, started.elapsed());
+            }
+            return;
+        }
+        // Get actual channel count from control file, or use a large number to read all available channels
+        let num_channels_hint = audmon_client::control_layout()
+            .map(|(ch, _)| ch)
+            .unwrap_or(100); // Use large number to read all available channels if control file not available
+        let mut frame = self.scratch_frame.lock().unwrap_or_else(|e| e.into_inner());
+        if audmon_client::fill_partials(&mut frame, num_channels_hint, self.partials_per_channel()) {
+            Self::inject_frame_faults(&mut frame);
+            self.analyse_frame(&frame);
+            profiling::record(Probe::Analysis, started.elapsed());
+        }
+    }
+    
+    /// Garble the frame when the fault injector's shm_corrupt strikes
+    #[cfg(feature = "fault-injection")]
+    fn inject_frame_faults(frame: &mut PartialsFrame) {
+        let Some(faults) = crate::fault_injection::active() else { return };
+        if faults.strike(crate::fault_injection::Fault::ShmCorrupt) {
+            for ch in 0..frame.num_channels() {
+                faults.scramble(frame.channel_mut(ch));
+            }
+        }
+    }
+    
+    #[cfg(not(feature = "fault-injection"))]
+    fn inject_frame_faults(_frame: &mut PartialsFrame) {}
+    
+    /// Recompute voice_count and amp_sum in place from one frame
+    fn analyse_frame(&self, frame: &PartialsFrame) {
+        self.track_partials_freshness(frame);
+        // Use actual number of channels from audio data (not limited by string_num)
+        let num_channels = frame.num_channels();
+        if num_channels == 0 {
+            return;
+        }
+        self.partials_per_channel.store(frame.channel(0).len().max(1), std::sync::atomic::Ordering::Relaxed);
+        
+        if let (Ok(voice_floor), Ok(mut voice_count)) = (self.voice_floor.lock(), self.voice_count.lock()) {
+            // Follow the actual channel count (not string_num), shrinking too, so channels a
+            // reconfigured audmon no longer writes do not keep their last counts
+            voice_count.resize(num_channels, 0);
+            for (ch_idx, channel_partials) in frame.channels().enumerate() {
+                voice_count[ch_idx] = calculate_voice_count(ch_idx, channel_partials, &voice_floor);
+            }
+        }
+        
+        if let Ok(mut amp_sum) = self.amp_sum.lock() {
+            amp_sum.resize(num_channels, 0.0);
+            for (ch_idx, channel_partials) in frame.channels().enumerate() {
+                amp_sum[ch_idx] = calculate_amp_sum(channel_partials);
+            }
+            if let Ok(mut history) = self.amp_history.lock() {
+                history.push(Instant::now(), &amp_sum);
+            }
+        }
+
+        if let Ok(mut pitch) = self.pitch.lock() {
+            pitch.clear();
+            pitch.extend(frame.channels().map(tension::estimate_pitch));
+        }
+    }
+    
+    /// Note a new frame when the frame marker or the data changed since the last call
+    fn track_partials_freshness(&self, partials: &PartialsFrame) {
+        let control = read_audio_control();
+        if let Ok(mut freshness) = self.partials_freshness.lock() {
+            freshness.observe(partials, control.as_ref());
+        }
+        if let Ok(mut current) = self.audio_control.lock() {
+            *current = control;
+        }
+    }
+    
+    /// audmon's label for an audio channel (its JACK port), when its control file sends labels
+    pub fn audio_channel_source(&self, ch_idx: usize) -> Option<String> {
+        self.audio_control.lock().ok()?.as_ref()?.label(ch_idx).map(str::to_string)
+    }
+    
+    /// Sample rate audmon analyses at, when its control file says
+    pub fn audio_sample_rate(&self) -> Option<u32> {
+        self.audio_control.lock().ok()?.as_ref()?.sample_rate
+    }
+    
+    /// Time since the partials data last changed (None if no frame seen yet)
+    pub fn partials_age(&self) -> Option<Duration> {
+        self.partials_freshness.lock()
+            .ok()
+            .and_then(|f| f.age())
+    }
+    
+    /// Set the partials staleness limit in seconds (0 disables the interlock)
+    pub fn set_partials_stale_limit(&self, seconds: f32) {
+        if let Ok(mut limit) = self.partials_stale_limit.lock() {
+            *limit = seconds.max(0.0);
+        }
+    }
+    
+    /// Get the partials staleness limit in seconds
+    pub fn get_partials_stale_limit(&self) -> f32 {
+        self.partials_stale_limit.lock()
+            .map(|l| *l)
+            .unwrap_or(2.0)
+    }
+    
+    /// (section, YAML key, current value) of every setting that can change at run time
+    fn tunable_settings(&self) -> Vec<(&'static str, &'static str, String)> {
+        let strategy = self.get_bump_strategy();
+        let voice_floor = self.get_voice_floor();
+        let mut floors: Vec<(usize, f32)> = voice_floor.channels.iter().map(|(&ch, &floor)| (ch, floor)).collect();
+        floors.sort_by_key(|&(ch, _)| ch);
+        vec![
+            ("Steps", "Z_UP_STEP", self.get_z_up_step().to_string()),
+            ("Steps", "Z_DOWN_STEP", self.get_z_down_step().to_string()),
+            ("Steps", "ADAPTIVE_Z_STEP", self.get_adaptive_z_step().to_string()),
+            ("Steps", "Z_MAX_STEP", self.get_z_max_step().to_string()),
+            ("Steps", "STRING_Z_STEPS", self.string_z_steps_text()),
+            ("Rests", "TUNE_REST", self.get_tune_rest().to_string()),
+            ("Rests", "X_REST", self.get_x_rest().to_string()),
+            ("Rests", "Z_REST", self.get_z_rest().to_string()),
+            ("Rests", "LAP_REST", self.get_lap_rest().to_string()),
+            ("Adjustment", "ADJUSTMENT_LEVEL", self.get_adjustment_level().to_string()),
+            ("Adjustment", "RETRY_THRESHOLD", self.get_retry_threshold().to_string()),
+            ("Adjustment", "DELTA_THRESHOLD", self.get_delta_threshold().to_string()),
+            ("Adjustment", "Z_VARIANCE_THRESHOLD", self.get_z_variance_threshold().to_string()),
+            ("Sweep", "X_START", self.get_x_start().to_string()),
+            ("Sweep", "X_FINISH", self.get_x_finish().to_string()),
+            ("Sweep", "X_STEP", self.get_x_step().to_string()),
+            ("Sweep", "SWEEP_LAPS", self.get_sweep_laps().to_string()),
+            ("Sweep", "SWEEP_CONVERGE_BELOW", self.get_sweep_converge_below().to_string()),
+            ("Bump check", "BUMP_CHECK_ENABLE", self.get_bump_check_enable().to_string()),
+            ("Bump check", "BUMP_RETRACT_STEP", strategy.retract_step.map_or("z_up_step".to_string(), |step| step.to_string())),
+            ("Bump check", "BUMP_CLEAR_READINGS", strategy.clear_readings.to_string()),
+            ("Bump check", "BUMP_SETTLE_REST", strategy.settle_rest.to_string()),
+            ("Bump check", "BUMP_FINAL_MARGIN", strategy.final_margin.to_string()),
+            ("Performance", "PERFORMANCE_REST", self.get_performance_rest().to_string()),
+            ("Performance", "PERFORMANCE_BUMP_INTERVAL", self.get_performance_bump_interval().to_string()),
+            ("Performance", "PERFORMANCE_USE_CONTROLLER", self.get_performance_use_controller().to_string()),
+            ("Audio", "VOICE_FLOOR_MODE", if voice_floor.relative { "relative" } else { "absolute" }.to_string()),
+            ("Audio", "VOICE_FLOOR", voice_floor.default.to_string()),
+            ("Audio", "VOICE_FLOORS", format!("{:?}", floors)),
+            ("Audio", "PARTIALS_STALE_LIMIT", self.get_partials_stale_limit().to_string()),
+        ]
+    }
+    
+    /// "string 0: up 4, down -4; ..." for the strings with their own Z steps, "none" without
+    fn string_z_steps_text(&self) -> String {
+        let steps = self.string_z_steps.lock().map(|s| s.clone()).unwrap_or_default();
+        let show = |step: Option<i32>| step.map_or("global".to_string(), |s| s.to_string());
+        let parts: Vec<String> = steps.iter().enumerate()
+            .filter(|(_, (up, down))| up.is_some() || down.is_some())
+            .map(|(idx, &(up, down))| format!("string {}: up {}, down {}", idx, show(up), show(down)))
+            .collect();
+        if parts.is_empty() { "none".to_string() } else { parts.join("; ") }
+    }
+    
+    /// Every setting in effect with where it came from: built-in default, the host
+    /// section of string_driver.yaml, changed since start, or learned by a calibration
+    pub fn effective_config(&self) -> EffectiveConfig {
+        let mut config = EffectiveConfig {
+            host: self.hostname.clone(),
+            config_file: crate::config_loader::config_path().display().to_string(),
+            settings: Vec::new(),
+        };
+        for (section, key, value) in self.tunable_settings() {
+            let source = self.config_baseline.source(key, &value);
+            config.push(section, key, value, source);
+        }
+        let blocks: [(&str, String); 10] = [
+            ("Z_CONTROLLER", format!("{:?}", self.z_controller_settings)),
+            ("RESPONSE_MAP", format!("{:?}", self.response_map_settings)),
+            ("HEIGHT_CALIBRATION", format!("{:?}", self.height_settings)),
+            ("Z_HOME", format!("{:?}", self.z_home_settings)),
+            ("X_VERIFY", format!("{:?}", self.x_verify_settings)),
+            ("X_REGIONS", format!("{:?}", self.x_regions)),
+            ("X_CLEARANCE", format!("{:?}", self.x_clearance)),
+            ("PARK", format!("{:?}", self.park_settings)),
+            ("SENSOR_HEALTH", format!("{:?}", self.sensor_health_settings)),
+            ("BOW_DRIVE", format!("{:?}", self.bow_drive)),
+        ];
+        for (key, value) in blocks {
+            let source = if self.config_baseline.in_yaml_either_case(key) { Source::Yaml } else { Source::Default };
+            config.push("Blocks", key, value, source);
+        }
+        if let Some(map) = self.get_height_map() {
+            let value = format!("{} channel curve(s) recorded {}", map.curves.len(), map.recorded_at);
+            config.push("Learned", "HEIGHT_MAP", value, Source::Learned);
+        }
+        for line in self.tuner_safety_report() {
+            let (string, curve) = line.split_once(": ").unwrap_or(("", line.as_str()));
+            config.push("Learned", &format!("PITCH_CURVE {}", string), curve, Source::Learned);
+        }
+        config
+    }
+    
+    /// Why the partials data can't be trusted right now, or None if it is fresh
+    /// (or the interlock is off)
+    pub fn partials_stale_reason(&self) -> Option<String> {
+        let limit = self.get_partials_stale_limit();
+        if limit <= 0.0 {
+            return None;
+        }
+        match self.partials_age() {
+            None => Some("no partials received from audio_monitor".to_string()),
+            Some(age) if age.as_secs_f32() > limit => Some(format!(
+                "partials data is stale (last frame {:.1}s ago, limit {:.1}s) - is audio_monitor running?",
+                age.as_secs_f32(), limit
+            )),
+            Some(_) => None,
+        }
+    }
+    
+    /// Block until the partials data is fresh again. Reports the pause and the
+    /// resume through `messages` and `progress_sender`; `interject` runs what was
+    /// asked for meanwhile (see run_interjections).
+    /// Returns false if the exit flag was set while waiting.
+    fn wait_for_fresh_partials(
+        &self,
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        progress_sender: Option<&std::sync::mpsc::Sender<String>>,
+        messages: &mut Vec<String>,
+        interject: &mut dyn FnMut(&mut Vec<String>),
+    ) -> bool {
+        let Some(reason) = self.partials_stale_reason() else { return true };
+        let paused_msg = format!("Pausing: {}", reason);
+        tracing::warn!("{}", paused_msg);
+        if let Some(sender) = progress_sender {
+            let _ = sender.send(paused_msg.clone());
+        }
+        messages.push(paused_msg);
+        if let Ok(mut machine) = self.state.lock() {
+            machine.pause();
+        }
+        let started = Instant::now();
+        while self.partials_stale_reason().is_some() {
+            if let Some(exit) = exit_flag {
+                if exit.load(std::sync::atomic::Ordering::Relaxed) {
+                    return false;
+                }
+            }
+            Self::sleep_interruptible(0.5, exit_flag);
+            interject(messages);
+        }
+        if let Ok(mut machine) = self.state.lock() {
+            machine.resume();
+        }
+        let resumed_msg = format!("Resuming: partials data fresh again after {:.1}s", started.elapsed().as_secs_f32());
+        tracing::info!("{}", resumed_msg);
+        if let Some(sender) = progress_sender {
+            let _ = sender.send(resumed_msg.clone());
+        }
+        messages.push(resumed_msg);
+        true
+    }
+    
+    /// Partials per channel in the last analysed frame (the control file's before one):
+    /// the most voices a channel can count
+    pub fn partials_per_channel(&self) -> usize {
+        self.partials_per_channel.load(std::sync::atomic::Ordering::Relaxed)
+    }
+    
+    /// Get voice_count array (clone)
+    pub fn get_voice_count(&self) -> Vec<usize> {
+        self.voice_count.lock()
+            .map(|vc| vc.clone())
+            .unwrap_or_default()
+    }
+    
+    /// Get amp_sum array (clone)
+    pub fn get_amp_sum(&self) -> Vec<f32> {
+        self.amp_sum.lock()
+            .map(|asum| asum.clone())
+            .unwrap_or_default()
+    }
+
+    /// Per-channel pitch (see tension::estimate_pitch)
+    pub fn get_pitch(&self) -> Vec<Option<f32>> {
+        self.pitch.lock()
+            .map(|pitch| pitch.clone())
+            .unwrap_or_default()
+    }
+
+    /// Pitch heard on a string's channel (CHANNEL_MAP)
+    pub fn string_pitch(&self, string_idx: usize) -> Option<f32> {
+        let channel = self.topology.string(string_idx)?.channel?;
+        self.get_pitch().get(channel).copied().flatten()
+    }
+    
+    /// Current instrument state, whether the Z reference is established, and since when
+    pub fn instrument_state(&self) -> (InstrumentState, bool, Instant) {
+        self.state.lock()
+            .map(|m| (m.state().clone(), m.calibrated(), m.since()))
+            .unwrap_or((InstrumentState::Uninitialized, false, Instant::now()))
+    }
+    
+    /// Whether `operation` may start in the current state; Err says why not
+    pub fn can_start(&self, operation: &str) -> std::result::Result<(), String> {
+        self.state.lock().map_err(|_| "state lock poisoned".to_string())?.can_start(operation)
+    }
+    
+    /// Enter the running state for `operation` (re-checks the guards)
+    pub fn begin_operation(&self, operation: &str) -> Result<()> {
+        let mut machine = self.state.lock().map_err(|_| Error::Other("state lock poisoned".to_string()))?;
+        machine.begin(operation).map_err(|reason| Error::OperationAborted(format!("{} not started: {}", operation, reason)))
+    }
+    
+    /// Leave the running state; a stopped operation (OperationAborted) is not a fault
+    pub fn end_operation<T>(&self, operation: &str, outcome: &Result<T>) {
+        let Ok(mut machine) = self.state.lock() else {
+            return;
+        };
+        match outcome {
+            Ok(_) => machine.end(operation, Ok(())),
+            Err(Error::OperationAborted(_)) => machine.stop(),
+            Err(e) => machine.end(operation, Err(e.to_string())),
+        }
+    }
+    
+    /// Leave Faulted without a recovery operation; false if not faulted
+    pub fn clear_fault(&self) -> bool {
+        self.state.lock().map(|mut m| m.clear_fault()).unwrap_or(false)
+    }
+    
+    /// Probe for the built-in read-only operations, Motion for everything else
+    pub fn operation_category(&self, operation: &str) -> Category {
+        if OPERATIONS.iter().any(|op| op.name == operation && op.read_only) {
+            Category::Probe
+        } else {
+            Category::Motion
+        }
+    }
+    
+    /// Whether `operation` may start now (see arbitration); a motion request that
+    /// has to wait is queued, bump_check during a paused sweep goes to the sweep
+    pub fn request_operation(&self, operation: &str, priority: Priority) -> Admission {
+        let paused = matches!(self.instrument_state().0, InstrumentState::Paused(_));
+        let category = self.operation_category(operation);
+        let priority = Priority::for_operation(operation, priority);
+        match self.arbiter.lock() {
+            Ok(mut arbiter) => arbiter.admit(operation, category, priority, paused),
+            Err(_) => Admission::Refused("arbiter lock poisoned".to_string()),
+        }
+    }
+    
+    /// `operation`, admitted by request_operation, is starting
+    pub fn claim_operation(&self, operation: &str) {
+        let category = self.operation_category(operation);
+        if let Ok(mut arbiter) = self.arbiter.lock() {
+            arbiter.claim(operation, category);
+        }
+    }
+    
+    /// `operation` has finished (or never got going)
+    pub fn release_operation(&self, operation: &str) {
+        let category = self.operation_category(operation);
+        if let Ok(mut arbiter) = self.arbiter.lock() {
+            arbiter.release(operation, category);
+        }
+    }
+    
+    /// The queued request to start now that no motion operation runs
+    pub fn next_queued_operation(&self) -> Option<Request> {
+        self.arbiter.lock().ok()?.next_to_start()
+    }
+    
+    /// Drop the queued requests below `keep`; returns them
+    pub fn drop_queued_operations(&self, keep: Priority) -> Vec<Request> {
+        self.arbiter.lock().map(|mut arbiter| arbiter.drop_below(keep)).unwrap_or_default()
+    }
+    
+    /// Motion requests waiting for the running one, next first
+    pub fn queued_operations(&self) -> Vec<Request> {
+        self.arbiter.lock().map(|arbiter| arbiter.queued().to_vec()).unwrap_or_default()
+    }
+    
+    /// Run a probe (a read-only operation): reads sensors, never moves a stepper
+    pub fn run_probe(&self, operation: &str) -> Result<String> {
+        match operation {
+            "bump_probe" => self.bump_probe(),
+            other => Err(Error::Other(format!("{} is not a probe", other))),
+        }
+    }
+    
+    /// Run what was asked to interject while this operation is paused (see arbitration),
+    /// with the stepper link it holds: bump_check
+    fn run_interjections<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        progress_sender: Option<&std::sync::mpsc::Sender<String>>,
+        messages: &mut Vec<String>,
+    ) {
+        while let Some(operation) = self.arbiter.lock().ok().and_then(|mut arbiter| arbiter.take_interjection()) {
+            let outcome = match operation.as_str() {
+                "bump_check" => match self.bump_check(None, positions, max_positions, stepper_ops, exit_flag) {
+                    Ok(report) if report.message.trim().is_empty() => "no bumps detected".to_string(),
+                    Ok(report) => report.message.trim().to_string(),
+                    Err(e) => format!("error: {}", e),
+                },
+                other => format!("{} cannot run during a pause", other),
+            };
+            let line = format!("{} during the pause: {}", operation, outcome);
+            tracing::info!("{}", line);
+            if let Some(sender) = progress_sender {
+                let _ = sender.send(line.clone());
+            }
+            messages.push(line);
+        }
+    }
+    
+    /// Read one Z touch sensor, recording it for sensor health. A quarantined
+    /// sensor is treated as absent and always reads "not touching".
+    fn sensor_press_check(&self, gpio: &crate::gpio::GpioBoard, sensor: usize) -> Result<Vec<bool>> {
+        if self.sensor_quarantined(sensor) {
+            return Ok(vec![false]);
+        }
+        let states = gpio.press_check(Some(sensor))?;
+        if let (Some(&pressed), Ok(mut health)) = (states.first(), self.sensor_health.lock()) {
+            health.observe(sensor, pressed, Instant::now());
+        }
+        Ok(states)
+    }
+    
+    pub fn sensor_quarantined(&self, sensor: usize) -> bool {
+        self.sensor_health.lock().map(|h| h.is_quarantined(sensor)).unwrap_or(false)
+    }
+    
+    /// Quarantine (treat as absent) or restore a Z touch sensor
+    pub fn set_sensor_quarantined(&self, sensor: usize, quarantined: bool) {
+        if let Ok(mut health) = self.sensor_health.lock() {
+            health.set_quarantined(sensor, quarantined);
+        }
+        let label = self.sensor_label(sensor);
+        if quarantined {
+            tracing::warn!("Touch sensor of {} quarantined - no crash protection for it", label);
+        } else {
+            tracing::info!("Touch sensor of {} back in service", label);
+        }
+    }
+    
+    /// Quarantined Z touch sensors, lowest first
+    pub fn quarantined_sensors(&self) -> Vec<usize> {
+        self.sensor_health.lock().map(|h| h.quarantined()).unwrap_or_default()
+    }
+    
+    /// Z touch sensors that look stuck or dead
+    pub fn sensor_faults(&self) -> Vec<(usize, SensorFault)> {
+        self.sensor_health.lock()
+            .map(|h| h.faults(&self.sensor_health_settings, Instant::now()))
+            .unwrap_or_default()
+    }
+    
+    /// With SENSOR_HEALTH.AUTO_QUARANTINE, quarantine a faulty sensor instead of
+    /// disabling its stepper. True when the sensor was quarantined.
+    fn quarantine_instead_of_disable(&self, stepper_idx: usize, messages: &mut Vec<String>) -> bool {
+        let sensor = self.sensor_index(stepper_idx);
+        if !self.sensor_health_settings.auto_quarantine {
+            return false;
+        }
+        let fault = self.sensor_health.lock().ok().and_then(|h| h.fault(sensor, &self.sensor_health_settings, Instant::now()));
+        let Some(fault) = fault else {
+            return false;
+        };
+        self.set_sensor_quarantined(sensor, true);
+        messages.push(format!(
+            "\nTouch sensor of {} {} - quarantined instead of disabling the stepper.",
+            self.stepper_label(stepper_idx), fault.describe()
+        ));
+        true
+    }
+    
+    /// The bump_probe operation: which Z touch sensors are pressed, without moving
+    pub fn bump_probe(&self) -> Result<String> {
+        let gpio = self.gpio.as_ref().ok_or_else(|| Error::GpioUnavailable("GPIO not initialized".to_string()))?;
+        if !gpio.exist {
+            return Ok("no GPIO".to_string());
+        }
+        let status = self.get_bump_status();
+        let pressed: Vec<String> = status.iter().filter(|(_, bumping)| *bumping).map(|(idx, _)| self.stepper_label(*idx)).collect();
+        Ok(if pressed.is_empty() {
+            format!("Bump probe: all {} touch sensors clear", status.len())
+        } else {
+            format!("Bump probe: pressed: {}", pressed.join(", "))
+        })
+    }
+    
+    /// Note a pressed touch sensor with the X position and the channel's level then and before
+    fn record_bump(&self, stepper_idx: usize, positions: &[i32]) {
+        let now = Instant::now();
+        let string = self.string_of_z_stepper(stepper_idx);
+        let channel = string.and_then(|s| self.channel_for_string(s));
+        let (amp_sum, amp_baseline) = match (channel, self.amp_history.lock()) {
+            (Some(ch_idx), Ok(history)) => (history.latest(ch_idx), history.baseline(ch_idx, now)),
+            _ => (None, None),
+        };
+        let event = BumpEvent {
+            recorded_at: chrono::Utc::now(),
+            host: self.hostname.clone(),
+            stepper: stepper_idx,
+            string,
+            channel,
+            x_position: self.x_step_index().and_then(|x_idx| positions.get(x_idx).copied()),
+            z_position: positions.get(stepper_idx).copied().unwrap_or(0),
+            z_down_step: self.z_down_step_for(string),
+            amp_sum,
+            amp_baseline,
+        };
+        tracing::info!(
+            "Bump on {}: amp sum {:?} against {:?} before ({})",
+            self.stepper_label(stepper_idx), event.amp_sum, event.amp_baseline, event.verdict().name()
+        );
+        if let Ok(mut events) = self.bump_events.lock() {
+            if events.len() >= MAX_PENDING_BUMPS {
+                events.pop_front();
+            }
+            events.push_back(event);
+        }
+    }
+    
+    /// Bumps recorded since the last call, oldest first
+    pub fn take_bump_events(&self) -> Vec<BumpEvent> {
+        self.bump_events.lock().map(|mut events| events.drain(..).collect()).unwrap_or_default()
+    }
+    
+    /// The AUDIO_TRIGGER block, if the host has one
+    pub fn audio_trigger_settings(&self) -> Option<AudioTriggerSettings> {
+        self.audio_trigger.lock().ok()?.as_ref().map(|trigger| trigger.settings.clone())
+    }
+    
+    /// The audio trigger's state (None without AUDIO_TRIGGER)
+    pub fn audio_trigger_state(&self) -> Option<TriggerState> {
+        self.audio_trigger.lock().ok()?.as_ref().map(|trigger| trigger.state())
+    }
+    
+    /// The audio trigger's state with its progress, for display
+    pub fn describe_audio_trigger(&self) -> Option<String> {
+        self.audio_trigger.lock().ok()?.as_ref().map(|trigger| trigger.describe(Instant::now()))
+    }
+    
+    /// Arm or disarm the audio trigger; disarming leaves a started operation running
+    pub fn arm_audio_trigger(&self, armed: bool) {
+        if let Ok(mut trigger) = self.audio_trigger.lock() {
+            if let Some(trigger) = trigger.as_mut() {
+                if armed { trigger.arm() } else { trigger.disarm() }
+            }
+        }
+    }
+    
+    /// Feed the audio trigger the latest amp sums (none while the partials are stale).
+    /// `running`: its operation is running; `busy`: any motion operation is.
+    pub fn observe_audio_trigger(&self, running: bool, busy: bool) -> Option<TriggerAction> {
+        let amp_sums = if self.partials_stale_reason().is_some() { Vec::new() } else { self.get_amp_sum() };
+        self.audio_trigger.lock().ok()?.as_mut()?.observe(Instant::now(), &amp_sums, running, busy)
+    }
+    
+    /// Get bump status for all Z steppers
+    /// Returns Vec<(stepper_index, is_bumping)>
+    pub fn get_bump_status(&self) -> Vec<(usize, bool)> {
+        let mut status = Vec::new();
+        
+        if let Some(ref gpio) = self.gpio {
+            if !gpio.exist {
+                return status;
+            }
+            
+            let z_indices = self.get_z_stepper_indices();
+            for &stepper_idx in &z_indices {
+                let gpio_index = self.sensor_index(stepper_idx);
+                match self.sensor_press_check(gpio, gpio_index) {
+                    Ok(states) => {
+                        let is_bumping = states.get(0).copied().unwrap_or(false);
+                        status.push((stepper_idx, is_bumping));
+                    }
+                    Err(_) => {
+                        status.push((stepper_idx, false));
+                    }
+                }
+            }
+        }
+        
+        status
+    }
+    
+    /// Perform bump check on Z-steppers using the run's strategy (BUMP_* plus its overrides).
+    ///
+    /// See `bump_check_with_strategy` for the algorithm.
+    pub fn bump_check<T: StepperOperations>(
+        &self,
+        stepper_index: Option<usize>,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        stepper_ops: &mut T,
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+    ) -> Result<BumpReport> {
+        let strategy = self.run_bump_strategy();
+        self.bump_check_with_strategy(stepper_index, positions, max_positions, stepper_ops, exit_flag, &strategy)
+    }
+
+    /// Perform bump check on Z-steppers with an explicit (per-run) strategy.
+    ///
+    /// For each enabled Z-stepper (or the specified index):
+    /// 1. Poll the touch sensor; if not bumping, do nothing.
+    /// 2. If bumping, issue repeated upward moves of the retract step (default the string's `z_up_step`),
+    ///    waiting `settle_rest` after each move, until `clear_readings` consecutive sensor
+    ///    reads report clear or the reported position reaches `max_pos`.
+    /// 3. When cleared, retract a further `final_margin` (if any, clamped so the stepper
+    ///    stays at or below `max_pos`) and reset the controller position to
+    ///    `retract_step + final_margin` (no hardware motion for the reset).
+    /// 4. If the sensor never clears and the stepper is already at/above `max_pos`, disable it.
+    pub fn bump_check_with_strategy<T: StepperOperations>(
+        &self,
+        stepper_index: Option<usize>,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        stepper_ops: &mut T,
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        strategy: &BumpCheckStrategy,
+    ) -> Result<BumpReport> {
+        let _span = tracing::info_span!("bump_check", stepper = ?stepper_index).entered();
+        let gpio = self.gpio.as_ref().ok_or_else(|| Error::GpioUnavailable("GPIO not initialized".to_string()))?;
+        if !gpio.exist {
+            return Ok(BumpReport::clear("\nno GPIO"));
+        }
+
+        if !self.get_bump_check_enable() {
+            return Ok(BumpReport::clear("bump_check disabled - skipping"));
+        }
+
+        // Per stepper below: BUMP_RETRACT_STEP, else its string's z_up_step (positive when set)
+        let retract_step = strategy.retract_step.unwrap_or_else(|| self.get_z_up_step());
+        if retract_step <= 0 {
+            return Err(Error::ConfigInvalid(format!(
+                "Invalid retract step {} for bump_check: value must be positive to move away from the string",
+                retract_step
+            )));
+        }
+        if strategy.final_margin < 0 {
+            return Err(Error::ConfigInvalid(format!(
+                "Invalid final margin {} for bump_check: value must not be negative",
+                strategy.final_margin
+            )));
+        }
+        let clear_readings = strategy.clear_readings.max(1);
+
+        // Get all Z-stepper indices
+        let all_z_indices = self.get_z_stepper_indices();
+        
+        if all_z_indices.is_empty() {
+            return Ok(BumpReport::clear(String::new()));
+        }
+
+        // Build the list of steppers to probe: either all, or one specified
+        let steppers_to_check = if let Some(spec_idx) = stepper_index {
+            let idx_0_based = if spec_idx > 0 { spec_idx - 1 } else { spec_idx };
+            if idx_0_based < all_z_indices.len() {
+                vec![all_z_indices[idx_0_based]]
+            } else {
+                return Ok(BumpReport::clear(format!("\nInvalid stepper index: {}", spec_idx)));
+            }
+        } else {
+            all_z_indices.clone()
+        };
+
+        let enabled_states = self.get_all_stepper_enabled();
+        const MAX_MOVE_ITERATIONS: u32 = 50;
+        let mut messages = Vec::new();
+        let mut outcome = BumpOutcome::Clear;
+
+        for &stepper_idx in &steppers_to_check {
+            if let Some(exit) = exit_flag {
+                if exit.load(std::sync::atomic::Ordering::Relaxed) {
+                    return Ok(BumpReport { outcome, message: messages.join("\n") });
+                }
+            }
+
+            let enabled = enabled_states.get(&stepper_idx).copied().unwrap_or(false);
+            if !enabled {
+                continue;
+            }
+
+            let gpio_index = self.sensor_index(stepper_idx);
+            let max_pos = max_positions.get(&stepper_idx).copied().unwrap_or(100);
+            let retract_step = strategy.retract_step.unwrap_or_else(|| self.z_up_step_for(self.string_of_z_stepper(stepper_idx)));
+            
+            // Check initial bump state
+            let initial_bumping = match self.sensor_press_check(gpio, gpio_index) {
+                Ok(states) => states.get(0).copied().unwrap_or(false),
+                Err(e) => {
+                    messages.push(format!("GPIO error for {}: {}", self.stepper_label(stepper_idx), e));
+                    outcome = outcome.max(BumpOutcome::SensorError);
+                    continue; // Skip this stepper on GPIO error
+                }
+            };
+
+            // If not bumping, skip this stepper
+            if !initial_bumping {
+                continue;
+            }
+            self.record_bump(stepper_idx, positions);
+
+            // Stepper is bumping - move it up until cleared
+            let mut cleared = false;
+            let mut iterations = 0u32;
+            let mut retracted = 0;
+
+            loop {
+                if let Some(exit) = exit_flag {
+                    if exit.load(std::sync::atomic::Ordering::Relaxed) {
+                        return Ok(BumpReport { outcome, message: messages.join("\n") });
+                    }
+                }
+
+                let current_pos = positions.get(stepper_idx).copied().unwrap_or(0);
+                if current_pos >= max_pos {
+                    if self.quarantine_instead_of_disable(stepper_idx, &mut messages) {
+                        outcome = outcome.max(BumpOutcome::Quarantined);
+                        break;
+                    }
+                    self.apply_stepper_enabled(stepper_ops, stepper_idx, false)?;
+                    self.report_disabled(stepper_idx, &format!("{} disabled by bump_check: still bumping at max_pos {}", self.stepper_label(stepper_idx), max_pos));
+                    messages.push(format!(
+                        "\nCRITICAL: DISABLING {}. Reason: Bumping at max_pos {}.",
+                        self.stepper_label(stepper_idx), max_pos
+                    ));
+                    outcome = BumpOutcome::Disabled;
+                    break;
+                }
+
+                let remaining = max_pos - current_pos;
+                let move_delta = remaining.min(retract_step);
+                self.rel_move_z_no_rest(stepper_ops, stepper_idx, move_delta)?;
+                retracted += move_delta;
+                // Position is updated by refresh_positions() - Arduino is source of truth
 
-                if !still_bumping {
+                // Require consecutive clear readings, settling before each read
+                let mut clear_count = 0u32;
+                while clear_count < clear_readings {
+                    Self::sleep_for(strategy.settle_rest);
+                    let still_bumping = match self.sensor_press_check(gpio, gpio_index) {
+                        Ok(states) => states.get(0).copied().unwrap_or(false),
+                        Err(e) => {
+                            messages.push(format!("GPIO error for {}: {}", self.stepper_label(stepper_idx), e));
+                            outcome = outcome.max(BumpOutcome::SensorError);
+                            false // Assume cleared on error
+                        }
+                    };
+                    if still_bumping {
+                        break;
+                    }
+                    clear_count += 1;
+                }
+
+                if clear_count >= clear_readings {
                     cleared = true;
                     break;
                 }
@@ -889,26 +3410,36 @@ impl Operations {
 
                 iterations += 1;
                 if iterations >= MAX_MOVE_ITERATIONS {
-                    stepper_ops.disable(stepper_idx)?;
+                    self.apply_stepper_enabled(stepper_ops, stepper_idx, false)?;
+                    self.report_disabled(stepper_idx, &format!("{} disabled by bump_check after {} move attempts", self.stepper_label(stepper_idx), MAX_MOVE_ITERATIONS));
                     messages.push(format!(
-                        "\nCRITICAL: Stepper {} exceeded {} move attempts while bumping - disabling.",
-                        stepper_idx, MAX_MOVE_ITERATIONS
+                        "\nCRITICAL: {} exceeded {} move attempts while bumping - disabling.",
+                        self.stepper_label(stepper_idx), MAX_MOVE_ITERATIONS
                     ));
+                    outcome = BumpOutcome::Disabled;
                     break;
                 }
             }
 
             if cleared {
-                stepper_ops.reset(stepper_idx, z_up_step)?;
+                // The margin never takes the stepper past max_pos
+                let reached = positions.get(stepper_idx).copied().unwrap_or(0) + retracted;
+                let final_margin = strategy.final_margin.min(max_pos - reached).max(0);
+                if final_margin > 0 {
+                    self.rel_move_z_no_rest(stepper_ops, stepper_idx, final_margin)?;
+                }
+                let reset_pos = retract_step + final_margin;
+                stepper_ops.reset(stepper_idx, reset_pos)?;
                 // Position is updated by refresh_positions() - Arduino is source of truth
                 messages.push(format!(
-                    "\nStepper {} bump cleared - controller set to {}.",
-                    stepper_idx, z_up_step
+                    "\n{} bump cleared - controller set to {}.",
+                    self.stepper_label(stepper_idx), reset_pos
                 ));
+                outcome = outcome.max(BumpOutcome::Cleared);
             }
         }
 
-        Ok(messages.join("\n"))
+        Ok(BumpReport { outcome, message: messages.join("\n") })
     }
     
     /// Z-calibrate: Move Z steppers down until they touch sensors.
@@ -923,7 +3454,8 @@ impl Operations {
     /// - max_positions: Maximum positions for each stepper (index -> max_pos)
     /// - exit_flag: Optional exit flag to check for early return
     /// 
-    /// Returns message string describing results
+    /// Returns message string describing results. Strings are damped throughout
+    /// when DAMPERS lists z_calibrate under DURING.
     pub fn z_calibrate<T: StepperOperations>(
         &self,
         stepper_ops: &mut T,
@@ -931,21 +3463,33 @@ impl Operations {
         max_positions: &HashMap<usize, i32>,
         exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
     ) -> Result<String> {
-        let gpio = self.gpio.as_ref().ok_or_else(|| anyhow!("GPIO not initialized"))?;
+        self.with_dampers("z_calibrate", stepper_ops, |stepper_ops| {
+            self.z_calibrate_pass(stepper_ops, positions, max_positions, exit_flag)
+        })
+    }
+
+    fn z_calibrate_pass<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+    ) -> Result<String> {
+        let _span = tracing::info_span!("z_calibrate").entered();
+        let gpio = self.gpio.as_ref().ok_or_else(|| Error::GpioUnavailable("GPIO not initialized".to_string()))?;
         if !gpio.exist {
             return Ok("Z-Calibration requires GPIO".to_string());
         }
         
         let mut messages = Vec::new();
         messages.push("Running bump_check before Z calibration...".to_string());
-        let bump_msg_initial = self.bump_check(None, positions, max_positions, stepper_ops, exit_flag)?;
+        let bump_msg_initial = self.bump_check(None, positions, max_positions, stepper_ops, exit_flag)?.message;
         if !bump_msg_initial.trim().is_empty() {
             messages.push(bump_msg_initial);
         }
         
         let z_indices = self.get_z_stepper_indices();
         let enabled_states = self.get_all_stepper_enabled();
-        let z_down_step = self.get_z_down_step();
         let mut original_positions = std::collections::HashMap::new();
         for &idx in &z_indices {
             if let Some(pos) = positions.get(idx).copied() {
@@ -967,13 +3511,18 @@ impl Operations {
             
             let enabled = enabled_states.get(&stepper_idx).copied().unwrap_or(false);
             if !enabled {
-                messages.push(format!("Skipping disabled stepper {}", stepper_idx));
+                messages.push(format!("Skipping disabled {}", self.stepper_label(stepper_idx)));
                 continue;
             }
             
-            let gpio_index = stepper_idx.saturating_sub(self.z_first_index);
+            let gpio_index = self.sensor_index(stepper_idx);
+            if self.sensor_quarantined(gpio_index) {
+                messages.push(format!("Skipping {}: touch sensor quarantined", self.stepper_label(stepper_idx)));
+                continue;
+            }
             let max_pos = max_positions.get(&stepper_idx).copied().unwrap_or(100);
-            let min_pos = 0; // Default min_pos (could be made configurable)
+            let min_pos = Z_MIN_POS;
+            let z_down_step = self.z_down_step_for(self.string_of_z_stepper(stepper_idx));
             
             // Set position to max_pos without moving (like surfer.py's set_stepper)
             // This sets the Arduino's internal position counter without physical movement
@@ -989,13 +3538,13 @@ impl Operations {
                 // Check exit flag
                 if let Some(exit) = exit_flag {
                     if exit.load(std::sync::atomic::Ordering::Relaxed) {
-                        messages.push(format!("Calibration cancelled for stepper {}", stepper_idx));
+                        messages.push(format!("Calibration cancelled for {}", self.stepper_label(stepper_idx)));
                         break;
                     }
                 }
                 
                 // Check sensor BEFORE moving (surfer.py checks before move)
-                match gpio.press_check(Some(gpio_index)) {
+                match self.sensor_press_check(gpio, gpio_index) {
                     Ok(states) => {
                         if let Some(&is_touching) = states.get(0) {
                             if is_touching {
@@ -1005,17 +3554,23 @@ impl Operations {
                         }
                     }
                     Err(e) => {
-                        messages.push(format!("GPIO error for stepper {}: {}", stepper_idx, e));
+                        messages.push(format!("GPIO error for {}: {}", self.stepper_label(stepper_idx), e));
                         break;
                     }
                 }
                 
                 // Check if we've hit minimum position BEFORE moving
                 if pos_local <= min_pos {
-                    messages.push(format!("Stepper {} bottomed out during calibration (reached min_pos {} without touching) - disabling and leaving at current position", stepper_idx, min_pos));
+                    if let Ok(mut health) = self.sensor_health.lock() {
+                        health.calibrated(gpio_index, false);
+                    }
+                    if self.quarantine_instead_of_disable(stepper_idx, &mut messages) {
+                        break;
+                    }
+                    messages.push(format!("{} bottomed out during calibration (reached min_pos {} without touching) - disabling and leaving at current position", self.stepper_label(stepper_idx), min_pos));
                     // Disable the stepper since it can't reach the sensor
-                    self.set_stepper_enabled(stepper_idx, false);
-                    stepper_ops.disable(stepper_idx)?;
+                    self.apply_stepper_enabled(stepper_ops, stepper_idx, false)?;
+                    self.report_disabled(stepper_idx, &format!("{} disabled by z_calibrate: reached min_pos {} without touching", self.stepper_label(stepper_idx), min_pos));
                     break;
                 }
                 
@@ -1029,11 +3584,14 @@ impl Operations {
             }
             
             if touched {
+                if let Ok(mut health) = self.sensor_health.lock() {
+                    health.calibrated(gpio_index, true);
+                }
                 stepper_ops.reset(stepper_idx, 0)?;
                 // Position is updated by refresh_positions() - Arduino is source of truth
-                messages.push(format!("Stepper {} calibrated (touched sensor, reset to 0)", stepper_idx));
+                messages.push(format!("{} calibrated (touched sensor, reset to 0)", self.stepper_label(stepper_idx)));
             } else {
-                messages.push(format!("Stepper {} calibration incomplete", stepper_idx));
+                messages.push(format!("{} calibration incomplete", self.stepper_label(stepper_idx)));
             }
         }
         
@@ -1086,8 +3644,8 @@ impl Operations {
             for &stepper_idx in &z_indices {
                 let enabled = current_enabled_states.get(&stepper_idx).copied().unwrap_or(false);
                 if enabled {
-                    let gpio_index = stepper_idx.saturating_sub(self.z_first_index);
-                    match gpio.press_check(Some(gpio_index)) {
+                    let gpio_index = self.sensor_index(stepper_idx);
+                    match self.sensor_press_check(gpio, gpio_index) {
                         Ok(states) => {
                             if let Some(&is_touching) = states.get(0) {
                                 if is_touching {
@@ -1113,6 +3671,215 @@ impl Operations {
         Ok(messages.join("\n"))
     }
     
+    /// Step a Z stepper toward its sensor until it reads touched.
+    /// `pos_local` tracks the position as moves are issued.
+    fn approach_sensor<T: StepperOperations>(
+        &self,
+        gpio: &crate::gpio::GpioBoard,
+        gpio_index: usize,
+        stepper_ops: &mut T,
+        stepper_idx: usize,
+        step: i32,
+        min_pos: i32,
+        pos_local: &mut i32,
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+    ) -> Result<SensorApproach> {
+        loop {
+            if let Some(exit) = exit_flag {
+                if exit.load(std::sync::atomic::Ordering::Relaxed) {
+                    return Ok(SensorApproach::Cancelled);
+                }
+            }
+            match self.sensor_press_check(gpio, gpio_index) {
+                Ok(states) => {
+                    if states.get(0).copied().unwrap_or(false) {
+                        return Ok(SensorApproach::Touched);
+                    }
+                }
+                Err(e) => return Ok(SensorApproach::GpioError(e.to_string())),
+            }
+            if *pos_local <= min_pos {
+                return Ok(SensorApproach::BottomedOut);
+            }
+            self.rel_move_z(stepper_ops, stepper_idx, step)?;
+            *pos_local += step;
+        }
+    }
+
+    /// Two-phase Z homing: fast approach to the sensor, then repeatedly back off and
+    /// slowly re-approach (at Z_HOME.SLOW_SPEED), averaging the trigger positions to set zero.
+    /// Leaves each stepper backed off the sensor so it isn't left compressed.
+    pub fn z_home<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+    ) -> Result<String> {
+        let _span = tracing::info_span!("z_home").entered();
+        let gpio = self.gpio.as_ref().ok_or_else(|| Error::GpioUnavailable("GPIO not initialized".to_string()))?;
+        if !gpio.exist {
+            return Ok("Z-Homing requires GPIO".to_string());
+        }
+        let settings = self.z_home_settings;
+        
+        let mut messages = Vec::new();
+        messages.push("Running bump_check before Z homing...".to_string());
+        let bump_msg_initial = self.bump_check(None, positions, max_positions, stepper_ops, exit_flag)?.message;
+        if !bump_msg_initial.trim().is_empty() {
+            messages.push(bump_msg_initial);
+        }
+        
+        let z_indices = self.get_z_stepper_indices();
+        let enabled_states = self.get_all_stepper_enabled();
+        let min_pos = Z_MIN_POS;
+        
+        messages.push(format!(
+            "Starting Z homing (backoff {}, slow step {}, {} samples)...",
+            settings.backoff, settings.slow_step, settings.samples
+        ));
+        
+        for &stepper_idx in &z_indices {
+            if !enabled_states.get(&stepper_idx).copied().unwrap_or(false) {
+                messages.push(format!("Skipping disabled {}", self.stepper_label(stepper_idx)));
+                continue;
+            }
+            
+            let gpio_index = self.sensor_index(stepper_idx);
+            if self.sensor_quarantined(gpio_index) {
+                messages.push(format!("Skipping {}: touch sensor quarantined", self.stepper_label(stepper_idx)));
+                continue;
+            }
+            let max_pos = max_positions.get(&stepper_idx).copied().unwrap_or(100);
+            let z_down_step = self.z_down_step_for(self.string_of_z_stepper(stepper_idx));
+            
+            // Phase 1: fast approach from max_pos (set without moving)
+            stepper_ops.reset(stepper_idx, max_pos)?;
+            let mut pos_local = max_pos;
+            match self.approach_sensor(gpio, gpio_index, stepper_ops, stepper_idx, z_down_step, min_pos, &mut pos_local, exit_flag)? {
+                SensorApproach::Touched => {}
+                SensorApproach::Cancelled => {
+                    messages.push(format!("Homing cancelled for {}", self.stepper_label(stepper_idx)));
+                    return Ok(messages.join("\n"));
+                }
+                SensorApproach::BottomedOut => {
+                    messages.push(format!("{} bottomed out during fast approach (reached min_pos {} without touching) - disabling", self.stepper_label(stepper_idx), min_pos));
+                    self.apply_stepper_enabled(stepper_ops, stepper_idx, false)?;
+                    self.report_disabled(stepper_idx, &format!("{} disabled by z_home: reached min_pos {} without touching", self.stepper_label(stepper_idx), min_pos));
+                    continue;
+                }
+                SensorApproach::GpioError(e) => {
+                    messages.push(format!("GPIO error for {}: {}", self.stepper_label(stepper_idx), e));
+                    continue;
+                }
+            }
+            
+            // Phase 2: back off and slowly re-approach, recording each trigger position
+            let mut triggers: Vec<i32> = Vec::new();
+            let mut failure: Option<String> = None;
+            for _ in 0..settings.samples {
+                self.rel_move_z(stepper_ops, stepper_idx, settings.backoff)?;
+                pos_local += settings.backoff;
+                
+                if let Some(speed) = settings.slow_speed {
+                    stepper_ops.set_speed(stepper_idx, speed)?;
+                }
+                let approach = self.approach_sensor(gpio, gpio_index, stepper_ops, stepper_idx, -settings.slow_step, min_pos, &mut pos_local, exit_flag);
+                if settings.slow_speed.is_some() {
+                    stepper_ops.set_speed(stepper_idx, settings.normal_speed)?;
+                }
+                
+                match approach? {
+                    SensorApproach::Touched => triggers.push(pos_local),
+                    SensorApproach::Cancelled => {
+                        messages.push(format!("Homing cancelled for {}", self.stepper_label(stepper_idx)));
+                        return Ok(messages.join("\n"));
+                    }
+                    SensorApproach::BottomedOut => {
+                        failure = Some(format!("bottomed out on slow re-approach at {}", pos_local));
+                        break;
+                    }
+                    SensorApproach::GpioError(e) => {
+                        failure = Some(format!("GPIO error: {}", e));
+                        break;
+                    }
+                }
+            }
+            if let Some(reason) = failure {
+                messages.push(format!("{} homing incomplete: {}", self.stepper_label(stepper_idx), reason));
+                continue;
+            }
+            
+            // Zero at the average trigger position; we're currently sitting at the last trigger
+            let average = triggers.iter().sum::<i32>() as f32 / triggers.len() as f32;
+            let spread = triggers.iter().max().unwrap_or(&0) - triggers.iter().min().unwrap_or(&0);
+            let current = (pos_local as f32 - average).round() as i32;
+            stepper_ops.reset(stepper_idx, current)?;
+            
+            // Leave the sensor uncompressed
+            self.rel_move_z(stepper_ops, stepper_idx, settings.backoff)?;
+            messages.push(format!(
+                "{} homed: triggers {:?}, spread {}, zero set (now at {})",
+                self.stepper_label(stepper_idx), triggers, spread, current + settings.backoff
+            ));
+        }
+        
+        messages.push("Z homing complete".to_string());
+        Ok(messages.join("\n"))
+    }
+    
+    /// Pick which stepper of a channel's Z pair to move.
+    /// Too close: move the one closest to the string (most negative position).
+    /// Too far: move the one farthest from the string. Ties alternate by channel.
+    fn select_z_stepper(
+        ch_idx: usize,
+        too_close: bool,
+        z_in_idx: usize,
+        z_out_idx: usize,
+        z_in_pos: i32,
+        z_out_pos: i32,
+        z_in_enabled: bool,
+        z_out_enabled: bool,
+    ) -> usize {
+        if !z_in_enabled {
+            z_out_idx
+        } else if !z_out_enabled {
+            z_in_idx
+        } else if too_close {
+            // Too close: move the stepper that's closest to the string (most negative position)
+            // Example: if z_in_pos=-10 and z_out_pos=-5, z_in is closer (more negative)
+            // If equal, alternate to keep balanced
+            if z_in_pos < z_out_pos {
+                z_in_idx  // z_in is more negative (closer)
+            } else if z_out_pos < z_in_pos {
+                z_out_idx  // z_out is more negative (closer)
+            } else {
+                // Equal positions: alternate based on channel index to keep balanced
+                if ch_idx % 2 == 0 {
+                    z_in_idx
+                } else {
+                    z_out_idx
+                }
+            }
+        } else {
+            // too_far: move the stepper that's farthest from the string (most positive/least negative position)
+            // Example: if z_in_pos=-5 and z_out_pos=-10, z_in is farther (less negative)
+            // If equal, alternate to keep balanced
+            if z_in_pos > z_out_pos {
+                z_in_idx  // z_in is less negative/more positive (farther)
+            } else if z_out_pos > z_in_pos {
+                z_out_idx  // z_out is less negative/more positive (farther)
+            } else {
+                // Equal positions: alternate based on channel index to keep balanced
+                if ch_idx % 2 == 0 {
+                    z_out_idx
+                } else {
+                    z_in_idx
+                }
+            }
+        }
+    }
+    
     /// Z-adjust: Adjust Z steppers based on audio analysis (amplitude and voice count).
     /// 
     /// This function adjusts Z-steppers based on audio analysis to keep strings
@@ -1140,6 +3907,7 @@ impl Operations {
         max_voices: &[usize],
         exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
     ) -> Result<String> {
+        let _span = tracing::info_span!("z_adjust").entered();
         self.z_adjust_with_skip(stepper_ops, positions, max_positions, min_thresholds, max_thresholds, min_voices, max_voices, exit_flag, &HashSet::new())
     }
     
@@ -1156,17 +3924,46 @@ impl Operations {
         exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
         skip_channels: &std::collections::HashSet<usize>,
     ) -> Result<String> {
+        self.z_adjust_pass(
+            stepper_ops, positions, max_positions,
+            min_thresholds, max_thresholds, min_voices, max_voices,
+            exit_flag, skip_channels, "delta threshold exceeded, still settling", true,
+        )
+    }
+    
+    /// Single z_adjust pass. `skip_reason` is reported for skipped channels;
+    /// `with_bump_check` wraps the pass in bump_check before and after.
+    fn z_adjust_pass<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        min_thresholds: &[f32],
+        max_thresholds: &[f32],
+        min_voices: &[usize],
+        max_voices: &[usize],
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        skip_channels: &std::collections::HashSet<usize>,
+        skip_reason: &str,
+        with_bump_check: bool,
+    ) -> Result<String> {
+        if let Some(reason) = self.partials_stale_reason() {
+            return Err(Error::OperationAborted(format!("Z adjustment refused: {}", reason)));
+        }
         let enabled_states = self.get_all_stepper_enabled();
-        let z_up_step = self.get_z_up_step();
-        let z_down_step = self.get_z_down_step();
+        let adaptive_z_step = self.get_adaptive_z_step();
+        let z_max_step = self.get_z_max_step();
         let amp_sums = self.get_amp_sum();
         let voice_counts = self.get_voice_count();
+        let channel_mask = self.get_channel_mask();
         let mut messages = Vec::new();
         
-        messages.push("Running bump_check before Z adjustment...".to_string());
-        let bump_msg_initial = self.bump_check(None, positions, max_positions, stepper_ops, exit_flag)?;
-        if !bump_msg_initial.trim().is_empty() {
-            messages.push(bump_msg_initial);
+        if with_bump_check {
+            messages.push("Running bump_check before Z adjustment...".to_string());
+            let bump_msg_initial = self.bump_check(None, positions, max_positions, stepper_ops, exit_flag)?.message;
+            if !bump_msg_initial.trim().is_empty() {
+                messages.push(bump_msg_initial);
+            }
         }
         
         messages.push("Starting Z adjustment...".to_string());
@@ -1185,23 +3982,34 @@ impl Operations {
             
             // Skip this channel if it's in the skip set (e.g., delta threshold exceeded)
             if skip_channels.contains(&ch_idx) {
-                messages.push(format!("Channel {}: skipping adjustment (delta threshold exceeded, still settling)", ch_idx));
+                messages.push(format!("Channel {}: skipping adjustment ({})", ch_idx, skip_reason));
                 continue;
             }
             
+            if let Some(reason) = channel_mask.excluded_reason(ch_idx) {
+                messages.push(format!("Channel {}: {}, ignoring analysis", ch_idx, reason));
+                continue;
+            }
+            
+            // audio_monitor may stop mid-pass; don't keep stepping on its last frame
+            if let Some(reason) = self.partials_stale_reason() {
+                messages.push(format!("Channel {}: stopping Z adjustment ({})", ch_idx, reason));
+                break;
+            }
+            
             let amp_sum = amp_sums[ch_idx];
             let voice_count = voice_counts[ch_idx];
             
             let min_thresh = min_thresholds.get(ch_idx).copied().unwrap_or(20.0);
             let max_thresh = max_thresholds.get(ch_idx).copied().unwrap_or(100.0);
             let min_voice = min_voices.get(ch_idx).copied().unwrap_or(0);
-            let max_voice = max_voices.get(ch_idx).copied().unwrap_or(12);
+            let max_voice = max_voices.get(ch_idx).copied().unwrap_or(self.partials_per_channel());
             
-            // Determine which stepper to move (z_in or z_out)
-            // Note: Assumes channel index maps to string index (1:1 mapping)
-            // If channels != strings, this would need a mapping function
-            let z_in_idx = self.z_first_index + (ch_idx * 2);
-            let z_out_idx = self.z_first_index + (ch_idx * 2) + 1;
+            // Determine which stepper to move (z_in or z_out) via CHANNEL_MAP
+            let Some((z_in_idx, z_out_idx)) = self.z_pair_for_channel(ch_idx) else {
+                messages.push(format!("Channel {}: no string mapped, skipping", ch_idx));
+                continue;
+            };
             
             let z_in_enabled = enabled_states.get(&z_in_idx).copied().unwrap_or(false);
             let z_out_enabled = enabled_states.get(&z_out_idx).copied().unwrap_or(false);
@@ -1223,53 +4031,40 @@ impl Operations {
             let too_far = voice_too_low || (amp_too_low && !voice_too_high);
             
             if too_close || too_far {
+                // BOW_DRIVE MODULATE: amplitude alone out of range changes the wheel speed
+                // first; Z moves once the wheel is stopped or at its speed limit
+                if !voice_too_high && !voice_too_low && self.get_bow_modulate() {
+                    if let Some(message) = self.modulate_bow(stepper_ops, ch_idx, amp_too_high, amp_sum, min_thresh, max_thresh)? {
+                        messages.push(message);
+                        self.rest_lap();
+                        continue;
+                    }
+                }
+                
+                // Proportional step sizing: the further outside the band, the larger the move.
+                // Voice count violations take precedence, matching the direction logic above.
+                let error_ratio = if !adaptive_z_step {
+                    0.0
+                } else if voice_too_high || voice_too_low {
+                    band_error_ratio(voice_count as f32, min_voice as f32, max_voice as f32)
+                } else {
+                    band_error_ratio(amp_sum, min_thresh, max_thresh)
+                };
+                let string_idx = self.string_for_channel(ch_idx);
+                let up_step = scale_z_step(self.z_up_step_for(string_idx), error_ratio, z_max_step);
+                let down_step = scale_z_step(self.z_down_step_for(string_idx), error_ratio, z_max_step);
+                
                 // Determine which stepper to move based on adjustment direction
                 // Positions can be negative (steppers below zero are closer to string)
                 // More negative = closer to string, more positive = farther from string
                 let z_in_pos = positions.get(z_in_idx).copied().unwrap_or(0);
                 let z_out_pos = positions.get(z_out_idx).copied().unwrap_or(0);
                 
-                let stepper_to_move = if !z_in_enabled {
-                    z_out_idx
-                } else if !z_out_enabled {
-                    z_in_idx
-                } else if too_close {
-                    // Too close: move the stepper that's closest to the string (most negative position)
-                    // Example: if z_in_pos=-10 and z_out_pos=-5, z_in is closer (more negative)
-                    // If equal, alternate to keep balanced
-                    if z_in_pos < z_out_pos {
-                        z_in_idx  // z_in is more negative (closer)
-                    } else if z_out_pos < z_in_pos {
-                        z_out_idx  // z_out is more negative (closer)
-                    } else {
-                        // Equal positions: alternate based on channel index to keep balanced
-                        if ch_idx % 2 == 0 {
-                            z_in_idx
-                        } else {
-                            z_out_idx
-                        }
-                    }
-                } else {
-                    // too_far: move the stepper that's farthest from the string (most positive/least negative position)
-                    // Example: if z_in_pos=-5 and z_out_pos=-10, z_in is farther (less negative)
-                    // If equal, alternate to keep balanced
-                    if z_in_pos > z_out_pos {
-                        z_in_idx  // z_in is less negative/more positive (farther)
-                    } else if z_out_pos > z_in_pos {
-                        z_out_idx  // z_out is less negative/more positive (farther)
-                    } else {
-                        // Equal positions: alternate based on channel index to keep balanced
-                        if ch_idx % 2 == 0 {
-                            z_out_idx
-                        } else {
-                            z_in_idx
-                        }
-                    }
-                };
+                let stepper_to_move = Self::select_z_stepper(ch_idx, too_close, z_in_idx, z_out_idx, z_in_pos, z_out_pos, z_in_enabled, z_out_enabled);
                 
                 if too_close {
                     // Move stepper up (away from string)
-                    self.rel_move_z(stepper_ops, stepper_to_move, z_up_step)?;
+                    self.rel_move_z(stepper_ops, stepper_to_move, up_step)?;
                     // Position is updated by refresh_positions() - Arduino is source of truth
                     let reason = if voice_too_high {
                         format!("voices={} > max={}", voice_count, max_voice)
@@ -1279,13 +4074,13 @@ impl Operations {
                         "unknown".to_string()
                     };
                     messages.push(format!(
-                        "Channel {}: too close ({}, amp={:.2}, voices={}), moved stepper {} (closest) up by {}",
-                        ch_idx, reason, amp_sum, voice_count, stepper_to_move, z_up_step
+                        "Channel {}: too close ({}, amp={:.2}, voices={}), moved {} (closest) up by {}",
+                        ch_idx, reason, amp_sum, voice_count, self.stepper_label(stepper_to_move), up_step
                     ));
                     self.rest_lap();
                 } else {
                     // Move stepper down (toward string)
-                    self.rel_move_z(stepper_ops, stepper_to_move, z_down_step)?;
+                    self.rel_move_z(stepper_ops, stepper_to_move, down_step)?;
                     // Position is updated by refresh_positions() - Arduino is source of truth
                     let reason = if voice_too_low {
                         format!("voices={} < min={}", voice_count, min_voice)
@@ -1295,33 +4090,194 @@ impl Operations {
                         "unknown".to_string()
                     };
                     messages.push(format!(
-                        "Channel {}: too far ({}, amp={:.2}, voices={}), moved stepper {} (farthest) down by {}",
-                        ch_idx, reason, amp_sum, voice_count, stepper_to_move, z_down_step
+                        "Channel {}: too far ({}, amp={:.2}, voices={}), moved {} (farthest) down by {}",
+                        ch_idx, reason, amp_sum, voice_count, self.stepper_label(stepper_to_move), down_step
                     ));
                     self.rest_lap();
                 }
             } else {
-                messages.push(format!(
-                    "Channel {}: in range (amp={:.2}, voices={})",
-                    ch_idx, amp_sum, voice_count
-                ));
+                messages.push(format!(
+                    "Channel {}: in range (amp={:.2}, voices={})",
+                    ch_idx, amp_sum, voice_count
+                ));
+            }
+        }
+        
+        if with_bump_check {
+            messages.push("Running bump_check after Z adjustment...".to_string());
+            let bump_msg_final = self.bump_check(None, positions, max_positions, stepper_ops, exit_flag)?.message;
+            if !bump_msg_final.trim().is_empty() {
+                messages.push(bump_msg_final);
+            }
+        }
+        messages.push("Z adjustment complete".to_string());
+        Ok(messages.join("\n"))
+    }
+    
+    /// Move channel `ch_idx`'s bow wheel one SPEED_STEP slower (too loud) or faster
+    /// (too quiet). None when there is no wheel or it cannot change, so Z moves instead.
+    fn modulate_bow<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        ch_idx: usize,
+        too_loud: bool,
+        amp_sum: f32,
+        min_thresh: f32,
+        max_thresh: f32,
+    ) -> Result<Option<String>> {
+        let (Some(bow), Some(string_idx)) = (&self.bow_drive, self.string_for_channel(ch_idx)) else {
+            return Ok(None);
+        };
+        let current = self.get_bow_speeds().get(string_idx).copied().unwrap_or(0);
+        let Some(next) = crate::bow_drive::modulated_speed(bow, current, too_loud) else {
+            return Ok(None);
+        };
+        let sent = self.set_bow_speed(stepper_ops, string_idx, next)?;
+        let reason = if too_loud {
+            format!("amp={:.2} > max={:.2}", amp_sum, max_thresh)
+        } else {
+            format!("amp={:.2} < min={:.2}", amp_sum, min_thresh)
+        };
+        Ok(Some(format!(
+            "Channel {}: {} ({}), {} bow wheel {} -> {}",
+            ch_idx, if too_loud { "too loud" } else { "too quiet" }, reason, self.string_label(string_idx), current, sent
+        )))
+    }
+    
+    /// Z-hold: continuous closed-loop regulation using the Z_CONTROLLER PID loops.
+    /// 
+    /// Alternative to discrete z_adjust passes. Each period, every channel's metric
+    /// (amp_sum or voice_count, per Z_CONTROLLER.METRIC) is compared with its setpoint
+    /// and the PID output is applied to the appropriate Z stepper of the pair. Runs
+    /// bump_check every cycle and continues until exit_flag is set.
+    /// 
+    /// Args:
+    /// - setpoints: Target metric value per channel (e.g. the middle of the amp_sum band)
+    /// - progress_sender: Optional sender to stream per-cycle status in real-time
+    /// 
+    /// Returns message string describing results
+    pub fn z_hold<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        setpoints: &[f32],
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        progress_sender: Option<&std::sync::mpsc::Sender<String>>,
+    ) -> Result<String> {
+        let _span = tracing::info_span!("z_hold").entered();
+        let settings = self.z_controller_settings.as_ref()
+            .ok_or_else(|| Error::ConfigMissing(format!("Z_CONTROLLER not configured for '{}' in string_driver.yaml", self.hostname)))?;
+        let mut controller = ZController::new(settings);
+        let mut messages = Vec::new();
+        messages.push(format!(
+            "Starting z_hold ({:?}, period {:.2}s)",
+            controller.metric, controller.period
+        ));
+        
+        let mut cycles = 0u64;
+        let mut last_update = std::time::Instant::now();
+        loop {
+            if let Some(exit) = exit_flag {
+                if exit.load(std::sync::atomic::Ordering::Relaxed) {
+                    break;
+                }
+            }
+            
+            let dt = last_update.elapsed().as_secs_f32().max(controller.period);
+            last_update = std::time::Instant::now();
+            cycles += 1;
+            
+            let bump = self.bump_check(None, positions, max_positions, stepper_ops, exit_flag)?;
+            if bump.z_moved() {
+                // Z moved under us - drop accumulated controller state
+                controller.reset();
+                messages.push(bump.message.clone());
+                if let Some(sender) = progress_sender {
+                    let _ = sender.send(bump.message);
+                }
+            }
+            
+            if let Some(reason) = self.partials_stale_reason() {
+                // Don't integrate error from a frozen frame
+                controller.reset();
+                if let Some(sender) = progress_sender {
+                    let _ = sender.send(format!("z_hold cycle {}: holding ({})", cycles, reason));
+                }
+            } else {
+                let status = self.z_hold_cycle(&mut controller, stepper_ops, positions, setpoints, dt, &HashSet::new())?;
+                if let Some(sender) = progress_sender {
+                    let _ = sender.send(format!("z_hold cycle {}: {}", cycles, status.join(" ")));
+                }
             }
+            
+            Self::sleep_interruptible(controller.period, exit_flag);
         }
         
-        messages.push("Running bump_check after Z adjustment...".to_string());
-        let bump_msg_final = self.bump_check(None, positions, max_positions, stepper_ops, exit_flag)?;
-        if !bump_msg_final.trim().is_empty() {
-            messages.push(bump_msg_final);
-        }
-        messages.push("Z adjustment complete".to_string());
+        messages.push(format!("z_hold stopped after {} cycles", cycles));
         Ok(messages.join("\n"))
     }
     
-    /// Right to left move operation: moves X from x_start to x_finish, adjusting Z at each position
-    /// Uses Adjustment Level to iterate in place until successfully passing the value
-    /// If attempts exceed Retry Threshold or Z variance threshold, performs calibration
-    /// progress_sender: Optional sender to stream progress messages in real-time
-    pub fn right_left_move<T: StepperOperations>(
+    /// One controller update across all channels (no bump_check, no rest).
+    /// Returns a short status entry per regulated channel.
+    fn z_hold_cycle<T: StepperOperations>(
+        &self,
+        controller: &mut ZController,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        setpoints: &[f32],
+        dt: f32,
+        skip_channels: &HashSet<usize>,
+    ) -> Result<Vec<String>> {
+        let enabled_states = self.get_all_stepper_enabled();
+        let channel_mask = self.get_channel_mask();
+        let measurements: Vec<f32> = match controller.metric {
+            ControllerMetric::AmpSum => self.get_amp_sum(),
+            ControllerMetric::VoiceCount => self.get_voice_count().iter().map(|&v| v as f32).collect(),
+        };
+        
+        let mut status = Vec::new();
+        for (ch_idx, &measured) in measurements.iter().enumerate() {
+            let Some(&setpoint) = setpoints.get(ch_idx) else { continue; };
+            if skip_channels.contains(&ch_idx) {
+                // Paused channels restart cleanly when resumed
+                controller.reset_channel(ch_idx);
+                status.push(format!("Ch{}:paused", ch_idx));
+                continue;
+            }
+            if let Some(reason) = channel_mask.excluded_reason(ch_idx) {
+                controller.reset_channel(ch_idx);
+                status.push(format!("Ch{}:{}", ch_idx, reason));
+                continue;
+            }
+            let Some((z_in_idx, z_out_idx)) = self.z_pair_for_channel(ch_idx) else { continue; };
+            let z_in_enabled = enabled_states.get(&z_in_idx).copied().unwrap_or(false);
+            let z_out_enabled = enabled_states.get(&z_out_idx).copied().unwrap_or(false);
+            if !z_in_enabled && !z_out_enabled {
+                continue;
+            }
+            
+            let delta = controller.update(ch_idx, setpoint, measured, dt);
+            if delta != 0 {
+                let z_in_pos = positions.get(z_in_idx).copied().unwrap_or(0);
+                let z_out_pos = positions.get(z_out_idx).copied().unwrap_or(0);
+                let stepper = Self::select_z_stepper(ch_idx, delta > 0, z_in_idx, z_out_idx, z_in_pos, z_out_pos, z_in_enabled, z_out_enabled);
+                self.rel_move_z_no_rest(stepper_ops, stepper, delta)?;
+                // Position is updated by refresh_positions() - Arduino is source of truth
+            }
+            status.push(format!("Ch{}:{:.1}/{:.1}->{}", ch_idx, measured, setpoint, delta));
+        }
+        Ok(status)
+    }
+    
+    /// Performance mode: hold all strings in range until stopped.
+    /// 
+    /// Loops z_adjust (or the Z_CONTROLLER PID loops when `performance_use_controller`
+    /// is set) with a bump_check every `performance_bump_interval` cycles. Each cycle
+    /// takes at least `performance_rest` seconds (rate limit). Channels paused via
+    /// `set_channel_paused` are left untouched. A status line per cycle is streamed
+    /// through progress_sender; only bump events are kept in the returned message.
+    pub fn performance_mode<T: StepperOperations>(
         &self,
         stepper_ops: &mut T,
         positions: &mut [i32],
@@ -1330,255 +4286,416 @@ impl Operations {
         max_thresholds: &[f32],
         min_voices: &[usize],
         max_voices: &[usize],
+        setpoints: &[f32],
         exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
         progress_sender: Option<&std::sync::mpsc::Sender<String>>,
     ) -> Result<String> {
-        let x_step_index = self.x_step_index.ok_or_else(|| anyhow!("X stepper not configured"))?;
-        let x_start = self.get_x_start();
-        let x_finish = self.get_x_finish();
-        let x_step = self.get_x_step();
-        let adjustment_level = self.get_adjustment_level();
-        let retry_threshold = self.get_retry_threshold();
-        let z_variance_threshold = self.get_z_variance_threshold();
-        let delta_threshold = self.get_delta_threshold() as f32;
+        let _span = tracing::info_span!("performance_mode").entered();
+        let use_controller = self.get_performance_use_controller();
+        let mut controller = if use_controller {
+            let settings = self.z_controller_settings.as_ref()
+                .ok_or_else(|| Error::ConfigMissing(format!("Performance mode set to use Z_CONTROLLER, but it is not configured for '{}'", self.hostname)))?;
+            Some(ZController::new(settings))
+        } else {
+            None
+        };
         
         let mut messages = Vec::new();
-        messages.push(format!("Starting right_left_move: X from {} to {} (step: {})", x_start, x_finish, x_step));
+        messages.push(format!(
+            "Starting performance mode ({}, rest {:.2}s, bump_check every {} cycle(s))",
+            if use_controller { "PID" } else { "z_adjust" },
+            self.get_performance_rest(),
+            self.get_performance_bump_interval()
+        ));
         
-        // Read current X position from Arduino - Arduino is source of truth
-        let current_x_pos = positions.get(x_step_index).copied().ok_or_else(|| anyhow!("Failed to read X position from Arduino"))?;
-        messages.push(format!("Current X position from Arduino: {}", current_x_pos));
+        let mut cycles = 0u64;
+        let mut last_cycle = std::time::Instant::now();
+        loop {
+            if let Some(exit) = exit_flag {
+                if exit.load(std::sync::atomic::Ordering::Relaxed) {
+                    break;
+                }
+            }
+            
+            // Settings are re-read every cycle so they can be tuned while running
+            let rest = self.get_performance_rest();
+            let bump_interval = self.get_performance_bump_interval().max(1) as u64;
+            let paused = self.get_paused_channels();
+            let dt = last_cycle.elapsed().as_secs_f32();
+            last_cycle = std::time::Instant::now();
+            
+            if cycles % bump_interval == 0 {
+                let bump = self.bump_check(None, positions, max_positions, stepper_ops, exit_flag)?;
+                if bump.z_moved() {
+                    if let Some(ctrl) = controller.as_mut() {
+                        ctrl.reset();
+                    }
+                    messages.push(bump.message.clone());
+                    if let Some(sender) = progress_sender {
+                        let _ = sender.send(bump.message);
+                    }
+                }
+            }
+            cycles += 1;
+            
+            let status = if let Some(reason) = self.partials_stale_reason() {
+                // Keep bump protection running but don't move on stale audio data
+                if let Some(ctrl) = controller.as_mut() {
+                    ctrl.reset();
+                }
+                format!("holding ({})", reason)
+            } else if let Some(ctrl) = controller.as_mut() {
+                self.z_hold_cycle(ctrl, stepper_ops, positions, setpoints, dt, &paused)?.join(" ")
+            } else {
+                let pass_msg = self.z_adjust_pass(
+                    stepper_ops, positions, max_positions,
+                    min_thresholds, max_thresholds, min_voices, max_voices,
+                    exit_flag, &paused, "paused", false,
+                )?;
+                let moved: Vec<&str> = pass_msg.lines().filter(|l| l.contains("moved stepper")).collect();
+                if moved.is_empty() {
+                    "all active channels in range".to_string()
+                } else {
+                    moved.join("; ")
+                }
+            };
+            
+            if let Some(sender) = progress_sender {
+                let paused_str = if paused.is_empty() {
+                    String::new()
+                } else {
+                    let mut p: Vec<usize> = paused.iter().copied().collect();
+                    p.sort_unstable();
+                    format!(" [paused: {:?}]", p)
+                };
+                let _ = sender.send(format!("perf cycle {}: {}{}", cycles, status, paused_str));
+            }
+            
+            // Rate limit: the whole cycle takes at least `rest` seconds
+            let elapsed = last_cycle.elapsed().as_secs_f32();
+            Self::sleep_interruptible(rest - elapsed, exit_flag);
+        }
         
-        // Absolute move to x_start if not already there
-        if current_x_pos != x_start {
-            messages.push(format!("Moving X to absolute position: {} (current: {})", x_start, current_x_pos));
-            stepper_ops.abs_move(x_step_index, x_start)?;
-            // Wait for physical movement to complete using x_rest
-            self.rest_x();
-            // Position is updated by refresh_positions() in stepper_gui - Arduino knows the position
-            // Note: local positions array will be updated when operations_gui polls stepper_gui
+        messages.push(format!("Performance mode stopped after {} cycles", cycles));
+        Ok(messages.join("\n"))
+    }
+    
+    /// Response map: sweep a grid of X positions and Z heights and record the audio response.
+    /// 
+    /// For each X position (x_start..x_finish by x_step, or the current X if no X stepper)
+    /// every enabled Z stepper is moved to each height in RESPONSE_MAP Z_MIN..Z_MAX by Z_STEP
+    /// (heights outside any of those steppers' limits, Z_MIN_POS..max position, are skipped).
+    /// After settling (z_rest), amp_sum and voice_count are averaged over SAMPLES readings and
+    /// written per channel to a CSV file in RESPONSE_MAP OUTPUT_DIR. bump_check runs after
+    /// every Z move so low heights can't leave a stepper pressed into the string.
+    /// 
+    /// Returns message string including the output file path
+    pub fn response_map<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        progress_sender: Option<&std::sync::mpsc::Sender<String>>,
+    ) -> Result<String> {
+        let _span = tracing::info_span!("response_map").entered();
+        use std::io::Write;
+        
+        let settings = &self.response_map_settings;
+        let z_indices: Vec<usize> = self.get_z_stepper_indices().into_iter()
+            .filter(|idx| self.get_stepper_enabled(*idx))
+            .collect();
+        if z_indices.is_empty() {
+            return Err(Error::OperationAborted("response_map: no enabled Z steppers".to_string()));
         }
         
-        // Read current X position from Arduino (after move) - Arduino is source of truth
-        let mut current_x = positions.get(x_step_index).copied().ok_or_else(|| anyhow!("Failed to read X position from Arduino"))?;
-        messages.push(format!("X position after initial move: {}", current_x));
-        let step_direction = if x_finish > x_start { 1 } else { -1 };
-        let abs_step = x_step.abs();
+        // Build the X grid (a single column at the current X when there is no X stepper)
+        let x_positions: Vec<Option<i32>> = match self.x_step_index() {
+            Some(_) => {
+                let x_start = self.get_x_start();
+                let x_finish = self.get_x_finish();
+                let x_step = self.get_x_step().abs().max(1);
+                let direction = if x_finish >= x_start { 1 } else { -1 };
+                let mut xs = Vec::new();
+                let mut x = x_start;
+                while (direction > 0 && x <= x_finish) || (direction < 0 && x >= x_finish) {
+                    xs.push(Some(x));
+                    x += direction * x_step;
+                }
+                xs
+            }
+            None => vec![None],
+        };
+        // Only heights every mapped stepper can reach; a clamped height would be recorded as the wrong Z
+        let (z_heights, out_of_range): (Vec<i32>, Vec<i32>) = (settings.z_min..=settings.z_max)
+            .step_by(settings.z_step as usize)
+            .partition(|&z| z_indices.iter().all(|&idx| z_within_limits(max_positions, idx, z)));
+        if z_heights.is_empty() {
+            return Err(Error::ConfigInvalid(format!(
+                "response_map: no Z height in {}..={} is within the Z steppers' limits",
+                settings.z_min, settings.z_max
+            )));
+        }
         
-        while (step_direction > 0 && current_x < x_finish) || (step_direction < 0 && current_x > x_finish) {
-            // Check exit flag
-            if let Some(exit) = exit_flag {
-                if exit.load(std::sync::atomic::Ordering::Relaxed) {
-                    messages.push("Operation cancelled".to_string());
-                    return Ok(messages.join("\n"));
+        std::fs::create_dir_all(&settings.output_dir)
+            .map_err(|e| Error::io(format!("Failed to create response map directory {:?}", settings.output_dir), e))?;
+        let file_path = settings.output_dir.join(format!(
+            "response_map_{}_{}.csv",
+            self.hostname,
+            chrono::Local::now().format("%Y%m%d_%H%M%S")
+        ));
+        let mut file = std::fs::File::create(&file_path)
+            .map_err(|e| Error::io(format!("Failed to create response map file {:?}", file_path), e))?;
+        let write_failed = |e| Error::io(format!("Failed to write response map file {:?}", file_path), e);
+        writeln!(file, "timestamp,x,z,channel,amp_sum,voice_count").map_err(write_failed)?;
+        
+        let mut messages = Vec::new();
+        if !out_of_range.is_empty() {
+            messages.push(format!("Skipping Z height(s) {:?}: outside the Z steppers' limits", out_of_range));
+        }
+        messages.push(format!(
+            "Starting response_map: {} X position(s) x {} Z height(s) ({}..={} by {}), {} sample(s) per point",
+            x_positions.len(), z_heights.len(), settings.z_min, settings.z_max, settings.z_step, settings.samples
+        ));
+        
+        let mut points = 0usize;
+        'sweep: for x in &x_positions {
+            if let (Some(x_idx), Some(x)) = (self.x_step_index(), x) {
+                let x_from = positions.get(x_idx).copied().unwrap_or(*x);
+                self.clear_x_path(stepper_ops, positions, x_from, *x, &mut messages)?;
+                stepper_ops.abs_move(x_idx, *x)?;
+                self.rest_x();
+                if let Some(slot) = positions.get_mut(x_idx) {
+                    *slot = *x;
                 }
             }
             
-            // At current X position, iterate until we get Adjustment Level consecutive successful passes
-            // Each pass = z_adjust + bump_check
-            let mut pass_count = 0; // Consecutive successful passes
-            let mut attempts = 0; // Total attempts (for retry threshold)
-            let mut last_voice_counts = Vec::new();
-            let mut last_amp_sums = Vec::new(); // Track previous amp_sum for delta calculation
-            
-            loop {
-                // Check exit flag
+            for &z in &z_heights {
                 if let Some(exit) = exit_flag {
                     if exit.load(std::sync::atomic::Ordering::Relaxed) {
-                        messages.push("Operation cancelled".to_string());
-                        return Ok(messages.join("\n"));
+                        messages.push("response_map cancelled".to_string());
+                        break 'sweep;
                     }
                 }
                 
-                attempts += 1;
-                
-                // Get current amp_sums before adjustment
-                let current_amp_sums = self.get_amp_sum();
-                
-                // Calculate delta per channel (difference from previous amp_sum)
-                let amp_deltas = calculate_amp_delta(&last_amp_sums, &current_amp_sums);
-                
-                // Determine which channels to skip (delta threshold exceeded)
-                let mut skip_channels = HashSet::new();
-                for (ch_idx, delta) in amp_deltas.iter().enumerate() {
-                    if *delta > delta_threshold {
-                        skip_channels.insert(ch_idx);
-                    }
+                for &z_idx in &z_indices {
+                    stepper_ops.abs_move(z_idx, z)?;
                 }
-                
-                // Calculate Z variance (sum of absolute differences in voice counts)
-                let voice_counts = self.get_voice_count();
-                let z_variance = if !last_voice_counts.is_empty() && last_voice_counts.len() == voice_counts.len() {
-                    voice_counts.iter()
-                        .zip(last_voice_counts.iter())
-                        .map(|(curr, last)| ((*curr as i32) - (*last as i32)).abs())
-                        .sum::<i32>()
-                } else {
-                    0
-                };
-                
-                // Per-loop message: Retries, Level, Delta per channel, Z variance
-                let delta_str = amp_deltas.iter()
-                    .enumerate()
-                    .map(|(ch, delta)| format!("Ch{}:{:.2}", ch, delta))
-                    .collect::<Vec<_>>()
-                    .join(" ");
-                let loop_msg = format!(
-                    "Loop at X={}: Retries={}, Level={}/{}, Delta=[{}], Zvariance={}",
-                    current_x, attempts, pass_count, adjustment_level, delta_str, z_variance
-                );
-                messages.push(loop_msg.clone());
-                
-                // Send progress message in real-time if sender provided
-                if let Some(sender) = progress_sender {
-                    let _ = sender.send(loop_msg);
+                self.rest_z();
+                let bump_msg = self.bump_check(None, positions, max_positions, stepper_ops, exit_flag)?.message;
+                if !bump_msg.trim().is_empty() {
+                    messages.push(bump_msg);
                 }
                 
-                // Run z_adjust with skip_channels (channels exceeding delta threshold are skipped)
-                let z_adjust_msg = self.z_adjust_with_skip(
-                    stepper_ops,
-                    positions,
-                    max_positions,
-                    min_thresholds,
-                    max_thresholds,
-                    min_voices,
-                    max_voices,
-                    exit_flag,
-                    &skip_channels,
-                )?;
-                
-                // Run bump_check
-                let bump_msg = self.bump_check(None, positions, max_positions, stepper_ops, exit_flag)?;
-                
-                // Check if bump_check passed (no CRITICAL errors, no bumps detected)
-                // bump_check returns empty string if no bumps, or messages if bumps were detected/cleared
-                // A CRITICAL message means a stepper was disabled - this is a failure
-                // If bumps were detected (even if cleared), that means steppers were touching - this is a failure
-                let bump_check_passed = !bump_msg.contains("CRITICAL") && 
-                    !bump_msg.contains("bump cleared") &&
-                    !bump_msg.contains("bumping") &&
-                    (bump_msg.trim().is_empty() || 
-                     bump_msg.contains("bump_check disabled") || 
-                     bump_msg.contains("no GPIO"));
-                
-                // Get current voice counts and amp sums (refresh after z_adjust)
-                let voice_counts = self.get_voice_count();
-                let amp_sums = self.get_amp_sum();
-                
-                // Update last_amp_sums for next iteration delta calculation
-                last_amp_sums = amp_sums.clone();
-                
-                // Check if all channels are within their min/max ranges (green indicators)
-                // A pass is when voice_count AND amp_sum for all channels are within their ranges
-                let num_channels = amp_sums.len().min(voice_counts.len());
-                let voice_amp_pass = (0..num_channels).all(|ch_idx| {
-                    let amp_sum = amp_sums[ch_idx];
-                    let voice_count = voice_counts[ch_idx];
-                    
-                    let min_thresh = min_thresholds.get(ch_idx).copied().unwrap_or(20.0);
-                    let max_thresh = max_thresholds.get(ch_idx).copied().unwrap_or(100.0);
-                    let min_voice = min_voices.get(ch_idx).copied().unwrap_or(0);
-                    let max_voice = max_voices.get(ch_idx).copied().unwrap_or(12);
-                    
-                    // Check both amp_sum and voice_count are within their ranges
-                    amp_sum >= min_thresh && amp_sum <= max_thresh &&
-                    voice_count >= min_voice && voice_count <= max_voice
-                });
-                
-                // A pass requires BOTH bump_check passed AND voice/amp checks passed
-                let all_pass = bump_check_passed && voice_amp_pass;
-                
-                if all_pass {
-                    // Successful pass - increment pass counter
-                    pass_count += 1;
-                    messages.push(format!("Pass {} of {} successful at X={} (attempt {})", pass_count, adjustment_level, current_x, attempts));
-                    
-                    // If we've reached Adjustment Level consecutive passes, move X by step_size and break
-                    if pass_count >= adjustment_level {
-                        messages.push(format!("Adjustment level {} met at X={} after {} attempts, moving X by step size {}", adjustment_level, current_x, attempts, abs_step));
-                        
-                        // Move X by exactly x_step_size (relative move)
-                        let step_delta = step_direction * abs_step;
-                        self.rel_move_x(stepper_ops, x_step_index, step_delta)?;
-                        // Position is updated by refresh_positions() - Arduino knows the position
-                        // Read updated position from Arduino for next iteration - Arduino is source of truth
-                        current_x = positions.get(x_step_index).copied().ok_or_else(|| anyhow!("Failed to read X position from Arduino"))?;
-                        messages.push(format!("Moved X by {} to position: {}", step_delta, current_x));
-                        
-                        // Reset pass counter for next X position
-                        pass_count = 0;
-                        attempts = 0;
-                        break; // Break inner loop to move to next X position
-                    }
-                } else {
-                    // Adjustment failed - reset pass counter
-                    if pass_count > 0 {
-                        let failure_reason = if !bump_check_passed {
-                            "bump_check failed"
-                        } else if !voice_amp_pass {
-                            "voice/amp checks failed"
-                        } else {
-                            "unknown"
-                        };
-                        messages.push(format!("Adjustment failed at X={} ({}), resetting pass count from {} to 0", current_x, failure_reason, pass_count));
-                    } else {
-                        // Log why it failed even if pass_count was 0
-                        if !bump_check_passed {
-                            messages.push(format!("bump_check failed at X={}: {}", current_x, bump_msg.trim()));
-                        }
-                        if !voice_amp_pass {
-                            messages.push(format!("voice/amp checks failed at X={}", current_x));
-                        }
-                    }
-                    pass_count = 0;
+                let (amp_avg, voice_avg) = self.averaged_audio(settings.samples);
+                let timestamp = chrono::Utc::now().to_rfc3339();
+                let x_str = x.map(|v| v.to_string()).unwrap_or_default();
+                for (ch_idx, (amp, voices)) in amp_avg.iter().zip(&voice_avg).enumerate() {
+                    writeln!(
+                        file,
+                        "{},{},{},{},{:.4},{:.2}",
+                        timestamp, x_str, z, ch_idx, amp, voices
+                    ).map_err(write_failed)?;
                 }
+                points += 1;
                 
-                // Check if we've exceeded retry threshold
-                if attempts >= retry_threshold {
-                    messages.push(format!("Retry threshold {} exceeded at X={}, performing calibration", retry_threshold, current_x));
-                    let cal_msg = self.z_calibrate(stepper_ops, positions, max_positions, exit_flag)?;
-                    messages.push(cal_msg);
-                    // Reset counters after calibration
-                    pass_count = 0;
-                    attempts = 0;
-                    // Reset tracking arrays after calibration
-                    last_voice_counts.clear();
-                    last_amp_sums.clear();
-                    // Continue trying at current X position
+                if let Some(sender) = progress_sender {
+                    let _ = sender.send(format!(
+                        "response_map point {}/{}: X={} Z={}",
+                        points, x_positions.len() * z_heights.len(), x_str, z
+                    ));
+                }
+            }
+        }
+        
+        file.flush().map_err(write_failed)?;
+        messages.push(format!("response_map wrote {} grid point(s) to {}", points, file_path.display()));
+        Ok(messages.join("\n"))
+    }
+    
+    /// Per-channel amp_sum and voice_count averaged over `samples` readings 100 ms apart,
+    /// smoothing out frame-to-frame jitter
+    fn averaged_audio(&self, samples: u32) -> (Vec<f32>, Vec<f32>) {
+        let mut amp_totals: Vec<f32> = Vec::new();
+        let mut voice_totals: Vec<f32> = Vec::new();
+        for sample in 0..samples.max(1) {
+            if sample > 0 {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            let amp = self.get_amp_sum();
+            let voices = self.get_voice_count();
+            amp_totals.resize(amp.len().max(amp_totals.len()), 0.0);
+            voice_totals.resize(voices.len().max(voice_totals.len()), 0.0);
+            for (i, a) in amp.iter().enumerate() {
+                amp_totals[i] += a;
+            }
+            for (i, v) in voices.iter().enumerate() {
+                voice_totals[i] += *v as f32;
+            }
+        }
+        let n = samples.max(1) as f32;
+        (amp_totals.iter().map(|a| a / n).collect(), voice_totals.iter().map(|v| v / n).collect())
+    }
+    
+    /// Threshold-to-height calibration: record each string's amp_sum from contact upward.
+    /// 
+    /// Starts from the touched zero set by z_calibrate / z_home: every enabled Z stepper of
+    /// every channel's pair is moved to 0, then up by HEIGHT_CALIBRATION STEP to MAX_STEPS,
+    /// both steppers of a pair at the same height. After settling (z_rest) amp_sum is
+    /// averaged over SAMPLES readings per channel. The curves replace the current height
+    /// map and are saved to HEIGHT_CALIBRATION FILE; see height_map.rs.
+    /// 
+    /// Returns message string including the per-string amplitude range
+    pub fn height_calibrate<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        progress_sender: Option<&std::sync::mpsc::Sender<String>>,
+    ) -> Result<String> {
+        let _span = tracing::info_span!("height_calibrate").entered();
+        let settings = &self.height_settings;
+        
+        // Channels with at least one enabled Z stepper, and those steppers
+        let channels: Vec<(usize, Vec<usize>)> = (0..self.get_amp_sum().len())
+            .filter_map(|ch_idx| {
+                let (z_in, z_out) = self.z_pair_for_channel(ch_idx)?;
+                let steppers: Vec<usize> = [z_in, z_out].into_iter().filter(|idx| self.get_stepper_enabled(*idx)).collect();
+                (!steppers.is_empty()).then_some((ch_idx, steppers))
+            })
+            .collect();
+        if channels.is_empty() {
+            return Err(Error::OperationAborted("height_calibrate: no channel with an enabled Z stepper (is audio running?)".to_string()));
+        }
+        let heights: Vec<i32> = (0..=settings.max_steps).step_by(settings.step as usize).collect();
+        
+        let mut messages = Vec::new();
+        messages.push(format!(
+            "Starting height_calibrate: {} string(s), heights 0..={} by {} above contact, {} sample(s) per height",
+            channels.len(), settings.max_steps, settings.step, settings.samples
+        ));
+        
+        let mut curves: std::collections::BTreeMap<usize, Vec<HeightPoint>> = std::collections::BTreeMap::new();
+        for (done, &height) in heights.iter().enumerate() {
+            if let Some(exit) = exit_flag {
+                if exit.load(std::sync::atomic::Ordering::Relaxed) {
+                    messages.push("height_calibrate cancelled - height map left unchanged".to_string());
+                    return Ok(messages.join("\n"));
                 }
-                
-                // Check Z variance threshold (using already calculated z_variance)
-                if z_variance > z_variance_threshold {
-                    messages.push(format!("Z variance threshold {} exceeded at X={}, performing calibration", z_variance_threshold, current_x));
-                    let cal_msg = self.z_calibrate(stepper_ops, positions, max_positions, exit_flag)?;
-                    messages.push(cal_msg);
-                    // Reset counters after calibration
-                    pass_count = 0;
-                    attempts = 0;
-                    // Reset tracking arrays after calibration
-                    last_voice_counts.clear();
-                    last_amp_sums.clear();
-                    // Continue trying at current X position
-                } else {
-                    // Update tracking arrays for next iteration
-                    last_voice_counts = voice_counts.clone();
+            }
+            for (_, steppers) in &channels {
+                for &z_idx in steppers {
+                    stepper_ops.abs_move(z_idx, height)?;
+                    if let Some(pos) = positions.get_mut(z_idx) {
+                        *pos = height;
+                    }
                 }
             }
+            self.rest_z();
             
-            // Break if we've reached x_finish
-            if current_x == x_finish {
-                break;
+            let (amp_avg, _) = self.averaged_audio(settings.samples);
+            for (ch_idx, _) in &channels {
+                if let Some(&amp_sum) = amp_avg.get(*ch_idx) {
+                    curves.entry(*ch_idx).or_default().push(HeightPoint { steps: height, amp_sum });
+                }
+            }
+            if let Some(sender) = progress_sender {
+                let _ = sender.send(format!("height_calibrate height {}/{}: {} steps above contact", done + 1, heights.len(), height));
             }
         }
         
-        messages.push("right_left_move complete".to_string());
+        // Nothing below contact was visited, but leave no sensor pressed at the bottom
+        let bump_msg = self.bump_check(None, positions, max_positions, stepper_ops, exit_flag)?.message;
+        if !bump_msg.trim().is_empty() {
+            messages.push(bump_msg);
+        }
+        
+        for (ch_idx, curve) in &curves {
+            if let (Some(contact), Some(top)) = (curve.first(), curve.last()) {
+                messages.push(format!(
+                    "{}: amp_sum {:.1} at contact, {:.1} at {} steps",
+                    self.string_for_channel(*ch_idx).map(|s| self.string_label(s)).unwrap_or_else(|| format!("Channel {}", ch_idx)),
+                    contact.amp_sum, top.amp_sum, top.steps
+                ));
+            }
+        }
+        let map = HeightMap { host: self.hostname.clone(), recorded_at: chrono::Local::now().to_rfc3339(), curves };
+        match crate::height_map::save(&settings.file, &map) {
+            Ok(()) => messages.push(format!("height_calibrate saved the height map to {}", settings.file.display())),
+            Err(e) => messages.push(format!("WARNING: height map not saved to {}: {}", settings.file.display(), e)),
+        }
+        if let Ok(mut slot) = self.height_map.lock() {
+            *slot = Some(map);
+        }
         Ok(messages.join("\n"))
     }
     
+    /// Curves from the last height_calibrate (this run or a saved one)
+    pub fn get_height_map(&self) -> Option<HeightMap> {
+        self.height_map.lock().ok().and_then(|m| m.clone())
+    }
+    
+    /// amp_sum (min, max) per channel for a target `low..=high` steps above contact;
+    /// None for channels the height map has no curve for
+    pub fn height_target_thresholds(&self, low: i32, high: i32, channels: usize) -> Vec<Option<(f32, f32)>> {
+        let map = self.get_height_map();
+        (0..channels)
+            .map(|ch_idx| map.as_ref().and_then(|m| m.amp_band(ch_idx, low, high)))
+            .collect()
+    }
+    
+    /// First X_REGIONS entry covering `x`
+    fn x_region_at(&self, x: i32) -> Option<usize> {
+        self.x_regions.iter().position(|r| (r.x_min..=r.x_max).contains(&x))
+    }
+    
+    /// The given (GUI) thresholds with X_REGIONS entry `region` laid over them
+    fn region_thresholds(
+        &self,
+        region: Option<usize>,
+        min_thresholds: &[f32],
+        max_thresholds: &[f32],
+        min_voices: &[usize],
+        max_voices: &[usize],
+    ) -> PassThresholds {
+        let Some(region) = region.and_then(|r| self.x_regions.get(r)) else {
+            return PassThresholds {
+                min_amp: min_thresholds.to_vec(),
+                max_amp: max_thresholds.to_vec(),
+                min_voices: min_voices.to_vec(),
+                max_voices: max_voices.to_vec(),
+            };
+        };
+        let channels = self.get_amp_sum().len();
+        PassThresholds {
+            min_amp: overlay_thresholds(min_thresholds, &region.amp_min, channels),
+            max_amp: overlay_thresholds(max_thresholds, &region.amp_max, channels),
+            min_voices: overlay_thresholds(min_voices, &region.voice_min, channels),
+            max_voices: overlay_thresholds(max_voices, &region.voice_max, channels),
+        }
+    }
+    
+    /// Right to left move operation: moves X from x_start to x_finish, adjusting Z at each position
+    /// (one lap of x_sweep; kept for existing callers)
+    pub fn right_left_move<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        min_thresholds: &[f32],
+        max_thresholds: &[f32],
+        min_voices: &[usize],
+        max_voices: &[usize],
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        progress_sender: Option<&std::sync::mpsc::Sender<String>>,
+    ) -> Result<String> {
+        let params = SweepParams { min_thresholds, max_thresholds, min_voices, max_voices, laps: 1, converge_below: 0 };
+        self.x_sweep(stepper_ops, positions, max_positions, SweepDirection::RightLeft, &params, exit_flag, progress_sender)
+    }
+    
     /// Left to right move operation: moves X from x_finish to x_start, adjusting Z at each position
-    /// Uses Adjustment Level to iterate in place until successfully passing the value
-    /// If attempts exceed Retry Threshold or Z variance threshold, performs calibration
-    /// progress_sender: Optional sender to stream progress messages in real-time
+    /// (one lap of x_sweep; kept for existing callers)
     pub fn left_right_move<T: StepperOperations>(
         &self,
         stepper_ops: &mut T,
@@ -1591,26 +4708,120 @@ impl Operations {
         exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
         progress_sender: Option<&std::sync::mpsc::Sender<String>>,
     ) -> Result<String> {
-        let x_step_index = self.x_step_index.ok_or_else(|| anyhow!("X stepper not configured"))?;
+        let params = SweepParams { min_thresholds, max_thresholds, min_voices, max_voices, laps: 1, converge_below: 0 };
+        self.x_sweep(stepper_ops, positions, max_positions, SweepDirection::LeftRight, &params, exit_flag, progress_sender)
+    }
+    
+    /// X sweep: moves X between x_start and x_finish in `direction`, adjusting Z at each position
+    /// Uses Adjustment Level to iterate in place until successfully passing the value
+    /// If attempts exceed Retry Threshold or Z variance threshold, performs calibration
+    /// Runs `params.laps` laps (see SweepParams); a summary of each lap is streamed through
+    /// progress_sender and included in the result.
+    /// progress_sender: Optional sender to stream progress messages in real-time
+    pub fn x_sweep<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        direction: SweepDirection,
+        params: &SweepParams,
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        progress_sender: Option<&std::sync::mpsc::Sender<String>>,
+    ) -> Result<String> {
+        let _span = tracing::info_span!("x_sweep", direction = direction.name()).entered();
+        let x_step_index = self.x_step_index().ok_or_else(|| Error::ConfigMissing("X stepper not configured".to_string()))?;
         let x_start = self.get_x_start();
         let x_finish = self.get_x_finish();
+        let until_stopped = params.laps == 0 && direction == SweepDirection::PingPong;
+        let laps = if params.laps == 0 { 1 } else { params.laps };
+        
+        let mut messages = Vec::new();
+        messages.push(format!(
+            "Starting {}: X from {} to {} (step: {}, laps: {})",
+            direction.name(), x_start, x_finish, self.get_x_step(),
+            if until_stopped { "until stopped".to_string() } else { laps.to_string() }
+        ));
+        self.record_sweep(|sweep| *sweep = Some(SweepReport { direction, laps: Vec::new(), converged: false }));
+        
+        let mut lap = 0;
+        while until_stopped || lap < laps {
+            let (from, to) = direction.leg(lap, x_start, x_finish);
+            let _lap_span = tracing::info_span!("lap", n = lap + 1, from, to).entered();
+            // An endless ping-pong keeps only the lap summaries, not every lap's detail
+            let mut leg_messages = Vec::new();
+            let z_moves_before = self.z_moves.load(std::sync::atomic::Ordering::Relaxed);
+            let mut report = self.sweep_leg(stepper_ops, positions, max_positions, x_step_index, from, to, params, exit_flag, progress_sender, &mut leg_messages)?;
+            report.z_moves = self.z_moves.load(std::sync::atomic::Ordering::Relaxed) - z_moves_before;
+            if !until_stopped {
+                messages.append(&mut leg_messages);
+            }
+            lap += 1;
+            let summary = report.summary(lap);
+            tracing::info!("{}: {}", direction.name(), summary);
+            if let Some(sender) = progress_sender {
+                let _ = sender.send(summary.clone());
+            }
+            messages.push(summary);
+            let cancelled = report.cancelled;
+            let converged = !cancelled && params.converge_below > 0 && report.z_moves < params.converge_below as u64;
+            self.record_sweep(|sweep| {
+                if let Some(sweep) = sweep {
+                    sweep.laps.push(report);
+                    sweep.converged = converged;
+                }
+            });
+            if cancelled {
+                messages.push("Operation cancelled".to_string());
+                return Ok(messages.join("\n"));
+            }
+            if converged && (until_stopped || lap < laps) {
+                messages.push(format!("Converged: fewer than {} Z moves in lap {}, stopping", params.converge_below, lap));
+                break;
+            }
+        }
+        
+        messages.push(format!("{} complete", direction.name()));
+        Ok(messages.join("\n"))
+    }
+    
+    fn record_sweep(&self, update: impl FnOnce(&mut Option<SweepReport>)) {
+        if let Ok(mut sweep) = self.last_sweep.lock() {
+            update(&mut sweep);
+        }
+    }
+    
+    /// One lap of x_sweep, from `from` to `to`
+    fn sweep_leg<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        x_step_index: usize,
+        from: i32,
+        to: i32,
+        params: &SweepParams,
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        progress_sender: Option<&std::sync::mpsc::Sender<String>>,
+        messages: &mut Vec<String>,
+    ) -> Result<LapReport> {
+        let started = Instant::now();
         let x_step = self.get_x_step();
         let adjustment_level = self.get_adjustment_level();
         let retry_threshold = self.get_retry_threshold();
         let z_variance_threshold = self.get_z_variance_threshold();
         let delta_threshold = self.get_delta_threshold() as f32;
-        
-        let mut messages = Vec::new();
-        messages.push(format!("Starting left_right_move: X from {} to {} (step: {})", x_finish, x_start, x_step));
+        let SweepParams { min_thresholds, max_thresholds, min_voices, max_voices, .. } = *params;
+        let mut report = LapReport { from, to, ..LapReport::default() };
         
         // Read current X position from Arduino - Arduino is source of truth
-        let current_x_pos = positions.get(x_step_index).copied().ok_or_else(|| anyhow!("Failed to read X position from Arduino"))?;
+        let current_x_pos = positions.get(x_step_index).copied().ok_or_else(|| Error::Other("Failed to read X position from Arduino".to_string()))?;
         messages.push(format!("Current X position from Arduino: {}", current_x_pos));
         
-        // Absolute move to x_finish if not already there
-        if current_x_pos != x_finish {
-            messages.push(format!("Moving X to absolute position: {} (current: {})", x_finish, current_x_pos));
-            stepper_ops.abs_move(x_step_index, x_finish)?;
+        // Absolute move to the start of the lap if not already there
+        if current_x_pos != from {
+            messages.push(format!("Moving X to absolute position: {} (current: {})", from, current_x_pos));
+            self.clear_x_path(stepper_ops, positions, current_x_pos, from, messages)?;
+            stepper_ops.abs_move(x_step_index, from)?;
             // Wait for physical movement to complete using x_rest
             self.rest_x();
             // Position is updated by refresh_positions() in stepper_gui - Arduino knows the position
@@ -1618,20 +4829,39 @@ impl Operations {
         }
         
         // Read current X position from Arduino (after move) - Arduino is source of truth
-        let mut current_x = positions.get(x_step_index).copied().ok_or_else(|| anyhow!("Failed to read X position from Arduino"))?;
+        let mut current_x = positions.get(x_step_index).copied().ok_or_else(|| Error::Other("Failed to read X position from Arduino".to_string()))?;
         messages.push(format!("X position after initial move: {}", current_x));
-        let step_direction = if x_start > x_finish { 1 } else { -1 };
+        let step_direction = if to > from { 1 } else { -1 };
         let abs_step = x_step.abs();
+        let mut x_failures = 0; // Unconfirmed X steps in a row (obstruction detection)
+        let mut last_region = None; // X_REGIONS entry in use, for logging changes
         
-        while (step_direction > 0 && current_x < x_start) || (step_direction < 0 && current_x > x_start) {
+        while (step_direction > 0 && current_x < to) || (step_direction < 0 && current_x > to) {
             // Check exit flag
             if let Some(exit) = exit_flag {
                 if exit.load(std::sync::atomic::Ordering::Relaxed) {
                     messages.push("Operation cancelled".to_string());
-                    return Ok(messages.join("\n"));
+                    report.cancelled = true;
+                    return Ok(report);
                 }
             }
             
+            // Everything done at this X position, for the operation timeline
+            let _dwell = tracing::info_span!("dwell", x = current_x).entered();
+            
+            // X_REGIONS change the pass criteria as the carriage crosses them
+            let region = self.x_region_at(current_x);
+            if !self.x_regions.is_empty() && last_region != Some(region) {
+                messages.push(match region {
+                    Some(r) => format!("X={}: using X_REGIONS entry {} thresholds", current_x, r),
+                    None => format!("X={}: outside X_REGIONS, using the GUI thresholds", current_x),
+                });
+                last_region = Some(region);
+            }
+            let thresholds = self.region_thresholds(region, min_thresholds, max_thresholds, min_voices, max_voices);
+            let (min_thresholds, max_thresholds, min_voices, max_voices) =
+                (&thresholds.min_amp[..], &thresholds.max_amp[..], &thresholds.min_voices[..], &thresholds.max_voices[..]);
+            
             // At current X position, iterate until we get Adjustment Level consecutive successful passes
             // Each pass = z_adjust + bump_check
             let mut pass_count = 0; // Consecutive successful passes
@@ -1644,11 +4874,16 @@ impl Operations {
                 if let Some(exit) = exit_flag {
                     if exit.load(std::sync::atomic::Ordering::Relaxed) {
                         messages.push("Operation cancelled".to_string());
-                        return Ok(messages.join("\n"));
+                        report.cancelled = true;
+                        return Ok(report);
                     }
                 }
                 
                 attempts += 1;
+                report.attempts += 1;
+                
+                // Mute/solo may change while running; re-read every attempt
+                let channel_mask = self.get_channel_mask();
                 
                 // Get current amp_sums before adjustment
                 let current_amp_sums = self.get_amp_sum();
@@ -1669,7 +4904,9 @@ impl Operations {
                 let z_variance = if !last_voice_counts.is_empty() && last_voice_counts.len() == voice_counts.len() {
                     voice_counts.iter()
                         .zip(last_voice_counts.iter())
-                        .map(|(curr, last)| ((*curr as i32) - (*last as i32)).abs())
+                        .enumerate()
+                        .filter(|(ch_idx, _)| channel_mask.includes(*ch_idx))
+                        .map(|(_, (curr, last))| ((*curr as i32) - (*last as i32)).abs())
                         .sum::<i32>()
                 } else {
                     0
@@ -1692,6 +4929,16 @@ impl Operations {
                     let _ = sender.send(loop_msg);
                 }
                 
+                // Hold position while the audio data is stale rather than adjust against a frozen frame
+                let mut interject = |messages: &mut Vec<String>| {
+                    self.run_interjections(stepper_ops, positions, max_positions, exit_flag, progress_sender, messages)
+                };
+                if !self.wait_for_fresh_partials(exit_flag, progress_sender, messages, &mut interject) {
+                    messages.push("Operation cancelled".to_string());
+                    report.cancelled = true;
+                    return Ok(report);
+                }
+                
                 // Run z_adjust with skip_channels (channels exceeding delta threshold are skipped)
                 let z_adjust_msg = self.z_adjust_with_skip(
                     stepper_ops,
@@ -1706,18 +4953,12 @@ impl Operations {
                 )?;
                 
                 // Run bump_check
-                let bump_msg = self.bump_check(None, positions, max_positions, stepper_ops, exit_flag)?;
+                let bump = self.bump_check(None, positions, max_positions, stepper_ops, exit_flag)?;
                 
-                // Check if bump_check passed (no CRITICAL errors, no bumps detected)
-                // bump_check returns empty string if no bumps, or messages if bumps were detected/cleared
-                // A CRITICAL message means a stepper was disabled - this is a failure
-                // If bumps were detected (even if cleared), that means steppers were touching - this is a failure
-                let bump_check_passed = !bump_msg.contains("CRITICAL") && 
-                    !bump_msg.contains("bump cleared") &&
-                    !bump_msg.contains("bumping") &&
-                    (bump_msg.trim().is_empty() || 
-                     bump_msg.contains("bump_check disabled") || 
-                     bump_msg.contains("no GPIO"));
+                // A pass needs nothing touching: a stepper that had to be retracted (even if
+                // cleared), disabled or quarantined, or a sensor that could not be read, fails
+                let bump_check_passed = bump.passed();
+                let bump_msg = bump.message;
                 
                 // Get current voice counts and amp sums (refresh after z_adjust)
                 let voice_counts = self.get_voice_count();
@@ -1728,20 +4969,17 @@ impl Operations {
                 
                 // Check if all channels are within their min/max ranges (green indicators)
                 // A pass is when voice_count AND amp_sum for all channels are within their ranges
-                let num_channels = amp_sums.len().min(voice_counts.len());
-                let voice_amp_pass = (0..num_channels).all(|ch_idx| {
-                    let amp_sum = amp_sums[ch_idx];
-                    let voice_count = voice_counts[ch_idx];
-                    
-                    let min_thresh = min_thresholds.get(ch_idx).copied().unwrap_or(20.0);
-                    let max_thresh = max_thresholds.get(ch_idx).copied().unwrap_or(100.0);
-                    let min_voice = min_voices.get(ch_idx).copied().unwrap_or(0);
-                    let max_voice = max_voices.get(ch_idx).copied().unwrap_or(12);
-                    
-                    // Check both amp_sum and voice_count are within their ranges
-                    amp_sum >= min_thresh && amp_sum <= max_thresh &&
-                    voice_count >= min_voice && voice_count <= max_voice
-                });
+                // Muted (or not soloed) channels don't count toward a pass
+                let evaluations = evaluate_channels(&amp_sums, &voice_counts, min_thresholds, max_thresholds, min_voices, max_voices, self.partials_per_channel(), &channel_mask);
+                let voice_amp_pass = evaluations.iter().all(ChannelEvaluation::passed);
+                let failed_channels = evaluations.iter()
+                    .filter(|e| !e.passed())
+                    .map(|e| format!("Ch{} {}", e.channel, e.failures.iter().map(|f| f.name()).collect::<Vec<_>>().join(", ")))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                if let Ok(mut last) = self.last_evaluation.lock() {
+                    *last = Some(PassEvaluation { x: current_x, attempt: attempts, bump_check_passed, channels: evaluations, at: Instant::now() });
+                }
                 
                 // A pass requires BOTH bump_check passed AND voice/amp checks passed
                 let all_pass = bump_check_passed && voice_amp_pass;
@@ -1753,14 +4991,15 @@ impl Operations {
                     
                     // If we've reached Adjustment Level consecutive passes, move X by step_size and break
                     if pass_count >= adjustment_level {
+                        report.positions += 1;
                         messages.push(format!("Adjustment level {} met at X={} after {} attempts, moving X by step size {}", adjustment_level, current_x, attempts, abs_step));
                         
-                        // Move X by exactly x_step_size (relative move)
+                        // Move X by exactly x_step_size (relative move), verified against the reported position;
+                        // an unconfirmed step leaves X where it was and the next attempt retries it
                         let step_delta = step_direction * abs_step;
-                        self.rel_move_x(stepper_ops, x_step_index, step_delta)?;
-                        // Position is updated by refresh_positions() - Arduino knows the position
+                        self.sweep_step_x(stepper_ops, positions, max_positions, x_step_index, step_delta, &mut x_failures, messages)?;
                         // Read updated position from Arduino for next iteration - Arduino is source of truth
-                        current_x = positions.get(x_step_index).copied().ok_or_else(|| anyhow!("Failed to read X position from Arduino"))?;
+                        current_x = positions.get(x_step_index).copied().ok_or_else(|| Error::Other("Failed to read X position from Arduino".to_string()))?;
                         messages.push(format!("Moved X by {} to position: {}", step_delta, current_x));
                         
                         // Reset pass counter for next X position
@@ -1785,7 +5024,7 @@ impl Operations {
                             messages.push(format!("bump_check failed at X={}: {}", current_x, bump_msg.trim()));
                         }
                         if !voice_amp_pass {
-                            messages.push(format!("voice/amp checks failed at X={}", current_x));
+                            messages.push(format!("voice/amp checks failed at X={}: {}", current_x, failed_channels));
                         }
                     }
                     pass_count = 0;
@@ -1795,6 +5034,7 @@ impl Operations {
                 if attempts >= retry_threshold {
                     messages.push(format!("Retry threshold {} exceeded at X={}, performing calibration", retry_threshold, current_x));
                     let cal_msg = self.z_calibrate(stepper_ops, positions, max_positions, exit_flag)?;
+                    report.calibrations += 1;
                     messages.push(cal_msg);
                     // Reset counters after calibration
                     pass_count = 0;
@@ -1809,6 +5049,7 @@ impl Operations {
                 if z_variance > z_variance_threshold {
                     messages.push(format!("Z variance threshold {} exceeded at X={}, performing calibration", z_variance_threshold, current_x));
                     let cal_msg = self.z_calibrate(stepper_ops, positions, max_positions, exit_flag)?;
+                    report.calibrations += 1;
                     messages.push(cal_msg);
                     // Reset counters after calibration
                     pass_count = 0;
@@ -1823,44 +5064,185 @@ impl Operations {
                 }
             }
             
-            // Break if we've reached x_start
-            if current_x == x_start {
+            // Break if we've reached the end of the leg
+            if current_x == to {
                 break;
             }
         }
         
-        messages.push("left_right_move complete".to_string());
-        Ok(messages.join("\n"))
+        report.elapsed = started.elapsed();
+        Ok(report)
     }
     
     /// Helper function to fetch x_step from stepper_gui socket
     fn fetch_x_step_from_socket(socket_path: &str) -> Result<i32> {
         use std::io::{BufRead, BufReader, Write};
-        use std::os::unix::net::UnixStream;
 
-        let mut stream = UnixStream::connect(socket_path)
-            .map_err(|e| anyhow::anyhow!("Failed to connect to stepper_gui socket at {}: {}", socket_path, e))?;
+        let mut stream = crate::stepper_link::connect(socket_path)
+            .map_err(|e| Error::StepperLink(format!("Failed to connect to stepper_gui socket at {}: {}", socket_path, e)))?;
         stream
             .write_all(b"get_x_step\n")
-            .map_err(|e| anyhow::anyhow!("Failed to request x_step: {}", e))?;
+            .map_err(|e| Error::StepperLink(format!("Failed to request x_step: {}", e)))?;
         stream
             .flush()
-            .map_err(|e| anyhow::anyhow!("Failed to flush x_step request: {}", e))?;
+            .map_err(|e| Error::StepperLink(format!("Failed to flush x_step request: {}", e)))?;
 
         let mut reader = BufReader::new(stream);
         let mut response = String::new();
         let bytes = reader
             .read_line(&mut response)
-            .map_err(|e| anyhow::anyhow!("Failed to read x_step response: {}", e))?;
+            .map_err(|e| Error::StepperLink(format!("Failed to read x_step response: {}", e)))?;
         if bytes == 0 {
-            return Err(anyhow::anyhow!("Stepper GUI closed socket without replying"));
+            return Err(Error::StepperLink("Stepper GUI closed socket without replying".to_string()));
         }
         response.trim().parse::<i32>()
-            .map_err(|e| anyhow::anyhow!("Failed to parse x_step response '{}': {}", response.trim(), e))
+            .map_err(|e| Error::StepperLink(format!("Failed to parse x_step response '{}': {}", response.trim(), e)))
     }
 
-    /// X Home operation: moves X stepper toward home until home limit is hit
-    /// Handles both separate home/away pins and single X_LIMIT_PIN (direction-based)
+    /// Park height for the `z_num`th Z stepper: PARK.PARK_POS, capped at its max
+    fn z_park_position(&self, z_num: usize, stepper_idx: usize, max_positions: &HashMap<usize, i32>) -> i32 {
+        let max_pos = max_positions.get(&stepper_idx).copied().unwrap_or(100);
+        self.park_settings.z_positions.get(z_num).copied().unwrap_or(max_pos).min(max_pos)
+    }
+    
+    /// Enabled Z steppers more than `margin` steps below their park height
+    pub fn z_steppers_below_park(&self, positions: &HashMap<usize, i32>, max_positions: &HashMap<usize, i32>, margin: i32) -> Vec<usize> {
+        let enabled_states = self.get_all_stepper_enabled();
+        self.get_z_stepper_indices()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, idx)| enabled_states.get(idx).copied().unwrap_or(false))
+            .filter(|&(z_num, idx)| {
+                positions.get(&idx).is_some_and(|&pos| pos < self.z_park_position(z_num, idx, max_positions) - margin)
+            })
+            .map(|(_, idx)| idx)
+            .collect()
+    }
+    
+    /// Raise every enabled Z stepper to its park height after an operation was cut
+    /// short (e.g. timed out), without the rest of park. Keeps going past a failed move
+    /// so one stuck stepper does not leave the others down; returns what was done.
+    pub fn retract_z_to_safe_height<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+    ) -> String {
+        let enabled_states = self.get_all_stepper_enabled();
+        let mut messages = Vec::new();
+        for (z_num, stepper_idx) in self.get_z_stepper_indices().into_iter().enumerate() {
+            if !enabled_states.get(&stepper_idx).copied().unwrap_or(false) {
+                continue;
+            }
+            let safe_pos = self.z_park_position(z_num, stepper_idx, max_positions);
+            match stepper_ops.abs_move(stepper_idx, safe_pos) {
+                Ok(()) => {
+                    if let Some(slot) = positions.get_mut(stepper_idx) {
+                        *slot = safe_pos;
+                    }
+                    messages.push(format!("Retracted {} to {}", self.stepper_label(stepper_idx), safe_pos));
+                }
+                Err(e) => messages.push(format!("Failed to retract {}: {}", self.stepper_label(stepper_idx), e)),
+            }
+        }
+        self.rest_z();
+        messages.join("\n")
+    }
+    
+    /// Park: raise every enabled Z stepper to its PARK_POS (default its max), move X to
+    /// PARK.X_POS, then check the touch sensors. Only when no string is in contact are
+    /// the steppers disabled (PARK.DISABLE_MOTORS); contact is reported as an error.
+    pub fn park<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        max_positions: &HashMap<usize, i32>,
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+    ) -> Result<String> {
+        let _span = tracing::info_span!("park").entered();
+        let settings = &self.park_settings;
+        let cancelled = || exit_flag.is_some_and(|exit| exit.load(std::sync::atomic::Ordering::Relaxed));
+        let mut messages = vec!["Parking...".to_string()];
+        
+        // Retract Z first so X never drags a string
+        let z_indices = self.get_z_stepper_indices();
+        let enabled_states = self.get_all_stepper_enabled();
+        for (z_num, &stepper_idx) in z_indices.iter().enumerate() {
+            if cancelled() {
+                messages.push("Operation cancelled".to_string());
+                return Ok(messages.join("\n"));
+            }
+            if !enabled_states.get(&stepper_idx).copied().unwrap_or(false) {
+                messages.push(format!("Skipping disabled {}", self.stepper_label(stepper_idx)));
+                continue;
+            }
+            let park_pos = self.z_park_position(z_num, stepper_idx, max_positions);
+            stepper_ops.abs_move(stepper_idx, park_pos)?;
+            if let Some(slot) = positions.get_mut(stepper_idx) {
+                *slot = park_pos;
+            }
+            messages.push(format!("{} raised to {}", self.stepper_label(stepper_idx), park_pos));
+        }
+        self.rest_z();
+        
+        if let (Some(x_idx), Some(x_pos)) = (self.x_step_index(), settings.x_position) {
+            if self.x_max_pos == Some(0) {
+                messages.push("X stepper is dummy (X_MAX_POS=0) - X left in place".to_string());
+            } else if cancelled() {
+                messages.push("Operation cancelled".to_string());
+                return Ok(messages.join("\n"));
+            } else {
+                let x_pos = self.x_max_pos.map_or(x_pos, |max| x_pos.clamp(0, max));
+                let x_from = positions.get(x_idx).copied().unwrap_or(x_pos);
+                self.clear_x_path(stepper_ops, positions, x_from, x_pos, &mut messages)?;
+                stepper_ops.abs_move(x_idx, x_pos)?;
+                self.rest_x();
+                if let Some(slot) = positions.get_mut(x_idx) {
+                    *slot = x_pos;
+                }
+                messages.push(format!("X moved to park position {}", x_pos));
+            }
+        }
+        
+        // Verify no string is still in contact
+        match self.gpio.as_ref().filter(|gpio| gpio.exist) {
+            Some(gpio) => {
+                let touches = gpio.press_check(None)?;
+                let touching: Vec<String> = z_indices
+                    .iter()
+                    .filter(|&&idx| {
+                        let sensor = self.sensor_index(idx);
+                        touches.get(sensor).copied().unwrap_or(false) && !self.sensor_quarantined(sensor)
+                    })
+                    .map(|idx| idx.to_string())
+                    .collect();
+                if !touching.is_empty() {
+                    messages.push(format!("Stepper(s) {} still touching after retracting - motors left enabled", touching.join(", ")));
+                    return Err(Error::Other(format!("Park incomplete: a string is still in contact\n{}", messages.join("\n"))));
+                }
+                messages.push("Touch sensors clear".to_string());
+            }
+            None => messages.push("No GPIO - string contact not verified".to_string()),
+        }
+        
+        if settings.disable_motors {
+            let mut parked = z_indices.clone();
+            parked.extend(self.x_step_index());
+            for stepper_idx in parked {
+                self.apply_stepper_enabled(stepper_ops, stepper_idx, false)?;
+            }
+            messages.push("Steppers disabled - re-enable them before the next operation".to_string());
+        }
+        
+        messages.push("Park complete".to_string());
+        self.hook(HookEvent::ParkComplete, serde_json::json!({
+            "positions": positions,
+            "motors_disabled": settings.disable_motors,
+        }));
+        Ok(messages.join("\n"))
+    }
+    
+    /// X Home operation (see axis_home)
     pub fn x_home<T: StepperOperations>(
         &self,
         stepper_ops: &mut T,
@@ -1868,36 +5250,93 @@ impl Operations {
         exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
         socket_path: Option<&str>,
     ) -> Result<String> {
-        let x_step_index = self.x_step_index.ok_or_else(|| anyhow!("X stepper not configured"))?;
+        self.axis_home("X", stepper_ops, positions, exit_flag, socket_path)
+    }
+    
+    /// X Away operation (see axis_away)
+    pub fn x_away<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        socket_path: Option<&str>,
+    ) -> Result<String> {
+        self.axis_away("X", stepper_ops, positions, exit_flag, socket_path)
+    }
+    
+    /// X Calibrate operation (see axis_calibrate)
+    pub fn x_calibrate<T: StepperOperations>(
+        &self,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        socket_path: Option<&str>,
+    ) -> Result<String> {
+        self.axis_calibrate("X", stepper_ops, positions, exit_flag, socket_path)
+    }
+    
+    /// Run `<axis>_home`, `<axis>_away` or `<axis>_calibrate`
+    pub fn run_axis_seek<T: StepperOperations>(
+        &self,
+        axis: &str,
+        seek: AxisSeek,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        socket_path: Option<&str>,
+    ) -> Result<String> {
+        match seek {
+            AxisSeek::Home => self.axis_home(axis, stepper_ops, positions, exit_flag, socket_path),
+            AxisSeek::Away => self.axis_away(axis, stepper_ops, positions, exit_flag, socket_path),
+            AxisSeek::Calibrate => self.axis_calibrate(axis, stepper_ops, positions, exit_flag, socket_path),
+        }
+    }
+    
+    /// Axis Home operation: moves the axis stepper toward home until the home limit is hit
+    /// Handles both separate home/away pins and single X_LIMIT_PIN (direction-based)
+    pub fn axis_home<T: StepperOperations>(
+        &self,
+        axis_name: &str,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        socket_path: Option<&str>,
+    ) -> Result<String> {
+        let _span = tracing::info_span!("axis_home", axis = axis_name).entered();
+        let axis = self.linear_axis(axis_name)?;
+        let step_index = axis.step_index;
         
-        // Check if this is a dummy X stepper (X_MAX_POS == 0)
-        if self.x_max_pos == Some(0) {
-            return Ok("X stepper is dummy (X_MAX_POS=0) - operation skipped".to_string());
+        // Check if this is a dummy stepper (max position 0)
+        if axis.max_pos == Some(0) {
+            return Ok(format!("{} stepper is dummy ({}=0) - operation skipped", axis.name, axis.max_pos_key()));
         }
         
-        let gpio = self.gpio.as_ref().ok_or_else(|| anyhow!("GPIO not initialized"))?;
+        let gpio = self.gpio.as_ref().ok_or_else(|| Error::GpioUnavailable("GPIO not initialized".to_string()))?;
         if !gpio.exist {
             return Ok("GPIO not available - cannot check home limit".to_string());
         }
         
         let mut messages = Vec::new();
-        messages.push("Starting X Home operation...".to_string());
+        messages.push(format!("Starting {} Home operation...", axis.name));
         
         // Check if we have home limit detection
-        if gpio.x_home_line.is_none() {
-            return Ok("No X home limit switch configured".to_string());
+        if !gpio.has_home_switch(&axis.name) {
+            return Ok(format!("No {} home limit switch configured", axis.name));
         }
         
         // Get max position - required for this operation
-        let x_max_pos = self.x_max_pos.ok_or_else(|| anyhow!("X_MAX_POS not configured"))?;
-        if x_max_pos <= 0 {
-            return Ok("X_MAX_POS is invalid (must be > 0) - operation skipped".to_string());
+        let max_pos = axis.max_pos.ok_or_else(|| Error::ConfigMissing(format!("{} not configured", axis.max_pos_key())))?;
+        if max_pos <= 0 {
+            return Ok(format!("{} is invalid (must be > 0) - operation skipped", axis.max_pos_key()));
         }
         
-        // Reset X to max position BEFORE moving to home
-        stepper_ops.reset(x_step_index, x_max_pos)?;
+        // Where the axis starts is unknown until home is found: clear the whole travel
+        self.clear_axis_path(axis, stepper_ops, positions, 0, max_pos, &mut messages)?;
+        
+        // Reset to max position BEFORE moving to home
+        stepper_ops.reset(step_index, max_pos)?;
         // Position is updated by refresh_positions() - Arduino is source of truth
-        messages.push(format!("X position reset to max ({}) before moving to home", x_max_pos));
+        messages.push(format!("{} position reset to max ({}) before moving to home", axis.name, max_pos));
         
         // Move toward home (negative direction) in -10 step increments until GPIO trigger
         const STEP_SIZE: i32 = -10; // Move 10 steps toward home at a time
@@ -1914,7 +5353,7 @@ impl Operations {
             }
             
             // Check if we've hit the GPIO trigger (home limit)
-            let at_home = gpio.x_home_check().unwrap_or(false);
+            let at_home = gpio.home_check(&axis.name).unwrap_or(false);
             
             if at_home {
                 messages.push("Home GPIO trigger detected".to_string());
@@ -1928,14 +5367,10 @@ impl Operations {
             }
             
             // Sync x_step from stepper_gui before move (may have changed during execution)
-            if let Some(socket) = socket_path {
-                if let Ok(x_step) = Self::fetch_x_step_from_socket(socket) {
-                    self.set_x_step(x_step);
-                }
-            }
+            self.sync_axis_step(axis, socket_path);
             
             // Move -10 steps toward home
-            self.rel_move_x(stepper_ops, x_step_index, STEP_SIZE)?;
+            self.rel_move_axis(axis, stepper_ops, STEP_SIZE)?;
             // Position is updated by refresh_positions() in stepper_ops.rel_move(), don't manually update
             iterations += 1;
             
@@ -1945,63 +5380,69 @@ impl Operations {
         }
         
         // Verify we're at home with position 0
-        let final_pos = positions.get(x_step_index).copied().unwrap_or(0);
-        let still_at_home = gpio.x_home_check().unwrap_or(false);
+        let final_pos = positions.get(step_index).copied().unwrap_or(0);
+        let still_at_home = gpio.home_check(&axis.name).unwrap_or(false);
         
         if still_at_home {
-            // Home verified by GPIO - set X to 0
-            stepper_ops.reset(x_step_index, 0)?;
+            // Home verified by GPIO - set the axis to 0
+            stepper_ops.reset(step_index, 0)?;
             // Position is updated by refresh_positions() - Arduino is source of truth
-            messages.push(format!("X Home complete - position set to 0, verified at home"));
+            messages.push(format!("{} Home complete - position set to 0, verified at home", axis.name));
         } else {
             // Never reached home - check if Arduino position is already 0
             if final_pos == 0 {
-                messages.push(format!("X Home failed - never reached home and Arduino position is already 0"));
-                messages.push("Disabling X stepper due to home failure".to_string());
-                self.set_stepper_enabled(x_step_index, false);
-                stepper_ops.disable(x_step_index)?;
+                messages.push(format!("{} Home failed - never reached home and Arduino position is already 0", axis.name));
+                messages.push(format!("Disabling {} stepper due to home failure", axis.name));
+                self.apply_stepper_enabled(stepper_ops, step_index, false)?;
+                self.report_disabled(step_index, &format!("{} disabled: {} home never reached home", self.stepper_label(step_index), axis.name));
             } else {
-                messages.push(format!("X Home failed - never reached home, position: {}", final_pos));
+                messages.push(format!("{} Home failed - never reached home, position: {}", axis.name, final_pos));
             }
         }
         
         Ok(messages.join("\n"))
     }
     
-    /// X Away operation: moves X stepper toward away until away limit is hit
+    /// Axis Away operation: moves the axis stepper toward away until the away limit is hit
     /// Handles both separate home/away pins and single X_LIMIT_PIN (direction-based)
-    pub fn x_away<T: StepperOperations>(
+    pub fn axis_away<T: StepperOperations>(
         &self,
+        axis_name: &str,
         stepper_ops: &mut T,
         positions: &mut [i32],
         exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
         socket_path: Option<&str>,
     ) -> Result<String> {
-        let x_step_index = self.x_step_index.ok_or_else(|| anyhow!("X stepper not configured"))?;
+        let _span = tracing::info_span!("axis_away", axis = axis_name).entered();
+        let axis = self.linear_axis(axis_name)?;
+        let step_index = axis.step_index;
         
-        // Check if this is a dummy X stepper (X_MAX_POS == 0)
-        if self.x_max_pos == Some(0) {
-            return Ok("X stepper is dummy (X_MAX_POS=0) - operation skipped".to_string());
+        // Check if this is a dummy stepper (max position 0)
+        if axis.max_pos == Some(0) {
+            return Ok(format!("{} stepper is dummy ({}=0) - operation skipped", axis.name, axis.max_pos_key()));
         }
         
-        let gpio = self.gpio.as_ref().ok_or_else(|| anyhow!("GPIO not initialized"))?;
+        let gpio = self.gpio.as_ref().ok_or_else(|| Error::GpioUnavailable("GPIO not initialized".to_string()))?;
         if !gpio.exist {
             return Ok("GPIO not available - cannot check away limit".to_string());
         }
         
         let mut messages = Vec::new();
-        messages.push("Starting X Away operation...".to_string());
+        messages.push(format!("Starting {} Away operation...", axis.name));
         
         // Get max position - required for this operation
-        let x_max_pos = self.x_max_pos.ok_or_else(|| anyhow!("X_MAX_POS not configured"))?;
-        if x_max_pos <= 0 {
-            return Ok("X_MAX_POS is invalid (must be > 0) - operation skipped".to_string());
+        let max_pos = axis.max_pos.ok_or_else(|| Error::ConfigMissing(format!("{} not configured", axis.max_pos_key())))?;
+        if max_pos <= 0 {
+            return Ok(format!("{} is invalid (must be > 0) - operation skipped", axis.max_pos_key()));
         }
         
-        // Set X to 0 first
-        stepper_ops.reset(x_step_index, 0)?;
+        // Where the axis starts is unknown until away is found: clear the whole travel
+        self.clear_axis_path(axis, stepper_ops, positions, 0, max_pos, &mut messages)?;
+        
+        // Set the axis to 0 first
+        stepper_ops.reset(step_index, 0)?;
         // Position is updated by refresh_positions() - Arduino is source of truth
-        messages.push("X position set to 0".to_string());
+        messages.push(format!("{} position set to 0", axis.name));
         
         // Move toward away (positive direction) in +10 step increments until max pos or GPIO trigger
         const STEP_SIZE: i32 = 10; // Move 10 steps toward away at a time
@@ -2018,16 +5459,16 @@ impl Operations {
             }
             
             // Get current position (updated by refresh_positions() in previous iteration)
-            let current_pos = positions.get(x_step_index).copied().unwrap_or(0);
+            let current_pos = positions.get(step_index).copied().unwrap_or(0);
             
             // Check if we've reached max position
-            if current_pos >= x_max_pos {
-                messages.push(format!("Max position ({}) reached", x_max_pos));
+            if current_pos >= max_pos {
+                messages.push(format!("Max position ({}) reached", max_pos));
                 break;
             }
             
             // Check if we've hit the GPIO trigger (away limit)
-            let at_away = gpio.x_away_check().unwrap_or(false);
+            let at_away = gpio.away_check(&axis.name).unwrap_or(false);
             if at_away {
                 messages.push("Away GPIO trigger detected".to_string());
                 break;
@@ -2040,84 +5481,99 @@ impl Operations {
             }
             
             // Sync x_step from stepper_gui before move (may have changed during execution)
-            if let Some(socket) = socket_path {
-                if let Ok(x_step) = Self::fetch_x_step_from_socket(socket) {
-                    self.set_x_step(x_step);
-                }
-            }
+            self.sync_axis_step(axis, socket_path);
             
             // Move +10 steps toward away
-            self.rel_move_x(stepper_ops, x_step_index, STEP_SIZE)?;
+            self.rel_move_axis(axis, stepper_ops, STEP_SIZE)?;
             // Position is updated by refresh_positions() in stepper_ops.rel_move(), don't manually update
             // The local positions array will be updated when operations_gui polls stepper_gui
             iterations += 1;
             
             if iterations % 10 == 0 {
                 // Read current position for logging (may be stale until next poll)
-                let logged_pos = positions.get(x_step_index).copied().unwrap_or(0);
+                let logged_pos = positions.get(step_index).copied().unwrap_or(0);
                 messages.push(format!("Moving toward away... (iteration {}, position: {})", iterations, logged_pos));
             }
         }
         
         // Check final state: if GPIO verified, set to max; if never reached away and at max, disable
-        let final_pos = positions.get(x_step_index).copied().unwrap_or(0);
-        let at_away_gpio = gpio.x_away_check().unwrap_or(false);
+        let final_pos = positions.get(step_index).copied().unwrap_or(0);
+        let at_away_gpio = gpio.away_check(&axis.name).unwrap_or(false);
         
         if at_away_gpio {
-            // Away verified by GPIO - set X to max pos
-            stepper_ops.reset(x_step_index, x_max_pos)?;
+            // Away verified by GPIO - set the axis to max pos
+            stepper_ops.reset(step_index, max_pos)?;
             // Position is updated by refresh_positions() - Arduino is source of truth
-            messages.push(format!("X Away complete - position set to max: {}, verified at away", x_max_pos));
+            messages.push(format!("{} Away complete - position set to max: {}, verified at away", axis.name, max_pos));
         } else {
             // Never reached away - check if Arduino position is already at max
-            if final_pos >= x_max_pos {
-                messages.push(format!("X Away failed - never reached away and Arduino position is already at max ({})", final_pos));
-                messages.push("Disabling X stepper due to away failure".to_string());
-                self.set_stepper_enabled(x_step_index, false);
-                stepper_ops.disable(x_step_index)?;
+            if final_pos >= max_pos {
+                messages.push(format!("{} Away failed - never reached away and Arduino position is already at max ({})", axis.name, final_pos));
+                messages.push(format!("Disabling {} stepper due to away failure", axis.name));
+                self.apply_stepper_enabled(stepper_ops, step_index, false)?;
+                self.report_disabled(step_index, &format!("{} disabled: {} away never reached away", self.stepper_label(step_index), axis.name));
             } else {
-                messages.push(format!("X Away failed - never reached away, position: {}", final_pos));
+                messages.push(format!("{} Away failed - never reached away, position: {}", axis.name, final_pos));
             }
         }
         
         Ok(messages.join("\n"))
     }
     
-    /// X Calibrate operation: stores current position, moves to closer of home/away, then returns to stored position
-    pub fn x_calibrate<T: StepperOperations>(
+    /// Axis Calibrate operation: stores current position, moves to closer of home/away, then returns to stored position.
+    /// Damped when DAMPERS lists `<axis>_calibrate` (e.g. x_calibrate) under DURING.
+    pub fn axis_calibrate<T: StepperOperations>(
+        &self,
+        axis_name: &str,
+        stepper_ops: &mut T,
+        positions: &mut [i32],
+        exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
+        socket_path: Option<&str>,
+    ) -> Result<String> {
+        let operation = format!("{}_calibrate", axis_name.to_lowercase());
+        self.with_dampers(&operation, stepper_ops, |stepper_ops| {
+            self.axis_calibrate_pass(axis_name, stepper_ops, positions, exit_flag, socket_path)
+        })
+    }
+
+    fn axis_calibrate_pass<T: StepperOperations>(
         &self,
+        axis_name: &str,
         stepper_ops: &mut T,
         positions: &mut [i32],
         exit_flag: Option<&Arc<std::sync::atomic::AtomicBool>>,
         socket_path: Option<&str>,
     ) -> Result<String> {
-        let x_step_index = self.x_step_index.ok_or_else(|| anyhow!("X stepper not configured"))?;
+        let _span = tracing::info_span!("axis_calibrate", axis = axis_name).entered();
+        let axis = self.linear_axis(axis_name)?;
+        let step_index = axis.step_index;
         
-        // Check if this is a dummy X stepper (X_MAX_POS == 0)
-        if self.x_max_pos == Some(0) {
-            return Ok("X stepper is dummy (X_MAX_POS=0) - calibration skipped".to_string());
+        // Check if this is a dummy stepper (max position 0)
+        if axis.max_pos == Some(0) {
+            return Ok(format!("{} stepper is dummy ({}=0) - calibration skipped", axis.name, axis.max_pos_key()));
         }
         
-        let gpio = self.gpio.as_ref().ok_or_else(|| anyhow!("GPIO not initialized"))?;
+        let gpio = self.gpio.as_ref().ok_or_else(|| Error::GpioUnavailable("GPIO not initialized".to_string()))?;
         if !gpio.exist {
-            return Ok("GPIO not available - cannot calibrate X".to_string());
+            return Ok(format!("GPIO not available - cannot calibrate {}", axis.name));
         }
         
-        let x_max_pos = self.x_max_pos.ok_or_else(|| anyhow!("X_MAX_POS not configured"))?;
-        if x_max_pos <= 0 {
-            return Ok("X_MAX_POS is invalid (must be > 0) - calibration skipped".to_string());
+        let max_pos = axis.max_pos.ok_or_else(|| Error::ConfigMissing(format!("{} not configured", axis.max_pos_key())))?;
+        if max_pos <= 0 {
+            return Ok(format!("{} is invalid (must be > 0) - calibration skipped", axis.max_pos_key()));
         }
         
         let mut messages = Vec::new();
-        messages.push("Starting X Calibration...".to_string());
+        messages.push(format!("Starting {} Calibration...", axis.name));
         
-        // Step 1: Store current X position - Arduino is source of truth
-        let stored_x_pos = positions.get(x_step_index).copied().ok_or_else(|| anyhow!("Failed to read X position from Arduino"))?;
-        messages.push(format!("Stored current X position: {}", stored_x_pos));
+        // Step 1: Store current position - Arduino is source of truth
+        let stored_pos = positions.get(step_index).copied()
+            .ok_or_else(|| Error::Other(format!("Failed to read {} position from Arduino", axis.name)))?;
+        messages.push(format!("Stored current {} position: {}", axis.name, stored_pos));
         
-        // Step 2: Determine which is closer - home (0) or away (x_max_pos)
-        let distance_to_home = stored_x_pos.abs();
-        let distance_to_away = (x_max_pos - stored_x_pos).abs();
+        // Step 2: Determine which is closer - home (0) or away (max_pos)
+        let distance_to_home = stored_pos.abs();
+        let distance_to_away = (max_pos - stored_pos).abs();
         
         let use_home = distance_to_home <= distance_to_away;
         messages.push(format!("Distance to home: {}, distance to away: {}, choosing {}", 
@@ -2126,11 +5582,11 @@ impl Operations {
         // Step 3: Move to the closer limit
         if use_home {
             messages.push("Step 3: Moving to home position...".to_string());
-            let home_msg = self.x_home(stepper_ops, positions, exit_flag, socket_path)?;
+            let home_msg = self.axis_home(axis_name, stepper_ops, positions, exit_flag, socket_path)?;
             messages.push(home_msg);
         } else {
             messages.push("Step 3: Moving to away position...".to_string());
-            let away_msg = self.x_away(stepper_ops, positions, exit_flag, socket_path)?;
+            let away_msg = self.axis_away(axis_name, stepper_ops, positions, exit_flag, socket_path)?;
             messages.push(away_msg);
         }
         
@@ -2143,14 +5599,55 @@ impl Operations {
         }
         
         // Step 4: Move back to stored position using absolute move
-        messages.push(format!("Step 4: Moving back to stored position {}...", stored_x_pos));
-        stepper_ops.abs_move(x_step_index, stored_x_pos)?;
-        // Wait for physical movement to complete using x_rest
-        self.rest_x();
+        messages.push(format!("Step 4: Moving back to stored position {}...", stored_pos));
+        let limit_pos = if use_home { 0 } else { max_pos };
+        self.clear_axis_path(axis, stepper_ops, positions, limit_pos, stored_pos, &mut messages)?;
+        stepper_ops.abs_move(step_index, stored_pos)?;
+        // Wait for physical movement to complete using the axis rest
+        self.rest_axis(axis);
         // Position is updated by refresh_positions() - Arduino is source of truth
-        messages.push(format!("X Calibration complete - returned to stored position {}", stored_x_pos));
+        messages.push(format!("{} Calibration complete - returned to stored position {}", axis.name, stored_pos));
         
         Ok(messages.join("\n"))
     }
 }
 
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_z_step_keeps_the_sign_and_stays_within_base_and_max() {
+        // At the band edge: the base step
+        assert_eq!(scale_z_step(10, 0.0, 50), 10);
+        assert_eq!(scale_z_step(-10, 0.0, 50), -10);
+        // Grows with the error, in the direction of the base step
+        assert_eq!(scale_z_step(10, 1.5, 50), 25);
+        assert_eq!(scale_z_step(-10, 1.5, 50), -25);
+        // Clamped at max_step
+        assert_eq!(scale_z_step(10, 10.0, 50), 50);
+        assert_eq!(scale_z_step(-10, 10.0, 50), -50);
+        // Never below the base step: a negative ratio or a max_step under it
+        assert_eq!(scale_z_step(10, -0.5, 50), 10);
+        assert_eq!(scale_z_step(-10, 2.0, 5), -10);
+        // No step stays no step
+        assert_eq!(scale_z_step(0, 3.0, 50), 0);
+    }
+
+    #[test]
+    fn test_band_error_ratio_is_zero_inside_and_normalised_outside() {
+        assert_eq!(band_error_ratio(50.0, 20.0, 250.0), 0.0);
+        assert_eq!(band_error_ratio(20.0, 20.0, 250.0), 0.0);
+        assert_eq!(band_error_ratio(250.0, 20.0, 250.0), 0.0);
+        // Positive on both sides of the band
+        assert_eq!(band_error_ratio(480.0, 20.0, 250.0), 1.0);
+        assert_eq!(band_error_ratio(-210.0, 20.0, 250.0), 1.0);
+        // A zero-width (or inverted) band counts as one unit wide
+        assert_eq!(band_error_ratio(12.0, 10.0, 10.0), 2.0);
+        assert_eq!(band_error_ratio(8.0, 10.0, 10.0), 2.0);
+        assert_eq!(band_error_ratio(10.0, 10.0, 10.0), 0.0);
+        assert_eq!(band_error_ratio(5.0, 10.0, 4.0), 1.0);
+    }
+}
@@ -0,0 +1,226 @@
+/// Triple-buffered partials frames between the shared-memory reader and the analysis
+///
+/// The partials reader thread fills one of three fixed-size frames (up to
+/// MAX_CHANNELS x MAX_PARTIALS (freq, amp) pairs, allocated once) and publishes
+/// it; Operations takes the newest published frame and analyses it in place.
+/// Writer and reader each own one frame and swap through the third, so neither
+/// waits for the other and nothing is allocated or cloned per frame. Every
+/// publish bumps the frame's generation, so the reader can tell a new frame from
+/// the one it already analysed.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+pub const MAX_CHANNELS: usize = 32;
+pub const MAX_PARTIALS: usize = 64;
+
+/// Set in `Shared::middle` when the middle frame was published after the reader's last swap
+const FRESH: usize = 0b100;
+const INDEX: usize = 0b011;
+
+/// One frame of partials, channel-major, in storage sized for the largest frame
+#[derive(Debug, Clone)]
+pub struct PartialsFrame {
+    data: Box<[(f32, f32)]>,
+    channels: usize,
+    partials: usize, // Per channel
+    generation: u64, // 0 until first published
+}
+
+impl Default for PartialsFrame {
+    fn default() -> Self {
+        Self { data: vec![(0.0, 0.0); MAX_CHANNELS * MAX_PARTIALS].into_boxed_slice(), channels: 0, partials: 0, generation: 0 }
+    }
+}
+
+impl PartialsFrame {
+    /// Set the shape before filling; anything past MAX_CHANNELS / MAX_PARTIALS is
+    /// dropped. Returns the (channels, partials per channel) kept.
+    pub fn reshape(&mut self, channels: usize, partials: usize) -> (usize, usize) {
+        self.partials = partials.min(MAX_PARTIALS);
+        self.channels = if self.partials == 0 { 0 } else { channels.min(MAX_CHANNELS) };
+        (self.channels, self.partials)
+    }
+
+    pub fn num_channels(&self) -> usize {
+        self.channels
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.channels == 0
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn channel(&self, ch: usize) -> &[(f32, f32)] {
+        &self.data[ch * self.partials..(ch + 1) * self.partials]
+    }
+
+    pub fn channel_mut(&mut self, ch: usize) -> &mut [(f32, f32)] {
+        &mut self.data[ch * self.partials..(ch + 1) * self.partials]
+    }
+
+    pub fn channels(&self) -> impl Iterator<Item = &[(f32, f32)]> {
+        (0..self.channels).map(move |ch| self.channel(ch))
+    }
+
+    /// Same shape and values (generation aside)
+    pub fn same_data(&self, other: &PartialsFrame) -> bool {
+        let used = self.channels * self.partials;
+        self.channels == other.channels && self.partials == other.partials && self.data[..used] == other.data[..used]
+    }
+
+    /// Copy another frame into this one's storage
+    pub fn copy_from(&mut self, other: &PartialsFrame) {
+        let used = other.channels * other.partials;
+        self.data[..used].copy_from_slice(&other.data[..used]);
+        self.channels = other.channels;
+        self.partials = other.partials;
+        self.generation = other.generation;
+    }
+
+    /// Owned copy for consumers that need one (e.g. sending to the synth)
+    pub fn to_vec(&self) -> Vec<Vec<(f32, f32)>> {
+        self.channels().map(|c| c.to_vec()).collect()
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    frames: [Mutex<PartialsFrame>; 3],
+    middle: AtomicUsize, // Index of the frame between writer and reader, | FRESH once published
+}
+
+impl Shared {
+    /// Frames are only ever touched by their current owner, so the lock is never contended
+    fn frame(&self, index: usize) -> MutexGuard<'_, PartialsFrame> {
+        self.frames[index].lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The reader thread's end
+#[derive(Debug)]
+pub struct PartialsWriter {
+    shared: Arc<Shared>,
+    back: usize,
+    generation: u64,
+}
+
+/// The analysis end
+#[derive(Debug)]
+pub struct PartialsReader {
+    shared: Arc<Shared>,
+    front: usize,
+    seen: u64, // Generation last handed out by take_new
+}
+
+pub fn triple_buffer() -> (PartialsWriter, PartialsReader) {
+    let shared = Arc::new(Shared {
+        frames: Default::default(),
+        middle: AtomicUsize::new(1),
+    });
+    (
+        PartialsWriter { shared: Arc::clone(&shared), back: 0, generation: 0 },
+        PartialsReader { shared, front: 2, seen: 0 },
+    )
+}
+
+impl PartialsWriter {
+    /// Fill the back frame with `fill` and publish it when `fill` returns true
+    pub fn write(&mut self, fill: impl FnOnce(&mut PartialsFrame) -> bool) -> bool {
+        {
+            let mut frame = self.shared.frame(self.back);
+            if !fill(&mut frame) {
+                return false;
+            }
+            self.generation += 1;
+            frame.generation = self.generation;
+        }
+        let previous = self.shared.middle.swap(self.back | FRESH, Ordering::AcqRel);
+        self.back = previous & INDEX;
+        true
+    }
+}
+
+impl PartialsReader {
+    /// The newest published frame (None before the first publish)
+    pub fn latest(&mut self) -> Option<MutexGuard<'_, PartialsFrame>> {
+        self.swap_in_published();
+        let frame = self.shared.frame(self.front);
+        (frame.generation > 0).then_some(frame)
+    }
+
+    /// The newest frame if it was published since the last call, else None
+    pub fn take_new(&mut self) -> Option<MutexGuard<'_, PartialsFrame>> {
+        self.swap_in_published();
+        let frame = self.shared.frame(self.front);
+        if frame.generation <= self.seen {
+            return None;
+        }
+        self.seen = frame.generation;
+        Some(frame)
+    }
+
+    /// Trade the front frame for the middle one if the writer published since
+    fn swap_in_published(&mut self) {
+        if self.shared.middle.load(Ordering::Acquire) & FRESH != 0 {
+            let previous = self.shared.middle.swap(self.front, Ordering::AcqRel);
+            self.front = previous & INDEX;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reader_sees_newest_frame_without_tearing() {
+        let (mut writer, mut reader) = triple_buffer();
+        assert!(reader.latest().is_none());
+
+        let fill = |value: f32| {
+            move |frame: &mut PartialsFrame| {
+                frame.reshape(2, 3);
+                for ch in 0..2 {
+                    frame.channel_mut(ch).fill((ch as f32, value));
+                }
+                true
+            }
+        };
+        writer.write(fill(1.0));
+        writer.write(fill(2.0));
+        {
+            let frame = reader.take_new().unwrap();
+            assert_eq!(frame.generation(), 2);
+            assert_eq!(frame.to_vec(), vec![vec![(0.0, 2.0); 3], vec![(1.0, 2.0); 3]]);
+        }
+        assert!(reader.take_new().is_none());
+        assert!(!writer.write(|_| false));
+        assert_eq!(reader.latest().unwrap().generation(), 2);
+
+        // Oversized frames are cut to the fixed storage
+        let mut big = PartialsFrame::default();
+        assert_eq!(big.reshape(MAX_CHANNELS + 5, MAX_PARTIALS * 2), (MAX_CHANNELS, MAX_PARTIALS));
+
+        // Concurrent writes: every frame read is whole and generations only go up
+        let writer_thread = std::thread::spawn(move || {
+            for i in 3..2000 {
+                writer.write(fill(i as f32));
+            }
+        });
+        let mut last = 0;
+        while !writer_thread.is_finished() {
+            if let Some(frame) = reader.take_new() {
+                let value = frame.channel(0)[0].1;
+                assert!(frame.channels().flatten().all(|p| p.1 == value));
+                assert!(frame.generation() > last);
+                last = frame.generation();
+            }
+        }
+        writer_thread.join().unwrap();
+        assert_eq!(reader.latest().unwrap().channel(1)[2], (1.0, 1999.0));
+    }
+}
@@ -0,0 +1,417 @@
+/// Custom operations: the `Operation` trait, its registry and the operation descriptors
+///
+/// A new algorithm does not need a patch to operations.rs: implement `Operation`
+/// (a descriptor plus `run`) and `register` it. Registered operations join the
+/// built-ins in `operation_catalog`, so they show up in operations_gui's menu and
+/// parameter form, `operations_gui --help` / `--list-operations` and the inbox,
+/// and run on the operation worker like any other (state guards, time budget,
+/// BREAK). `run` drives the instrument through `OperationContext`: moves, audio
+/// analysis, bump checks, rests and parameters, nothing that bypasses the limits.
+///
+/// With the `plugins` feature the GUIs also load the shared libraries listed under
+/// PLUGINS in string_driver.yaml; a library exports its operations with
+/// `export_operations!`. Rust has no stable ABI, so a plugin must be built with the
+/// same compiler and the same stringdriver version as the GUI that loads it.
+///
+/// The GUI binaries reach this module through the library (`use stringdriver::plugins`),
+/// not a `#[path]` copy, so a plugin and the GUI agree on every type here.
+
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Moves, reads and checks failing inside a custom operation; the message ends up in the log
+pub type PluginResult<T> = std::result::Result<T, String>;
+
+/// Names that are not operations but mean something to the inbox or the menu
+const RESERVED_NAMES: &[&str] = &["None", "clear_fault", "list_operations"];
+
+/// Symbol `export_operations!` defines in a plugin library
+#[cfg(feature = "plugins")]
+const EXPORT_SYMBOL: &[u8] = b"stringdriver_operations";
+
+static REGISTRY: RwLock<Vec<Arc<dyn Operation>>> = RwLock::new(Vec::new());
+/// Values set in the GUI for parameters that are not PRESETS keys
+static PARAMS: RwLock<Option<HashMap<String, f64>>> = RwLock::new(None);
+
+/// Hardware an operation cannot do its job without
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Requirement {
+    Gpio,  // Touch sensors and limit switches (GPIO_ENABLED)
+    XAxis, // An X stepper with a travel (X_STEP_INDEX, X_MAX_POS)
+}
+
+impl Requirement {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Requirement::Gpio => "GPIO",
+            Requirement::XAxis => "X axis",
+        }
+    }
+}
+
+/// A parameter an operation reads: a PRESETS key (see config_loader::ParameterPreset),
+/// or for a custom operation also a key of its own
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct OperationParam {
+    pub key: &'static str,
+    pub description: &'static str,
+    pub min: f64,
+    pub max: f64,
+    pub default: Option<f64>, // Without the key in string_driver.yaml; None = derived (X_FINISH)
+    pub integer: bool,
+}
+
+impl OperationParam {
+    pub const fn new(key: &'static str, description: &'static str, min: f64, max: f64, default: f64, integer: bool) -> Self {
+        Self { key, description, min, max, default: Some(default), integer }
+    }
+}
+
+/// Machine-readable description of an operation: drives operations_gui's
+/// operation menu and parameter form, `operations_gui --help` and
+/// `--list-operations`, and the inbox's `list_operations`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OperationDescriptor {
+    pub name: Cow<'static, str>,
+    pub label: Cow<'static, str>,
+    pub description: &'static str,
+    pub requires: &'static [Requirement],
+    pub params: &'static [OperationParam],
+    pub performer: bool,        // Also allowed in performer mode
+    pub runs_until_break: bool, // Never finishes on its own (BREAK stops it)
+    pub broadcast: bool,        // Offered for running on every instrument in turn
+    pub read_only: bool,        // Reads sensors only: runs beside other operations (see arbitration)
+}
+
+impl OperationDescriptor {
+    pub const fn new(
+        name: &'static str,
+        label: &'static str,
+        description: &'static str,
+        requires: &'static [Requirement],
+        params: &'static [OperationParam],
+    ) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            label: Cow::Borrowed(label),
+            description,
+            requires,
+            params,
+            performer: false,
+            runs_until_break: false,
+            broadcast: false,
+            read_only: false,
+        }
+    }
+
+    pub const fn performer(mut self) -> Self {
+        self.performer = true;
+        self
+    }
+
+    pub const fn until_break(mut self) -> Self {
+        self.runs_until_break = true;
+        self
+    }
+
+    pub const fn broadcast(mut self) -> Self {
+        self.broadcast = true;
+        self
+    }
+
+    pub const fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+}
+
+/// What an operation hands back; its lines become the message in the log
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    pub lines: Vec<String>,
+}
+
+impl Report {
+    pub fn line(&mut self, line: impl Into<String>) {
+        self.lines.push(line.into());
+    }
+
+    pub fn message(&self) -> String {
+        self.lines.join("\n")
+    }
+}
+
+impl From<String> for Report {
+    fn from(line: String) -> Self {
+        Self { lines: vec![line] }
+    }
+}
+
+/// The instrument as a custom operation sees it; Operations provides it for each run
+pub trait OperationHost {
+    /// Known position of every stepper, by stepper index
+    fn positions(&self) -> Vec<i32>;
+    fn x_index(&self) -> Option<usize>;
+    /// Z pair of the string on an audio channel (CHANNEL_MAP)
+    fn z_pair_for_channel(&self, channel: usize) -> Option<(usize, usize)>;
+    /// Rests Z_REST / X_REST after the move like the built-ins; refused past the stepper's max position
+    fn rel_move(&mut self, stepper: usize, delta: i32) -> PluginResult<()>;
+    fn abs_move(&mut self, stepper: usize, position: i32) -> PluginResult<()>;
+    /// Amplitude sum and voice count per channel from the latest analysis
+    fn amp_sum(&self) -> Vec<f32>;
+    fn voice_count(&self) -> Vec<usize>;
+    /// Raise any Z stepper whose touch sensor is pressed
+    fn bump_check(&mut self) -> PluginResult<String>;
+    /// Wait another Z_REST / X_REST (the running operation's overrides apply)
+    fn rest_z(&self);
+    fn rest_x(&self);
+    /// Current value of a PRESETS key, e.g. Z_UP_STEP
+    fn parameter(&self, key: &str) -> Option<f64>;
+    /// BREAK was pressed or the time budget ran out
+    fn stop_requested(&self) -> bool;
+}
+
+/// Handed to `Operation::run`: the host plus the operation's own parameter values
+pub struct OperationContext<'a> {
+    host: &'a mut dyn OperationHost,
+    descriptor: OperationDescriptor,
+}
+
+impl<'a> OperationContext<'a> {
+    pub fn new(host: &'a mut dyn OperationHost, descriptor: OperationDescriptor) -> Self {
+        Self { host, descriptor }
+    }
+
+    /// A parameter from the descriptor: the value set in the GUI, else the host's
+    /// setting for a PRESETS key, else the descriptor's default
+    pub fn param(&self, key: &str) -> Option<f64> {
+        param_value(key)
+            .or_else(|| self.host.parameter(key))
+            .or_else(|| self.descriptor.params.iter().find(|p| p.key == key).and_then(|p| p.default))
+    }
+
+    pub fn positions(&self) -> Vec<i32> {
+        self.host.positions()
+    }
+
+    pub fn x_index(&self) -> Option<usize> {
+        self.host.x_index()
+    }
+
+    pub fn z_pair_for_channel(&self, channel: usize) -> Option<(usize, usize)> {
+        self.host.z_pair_for_channel(channel)
+    }
+
+    pub fn rel_move(&mut self, stepper: usize, delta: i32) -> PluginResult<()> {
+        self.host.rel_move(stepper, delta)
+    }
+
+    pub fn abs_move(&mut self, stepper: usize, position: i32) -> PluginResult<()> {
+        self.host.abs_move(stepper, position)
+    }
+
+    pub fn amp_sum(&self) -> Vec<f32> {
+        self.host.amp_sum()
+    }
+
+    pub fn voice_count(&self) -> Vec<usize> {
+        self.host.voice_count()
+    }
+
+    pub fn bump_check(&mut self) -> PluginResult<String> {
+        self.host.bump_check()
+    }
+
+    pub fn rest_z(&self) {
+        self.host.rest_z()
+    }
+
+    pub fn rest_x(&self) {
+        self.host.rest_x()
+    }
+
+    pub fn stop_requested(&self) -> bool {
+        self.host.stop_requested()
+    }
+}
+
+/// A custom operation
+pub trait Operation: Send + Sync {
+    fn descriptor(&self) -> OperationDescriptor;
+    /// Run to completion; return early (with what was done) once `stop_requested`
+    fn run(&self, ctx: &mut OperationContext<'_>) -> PluginResult<Report>;
+}
+
+/// Add `operation` to the registry; its name must not be taken
+pub fn register(operation: Arc<dyn Operation>) -> PluginResult<()> {
+    let name = operation.descriptor().name;
+    let built_in = crate::operations::OPERATIONS.iter().any(|op| op.name == name);
+    let mut registry = REGISTRY.write().unwrap_or_else(|e| e.into_inner());
+    if built_in || RESERVED_NAMES.contains(&name.as_ref()) || registry.iter().any(|op| op.descriptor().name == name) {
+        return Err(format!("operation '{}' already exists", name));
+    }
+    tracing::info!("Registered custom operation '{}'", name);
+    registry.push(operation);
+    Ok(())
+}
+
+/// Every registered operation, in registration order
+pub fn registered() -> Vec<Arc<dyn Operation>> {
+    REGISTRY.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn find(name: &str) -> Option<Arc<dyn Operation>> {
+    registered().into_iter().find(|op| op.descriptor().name == name)
+}
+
+/// Value set for a custom operation's own parameter
+pub fn param_value(key: &str) -> Option<f64> {
+    PARAMS.read().ok()?.as_ref()?.get(key).copied()
+}
+
+pub fn set_param_value(key: &str, value: f64) {
+    let mut params = PARAMS.write().unwrap_or_else(|e| e.into_inner());
+    params.get_or_insert_with(HashMap::new).insert(key.to_string(), value);
+}
+
+/// Define the symbol a plugin library exports its operations under:
+/// `stringdriver::export_operations!(MyScan, MyTuner::default());`
+#[macro_export]
+macro_rules! export_operations {
+    ($($operation:expr),* $(,)?) => {
+        #[no_mangle]
+        pub fn stringdriver_operations() -> Vec<std::sync::Arc<dyn $crate::plugins::Operation>> {
+            vec![$(std::sync::Arc::new($operation)),*]
+        }
+    };
+}
+
+/// Load a plugin library and register its operations; returns their names
+#[cfg(feature = "plugins")]
+pub fn load_library(path: &std::path::Path) -> PluginResult<Vec<String>> {
+    type Export = fn() -> Vec<Arc<dyn Operation>>;
+    // SAFETY: the library is trusted configuration (PLUGINS) built against this crate
+    let library = unsafe { libloading::Library::new(path) }.map_err(|e| format!("cannot load plugin {}: {}", path.display(), e))?;
+    let operations = {
+        // SAFETY: export_operations! defines the symbol with exactly this signature
+        let export = unsafe { library.get::<Export>(EXPORT_SYMBOL) }
+            .map_err(|e| format!("{} exports no operations (export_operations!): {}", path.display(), e))?;
+        export()
+    };
+    // The operations' code lives in the library: it stays loaded for the life of the process
+    std::mem::forget(library);
+    let mut names = Vec::new();
+    for operation in operations {
+        let name = operation.descriptor().name.to_string();
+        register(operation)?;
+        names.push(name);
+    }
+    Ok(names)
+}
+
+/// Load every PLUGINS library, reporting each outcome as a line for the log
+#[cfg(feature = "plugins")]
+pub fn load_libraries(paths: &[std::path::PathBuf]) -> Vec<String> {
+    paths
+        .iter()
+        .map(|path| match load_library(path) {
+            Ok(names) => format!("Plugin {}: {}", path.display(), names.join(", ")),
+            Err(e) => format!("ERROR: {}", e),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Nudge;
+
+    const NUDGE_PARAMS: &[OperationParam] = &[OperationParam::new("NUDGE_STEPS", "Steps up and back down", 1.0, 50.0, 4.0, true)];
+
+    impl Operation for Nudge {
+        fn descriptor(&self) -> OperationDescriptor {
+            OperationDescriptor::new("test_nudge", "Nudge", "Lift every Z stepper and put it back", &[], NUDGE_PARAMS)
+        }
+
+        fn run(&self, ctx: &mut OperationContext<'_>) -> PluginResult<Report> {
+            let steps = ctx.param("NUDGE_STEPS").unwrap_or(1.0) as i32;
+            ctx.rel_move(1, steps)?;
+            ctx.rel_move(1, -steps)?;
+            Ok(Report::from(format!("nudged by {}", steps)))
+        }
+    }
+
+    struct Host {
+        moves: Vec<(usize, i32)>,
+    }
+
+    impl OperationHost for Host {
+        fn positions(&self) -> Vec<i32> {
+            vec![0; 3]
+        }
+        fn x_index(&self) -> Option<usize> {
+            Some(0)
+        }
+        fn z_pair_for_channel(&self, _channel: usize) -> Option<(usize, usize)> {
+            Some((1, 2))
+        }
+        fn rel_move(&mut self, stepper: usize, delta: i32) -> PluginResult<()> {
+            self.moves.push((stepper, delta));
+            Ok(())
+        }
+        fn abs_move(&mut self, _stepper: usize, _position: i32) -> PluginResult<()> {
+            Err("not here".to_string())
+        }
+        fn amp_sum(&self) -> Vec<f32> {
+            Vec::new()
+        }
+        fn voice_count(&self) -> Vec<usize> {
+            Vec::new()
+        }
+        fn bump_check(&mut self) -> PluginResult<String> {
+            Ok(String::new())
+        }
+        fn rest_z(&self) {}
+        fn rest_x(&self) {}
+        fn parameter(&self, _key: &str) -> Option<f64> {
+            None
+        }
+        fn stop_requested(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_registered_operations_run_with_their_params() {
+        register(Arc::new(Nudge)).unwrap();
+        assert!(register(Arc::new(Nudge)).is_err());
+        let nudge = find("test_nudge").unwrap();
+        assert!(crate::operations::operation_catalog(&["X"]).iter().any(|op| op.name == "test_nudge"));
+
+        let mut host = Host { moves: Vec::new() };
+        let report = nudge.run(&mut OperationContext::new(&mut host, nudge.descriptor())).unwrap();
+        assert_eq!(report.message(), "nudged by 4");
+        set_param_value("NUDGE_STEPS", 7.0);
+        nudge.run(&mut OperationContext::new(&mut host, nudge.descriptor())).unwrap();
+        assert_eq!(host.moves, [(1, 4), (1, -4), (1, 7), (1, -7)]);
+    }
+
+    #[test]
+    fn test_built_in_and_reserved_names_are_refused() {
+        struct Named(&'static str);
+        impl Operation for Named {
+            fn descriptor(&self) -> OperationDescriptor {
+                OperationDescriptor::new(self.0, self.0, "", &[], &[])
+            }
+            fn run(&self, _ctx: &mut OperationContext<'_>) -> PluginResult<Report> {
+                Ok(Report::default())
+            }
+        }
+        assert!(register(Arc::new(Named("z_adjust"))).is_err());
+        assert!(register(Arc::new(Named("list_operations"))).is_err());
+    }
+}
@@ -0,0 +1,59 @@
+/// Persisted position model - last known stepper positions across restarts
+///
+/// stepper_gui saves the positions each board reports and, on the next start,
+/// compares the board's first report with them. A board that lost power comes
+/// back reporting zeros while the steppers are still where they were, so any
+/// stepper that disagrees is listed and its axis flagged for re-homing.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub const MODEL_FILE_NAME: &str = "last_positions.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PositionModel {
+    pub main: Vec<i32>,
+    #[serde(default)]
+    pub tuner: Vec<i32>, // Separate tuner board, empty without one
+    pub saved_at: String,
+}
+
+/// A stepper whose reported position differs from the model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Discrepancy {
+    pub stepper: usize,
+    pub expected: i32,
+    pub reported: i32,
+}
+
+/// Read the model; None when there is none yet or it cannot be used
+pub fn load(path: &Path) -> Option<PositionModel> {
+    let json = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&json) {
+        Ok(model) => Some(model),
+        Err(e) => {
+            tracing::warn!("Ignoring unreadable position model {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Write the model through a temporary file so a crash never leaves half a file
+pub fn save(path: &Path, model: &PositionModel) -> std::io::Result<()> {
+    let json = serde_json::to_string(model).map_err(std::io::Error::other)?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Steppers more than `tolerance` steps away from the model. Steppers the model
+/// does not cover (e.g. after adding one) are not reported.
+pub fn compare(expected: &[i32], reported: &[i32], tolerance: i32) -> Vec<Discrepancy> {
+    expected
+        .iter()
+        .zip(reported)
+        .enumerate()
+        .filter(|(_, (e, r))| (*e - *r).abs() > tolerance)
+        .map(|(stepper, (e, r))| Discrepancy { stepper, expected: *e, reported: *r })
+        .collect()
+}
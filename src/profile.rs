@@ -0,0 +1,180 @@
+/// Instrument profile bundle - move a tuned setup between hosts
+///
+/// A profile is one versioned JSON file holding a host's string_driver.yaml
+/// section, the operations_gui thresholds in effect when it was exported, the
+/// host's scenes (its saved positions) and its calibration history: the
+/// height_calibrate curves and the response_map sweeps. The section is exported
+/// with what the host inherits from DEFAULTS and EXTENDS, so it stands on its own
+/// on another machine; the motion profiles (MOTION_PARAMS, AXES speeds and
+/// accelerations) and PRESETS travel inside it. Importing installs the YAML
+/// section under this machine's hostname (keeping a backup of string_driver.yaml),
+/// restores the files where this host keeps them and hands the thresholds back to
+/// the GUI to apply.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use crate::config_loader::{
+    install_host_section, load_height_calibration_settings, load_host_section, load_response_map_settings, load_scene_dir,
+};
+
+/// Bumped whenever the layout changes incompatibly
+pub const PROFILE_VERSION: u32 = 1;
+
+/// Per-channel z_adjust thresholds as set in operations_gui
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileThresholds {
+    pub voice_count_min: Vec<i32>,
+    pub voice_count_max: Vec<i32>,
+    pub amp_sum_min: Vec<i32>,
+    pub amp_sum_max: Vec<i32>,
+}
+
+/// A text file carried in the bundle (name only, no directories)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileFile {
+    pub name: String,
+    pub contents: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstrumentProfile {
+    pub version: u32,
+    pub source_host: String,
+    pub os_section: String, // RaspberryPi / Ubuntu / macOS
+    pub exported_at: String,
+    pub host_section: serde_yaml::Mapping,
+    #[serde(default)]
+    pub thresholds: Option<ProfileThresholds>,
+    #[serde(default)]
+    pub response_maps: Vec<ProfileFile>,
+    #[serde(default)]
+    pub scenes: Vec<ProfileFile>,
+    #[serde(default)]
+    pub height_map: Option<ProfileFile>,
+}
+
+/// The files in `dir` whose names `keep` accepts, sorted by name. A missing
+/// directory has none.
+fn collect_files(dir: &Path, keep: impl Fn(&str) -> bool) -> Result<Vec<ProfileFile>> {
+    let mut files = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if keep(&name) {
+                let contents = std::fs::read_to_string(entry.path())
+                    .map_err(|e| Error::io(format!("Cannot read {:?}", entry.path()), e))?;
+                files.push(ProfileFile { name, contents });
+            }
+        }
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(files)
+}
+
+/// Write bundled files into `dir`, leaving existing ones alone. Returns how many
+/// were written.
+fn restore_files(files: &[ProfileFile], dir: &Path) -> Result<usize> {
+    std::fs::create_dir_all(dir).map_err(|e| Error::io(format!("Failed to create {:?}", dir), e))?;
+    let mut restored = 0;
+    for file in files {
+        // Bundled names are plain file names; anything else is ignored
+        let Some(name) = Path::new(&file.name).file_name() else { continue };
+        let path = dir.join(name);
+        if path.exists() {
+            continue;
+        }
+        std::fs::write(&path, &file.contents).map_err(|e| Error::io(format!("Cannot write {:?}", path), e))?;
+        restored += 1;
+    }
+    Ok(restored)
+}
+
+/// Collect everything for `hostname` into a profile
+pub fn build_profile(hostname: &str, thresholds: Option<ProfileThresholds>) -> Result<InstrumentProfile> {
+    let (os_section, host_section) = load_host_section(hostname)?;
+
+    // response_map writes response_map_<host>_<timestamp>.csv into its output directory
+    let prefix = format!("response_map_{}_", hostname);
+    let response_maps = collect_files(&load_response_map_settings(hostname)?.output_dir, |name| {
+        name.starts_with(&prefix) && name.ends_with(".csv")
+    })?;
+    let scenes = collect_files(&load_scene_dir(hostname)?, |name| crate::scene::scene_name(Path::new(name)).is_some())?;
+    let height_map_file = load_height_calibration_settings(hostname)?.file;
+    let height_map = match std::fs::read_to_string(&height_map_file) {
+        Ok(contents) => {
+            let name = height_map_file.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            Some(ProfileFile { name, contents })
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(Error::io(format!("Cannot read {:?}", height_map_file), e)),
+    };
+
+    Ok(InstrumentProfile {
+        version: PROFILE_VERSION,
+        source_host: hostname.to_string(),
+        os_section,
+        exported_at: chrono::Local::now().to_rfc3339(),
+        host_section,
+        thresholds,
+        response_maps,
+        scenes,
+        height_map,
+    })
+}
+
+pub fn write_profile(profile: &InstrumentProfile, path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(profile).map_err(|e| Error::Other(format!("Cannot encode profile: {}", e)))?;
+    std::fs::write(path, json).map_err(|e| Error::io(format!("Cannot write profile {:?}", path), e))
+}
+
+/// Read a profile, refusing ones written by a newer version
+pub fn read_profile(path: &Path) -> Result<InstrumentProfile> {
+    let json = std::fs::read_to_string(path).map_err(|e| Error::io(format!("Cannot read profile {:?}", path), e))?;
+    let profile: InstrumentProfile = serde_json::from_str(&json)
+        .map_err(|e| Error::Other(format!("{:?} is not an instrument profile: {}", path, e)))?;
+    if profile.version > PROFILE_VERSION {
+        return Err(Error::Other(format!(
+            "Profile {:?} is version {}, this build understands up to {}",
+            path, profile.version, PROFILE_VERSION
+        )));
+    }
+    Ok(profile)
+}
+
+/// Install the profile's YAML section as `hostname` and restore its scenes,
+/// height map and sweep files. Existing files are left alone. Returns a summary
+/// for the message log; YAML changes take effect after a restart.
+pub fn import_profile(profile: &InstrumentProfile, hostname: &str) -> Result<String> {
+    let backup = install_host_section(&profile.os_section, hostname, &profile.host_section)?;
+    let mut messages = vec![format!(
+        "Installed {}'s string_driver.yaml section as '{}' (previous file saved to {})",
+        profile.source_host, hostname, backup.display()
+    )];
+
+    if !profile.response_maps.is_empty() {
+        let output_dir = load_response_map_settings(hostname)?.output_dir;
+        let restored = restore_files(&profile.response_maps, &output_dir)?;
+        messages.push(format!("Restored {} of {} response map(s) to {}", restored, profile.response_maps.len(), output_dir.display()));
+    }
+    if !profile.scenes.is_empty() {
+        let scene_dir = load_scene_dir(hostname)?;
+        let restored = restore_files(&profile.scenes, &scene_dir)?;
+        messages.push(format!("Restored {} of {} scene(s) to {}", restored, profile.scenes.len(), scene_dir.display()));
+    }
+    if let Some(height_map) = &profile.height_map {
+        // Saved under this host's HEIGHT_CALIBRATION.FILE, whatever it was called on the source
+        let file = load_height_calibration_settings(hostname)?.file;
+        if file.exists() {
+            messages.push(format!("Kept the existing height map {}", file.display()));
+        } else {
+            if let Some(dir) = file.parent().filter(|d| !d.as_os_str().is_empty()) {
+                std::fs::create_dir_all(dir).map_err(|e| Error::io(format!("Failed to create {:?}", dir), e))?;
+            }
+            std::fs::write(&file, &height_map.contents).map_err(|e| Error::io(format!("Cannot write {:?}", file), e))?;
+            messages.push(format!("Restored the height map to {}", file.display()));
+        }
+    }
+    messages.push("Restart the GUIs to load the imported settings".to_string());
+    Ok(messages.join("\n"))
+}
@@ -0,0 +1,299 @@
+/// Frame budget profiling
+///
+/// On a Raspberry Pi 3 the GUIs fall to about 10 fps while an operation runs, as
+/// serial round trips and the audio analysis still compete with the UI thread.
+/// Three probes time each UI frame (update, tick and render), each serial
+/// exchange on a board's serial thread and each analysis update, keeping the
+/// last WINDOW samples of each. A sample over its budget is logged (at most once
+/// per WARN_INTERVAL per probe, with the count since the last warning). F12 opens
+/// a diagnostics overlay with the figures in every GUI, so a threading change can
+/// be checked against the same numbers before and after.
+///
+/// One profiler per process: in master_gui the stepper and operations panels
+/// report into the same window.
+
+use crate::config_loader::RefreshRates;
+#[cfg(feature = "gui")]
+use crate::i18n::tr;
+#[cfg(feature = "gui")]
+use eframe::egui;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Samples kept per probe for the overlay's mean and max
+const WINDOW: usize = 120;
+/// At most one over-budget warning per probe this often
+const WARN_INTERVAL: Duration = Duration::from_secs(10);
+/// Input repaints at once, so a frame must stay interactive however low REPAINT_HZ is
+const FRAME_BUDGET_CAP: Duration = Duration::from_millis(33);
+/// A position query waits 50 ms before reading, then ~2 ms per stepper
+const SERIAL_BUDGET: Duration = Duration::from_millis(150);
+
+static PROFILER: Mutex<Profiler> = Mutex::new(Profiler::new(Budgets::DEFAULT));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Probe {
+    Frame,    // One GUI update: tick plus render
+    Serial,   // One command write or query round trip on a serial thread
+    Analysis, // One update_audio_analysis that found a new frame
+}
+
+impl Probe {
+    pub const ALL: [Probe; 3] = [Probe::Frame, Probe::Serial, Probe::Analysis];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Probe::Frame => "UI frame",
+            Probe::Serial => "Serial command",
+            Probe::Analysis => "Analysis update",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Budgets {
+    pub frame: Duration,
+    pub serial: Duration,
+    pub analysis: Duration,
+}
+
+impl Budgets {
+    /// 30 fps frames with a quarter of each left for the analysis
+    pub const DEFAULT: Self = Self {
+        frame: FRAME_BUDGET_CAP,
+        serial: SERIAL_BUDGET,
+        analysis: Duration::from_millis(8),
+    };
+
+    /// A frame gets the repaint interval (at most FRAME_BUDGET_CAP); the analysis runs
+    /// inside the frame's tick, so it gets a quarter of that
+    pub fn from_rates(rates: &RefreshRates) -> Self {
+        let frame = RefreshRates::interval(rates.repaint_hz).min(FRAME_BUDGET_CAP);
+        Self { frame, serial: SERIAL_BUDGET, analysis: frame / 4 }
+    }
+
+    pub fn get(&self, probe: Probe) -> Duration {
+        match probe {
+            Probe::Frame => self.frame,
+            Probe::Serial => self.serial,
+            Probe::Analysis => self.analysis,
+        }
+    }
+}
+
+/// One probe's figures over the current window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProbeStats {
+    pub probe: Probe,
+    pub budget: Duration,
+    pub samples: usize,
+    pub last: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+    pub over: usize, // Samples in the window over budget
+}
+
+impl ProbeStats {
+    pub fn over_budget(&self) -> bool {
+        self.samples > 0 && (self.mean > self.budget || self.last > self.budget)
+    }
+}
+
+#[derive(Debug)]
+struct ProbeWindow {
+    samples: VecDeque<Duration>,
+    last_warning: Option<Instant>,
+    unreported: usize, // Overruns since the last warning
+}
+
+impl ProbeWindow {
+    const fn new() -> Self {
+        Self { samples: VecDeque::new(), last_warning: None, unreported: 0 }
+    }
+}
+
+#[derive(Debug)]
+pub struct Profiler {
+    budgets: Budgets,
+    windows: [ProbeWindow; 3],
+    #[cfg(feature = "gui")]
+    overlay_open: bool,
+}
+
+impl Profiler {
+    pub const fn new(budgets: Budgets) -> Self {
+        Self {
+            budgets,
+            windows: [ProbeWindow::new(), ProbeWindow::new(), ProbeWindow::new()],
+            #[cfg(feature = "gui")]
+            overlay_open: false,
+        }
+    }
+
+    /// Add a sample; returns the warning to log when it is over budget and the
+    /// probe has not warned within WARN_INTERVAL
+    pub fn record(&mut self, probe: Probe, elapsed: Duration, now: Instant) -> Option<String> {
+        let budget = self.budgets.get(probe);
+        let window = &mut self.windows[probe.index()];
+        if window.samples.len() == WINDOW {
+            window.samples.pop_front();
+        }
+        window.samples.push_back(elapsed);
+        if elapsed <= budget {
+            return None;
+        }
+        window.unreported += 1;
+        if window.last_warning.is_some_and(|at| now.duration_since(at) < WARN_INTERVAL) {
+            return None;
+        }
+        let overruns = std::mem::take(&mut window.unreported);
+        window.last_warning = Some(now);
+        Some(format!(
+            "{} took {:.1} ms, over its {:.1} ms budget ({} overrun(s) since the last warning)",
+            probe.label(),
+            elapsed.as_secs_f64() * 1000.0,
+            budget.as_secs_f64() * 1000.0,
+            overruns
+        ))
+    }
+
+    pub fn stats(&self, probe: Probe) -> ProbeStats {
+        let budget = self.budgets.get(probe);
+        let samples = &self.windows[probe.index()].samples;
+        let total: Duration = samples.iter().sum();
+        ProbeStats {
+            probe,
+            budget,
+            samples: samples.len(),
+            last: samples.back().copied().unwrap_or_default(),
+            mean: if samples.is_empty() { Duration::ZERO } else { total / samples.len() as u32 },
+            max: samples.iter().max().copied().unwrap_or_default(),
+            over: samples.iter().filter(|s| **s > budget).count(),
+        }
+    }
+}
+
+fn profiler() -> std::sync::MutexGuard<'static, Profiler> {
+    PROFILER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Budgets for this process, normally from the host's REFRESH_RATES
+pub fn set_budgets(budgets: Budgets) {
+    profiler().budgets = budgets;
+}
+
+/// Add a timed sample for `probe`, logging a warning when it ran over budget
+pub fn record(probe: Probe, elapsed: Duration) {
+    let warning = profiler().record(probe, elapsed, Instant::now());
+    if let Some(warning) = warning {
+        tracing::warn!("{}", warning);
+    }
+}
+
+/// Run `f` and record how long it took
+pub fn time<T>(probe: Probe, f: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = f();
+    record(probe, started.elapsed());
+    result
+}
+
+pub fn snapshot() -> Vec<ProbeStats> {
+    let profiler = profiler();
+    Probe::ALL.iter().map(|probe| profiler.stats(*probe)).collect()
+}
+
+/// F12 toggles the diagnostics overlay; call once per frame from the top-level App
+#[cfg(feature = "gui")]
+pub fn show_overlay(ctx: &egui::Context) {
+    let open = {
+        let mut profiler = profiler();
+        if ctx.input(|i| i.key_pressed(egui::Key::F12)) {
+            profiler.overlay_open = !profiler.overlay_open;
+        }
+        profiler.overlay_open
+    };
+    if !open {
+        return;
+    }
+    let mut still_open = true;
+    egui::Window::new(tr("Diagnostics"))
+        .open(&mut still_open)
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -8.0))
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("profiling_overlay").striped(true).show(ui, |ui| {
+                ui.label("");
+                for header in ["last", "mean", "max", "budget", "over"] {
+                    ui.label(tr(header));
+                }
+                ui.end_row();
+                let ms = |d: Duration| format!("{:.1} ms", d.as_secs_f64() * 1000.0);
+                for stats in snapshot() {
+                    let color = if stats.samples == 0 {
+                        egui::Color32::GRAY
+                    } else if stats.over_budget() {
+                        egui::Color32::from_rgb(220, 40, 40)
+                    } else {
+                        egui::Color32::from_rgb(40, 170, 60)
+                    };
+                    ui.colored_label(color, tr(stats.probe.label()));
+                    ui.label(ms(stats.last));
+                    ui.label(ms(stats.mean));
+                    ui.label(ms(stats.max));
+                    ui.label(ms(stats.budget));
+                    ui.label(format!("{}/{}", stats.over, stats.samples));
+                    ui.end_row();
+                }
+            });
+            ui.small(tr("F12 hides this overlay"));
+        });
+    if !still_open {
+        profiler().overlay_open = false;
+    }
+    // Keep the figures moving while the overlay is up
+    ctx.request_repaint_after(Duration::from_millis(250));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budgets_follow_refresh_rates() {
+        let desktop = Budgets::from_rates(&RefreshRates::DESKTOP);
+        assert!(desktop.frame < FRAME_BUDGET_CAP);
+        assert_eq!(desktop.analysis, desktop.frame / 4);
+        // 10 Hz repaint on a Pi still has to keep input responsive
+        let pi = Budgets::from_rates(&RefreshRates::LOW_POWER);
+        assert_eq!(pi.frame, FRAME_BUDGET_CAP);
+        assert_eq!(pi.serial, SERIAL_BUDGET);
+    }
+
+    #[test]
+    fn test_overruns_warn_once_per_interval() {
+        let mut profiler = Profiler::new(Budgets::DEFAULT);
+        let start = Instant::now();
+        let slow = Duration::from_millis(100);
+        assert!(profiler.record(Probe::Frame, Duration::from_millis(5), start).is_none());
+        assert!(profiler.record(Probe::Frame, slow, start).is_some());
+        assert!(profiler.record(Probe::Frame, slow, start + Duration::from_secs(1)).is_none());
+        let warning = profiler.record(Probe::Frame, slow, start + WARN_INTERVAL + Duration::from_secs(1)).unwrap();
+        assert!(warning.contains("2 overrun(s)"), "{}", warning);
+
+        let stats = profiler.stats(Probe::Frame);
+        assert_eq!((stats.samples, stats.over, stats.max), (4, 3, slow));
+        assert!(stats.over_budget());
+        assert_eq!(profiler.stats(Probe::Serial).samples, 0);
+        for _ in 0..WINDOW {
+            profiler.record(Probe::Frame, Duration::from_millis(1), start);
+        }
+        assert_eq!(profiler.stats(Probe::Frame).over, 0);
+    }
+}
@@ -0,0 +1,100 @@
+/// Readiness checks - poll until another component is up
+///
+/// Shared by the launcher (waiting on the components it starts) and the GUIs
+/// (waiting on stepper_gui's socket). Polling starts fast and backs off so a
+/// long wait does not spin.
+
+use std::net::{SocketAddr, TcpStream};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long to wait and how often to look
+#[derive(Debug, Clone, Copy)]
+pub struct WaitOptions {
+    pub timeout: Duration,
+    pub initial_interval: Duration,
+    pub max_interval: Duration,
+    pub backoff: f32, // Interval multiplier after each unsuccessful poll
+}
+
+impl Default for WaitOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            initial_interval: Duration::from_millis(100),
+            max_interval: Duration::from_secs(1),
+            backoff: 1.5,
+        }
+    }
+}
+
+impl WaitOptions {
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self { timeout, ..Self::default() }
+    }
+}
+
+/// Poll `ready` until it returns true or the timeout passes.
+/// `on_wait` is called with the poll count before each sleep (e.g. to print progress).
+pub fn poll_until(options: WaitOptions, mut ready: impl FnMut() -> bool, mut on_wait: impl FnMut(u32)) -> bool {
+    let deadline = Instant::now() + options.timeout;
+    let mut interval = options.initial_interval;
+    let mut polls = 0u32;
+    loop {
+        if ready() {
+            return true;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return false;
+        }
+        polls += 1;
+        on_wait(polls);
+        thread::sleep(interval.min(deadline - now));
+        interval = interval.mul_f32(options.backoff.max(1.0)).min(options.max_interval);
+    }
+}
+
+pub fn wait_until(options: WaitOptions, ready: impl FnMut() -> bool) -> bool {
+    poll_until(options, ready, |_| {})
+}
+
+/// Unix socket file exists (the listener creates it once bound)
+pub fn socket_ready(path: &Path) -> bool {
+    path.exists()
+}
+
+/// Shared memory file exists with non-zero size (audio_monitor creates it at full size)
+pub fn shm_ready(path: &Path) -> bool {
+    std::fs::metadata(path).map(|m| m.len() > 0).unwrap_or(false)
+}
+
+/// File exists and is non-empty, and contains `contains` if given
+pub fn file_ready(path: &Path, contains: Option<&str>) -> bool {
+    match contains {
+        None => shm_ready(path),
+        Some(text) => std::fs::read_to_string(path).map(|c| c.contains(text)).unwrap_or(false),
+    }
+}
+
+/// TCP port on localhost accepts connections
+pub fn port_ready(port: u16) -> bool {
+    TcpStream::connect_timeout(&SocketAddr::from(([127, 0, 0, 1], port)), Duration::from_millis(200)).is_ok()
+}
+
+pub fn wait_for_socket(path: &Path, options: WaitOptions) -> bool {
+    wait_until(options, || socket_ready(path))
+}
+
+pub fn wait_for_shm(path: &Path, options: WaitOptions) -> bool {
+    wait_until(options, || shm_ready(path))
+}
+
+pub fn wait_for_file(path: &Path, contains: Option<&str>, options: WaitOptions) -> bool {
+    wait_until(options, || file_ready(path, contains))
+}
+
+pub fn wait_for_port(port: u16, options: WaitOptions) -> bool {
+    wait_until(options, || port_ready(port))
+}
@@ -0,0 +1,162 @@
+/// Performer / technician mode shared by the GUIs
+///
+/// Performer mode hides or disables anything that can hurt the instrument
+/// mid-show (calibration, speed/accel, min/max limits, kill) and leaves the
+/// live controls. Switching to technician mode asks for the ROLE
+/// TECHNICIAN_PIN when one is set. Clones share state, so GUIs embedded in
+/// master_gui lock and unlock together.
+
+#[cfg(feature = "gui")]
+use eframe::egui;
+use std::sync::{Arc, Mutex};
+use crate::config_loader::{load_role_settings, Role};
+
+#[derive(Debug)]
+struct RoleState {
+    role: Role,
+    pin: Option<String>,
+    pin_entry: Option<String>, // Some while the PIN prompt is open
+    pin_rejected: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct RoleLock {
+    state: Arc<Mutex<RoleState>>,
+}
+
+impl Default for RoleLock {
+    /// Unlocked, no PIN - the behaviour before roles existed
+    fn default() -> Self {
+        Self::new(Role::Technician, None)
+    }
+}
+
+impl RoleLock {
+    pub fn new(role: Role, pin: Option<String>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(RoleState { role, pin, pin_entry: None, pin_rejected: false })),
+        }
+    }
+
+    /// Start in the host's ROLE DEFAULT; falls back to technician if the block is invalid
+    pub fn for_host(hostname: &str) -> Self {
+        match load_role_settings(hostname) {
+            Ok(settings) => Self::new(settings.default, settings.technician_pin),
+            Err(e) => {
+                tracing::warn!("Ignoring ROLE settings for '{}': {}", hostname, e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn role(&self) -> Role {
+        self.state.lock().unwrap().role
+    }
+
+    pub fn is_technician(&self) -> bool {
+        self.role() == Role::Technician
+    }
+
+    /// Back to performer mode (never needs the PIN)
+    pub fn lock(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.role = Role::Performer;
+        state.pin_entry = None;
+        state.pin_rejected = false;
+    }
+
+    /// Switch to technician mode if `pin` matches (any PIN works when none is configured)
+    pub fn unlock(&self, pin: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let accepted = match state.pin.as_deref() {
+            Some(expected) => expected == pin,
+            None => true,
+        };
+        if accepted {
+            state.role = Role::Technician;
+            state.pin_entry = None;
+        }
+        state.pin_rejected = !accepted;
+        accepted
+    }
+
+    /// Mode indicator with the lock / unlock controls
+    #[cfg(feature = "gui")]
+    pub fn show(&self, ui: &mut egui::Ui) {
+        let mut state = self.state.lock().unwrap();
+        ui.horizontal(|ui| {
+            match state.role {
+                Role::Performer => {
+                    ui.colored_label(egui::Color32::from_rgb(60, 140, 220), egui::RichText::new("PERFORMER MODE").strong());
+                    if state.pin_entry.is_none() && ui.button("Technician...").clicked() {
+                        if state.pin.is_some() {
+                            state.pin_entry = Some(String::new());
+                        } else {
+                            state.role = Role::Technician;
+                        }
+                    }
+                }
+                Role::Technician => {
+                    ui.colored_label(egui::Color32::from_rgb(220, 140, 0), egui::RichText::new("TECHNICIAN MODE").strong());
+                    if ui.button("Lock (performer)").clicked() {
+                        state.role = Role::Performer;
+                    }
+                }
+            }
+        });
+        let Some(mut entry) = state.pin_entry.take() else { return; };
+        let mut submitted = false;
+        let mut cancelled = false;
+        ui.horizontal(|ui| {
+            ui.label("PIN:");
+            let response = ui.add(egui::TextEdit::singleline(&mut entry).password(true).desired_width(80.0));
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                submitted = true;
+            }
+            submitted |= ui.button("Unlock").clicked();
+            cancelled = ui.button("Cancel").clicked();
+            if state.pin_rejected {
+                ui.colored_label(egui::Color32::RED, "Wrong PIN");
+            }
+        });
+        if cancelled {
+            state.pin_rejected = false;
+            return;
+        }
+        state.pin_entry = Some(entry.clone());
+        if submitted {
+            drop(state);
+            if !self.unlock(&entry) {
+                tracing::warn!("Technician unlock refused: wrong PIN");
+                if let Some(pending) = self.state.lock().unwrap().pin_entry.as_mut() {
+                    pending.clear();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlock_requires_configured_pin() {
+        let lock = RoleLock::new(Role::Performer, Some("2468".to_string()));
+        assert!(!lock.unlock("1234"));
+        assert!(!lock.is_technician());
+        assert!(lock.unlock("2468"));
+        assert!(lock.is_technician());
+
+        let shared = lock.clone();
+        shared.lock();
+        assert_eq!(lock.role(), Role::Performer);
+    }
+
+    #[test]
+    fn test_unlock_without_pin() {
+        let lock = RoleLock::new(Role::Performer, None);
+        assert!(lock.unlock(""));
+        assert!(lock.is_technician());
+    }
+}
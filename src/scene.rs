@@ -0,0 +1,174 @@
+/// Scenes: the whole instrument's state saved under a name and recalled mid-piece
+///
+/// A scene holds each Z stepper's position, each carriage board tuner's (by
+/// string), the X position and the operations parameters as a preset. It is saved
+/// as `<name>.scene.yaml` in the SCENES directory (default `scenes/<host>`):
+///
+/// ```yaml
+/// NAME: chorale
+/// HOST: studio
+/// SAVED_AT: 2026-10-16 20:14:03
+/// X: 1200
+/// Z: { 1: 40, 2: 42, 3: 100, 4: 100 }   # by stepper
+/// TUNERS: { 0: 310, 1: -25 }            # by string
+/// PARAMS: { LAP_REST: 2.0, ADJUSTMENT_LEVEL: 10 }
+/// ```
+///
+/// Recalling a scene (Operations::recall_scene) drives the instrument back with
+/// absolute moves: the Z steppers going up first, then X, then the Z steppers going
+/// down and last the tuners, so the carriage travels with each Z stepper at the
+/// higher of its two positions, and a bump_check follows the moves down. X keeps
+/// to X_MAX_POS and X_CLEARANCE, Z to Z_MIN_POS and its max positions and the
+/// tuners to MAX_PITCH.
+
+use crate::config_loader::ParameterPreset;
+use crate::operations::Z_MIN_POS;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+const SCENE_SUFFIX: &str = ".scene.yaml";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", deny_unknown_fields)]
+pub struct Scene {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub host: String,
+    #[serde(default)]
+    pub saved_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x: Option<i32>,
+    #[serde(default)]
+    pub z: BTreeMap<usize, i32>, // By stepper
+    #[serde(default)]
+    pub tuners: BTreeMap<usize, i32>, // By string
+    #[serde(default)]
+    pub params: ParameterPreset,
+}
+
+impl Scene {
+    /// Read a scene file; without a NAME it is named after the file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        let mut scene: Scene = serde_yaml::from_str(&text).map_err(|e| format!("invalid scene {}: {}", path.display(), e))?;
+        if scene.name.is_empty() {
+            scene.name = scene_name(path).unwrap_or_default().to_string();
+        }
+        Ok(scene)
+    }
+
+    /// Write the scene to `dir` (created if needed), replacing one of the same name
+    pub fn save(&self, dir: &Path) -> Result<PathBuf, String> {
+        let path = scene_path(dir, &self.name)?;
+        std::fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+        let text = serde_yaml::to_string(self).map_err(|e| e.to_string())?;
+        std::fs::write(&path, text).map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+
+    /// Z moves still needed from `positions`, targets held to Z_MIN_POS and capped at
+    /// `max_positions`: with `up` those that raise a stepper, otherwise those that lower it
+    pub fn z_moves(&self, positions: &[i32], max_positions: &HashMap<usize, i32>, up: bool) -> Vec<(usize, i32)> {
+        self.z.iter()
+            .map(|(&stepper, &target)| (stepper, max_positions.get(&stepper).map_or(target, |&max| target.min(max)).max(Z_MIN_POS)))
+            .filter(|&(stepper, target)| {
+                let current = positions.get(stepper).copied().unwrap_or(0);
+                if up { target > current } else { target < current }
+            })
+            .collect()
+    }
+}
+
+/// `<dir>/<name>.scene.yaml`; a name is one plain file name
+pub fn scene_path(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Scene name must not be empty".to_string());
+    }
+    if name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("'{}' is not a usable scene name", name));
+    }
+    Ok(dir.join(format!("{}{}", name, SCENE_SUFFIX)))
+}
+
+/// "chorale" for .../chorale.scene.yaml
+pub fn scene_name(path: &Path) -> Option<&str> {
+    path.file_name()?.to_str()?.strip_suffix(SCENE_SUFFIX).filter(|name| !name.is_empty())
+}
+
+/// Every scene in `dir` by name, with a message per file that could not be read.
+/// A missing directory has no scenes.
+pub fn load_scenes(dir: &Path) -> (Vec<Scene>, Vec<String>) {
+    let mut scenes = Vec::new();
+    let mut errors = Vec::new();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (scenes, errors);
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if scene_name(&path).is_none() {
+            continue;
+        }
+        match Scene::load(&path) {
+            Ok(scene) => scenes.push(scene),
+            Err(e) => errors.push(e),
+        }
+    }
+    scenes.sort_by(|a, b| a.name.cmp(&b.name));
+    (scenes, errors)
+}
+
+pub fn delete_scene(dir: &Path, name: &str) -> Result<(), String> {
+    let path = scene_path(dir, name)?;
+    std::fs::remove_file(&path).map_err(|e| format!("cannot delete {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_z_moves_raise_before_x_and_lower_after() {
+        let scene = Scene { z: BTreeMap::from([(1, 40), (2, 10), (3, 150), (4, -5)]), ..Scene::default() };
+        let positions = [300, 20, 10, 20, 20];
+        let max = HashMap::from([(1, 100), (2, 100), (3, 100), (4, 100)]);
+        assert_eq!(scene.z_moves(&positions, &max, true), [(1, 40), (3, 100)]);
+        // Never below Z_MIN_POS
+        assert_eq!(scene.z_moves(&positions, &max, false), [(4, 0)]);
+        // Already there: nothing to do
+        let there = [300, 40, 10, 100, 0];
+        assert!(scene.z_moves(&there, &max, true).is_empty() && scene.z_moves(&there, &max, false).is_empty());
+    }
+
+    #[test]
+    fn test_scenes_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("stringdriver-scenes-{}", std::process::id()));
+        let scene = Scene {
+            name: "chorale".to_string(),
+            host: "studio".to_string(),
+            saved_at: "2026-10-16 20:14:03".to_string(),
+            x: Some(1200),
+            z: BTreeMap::from([(1, 40), (2, 42)]),
+            tuners: BTreeMap::from([(0, 310)]),
+            params: ParameterPreset { lap_rest: Some(2.0), ..ParameterPreset::default() },
+        };
+        let path = scene.save(&dir).unwrap();
+        assert_eq!(scene_name(&path), Some("chorale"));
+        std::fs::write(dir.join("bare.scene.yaml"), "X: 5\nZ: { 1: 3 }\n").unwrap();
+        std::fs::write(dir.join("broken.scene.yaml"), "X: [").unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a scene").unwrap();
+
+        let (scenes, errors) = load_scenes(&dir);
+        assert_eq!(scenes.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["bare", "chorale"]);
+        assert_eq!(scenes[1], scene);
+        assert_eq!((scenes[0].x, scenes[0].z.get(&1)), (Some(5), Some(&3)));
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+
+        delete_scene(&dir, "chorale").unwrap();
+        assert_eq!(load_scenes(&dir).0.len(), 1);
+        assert!(scene_path(&dir, "../up").is_err() && scene_path(&dir, " ").is_err());
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(load_scenes(&dir).0.is_empty());
+    }
+}
@@ -0,0 +1,614 @@
+/// Composition scores - timed event files that perform a piece unattended
+///
+/// A score (`<name>.score.yaml`, `.score.yml` or `.score.json`) is dropped into the
+/// SCORES directory; operations_gui loads it and plays it on a transport (play,
+/// pause, stop, seek) that runs on its own clock or follows a MIDI clock. Each event
+/// happens at `AT` seconds, or at `BAR` (and `BEAT`, both from 1) at the score's
+/// TEMPO, and does exactly one thing:
+///
+/// ```yaml
+/// TITLE: Drift
+/// TEMPO: 72            # BPM; needed for BAR / BEAT and MIDI clock sync
+/// BEATS_PER_BAR: 4
+/// EVENTS:
+///   - { AT: 0, PRESET: rehearsal }
+///   - { AT: 0, OPERATION: z_adjust }
+///   - { BAR: 5, X: 800, OVER: 20 }                          # glide X to 800 over 20 s
+///   - { BAR: 9, BEAT: 3, Z: { STRING: 1, POSITION: -30 } }  # both Z of string 1
+///   - { BAR: 12, TUNE: { STRING: 0, STEPS: 12 } }
+///   - { BAR: 13, OPERATION: z_hold, RESTS: { Z_REST: 0.5 } }   # rests for this run only
+///   - { BAR: 29, STOP: true }                               # BREAK what is running
+/// ```
+///
+/// Seeking chases the score: the X position, Z targets and preset in force at the
+/// new position are cued at once; operations, tuner changes and stops before it are not.
+
+use crate::config_loader::RestOverrides;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const SCORE_SUFFIXES: &[&str] = &[".score.yaml", ".score.yml", ".score.json"];
+/// X steps between the positions a glide is cued at
+const GLIDE_STEP: i32 = 10;
+const PULSES_PER_BEAT: u64 = 24; // MIDI clock
+const MIDI_POLL: Duration = Duration::from_millis(2);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", deny_unknown_fields)]
+struct ScoreFile {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    tempo: Option<f64>,
+    #[serde(default)]
+    beats_per_bar: Option<u32>,
+    events: Vec<EventEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", deny_unknown_fields)]
+struct EventEntry {
+    #[serde(default)]
+    at: Option<f64>,
+    #[serde(default)]
+    bar: Option<f64>,
+    #[serde(default)]
+    beat: Option<f64>,
+    #[serde(default)]
+    x: Option<i32>,
+    #[serde(default)]
+    over: Option<f64>,
+    #[serde(default)]
+    z: Option<ZTarget>,
+    #[serde(default)]
+    tune: Option<TuneChange>,
+    #[serde(default)]
+    operation: Option<String>,
+    #[serde(default)]
+    rests: Option<RestOverrides>, // Only with OPERATION
+    #[serde(default)]
+    preset: Option<String>,
+    #[serde(default)]
+    stop: bool,
+}
+
+/// Move both Z steppers of a string to a position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", deny_unknown_fields)]
+pub struct ZTarget {
+    pub string: usize,
+    pub position: i32,
+}
+
+/// Turn a string's tuner by some steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", deny_unknown_fields)]
+pub struct TuneChange {
+    pub string: usize,
+    pub steps: i32,
+}
+
+/// What an event does
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScoreAction {
+    MoveX(i32),
+    GlideX { to: i32, over: f64 }, // Cued by the transport as a run of MoveX over `over` seconds
+    MoveZ(ZTarget),
+    Tune(TuneChange),
+    Operation(String, RestOverrides),
+    Preset(String),
+    Stop, // BREAK the running operation
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreEvent {
+    pub at: f64, // Seconds from the start
+    pub action: ScoreAction,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Score {
+    pub title: String,
+    pub tempo: Option<f64>, // Beats per minute
+    pub beats_per_bar: u32,
+    pub events: Vec<ScoreEvent>, // In time order; events at the same time keep file order
+}
+
+impl Score {
+    /// Parse a score (YAML, or JSON which YAML reads as well)
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let file: ScoreFile = serde_yaml::from_str(text).map_err(|e| e.to_string())?;
+        let beats_per_bar = file.beats_per_bar.unwrap_or(4);
+        if beats_per_bar == 0 {
+            return Err("BEATS_PER_BAR must be at least 1".to_string());
+        }
+        if file.tempo.is_some_and(|tempo| !tempo.is_finite() || tempo <= 0.0) {
+            return Err("TEMPO must be a positive number of beats per minute".to_string());
+        }
+
+        let mut events = Vec::new();
+        for (i, entry) in file.events.into_iter().enumerate() {
+            let n = i + 1;
+            let at = match (entry.at, entry.bar) {
+                (Some(at), None) if entry.beat.is_none() => at,
+                (None, Some(bar)) => {
+                    let tempo = file.tempo.ok_or_else(|| format!("event {}: BAR needs the score's TEMPO", n))?;
+                    let beat = entry.beat.unwrap_or(1.0);
+                    if bar < 1.0 || beat < 1.0 {
+                        return Err(format!("event {}: BAR and BEAT count from 1", n));
+                    }
+                    ((bar - 1.0) * beats_per_bar as f64 + beat - 1.0) * 60.0 / tempo
+                }
+                _ => return Err(format!("event {}: give either AT (seconds) or BAR (and BEAT)", n)),
+            };
+            if !at.is_finite() || at < 0.0 {
+                return Err(format!("event {}: AT must not be negative", n));
+            }
+            if entry.over.is_some() && entry.x.is_none() {
+                return Err(format!("event {}: OVER only applies to X", n));
+            }
+            if entry.rests.is_some() && entry.operation.is_none() {
+                return Err(format!("event {}: RESTS only applies to OPERATION", n));
+            }
+            if let Some(name) = entry.rests.as_ref().and_then(RestOverrides::invalid) {
+                return Err(format!("event {}: RESTS {} must be a non-negative number of seconds", n, name));
+            }
+
+            let mut actions = Vec::new();
+            if let Some(to) = entry.x {
+                actions.push(match entry.over {
+                    Some(over) if over > 0.0 => ScoreAction::GlideX { to, over },
+                    _ => ScoreAction::MoveX(to),
+                });
+            }
+            actions.extend(entry.z.map(ScoreAction::MoveZ));
+            actions.extend(entry.tune.map(ScoreAction::Tune));
+            let rests = entry.rests.unwrap_or_default();
+            actions.extend(entry.operation.map(|name| ScoreAction::Operation(name, rests)));
+            actions.extend(entry.preset.map(ScoreAction::Preset));
+            if entry.stop {
+                actions.push(ScoreAction::Stop);
+            }
+            let action = match <[ScoreAction; 1]>::try_from(actions) {
+                Ok([action]) => action,
+                Err(_) => return Err(format!("event {}: needs exactly one of X, Z, TUNE, OPERATION, PRESET, STOP", n)),
+            };
+            events.push(ScoreEvent { at, action });
+        }
+        events.sort_by(|a, b| a.at.total_cmp(&b.at));
+        Ok(Self { title: file.title.unwrap_or_default(), tempo: file.tempo, beats_per_bar, events })
+    }
+
+    /// Read a score file; without a TITLE it is named after the file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        let mut score = Self::parse(&text).map_err(|e| format!("invalid score {}: {}", path.display(), e))?;
+        if score.title.is_empty() {
+            score.title = score_name(path).unwrap_or_default().to_string();
+        }
+        Ok(score)
+    }
+
+    /// Seconds until the last event (or glide) is done
+    pub fn length(&self) -> f64 {
+        self.events.iter().map(|e| match e.action {
+            ScoreAction::GlideX { over, .. } => e.at + over,
+            _ => e.at,
+        }).fold(0.0, f64::max)
+    }
+
+    pub fn beats_to_seconds(&self, beats: f64) -> Option<f64> {
+        self.tempo.map(|tempo| beats * 60.0 / tempo)
+    }
+
+    /// Bar and beat (both from 1) at `seconds`, with a TEMPO
+    pub fn bar_beat(&self, seconds: f64) -> Option<(u32, f64)> {
+        let beats = seconds * self.tempo? / 60.0;
+        let bar = (beats / self.beats_per_bar as f64).floor();
+        Some((bar as u32 + 1, beats - bar * self.beats_per_bar as f64 + 1.0))
+    }
+
+    /// Operations and presets the score names, for checking before it plays
+    pub fn references(&self) -> (Vec<&str>, Vec<&str>) {
+        let mut operations = Vec::new();
+        let mut presets = Vec::new();
+        for event in &self.events {
+            match &event.action {
+                ScoreAction::Operation(name, _) if !operations.contains(&name.as_str()) => operations.push(name.as_str()),
+                ScoreAction::Preset(name) if !presets.contains(&name.as_str()) => presets.push(name.as_str()),
+                _ => {}
+            }
+        }
+        (operations, presets)
+    }
+}
+
+/// File name without the score suffix, for a score file
+pub fn score_name(path: &Path) -> Option<&str> {
+    let name = path.file_name()?.to_str()?;
+    SCORE_SUFFIXES.iter().find_map(|suffix| name.strip_suffix(suffix)).filter(|stem| !stem.is_empty() && !stem.starts_with('.'))
+}
+
+/// Score files in `dir`, by name
+pub fn score_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && score_name(p).is_some())
+        .collect();
+    files.sort();
+    files
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportState {
+    Stopped, // At the start
+    Playing,
+    Paused,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Glide {
+    from: i32,
+    to: i32,
+    start: f64,
+    over: f64,
+    last: i32, // Last position cued
+}
+
+impl Glide {
+    fn at(&self, seconds: f64) -> i32 {
+        let t = ((seconds - self.start) / self.over).clamp(0.0, 1.0);
+        self.from + ((self.to - self.from) as f64 * t).round() as i32
+    }
+}
+
+/// Plays a score: `poll` hands out the actions that have come due since the last call
+#[derive(Debug, Clone)]
+pub struct Transport {
+    pub score: Score,
+    state: TransportState,
+    offset: f64,            // Score position at `since`, or while paused
+    since: Option<Instant>, // While playing: when `offset` was taken
+    next: usize,            // First event not yet cued
+    glide: Option<Glide>,
+    x_target: Option<i32>, // Last X cued; where the next glide starts
+}
+
+impl Transport {
+    pub fn new(score: Score) -> Self {
+        Self { score, state: TransportState::Stopped, offset: 0.0, since: None, next: 0, glide: None, x_target: None }
+    }
+
+    pub fn state(&self) -> TransportState {
+        self.state
+    }
+
+    /// Seconds into the score
+    pub fn position(&self, now: Instant) -> f64 {
+        self.offset + self.since.map_or(0.0, |since| now.saturating_duration_since(since).as_secs_f64())
+    }
+
+    pub fn play(&mut self, now: Instant) {
+        if self.state != TransportState::Playing {
+            self.state = TransportState::Playing;
+            self.since = Some(now);
+        }
+    }
+
+    pub fn pause(&mut self, now: Instant) {
+        if self.state == TransportState::Playing {
+            self.offset = self.position(now);
+            self.since = None;
+            self.state = TransportState::Paused;
+        }
+    }
+
+    /// Back to the start
+    pub fn stop(&mut self) {
+        *self = Self::new(std::mem::replace(&mut self.score, Score { title: String::new(), tempo: None, beats_per_bar: 4, events: Vec::new() }));
+    }
+
+    /// Jump to `seconds`, returning the chase: the X position, Z targets and preset in force there
+    pub fn seek(&mut self, seconds: f64, now: Instant) -> Vec<ScoreAction> {
+        let seconds = seconds.clamp(0.0, self.score.length());
+        self.offset = seconds;
+        if self.state == TransportState::Playing {
+            self.since = Some(now);
+        } else if self.state == TransportState::Stopped {
+            self.state = TransportState::Paused;
+        }
+        self.next = self.score.events.partition_point(|e| e.at < seconds);
+
+        let mut x = None;
+        let mut glide = None;
+        let mut z_targets: Vec<ZTarget> = Vec::new();
+        let mut preset = None;
+        for event in &self.score.events[..self.next] {
+            match &event.action {
+                ScoreAction::MoveX(to) => {
+                    x = Some(*to);
+                    glide = None;
+                }
+                ScoreAction::GlideX { to, over } => {
+                    let from = x.unwrap_or(*to);
+                    let g = Glide { from, to: *to, start: event.at, over: *over, last: from };
+                    x = Some(g.at(seconds));
+                    glide = (seconds < event.at + over).then_some(g);
+                }
+                ScoreAction::MoveZ(target) => {
+                    z_targets.retain(|t| t.string != target.string);
+                    z_targets.push(*target);
+                }
+                ScoreAction::Preset(name) => preset = Some(name.clone()),
+                _ => {}
+            }
+        }
+        self.glide = glide.map(|g| Glide { last: x.unwrap_or(g.from), ..g });
+        self.x_target = x;
+        let mut chase: Vec<ScoreAction> = preset.map(ScoreAction::Preset).into_iter().collect();
+        chase.extend(x.map(ScoreAction::MoveX));
+        chase.extend(z_targets.into_iter().map(ScoreAction::MoveZ));
+        chase
+    }
+
+    /// Actions due by `now` (never GlideX: a glide comes out as MoveX steps). Past the
+    /// last event the transport stops and returns to the start.
+    pub fn poll(&mut self, now: Instant) -> Vec<ScoreAction> {
+        if self.state != TransportState::Playing {
+            return Vec::new();
+        }
+        let position = self.position(now);
+        let mut cues = Vec::new();
+        while let Some(event) = self.score.events.get(self.next).filter(|e| e.at <= position) {
+            match &event.action {
+                ScoreAction::GlideX { to, over } => {
+                    let from = self.x_target.unwrap_or(*to);
+                    self.glide = Some(Glide { from, to: *to, start: event.at, over: *over, last: from });
+                }
+                ScoreAction::MoveX(to) => {
+                    self.glide = None;
+                    self.x_target = Some(*to);
+                    cues.push(ScoreAction::MoveX(*to));
+                }
+                action => cues.push(action.clone()),
+            }
+            self.next += 1;
+        }
+        if let Some(glide) = self.glide.as_mut() {
+            let x = glide.at(position);
+            if (x - glide.last).abs() >= GLIDE_STEP || (x == glide.to && glide.last != glide.to) {
+                glide.last = x;
+                self.x_target = Some(x);
+                cues.push(ScoreAction::MoveX(x));
+            }
+            if glide.last == glide.to {
+                self.glide = None;
+            }
+        }
+        if self.next == self.score.events.len() && self.glide.is_none() {
+            self.stop();
+        }
+        cues
+    }
+
+    /// Follow a MIDI clock: Start, Stop and Continue drive the transport, song position
+    /// seeks and clock pulses keep the position on the clock (at the score's TEMPO)
+    pub fn follow(&mut self, message: MidiMessage, now: Instant) -> Vec<ScoreAction> {
+        match message {
+            MidiMessage::Start => {
+                self.stop();
+                self.play(now);
+                Vec::new()
+            }
+            MidiMessage::Continue => {
+                self.play(now);
+                Vec::new()
+            }
+            MidiMessage::Stop => {
+                self.pause(now);
+                Vec::new()
+            }
+            MidiMessage::SongPosition(beats) => self.score.beats_to_seconds(beats).map(|seconds| self.seek(seconds, now)).unwrap_or_default(),
+            MidiMessage::Clock(beats) => {
+                if let (TransportState::Playing, Some(seconds)) = (self.state, self.score.beats_to_seconds(beats)) {
+                    self.offset = seconds;
+                    self.since = Some(now);
+                }
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// A MIDI clock or transport message; beats count from the song start
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MidiMessage {
+    Start,
+    Continue,
+    Stop,
+    SongPosition(f64),
+    Clock(f64),
+}
+
+/// Picks the clock and transport messages out of a raw MIDI byte stream
+#[derive(Debug, Default)]
+pub struct MidiClock {
+    pulses: u64,   // Clock pulses since the song start, 24 per beat
+    running: bool, // Between Start / Continue and Stop
+    song_position: Option<Vec<u8>>, // Data bytes of a song position pointer being read
+}
+
+impl MidiClock {
+    pub fn feed(&mut self, byte: u8) -> Option<MidiMessage> {
+        match byte {
+            0xF8 if self.running => {
+                self.pulses += 1;
+                Some(MidiMessage::Clock(self.beats()))
+            }
+            0xFA => {
+                self.pulses = 0;
+                self.running = true;
+                Some(MidiMessage::Start)
+            }
+            0xFB => {
+                self.running = true;
+                Some(MidiMessage::Continue)
+            }
+            0xFC => {
+                self.running = false;
+                Some(MidiMessage::Stop)
+            }
+            0xF2 => {
+                self.song_position = Some(Vec::with_capacity(2));
+                None
+            }
+            // Other real-time bytes may come between any two bytes
+            0xF8..=0xFF => None,
+            0x80..=0xF7 => {
+                self.song_position = None;
+                None
+            }
+            data => {
+                let pending = self.song_position.as_mut()?;
+                pending.push(data);
+                if pending.len() < 2 {
+                    return None;
+                }
+                // In 16th notes, 6 pulses each
+                let sixteenths = pending[0] as u64 | (pending[1] as u64) << 7;
+                self.song_position = None;
+                self.pulses = sixteenths * 6;
+                Some(MidiMessage::SongPosition(self.beats()))
+            }
+        }
+    }
+
+    pub fn beats(&self) -> f64 {
+        self.pulses as f64 / PULSES_PER_BEAT as f64
+    }
+}
+
+/// Read a raw MIDI device (e.g. ALSA's /dev/snd/midiC1D0) and hand on its clock
+/// and transport messages. `wait` is called whenever no bytes are pending with
+/// how long to wait, and returns false to stop.
+pub fn follow_midi_clock(path: &Path, mut wait: impl FnMut(Duration) -> bool, mut on_message: impl FnMut(MidiMessage)) -> std::io::Result<()> {
+    use std::io::Read;
+    use std::os::unix::fs::OpenOptionsExt;
+    let mut device = std::fs::OpenOptions::new().read(true).custom_flags(libc::O_NONBLOCK).open(path)?;
+    let mut clock = MidiClock::default();
+    let mut buffer = [0u8; 64];
+    loop {
+        match device.read(&mut buffer) {
+            Ok(0) => {
+                if !wait(MIDI_POLL) {
+                    return Ok(());
+                }
+            }
+            Ok(n) => buffer[..n].iter().filter_map(|&byte| clock.feed(byte)).for_each(&mut on_message),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                if !wait(MIDI_POLL) {
+                    return Ok(());
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCORE: &str = r#"
+TEMPO: 60
+BEATS_PER_BAR: 4
+EVENTS:
+  - { BAR: 2, OPERATION: z_hold, RESTS: { LAP_REST: 1.5 } }
+  - { AT: 0, X: 100 }
+  - { AT: 0, PRESET: rehearsal }
+  - { BAR: 1, BEAT: 3, X: 200, OVER: 2 }
+  - { AT: 3, Z: { STRING: 1, POSITION: -30 } }
+  - { AT: 6, STOP: true }
+"#;
+
+    /// SCORE's OPERATION event
+    fn z_hold() -> ScoreAction {
+        ScoreAction::Operation("z_hold".to_string(), RestOverrides { lap_rest: Some(1.5), ..Default::default() })
+    }
+
+    fn at(start: Instant, seconds: f64) -> Instant {
+        start + Duration::from_secs_f64(seconds)
+    }
+
+    #[test]
+    fn test_score_times_come_from_seconds_or_bars() {
+        let score = Score::parse(SCORE).unwrap();
+        let times: Vec<f64> = score.events.iter().map(|e| e.at).collect();
+        assert_eq!(times, [0.0, 0.0, 2.0, 3.0, 4.0, 6.0]);
+        assert_eq!(score.events[0].action, ScoreAction::MoveX(100));
+        assert_eq!(score.events[4].action, z_hold());
+        assert_eq!(score.bar_beat(6.5), Some((2, 3.5)));
+        assert_eq!(score.references(), (vec!["z_hold"], vec!["rehearsal"]));
+
+        assert!(Score::parse("EVENTS: [{ BAR: 2, STOP: true }]").unwrap_err().contains("TEMPO"));
+        assert!(Score::parse("EVENTS: [{ AT: 1, X: 5, OPERATION: park }]").unwrap_err().contains("exactly one"));
+        assert!(Score::parse("EVENTS: [{ AT: 1, BAR: 1, STOP: true }]").is_err());
+        assert!(Score::parse("EVENTS: [{ AT: 1, X: 5, RESTS: { Z_REST: 1 } }]").unwrap_err().contains("RESTS"));
+        assert!(Score::parse(r#"{"EVENTS": [{"AT": 1.5, "TUNE": {"STRING": 0, "STEPS": -4}}]}"#).is_ok());
+        assert_eq!(score_name(Path::new("/scores/drift.score.yaml")), Some("drift"));
+        assert_eq!(score_name(Path::new("/scores/drift.yaml")), None);
+    }
+
+    #[test]
+    fn test_transport_cues_events_and_glides_in_time() {
+        let start = Instant::now();
+        let mut transport = Transport::new(Score::parse(SCORE).unwrap());
+        assert!(transport.poll(start).is_empty());
+        transport.play(start);
+        assert_eq!(transport.poll(start), [ScoreAction::MoveX(100), ScoreAction::Preset("rehearsal".to_string())]);
+
+        // The glide from 100 to 200 between 2 s and 4 s comes out in steps
+        assert_eq!(transport.poll(at(start, 3.0)), [ScoreAction::MoveZ(ZTarget { string: 1, position: -30 }), ScoreAction::MoveX(150)]);
+        transport.pause(at(start, 3.0));
+        assert!(transport.poll(at(start, 10.0)).is_empty());
+        transport.play(at(start, 10.0));
+        assert_eq!(transport.poll(at(start, 11.5)), [z_hold(), ScoreAction::MoveX(200)]);
+        assert_eq!(transport.poll(at(start, 13.0)), [ScoreAction::Stop]);
+        assert_eq!(transport.state(), TransportState::Stopped);
+        assert_eq!(transport.position(at(start, 20.0)), 0.0);
+    }
+
+    #[test]
+    fn test_seek_chases_positions_and_midi_clock_drives_the_transport() {
+        let start = Instant::now();
+        let mut transport = Transport::new(Score::parse(SCORE).unwrap());
+        let chase = transport.seek(3.5, start);
+        assert_eq!(chase, [
+            ScoreAction::Preset("rehearsal".to_string()),
+            ScoreAction::MoveX(175),
+            ScoreAction::MoveZ(ZTarget { string: 1, position: -30 }),
+        ]);
+        transport.play(start);
+        assert_eq!(transport.poll(at(start, 0.5)), [z_hold(), ScoreAction::MoveX(200)]);
+
+        // Song position at beat 2 (8 sixteenths), then Continue and 24 pulses: beat 3 = 2 s at 60 BPM
+        let mut clock = MidiClock::default();
+        let messages: Vec<MidiMessage> = [0xF2, 8, 0, 0xFB].into_iter().chain([0xF8; 24]).filter_map(|b| clock.feed(b)).collect();
+        assert_eq!(messages[0], MidiMessage::SongPosition(2.0));
+        assert_eq!(messages.last(), Some(&MidiMessage::Clock(3.0)));
+        transport.stop();
+        for message in messages {
+            transport.follow(message, start);
+        }
+        assert_eq!(transport.state(), TransportState::Playing);
+        assert_eq!(transport.position(start), 3.0);
+        assert_eq!(transport.poll(start), [ScoreAction::MoveZ(ZTarget { string: 1, position: -30 }), ScoreAction::MoveX(150)]);
+        assert_eq!(clock.feed(0xFC), Some(MidiMessage::Stop));
+        assert_eq!(clock.feed(0xF8), None);
+    }
+}
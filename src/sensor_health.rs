@@ -0,0 +1,136 @@
+/// Touch sensor health - stuck and dead Z touch sensors
+///
+/// A sensor stuck "pressed" makes bump_check retract its stepper to max and
+/// disable it on every run; one stuck "open" silently removes crash protection.
+/// Operations feeds every Z touch reading and every calibration outcome in here.
+/// A sensor pressed without a break for SENSOR_HEALTH.STUCK_MINUTES, or one that
+/// never triggered across SILENT_CALIBRATIONS calibrations in a row, is reported.
+/// A quarantined sensor is treated as absent: it reads "not touching" and its
+/// stepper is left out of calibration instead of being disabled.
+
+use crate::config_loader::SensorHealthSettings;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// Why a sensor looks broken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SensorFault {
+    StuckPressed(Duration), // Pressed continuously for this long
+    NeverTriggered(u32),    // Calibrations in a row that bottomed out without a touch
+}
+
+impl SensorFault {
+    pub fn describe(&self) -> String {
+        match self {
+            SensorFault::StuckPressed(d) => format!("pressed for {:.0} min - stuck?", d.as_secs_f32() / 60.0),
+            SensorFault::NeverTriggered(n) => format!("not triggered in {} calibrations - dead?", n),
+        }
+    }
+}
+
+/// Per-sensor observations, keyed by Z touch index (stepper - Z_FIRST_INDEX)
+#[derive(Debug, Default)]
+pub struct SensorHealth {
+    pressed_since: HashMap<usize, Instant>,
+    silent_calibrations: HashMap<usize, u32>,
+    quarantined: HashSet<usize>,
+}
+
+impl SensorHealth {
+    pub fn new(quarantined: &[usize]) -> Self {
+        Self { quarantined: quarantined.iter().copied().collect(), ..Self::default() }
+    }
+
+    /// Record one reading of a sensor
+    pub fn observe(&mut self, sensor: usize, pressed: bool, now: Instant) {
+        if pressed {
+            self.pressed_since.entry(sensor).or_insert(now);
+        } else {
+            self.pressed_since.remove(&sensor);
+        }
+    }
+
+    /// Record whether calibration ended on a touch or bottomed out
+    pub fn calibrated(&mut self, sensor: usize, touched: bool) {
+        if touched {
+            self.silent_calibrations.remove(&sensor);
+        } else {
+            *self.silent_calibrations.entry(sensor).or_insert(0) += 1;
+        }
+    }
+
+    pub fn fault(&self, sensor: usize, settings: &SensorHealthSettings, now: Instant) -> Option<SensorFault> {
+        if let Some(since) = self.pressed_since.get(&sensor) {
+            let pressed_for = now.duration_since(*since);
+            if pressed_for >= settings.stuck_pressed {
+                return Some(SensorFault::StuckPressed(pressed_for));
+            }
+        }
+        match self.silent_calibrations.get(&sensor) {
+            Some(&n) if settings.silent_calibrations > 0 && n >= settings.silent_calibrations => Some(SensorFault::NeverTriggered(n)),
+            _ => None,
+        }
+    }
+
+    /// Every sensor with a fault, lowest index first
+    pub fn faults(&self, settings: &SensorHealthSettings, now: Instant) -> Vec<(usize, SensorFault)> {
+        let mut sensors: Vec<usize> = self.pressed_since.keys().chain(self.silent_calibrations.keys()).copied().collect();
+        sensors.sort_unstable();
+        sensors.dedup();
+        sensors.into_iter().filter_map(|s| self.fault(s, settings, now).map(|f| (s, f))).collect()
+    }
+
+    pub fn is_quarantined(&self, sensor: usize) -> bool {
+        self.quarantined.contains(&sensor)
+    }
+
+    pub fn set_quarantined(&mut self, sensor: usize, quarantined: bool) {
+        if quarantined {
+            self.quarantined.insert(sensor);
+        } else {
+            self.quarantined.remove(&sensor);
+            // Start over, so an old streak does not flag the sensor the moment it is back
+            self.pressed_since.remove(&sensor);
+            self.silent_calibrations.remove(&sensor);
+        }
+    }
+
+    pub fn quarantined(&self) -> Vec<usize> {
+        let mut sensors: Vec<usize> = self.quarantined.iter().copied().collect();
+        sensors.sort_unstable();
+        sensors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stuck_and_silent_sensors_are_flagged() {
+        let settings = SensorHealthSettings {
+            stuck_pressed: Duration::from_secs(600),
+            silent_calibrations: 2,
+            ..SensorHealthSettings::default()
+        };
+        let start = Instant::now();
+        let mut health = SensorHealth::new(&[]);
+
+        health.observe(0, true, start);
+        health.observe(0, true, start + Duration::from_secs(300));
+        assert_eq!(health.fault(0, &settings, start + Duration::from_secs(300)), None);
+        assert!(matches!(health.fault(0, &settings, start + Duration::from_secs(601)), Some(SensorFault::StuckPressed(_))));
+        health.observe(0, false, start + Duration::from_secs(602));
+        assert_eq!(health.fault(0, &settings, start + Duration::from_secs(700)), None);
+
+        health.calibrated(3, false);
+        health.calibrated(3, false);
+        assert_eq!(health.faults(&settings, start), vec![(3, SensorFault::NeverTriggered(2))]);
+        health.calibrated(3, true);
+        assert!(health.faults(&settings, start).is_empty());
+
+        health.set_quarantined(1, true);
+        assert!(health.is_quarantined(1));
+        assert_eq!(health.quarantined(), vec![1]);
+    }
+}
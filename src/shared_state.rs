@@ -0,0 +1,93 @@
+/// Settings both GUIs edit - step sizes and the stepper lockout - kept in one place
+///
+/// stepper_gui holds the state and serves it over its socket: `get_shared` answers
+/// one `shared ...` line, `set_shared key=value ...` changes step sizes, and
+/// `subscribe shared` pushes a fresh line whenever anything changes (checked twice a
+/// second). Steppers are locked out with the existing `disable` / `enable` commands,
+/// which also switch driver current. operations_gui subscribes and publishes its own
+/// edits, so a change made in either GUI shows in the other within a second.
+///
+/// Wire form: `shared z_up_step=2 z_down_step=-2 x_step=10 disabled=1,4` (`disabled=-` when none)
+
+use std::collections::BTreeSet;
+
+/// Keys `set_shared` accepts
+pub const SETTABLE_KEYS: &[&str] = &["z_up_step", "z_down_step", "x_step"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SharedState {
+    pub z_up_step: i32,
+    pub z_down_step: i32,
+    pub x_step: i32,
+    pub disabled: BTreeSet<usize>, // Locked-out steppers
+}
+
+impl SharedState {
+    pub fn to_line(&self) -> String {
+        let disabled: Vec<String> = self.disabled.iter().map(|idx| idx.to_string()).collect();
+        format!(
+            "shared z_up_step={} z_down_step={} x_step={} disabled={}",
+            self.z_up_step,
+            self.z_down_step,
+            self.x_step,
+            if disabled.is_empty() { "-".to_string() } else { disabled.join(",") }
+        )
+    }
+
+    /// Parse a `shared ...` line; unknown keys are skipped so either side can add keys first
+    pub fn parse_line(line: &str) -> Result<Self, String> {
+        let mut tokens = line.split_whitespace();
+        if tokens.next() != Some("shared") {
+            return Err(format!("not a shared state line: '{}'", line.trim()));
+        }
+        let mut state = SharedState::default();
+        for token in tokens {
+            let (key, value) = token.split_once('=').ok_or_else(|| format!("malformed token '{}'", token))?;
+            match key {
+                "disabled" if value == "-" => state.disabled.clear(),
+                "disabled" => {
+                    state.disabled = value
+                        .split(',')
+                        .map(|idx| idx.parse().map_err(|_| format!("bad stepper index '{}'", idx)))
+                        .collect::<Result<_, _>>()?;
+                }
+                key if SETTABLE_KEYS.contains(&key) => state.set(key, value)?,
+                _ => {}
+            }
+        }
+        Ok(state)
+    }
+
+    /// Change one step size; Err for keys set_shared does not take or unusable values
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let parsed: i32 = value.parse().map_err(|_| format!("{}: '{}' is not a whole number", key, value))?;
+        match key {
+            "z_up_step" if parsed > 0 => self.z_up_step = parsed,
+            "z_down_step" if parsed < 0 => self.z_down_step = parsed,
+            "x_step" if parsed > 0 => self.x_step = parsed,
+            "z_up_step" | "x_step" => return Err(format!("{} must be positive", key)),
+            "z_down_step" => return Err("z_down_step must be negative".to_string()),
+            _ => return Err(format!("unknown shared key '{}'", key)),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_round_trip_and_validation() {
+        let mut state = SharedState { z_up_step: 2, z_down_step: -2, x_step: 10, disabled: BTreeSet::new() };
+        assert_eq!(state.to_line(), "shared z_up_step=2 z_down_step=-2 x_step=10 disabled=-");
+        state.disabled.extend([4, 1]);
+        state.set("z_up_step", "3").unwrap();
+        assert_eq!(SharedState::parse_line(&state.to_line()).unwrap(), state);
+        assert_eq!(SharedState::parse_line("shared z_up_step=3 tuner_step=5").unwrap().z_up_step, 3);
+
+        assert!(state.set("z_down_step", "2").is_err());
+        assert!(state.set("disabled", "1").is_err());
+        assert!(SharedState::parse_line("positions 0=1").is_err());
+    }
+}
@@ -0,0 +1,426 @@
+/// Stopping String Driver processes: what kill_all.sh used to do
+///
+/// The processes to stop come from the launcher's component registry (the
+/// host's LAUNCH list, or both built-in lists without one) rather than a fixed
+/// list of pkill patterns, so a component added under LAUNCH is stopped by EXIT
+/// too. Each component is matched by its program, or its interpreter plus the
+/// script ("bash audmon.sh", never "python3" alone), plus the PROCESSES it
+/// leaves running (a persist xterm, qjackctl). `terminate` asks
+/// them to exit with SIGTERM and sends SIGKILL to whatever is left after the
+/// grace period, or only reports what it would stop (`Mode::DryRun`); a subset
+/// of components can be stopped on its own, e.g. to restart audio_monitor.
+/// Processes are found through /proc (Linux); the caller and its parents are
+/// never matched.
+
+use crate::config_loader::{self, LaunchComponent};
+use crate::error::{Error, Result};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Grace period between SIGTERM and SIGKILL for the EXIT button and `launcher --stop`
+pub const DEFAULT_GRACE: Duration = Duration::from_secs(2);
+/// How long SIGKILL gets to take effect before a process counts as a survivor
+const KILL_WAIT: Duration = Duration::from_secs(1);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// stepper_gui's command sockets, left behind when it is killed
+const SOCKET_DIR: &str = "/tmp";
+const SOCKET_PREFIX: &str = "stepper_gui_";
+/// Programs that run another one: their name alone would match every script or
+/// build they run, so a command starting with one is matched with its script
+const INTERPRETERS: &[&str] = &["bash", "sh", "dash", "env", "python", "python3", "perl", "ruby", "node", "cargo"];
+
+/// A component and the command line patterns of its processes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Target {
+    pub name: String,
+    pub patterns: Vec<String>,
+}
+
+impl Target {
+    /// The command's own pattern (see command_pattern), then PROCESSES
+    pub fn from_component(component: &LaunchComponent) -> Self {
+        let mut patterns: Vec<String> = command_pattern(&component.command).into_iter().collect();
+        for pattern in &component.processes {
+            if !patterns.contains(pattern) {
+                patterns.push(pattern.clone());
+            }
+        }
+        Target { name: component.name.clone(), patterns }
+    }
+
+    fn stops_stepper_gui(&self) -> bool {
+        self.name == "stepper_gui" || self.name == "master_gui"
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// How to find the process a command starts: a program by its file name, an
+/// interpreter by the command line up to its script ("bash audmon.sh",
+/// "python3 -u tools/bridge.py"), `env` by the command it runs. None when the
+/// script cannot be told (`cargo run ...`, a bare `python3`): PROCESSES must name it.
+fn command_pattern(command: &[String]) -> Option<String> {
+    let program = file_name(command.first()?);
+    if program == "env" {
+        let rest: Vec<String> = command[1..].iter().skip_while(|arg| arg.starts_with('-') || arg.contains('=')).cloned().collect();
+        return command_pattern(&rest);
+    }
+    if !INTERPRETERS.contains(&program) && !program.starts_with("python") {
+        return Some(program.to_string());
+    }
+    let script = command.iter().skip(1).position(|arg| !arg.starts_with('-'))? + 1;
+    let name = &command[script];
+    (name.contains('/') || name.contains('.')).then(|| command[..=script].join(" "))
+}
+
+/// Every component this host may be running: its LAUNCH list, or the master_gui and
+/// separate lists together without one
+pub fn registry_components(hostname: &str, project_root: &Path) -> Result<Vec<LaunchComponent>> {
+    let components = config_loader::load_launch_settings(hostname)?;
+    if !components.is_empty() {
+        return Ok(components);
+    }
+    let mut components = config_loader::default_launch_components(false, project_root);
+    for component in config_loader::default_launch_components(true, project_root) {
+        if !components.iter().any(|c| c.name == component.name) {
+            components.push(component);
+        }
+    }
+    Ok(components)
+}
+
+/// The targets of `registry_components`, after the launcher itself (stopped first,
+/// so it does not restart what is being stopped). A component with nothing to
+/// match it by is an error rather than a pattern that would stop strangers.
+pub fn registry(hostname: &str, project_root: &Path) -> Result<Vec<Target>> {
+    let mut targets = vec![Target { name: "launcher".to_string(), patterns: vec!["launcher".to_string()] }];
+    for component in registry_components(hostname, project_root)? {
+        if targets.iter().any(|t| t.name == component.name) {
+            continue;
+        }
+        let target = Target::from_component(&component);
+        if target.patterns.is_empty() {
+            return Err(Error::ConfigInvalid(format!(
+                "LAUNCH component '{}' runs '{}', whose process cannot be told from others of its kind - name it under PROCESSES",
+                component.name,
+                component.command.join(" ")
+            )));
+        }
+        targets.push(target);
+    }
+    Ok(targets)
+}
+
+/// The targets named in `names`, in registry order; an unknown name is an error
+/// listing the known ones
+pub fn select(registry: &[Target], names: &[String]) -> Result<Vec<Target>> {
+    if let Some(unknown) = names.iter().find(|n| !registry.iter().any(|t| &t.name == *n)) {
+        let known: Vec<&str> = registry.iter().map(|t| t.name.as_str()).collect();
+        return Err(Error::ConfigInvalid(format!("Unknown component '{}' (known: {})", unknown, known.join(", "))));
+    }
+    Ok(registry.iter().filter(|t| names.contains(&t.name)).cloned().collect())
+}
+
+/// Whether a process's arguments match `pattern`. A pattern with a '/' or a space
+/// (a path, an xterm title) is looked for anywhere in the command line; a bare name
+/// must be the file name of one of the arguments, so "launcher" does not match
+/// "launcher_notes.txt" and "audio_monitor" does not match "audio_monitor.yaml".
+pub fn matches(cmdline: &[String], pattern: &str) -> bool {
+    if pattern.contains('/') || pattern.contains(' ') {
+        cmdline.join(" ").contains(pattern)
+    } else {
+        cmdline.iter().any(|arg| file_name(arg) == pattern)
+    }
+}
+
+/// A running process
+#[derive(Debug, Clone, PartialEq)]
+pub struct Process {
+    pub pid: u32,
+    pub cmdline: Vec<String>,
+}
+
+/// The processes to stop, each with the index of the first target it matches.
+/// `spared` (the caller and its parents) are left out.
+pub fn plan<'a>(targets: &[Target], processes: &'a [Process], spared: &[u32]) -> Vec<(usize, &'a Process)> {
+    processes
+        .iter()
+        .filter(|p| !spared.contains(&p.pid))
+        .filter_map(|p| {
+            targets
+                .iter()
+                .position(|t| t.patterns.iter().any(|pattern| matches(&p.cmdline, pattern)))
+                .map(|idx| (idx, p))
+        })
+        .collect()
+}
+
+/// Processes with a command line (kernel threads have none)
+pub fn running_processes() -> Vec<Process> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    let mut processes: Vec<Process> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            let raw = std::fs::read(entry.path().join("cmdline")).ok()?;
+            let cmdline: Vec<String> = raw
+                .split(|b| *b == 0)
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| String::from_utf8_lossy(arg).to_string())
+                .collect();
+            (!cmdline.is_empty()).then_some(Process { pid, cmdline })
+        })
+        .collect();
+    processes.sort_by_key(|p| p.pid);
+    processes
+}
+
+/// This process and its parents (a `cargo run` or shell that started it)
+pub fn own_lineage() -> Vec<u32> {
+    let mut lineage = vec![std::process::id()];
+    while let Some(parent) = lineage.last().and_then(|pid| parent_pid(*pid)) {
+        if parent <= 1 || lineage.contains(&parent) {
+            break;
+        }
+        lineage.push(parent);
+    }
+    lineage
+}
+
+/// "pid (comm) state ppid ..." from /proc, after the comm (which may contain spaces and parentheses)
+fn stat_fields(pid: u32) -> Option<Vec<String>> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    Some(stat.rsplit_once(')')?.1.split_whitespace().map(|s| s.to_string()).collect())
+}
+
+fn parent_pid(pid: u32) -> Option<u32> {
+    stat_fields(pid)?.get(1)?.parse().ok()
+}
+
+fn signal(pid: u32, sig: libc::c_int) -> bool {
+    // SAFETY: kill() has no memory effects; pid comes from /proc
+    unsafe { libc::kill(pid as libc::pid_t, sig) == 0 }
+}
+
+/// Still there and not a zombie waiting for its parent
+fn alive(pid: u32) -> bool {
+    signal(pid, 0) && stat_fields(pid).and_then(|f| f.first().cloned()).is_none_or(|state| state != "Z")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    DryRun,             // Report what would be stopped
+    Graceful(Duration), // SIGTERM, then SIGKILL after the grace period
+    Kill,               // SIGKILL at once
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    WouldStop,  // Dry run
+    Terminated, // Exited on SIGTERM
+    Killed,     // Needed SIGKILL
+    Survived,   // Still running (another user's process, or stuck in the kernel)
+}
+
+impl Outcome {
+    pub fn name(self) -> &'static str {
+        match self {
+            Outcome::WouldStop => "would stop",
+            Outcome::Terminated => "terminated",
+            Outcome::Killed => "killed",
+            Outcome::Survived => "still running",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stopped {
+    pub target: String,
+    pub pid: u32,
+    pub cmdline: String,
+    pub outcome: Outcome,
+}
+
+/// What `terminate` found and did
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub mode: Mode,
+    pub targets: Vec<String>,
+    pub processes: Vec<Stopped>,
+    pub sockets: Vec<PathBuf>, // stepper_gui sockets removed (or that would be)
+}
+
+impl Report {
+    pub fn survivors(&self) -> impl Iterator<Item = &Stopped> {
+        self.processes.iter().filter(|p| p.outcome == Outcome::Survived)
+    }
+
+    /// One line per target and process, for the console and the message log
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for target in &self.targets {
+            let processes: Vec<&Stopped> = self.processes.iter().filter(|p| &p.target == target).collect();
+            if processes.is_empty() {
+                lines.push(format!("{}: not running", target));
+            }
+            for p in processes {
+                lines.push(format!("{}: {} pid {} ({})", target, p.outcome.name(), p.pid, p.cmdline));
+            }
+        }
+        let verb = if self.mode == Mode::DryRun { "would remove" } else { "removed" };
+        for socket in &self.sockets {
+            lines.push(format!("{} {}", verb, socket.display()));
+        }
+        match self.survivors().count() {
+            0 => {}
+            n => lines.push(format!("{} process(es) still running", n)),
+        }
+        lines
+    }
+}
+
+/// Stop the processes of `targets`, sparing this process and its parents
+pub fn terminate(targets: &[Target], mode: Mode) -> Report {
+    let processes = running_processes();
+    let spared = own_lineage();
+    let planned = plan(targets, &processes, &spared);
+    let mut stopped: Vec<Stopped> = planned
+        .iter()
+        .map(|(idx, p)| Stopped {
+            target: targets[*idx].name.clone(),
+            pid: p.pid,
+            cmdline: p.cmdline.join(" "),
+            outcome: Outcome::WouldStop,
+        })
+        .collect();
+
+    let wait_for_exit = |stopped: &[Stopped], timeout: Duration| {
+        let deadline = Instant::now() + timeout;
+        while stopped.iter().any(|p| alive(p.pid)) && Instant::now() < deadline {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    };
+    match mode {
+        Mode::DryRun => {}
+        Mode::Graceful(grace) => {
+            for p in &stopped {
+                signal(p.pid, libc::SIGTERM);
+            }
+            wait_for_exit(&stopped, grace);
+            for p in stopped.iter_mut() {
+                p.outcome = if alive(p.pid) {
+                    signal(p.pid, libc::SIGKILL);
+                    Outcome::Killed
+                } else {
+                    Outcome::Terminated
+                };
+            }
+        }
+        Mode::Kill => {
+            for p in stopped.iter_mut() {
+                signal(p.pid, libc::SIGKILL);
+                p.outcome = Outcome::Killed;
+            }
+        }
+    }
+    if mode != Mode::DryRun {
+        wait_for_exit(&stopped, KILL_WAIT);
+        for p in stopped.iter_mut() {
+            if alive(p.pid) {
+                p.outcome = Outcome::Survived;
+            }
+        }
+    }
+
+    let sockets = if targets.iter().any(Target::stops_stepper_gui) {
+        stale_sockets(mode)
+    } else {
+        Vec::new()
+    };
+    Report { mode, targets: targets.iter().map(|t| t.name.clone()).collect(), processes: stopped, sockets }
+}
+
+/// stepper_gui sockets in SOCKET_DIR, removed unless this is a dry run
+fn stale_sockets(mode: Mode) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(SOCKET_DIR) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(SOCKET_PREFIX) && n.ends_with(".sock"))
+        })
+        .filter(|path| mode == Mode::DryRun || std::fs::remove_file(path).is_ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split(' ').map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_matches_names_and_paths() {
+        assert!(matches(&args("/home/pi/stringdriver/target/release/stepper_gui"), "stepper_gui"));
+        assert!(matches(&args("bash audmon.sh"), "audmon.sh"));
+        assert!(matches(&args("xterm -T Master GUI Persist Monitor -e env PERSIST_CHILD=1"), "Persist Monitor"));
+        assert!(matches(&args("../audmon/target/release/audio_monitor --jack"), "target/release/audio_monitor"));
+        assert!(!matches(&args("vim launcher_notes.txt"), "launcher"));
+        assert!(!matches(&args("less audio_monitor.yaml"), "audio_monitor"));
+
+        let component = config_loader::default_launch_components(true, Path::new("/srv/sd")).remove(0);
+        let target = Target::from_component(&component);
+        assert_eq!(target.patterns, ["bash audmon.sh", "target/release/audio_monitor", "Persist Monitor", "qjackctl"]);
+        assert!(matches(&args("/bin/bash audmon.sh --jack"), "bash audmon.sh"));
+    }
+
+    #[test]
+    fn test_interpreters_are_matched_with_their_script() {
+        let pattern = |line: &str| command_pattern(&args(line));
+        assert_eq!(pattern("target/release/stringdriverd --no-park").as_deref(), Some("stringdriverd"));
+        assert_eq!(pattern("python3 -u tools/bridge.py --port 9000").as_deref(), Some("python3 -u tools/bridge.py"));
+        assert_eq!(pattern("env RUST_LOG=debug target/release/stepper_gui").as_deref(), Some("stepper_gui"));
+        assert_eq!(pattern("env PYTHONPATH=lib python3 monitor.py").as_deref(), Some("python3 monitor.py"));
+        // Nothing that would match every python3 or cargo process
+        assert_eq!(pattern("cargo run --release --bin stringdriverd"), None);
+        assert_eq!(pattern("python3"), None);
+
+        let bridge = "python3 -u tools/bridge.py";
+        assert!(matches(&args("python3 -u tools/bridge.py --port 9000"), bridge));
+        assert!(!matches(&args("python3 -u tools/other.py"), bridge));
+    }
+
+    #[test]
+    fn test_plan_assigns_each_process_once_and_spares_the_caller() {
+        let targets = vec![
+            Target { name: "launcher".to_string(), patterns: vec!["launcher".to_string()] },
+            Target { name: "stepper_gui".to_string(), patterns: vec!["stepper_gui".to_string()] },
+            Target { name: "audio_monitor".to_string(), patterns: vec!["audmon.sh".to_string(), "qjackctl".to_string()] },
+        ];
+        let processes = vec![
+            Process { pid: 10, cmdline: args("cargo run --release --bin launcher -- --stop") },
+            Process { pid: 11, cmdline: args("target/release/launcher --stop") },
+            Process { pid: 20, cmdline: args("target/release/stepper_gui") },
+            Process { pid: 30, cmdline: args("bash audmon.sh") },
+            Process { pid: 31, cmdline: args("qjackctl") },
+            Process { pid: 40, cmdline: args("target/release/operations_gui") },
+        ];
+        let planned: Vec<(usize, u32)> = plan(&targets, &processes, &[11, 10]).iter().map(|(t, p)| (*t, p.pid)).collect();
+        assert_eq!(planned, [(1, 20), (2, 30), (2, 31)]);
+
+        let audio = select(&targets, &["audio_monitor".to_string()]).unwrap();
+        let planned: Vec<u32> = plan(&audio, &processes, &[11]).iter().map(|(_, p)| p.pid).collect();
+        assert_eq!(planned, [30, 31]);
+        assert!(select(&targets, &["audmon".to_string()]).unwrap_err().to_string().contains("known: launcher, stepper_gui"));
+    }
+}
@@ -0,0 +1,156 @@
+/// Stage view: the instrument at a glance from across the stage
+///
+/// A full-screen, high-contrast page with one big status dot per string (green in
+/// range, amber adjusting or out of range, red fault, grey no signal), the X
+/// position in large digits and a big STOP button. operations_gui switches to it
+/// and back with F11 (Escape also leaves); everything else stays in the normal view.
+
+#[cfg(feature = "gui")]
+use crate::i18n::tr;
+#[cfg(feature = "gui")]
+use eframe::egui;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// A string counts as adjusting for this long after one of its Z steppers moved
+pub const ADJUSTING_HOLD: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringStatus {
+    InRange,
+    Adjusting, // A Z stepper moved recently, or the level is outside its band
+    Fault,     // A Z stepper is disabled or its touch sensor quarantined
+    NoSignal,  // No analysis for the string's channel
+}
+
+impl StringStatus {
+    pub fn describe(self) -> &'static str {
+        match self {
+            StringStatus::InRange => "in range",
+            StringStatus::Adjusting => "adjusting",
+            StringStatus::Fault => "fault",
+            StringStatus::NoSignal => "no signal",
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl StringStatus {
+    fn color(self) -> egui::Color32 {
+        match self {
+            StringStatus::InRange => egui::Color32::from_rgb(0, 220, 0),
+            StringStatus::Adjusting => egui::Color32::from_rgb(255, 176, 0),
+            StringStatus::Fault => egui::Color32::from_rgb(255, 0, 0),
+            StringStatus::NoSignal => egui::Color32::from_gray(110),
+        }
+    }
+}
+
+/// What the stage view shows for one string
+#[derive(Debug, Clone)]
+pub struct StringStage {
+    pub label: String,
+    pub status: StringStatus,
+}
+
+/// Fault wins over movement, movement over the audio level. `level` is the channel's
+/// amplitude sum with its (min, max) band; None without analysis.
+pub fn classify(fault: bool, moved_recently: bool, level: Option<(f32, (f32, f32))>) -> StringStatus {
+    if fault {
+        return StringStatus::Fault;
+    }
+    if moved_recently {
+        return StringStatus::Adjusting;
+    }
+    match level {
+        None => StringStatus::NoSignal,
+        Some((amp, (min, max))) if amp >= min && amp <= max => StringStatus::InRange,
+        Some(_) => StringStatus::Adjusting,
+    }
+}
+
+/// Stage view state: when each Z stepper last changed position
+#[derive(Debug, Default)]
+pub struct StageView {
+    last_moves: HashMap<usize, (i32, Option<Instant>)>, // Position last seen, when it last changed
+}
+
+impl StageView {
+    /// Record `position` for a Z stepper; true while it moved within ADJUSTING_HOLD
+    pub fn moved_recently(&mut self, stepper_idx: usize, position: i32, now: Instant) -> bool {
+        let entry = self.last_moves.entry(stepper_idx).or_insert((position, None));
+        if entry.0 != position {
+            *entry = (position, Some(now));
+        }
+        entry.1.is_some_and(|moved| now.duration_since(moved) < ADJUSTING_HOLD)
+    }
+}
+
+/// Draw the stage view filling `ui`; returns true when STOP was pressed
+#[cfg(feature = "gui")]
+pub fn render_stage_view(ui: &mut egui::Ui, strings: &[StringStage], x_position: Option<i32>, activity: &str) -> bool {
+    let mut stop = false;
+    let background = egui::Frame::default().fill(egui::Color32::BLACK).inner_margin(egui::Margin::same(24.0));
+    background.show(ui, |ui| {
+        ui.set_min_size(ui.available_size());
+        ui.vertical_centered(|ui| {
+            let x_text = x_position.map_or("X —".to_string(), |x| format!("X {}", x));
+            ui.label(egui::RichText::new(x_text).size(96.0).strong().color(egui::Color32::WHITE));
+            ui.label(egui::RichText::new(activity).size(36.0).color(egui::Color32::from_gray(200)));
+            ui.add_space(24.0);
+
+            // Dots share the width; each slot at most 220 px across
+            let slot = (ui.available_width() / strings.len().max(1) as f32).min(220.0);
+            let radius = (slot * 0.4).min(90.0);
+            ui.horizontal(|ui| {
+                let used = slot * strings.len() as f32;
+                ui.add_space(((ui.available_width() - used) / 2.0).max(0.0));
+                for string in strings {
+                    ui.allocate_ui(egui::vec2(slot, radius * 2.0 + 90.0), |ui| {
+                        ui.vertical_centered(|ui| {
+                            let (rect, _) = ui.allocate_exact_size(egui::vec2(radius * 2.0, radius * 2.0), egui::Sense::hover());
+                            ui.painter().circle_filled(rect.center(), radius, string.status.color());
+                            ui.label(egui::RichText::new(&string.label).size(28.0).strong().color(egui::Color32::WHITE));
+                            ui.label(egui::RichText::new(tr(string.status.describe())).size(22.0).color(string.status.color()));
+                        });
+                    });
+                }
+            });
+            ui.add_space(32.0);
+
+            let stop_button = egui::Button::new(egui::RichText::new(tr("STOP")).size(72.0).strong().color(egui::Color32::WHITE))
+                .fill(egui::Color32::from_rgb(200, 0, 0))
+                .min_size(egui::vec2(ui.available_width().min(600.0), 160.0));
+            stop = ui.add(stop_button).clicked();
+            ui.add_space(12.0);
+            ui.label(egui::RichText::new(tr("F11 / Esc: back to the full controls")).size(18.0).color(egui::Color32::from_gray(150)));
+        });
+    });
+    stop
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_prefers_fault_then_movement() {
+        let band = (20.0, 250.0);
+        assert_eq!(classify(false, false, Some((100.0, band))), StringStatus::InRange);
+        assert_eq!(classify(false, false, Some((300.0, band))), StringStatus::Adjusting);
+        assert_eq!(classify(false, true, Some((100.0, band))), StringStatus::Adjusting);
+        assert_eq!(classify(true, true, Some((100.0, band))), StringStatus::Fault);
+        assert_eq!(classify(false, false, None), StringStatus::NoSignal);
+    }
+
+    #[test]
+    fn test_moves_hold_adjusting() {
+        let mut view = StageView::default();
+        let start = Instant::now();
+        // First sight of a position is not a move
+        assert!(!view.moved_recently(3, 40, start));
+        assert!(view.moved_recently(3, 38, start + Duration::from_millis(100)));
+        assert!(view.moved_recently(3, 38, start + Duration::from_secs(1)));
+        assert!(!view.moved_recently(3, 38, start + Duration::from_millis(100) + ADJUSTING_HOLD));
+    }
+}
@@ -0,0 +1,462 @@
+/// "What changed" between two machine state snapshots
+///
+/// Reads snapshots back from the machine state database (the machine_state and
+/// operations tables machine_state_logger writes) and compares two of them:
+/// which steppers moved or were enabled/disabled, which control settings and
+/// z_adjust bands changed, and how each channel's voice count and amp sum
+/// shifted. The two points are either times or the start and end of a logged
+/// operation. operations_gui shows the result in its "What Changed" panel, and
+/// reads the logged bumps back for its "Bumps" panel (see bump_log).
+
+use chrono::{DateTime, Duration, Local, NaiveDateTime, TimeZone, Utc};
+use postgres::{Client, NoTls, Row};
+use uuid::Uuid;
+
+use crate::bump_log::BumpEvent;
+use crate::config_loader::DbSettings;
+use crate::error::{Error, Result};
+use crate::machine_state_logger::{MachineStateSnapshot, OperationEvent, StepperRoleEntry};
+
+/// Smallest amp sum change worth reporting (the sums are never exactly equal twice)
+const AMP_SUM_EPSILON: f32 = 0.01;
+
+const SNAPSHOT_COLUMNS: &str = "state_id, controls_id, host, recorded_at, stepper_positions, stepper_enabled, bump_check_enable, z_up_step, z_down_step, tune_rest, x_rest, z_rest, lap_rest, adjustment_level, retry_threshold, delta_threshold, z_variance_threshold, voice_count, amp_sum, voice_count_min, voice_count_max, amp_sum_min, amp_sum_max, build_info";
+
+/// A stepper whose position or enable state differs between the two snapshots
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepperChange {
+    pub index: usize,
+    pub role: Option<String>, // "z_in 2", "x", ... from host_config_stepper_roles
+    pub position: (Option<i32>, Option<i32>),
+    pub enabled: (Option<bool>, Option<bool>),
+}
+
+impl StepperChange {
+    pub fn moved_by(&self) -> Option<i32> {
+        match self.position {
+            (Some(from), Some(to)) => Some(to - from),
+            _ => None,
+        }
+    }
+}
+
+/// A control setting (or one channel's z_adjust band edge) before and after
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingChange {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// One channel's analysis values before and after
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelShift {
+    pub channel: usize,
+    pub voice_count: (Option<i32>, Option<i32>),
+    pub amp_sum: (Option<f32>, Option<f32>),
+}
+
+/// Where one side of a diff came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotRef {
+    pub state_id: Uuid,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotDiff {
+    pub before: SnapshotRef,
+    pub after: SnapshotRef,
+    pub steppers: Vec<StepperChange>,
+    pub settings: Vec<SettingChange>,
+    pub channels: Vec<ChannelShift>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.steppers.is_empty() && self.settings.is_empty() && self.channels.is_empty()
+    }
+
+    /// Plain-text report, one change per line (for the Copy button and the log)
+    pub fn summary_lines(&self) -> Vec<String> {
+        let show = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+        let mut lines = vec![format!(
+            "{} -> {} ({:.0} s)",
+            self.before.recorded_at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+            self.after.recorded_at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+            (self.after.recorded_at - self.before.recorded_at).num_milliseconds() as f64 / 1000.0
+        )];
+        if self.is_empty() {
+            lines.push("Nothing changed".to_string());
+        }
+        for stepper in &self.steppers {
+            let name = stepper.role.clone().map_or(format!("stepper {}", stepper.index), |r| format!("stepper {} ({})", stepper.index, r));
+            if stepper.position.0 != stepper.position.1 {
+                let delta = stepper.moved_by().map(|d| format!(" ({:+})", d)).unwrap_or_default();
+                lines.push(format!(
+                    "{} moved {} -> {}{}",
+                    name,
+                    show(stepper.position.0.map(|p| p.to_string())),
+                    show(stepper.position.1.map(|p| p.to_string())),
+                    delta
+                ));
+            }
+            if stepper.enabled.0 != stepper.enabled.1 {
+                let state = |e: Option<bool>| show(e.map(|e| if e { "enabled" } else { "disabled" }.to_string()));
+                lines.push(format!("{} {} -> {}", name, state(stepper.enabled.0), state(stepper.enabled.1)));
+            }
+        }
+        for setting in &self.settings {
+            lines.push(format!("{}: {} -> {}", setting.name, setting.from, setting.to));
+        }
+        for shift in &self.channels {
+            lines.push(format!(
+                "channel {}: voices {} -> {}, amp sum {} -> {}",
+                shift.channel + 1,
+                show(shift.voice_count.0.map(|v| v.to_string())),
+                show(shift.voice_count.1.map(|v| v.to_string())),
+                show(shift.amp_sum.0.map(|a| format!("{:.2}", a))),
+                show(shift.amp_sum.1.map(|a| format!("{:.2}", a)))
+            ));
+        }
+        lines
+    }
+}
+
+/// Compare two snapshots of the same host
+pub fn diff_snapshots(before: &MachineStateSnapshot, after: &MachineStateSnapshot) -> SnapshotDiff {
+    let role_of = |idx: usize| {
+        after.stepper_roles.iter().chain(before.stepper_roles.iter()).find(|r| r.stepper_index == idx).map(|r| match r.string_index {
+            Some(string) => format!("{} {}", r.role, string),
+            None => r.role.clone(),
+        })
+    };
+    let steppers = (0..before.stepper_positions.len().max(after.stepper_positions.len()))
+        .filter_map(|idx| {
+            let position = (before.stepper_positions.get(idx).copied(), after.stepper_positions.get(idx).copied());
+            let enabled = (before.stepper_enabled.get(idx).copied(), after.stepper_enabled.get(idx).copied());
+            (position.0 != position.1 || enabled.0 != enabled.1).then(|| StepperChange { index: idx, role: role_of(idx), position, enabled })
+        })
+        .collect();
+
+    let mut settings = Vec::new();
+    let mut compare = |name: &str, from: String, to: String| {
+        if from != to {
+            settings.push(SettingChange { name: name.to_string(), from, to });
+        }
+    };
+    compare("bump_check_enable", before.bump_check_enable.to_string(), after.bump_check_enable.to_string());
+    compare("z_up_step", before.z_up_step.to_string(), after.z_up_step.to_string());
+    compare("z_down_step", before.z_down_step.to_string(), after.z_down_step.to_string());
+    compare("tune_rest", before.tune_rest.to_string(), after.tune_rest.to_string());
+    compare("x_rest", before.x_rest.to_string(), after.x_rest.to_string());
+    compare("z_rest", before.z_rest.to_string(), after.z_rest.to_string());
+    compare("lap_rest", before.lap_rest.to_string(), after.lap_rest.to_string());
+    compare("adjustment_level", before.adjustment_level.to_string(), after.adjustment_level.to_string());
+    compare("retry_threshold", before.retry_threshold.to_string(), after.retry_threshold.to_string());
+    compare("delta_threshold", before.delta_threshold.to_string(), after.delta_threshold.to_string());
+    compare("z_variance_threshold", before.z_variance_threshold.to_string(), after.z_variance_threshold.to_string());
+    for (name, from, to) in [
+        ("voice_count_min", &before.voice_count_min, &after.voice_count_min),
+        ("voice_count_max", &before.voice_count_max, &after.voice_count_max),
+        ("amp_sum_min", &before.amp_sum_min, &after.amp_sum_min),
+        ("amp_sum_max", &before.amp_sum_max, &after.amp_sum_max),
+    ] {
+        for channel in 0..from.len().max(to.len()) {
+            let show = |v: Option<&i32>| v.map_or("-".to_string(), |v| v.to_string());
+            compare(&format!("{}[{}]", name, channel + 1), show(from.get(channel)), show(to.get(channel)));
+        }
+    }
+    compare("build_info", before.build_info.clone(), after.build_info.clone());
+
+    let channels = (0..before.voice_count.len().max(after.voice_count.len()).max(before.amp_sum.len()).max(after.amp_sum.len()))
+        .filter_map(|channel| {
+            let voice_count = (before.voice_count.get(channel).copied(), after.voice_count.get(channel).copied());
+            let amp_sum = (before.amp_sum.get(channel).copied(), after.amp_sum.get(channel).copied());
+            let amp_changed = match amp_sum {
+                (Some(from), Some(to)) => (to - from).abs() >= AMP_SUM_EPSILON,
+                (from, to) => from.is_some() != to.is_some(),
+            };
+            (voice_count.0 != voice_count.1 || amp_changed).then_some(ChannelShift { channel, voice_count, amp_sum })
+        })
+        .collect();
+
+    SnapshotDiff {
+        before: SnapshotRef { state_id: before.state_id, recorded_at: before.recorded_at },
+        after: SnapshotRef { state_id: after.state_id, recorded_at: after.recorded_at },
+        steppers,
+        settings,
+        channels,
+    }
+}
+
+/// A point in time as typed in the panel: "now", minutes/hours/seconds ago
+/// ("-10m", "-2h", "-30s") or a local "YYYY-MM-DD HH:MM[:SS]"
+pub fn parse_time(text: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if text.eq_ignore_ascii_case("now") {
+        return Some(now);
+    }
+    if let Some(ago) = text.strip_prefix('-') {
+        let (number, unit) = ago.split_at(ago.len().checked_sub(1)?);
+        let number: i64 = number.trim().parse().ok()?;
+        let ago = match unit {
+            "s" => Duration::seconds(number),
+            "m" => Duration::minutes(number),
+            "h" => Duration::hours(number),
+            "d" => Duration::days(number),
+            _ => return None,
+        };
+        return Some(now - ago);
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+        .map(|local| local.with_timezone(&Utc))
+}
+
+/// Read-only connection to the machine state database
+pub struct StateHistory {
+    client: Client,
+}
+
+impl StateHistory {
+    pub fn connect(db_config: &DbSettings) -> Result<Self> {
+        let connection_str = format!(
+            "host={} port={} user={} password={} dbname={}",
+            db_config.host, db_config.port, db_config.user, db_config.password, db_config.database,
+        );
+        let client = Client::connect(&connection_str, NoTls).map_err(db_error("Failed to connect to machine state database"))?;
+        Ok(Self { client })
+    }
+
+    /// The last snapshot recorded at or before `at`
+    pub fn snapshot_before(&mut self, host: &str, at: DateTime<Utc>) -> Result<Option<MachineStateSnapshot>> {
+        let query = format!("SELECT {} FROM machine_state WHERE host = $1 AND recorded_at <= $2 ORDER BY recorded_at DESC LIMIT 1", SNAPSHOT_COLUMNS);
+        self.snapshot(&query, host, &at)
+    }
+
+    /// The first snapshot recorded at or after `at`
+    pub fn snapshot_after(&mut self, host: &str, at: DateTime<Utc>) -> Result<Option<MachineStateSnapshot>> {
+        let query = format!("SELECT {} FROM machine_state WHERE host = $1 AND recorded_at >= $2 ORDER BY recorded_at ASC LIMIT 1", SNAPSHOT_COLUMNS);
+        self.snapshot(&query, host, &at)
+    }
+
+    fn snapshot_by_id(&mut self, host: &str, state_id: Uuid) -> Result<Option<MachineStateSnapshot>> {
+        let query = format!("SELECT {} FROM machine_state WHERE host = $1 AND state_id = $2", SNAPSHOT_COLUMNS);
+        self.snapshot(&query, host, &state_id)
+    }
+
+    fn snapshot(&mut self, query: &str, host: &str, param: &(dyn postgres::types::ToSql + Sync)) -> Result<Option<MachineStateSnapshot>> {
+        let row = self.client.query_opt(query, &[&host, param]).map_err(db_error("Failed to read machine_state"))?;
+        let Some(row) = row else { return Ok(None) };
+        let mut snapshot = snapshot_from_row(&row)?;
+        snapshot.stepper_roles = self.stepper_roles(host)?;
+        Ok(Some(snapshot))
+    }
+
+    /// Roles as last synced for the host (older databases may not have the table yet)
+    fn stepper_roles(&mut self, host: &str) -> Result<Vec<StepperRoleEntry>> {
+        let rows = match self.client.query("SELECT stepper_index, role, string_index FROM host_config_stepper_roles WHERE host = $1", &[&host]) {
+            Ok(rows) => rows,
+            Err(_) => return Ok(Vec::new()),
+        };
+        Ok(rows
+            .iter()
+            .map(|row| StepperRoleEntry {
+                stepper_index: row.get::<_, i32>(0) as usize,
+                role: row.get(1),
+                string_index: row.get::<_, Option<i32>>(2).map(|s| s as usize),
+            })
+            .collect())
+    }
+
+    /// The host's most recent logged operations, newest first
+    pub fn recent_operations(&mut self, host: &str, limit: i64) -> Result<Vec<OperationEvent>> {
+        let rows = self.client.query(
+            "SELECT operation_id, state_id, host, recorded_at, operation_type, operation_status, message, stepper_indices, final_positions, started_at \
+             FROM operations WHERE host = $1 ORDER BY recorded_at DESC LIMIT $2",
+            &[&host, &limit],
+        ).map_err(db_error("Failed to read operations"))?;
+        Ok(rows
+            .iter()
+            .map(|row| OperationEvent {
+                operation_id: row.get(0),
+                state_id: row.get(1),
+                host: row.get(2),
+                recorded_at: row.get(3),
+                operation_type: row.get(4),
+                operation_status: row.get(5),
+                message: row.get::<_, Option<String>>(6).unwrap_or_default(),
+                stepper_indices: row.get::<_, Vec<i32>>(7).into_iter().map(|i| i as usize).collect(),
+                final_positions: row.get(8),
+                started_at: row.get(9),
+            })
+            .collect())
+    }
+
+    /// The host's bumps since `since`, oldest first
+    pub fn bump_events(&mut self, host: &str, since: DateTime<Utc>) -> Result<Vec<BumpEvent>> {
+        let rows = self.client.query(
+            "SELECT host, recorded_at, stepper_index, string_index, channel_index, x_position, z_position, z_down_step, amp_sum, amp_baseline \
+             FROM bump_events WHERE host = $1 AND recorded_at >= $2 ORDER BY recorded_at ASC",
+            &[&host, &since],
+        ).map_err(db_error("Failed to read bump_events"))?;
+        let index = |idx: Option<i32>| idx.map(|i| i as usize);
+        Ok(rows
+            .iter()
+            .map(|row| BumpEvent {
+                host: row.get(0),
+                recorded_at: row.get(1),
+                stepper: row.get::<_, i32>(2) as usize,
+                string: index(row.get(3)),
+                channel: index(row.get(4)),
+                x_position: row.get(5),
+                z_position: row.get(6),
+                z_down_step: row.get(7),
+                amp_sum: row.get(8),
+                amp_baseline: row.get(9),
+            })
+            .collect())
+    }
+
+    /// Diff the snapshots around two times
+    pub fn diff_times(&mut self, host: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<SnapshotDiff> {
+        let before = self.snapshot_before(host, from)?
+            .or(self.snapshot_after(host, from)?)
+            .ok_or_else(|| Error::Other(format!("No snapshot for {} near {}", host, from.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"))))?;
+        let after = self.snapshot_before(host, to)?
+            .filter(|s| s.recorded_at > before.recorded_at)
+            .ok_or_else(|| Error::Other(format!("No snapshot for {} between the two times", host)))?;
+        Ok(diff_snapshots(&before, &after))
+    }
+
+    /// Diff the state before an operation started against the state after it finished
+    pub fn diff_operation(&mut self, operation: &OperationEvent) -> Result<SnapshotDiff> {
+        let host = operation.host.as_str();
+        let started = operation.started_at.unwrap_or(operation.recorded_at);
+        let before = match operation.state_id {
+            Some(id) => self.snapshot_by_id(host, id)?,
+            None => None,
+        };
+        let before = match before {
+            Some(snapshot) => snapshot,
+            None => self.snapshot_before(host, started)?
+                .ok_or_else(|| Error::Other(format!("No snapshot before {} started", operation.operation_type)))?,
+        };
+        let after = self.snapshot_after(host, operation.recorded_at)?
+            .ok_or_else(|| Error::Other(format!("No snapshot after {} finished yet", operation.operation_type)))?;
+        Ok(diff_snapshots(&before, &after))
+    }
+}
+
+/// A database error with what was being done
+fn db_error(context: &'static str) -> impl FnOnce(postgres::Error) -> Error {
+    move |e| Error::Other(format!("{}: {}", context, e))
+}
+
+fn snapshot_from_row(row: &Row) -> Result<MachineStateSnapshot> {
+    read_snapshot(row).map_err(db_error("Unreadable machine_state row"))
+}
+
+fn read_snapshot(row: &Row) -> std::result::Result<MachineStateSnapshot, postgres::Error> {
+    Ok(MachineStateSnapshot {
+        state_id: row.try_get(0)?,
+        controls_id: row.try_get::<_, Option<String>>(1)?.and_then(|id| Uuid::parse_str(&id).ok()),
+        host: row.try_get(2)?,
+        recorded_at: row.try_get(3)?,
+        stepper_positions: row.try_get(4)?,
+        stepper_enabled: row.try_get(5)?,
+        bump_check_enable: row.try_get(6)?,
+        z_up_step: row.try_get(7)?,
+        z_down_step: row.try_get(8)?,
+        tune_rest: row.try_get(9)?,
+        x_rest: row.try_get(10)?,
+        z_rest: row.try_get(11)?,
+        lap_rest: row.try_get(12)?,
+        adjustment_level: row.try_get(13)?,
+        retry_threshold: row.try_get(14)?,
+        delta_threshold: row.try_get(15)?,
+        z_variance_threshold: row.try_get(16)?,
+        voice_count: row.try_get(17)?,
+        amp_sum: row.try_get(18)?,
+        voice_count_min: row.try_get(19)?,
+        voice_count_max: row.try_get(20)?,
+        amp_sum_min: row.try_get(21)?,
+        amp_sum_max: row.try_get(22)?,
+        stepper_roles: Vec::new(),
+        channel_strings: Vec::new(),
+        build_info: row.try_get::<_, Option<String>>(23)?.unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(positions: Vec<i32>, voice_count: Vec<i32>, amp_sum: Vec<f32>) -> MachineStateSnapshot {
+        MachineStateSnapshot {
+            state_id: Uuid::new_v4(),
+            controls_id: None,
+            host: "stringdriver-1".to_string(),
+            recorded_at: Utc::now(),
+            stepper_enabled: vec![true; positions.len()],
+            stepper_positions: positions,
+            bump_check_enable: true,
+            z_up_step: 2,
+            z_down_step: -2,
+            tune_rest: 1.0,
+            x_rest: 1.0,
+            z_rest: 1.0,
+            lap_rest: 4.0,
+            adjustment_level: 4,
+            retry_threshold: 50,
+            delta_threshold: 50,
+            z_variance_threshold: 50,
+            voice_count_min: vec![2; voice_count.len()],
+            voice_count_max: vec![8; voice_count.len()],
+            amp_sum_min: vec![20; voice_count.len()],
+            amp_sum_max: vec![250; voice_count.len()],
+            voice_count,
+            amp_sum,
+            stepper_roles: vec![StepperRoleEntry { stepper_index: 3, role: "z_in".to_string(), string_index: Some(0) }],
+            channel_strings: Vec::new(),
+            build_info: "stepper_gui 0.1.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_diff_reports_moves_settings_and_channel_shifts() {
+        let before = snapshot(vec![0, 0, 500, 40, 40], vec![3, 5], vec![80.0, 120.0]);
+        let mut after = snapshot(vec![0, 0, 500, 36, 40], vec![3, 7], vec![80.004, 150.5]);
+        after.recorded_at = before.recorded_at + Duration::seconds(90);
+        after.stepper_enabled[4] = false;
+        after.z_down_step = -4;
+        after.voice_count_max[1] = 9;
+
+        let diff = diff_snapshots(&before, &after);
+        assert_eq!(diff.steppers.len(), 2);
+        assert_eq!((diff.steppers[0].index, diff.steppers[0].role.as_deref(), diff.steppers[0].moved_by()), (3, Some("z_in 0"), Some(-4)));
+        assert_eq!((diff.steppers[1].index, diff.steppers[1].enabled), (4, (Some(true), Some(false))));
+        let names: Vec<&str> = diff.settings.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["z_down_step", "voice_count_max[2]"]);
+        // Channel 1's amp sum moved by less than AMP_SUM_EPSILON
+        assert_eq!(diff.channels, vec![ChannelShift { channel: 1, voice_count: (Some(5), Some(7)), amp_sum: (Some(120.0), Some(150.5)) }]);
+        let lines = diff.summary_lines();
+        assert!(lines.contains(&"stepper 3 (z_in 0) moved 40 -> 36 (-4)".to_string()));
+        assert!(lines.contains(&"z_down_step: -2 -> -4".to_string()));
+        assert!(diff_snapshots(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let now = Utc::now();
+        assert_eq!(parse_time("now", now), Some(now));
+        assert_eq!(parse_time("-10m", now), Some(now - Duration::minutes(10)));
+        assert_eq!(parse_time(" -2h ", now), Some(now - Duration::hours(2)));
+        assert!(parse_time("2026-03-01 14:05", now).is_some());
+        assert_eq!(parse_time("-10x", now), None);
+        assert_eq!(parse_time("yesterday", now), None);
+    }
+}
@@ -0,0 +1,216 @@
+/// Read-only machine status file for front-of-house tooling
+///
+/// operations_gui (or stringdriverd) rewrites STATUS_FILE.PATH every
+/// STATUS_FILE.INTERVAL seconds with a complete snapshot; readers get health without any control over the machine.
+/// The file is replaced atomically, so a reader never sees half a snapshot.
+///
+/// Schema (version 1), all keys always present:
+///
+/// ```json
+/// {
+///   "schema": 1,
+///   "host": "stringdriver-2",
+///   "written_at": "2026-10-16T20:15:02.125+02:00",
+///   "positions": { "0": 1200, "1": 14, "2": 15 },
+///   "enabled": { "1": true, "2": false },
+///   "analysis": { "voice_count": [3, 4], "amp_sum": [41.5, 38.0], "partials_age_ms": 12 },
+///   "state": { "name": "operating", "detail": "z_adjust", "calibrated": true, "seconds_in_state": 8.5 },
+///   "operation": "z_adjust",
+///   "last_operation": { "name": "bump_check", "succeeded": true, "outcome": "succeeded", "seconds_ago": 42.0 },
+///   "last_sweep": { "direction": "ping_pong_move", "converged": false, "laps": [
+///     { "from": 100, "to": 900, "positions": 40, "z_moves": 212, "attempts": 131,
+///       "avg_attempts": 3.3, "calibrations": 2, "seconds": 610.4, "cancelled": false } ] },
+///   "health": [ { "name": "Arduino main", "level": "ok", "detail": "connected" } ],
+///   "recent_errors": [ "20:14:58.301 WARN  Stepper link down; queued 'set_speed 1 100'" ],
+///   "build": { "operations_gui": { "component": "operations_gui", "version": "0.1.0", "git": "3f2c1aa",
+///     "protocol": 1, "firmware": null }, "stepper_gui": { ..., "firmware": 1 } }
+/// }
+/// ```
+///
+/// - `positions` / `enabled`: stepper index -> last known position / enable state
+/// - `partials_age_ms`: time since the last audio frame, null before the first one
+/// - `state.name`: one of uninitialized, homing, calibrated, idle, operating, paused,
+///   faulted, parked; `detail` is the running operation or fault reason, else null
+/// - `operation`: the running operation, null when idle
+/// - `last_operation`: null until an operation has finished; `outcome` is one of
+///   succeeded, failed, timeout (stopped by its MAX_DURATION, Z retracted)
+/// - `last_sweep`: lap statistics of the running or last X sweep, null before the
+///   first; fewer `z_moves` and `avg_attempts` per lap means the strings are converging
+/// - `health[].level`: one of ok, warn, error, unknown (as in the health panel)
+/// - `recent_errors`: the latest WARN and ERROR log lines, oldest first
+/// - `build.stepper_gui`: its answer to the version handshake, null until it answered;
+///   `firmware` is the main board's protocol, null when the board did not say. From
+///   stringdriverd both entries describe the daemon, which owns the boards itself
+///
+/// Fields may be added within a schema version; renames or removals bump `schema`.
+
+use crate::build_info::BuildInfo;
+use crate::logging::LogBuffer;
+use crate::operations::{OperationOutcome, Operations};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Instant;
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusSnapshot {
+    pub schema: u32,
+    pub host: String,
+    pub written_at: String,
+    pub positions: BTreeMap<usize, i32>,
+    pub enabled: BTreeMap<usize, bool>,
+    pub analysis: AnalysisStatus,
+    pub state: StateStatus,
+    pub operation: Option<String>,
+    pub last_operation: Option<LastOperation>,
+    pub last_sweep: Option<SweepStatus>,
+    pub health: Vec<HealthStatus>,
+    pub recent_errors: Vec<String>,
+    pub build: BuildStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisStatus {
+    pub voice_count: Vec<usize>,
+    pub amp_sum: Vec<f32>,
+    pub partials_age_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StateStatus {
+    pub name: String,
+    pub detail: Option<String>,
+    pub calibrated: bool,
+    pub seconds_in_state: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LastOperation {
+    pub name: String,
+    pub succeeded: bool,
+    pub outcome: String,
+    pub seconds_ago: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SweepStatus {
+    pub direction: String,
+    pub converged: bool,
+    pub laps: Vec<LapStatus>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LapStatus {
+    pub from: i32,
+    pub to: i32,
+    pub positions: u32,
+    pub z_moves: u64,
+    pub attempts: u32,
+    pub avg_attempts: f32,
+    pub calibrations: u32,
+    pub seconds: f32,
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildStatus {
+    pub operations_gui: BuildInfo,
+    pub stepper_gui: Option<BuildInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthStatus {
+    pub name: String,
+    pub level: String,
+    pub detail: String,
+}
+
+impl StatusSnapshot {
+    /// What Operations knows (analysis, enables, state, last sweep), stamped now;
+    /// the caller fills in positions, the running and last operation, health and errors
+    pub fn from_operations(host: &str, ops: &Operations, build: BuildStatus) -> Self {
+        let analysis = AnalysisStatus {
+            voice_count: ops.get_voice_count(),
+            amp_sum: ops.get_amp_sum(),
+            partials_age_ms: ops.partials_age().map(|age| age.as_millis() as u64),
+        };
+        let (state, calibrated, since) = ops.instrument_state();
+        let state = StateStatus {
+            name: state.name().to_string(),
+            detail: state.detail().map(str::to_string),
+            calibrated,
+            seconds_in_state: since.elapsed().as_secs_f32(),
+        };
+        let last_sweep = ops.get_last_sweep().map(|sweep| SweepStatus {
+            direction: sweep.direction.name().to_string(),
+            converged: sweep.converged,
+            laps: sweep.laps.iter().map(|lap| LapStatus {
+                from: lap.from,
+                to: lap.to,
+                positions: lap.positions,
+                z_moves: lap.z_moves,
+                attempts: lap.attempts,
+                avg_attempts: lap.avg_attempts(),
+                calibrations: lap.calibrations,
+                seconds: lap.elapsed.as_secs_f32(),
+                cancelled: lap.cancelled,
+            }).collect(),
+        });
+        Self {
+            schema: SCHEMA_VERSION,
+            host: host.to_string(),
+            written_at: chrono::Local::now().to_rfc3339(),
+            positions: BTreeMap::new(),
+            enabled: ops.get_all_stepper_enabled().into_iter().collect(),
+            analysis,
+            state,
+            operation: None,
+            last_operation: None,
+            last_sweep,
+            health: Vec::new(),
+            recent_errors: Vec::new(),
+            build,
+        }
+    }
+}
+
+impl LastOperation {
+    /// `name` finished with `outcome` at `at`
+    pub fn new(name: &str, outcome: OperationOutcome, at: Instant) -> Self {
+        Self {
+            name: name.to_string(),
+            succeeded: outcome.succeeded(),
+            outcome: outcome.name().to_string(),
+            seconds_ago: at.elapsed().as_secs_f32(),
+        }
+    }
+}
+
+/// The latest `count` WARN and ERROR lines of the log buffer, oldest first
+pub fn recent_errors(log: &LogBuffer, count: usize) -> Vec<String> {
+    log.lock()
+        .map(|lines| {
+            let mut errors: Vec<String> = lines.iter()
+                .rev()
+                .filter(|line| matches!(line.split_whitespace().nth(1), Some("WARN") | Some("ERROR")))
+                .take(count)
+                .cloned()
+                .collect();
+            errors.reverse();
+            errors
+        })
+        .unwrap_or_default()
+}
+
+/// Write the snapshot through a temporary file and rename it into place
+pub fn write(path: &Path, snapshot: &StatusSnapshot) -> std::io::Result<()> {
+    let json = serde_json::to_string_pretty(snapshot).map_err(std::io::Error::other)?;
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, path)
+}
@@ -0,0 +1,4328 @@
+/// Stepper core - the boards, the IPC handler and the position cache
+///
+/// StepperCore owns the main and tuner Arduino boards (one SerialWorker thread
+/// each), keeps the last positions they reported, and serves the socket and
+/// STEPPER_BRIDGE protocol operations_gui and master_gui drive it through. The
+/// stepper_gui binary puts a window on it and stringdriverd runs it headless;
+/// the egui panels are behind the `gui` feature.
+
+#[cfg(feature = "gui")]
+use eframe::egui;
+use std::thread;
+use std::time::Duration;
+use serialport;
+use std::io::{Read, Write};
+use std::process::Command;
+#[cfg(feature = "gui")]
+use egui::Color32;
+use std::os::unix::net::UnixListener;
+use std::sync::{Arc, Mutex, mpsc};
+use std::collections::BTreeMap;
+use std::time::Instant;
+use std::path::Path;
+
+use crate::{bow_drive, error, position_model, profiling, role, tension, topology};
+use crate::access::{self, AccessPolicy, Allow, Grant};
+use crate::arduino_connection::{self, UsbMatch};
+use crate::audit_log::{AuditEntry, AuditLog};
+use crate::build_info::{self, BuildInfo};
+use crate::config_loader::{self, ArduinoFirmware, MotionParams};
+#[cfg(feature = "gui")]
+use crate::i18n::{tr, trf};
+use crate::logging::{self, LogBuffer};
+use crate::position_model::{Discrepancy, PositionModel};
+use crate::shared_state::SharedState;
+use crate::stepper_link::{self, LinkStream};
+use crate::transport::Transport;
+#[cfg(feature = "fault-injection")]
+use crate::fault_injection;
+#[cfg(feature = "gui")]
+use crate::gui_state;
+#[cfg(test)]
+use crate::loopback_serial;
+
+/// Steppers that share one min/max pair in the firmware's `minmax` table
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LimitGroup {
+    X,
+    Z,
+    Tuners,
+}
+
+impl LimitGroup {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "x" => Some(LimitGroup::X),
+            "z" => Some(LimitGroup::Z),
+            "tuner" | "tuners" => Some(LimitGroup::Tuners),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            LimitGroup::X => "X",
+            LimitGroup::Z => "Z",
+            LimitGroup::Tuners => "tuners",
+        }
+    }
+}
+
+/// set_min/set_max's first argument for each group: a row of the firmware's `minmax`
+/// table, not a stepper index. None where the board keeps no separate row for the group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct LimitGroups {
+    x: Option<i16>,
+    z: Option<i16>,
+    tuners: Option<i16>,
+}
+
+impl LimitGroups {
+    /// v2/v3 carriage firmware: row 0 is X (stepper 0), row 1 every other stepper
+    const CARRIAGE: Self = Self { x: Some(0), z: Some(1), tuners: None };
+    /// v1 firmware: tuners (steppers 0-1), gantry X (stepper 2), coils Z (the rest)
+    const V1: Self = Self { x: Some(1), z: Some(2), tuners: Some(0) };
+    /// Tuner board firmware: one row shared by every tuner
+    const TUNER_BOARD: Self = Self { x: None, z: None, tuners: Some(0) };
+
+    fn get(&self, group: LimitGroup) -> Option<i16> {
+        match group {
+            LimitGroup::X => self.x,
+            LimitGroup::Z => self.z,
+            LimitGroup::Tuners => self.tuners,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CommandSet {
+    positions_cmd: &'static [u8],
+    amove_id: u8,
+    rmove_id: u8,
+    set_stepper_id: u8,
+    set_accel_id: u8,
+    set_speed_id: u8,
+    set_min_id: u8,
+    set_max_id: u8,
+    limit_groups: LimitGroups, // Which minmax row set_min/set_max write for each group
+    position_bytes: usize, // Width of each position in the positions reply (2 = i16, 4 = i32)
+    encoders_cmd: Option<&'static [u8]>, // Encoder read query, if the firmware has encoders
+    set_encoder_id: Option<u8>, // Re-zero an encoder alongside set_stepper
+    enable_id: Option<u8>, // set_enable <stepper> <1|0>: energize or cut a driver (None = no current control)
+    version_cmd: Option<&'static [u8]>, // Firmware protocol query, answered with one value
+    checksum: bool, // Replies end with a CRC-8 argument (ARD_CHECKSUM / ARD_T_CHECKSUM)
+    positions_timeout: Duration, // Wait for a positions reply (ARD_REPLY_TIMEOUTS)
+    encoders_timeout: Duration,
+}
+
+impl CommandSet {
+    const fn new(
+        positions_cmd: &'static [u8],
+        amove_id: u8,
+        rmove_id: u8,
+        set_stepper_id: u8,
+        set_accel_id: u8,
+        set_speed_id: u8,
+        set_min_id: u8,
+        set_max_id: u8,
+        position_bytes: usize,
+    ) -> Self {
+        Self {
+            positions_cmd,
+            amove_id,
+            rmove_id,
+            set_stepper_id,
+            set_accel_id,
+            set_speed_id,
+            set_min_id,
+            set_max_id,
+            limit_groups: LimitGroups::CARRIAGE,
+            position_bytes,
+            encoders_cmd: None,
+            set_encoder_id: None,
+            enable_id: None,
+            version_cmd: None,
+            checksum: false,
+            positions_timeout: Duration::from_secs(2),
+            encoders_timeout: Duration::from_secs(2),
+        }
+    }
+
+    fn with_encoders(self, encoders_cmd: &'static [u8], set_encoder_id: u8) -> Self {
+        Self {
+            encoders_cmd: Some(encoders_cmd),
+            set_encoder_id: Some(set_encoder_id),
+            ..self
+        }
+    }
+
+    fn with_driver_enable(self, enable_id: u8) -> Self {
+        Self { enable_id: Some(enable_id), ..self }
+    }
+
+    fn with_version_query(self, version_cmd: &'static [u8]) -> Self {
+        Self { version_cmd: Some(version_cmd), ..self }
+    }
+
+    fn with_limit_groups(self, limit_groups: LimitGroups) -> Self {
+        Self { limit_groups, ..self }
+    }
+
+    /// The same command IDs on a tuner board, whose limits are one row for all tuners
+    fn on_tuner_board(self) -> Self {
+        self.with_limit_groups(LimitGroups::TUNER_BOARD)
+    }
+
+    fn for_firmware(firmware: ArduinoFirmware) -> Self {
+        match firmware {
+            ArduinoFirmware::StringDriverV1 => CommandSet::new(b"2;", 3, 4, 7, 8, 9, 10, 11, 2).with_limit_groups(LimitGroups::V1),
+            ArduinoFirmware::StringDriverV2 => CommandSet::new(b"1;", 2, 3, 6, 7, 8, 9, 10, 2),
+            ArduinoFirmware::StringDriverV3 => CommandSet::new(b"1;", 2, 3, 6, 7, 8, 9, 10, 4).with_encoders(b"11;", 12).with_driver_enable(13).with_version_query(b"14;"),
+        }
+    }
+}
+
+/// Which Arduino a serial thread talks to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Board {
+    Main,
+    Tuner,
+}
+
+/// Work queued for a board's serial thread
+#[derive(Debug)]
+enum SerialRequest {
+    /// Send a CmdMessenger command; with `refresh_after`, wait that long and then read positions.
+    /// Motion commands carry an audit entry, recorded with the position read back afterwards.
+    Command { cmd_id: u8, stepper: i16, value: i32, refresh_after: Option<Duration>, audit: Option<AuditEntry> },
+    RefreshPositions,
+    QueryFirmwareVersion,
+    /// Answer once everything queued before it has been handled
+    Flush(mpsc::Sender<()>),
+}
+
+/// Results reported back by a serial thread, applied by StepperCore::drain_serial_events()
+#[derive(Debug)]
+enum SerialEvent {
+    Positions(Board, Vec<i32>),
+    Encoders(Board, Vec<Option<i32>>), // None where a stepper has no encoder
+    PositionsFailed(Board, QueryError), // Positions unreadable; the last reported values are stale
+    FirmwareVersion(Board, Option<i32>), // Firmware protocol, None when the query went unanswered
+    CommandStarted(Board, u8, i16), // (cmd_id, stepper) written to the board
+    CommandDone(Board, u8, i16), // (cmd_id, stepper) finished, positions read back if asked for
+}
+
+/// Why a query produced no values
+#[derive(Debug, Clone, PartialEq)]
+enum QueryError {
+    /// No valid reply arrived within the timeout
+    TimedOut { what: &'static str, after: Duration },
+    /// The port itself failed
+    Port(String),
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryError::TimedOut { what, after } => write!(f, "no valid {} reply within {}ms", what, after.as_millis()),
+            QueryError::Port(e) => write!(f, "read failed: {}", e),
+        }
+    }
+}
+
+impl QueryError {
+    /// Socket form: "timeout <ms>" or "port <message>"
+    fn to_wire(&self) -> String {
+        match self {
+            QueryError::TimedOut { after, .. } => format!("timeout {}", after.as_millis()),
+            QueryError::Port(e) => format!("port {}", e),
+        }
+    }
+}
+
+/// Encoder reply value for a stepper with no encoder fitted
+const ENCODER_ABSENT: i32 = i32::MIN;
+
+/// Minimum gap between consecutive commands written to one board
+const SERIAL_COMMAND_GAP: Duration = Duration::from_millis(10);
+
+/// Once a board has stopped answering, queries only wait this long until it replies
+/// again, so a wedged board doesn't hold every refresh for the full timeout
+const WEDGED_PROBE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Open a board's link (behind the fault injector in fault-injection builds)
+fn open_port(transport: &Transport, port_path: &str) -> serialport::Result<Box<dyn serialport::SerialPort>> {
+    let opened = transport.open(port_path, Duration::from_secs(2));
+    #[cfg(feature = "fault-injection")]
+    let opened = opened.map(fault_injection::wrap_port);
+    opened
+}
+
+/// Owns one Arduino serial port on a dedicated thread, so the blocking
+/// clear/write/sleep/read sequences never run on the egui update thread.
+struct SerialWorker {
+    board: Board,
+    port: Box<dyn serialport::SerialPort>,
+    command_set: CommandSet,
+    num_positions: usize,
+    events: mpsc::Sender<SerialEvent>,
+    audit_log: Arc<Mutex<AuditLog>>,
+    wedged: bool, // Last query went unanswered; probe with WEDGED_PROBE_TIMEOUT
+}
+
+impl SerialWorker {
+    fn spawn(
+        board: Board,
+        port: Box<dyn serialport::SerialPort>,
+        command_set: CommandSet,
+        num_positions: usize,
+        events: mpsc::Sender<SerialEvent>,
+        audit_log: Arc<Mutex<AuditLog>>,
+    ) -> mpsc::Sender<SerialRequest> {
+        let (tx, rx) = mpsc::channel::<SerialRequest>();
+        let mut worker = SerialWorker { board, port, command_set, num_positions, events, audit_log, wedged: false };
+        thread::spawn(move || {
+            let mut last_command: Option<Instant> = None;
+            // Exits once every StepperCore-side sender is dropped
+            for request in rx {
+                if let SerialRequest::Flush(done) = request {
+                    let _ = done.send(());
+                    continue;
+                }
+                if let Some(last) = last_command {
+                    let since = last.elapsed();
+                    if since < SERIAL_COMMAND_GAP {
+                        thread::sleep(SERIAL_COMMAND_GAP - since);
+                    }
+                }
+                worker.handle(request);
+                last_command = Some(Instant::now());
+            }
+        });
+        tx
+    }
+
+    fn handle(&mut self, request: SerialRequest) {
+        match request {
+            SerialRequest::Command { cmd_id, stepper, value, refresh_after, audit } => {
+                let source = audit.as_ref().map(|a| a.source.as_str()).unwrap_or("-");
+                let _span = tracing::debug_span!("serial_command", board = ?self.board, cmd_id, stepper, value, source).entered();
+                let _ = self.events.send(SerialEvent::CommandStarted(self.board, cmd_id, stepper));
+                // Flush input before command (mirror Python's flush_input_before_command)
+                let _ = self.port.clear(serialport::ClearBuffer::Input);
+                if let Err(e) = self.write_cmd_bin(cmd_id, stepper, value) {
+                    tracing::error!("Failed to write to port: {}", e);
+                }
+                let mut positions = None;
+                if let Some(settle) = refresh_after {
+                    thread::sleep(settle);
+                    positions = self.refresh_positions();
+                }
+                if let Some(mut entry) = audit {
+                    entry.result = positions.and_then(|p| p.get(entry.stepper).copied());
+                    if let Ok(mut log) = self.audit_log.lock() {
+                        log.record(&entry);
+                    }
+                }
+                let _ = self.events.send(SerialEvent::CommandDone(self.board, cmd_id, stepper));
+            }
+            SerialRequest::RefreshPositions => {
+                let _span = tracing::debug_span!("refresh_positions", board = ?self.board).entered();
+                self.refresh_positions();
+            }
+            SerialRequest::QueryFirmwareVersion => {
+                let Some(version_cmd) = self.command_set.version_cmd else { return; };
+                // Older firmware ignores the query; that must not mark the board as wedged
+                let wedged = self.wedged;
+                self.wedged = true; // Short probe timeout, no error log
+                let version = self.query(version_cmd, 1, 4, "firmware version", WEDGED_PROBE_TIMEOUT).ok();
+                self.wedged = wedged;
+                let _ = self.events.send(SerialEvent::FirmwareVersion(self.board, version.and_then(|v| v.first().copied())));
+            }
+            SerialRequest::Flush(done) => {
+                let _ = done.send(());
+            }
+        }
+    }
+
+    fn write_cmd_bin(&mut self, cmd_id: u8, stepper_idx: i16, value: i32) -> std::io::Result<()> {
+        profiling::time(profiling::Probe::Serial, || {
+            self.port.write_all(&arduino_connection::encode_cmd_bin(cmd_id, stepper_idx, value))?;
+            self.port.flush()
+        })
+    }
+
+    /// Send a query command and decode the reply's `width`-byte values as they arrive
+    fn query(&mut self, cmd: &[u8], num_values: usize, width: usize, what: &'static str, timeout: Duration) -> Result<Vec<i32>, QueryError> {
+        let mut decoder = arduino_connection::FrameDecoder::for_query(cmd, num_values, width, self.command_set.checksum)
+            .ok_or_else(|| QueryError::Port(format!("malformed {} command {:?}", what, String::from_utf8_lossy(cmd))))?;
+        let timeout = if self.wedged { timeout.min(WEDGED_PROBE_TIMEOUT) } else { timeout };
+
+        // Flush input buffer before command (mirror Python's flushInput)
+        let _ = self.port.clear(serialport::ClearBuffer::Input);
+        let sent = Instant::now(); // Round trip for the Serial probe; unanswered queries are logged as errors instead
+        let _ = self.port.write_all(cmd);
+        let _ = self.port.flush();
+
+        // Arduino sends positions with delay(2) per position, so with 13 steppers that's ~26ms minimum
+        // Wait a bit before starting to read
+        thread::sleep(Duration::from_millis(50));
+
+        // Read until a complete, valid frame has been decoded or timeout
+        let start_time = Instant::now();
+
+        while start_time.elapsed() < timeout {
+            let mut chunk = vec![0u8; 256];
+            match self.port.read(&mut chunk) {
+                Ok(bytes_read) if bytes_read > 0 => {
+                    if let Some(values) = decoder.push(&chunk[..bytes_read]) {
+                        if decoder.skipped > 0 || decoder.rejected > 0 {
+                            tracing::debug!("{} reply: skipped {} stray byte(s), {} malformed frame(s)", what, decoder.skipped, decoder.rejected);
+                        }
+                        if self.wedged {
+                            tracing::info!("{:?} board answering again", self.board);
+                            self.wedged = false;
+                        }
+                        profiling::record(profiling::Probe::Serial, sent.elapsed());
+                        return Ok(values);
+                    }
+                }
+                Ok(_) => {
+                    // No data available yet (timeout or empty read), wait a bit and retry
+                    thread::sleep(Duration::from_millis(10));
+                }
+                Err(e) => {
+                    // Timeout errors are expected - wait and retry
+                    if arduino_connection::is_timeout(&e) {
+                        thread::sleep(Duration::from_millis(10));
+                        continue;
+                    }
+                    tracing::warn!("Read error: {}", e);
+                    return Err(QueryError::Port(e.to_string()));
+                }
+            }
+        }
+
+        // Keep the last known values rather than report a partial or corrupt reply.
+        // Only the first miss is an error; probes of a wedged board stay quiet.
+        if !self.wedged {
+            tracing::error!(
+                "Failed to read {} from serial port ({} malformed frame(s), {} stray byte(s))",
+                what, decoder.rejected, decoder.skipped
+            );
+            self.wedged = true;
+        }
+        Err(QueryError::TimedOut { what, after: timeout })
+    }
+
+    fn refresh_positions(&mut self) -> Option<Vec<i32>> {
+        let cs = self.command_set;
+        let positions = match self.query(cs.positions_cmd, self.num_positions, cs.position_bytes, "positions", cs.positions_timeout) {
+            Ok(positions) => positions,
+            Err(e) => {
+                let _ = self.events.send(SerialEvent::PositionsFailed(self.board, e));
+                return None;
+            }
+        };
+        tracing::trace!(?positions, "Parsed positions");
+        let _ = self.events.send(SerialEvent::Positions(self.board, positions.clone()));
+
+        // Encoders (Z axes) live on the main board only
+        if self.board == Board::Main {
+            if let Some(encoders_cmd) = self.command_set.encoders_cmd {
+                self.refresh_encoders(encoders_cmd);
+            }
+        }
+        Some(positions)
+    }
+
+    fn refresh_encoders(&mut self, encoders_cmd: &'static [u8]) {
+        let Ok(values) = self.query(encoders_cmd, self.num_positions, 4, "encoders", self.command_set.encoders_timeout) else { return; };
+        let encoders: Vec<Option<i32>> = values
+            .into_iter()
+            .map(|v| if v == ENCODER_ABSENT { None } else { Some(v) })
+            .collect();
+        let _ = self.events.send(SerialEvent::Encoders(self.board, encoders));
+    }
+}
+
+#[derive(Debug)]
+pub struct StepperCore {
+    serial: Option<mpsc::Sender<SerialRequest>>, // Main board serial thread (owns the port)
+    positions: Vec<i32>,
+    connected: bool,
+    tuner_serial: Option<mpsc::Sender<SerialRequest>>, // Tuner board serial thread, if on a separate board
+    tuner_positions: Vec<i32>,
+    tuner_connected: bool,
+    log_buffer: LogBuffer, // Lines captured by the tracing GUI layer, shown in the Messages panel
+    role_lock: role::RoleLock, // Performer / technician mode (shared with operations_gui in master_gui)
+    port_path: String,
+    tuner_port_path: Option<String>,
+    usb_match: Option<UsbMatch>, // ARD_USB: find the main board by USB identity on each connect
+    tuner_usb_match: Option<UsbMatch>, // ARD_T_USB
+    transport: Transport, // ARD_TRANSPORT: serial device or BLE UART
+    tuner_transport: Transport, // ARD_T_TRANSPORT
+    topology: topology::Topology, // Which stepper is X, each string's Z pair and tuner
+    #[cfg(feature = "gui")]
+    pending_positions: std::collections::HashMap<usize, i32>, // Store pending edits per stepper
+    // Tuner stepper parameters (applied to all tuners)
+    tuner_accel: i32,
+    tuner_speed: i32,
+    tuner_min: i32,
+    tuner_max: i32,
+    tuner_step: i32,      // Coarse step size for tuner inc/dec buttons
+    tuner_fine_step: i32, // Fine step size (TUNER_STEPS.FINE)
+    #[cfg(feature = "gui")]
+    tuner_fine: bool,     // Inc/dec buttons use the fine step
+    string_names: Vec<Option<String>>,  // STRINGS NAME per string (Z pair / tuner)
+    string_colors: Vec<Option<[u8; 3]>>, // STRINGS COLOR overrides of the channel colors
+    // X stepper parameters
+    x_accel: i32,
+    x_speed: i32,
+    x_min: i32,
+    x_max: i32,
+    x_step: i32, // Step size for X stepper movement
+    // Z stepper parameters (applied to all z steppers)
+    z_accel: i32,
+    z_speed: i32,
+    z_min: i32,
+    z_max: i32,
+    z_up_step: i32,
+    z_down_step: i32,
+    motion_sync: bool, // MOTION_PARAMS configured: push the params above to the boards after every connect
+    last_motion_sync: Option<String>, // What the last sync sent, for the Motion Params panel
+    axes: Vec<config_loader::AxisSettings>, // AXES: linear axes besides X; step and SPEED / ACCEL edited in place
+    bow_drive: Option<bow_drive::BowDrive>, // BOW_DRIVE: per-string bow wheel speeds (None = no wheels)
+    tuner_guard: Option<tension::TunerGuard>, // TUNER_SAFETY: pitch curves tuner moves are checked against (None = unchecked)
+    damper_command: Option<u8>, // DAMPERS with OUTPUT serial: firmware command for the relays (None = not on this board)
+    socket_path: String,
+    firmware: ArduinoFirmware,
+    command_set: CommandSet,
+    tuner_command_set: CommandSet,
+    x_max_pos: Option<i32>, // X_MAX_POS from config for slider range
+    bridge: Option<String>, // STEPPER_BRIDGE: TCP listen address
+    firmware_protocol: Option<i32>, // Answer to the firmware version query, main board
+    tuner_firmware_protocol: Option<i32>,
+    peers: BTreeMap<String, BuildInfo>, // Latest hello from each socket client component
+    access: Arc<AccessPolicy>, // IPC_AUTH tokens and allow-lists for socket and bridge clients
+    position_subscribers: Arc<Mutex<Vec<LinkStream>>>, // Socket clients receiving pushed position updates
+    shared_subscribers: Arc<Mutex<Vec<LinkStream>>>, // Socket clients receiving shared state changes
+    last_shared: Option<SharedState>, // Shared state as last pushed to subscribers
+    serial_events_tx: mpsc::Sender<SerialEvent>,
+    serial_events_rx: mpsc::Receiver<SerialEvent>,
+    encoder_positions: Vec<Option<i32>>, // Encoder-reported positions (empty until firmware reports them)
+    encoder_slip_threshold: i32,
+    slipping_steppers: std::collections::HashSet<usize>, // Steppers whose step count disagrees with the encoder
+    positions_fault: Option<QueryError>, // Set while the main board's positions can't be read
+    tuner_positions_fault: Option<QueryError>,
+    audit_log: Arc<Mutex<AuditLog>>, // Persistent record of every motion command
+    position_model_path: Option<std::path::PathBuf>, // Last known positions, kept across restarts (None = not kept)
+    position_model: Option<PositionModel>,
+    startup_unchecked: Vec<Board>, // Boards whose first report hasn't been compared with the model yet
+    startup_mismatch: Vec<(Board, Discrepancy)>, // Steppers not where the last session left them
+    disabled_steppers: std::collections::BTreeSet<usize>, // Locked out of moves, driver current cut where the firmware can
+    moves_in_flight: Vec<MoveInFlight>, // Motion commands queued or executing, oldest first
+    last_error: Option<String>, // Last ERROR line logged, for run_command's result
+    jog_rate_hz: f32, // JOG_RATE_HZ: moves per second while a +/- button or arrow key is held
+    #[cfg(feature = "gui")]
+    jog: Option<Jog>,
+    #[cfg(feature = "gui")]
+    jog_requests: Vec<(JogTarget, i32)>, // Buttons and keys held down this frame, with their step
+    #[cfg(feature = "gui")]
+    jog_key_target: Option<JogTarget>, // Up / Down jog what a +/- button last moved (X has Left / Right)
+}
+
+impl Default for StepperCore {
+    fn default() -> Self {
+        let (serial_events_tx, serial_events_rx) = mpsc::channel();
+        Self {
+            serial: None,
+            positions: vec![0; 13],
+            connected: false,
+            tuner_serial: None,
+            tuner_positions: Vec::new(),
+            tuner_connected: false,
+            log_buffer: logging::new_buffer(),
+            role_lock: Default::default(),
+            port_path: String::new(),
+            tuner_port_path: None,
+            usb_match: None,
+            tuner_usb_match: None,
+            transport: Transport::Serial,
+            tuner_transport: Transport::Serial,
+            topology: topology::Topology::default(),
+            #[cfg(feature = "gui")]
+            pending_positions: std::collections::HashMap::new(),
+            tuner_accel: 10000,
+            tuner_speed: 250,
+            tuner_min: -100000,
+            tuner_max: 100000,
+            tuner_step: 100,
+            tuner_fine_step: 10,
+            #[cfg(feature = "gui")]
+            tuner_fine: false,
+            string_names: Vec::new(),
+            string_colors: Vec::new(),
+            x_accel: 10000,
+            x_speed: 500,
+            x_min: 0,
+            x_max: 2600,
+            x_step: 10,
+            z_accel: 10000,
+            z_speed: 100,
+            z_min: -100,
+            z_max: 100,
+            z_up_step: 2,
+            z_down_step: -2,
+            motion_sync: false,
+            last_motion_sync: None,
+            axes: Vec::new(),
+            bow_drive: None,
+            tuner_guard: None,
+            damper_command: None,
+            socket_path: String::new(),
+            bridge: None,
+            firmware_protocol: None,
+            tuner_firmware_protocol: None,
+            peers: BTreeMap::new(),
+            access: Arc::new(AccessPolicy::default()),
+            firmware: ArduinoFirmware::StringDriverV2,
+            command_set: CommandSet::for_firmware(ArduinoFirmware::StringDriverV2),
+            tuner_command_set: CommandSet::for_firmware(ArduinoFirmware::StringDriverV2),
+            x_max_pos: None,
+            position_subscribers: Arc::new(Mutex::new(Vec::new())),
+            shared_subscribers: Arc::new(Mutex::new(Vec::new())),
+            last_shared: None,
+            serial_events_tx,
+            serial_events_rx,
+            encoder_positions: Vec::new(),
+            encoder_slip_threshold: 4,
+            slipping_steppers: std::collections::HashSet::new(),
+            positions_fault: None,
+            tuner_positions_fault: None,
+            audit_log: Arc::new(Mutex::new(AuditLog::open(
+                std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("logs"),
+            ))),
+            position_model_path: None,
+            position_model: None,
+            startup_unchecked: Vec::new(),
+            startup_mismatch: Vec::new(),
+            disabled_steppers: std::collections::BTreeSet::new(),
+            moves_in_flight: Vec::new(),
+            last_error: None,
+            jog_rate_hz: config_loader::DEFAULT_JOG_RATE_HZ,
+            #[cfg(feature = "gui")]
+            jog: None,
+            #[cfg(feature = "gui")]
+            jog_requests: Vec::new(),
+            #[cfg(feature = "gui")]
+            jog_key_target: None,
+        }
+    }
+}
+
+/// Steps a stepper may be off the saved model before the startup check flags it
+const STARTUP_POSITION_TOLERANCE: i32 = 2;
+
+/// How often the shared poll loop refreshes positions while anyone is subscribed
+const SUBSCRIBER_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Longest an IPC command waits for the board to finish it (move, settle, position
+/// read-back) before the next command from the queue runs anyway
+const IPC_COMPLETION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How far past its estimated travel time a move may run before it is shown as overdue
+#[cfg(feature = "gui")]
+const MOVE_OVERDUE_GRACE: Duration = Duration::from_secs(2);
+
+/// A motion command sent to a board and not yet finished, for the live motion indicator
+#[derive(Debug, Clone)]
+struct MoveInFlight {
+    board: Board,
+    stepper: usize, // Index on `board` (tuner index on a separate tuner board)
+    cmd_id: u8,
+    target: i32,
+    #[cfg(feature = "gui")]
+    estimate: Duration, // Travel time at the axis speed and accel
+    started: Option<Instant>, // When the serial thread wrote it; None while still queued
+}
+
+/// Where a move in flight stands
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg(feature = "gui")]
+enum MoveProgress {
+    /// Waiting behind earlier commands for the same board
+    Queued,
+    /// Executing, with the estimated time left (zero while the positions are read back)
+    Moving { remaining: Duration },
+    /// Running well past its estimate: stuck, stalled or the board stopped answering
+    Overdue { elapsed: Duration },
+}
+
+impl MoveInFlight {
+    fn matches(&self, board: Board, cmd_id: u8, stepper: i16) -> bool {
+        self.board == board && self.cmd_id == cmd_id && self.stepper as i16 == stepper
+    }
+
+    #[cfg(feature = "gui")]
+    fn progress(&self, now: Instant) -> MoveProgress {
+        let Some(started) = self.started else { return MoveProgress::Queued; };
+        let elapsed = now.saturating_duration_since(started);
+        if elapsed > self.estimate + MOVE_OVERDUE_GRACE {
+            MoveProgress::Overdue { elapsed }
+        } else {
+            MoveProgress::Moving { remaining: self.estimate.saturating_sub(elapsed) }
+        }
+    }
+}
+
+/// Time to travel `distance` steps at `speed` steps/s with `accel` steps/s², as the
+/// firmware's AccelStepper ramps: trapezoidal, or triangular when full speed isn't reached
+#[cfg(feature = "gui")]
+fn travel_time(distance: i32, speed: i32, accel: i32) -> Duration {
+    let distance = distance.unsigned_abs() as f64;
+    if distance == 0.0 || speed <= 0 {
+        return Duration::ZERO;
+    }
+    let speed = speed as f64;
+    if accel <= 0 {
+        return Duration::from_secs_f64(distance / speed);
+    }
+    let accel = accel as f64;
+    let ramp_distance = speed * speed / accel; // Up to speed and back down
+    let secs = if distance >= ramp_distance {
+        distance / speed + speed / accel
+    } else {
+        2.0 * (distance / accel).sqrt()
+    };
+    Duration::from_secs_f64(secs)
+}
+
+/// A press shorter than this is a single step; held longer, the button starts jogging
+#[cfg(feature = "gui")]
+const JOG_HOLD_DELAY: Duration = Duration::from_millis(300);
+
+/// What a +/- button or arrow key moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg(feature = "gui")]
+enum JogTarget {
+    Stepper(usize), // Main board stepper: X, a Z or an AXES stepper
+    Tuner(usize),   // Tuner index, on whichever board carries it
+}
+
+/// A +/- button or arrow key being held
+#[derive(Debug, Clone)]
+#[cfg(feature = "gui")]
+struct Jog {
+    target: JogTarget,
+    delta: i32,         // Steps per jog move: the button's step
+    next_step: Instant, // Earliest time for the next move
+    steps: u32,         // Jog moves sent, after the press's own step
+    total: i32,
+    stopped: bool, // A move was refused (limit, disabled, tuner safety): nothing more until release
+}
+
+/// One IPC command waiting for the serial worker, plus the client that should get the reply
+struct SerialJob {
+    cmd: String,
+    client: String, // "IPC#<n>" / "TCP#<n>", numbered per socket connection
+    responder: Option<LinkStream>,
+}
+
+impl StepperCore {
+    fn write_positions_response(stream: &mut LinkStream, positions: &[i32]) -> std::io::Result<()> {
+        use std::io::Write;
+        let mut response = String::from("positions");
+        for (idx, pos) in positions.iter().enumerate() {
+            response.push(' ');
+            response.push_str(&format!("{}={}", idx, pos));
+        }
+        response.push('\n');
+        stream.write_all(response.as_bytes())?;
+        stream.flush()
+    }
+
+    /// Push the current positions to every subscriber, dropping clients that have gone away
+    fn broadcast_positions(&mut self) {
+        let dropped = {
+            let Ok(mut subscribers) = self.position_subscribers.lock() else { return; };
+            let before = subscribers.len();
+            let positions = &self.positions;
+            subscribers.retain_mut(|stream| Self::write_positions_response(stream, positions).is_ok());
+            before - subscribers.len()
+        };
+        if dropped > 0 {
+            self.log(&format!("IPC: dropped {} position subscriber(s)", dropped));
+        }
+    }
+
+    /// Step sizes and lockout as shared with operations_gui
+    fn shared_state(&self) -> SharedState {
+        SharedState {
+            z_up_step: self.z_up_step,
+            z_down_step: self.z_down_step,
+            x_step: self.x_step,
+            disabled: self.disabled_steppers.clone(),
+        }
+    }
+
+    /// Change a step size for both GUIs ("set_shared")
+    fn set_shared(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let mut shared = self.shared_state();
+        shared.set(key, value)?;
+        self.z_up_step = shared.z_up_step;
+        self.z_down_step = shared.z_down_step;
+        self.x_step = shared.x_step;
+        Ok(())
+    }
+
+    fn write_shared_line(stream: &mut LinkStream, shared: &SharedState) -> std::io::Result<()> {
+        use std::io::Write;
+        stream.write_all(format!("{}\n", shared.to_line()).as_bytes())?;
+        stream.flush()
+    }
+
+    /// Push the shared state to every subscriber if it changed since the last push
+    fn broadcast_shared_if_changed(&mut self) {
+        let shared = self.shared_state();
+        if self.last_shared.as_ref() == Some(&shared) {
+            return;
+        }
+        if let Ok(mut subscribers) = self.shared_subscribers.lock() {
+            subscribers.retain_mut(|stream| Self::write_shared_line(stream, &shared).is_ok());
+        }
+        self.last_shared = Some(shared);
+    }
+
+    fn has_position_subscribers(&self) -> bool {
+        self.position_subscribers.lock().map(|s| !s.is_empty()).unwrap_or(false)
+    }
+
+    pub fn new(port_path: String, num_steppers: usize, string_num: usize, x_step_index: Option<usize>, z_first_index: Option<usize>, tuner_first_index: Option<usize>, tuner_port_path: Option<String>, tuner_num_steppers: Option<usize>, debug: bool, z_up_step: i32, z_down_step: i32, firmware: ArduinoFirmware, x_max_pos: Option<i32>, x_step: i32, tuner_firmware: Option<ArduinoFirmware>, encoder_slip_threshold: i32) -> Self {
+        let mut s = Self::default();
+        s.port_path = port_path;
+        s.positions = vec![0; num_steppers];
+        // Tuners only exist with TUNER_FIRST_INDEX; they are on the tuner board when it has a port
+        let tuners = match (tuner_first_index, tuner_port_path.is_some(), tuner_num_steppers) {
+            (Some(_), true, Some(num)) => topology::TunerLayout::Board(num),
+            (Some(first), false, Some(num)) => topology::TunerLayout::Main((first..first + num).collect()),
+            _ => topology::TunerLayout::None,
+        };
+        s.topology = topology::Topology::new(string_num, z_first_index, x_step_index, tuners, None);
+        s.tuner_port_path = tuner_port_path.clone();
+        s.firmware = firmware;
+        let main_cmds = CommandSet::for_firmware(firmware);
+        s.command_set = main_cmds;
+        s.tuner_command_set = if tuner_port_path.is_some() {
+            CommandSet::for_firmware(tuner_firmware.unwrap_or(ArduinoFirmware::StringDriverV2)).on_tuner_board()
+        } else {
+            main_cmds
+        };
+        if let Some(num) = tuner_num_steppers {
+            s.tuner_positions = vec![0; num];
+            // Set tuner min/max based on board type
+            if tuner_port_path.is_some() {
+                // Separate tuner board: -100000 to 100000
+                s.tuner_min = -100000;
+                s.tuner_max = 100000;
+            } else if tuner_first_index.is_some() {
+                // Main board tuners (stringdriver-1): -25000 to 25000
+                s.tuner_min = -25000;
+                s.tuner_max = 25000;
+            }
+        }
+        s.encoder_slip_threshold = encoder_slip_threshold;
+        s.z_up_step = z_up_step;
+        s.z_down_step = z_down_step;
+        s.x_step = x_step;
+        s.log(&format!("Initialized: {} steppers, {} active string pairs", num_steppers, string_num));
+        if tuner_first_index.is_some() {
+            if tuner_port_path.is_some() {
+                s.log(&format!("Tuners on separate board: {} steppers", tuner_num_steppers.unwrap_or(0)));
+            } else {
+                s.log(&format!("Tuners on main board: first_index={:?}", tuner_first_index));
+            }
+        }
+        if debug { s.log("Debug logging enabled"); }
+        // Generate socket path from port path
+        let port_id = s.port_path.replace("/", "_").replace("\\", "_");
+        s.socket_path = format!("/tmp/stepper_gui_{}.sock", port_id);
+        s.x_max_pos = x_max_pos;
+        s
+    }
+    
+    /// Handle a text command from Unix socket.
+    /// `client` identifies the connection (e.g. "IPC#3"); a trailing `src=<name>` token
+    /// names the operation or script behind the command for the audit log.
+    fn handle_command(&mut self, cmd: &str, client: &str, mut responder: Option<&mut LinkStream>) {
+        let mut parts: Vec<&str> = cmd.trim().split_whitespace().collect();
+        let source = match parts.last().and_then(|p| p.strip_prefix("src=")) {
+            Some(name) => {
+                let source = format!("{}:{}", client, name);
+                parts.pop();
+                source
+            }
+            None => client.to_string(),
+        };
+        if parts.is_empty() {
+            return;
+        }
+        let _span = tracing::info_span!("ipc", client = %source).entered();
+        // Pick up positions the serial threads reported since the last command
+        self.drain_serial_events();
+        
+        match parts[0] {
+            "rel_move" => {
+                if parts.len() == 3 {
+                    if let (Ok(stepper), Ok(delta)) = (parts[1].parse::<usize>(), parts[2].parse::<i32>()) {
+                        self.log(&format!("IPC: rel_move {} {}", stepper, delta));
+                        self.move_stepper_with_source(&source, stepper, delta);
+                    }
+                }
+            }
+            "abs_move" => {
+                if parts.len() == 3 {
+                    if let (Ok(stepper), Ok(position)) = (parts[1].parse::<usize>(), parts[2].parse::<i32>()) {
+                        self.log(&format!("IPC: abs_move {} {}", stepper, position));
+                        self.move_stepper_absolute_with_source(&source, stepper, position);
+                    }
+                }
+            }
+            "reset" => {
+                if parts.len() == 3 {
+                    if let (Ok(stepper), Ok(position)) = (parts[1].parse::<usize>(), parts[2].parse::<i32>()) {
+                        self.log(&format!("IPC: reset {} {} (set_stepper - no physical move)", stepper, position));
+                        self.reset_position(&source, stepper, position);
+                    }
+                }
+            }
+            "disable" | "enable" => {
+                if let Some(Ok(stepper)) = parts.get(1).map(|p| p.parse::<usize>()) {
+                    self.log(&format!("IPC: {} {} ({})", parts[0], stepper, source));
+                    self.set_stepper_enabled(&source, stepper, parts[0] == "enable");
+                }
+            }
+            "tuner_rel_move" => {
+                if parts.len() == 3 {
+                    if let (Ok(tuner), Ok(delta)) = (parts[1].parse::<usize>(), parts[2].parse::<i32>()) {
+                        self.log(&format!("IPC: tuner_rel_move {} {}", tuner, delta));
+                        self.move_tuner_with_source(&source, tuner, delta);
+                    }
+                }
+            }
+            "tuner_abs_move" => {
+                if parts.len() == 3 {
+                    if let (Ok(tuner), Ok(position)) = (parts[1].parse::<usize>(), parts[2].parse::<i32>()) {
+                        self.log(&format!("IPC: tuner_abs_move {} {}", tuner, position));
+                        self.move_tuner_absolute_with_source(&source, tuner, position);
+                    }
+                }
+            }
+            "get_tuners" => {
+                if let Some(stream) = responder.as_deref_mut() {
+                    use std::io::Write;
+                    // "tuners <idx>=<pos> ... min=<min> max=<max>"
+                    let (tuner_min, tuner_max) = self.tuner_limits();
+                    let mut response = String::from("tuners");
+                    for (idx, pos) in self.tuner_positions.iter().enumerate() {
+                        response.push_str(&format!(" {}={}", idx, pos));
+                    }
+                    response.push_str(&format!(" min={} max={}\n", tuner_min, tuner_max));
+                    let _ = stream.write_all(response.as_bytes());
+                    let _ = stream.flush();
+                } else {
+                    self.log("IPC: get_tuners requested without responder stream");
+                }
+            }
+            "set_speed" => {
+                if parts.len() == 3 {
+                    if let (Ok(stepper), Ok(speed)) = (parts[1].parse::<usize>(), parts[2].parse::<i32>()) {
+                        self.log(&format!("IPC: set_speed {} {}", stepper, speed));
+                        self.set_speed(stepper, speed);
+                    }
+                }
+            }
+            "set_limits" => {
+                // "set_limits <x|z|tuners> <min> <max>": one firmware min/max row per group
+                if parts.len() == 4 {
+                    if let (Some(group), Ok(min), Ok(max)) = (LimitGroup::parse(parts[1]), parts[2].parse::<i32>(), parts[3].parse::<i32>()) {
+                        self.log(&format!("IPC: set_limits {} {} {}", group.label(), min, max));
+                        match self.set_limits(group, min, max) {
+                            Ok(()) => {
+                                let (lo, hi) = match group {
+                                    LimitGroup::X => (&mut self.x_min, &mut self.x_max),
+                                    LimitGroup::Z => (&mut self.z_min, &mut self.z_max),
+                                    LimitGroup::Tuners => (&mut self.tuner_min, &mut self.tuner_max),
+                                };
+                                (*lo, *hi) = (min, max);
+                            }
+                            Err(e) => self.log(&format!("ERROR: {}", e)),
+                        }
+                    }
+                }
+            }
+            "bow_speed" => {
+                if parts.len() == 3 {
+                    if let (Ok(string_idx), Ok(speed)) = (parts[1].parse::<usize>(), parts[2].parse::<u32>()) {
+                        self.log(&format!("IPC: bow_speed {} {}", string_idx, speed));
+                        self.set_bow_speed(&source, string_idx, speed);
+                    }
+                }
+            }
+            "damp" => {
+                if parts.len() == 3 {
+                    if let (Ok(string_idx), Ok(engaged)) = (parts[1].parse::<usize>(), parts[2].parse::<u8>()) {
+                        self.log(&format!("IPC: damp {} {}", string_idx, engaged));
+                        self.damp(&source, string_idx, engaged != 0);
+                    }
+                }
+            }
+            "get_bow_speeds" => {
+                if let Some(stream) = responder.as_deref_mut() {
+                    use std::io::Write;
+                    // "bow_speeds <string>=<speed> ... max=<max>", "bow_speeds none" without BOW_DRIVE
+                    let response = match &self.bow_drive {
+                        Some(drive) => {
+                            let mut response = String::from("bow_speeds");
+                            for (idx, speed) in drive.speeds().iter().enumerate() {
+                                response.push_str(&format!(" {}={}", idx, speed));
+                            }
+                            format!("{} max={}\n", response, drive.settings.max_speed)
+                        }
+                        None => "bow_speeds none\n".to_string(),
+                    };
+                    let _ = stream.write_all(response.as_bytes());
+                    let _ = stream.flush();
+                } else {
+                    self.log("IPC: get_bow_speeds requested without responder stream");
+                }
+            }
+            "get_x_step" => {
+                if let Some(ref mut resp) = responder {
+                    use std::io::Write;
+                    let _ = resp.write_all(format!("{}\n", self.x_step).as_bytes());
+                    let _ = resp.flush();
+                }
+            }
+            "get_x_step" => {
+                if let Some(stream) = responder.as_deref_mut() {
+                    use std::io::Write;
+                    let _ = stream.write_all(format!("{}\n", self.x_step).as_bytes());
+                    let _ = stream.flush();
+                }
+            }
+            "subscribe" => {
+                let topic = parts.get(1).copied().unwrap_or_default();
+                if topic != "positions" && topic != "shared" {
+                    self.log(&format!("IPC: Unknown subscription: {}", cmd.trim()));
+                    return;
+                }
+                if topic == "shared" {
+                    // Bring existing subscribers up to date first, so the newcomer's first line is not pushed twice
+                    self.broadcast_shared_if_changed();
+                }
+                let Some(stream) = responder.as_deref_mut() else {
+                    self.log("IPC: subscribe requested without responder stream");
+                    return;
+                };
+                match stream.try_clone() {
+                    Ok(mut subscriber) => {
+                        // A stalled subscriber must not hold up the serial loop
+                        let _ = subscriber.set_write_timeout(Some(Duration::from_millis(200)));
+                        let (written, subscribers) = if topic == "shared" {
+                            (Self::write_shared_line(&mut subscriber, &self.shared_state()), &self.shared_subscribers)
+                        } else {
+                            (Self::write_positions_response(&mut subscriber, &self.positions), &self.position_subscribers)
+                        };
+                        if written.is_ok() {
+                            if let Ok(mut subscribers) = subscribers.lock() {
+                                subscribers.push(subscriber);
+                            }
+                            self.log(&format!("IPC: {} subscriber added", topic));
+                        }
+                    }
+                    Err(e) => self.log(&format!("IPC: Failed to register subscriber: {}", e)),
+                }
+            }
+            "hello" => {
+                // Version handshake: note the client's build, answer with ours
+                let Some(peer) = BuildInfo::parse_hello(cmd) else {
+                    self.log(&format!("IPC: malformed hello: {}", cmd.trim()));
+                    return;
+                };
+                let own = self.build_info();
+                for problem in own.mismatches(&peer) {
+                    self.log(&format!("WARN: {}", problem));
+                }
+                self.log(&format!("IPC: {} is {}", source, peer.describe()));
+                self.peers.insert(peer.component.clone(), peer);
+                if let Some(stream) = responder.as_deref_mut() {
+                    if let Err(e) = stream.write_all(format!("{}\n", own.hello_line()).as_bytes()).and_then(|_| stream.flush()) {
+                        self.log(&format!("IPC: Failed to answer hello: {}", e));
+                    }
+                }
+            }
+            "get_shared" => {
+                if let Some(stream) = responder.as_deref_mut() {
+                    let _ = Self::write_shared_line(stream, &self.shared_state());
+                } else {
+                    self.log("IPC: get_shared requested without responder stream");
+                }
+            }
+            "set_shared" => {
+                for token in &parts[1..] {
+                    let applied = token.split_once('=')
+                        .ok_or_else(|| format!("malformed '{}'", token))
+                        .and_then(|(key, value)| self.set_shared(key, value));
+                    match applied {
+                        Ok(()) => self.log(&format!("IPC: set_shared {} ({})", token, source)),
+                        Err(e) => self.log(&format!("ERROR: IPC: set_shared {}: {}", token, e)),
+                    }
+                }
+            }
+            "get_encoders" => {
+                if let Some(stream) = responder.as_deref_mut() {
+                    use std::io::Write;
+                    // "encoders <idx>=<pos>|- ..." with '!' marking steppers flagged as slipping
+                    let mut response = String::from("encoders");
+                    for (idx, encoder) in self.encoder_positions.iter().enumerate() {
+                        let value = encoder.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string());
+                        let flag = if self.slipping_steppers.contains(&idx) { "!" } else { "" };
+                        response.push_str(&format!(" {}={}{}", idx, value, flag));
+                    }
+                    response.push('\n');
+                    let _ = stream.write_all(response.as_bytes());
+                    let _ = stream.flush();
+                } else {
+                    self.log("IPC: get_encoders requested without responder stream");
+                }
+            }
+            "get_status" => {
+                if let Some(stream) = responder.as_deref_mut() {
+                    use std::io::Write;
+                    // "status main=<up|down> tuner=<up|down|none> slipping=<n> positions=<ok|stale> tuner_positions=<ok|stale|none>
+                    //  startup=<ok|pending|mismatch> rehome=<axis,...|-> disabled=<idx,...|->"
+                    let up_down = |connected: bool| if connected { "up" } else { "down" };
+                    let ok_stale = |fault: &Option<QueryError>| if fault.is_some() { "stale" } else { "ok" };
+                    let separate_tuner = self.tuner_port_path.is_some();
+                    let tuner = if separate_tuner { up_down(self.tuner_connected) } else { "none" };
+                    let tuner_positions = if separate_tuner { ok_stale(&self.tuner_positions_fault) } else { "none" };
+                    let startup = if !self.startup_mismatch.is_empty() {
+                        "mismatch"
+                    } else if !self.startup_unchecked.is_empty() {
+                        "pending"
+                    } else {
+                        "ok"
+                    };
+                    let rehome: Vec<String> = self.rehome_axes().into_iter().map(str::to_string).collect();
+                    let disabled: Vec<String> = self.disabled_steppers.iter().map(|idx| idx.to_string()).collect();
+                    let list = |items: Vec<String>| if items.is_empty() { "-".to_string() } else { items.join(",") };
+                    let response = format!(
+                        "status main={} tuner={} slipping={} positions={} tuner_positions={} startup={} rehome={} disabled={}\n",
+                        up_down(self.connected), tuner, self.slipping_steppers.len(),
+                        ok_stale(&self.positions_fault), tuner_positions,
+                        startup, list(rehome), list(disabled)
+                    );
+                    let _ = stream.write_all(response.as_bytes());
+                    let _ = stream.flush();
+                } else {
+                    self.log("IPC: get_status requested without responder stream");
+                }
+            }
+            "get_positions" => {
+                if let Some(stream) = responder.as_deref_mut() {
+                    // Refuse to hand out stale positions: "error timeout <ms>" / "error port <message>"
+                    let written = match &self.positions_fault {
+                        Some(fault) => {
+                            use std::io::Write;
+                            stream.write_all(format!("error {}\n", fault.to_wire()).as_bytes()).and_then(|_| stream.flush())
+                        }
+                        None => Self::write_positions_response(stream, &self.positions),
+                    };
+                    if let Err(e) = written {
+                        self.log(&format!("IPC: Failed to send positions: {}", e));
+                    }
+                } else {
+                    self.log("IPC: get_positions requested without responder stream");
+                }
+            }
+            _ => {
+                self.log(&format!("IPC: Unknown command: {}", cmd.trim()));
+            }
+        }
+    }
+    
+    /// Minimum gap to leave after the previous serial command before running `cmd`.
+    /// Queries answered from cached state don't touch the port and need no spacing.
+    fn command_spacing(cmd: &str) -> Duration {
+        match cmd.split_whitespace().next() {
+            Some("rel_move") | Some("abs_move") => Duration::from_millis(50),
+            Some("reset") | Some("set_speed") | Some("set_limits") | Some("bow_speed") | Some("damp") => Duration::from_millis(20),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// IPC commands that queue work for a board; the job worker waits for the board to
+    /// finish them, so a get_positions sent next reads the positions they left
+    fn completes_on_board(cmd: &str) -> bool {
+        matches!(
+            cmd.split_whitespace().next(),
+            Some("rel_move" | "abs_move" | "reset" | "tuner_rel_move" | "tuner_abs_move" | "enable" | "disable"
+                | "set_speed" | "set_limits" | "bow_speed" | "damp")
+        )
+    }
+
+    /// Queue a Flush behind everything already sent to each live board thread
+    fn serial_barrier(&mut self) -> Vec<mpsc::Receiver<()>> {
+        [Board::Main, Board::Tuner]
+            .into_iter()
+            .filter_map(|board| {
+                let (done, finished) = mpsc::channel();
+                self.send_serial(board, SerialRequest::Flush(done)).then_some(finished)
+            })
+            .collect()
+    }
+
+    /// Wait for the boards to reach a serial_barrier, up to IPC_COMPLETION_TIMEOUT;
+    /// false when one of them did not get there in time
+    fn await_barrier(barrier: Vec<mpsc::Receiver<()>>, cmd: &str) -> bool {
+        let deadline = Instant::now() + IPC_COMPLETION_TIMEOUT;
+        let mut finished_all = true;
+        for finished in barrier {
+            if let Err(mpsc::RecvTimeoutError::Timeout) = finished.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                tracing::warn!("IPC: '{}' not finished by the board within {}s - running the next command", cmd, IPC_COMPLETION_TIMEOUT.as_secs());
+                finished_all = false;
+            }
+        }
+        finished_all
+    }
+
+    /// Run one queued IPC command. Commands that go to a board are complete (move made,
+    /// positions read back and applied) before this returns; the app lock is released
+    /// while waiting so the window and the serial events keep flowing.
+    fn run_job(app: &Arc<Mutex<StepperCore>>, job: &mut SerialJob) {
+        let barrier = match app.lock() {
+            Ok(mut guard) => {
+                guard.handle_command(&job.cmd, &job.client, job.responder.as_mut());
+                guard.broadcast_shared_if_changed();
+                if Self::completes_on_board(&job.cmd) { guard.serial_barrier() } else { Vec::new() }
+            }
+            Err(_) => return,
+        };
+        if barrier.is_empty() {
+            return;
+        }
+        Self::await_barrier(barrier, &job.cmd);
+        if let Ok(mut guard) = app.lock() {
+            guard.drain_serial_events();
+            guard.broadcast_shared_if_changed();
+        }
+    }
+
+    /// Single worker that runs IPC commands one at a time in arrival order, so one
+    /// client's command sequence can never interleave with another's on the serial
+    /// threads, and each has finished on the board before the next runs (see run_job).
+    /// The same loop performs the shared position poll while anyone is subscribed.
+    fn start_serial_worker(app: Arc<Mutex<StepperCore>>) -> mpsc::Sender<SerialJob> {
+        let (tx, rx) = mpsc::channel::<SerialJob>();
+        thread::spawn(move || {
+            let mut last_serial: Option<Instant> = None;
+            let mut last_poll = Instant::now();
+            loop {
+                match rx.recv_timeout(SUBSCRIBER_POLL_INTERVAL) {
+                    Ok(mut job) => {
+                        let spacing = Self::command_spacing(&job.cmd);
+                        if let Some(last) = last_serial {
+                            let since = last.elapsed();
+                            if since < spacing {
+                                thread::sleep(spacing - since);
+                            }
+                        }
+                        Self::run_job(&app, &mut job);
+                        if !spacing.is_zero() {
+                            last_serial = Some(Instant::now());
+                        }
+                    }
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+                if last_poll.elapsed() >= SUBSCRIBER_POLL_INTERVAL {
+                    last_poll = Instant::now();
+                    if let Ok(mut guard) = app.lock() {
+                        // Apply serial results even while the window isn't repainting
+                        guard.drain_serial_events();
+                        if guard.connected && guard.has_position_subscribers() {
+                            guard.refresh_positions();
+                        }
+                        // Picks up edits made in this window, which do not go through handle_command
+                        guard.broadcast_shared_if_changed();
+                    }
+                }
+            }
+        });
+        tx
+    }
+
+    /// Start Unix socket listener in background thread
+    pub fn start_socket_listener(app: Arc<Mutex<StepperCore>>) {
+        let (socket_path, bridge, access) = {
+            let guard = app.lock().unwrap();
+            (guard.socket_path.clone(), guard.bridge.clone(), Arc::clone(&guard.access))
+        };
+        
+        // Remove old socket if it exists
+        if Path::new(&socket_path).exists() {
+            let _ = std::fs::remove_file(&socket_path);
+        }
+        
+        let jobs = Self::start_serial_worker(Arc::clone(&app));
+        if let Some(listen) = bridge {
+            Self::start_bridge_listener(listen, Arc::clone(&access), jobs.clone());
+        }
+        
+        thread::spawn(move || {
+            let listener = match UnixListener::bind(&socket_path) {
+                Ok(l) => {
+                    eprintln!("Unix socket listener started at: {}", socket_path);
+                    l
+                }
+                Err(e) => {
+                    eprintln!("Failed to bind Unix socket at {}: {}", socket_path, e);
+                    return;
+                }
+            };
+            
+            // Set socket permissions (read/write for user and group)
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                if let Ok(metadata) = std::fs::metadata(&socket_path) {
+                    let mut perms = metadata.permissions();
+                    perms.set_mode(0o660);
+                    let _ = std::fs::set_permissions(&socket_path, perms);
+                }
+            }
+            
+            let mut next_client_id: usize = 0;
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let (jobs, access) = (jobs.clone(), Arc::clone(&access));
+                        next_client_id += 1;
+                        let client = format!("IPC#{}", next_client_id);
+                        let grant = access.socket_default();
+                        thread::spawn(move || Self::serve_client(LinkStream::Unix(stream), client, grant, access, jobs));
+                    }
+                    Err(e) => {
+                        eprintln!("Socket accept error: {}", e);
+                        // If we're getting too many errors, break to prevent infinite loop
+                        if e.raw_os_error() == Some(24) {
+                            eprintln!("Too many open files - breaking accept loop");
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Serve stepper_gui's socket protocol over TCP (STEPPER_BRIDGE) for operations_gui
+    /// on another machine; each connection must present a token before its first command
+    fn start_bridge_listener(listen: String, access: Arc<AccessPolicy>, jobs: mpsc::Sender<SerialJob>) {
+        thread::spawn(move || {
+            let listener = match std::net::TcpListener::bind(&listen) {
+                Ok(l) => {
+                    eprintln!("Stepper bridge listening on {}", listen);
+                    l
+                }
+                Err(e) => {
+                    eprintln!("Failed to bind stepper bridge at {}: {}", listen, e);
+                    return;
+                }
+            };
+            let mut next_client_id: usize = 0;
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        eprintln!("Bridge accept error: {}", e);
+                        continue;
+                    }
+                };
+                next_client_id += 1;
+                let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_else(|_| "unknown".to_string());
+                let client = format!("TCP#{}", next_client_id);
+                let (jobs, access) = (jobs.clone(), Arc::clone(&access));
+                thread::spawn(move || {
+                    let Some(grant) = stepper_link::accept_auth(&mut stream, |token| access.authenticate(token)) else {
+                        tracing::warn!("Stepper bridge: {} from {} refused (bad token)", client, peer);
+                        return;
+                    };
+                    let _ = stream.set_nodelay(true);
+                    tracing::info!("Stepper bridge: {} connected from {} as {}", client, peer, grant.name);
+                    Self::serve_client(LinkStream::Tcp(stream), client, grant, access, jobs);
+                });
+            }
+        });
+    }
+
+    /// Read one client's commands until it disconnects, queueing each its grant allows
+    /// for the serial worker. `auth <token>` switches to that token's grant.
+    fn serve_client(stream: LinkStream, client: String, mut grant: Grant, access: Arc<AccessPolicy>, jobs: mpsc::Sender<SerialJob>) {
+        use std::io::{BufRead, BufReader, Write};
+        let mut reader = BufReader::new(stream);
+        loop {
+            let mut cmd = String::new();
+            match reader.read_line(&mut cmd) {
+                Ok(0) => break, // EOF
+                Ok(_) => {
+                    let trimmed = cmd.trim();
+                    if trimmed.is_empty() {
+                        continue;
+                    }
+                    if let Some(token) = trimmed.strip_prefix("auth ") {
+                        let answer: &[u8] = match access.authenticate(token) {
+                            Some(granted) => {
+                                tracing::info!("IPC: {} authenticated as {}", client, granted.name);
+                                grant = granted;
+                                b"auth ok\n"
+                            }
+                            None => {
+                                tracing::warn!("IPC: {} offered an unknown token", client);
+                                b"auth denied\n"
+                            }
+                        };
+                        let _ = reader.get_mut().write_all(answer);
+                        continue;
+                    }
+                    let name = trimmed.split_whitespace().next().unwrap_or("");
+                    if !grant.allow.permits(name) {
+                        tracing::warn!("IPC: {} ({}) is not allowed to {} - refused", client, grant.name, trimmed);
+                        if access::READ_COMMANDS.contains(&name) {
+                            // The client is waiting for a reply
+                            let _ = reader.get_mut().write_all(b"error denied\n");
+                        }
+                        continue;
+                    }
+                    let job = SerialJob {
+                        cmd: trimmed.to_string(),
+                        client: client.clone(),
+                        responder: reader.get_ref().try_clone().ok(),
+                    };
+                    if jobs.send(job).is_err() {
+                        eprintln!("Serial worker stopped; dropping command: {}", trimmed);
+                        break;
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Socket read error: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn kill_port_users(&mut self, port_path: &str) {
+        // Find PIDs with the port open
+        let output = Command::new("/usr/bin/lsof")
+            .arg("-t")
+            .arg(port_path)
+            .output();
+        let Ok(out) = output else {
+            self.log("lsof not available or failed");
+            return;
+        };
+        if !out.status.success() {
+            self.log(&format!("lsof returned status {}", out.status));
+            return;
+        }
+        let pids_str = String::from_utf8_lossy(&out.stdout);
+        let self_pid = std::process::id();
+        for line in pids_str.lines() {
+            if let Ok(pid) = line.trim().parse::<u32>() {
+                if pid == self_pid { continue; }
+                self.log(&format!("KILL port user pid={} on {}", pid, port_path));
+                let _ = Command::new("/bin/kill").arg("-9").arg(pid.to_string()).status();
+            }
+        }
+    }
+    /// Queue a CmdMessenger command for a board's serial thread.
+    /// Returns false when that board has no live serial thread.
+    fn send_serial(&mut self, board: Board, request: SerialRequest) -> bool {
+        let sender = match board {
+            Board::Main => self.serial.as_ref(),
+            Board::Tuner => self.tuner_serial.as_ref(),
+        };
+        let Some(sender) = sender else { return false; };
+        if sender.send(request).is_ok() {
+            return true;
+        }
+        self.log(&format!("ERROR: {:?} board serial thread stopped", board));
+        match board {
+            Board::Main => {
+                self.serial = None;
+                self.connected = false;
+            }
+            Board::Tuner => {
+                self.tuner_serial = None;
+                self.tuner_connected = false;
+            }
+        }
+        false
+    }
+
+    fn send_cmd_bin(&mut self, cmd_id: u8, stepper_idx: i16, value: i32) {
+        self.send_serial(Board::Main, SerialRequest::Command { cmd_id, stepper: stepper_idx, value, refresh_after: None, audit: None });
+    }
+
+    /// Build the stepper core from `hostname`'s string_driver.yaml block (ARD_* settings,
+    /// OPERATIONS steps, IPC_AUTH, STEPPER_BRIDGE, MOTION_PARAMS, STRINGS), not yet connected.
+    /// Shared by the stepper_gui window and the headless stringdriverd.
+    pub fn from_config(hostname: &str, debug: bool) -> Result<Self, String> {
+        let settings = config_loader::load_arduino_settings(hostname)
+            .map_err(|e| format!("Missing/invalid Arduino settings in YAML for host '{}': {}", hostname, error::user_message(&e)))?;
+
+        // Calculate default x_finish: X_MAX_POS - 100
+        let default_x_finish = if let Some(max_pos) = settings.x_max_pos {
+            if max_pos > 0 {
+                max_pos - 100
+            } else {
+                100
+            }
+        } else {
+            100
+        };
+
+        // Use X_MAX_POS for X slider max range
+        let x_slider_max: Option<i32> = settings.x_max_pos;
+
+        // Load operations settings for z_up_step and z_down_step
+        let ops_settings = match config_loader::load_operations_settings(hostname) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Warning: Could not load operations settings: {}. Using defaults.", e);
+                config_loader::OperationsSettings {
+                    z_up_step: Some(2),
+                    z_down_step: Some(-2),
+                    bump_check_enable: true,
+                    tune_rest: Some(10.0),
+                    x_rest: Some(10.0),
+                    z_rest: Some(5.0),
+                    lap_rest: Some(4.0),
+                    adjustment_level: Some(4),
+                    retry_threshold: Some(50),
+                    delta_threshold: Some(50),
+                    z_variance_threshold: Some(50),
+                    x_start: Some(100),
+                    x_finish: Some(default_x_finish),
+                    x_step: Some(10),
+                    bump_retract_step: None,
+                    bump_clear_readings: None,
+                    bump_settle_rest: None,
+                    bump_final_margin: None,
+                    adaptive_z_step: false,
+                    z_max_step: None,
+                    performance_rest: None,
+                    performance_bump_interval: None,
+                    performance_use_controller: false,
+                    partials_stale_limit: None,
+                    voice_floor_relative: false,
+                    voice_floor: None,
+                    voice_floors: Vec::new(),
+                    channel_map: None,
+                }
+            }
+        };
+        let z_up_step = ops_settings.z_up_step.unwrap_or(2);
+        let z_down_step = ops_settings.z_down_step.unwrap_or(-2);
+        let x_step = ops_settings.x_step.unwrap_or(10);
+
+        let mainboard_tuner_count = config_loader::mainboard_tuner_indices(&settings).len();
+        let tuner_num_for_gui = if settings.ard_t_num_steppers.is_some() {
+            settings.ard_t_num_steppers
+        } else if mainboard_tuner_count > 0 {
+            Some(mainboard_tuner_count)
+        } else {
+            None
+        };
+
+        // Check if Arduino is configured
+        let port = settings.port.clone().ok_or_else(|| {
+            format!("No Arduino port configured for host '{}'. Set ARD_PORT in string_driver.yaml or use null if no Arduino.", hostname)
+        })?;
+        let num_steppers = settings.num_steppers.ok_or_else(|| {
+            format!("No Arduino steppers configured for host '{}'. Set ARD_NUM_STEPPERS in string_driver.yaml or use null if no Arduino.", hostname)
+        })?;
+
+        let mut app = StepperCore::new(
+            port,
+            num_steppers,
+            settings.string_num,
+            settings.x_step_index,
+            settings.z_first_index,
+            settings.tuner_first_index,
+            settings.ard_t_port.clone(),
+            tuner_num_for_gui,
+            debug,
+            z_up_step,
+            z_down_step,
+            settings.firmware,
+            x_slider_max, // Use GPIO_MAX_STEPS for slider range
+            x_step,
+            settings.ard_t_firmware,
+            settings.encoder_slip_threshold,
+        );
+
+        app.set_usb_matches(
+            settings.usb.as_ref().map(|u| UsbMatch { vid: u.vid, pid: u.pid, serial_number: u.serial_number.clone() }),
+            settings.ard_t_usb.as_ref().map(|u| UsbMatch { vid: u.vid, pid: u.pid, serial_number: u.serial_number.clone() }),
+        );
+        app.set_transports(Transport::from_settings(&settings.transport), Transport::from_settings(&settings.ard_t_transport));
+        app.set_reply_checksums(settings.checksum, settings.ard_t_checksum);
+        match config_loader::load_ipc_auth(hostname) {
+            Ok(Some(auth)) => {
+                let clients = auth
+                    .clients
+                    .iter()
+                    .map(|c| (c.token.clone(), Grant { name: c.name.clone(), allow: Allow::parse(&c.allow) }))
+                    .collect();
+                app.set_access(AccessPolicy::new(clients, Allow::parse(&auth.socket_default)));
+            }
+            Ok(None) => {}
+            // Fail closed: a broken IPC_AUTH must not leave the socket open
+            Err(e) => {
+                eprintln!("ERROR: IPC_AUTH invalid ({}); socket clients limited to queries", e);
+                app.set_access(AccessPolicy::new(Vec::new(), Allow::parse(&["read_only".to_string()])));
+            }
+        }
+        match config_loader::load_stepper_bridge(hostname) {
+            Ok(Some(config_loader::StepperBridgeSettings { listen: Some(listen), token, .. })) => app.set_bridge(listen, token),
+            Ok(_) => {}
+            Err(e) => eprintln!("WARNING: STEPPER_BRIDGE ignored: {}", e),
+        }
+        app.set_reply_timeouts(settings.reply_timeouts.positions, settings.reply_timeouts.encoders);
+        app.set_tuner_steps(settings.tuner_steps.coarse, settings.tuner_steps.fine);
+        app.set_jog_rate(settings.jog_rate_hz);
+        if let Some(params) = settings.motion_params {
+            app.set_motion_params(params);
+        }
+        app.set_axes(settings.axes.clone());
+        let bow_settings = config_loader::load_bow_drive_settings(hostname)
+            .map_err(|e| format!("Invalid BOW_DRIVE for host '{}': {}", hostname, error::user_message(&e)))?;
+        app.set_bow_drive(bow_settings.map(|bow| bow_drive::BowDrive::new(bow, settings.string_num)));
+        app.set_tuner_guard(settings.tuner_safety.as_ref().map(|safety| tension::TunerGuard::new(safety, settings.string_num)));
+        let damper_settings = config_loader::load_damper_settings(hostname)
+            .map_err(|e| format!("Invalid DAMPERS for host '{}': {}", hostname, error::user_message(&e)))?;
+        app.set_damper_command(damper_settings.as_ref().and_then(|dampers| dampers.serial_command()));
+        app.set_string_info(
+            settings.strings.iter().map(|s| s.name.clone()).collect(),
+            settings.strings.iter().map(|s| s.color).collect(),
+        );
+        app.enable_position_model();
+        Ok(app)
+    }
+
+    /// Auto-connect on startup (mirror Python's automatic arduino_init): the main board, then the tuner board if configured
+    pub fn connect_configured(&mut self) {
+        self.connect();
+        if !self.topology.tuners().is_empty() {
+            self.connect_tuner();
+        }
+        if !self.connected {
+            eprintln!("WARNING: Failed to connect to Arduino at {}", self.port_path);
+        }
+    }
+
+    /// Run one socket-protocol command in process, as client `client` (stringdriverd's own
+    /// automation). Err when it was refused or failed, did not finish on the board, or
+    /// left the board's positions unreadable.
+    pub fn run_command(&mut self, cmd: &str, client: &str) -> error::Result<()> {
+        self.last_error = None;
+        self.handle_command(cmd, client, None);
+        let mut finished = true;
+        if Self::completes_on_board(cmd) {
+            // The serial threads report through a channel, not this lock, so waiting here is safe
+            let barrier = self.serial_barrier();
+            finished = Self::await_barrier(barrier, cmd);
+            self.drain_serial_events();
+        }
+        self.broadcast_shared_if_changed();
+        if let Some(message) = self.last_error.take() {
+            return Err(error::Error::Other(format!("{}: {}", cmd, message.trim_start_matches("ERROR: "))));
+        }
+        if !finished {
+            return Err(error::Error::Serial(format!("{}: not finished by the board within {}s", cmd, IPC_COMPLETION_TIMEOUT.as_secs())));
+        }
+        if !Self::completes_on_board(cmd) {
+            return Ok(());
+        }
+        let fault = if cmd.starts_with("tuner_") && self.tuner_serial.is_some() { &self.tuner_positions_fault } else { &self.positions_fault };
+        match fault {
+            Some(fault) => Err(error::Error::Serial(format!("{}: board positions stale: {}", cmd, fault))),
+            None => Ok(()),
+        }
+    }
+
+    /// Main board positions, None while disconnected or while they can't be read
+    pub fn reported_positions(&mut self) -> Option<Vec<i32>> {
+        self.drain_serial_events();
+        (self.connected && self.positions_fault.is_none()).then(|| self.positions.clone())
+    }
+
+    /// Z stepper upper limit (MOTION_PARAMS Z MAX, else the default)
+    pub fn z_max(&self) -> i32 {
+        self.z_max
+    }
+
+    /// Main board firmware protocol, once it answered the version query
+    pub fn firmware_protocol(&self) -> Option<i32> {
+        self.firmware_protocol
+    }
+
+    /// Close both boards' ports. The serial threads finish the commands already
+    /// queued, then exit and drop their ports.
+    pub fn disconnect(&mut self) {
+        self.drain_serial_events();
+        if self.serial.take().is_some() {
+            self.log("Disconnected from main board");
+        }
+        if self.tuner_serial.take().is_some() {
+            self.log("Disconnected from tuner board");
+        }
+        self.connected = false;
+        self.tuner_connected = false;
+        self.moves_in_flight.clear();
+    }
+
+    /// Apply positions and encoder readings reported by the serial threads.
+    /// Returns true if anything arrived (the caller should repaint).
+    fn drain_serial_events(&mut self) -> bool {
+        let mut received = false;
+        while let Ok(event) = self.serial_events_rx.try_recv() {
+            received = true;
+            match event {
+                SerialEvent::Positions(Board::Main, positions) => {
+                    self.clear_positions_fault(Board::Main);
+                    let changed = positions != self.positions;
+                    self.positions = positions;
+                    if self.tuner_serial.is_none() {
+                        // Tuners on main board follow the main positions
+                        self.refresh_tuner_positions();
+                    }
+                    if changed {
+                        self.broadcast_positions();
+                    }
+                    if changed || self.startup_unchecked.contains(&Board::Main) {
+                        self.check_and_save_positions(Board::Main);
+                    }
+                }
+                SerialEvent::Positions(Board::Tuner, positions) => {
+                    self.clear_positions_fault(Board::Tuner);
+                    let changed = positions != self.tuner_positions;
+                    self.tuner_positions = positions;
+                    if changed || self.startup_unchecked.contains(&Board::Tuner) {
+                        self.check_and_save_positions(Board::Tuner);
+                    }
+                }
+                SerialEvent::PositionsFailed(board, error) => {
+                    let fault = match board {
+                        Board::Main => &mut self.positions_fault,
+                        Board::Tuner => &mut self.tuner_positions_fault,
+                    };
+                    if fault.replace(error.clone()).is_none() {
+                        self.log(&format!("ERROR: {:?} board positions stale: {}", board, error));
+                    }
+                }
+                SerialEvent::Encoders(Board::Main, encoders) => {
+                    self.encoder_positions = encoders;
+                    self.check_encoder_slip();
+                }
+                SerialEvent::Encoders(Board::Tuner, _) => {}
+                SerialEvent::FirmwareVersion(board, version) => {
+                    match version {
+                        Some(v) if v != build_info::FIRMWARE_PROTOCOL => self.log(&format!(
+                            "WARN: {:?} board firmware speaks protocol {}, expected {}",
+                            board, v, build_info::FIRMWARE_PROTOCOL
+                        )),
+                        Some(v) => self.log(&format!("{:?} board firmware protocol {}", board, v)),
+                        None => self.log(&format!("{:?} board did not answer the firmware version query", board)),
+                    }
+                    match board {
+                        Board::Main => self.firmware_protocol = version,
+                        Board::Tuner => self.tuner_firmware_protocol = version,
+                    }
+                }
+                SerialEvent::CommandStarted(board, cmd_id, stepper) => {
+                    if let Some(m) = self.moves_in_flight.iter_mut().find(|m| m.matches(board, cmd_id, stepper) && m.started.is_none()) {
+                        m.started = Some(Instant::now());
+                    }
+                }
+                SerialEvent::CommandDone(board, cmd_id, stepper) => {
+                    if let Some(i) = self.moves_in_flight.iter().position(|m| m.matches(board, cmd_id, stepper)) {
+                        self.moves_in_flight.remove(i);
+                    }
+                }
+            }
+        }
+        received
+    }
+
+    /// Keep positions in logs/ across restarts and check each board's first
+    /// report against what the last session left behind
+    pub fn enable_position_model(&mut self) {
+        let dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("logs");
+        self.set_position_model_path(dir.join(position_model::MODEL_FILE_NAME));
+    }
+
+    fn set_position_model_path(&mut self, path: std::path::PathBuf) {
+        self.position_model = position_model::load(&path);
+        self.startup_unchecked.clear();
+        if let Some(model) = &self.position_model {
+            self.startup_unchecked.push(Board::Main);
+            if self.tuner_port_path.is_some() && !model.tuner.is_empty() {
+                self.startup_unchecked.push(Board::Tuner);
+            }
+        }
+        self.position_model_path = Some(path);
+    }
+
+    /// Compare a board's first report with the model, then keep the model current.
+    /// While a board has unresolved discrepancies its saved positions are left
+    /// alone, so restarting again still shows the warning.
+    fn check_and_save_positions(&mut self, board: Board) {
+        let Some(path) = self.position_model_path.clone() else { return; };
+        let reported = match board {
+            Board::Main => self.positions.clone(),
+            Board::Tuner => self.tuner_positions.clone(),
+        };
+        if let Some(idx) = self.startup_unchecked.iter().position(|b| *b == board) {
+            self.startup_unchecked.remove(idx);
+            let expected = self.position_model.as_ref()
+                .map(|m| match board { Board::Main => m.main.clone(), Board::Tuner => m.tuner.clone() })
+                .unwrap_or_default();
+            let found = position_model::compare(&expected, &reported, STARTUP_POSITION_TOLERANCE);
+            if found.is_empty() {
+                self.log(&format!("Startup check: {:?} board positions match the last session", board));
+            }
+            for d in &found {
+                self.log(&format!(
+                    "WARNING: Startup check: {:?} stepper {} ({}) reports {}, last session left it at {}",
+                    board, d.stepper, self.axis_of(board, d.stepper), d.reported, d.expected
+                ));
+            }
+            self.startup_mismatch.extend(found.into_iter().map(|d| (board, d)));
+        }
+        if self.startup_mismatch.iter().any(|(b, _)| *b == board) {
+            return;
+        }
+        let model = self.position_model.get_or_insert_with(PositionModel::default);
+        match board {
+            Board::Main => model.main = reported,
+            Board::Tuner => model.tuner = reported,
+        }
+        model.saved_at = chrono::Local::now().to_rfc3339();
+        if let Err(e) = position_model::save(&path, model) {
+            // Reported once; the GUI carries on without the model
+            self.log(&format!("ERROR: Position model disabled: cannot write {:?}: {}", path, e));
+            self.position_model_path = None;
+        }
+    }
+
+    /// Axis a stepper belongs to, for the re-homing advice
+    fn axis_of(&self, board: Board, stepper: usize) -> &'static str {
+        if board == Board::Tuner {
+            return "tuners";
+        }
+        match self.topology.role_of(topology::Bank::Main, stepper) {
+            Some(role) => role.axis().name(),
+            None => "other",
+        }
+    }
+
+    /// Carriage board index of a tuner, None when tuners are on their own board
+    fn mainboard_tuner(&self, tuner_idx: usize) -> Option<usize> {
+        self.topology.tuner(tuner_idx).filter(|t| t.bank == topology::Bank::Main).map(|t| t.index)
+    }
+
+    /// Axes with unresolved startup discrepancies, Z first
+    fn rehome_axes(&self) -> Vec<&'static str> {
+        let flagged: Vec<&'static str> = self.startup_mismatch.iter().map(|(b, d)| self.axis_of(*b, d.stepper)).collect();
+        ["Z", "X", "tuners", "other"].into_iter().filter(|axis| flagged.contains(axis)).collect()
+    }
+
+    /// Operator confirmed the boards are right after all (e.g. steppers moved by hand)
+    #[cfg(feature = "gui")]
+    fn accept_reported_positions(&mut self) {
+        self.log("Startup check: reported positions accepted");
+        self.startup_mismatch.clear();
+        self.check_and_save_positions(Board::Main);
+        if self.tuner_port_path.is_some() {
+            self.check_and_save_positions(Board::Tuner);
+        }
+    }
+
+    /// A board answered again after failed position reads
+    fn clear_positions_fault(&mut self, board: Board) {
+        let fault = match board {
+            Board::Main => self.positions_fault.take(),
+            Board::Tuner => self.tuner_positions_fault.take(),
+        };
+        if fault.is_some() {
+            self.log(&format!("{:?} board positions readable again", board));
+            // A board that stopped answering may have reset and lost its speeds and limits
+            if self.motion_sync {
+                self.sync_motion_params(board, "board answering again");
+            }
+        }
+    }
+
+    /// Compare encoder readings against step counts and flag steppers that have slipped.
+    /// Logs once when a stepper starts slipping and once when it recovers.
+    fn check_encoder_slip(&mut self) {
+        let mut messages = Vec::new();
+        for (idx, encoder) in self.encoder_positions.iter().enumerate() {
+            let (Some(encoder), Some(&steps)) = (encoder, self.positions.get(idx)) else { continue; };
+            let diff = encoder - steps;
+            let slipping = diff.abs() > self.encoder_slip_threshold;
+            if slipping && self.slipping_steppers.insert(idx) {
+                messages.push(format!(
+                    "SLIP WARN: stepper {} step count {} vs encoder {} (diff {})",
+                    idx, steps, encoder, diff
+                ));
+            } else if !slipping && self.slipping_steppers.remove(&idx) {
+                messages.push(format!("SLIP CLEARED: stepper {} back within {} steps of encoder", idx, self.encoder_slip_threshold));
+            }
+        }
+        for message in messages {
+            self.log(&message);
+        }
+    }
+
+    /// Show the lines captured by the process-wide tracing subscriber (see logging::init)
+    pub fn attach_log_buffer(&mut self, buffer: LogBuffer) {
+        self.log_buffer = buffer;
+    }
+
+    /// Share a performer / technician mode switch with other GUIs
+    pub fn attach_role_lock(&mut self, role_lock: role::RoleLock) {
+        self.role_lock = role_lock;
+    }
+
+    /// Look boards up by USB VID/PID/serial number when connecting (ARD_USB / ARD_T_USB)
+    pub fn set_usb_matches(&mut self, main: Option<UsbMatch>, tuner: Option<UsbMatch>) {
+        self.usb_match = main;
+        self.tuner_usb_match = tuner;
+    }
+
+    /// Reach the boards over `main` / `tuner` instead of their serial devices
+    pub fn set_transports(&mut self, main: Transport, tuner: Transport) {
+        self.transport = main;
+        self.tuner_transport = tuner;
+    }
+
+    /// This build, with the main board's firmware protocol when it answered
+    fn build_info(&self) -> BuildInfo {
+        BuildInfo { firmware: self.firmware_protocol, ..BuildInfo::local("stepper_gui") }
+    }
+
+    /// Also serve the socket protocol over TCP at `listen` (STEPPER_BRIDGE); `token` gets full control
+    pub fn set_bridge(&mut self, listen: String, token: String) {
+        let bridge = Grant { name: "bridge".to_string(), allow: Allow::full() };
+        self.access = Arc::new((*self.access).clone().with_client(token, bridge));
+        self.bridge = Some(listen);
+    }
+
+    /// Check socket and bridge clients against `policy` (IPC_AUTH)
+    pub fn set_access(&mut self, policy: AccessPolicy) {
+        self.access = Arc::new(policy);
+    }
+
+    /// Require the CRC-8 reply argument from boards whose firmware sends one
+    pub fn set_reply_checksums(&mut self, main: bool, tuner: bool) {
+        self.command_set.checksum = main;
+        self.tuner_command_set.checksum = tuner;
+    }
+
+    /// How long to wait for positions and encoder replies (ARD_REPLY_TIMEOUTS), both boards
+    pub fn set_reply_timeouts(&mut self, positions: Duration, encoders: Duration) {
+        for command_set in [&mut self.command_set, &mut self.tuner_command_set] {
+            command_set.positions_timeout = positions;
+            command_set.encoders_timeout = encoders;
+        }
+    }
+
+    /// Device path to open for a board; port_path itself stays as configured
+    /// since it also names the IPC socket
+    fn resolve_device(&mut self, role: &str, configured: &str, usb: Option<UsbMatch>) -> Option<String> {
+        match arduino_connection::resolve_port(role, configured, usb.as_ref()) {
+            Ok(resolved) => {
+                if usb.is_some() {
+                    self.log(&format!("{} board bound to {} ({})", role, resolved.path, resolved.detail));
+                }
+                Some(resolved.path)
+            }
+            Err(e) => {
+                self.log(&format!("ERROR: {}", error::user_message(&e)));
+                None
+            }
+        }
+    }
+
+    fn log(&mut self, message: &str) {
+        // Level follows the message prefix; the GUI layer keeps debug and above
+        if message.starts_with("ERROR") {
+            tracing::error!("{}", message);
+            self.last_error = Some(message.to_string());
+        } else if message.starts_with("WARN") || message.starts_with("SLIP WARN") {
+            tracing::warn!("{}", message);
+        } else {
+            tracing::debug!("{}", message);
+        }
+    }
+
+    pub fn connect(&mut self) {
+        let configured = self.port_path.clone();
+        let transport = self.transport.clone();
+        let port_path = if transport.resets_board() {
+            let Some(port_path) = self.resolve_device("main", &configured, self.usb_match.clone()) else {
+                self.connected = false;
+                return;
+            };
+            self.kill_port_users(&port_path);
+            port_path
+        } else {
+            configured
+        };
+        self.log(&format!("Connecting to Arduino on {}", transport.describe(&port_path)));
+        match open_port(&transport, &port_path) {
+            Ok(port) => {
+                if transport.resets_board() {
+                    self.log("Port opened, waiting 2s for Arduino reset...");
+                    thread::sleep(Duration::from_millis(2000));
+                }
+                self.serial = Some(SerialWorker::spawn(
+                    Board::Main,
+                    port,
+                    self.command_set,
+                    self.positions.len(),
+                    self.serial_events_tx.clone(),
+                    Arc::clone(&self.audit_log),
+                ));
+                self.connected = true;
+                self.send_serial(Board::Main, SerialRequest::QueryFirmwareVersion);
+                self.log("Connected. Requesting positions...");
+                // The board resets on connect, so cut current again on steppers still disabled
+                for stepper in self.disabled_steppers.clone() {
+                    self.set_stepper_enabled("reconnect", stepper, false);
+                }
+                // Speeds and limits are back at firmware defaults as well
+                if self.motion_sync {
+                    self.sync_motion_params(Board::Main, "connect");
+                }
+                self.refresh_positions();
+            }
+            Err(e) => {
+                self.log(&format!("Connection failed: {}", e));
+            }
+        }
+    }
+
+    /// Ask the serial thread for fresh positions; they arrive via drain_serial_events()
+    fn refresh_positions(&mut self) {
+        self.send_serial(Board::Main, SerialRequest::RefreshPositions);
+    }
+
+    #[cfg(feature = "gui")]
+    fn move_stepper(&mut self, stepper: usize, delta: i32) {
+        self.move_stepper_with_source("UI", stepper, delta);
+    }
+
+    /// Enable or disable a main-board stepper. A disabled stepper refuses every move,
+    /// whoever sends it, and its driver current is cut where the firmware has set_enable.
+    fn set_stepper_enabled(&mut self, source: &str, stepper: usize, enabled: bool) {
+        let action = if enabled { "enable" } else { "disable" };
+        if enabled {
+            self.disabled_steppers.remove(&stepper);
+        } else {
+            self.disabled_steppers.insert(stepper);
+        }
+        match self.command_set.enable_id {
+            Some(enable_id) if self.serial.is_some() => {
+                self.log(&format!(">>> {} {} stepper {} (driver current {})", source, action.to_uppercase(), stepper, if enabled { "on" } else { "off" }));
+                self.send_serial(Board::Main, SerialRequest::Command {
+                    cmd_id: enable_id,
+                    stepper: stepper as i16,
+                    value: enabled as i32,
+                    refresh_after: None,
+                    audit: Some(AuditEntry::new(source, action, stepper, enabled as i32)),
+                });
+            }
+            _ => {
+                // No current control: the lockout alone keeps the stepper still
+                self.log(&format!(">>> {} {} stepper {} (moves only - firmware cannot switch driver current)", source, action.to_uppercase(), stepper));
+                let mut entry = AuditEntry::new(source, action, stepper, enabled as i32);
+                entry.result = self.positions.get(stepper).copied();
+                if let Ok(mut log) = self.audit_log.lock() {
+                    log.record(&entry);
+                }
+            }
+        }
+    }
+
+    /// Refuse moves to a disabled stepper (logged); true when the move may go ahead
+    fn move_allowed(&mut self, source: &str, stepper: usize) -> bool {
+        if self.disabled_steppers.contains(&stepper) {
+            self.log(&format!("ERROR: {} move of stepper {} refused - stepper is disabled", source, stepper));
+            return false;
+        }
+        true
+    }
+
+    fn move_stepper_with_source(&mut self, source: &str, stepper: usize, delta: i32) {
+        if self.serial.is_none() {
+            self.log(&format!("ERROR: Cannot move - port not connected"));
+            return;
+        }
+        if !self.move_allowed(source, stepper) {
+            return;
+        }
+        let s = stepper as i16;
+        let adjusted_delta = self.firmware_delta(stepper, delta);
+        self.log(&format!(">>> {} MOVING stepper {} by {} (rmove command, adjusted: {})", source, stepper, delta, adjusted_delta));
+        // Arduino move is synchronous - serial thread waits for it before refreshing positions
+        let cmd_id = self.command_set.rmove_id;
+        if self.send_serial(Board::Main, SerialRequest::Command {
+            cmd_id,
+            stepper: s,
+            value: adjusted_delta,
+            refresh_after: Some(Duration::from_millis(500)),
+            audit: Some(AuditEntry::new(source, "rel_move", stepper, delta)),
+        }) {
+            self.track_move(Board::Main, stepper, cmd_id, Some(delta), None);
+        }
+    }
+
+    /// V1 firmware multiplies X stepper (index 2) moves by 2, so divide by 2 to compensate
+    fn firmware_delta(&self, stepper: usize, delta: i32) -> i32 {
+        if self.firmware == ArduinoFirmware::StringDriverV1 && self.topology.x_index() == Some(stepper) {
+            delta / 2
+        } else {
+            delta
+        }
+    }
+
+    fn move_stepper_absolute_with_source(&mut self, source: &str, stepper: usize, position: i32) {
+        if self.serial.is_none() {
+            self.log(&format!("ERROR: Cannot move - port not connected"));
+            return;
+        }
+        if !self.move_allowed(source, stepper) {
+            return;
+        }
+        let s = stepper as i16;
+        self.log(&format!(">>> {} MOVING stepper {} to absolute position {} (amove command)", source, stepper, position));
+        // Arduino move is synchronous - serial thread waits for it before refreshing positions
+        let cmd_id = self.command_set.amove_id;
+        if self.send_serial(Board::Main, SerialRequest::Command {
+            cmd_id,
+            stepper: s,
+            value: position,
+            refresh_after: Some(Duration::from_millis(500)),
+            audit: Some(AuditEntry::new(source, "abs_move", stepper, position)),
+        }) {
+            self.track_move(Board::Main, stepper, cmd_id, None, Some(position));
+        }
+    }
+
+    fn reset_position(&mut self, source: &str, stepper: usize, position: i32) {
+        if self.serial.is_none() {
+            self.log(&format!("ERROR: Cannot reset position - port not connected"));
+            return;
+        }
+        let s = stepper as i16;
+        self.log(&format!(">>> {} RESETTING stepper {} to {} (set_stepper command - no physical move)", source, stepper, position));
+        if let Some(set_encoder_id) = self.command_set.set_encoder_id {
+            // Keep the encoder reference in step with the new step count
+            self.send_cmd_bin(set_encoder_id, s, position);
+        }
+        // set_stepper is fast - just sets internal counter
+        self.send_serial(Board::Main, SerialRequest::Command {
+            cmd_id: self.command_set.set_stepper_id,
+            stepper: s,
+            value: position,
+            refresh_after: Some(Duration::from_millis(100)),
+            audit: Some(AuditEntry::new(source, "reset", stepper, position)),
+        });
+        // Homing ends in a reset, which settles any startup discrepancy for the stepper
+        self.startup_mismatch.retain(|(b, d)| !(*b == Board::Main && d.stepper == stepper));
+    }
+
+    /// Speed and accel last set for the stepper's axis (0, 0 for steppers outside X, Z and the tuners)
+    #[cfg(feature = "gui")]
+    fn axis_motion(&self, board: Board, stepper: usize) -> (i32, i32) {
+        match self.axis_of(board, stepper) {
+            "X" => (self.x_speed, self.x_accel),
+            "Z" => (self.z_speed, self.z_accel),
+            "tuners" => (self.tuner_speed, self.tuner_accel),
+            _ => self.axes
+                .iter()
+                .find(|axis| board == Board::Main && axis.step_index == stepper)
+                .map_or((0, 0), |axis| (axis.motion.speed.unwrap_or(0), axis.motion.accel.unwrap_or(0))),
+        }
+    }
+
+    /// Record a queued move (by `delta` or to `target`) for the motion indicator.
+    /// It starts from where the stepper's previous queued move ends, else its reported position.
+    fn track_move(&mut self, board: Board, stepper: usize, cmd_id: u8, delta: Option<i32>, target: Option<i32>) {
+        let reported = match board {
+            Board::Main => self.positions.get(stepper),
+            Board::Tuner => self.tuner_positions.get(stepper),
+        };
+        let from = self.moves_in_flight.iter().rev()
+            .find(|m| m.board == board && m.stepper == stepper)
+            .map(|m| m.target)
+            .or(reported.copied())
+            .unwrap_or(0);
+        let target = target.unwrap_or(from + delta.unwrap_or(0));
+        self.moves_in_flight.push(MoveInFlight {
+            board,
+            stepper,
+            cmd_id,
+            target,
+            #[cfg(feature = "gui")]
+            estimate: {
+                let (speed, accel) = self.axis_motion(board, stepper);
+                travel_time(target - from, speed, accel)
+            },
+            started: None,
+        });
+    }
+
+    /// The oldest move in flight for a stepper (the one executing, or next to)
+    #[cfg(feature = "gui")]
+    fn move_in_flight(&self, board: Board, stepper: usize) -> Option<&MoveInFlight> {
+        self.moves_in_flight.iter().find(|m| m.board == board && m.stepper == stepper)
+    }
+
+    /// The board and stepper a jog target moves
+    #[cfg(feature = "gui")]
+    fn jog_stepper(&self, target: JogTarget) -> Option<(Board, usize)> {
+        match target {
+            JogTarget::Stepper(stepper) => Some((Board::Main, stepper)),
+            JogTarget::Tuner(tuner_idx) if self.tuner_serial.is_some() => Some((Board::Tuner, tuner_idx)),
+            JogTarget::Tuner(tuner_idx) => self.mainboard_tuner(tuner_idx).map(|main_idx| (Board::Main, main_idx)),
+        }
+    }
+
+    /// The + and - steps of a target's buttons
+    #[cfg(feature = "gui")]
+    fn jog_steps(&self, target: JogTarget) -> (i32, i32) {
+        match target {
+            JogTarget::Tuner(_) => (self.tuner_nudge(), -self.tuner_nudge()),
+            JogTarget::Stepper(stepper) if self.topology.x_index() == Some(stepper) => (self.x_step, -self.x_step),
+            JogTarget::Stepper(stepper) => match self.axes.iter().find(|axis| axis.step_index == stepper) {
+                Some(axis) => (axis.step, -axis.step),
+                None => (self.z_up_step, self.z_down_step),
+            },
+        }
+    }
+
+    /// Start, continue or end the jog for the buttons and keys held this frame.
+    /// A new press steps once at once, as a click did; held past JOG_HOLD_DELAY it
+    /// sends one move per 1 / JOG_RATE_HZ, each only once the previous one is done,
+    /// so at most one move is left to finish after release.
+    #[cfg(feature = "gui")]
+    fn service_jog(&mut self, now: Instant) {
+        let Some((target, delta)) = self.jog_requests.drain(..).next_back() else {
+            self.end_jog();
+            return;
+        };
+        let Some(mut jog) = self.jog.take().filter(|jog| jog.target == target && jog.delta == delta) else {
+            self.end_jog();
+            if self.topology.x_index().map(JogTarget::Stepper) != Some(target) {
+                self.jog_key_target = Some(target);
+            }
+            match target {
+                JogTarget::Stepper(stepper) => self.move_stepper(stepper, delta),
+                JogTarget::Tuner(tuner_idx) => self.move_tuner(tuner_idx, delta),
+            }
+            self.jog = Some(Jog { target, delta, next_step: now + JOG_HOLD_DELAY, steps: 0, total: 0, stopped: false });
+            return;
+        };
+        let busy = self.jog_stepper(target).is_some_and(|(board, stepper)| self.move_in_flight(board, stepper).is_some());
+        if !jog.stopped && !busy && now >= jog.next_step {
+            match self.jog_step(target, delta) {
+                Some(moved) => {
+                    jog.steps += 1;
+                    jog.total += moved;
+                    jog.next_step = now + Duration::from_secs_f32(1.0 / self.jog_rate_hz);
+                }
+                None => jog.stopped = true,
+            }
+        }
+        self.jog = Some(jog);
+    }
+
+    /// One jog move, checked as a click's move is but read back at once and with no
+    /// log line or audit entry of its own. Returns the steps moved, None when refused.
+    #[cfg(feature = "gui")]
+    fn jog_step(&mut self, target: JogTarget, delta: i32) -> Option<i32> {
+        let (board, stepper) = self.jog_stepper(target)?;
+        let delta = match target {
+            JogTarget::Tuner(tuner_idx) => {
+                let (tuner_min, tuner_max) = self.tuner_limits();
+                let current = self.tuner_positions.get(tuner_idx).copied().unwrap_or(0);
+                let delta = (current + delta).clamp(tuner_min, tuner_max) - current;
+                if delta == 0 {
+                    return None;
+                }
+                if let Err(e) = self.check_tuner_move(tuner_idx, current, current + delta) {
+                    self.log(&format!("ERROR: Tuner {} jog stopped - {}", tuner_idx, e));
+                    return None;
+                }
+                delta
+            }
+            JogTarget::Stepper(_) => delta,
+        };
+        if board == Board::Main && !self.move_allowed("UI", stepper) {
+            return None;
+        }
+        let (cmd_id, value) = match board {
+            Board::Main => (self.command_set.rmove_id, self.firmware_delta(stepper, delta)),
+            Board::Tuner => (self.tuner_command_set.rmove_id, delta),
+        };
+        // The board finishes the move before it answers, so the query right behind it reads where it stopped
+        if !self.send_serial(board, SerialRequest::Command { cmd_id, stepper: stepper as i16, value, refresh_after: Some(Duration::ZERO), audit: None }) {
+            return None;
+        }
+        self.track_move(board, stepper, cmd_id, Some(delta), None);
+        Some(delta)
+    }
+
+    /// Released: log and audit the jog as one move
+    #[cfg(feature = "gui")]
+    fn end_jog(&mut self) {
+        let Some(jog) = self.jog.take() else { return; };
+        if jog.steps == 0 {
+            return;
+        }
+        let (what, action, index, position) = match jog.target {
+            JogTarget::Stepper(stepper) => ("stepper", "jog", stepper, self.positions.get(stepper).copied()),
+            JogTarget::Tuner(tuner_idx) => ("tuner", "tuner_jog", tuner_idx, self.tuner_positions.get(tuner_idx).copied()),
+        };
+        self.log(&format!(">>> UI JOGGED {} {} by {} ({} moves after the first step)", what, index, jog.total, jog.steps));
+        let mut entry = AuditEntry::new("UI", action, index, jog.total);
+        // As last read back; the final move may still be running
+        entry.result = position;
+        if let Ok(mut log) = self.audit_log.lock() {
+            log.record(&entry);
+        }
+    }
+
+    fn set_accel(&mut self, stepper: usize, accel: i32) {
+        if self.serial.is_none() {
+            self.log(&format!("ERROR: Cannot set acceleration - port not connected"));
+            return;
+        }
+        let s = stepper as i16;
+        self.log(&format!(">>> SETTING stepper {} acceleration to {} (set_accel command)", stepper, accel));
+        self.send_cmd_bin(self.command_set.set_accel_id, s, accel);
+    }
+
+    fn set_speed(&mut self, stepper: usize, speed: i32) {
+        if self.serial.is_none() {
+            self.log(&format!("ERROR: Cannot set speed - port not connected"));
+            return;
+        }
+        let s = stepper as i16;
+        self.log(&format!(">>> SETTING stepper {} speed to {} (set_speed command)", stepper, speed));
+        self.send_cmd_bin(self.command_set.set_speed_id, s, speed);
+    }
+
+    /// Send the firmware min/max for every stepper in `group`, to the tuner board when the
+    /// tuners have one and the main board otherwise, at the minmax row that board's firmware
+    /// keeps for the group. Refused when the firmware has no row of its own for the group
+    /// (v2/v3 main-board tuners share Z's) or the board is not connected.
+    fn set_limits(&mut self, group: LimitGroup, min: i32, max: i32) -> Result<(), String> {
+        if min > max {
+            return Err(format!("{} limits {}..{}: min is above max", group.label(), min, max));
+        }
+        let board = if group == LimitGroup::Tuners && self.tuner_serial.is_some() { Board::Tuner } else { Board::Main };
+        let (command_set, connected) = match board {
+            Board::Main => (self.command_set, self.serial.is_some()),
+            Board::Tuner => (self.tuner_command_set, self.tuner_serial.is_some()),
+        };
+        let Some(row) = command_set.limit_groups.get(group) else {
+            return Err(format!("{:?} board firmware keeps no separate {} limits", board, group.label()));
+        };
+        if !connected {
+            return Err(format!("Cannot set {} limits - port not connected", group.label()));
+        }
+        self.log(&format!(">>> SETTING {} limits to {}..{} (set_min/set_max row {})", group.label(), min, max, row));
+        for (cmd_id, value) in [(command_set.set_min_id, min), (command_set.set_max_id, max)] {
+            self.send_serial(board, SerialRequest::Command { cmd_id, stepper: row, value, refresh_after: None, audit: None });
+        }
+        Ok(())
+    }
+
+    /// set_limits for the GUI and motion sync, which log a refusal instead of returning it
+    fn apply_limits(&mut self, group: LimitGroup, min: i32, max: i32) {
+        if let Err(e) = self.set_limits(group, min, max) {
+            self.log(&format!("ERROR: {}", e));
+        }
+    }
+
+    pub fn connect_tuner(&mut self) {
+        if let Some(configured) = self.tuner_port_path.clone() {
+            let transport = self.tuner_transport.clone();
+            let port_path = if transport.resets_board() {
+                let Some(port_path) = self.resolve_device("tuner", &configured, self.tuner_usb_match.clone()) else {
+                    self.tuner_connected = false;
+                    return;
+                };
+                self.kill_port_users(&port_path);
+                port_path
+            } else {
+                configured
+            };
+            self.log(&format!("Connecting to tuner Arduino on {}", transport.describe(&port_path)));
+            match open_port(&transport, &port_path) {
+                Ok(port) => {
+                    if transport.resets_board() {
+                        self.log("Tuner port opened, waiting 2s for Arduino reset...");
+                        thread::sleep(Duration::from_millis(2000));
+                    }
+                    self.tuner_serial = Some(SerialWorker::spawn(
+                        Board::Tuner,
+                        port,
+                        self.tuner_command_set,
+                        self.tuner_positions.len(),
+                        self.serial_events_tx.clone(),
+                        Arc::clone(&self.audit_log),
+                    ));
+                    self.tuner_connected = true;
+                    self.send_serial(Board::Tuner, SerialRequest::QueryFirmwareVersion);
+                    self.log("Tuner connected. Requesting positions...");
+                    if self.motion_sync {
+                        self.sync_motion_params(Board::Tuner, "connect");
+                    }
+                    self.refresh_tuner_positions();
+                }
+                Err(e) => {
+                    self.log(&format!("Tuner connection failed: {}", e));
+                }
+            }
+        } else if !self.topology.mainboard_tuner_indices().is_empty() {
+            // Tuners on main board - positions come from main board
+            self.log("Tuners on main board - using main positions");
+            self.tuner_connected = true;
+            self.refresh_tuner_positions();
+        }
+    }
+
+    fn refresh_tuner_positions(&mut self) {
+        if self.tuner_serial.is_some() {
+            self.send_serial(Board::Tuner, SerialRequest::RefreshPositions);
+        } else if self.tuner_connected {
+            // Tuners on main board - extract from main positions
+            for (i, main_idx) in self.topology.mainboard_tuner_indices().into_iter().enumerate() {
+                if main_idx < self.positions.len() && i < self.tuner_positions.len() {
+                    self.tuner_positions[i] = self.positions[main_idx];
+                }
+            }
+        }
+    }
+
+    /// Tuner inc/dec step for the current coarse / fine setting
+    #[cfg(feature = "gui")]
+    fn tuner_nudge(&self) -> i32 {
+        if self.tuner_fine { self.tuner_fine_step } else { self.tuner_step }
+    }
+
+    /// Coarse and fine tuner nudge sizes (TUNER_STEPS)
+    pub fn set_tuner_steps(&mut self, coarse: i32, fine: i32) {
+        self.tuner_step = coarse;
+        self.tuner_fine_step = fine;
+    }
+
+    /// Moves per second while a +/- button or arrow key is held (JOG_RATE_HZ)
+    pub fn set_jog_rate(&mut self, hz: f32) {
+        self.jog_rate_hz = hz;
+    }
+
+    /// Per-string NAME and COLOR from STRINGS, indexed by string
+    pub fn set_string_info(&mut self, names: Vec<Option<String>>, colors: Vec<Option<[u8; 3]>>) {
+        self.string_names = names;
+        self.string_colors = colors;
+    }
+
+    /// "String 4 (A2)", or "String 4" without a NAME
+    fn string_label(&self, string_idx: usize) -> String {
+        match self.string_names.get(string_idx).and_then(|n| n.as_deref()) {
+            Some(name) => format!("String {} ({})", string_idx, name),
+            None => format!("String {}", string_idx),
+        }
+    }
+
+    fn tuner_limits(&self) -> (i32, i32) {
+        (self.tuner_min.min(self.tuner_max), self.tuner_max.max(self.tuner_min))
+    }
+
+    #[cfg(feature = "gui")]
+    fn move_tuner(&mut self, tuner_idx: usize, delta: i32) {
+        self.move_tuner_with_source("UI", tuner_idx, delta);
+    }
+
+    /// Nudge a tuner, shortened so it stops at the tuner min/max
+    fn move_tuner_with_source(&mut self, source: &str, tuner_idx: usize, delta: i32) {
+        let (tuner_min, tuner_max) = self.tuner_limits();
+        let current = self.tuner_positions.get(tuner_idx).copied().unwrap_or(0);
+        let delta = (current + delta).clamp(tuner_min, tuner_max) - current;
+        if delta == 0 {
+            self.log(&format!("Tuner {} already at its limit ({})", tuner_idx, current));
+            return;
+        }
+        if let Err(e) = self.check_tuner_move(tuner_idx, current, current + delta) {
+            self.log(&format!("ERROR: Tuner {} move refused - {}", tuner_idx, e));
+            return;
+        }
+        if self.tuner_serial.is_some() {
+            // Tuners on separate board
+            let t = tuner_idx as i16;
+            self.log(&format!(">>> {} MOVING tuner {} by {} (rmove command)", source, tuner_idx, delta));
+            let cmd_id = self.tuner_command_set.rmove_id;
+            if self.send_serial(Board::Tuner, SerialRequest::Command {
+                cmd_id,
+                stepper: t,
+                value: delta,
+                refresh_after: Some(Duration::from_millis(500)),
+                audit: Some(AuditEntry::new(source, "tuner_rel_move", tuner_idx, delta)),
+            }) {
+                self.track_move(Board::Tuner, tuner_idx, cmd_id, Some(delta), None);
+            }
+        } else if let Some(main_idx) = self.mainboard_tuner(tuner_idx) {
+            // Tuners on main board - use main board
+            self.move_stepper_with_source(source, main_idx, delta);
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    fn move_tuner_absolute(&mut self, tuner_idx: usize, position: i32) {
+        self.move_tuner_absolute_with_source("UI", tuner_idx, position);
+    }
+
+    /// Move a tuner to `position`, clamped to the tuner min/max
+    fn move_tuner_absolute_with_source(&mut self, source: &str, tuner_idx: usize, position: i32) {
+        let (tuner_min, tuner_max) = self.tuner_limits();
+        let position = position.clamp(tuner_min, tuner_max);
+        let current = self.tuner_positions.get(tuner_idx).copied().unwrap_or(0);
+        if let Err(e) = self.check_tuner_move(tuner_idx, current, position) {
+            self.log(&format!("ERROR: Tuner {} move refused - {}", tuner_idx, e));
+            return;
+        }
+        if self.tuner_serial.is_some() {
+            // Tuners on separate board
+            let t = tuner_idx as i16;
+            self.log(&format!(">>> {} MOVING tuner {} to absolute position {} (amove command)", source, tuner_idx, position));
+            let cmd_id = self.tuner_command_set.amove_id;
+            if self.send_serial(Board::Tuner, SerialRequest::Command {
+                cmd_id,
+                stepper: t,
+                value: position,
+                refresh_after: Some(Duration::from_millis(500)),
+                audit: Some(AuditEntry::new(source, "tuner_abs_move", tuner_idx, position)),
+            }) {
+                self.track_move(Board::Tuner, tuner_idx, cmd_id, None, Some(position));
+            }
+        } else if let Some(main_idx) = self.mainboard_tuner(tuner_idx) {
+            // Tuners on main board - use main board
+            self.move_stepper_absolute_with_source(source, main_idx, position);
+        }
+    }
+
+    fn send_cmd_bin_tuner(&mut self, cmd_id: u8, stepper_idx: i16, value: i32) {
+        self.send_serial(Board::Tuner, SerialRequest::Command { cmd_id, stepper: stepper_idx, value, refresh_after: None, audit: None });
+    }
+
+    fn set_tuner_accel(&mut self, tuner_idx: usize, accel: i32) {
+        if self.tuner_serial.is_some() {
+            // Tuners on separate board
+            let t = tuner_idx as i16;
+            self.log(&format!(">>> SETTING tuner {} acceleration to {} (set_accel command)", tuner_idx, accel));
+            self.send_cmd_bin_tuner(self.tuner_command_set.set_accel_id, t, accel);
+        } else if let Some(main_idx) = self.mainboard_tuner(tuner_idx) {
+            // Tuners on main board - use main board
+            self.set_accel(main_idx, accel);
+        }
+    }
+
+    fn set_tuner_speed(&mut self, tuner_idx: usize, speed: i32) {
+        if self.tuner_serial.is_some() {
+            // Tuners on separate board
+            let t = tuner_idx as i16;
+            self.log(&format!(">>> SETTING tuner {} speed to {} (set_speed command)", tuner_idx, speed));
+            self.send_cmd_bin_tuner(self.tuner_command_set.set_speed_id, t, speed);
+        } else if let Some(main_idx) = self.mainboard_tuner(tuner_idx) {
+            // Tuners on main board - use main board
+            self.set_speed(main_idx, speed);
+        }
+    }
+
+    fn apply_z_params_to_all(&mut self) {
+        // Apply z parameters to every string's in/out Z stepper
+        if self.topology.has_z() {
+            for stepper_idx in self.topology.z_indices() {
+                if stepper_idx < self.positions.len() {
+                    // Serial thread spaces these out (SERIAL_COMMAND_GAP)
+                    self.set_accel(stepper_idx, self.z_accel);
+                    self.set_speed(stepper_idx, self.z_speed);
+                }
+            }
+            // Min/max are per group, so once covers every Z stepper
+            self.apply_limits(LimitGroup::Z, self.z_min, self.z_max);
+        }
+    }
+
+    /// MOTION_PARAMS: use these speeds/accels/limits instead of the built-in defaults
+    /// and push them to the boards after every connect
+    pub fn set_motion_params(&mut self, params: MotionParams) {
+        let groups = [
+            (params.x, [&mut self.x_speed, &mut self.x_accel, &mut self.x_min, &mut self.x_max]),
+            (params.z, [&mut self.z_speed, &mut self.z_accel, &mut self.z_min, &mut self.z_max]),
+            (params.tuner, [&mut self.tuner_speed, &mut self.tuner_accel, &mut self.tuner_min, &mut self.tuner_max]),
+        ];
+        for (axis, [speed, accel, min, max]) in groups {
+            for (value, slot) in [(axis.speed, speed), (axis.accel, accel), (axis.min, min), (axis.max, max)] {
+                if let Some(value) = value {
+                    *slot = value;
+                }
+            }
+        }
+        self.motion_sync = true;
+    }
+
+    /// AXES: shown below X with the same controls; their SPEED / ACCEL go out with the motion params
+    pub fn set_axes(&mut self, axes: Vec<config_loader::AxisSettings>) {
+        if axes.iter().any(|axis| axis.motion.speed.is_some() || axis.motion.accel.is_some()) {
+            self.motion_sync = true;
+        }
+        self.axes = axes;
+    }
+
+    /// Check tuner moves against the strings' pitch curves (TUNER_SAFETY)
+    pub fn set_tuner_guard(&mut self, tuner_guard: Option<tension::TunerGuard>) {
+        self.tuner_guard = tuner_guard;
+    }
+
+    /// Refuse a tuner move the string's logged pitch curve says passes its MAX_PITCH.
+    /// The log is re-read first: operations_gui adds to it as it hears the strings.
+    fn check_tuner_move(&mut self, tuner_idx: usize, from: i32, to: i32) -> Result<(), String> {
+        let Some(guard) = self.tuner_guard.as_mut() else { return Ok(()) };
+        // Tuners are numbered in string order
+        guard.reload(tuner_idx);
+        let result = guard.check(tuner_idx, from, to);
+        result.map_err(|e| format!("{}: {}", self.string_label(tuner_idx), e))
+    }
+
+    /// Attach the bow wheels (BOW_DRIVE); they stay stopped until a speed is set
+    pub fn set_bow_drive(&mut self, bow_drive: Option<bow_drive::BowDrive>) {
+        self.bow_drive = bow_drive;
+    }
+
+    /// Set string `string_idx`'s bow wheel speed: PWM directly, or the firmware's
+    /// bow command on the carriage board
+    fn set_bow_speed(&mut self, source: &str, string_idx: usize, speed: u32) {
+        let Some(drive) = self.bow_drive.as_mut() else {
+            self.log("ERROR: Cannot set bow speed - no BOW_DRIVE configured");
+            return;
+        };
+        let command_id = drive.serial_command();
+        if command_id.is_some() && self.serial.is_none() {
+            self.log("ERROR: Cannot set bow speed - port not connected");
+            return;
+        }
+        match drive.set_speed(string_idx, speed) {
+            Ok(applied) => {
+                self.log(&format!(">>> SETTING {} bow speed to {} ({})", self.string_label(string_idx), applied, source));
+                if let Some(cmd_id) = command_id {
+                    self.send_cmd_bin(cmd_id, string_idx as i16, applied as i32);
+                }
+            }
+            Err(e) => self.log(&format!("ERROR: Bow speed for string {}: {}", string_idx, error::user_message(&e))),
+        }
+    }
+
+    /// Drive the damper relays from the carriage board (DAMPERS with OUTPUT serial)
+    pub fn set_damper_command(&mut self, command_id: Option<u8>) {
+        self.damper_command = command_id;
+    }
+
+    /// Engage or release string `string_idx`'s damper through the firmware's damper command
+    fn damp(&mut self, source: &str, string_idx: usize, engaged: bool) {
+        let Some(cmd_id) = self.damper_command else {
+            self.log("ERROR: Cannot damp - no serial DAMPERS configured");
+            return;
+        };
+        if string_idx >= self.topology.string_num() {
+            self.log(&format!("ERROR: Cannot damp - no string {}", string_idx));
+            return;
+        }
+        if self.serial.is_none() {
+            self.log("ERROR: Cannot damp - port not connected");
+            return;
+        }
+        let state = if engaged { "ENGAGING" } else { "RELEASING" };
+        self.log(&format!(">>> {} {} damper ({})", state, self.string_label(string_idx), source));
+        self.send_cmd_bin(cmd_id, string_idx as i16, engaged as i32);
+    }
+
+    /// Send the current speed/accel/min/max of every stepper group on `board`;
+    /// the serial thread spaces the commands out
+    fn sync_motion_params(&mut self, board: Board, reason: &str) {
+        let num_tuners = self.topology.tuners().len();
+        let mut applied = Vec::new();
+        match board {
+            Board::Main => {
+                if self.serial.is_none() {
+                    self.log("ERROR: Cannot sync motion params - port not connected");
+                    return;
+                }
+                if let Some(x_idx) = self.topology.x_index().filter(|i| *i < self.positions.len()) {
+                    self.set_accel(x_idx, self.x_accel);
+                    self.set_speed(x_idx, self.x_speed);
+                    self.apply_limits(LimitGroup::X, self.x_min, self.x_max);
+                    applied.push(format!("X speed {} accel {} range {}..{}", self.x_speed, self.x_accel, self.x_min, self.x_max));
+                }
+                if self.topology.has_z() {
+                    self.apply_z_params_to_all();
+                    applied.push(format!("Z speed {} accel {} range {}..{}", self.z_speed, self.z_accel, self.z_min, self.z_max));
+                }
+                if !self.topology.mainboard_tuner_indices().is_empty() {
+                    for tuner_idx in 0..num_tuners {
+                        self.set_tuner_accel(tuner_idx, self.tuner_accel);
+                        self.set_tuner_speed(tuner_idx, self.tuner_speed);
+                    }
+                    // v1 keeps a tuner row; on v2/v3 main-board tuners share Z's, so only speed/accel go
+                    if self.command_set.limit_groups.tuners.is_some() {
+                        self.apply_limits(LimitGroup::Tuners, self.tuner_min, self.tuner_max);
+                        applied.push(format!("tuners speed {} accel {} range {}..{}", self.tuner_speed, self.tuner_accel, self.tuner_min, self.tuner_max));
+                    } else {
+                        applied.push(format!("tuners speed {} accel {}", self.tuner_speed, self.tuner_accel));
+                    }
+                }
+                for axis in self.axes.clone() {
+                    if axis.step_index >= self.positions.len() {
+                        continue;
+                    }
+                    let mut sent = Vec::new();
+                    if let Some(speed) = axis.motion.speed {
+                        self.set_speed(axis.step_index, speed);
+                        sent.push(format!("speed {}", speed));
+                    }
+                    if let Some(accel) = axis.motion.accel {
+                        self.set_accel(axis.step_index, accel);
+                        sent.push(format!("accel {}", accel));
+                    }
+                    if !sent.is_empty() {
+                        applied.push(format!("{} {}", axis.name, sent.join(" ")));
+                    }
+                }
+            }
+            Board::Tuner => {
+                if self.tuner_serial.is_none() {
+                    self.log("ERROR: Cannot sync motion params - tuner port not connected");
+                    return;
+                }
+                for tuner_idx in 0..num_tuners {
+                    self.set_tuner_accel(tuner_idx, self.tuner_accel);
+                    self.set_tuner_speed(tuner_idx, self.tuner_speed);
+                }
+                if num_tuners > 0 {
+                    // One shared row on the tuner board covers every tuner
+                    self.apply_limits(LimitGroup::Tuners, self.tuner_min, self.tuner_max);
+                    applied.push(format!("tuners speed {} accel {} range {}..{}", self.tuner_speed, self.tuner_accel, self.tuner_min, self.tuner_max));
+                }
+            }
+        }
+        if applied.is_empty() {
+            return;
+        }
+        let summary = format!("{:?} board ({}): {}", board, reason, applied.join("; "));
+        self.log(&format!("Motion params synced to {}", summary));
+        self.last_motion_sync = Some(summary);
+    }
+
+    /// "Sync now": push the params to every connected board
+    #[cfg(feature = "gui")]
+    fn sync_motion_params_now(&mut self) {
+        if self.serial.is_some() {
+            self.sync_motion_params(Board::Main, "manual");
+        }
+        if self.tuner_serial.is_some() {
+            self.sync_motion_params(Board::Tuner, "manual");
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl StepperCore {
+    fn string_color(&self, string_idx: usize, default: Color32) -> Color32 {
+        self.string_colors.get(string_idx).copied().flatten().map_or(default, |[r, g, b]| Color32::from_rgb(r, g, b))
+    }
+
+    /// Map a position into 0..1 across a configured [min, max] range for slider drawing
+    fn normalize_position(pos: i32, min: i32, max: i32) -> f32 {
+        if max <= min {
+            return 0.5;
+        }
+        ((pos - min) as f32 / (max - min) as f32).clamp(0.0, 1.0)
+    }
+
+    /// Encoder reading under a stepper label; red while the stepper is flagged as slipping
+    /// Enable checkbox for a stepper; unticking locks it out here and in operations_gui
+    fn enable_toggle(&mut self, ui: &mut egui::Ui, idx: usize) {
+        let mut enabled = !self.disabled_steppers.contains(&idx);
+        let text = if enabled { egui::RichText::new("on") } else { egui::RichText::new(tr("DISABLED")).color(Color32::RED) };
+        if ui.checkbox(&mut enabled, text).on_hover_text(tr("Disabled steppers refuse moves and have their driver current cut")).changed() {
+            self.set_stepper_enabled("UI", idx, enabled);
+        }
+    }
+
+    fn encoder_label(&self, ui: &mut egui::Ui, idx: usize) {
+        let Some(encoder) = self.encoder_positions.get(idx).copied().flatten() else { return; };
+        let text = egui::RichText::new(format!("enc {}", encoder)).small();
+        if self.slipping_steppers.contains(&idx) {
+            ui.label(text.color(Color32::RED));
+        } else {
+            ui.label(text);
+        }
+    }
+
+    /// Under a stepper label: its axis travel speed, or while a move is in flight the target
+    /// and estimated time left. The spinner marks the move the board is executing; it turns
+    /// red once the move runs well past its estimate, so a stuck command stands out from a slow move.
+    fn motion_indicator(&self, ui: &mut egui::Ui, board: Board, stepper: usize) {
+        let Some(m) = self.move_in_flight(board, stepper) else {
+            let (speed, _) = self.axis_motion(board, stepper);
+            if speed > 0 {
+                ui.label(egui::RichText::new(format!("{} steps/s", speed)).small().weak());
+            }
+            return;
+        };
+        ui.horizontal(|ui| match m.progress(Instant::now()) {
+            MoveProgress::Queued => {
+                ui.label(egui::RichText::new(format!("→ {} queued", m.target)).small().weak());
+            }
+            MoveProgress::Moving { remaining } => {
+                ui.add(egui::Spinner::new().size(10.0));
+                ui.label(egui::RichText::new(format!("→ {} ~{:.1}s", m.target, remaining.as_secs_f32())).small());
+            }
+            MoveProgress::Overdue { elapsed } => {
+                ui.add(egui::Spinner::new().size(10.0).color(Color32::RED));
+                ui.label(egui::RichText::new(format!("→ {} {:.0}s (est. {:.1}s)", m.target, elapsed.as_secs_f32(), m.estimate.as_secs_f32())).small().color(Color32::RED))
+                    .on_hover_text(tr("No reply from the board long after the estimated travel time - the move may be stuck"));
+            }
+        });
+    }
+
+    /// Outline a stepper's slider or dial while its move executes (yellow) or is overdue (red)
+    fn motion_highlight(&self, painter: &egui::Painter, rect: egui::Rect, board: Board, stepper: usize) {
+        let Some(m) = self.move_in_flight(board, stepper) else { return; };
+        let color = match m.progress(Instant::now()) {
+            MoveProgress::Queued => return,
+            MoveProgress::Moving { .. } => Color32::YELLOW,
+            MoveProgress::Overdue { .. } => Color32::RED,
+        };
+        painter.rect_stroke(rect.expand(2.0), 2.0, egui::Stroke::new(2.0, color));
+    }
+
+    /// One line per board executing a move: which stepper, where to and how long is left
+    fn motion_status(&self, ui: &mut egui::Ui) {
+        let now = Instant::now();
+        for m in self.moves_in_flight.iter().filter(|m| m.started.is_some()) {
+            let queued = self.moves_in_flight.iter().filter(|q| q.board == m.board && q.started.is_none()).count();
+            let what = match m.board {
+                Board::Main => format!("Stepper {} ({})", m.stepper, self.axis_of(m.board, m.stepper)),
+                Board::Tuner => format!("Tuner {}", m.stepper),
+            };
+            let queued = if queued > 0 { format!(", {} queued", queued) } else { String::new() };
+            ui.horizontal(|ui| match m.progress(now) {
+                MoveProgress::Overdue { elapsed } => {
+                    ui.add(egui::Spinner::new().size(12.0).color(Color32::RED));
+                    ui.colored_label(Color32::RED, format!(
+                        "{:?} board: {} → {} still running after {:.0}s (estimated {:.1}s) - stuck?{}",
+                        m.board, what, m.target, elapsed.as_secs_f32(), m.estimate.as_secs_f32(), queued
+                    ));
+                }
+                progress => {
+                    let remaining = if let MoveProgress::Moving { remaining } = progress { remaining } else { m.estimate };
+                    ui.add(egui::Spinner::new().size(12.0));
+                    ui.label(format!("{:?} board: {} → {}, ~{:.1}s left{}", m.board, what, m.target, remaining.as_secs_f32(), queued));
+                }
+            });
+        }
+    }
+
+    /// A +/- button: a press steps by `delta`, holding it jogs (see service_jog)
+    fn jog_button(&mut self, ui: &mut egui::Ui, text: &str, target: JogTarget, delta: i32) {
+        if ui.button(text).is_pointer_button_down_on() {
+            self.jog_requests.push((target, delta));
+        }
+    }
+
+    /// Arrow keys jog as the buttons do: Left / Right move X, Up / Down whatever a
+    /// +/- button last moved. Not while a number box has the keyboard.
+    fn jog_keys(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let held = |key| ctx.input(|i| i.key_down(key));
+        if let Some(x_idx) = self.topology.x_index() {
+            if held(egui::Key::ArrowLeft) {
+                self.jog_requests.push((JogTarget::Stepper(x_idx), -self.x_step));
+            } else if held(egui::Key::ArrowRight) {
+                self.jog_requests.push((JogTarget::Stepper(x_idx), self.x_step));
+            }
+        }
+        if let Some(target) = self.jog_key_target {
+            let (plus, minus) = self.jog_steps(target);
+            if held(egui::Key::ArrowUp) {
+                self.jog_requests.push((target, plus));
+            } else if held(egui::Key::ArrowDown) {
+                self.jog_requests.push((target, minus));
+            }
+        }
+    }
+
+        /// Render the UI content (can be called from panels or standalone)
+    pub fn render_ui(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        if self.drain_serial_events() {
+            ctx.request_repaint();
+        }
+        if !self.connected {
+            ui.label(tr("Connecting to Arduino..."));
+            // Ends a jog cut off by the disconnect
+            self.service_jog(Instant::now());
+            return;
+        }
+        self.jog_keys(ctx);
+        
+        // Refresh positions periodically (every 500ms)
+        ctx.request_repaint_after(Duration::from_millis(500));
+        if !self.moves_in_flight.is_empty() {
+            // Keep the motion indicators counting down
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+
+
+            // Channel colors matching plot.rs color scheme
+            let channel_colors = vec![
+                Color32::from_rgb(0, 0, 255),      // Blue
+                Color32::from_rgb(255, 165, 0),    // Orange
+                Color32::from_rgb(0, 255, 0),       // Green
+                Color32::from_rgb(255, 0, 0),       // Red
+                Color32::from_rgb(238, 130, 238),   // Magenta
+                Color32::from_rgb(165, 42, 42),    // Brown
+            ];
+
+            // Check if X stepper exists using X_STEP_INDEX from config
+            // COMMENTED OUT: X offset feature for Z stepper layout
+            // let x_offset = if let Some(x_idx) = self.x_step_index {
+            //     if x_idx < self.positions.len() && self.positions[x_idx] >= 0 {
+            //         self.positions[x_idx] as f32 // Shift layout based on x-axis position
+            //     } else {
+            //         0.0 // X stepper index out of range or invalid position
+            //     }
+            // } else {
+            //     0.0 // No X stepper for this host
+            // };
+            let x_offset = 0.0; // Feature disabled
+
+            // Performer mode keeps the tuner nudges and X steps, hides limits/speeds and locks Z
+            self.role_lock.show(ui);
+            let technician = self.role_lock.is_technician();
+            self.motion_status(ui);
+
+            // Startup check: boards reporting positions the last session didn't leave them at
+            if !self.startup_mismatch.is_empty() {
+                let advice: Vec<&str> = self.rehome_axes().into_iter().map(|axis| match axis {
+                    "Z" => "Z (z_home)",
+                    "X" => "X (x_home)",
+                    "tuners" => "tuners (re-zero by hand)",
+                    _ => "other steppers",
+                }).collect();
+                let mut accept = false;
+                egui::Frame::default()
+                    .fill(Color32::from_rgb(200, 110, 0))
+                    .inner_margin(egui::Margin::same(6.0))
+                    .show(ui, |ui| {
+                        ui.colored_label(Color32::WHITE, egui::RichText::new(format!(
+                            "⚠ Positions differ from the last session - re-home before running operations: {}",
+                            advice.join(", ")
+                        )).strong());
+                        for (board, d) in &self.startup_mismatch {
+                            ui.colored_label(Color32::WHITE, format!(
+                                "{:?} stepper {}: reports {}, expected {}", board, d.stepper, d.reported, d.expected
+                            ));
+                        }
+                        if technician {
+                            accept = ui.button(tr("Accept reported positions")).clicked();
+                        }
+                    });
+                if accept {
+                    self.accept_reported_positions();
+                }
+            }
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                // ========== TUNERS SECTION ==========
+                let num_tuners = self.topology.tuners().len();
+                if num_tuners > 0 {
+                    ui.label(tr("Tuners"));
+                    ui.horizontal(|ui| {
+                        for tuner_idx in 0..num_tuners {
+                            ui.vertical(|ui| {
+                                match self.string_names.get(tuner_idx).and_then(|n| n.as_deref()) {
+                                    Some(name) => ui.label(trf("Tuner {0} ({1})", &[&tuner_idx, &name])),
+                                    None => ui.label(trf("Tuner {0}", &[&tuner_idx])),
+                                };
+                                let channel_color = self.string_color(tuner_idx, channel_colors[tuner_idx % channel_colors.len()]);
+                                let (tuner_board, tuner_stepper) = match self.mainboard_tuner(tuner_idx) {
+                                    Some(main_idx) => (Board::Main, main_idx),
+                                    None => (Board::Tuner, tuner_idx),
+                                };
+                                self.motion_indicator(ui, tuner_board, tuner_stepper);
+                                
+                                // Get tuner position
+                                let tuner_pos = if tuner_idx < self.tuner_positions.len() {
+                                    self.tuner_positions[tuner_idx]
+                                } else {
+                                    0
+                                };
+                                
+                                // Rotary dial visualization
+                                let desired_size = egui::vec2(60.0, 60.0);
+                                let response = ui.allocate_response(desired_size, egui::Sense::hover());
+                                let rect = response.rect;
+                                let painter = ui.painter();
+                                
+                                let radius = rect.width() / 2.0 - 2.0;
+                                painter.circle_filled(rect.center(), radius, egui::Color32::from_rgb(40, 40, 40));
+                                painter.circle_stroke(rect.center(), radius, egui::Stroke::new(2.0, channel_color));
+                                
+                                let tuner_range = if self.tuner_serial.is_some() {
+                                    200000.0
+                                } else {
+                                    50000.0
+                                };
+                                let normalized = ((tuner_pos as f32 + tuner_range / 2.0) / tuner_range).clamp(0.0, 1.0);
+                                let angle = normalized * std::f32::consts::TAU - std::f32::consts::FRAC_PI_2;
+                                let radius = rect.width() / 2.0 - 5.0;
+                                let end_x = rect.center().x + angle.cos() * radius;
+                                let end_y = rect.center().y - angle.sin() * radius;
+                                painter.line_segment(
+                                    [rect.center(), egui::pos2(end_x, end_y)],
+                                    (2.0, channel_color)
+                                );
+                                self.motion_highlight(painter, rect, tuner_board, tuner_stepper);
+                                
+                                // + button
+                                self.jog_button(ui, "+", JogTarget::Tuner(tuner_idx), self.tuner_nudge());
+                                
+                                // Editable number box
+                                let pending_key = self.mainboard_tuner(tuner_idx).unwrap_or(10000 + tuner_idx);
+                                
+                                let current_pos = tuner_pos;
+                                let (tuner_min, tuner_max) = self.tuner_limits();
+                                let pending = self.pending_positions.entry(pending_key).or_insert(current_pos);
+                                
+                                let response = ui.add(egui::DragValue::new(pending)
+                                    .clamp_range(tuner_min..=tuner_max)
+                                    .speed(100.0));
+                                
+                                let has_focus = response.has_focus();
+                                let lost_focus = response.lost_focus();
+                                let enter_pressed = ui.input(|i| i.key_pressed(egui::Key::Enter));
+                                
+                                // Go: move to the entered target (Enter in the box does the same)
+                                let go_clicked = ui.small_button(tr("Go")).on_hover_text(tr("Move to the entered position")).clicked();
+                                if (lost_focus && enter_pressed) || go_clicked {
+                                    let pending_value = *pending;
+                                    let _ = pending;
+                                    if pending_value != current_pos {
+                                        self.move_tuner_absolute(tuner_idx, pending_value);
+                                    }
+                                    self.pending_positions.insert(pending_key, pending_value);
+                                } else {
+                                    if !has_focus && *pending != current_pos {
+                                        *pending = current_pos;
+                                    }
+                                }
+                                
+                                // - button
+                                self.jog_button(ui, "-", JogTarget::Tuner(tuner_idx), -self.tuner_nudge());
+                            });
+                            ui.add_space(10.0);
+                        }
+                    });
+                    
+                    // Shared tuner controls
+                    if technician {
+                        ui.horizontal(|ui| {
+                            ui.label(tr("Accel:"));
+                            let accel_response = ui.add(egui::DragValue::new(&mut self.tuner_accel).speed(100.0));
+                            if accel_response.changed() {
+                                for tuner_idx in 0..num_tuners {
+                                    self.set_tuner_accel(tuner_idx, self.tuner_accel);
+                                    thread::sleep(Duration::from_millis(10));
+                                }
+                            }
+                            ui.label(tr("Speed:"));
+                            let speed_response = ui.add(egui::DragValue::new(&mut self.tuner_speed).speed(10.0));
+                            if speed_response.changed() {
+                                for tuner_idx in 0..num_tuners {
+                                    self.set_tuner_speed(tuner_idx, self.tuner_speed);
+                                    thread::sleep(Duration::from_millis(10));
+                                }
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(tr("Min:"));
+                            let min_response = ui.add(egui::DragValue::new(&mut self.tuner_min).speed(1000.0));
+                            if min_response.changed() {
+                                self.apply_limits(LimitGroup::Tuners, self.tuner_min, self.tuner_max);
+                            }
+                            ui.label(tr("Max:"));
+                            let max_response = ui.add(egui::DragValue::new(&mut self.tuner_max).speed(1000.0));
+                            if max_response.changed() {
+                                self.apply_limits(LimitGroup::Tuners, self.tuner_min, self.tuner_max);
+                            }
+                        });
+                    }
+                    ui.horizontal(|ui| {
+                        // Step sizes are just stored, no command needed
+                        ui.label(tr("Tuner Step:"));
+                        ui.add(egui::DragValue::new(&mut self.tuner_step).speed(10.0).clamp_range(1..=10000));
+                        ui.label(tr("Fine:"));
+                        ui.add(egui::DragValue::new(&mut self.tuner_fine_step).speed(1.0).clamp_range(1..=10000));
+                        ui.checkbox(&mut self.tuner_fine, tr("Use fine step"));
+                    });
+                    ui.separator();
+                }
+                
+                // ========== X-AXIS SECTION ==========
+                // Only show X-axis if there is an X stepper AND x_max_pos is set and > 0 (not a dummy)
+                if let Some(x_idx) = self.topology.x_index() {
+                    if let Some(max_pos) = self.x_max_pos {
+                        if max_pos > 0 && x_idx < self.positions.len() {
+                            ui.horizontal(|ui| {
+                                ui.label(&format!("X-axis (Stepper {}):", x_idx));
+                                self.enable_toggle(ui, x_idx);
+                                self.motion_indicator(ui, Board::Main, x_idx);
+                            });
+                            
+                            // Slider full width of window
+                            let mut pos = self.positions[x_idx];
+                            let display_pos = pos.max(0);
+                            let max_range = max_pos;
+                            let x_min = self.x_min.min(max_range);
+                            
+                            // Allocate full available width for slider
+                            let available_width = ui.available_width();
+                            let slider_height = ui.spacing().interact_size.y;
+                            
+                            let slider_response = ui.allocate_response(
+                                egui::vec2(available_width, slider_height),
+                                egui::Sense::hover()
+                            );
+                            
+                            let slider_rect = slider_response.rect;
+                            let painter = ui.painter();
+                            
+                            let track_height = 4.0;
+                            let track_rect = egui::Rect::from_center_size(
+                                slider_rect.center(),
+                                egui::vec2(slider_rect.width(), track_height)
+                            );
+                            painter.rect_filled(track_rect, 2.0, egui::Color32::from_gray(60));
+                            
+                            let normalized_pos = Self::normalize_position(display_pos, x_min, max_range);
+                            
+                            let fill_width = slider_rect.width() * normalized_pos;
+                            let fill_rect = egui::Rect::from_min_size(
+                                slider_rect.min,
+                                egui::vec2(fill_width, track_height)
+                            );
+                            painter.rect_filled(fill_rect, 2.0, egui::Color32::from_gray(120));
+                            
+                            let indicator_x = slider_rect.min.x + fill_width;
+                            let indicator_y = slider_rect.center().y;
+                            painter.circle_filled(
+                                egui::pos2(indicator_x, indicator_y),
+                                6.0,
+                                egui::Color32::WHITE
+                            );
+                            self.motion_highlight(painter, slider_rect, Board::Main, x_idx);
+                            
+                            // Row with - numberbox +
+                            ui.horizontal(|ui| {
+                                self.jog_button(ui, "-", JogTarget::Stepper(x_idx), -self.x_step);
+                                
+                                let current_pos = self.positions[x_idx];
+                                let pending = self.pending_positions.entry(x_idx).or_insert_with(|| current_pos);
+                                let response = ui.add(egui::DragValue::new(pending)
+                                    .clamp_range(x_min..=max_range)
+                                    .speed(10.0));
+                                
+                                let has_focus = response.has_focus();
+                                let lost_focus = response.lost_focus();
+                                let enter_pressed = ui.input(|i| i.key_pressed(egui::Key::Enter));
+                                
+                                if lost_focus && enter_pressed {
+                                    let pending_value = *pending;
+                                    drop(pending);
+                                    if pending_value != current_pos {
+                                        self.move_stepper_absolute_with_source("UI", x_idx, pending_value);
+                                    }
+                                    self.pending_positions.remove(&x_idx);
+                                } else if !has_focus {
+                                    if *pending != current_pos {
+                                        *pending = current_pos;
+                                    }
+                                }
+                                
+                                self.jog_button(ui, "+", JogTarget::Stepper(x_idx), self.x_step);
+                            });
+                            
+                            // X stepper parameter controls
+                            if technician {
+                                ui.horizontal(|ui| {
+                                    ui.label(tr("Accel:"));
+                                    let accel_response = ui.add(egui::DragValue::new(&mut self.x_accel).speed(100.0));
+                                    if accel_response.changed() {
+                                        self.set_accel(x_idx, self.x_accel);
+                                    }
+                                    ui.label(tr("Speed:"));
+                                    let speed_response = ui.add(egui::DragValue::new(&mut self.x_speed).speed(10.0));
+                                    if speed_response.changed() {
+                                        self.set_speed(x_idx, self.x_speed);
+                                    }
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.label(tr("Min:"));
+                                    let min_response = ui.add(egui::DragValue::new(&mut self.x_min).speed(10.0));
+                                    if min_response.changed() {
+                                        self.apply_limits(LimitGroup::X, self.x_min, self.x_max);
+                                    }
+                                    ui.label(tr("Max:"));
+                                    let max_response = ui.add(egui::DragValue::new(&mut self.x_max).speed(10.0));
+                                    if max_response.changed() {
+                                        self.apply_limits(LimitGroup::X, self.x_min, self.x_max);
+                                    }
+                                });
+                            }
+                            ui.horizontal(|ui| {
+                                ui.label(tr("X Step:"));
+                                let step_response = ui.add(egui::DragValue::new(&mut self.x_step).speed(1.0).clamp_range(1..=1000));
+                                if step_response.changed() {
+                                    // x_step is just stored, no command needed
+                                }
+                            });
+                            ui.separator();
+                        }
+                    }
+                }
+                
+                // ========== OTHER AXES (AXES) ==========
+                // Same travel as X: 0 at the home switch up to MAX_POS; dummies (MAX_POS 0) hidden
+                for axis_idx in 0..self.axes.len() {
+                    let (name, idx, max_pos) = (self.axes[axis_idx].name.clone(), self.axes[axis_idx].step_index, self.axes[axis_idx].max_pos);
+                    if max_pos <= 0 || idx >= self.positions.len() {
+                        continue;
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label(trf("{0}-axis (Stepper {1}):", &[&name, &idx]));
+                        self.enable_toggle(ui, idx);
+                        self.motion_indicator(ui, Board::Main, idx);
+                    });
+                    let current_pos = self.positions[idx];
+                    let mut slider_pos = current_pos.clamp(0, max_pos);
+                    let slider = ui.add_sized(
+                        egui::vec2(ui.available_width(), ui.spacing().interact_size.y),
+                        egui::Slider::new(&mut slider_pos, 0..=max_pos).show_value(false),
+                    );
+                    self.motion_highlight(ui.painter(), slider.rect, Board::Main, idx);
+                    if slider.drag_stopped() && slider_pos != current_pos {
+                        self.move_stepper_absolute_with_source("UI", idx, slider_pos);
+                    }
+                    ui.horizontal(|ui| {
+                        let step = self.axes[axis_idx].step;
+                        self.jog_button(ui, "-", JogTarget::Stepper(idx), -step);
+                        let pending = self.pending_positions.entry(idx).or_insert(current_pos);
+                        let response = ui.add(egui::DragValue::new(pending).clamp_range(0..=max_pos).speed(10.0));
+                        let enter_pressed = ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        if response.lost_focus() && enter_pressed {
+                            let pending_value = *pending;
+                            self.pending_positions.remove(&idx);
+                            if pending_value != current_pos {
+                                self.move_stepper_absolute_with_source("UI", idx, pending_value);
+                            }
+                        } else if !response.has_focus() {
+                            *pending = current_pos;
+                        }
+                        self.jog_button(ui, "+", JogTarget::Stepper(idx), step);
+                        ui.label(trf("{0} Step:", &[&name]));
+                        ui.add(egui::DragValue::new(&mut self.axes[axis_idx].step).speed(1.0).clamp_range(1..=1000));
+                    });
+                    if technician {
+                        ui.horizontal(|ui| {
+                            let motion = self.axes[axis_idx].motion;
+                            let mut accel = motion.accel.unwrap_or(self.x_accel);
+                            ui.label(tr("Accel:"));
+                            if ui.add(egui::DragValue::new(&mut accel).speed(100.0).clamp_range(1..=100000)).changed() {
+                                self.axes[axis_idx].motion.accel = Some(accel);
+                                self.set_accel(idx, accel);
+                            }
+                            let mut speed = motion.speed.unwrap_or(self.x_speed);
+                            ui.label(tr("Speed:"));
+                            if ui.add(egui::DragValue::new(&mut speed).speed(10.0).clamp_range(1..=100000)).changed() {
+                                self.axes[axis_idx].motion.speed = Some(speed);
+                                self.set_speed(idx, speed);
+                            }
+                        });
+                    }
+                    ui.separator();
+                }
+
+                // ========== BOW WHEELS (BOW_DRIVE) ==========
+                // One speed per string; Run starts every wheel at its configured SPEED
+                if let Some((speeds, max_speed, setpoints)) = self.bow_drive.as_ref().map(|drive| {
+                    let setpoints: Vec<u32> = (0..drive.speeds().len()).map(|idx| drive.settings.speed_for(idx)).collect();
+                    (drive.speeds().to_vec(), drive.settings.max_speed, setpoints)
+                }) {
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Bow wheels"));
+                        if ui.button(tr("Run")).clicked() {
+                            for (string_idx, speed) in setpoints.iter().enumerate() {
+                                self.set_bow_speed("UI", string_idx, *speed);
+                            }
+                        }
+                        if ui.button(tr("Stop all")).clicked() {
+                            for string_idx in 0..speeds.len() {
+                                self.set_bow_speed("UI", string_idx, 0);
+                            }
+                        }
+                    });
+                    for (string_idx, current) in speeds.into_iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("{}:", self.string_label(string_idx)));
+                            let mut speed = current;
+                            let slider = ui.add(egui::Slider::new(&mut speed, 0..=max_speed));
+                            // Send on release (or a typed value), not every frame of a drag
+                            if (slider.drag_stopped() || (slider.changed() && !slider.dragged())) && speed != current {
+                                self.set_bow_speed("UI", string_idx, speed);
+                            }
+                        });
+                    }
+                    ui.separator();
+                }
+
+                // ========== Z-AXIS SECTION ==========
+                ui.label(tr("Z-axis"));
+                
+                // One row of Z steppers per active string (STRING_NUM in YAML)
+                let strings = self.topology.strings().to_vec();
+                // Manual Z moves are technician-only
+                ui.add_enabled_ui(technician, |ui| {
+                    if self.topology.has_z() {
+                        for string in strings {
+                            // Z steppers are arranged as pairs: (in, out) for each string
+                            // For stringdriver-3: Z_FIRST_INDEX 1, pairs at (1,2), (3,4), (5,6), (7,8)
+                            // For stringdriver-1: Z_FIRST_INDEX 3, pairs at (3,4), (5,6)
+                            let (z_lo, z_hi) = (self.z_min.min(self.z_max), self.z_min.max(self.z_max));
+                            let Some((right_idx, left_idx)) = string.z_pair else { break };
+                            let row = string.string;
+                        
+                            if left_idx >= self.positions.len() || right_idx >= self.positions.len() {
+                                break;
+                            }
+
+                            let color = self.string_color(row, channel_colors[row % channel_colors.len()]);
+                            ui.colored_label(color, self.string_label(row));
+
+                            ui.horizontal(|ui| {
+                                // COMMENTED OUT: Apply horizontal offset based on x-axis carriage position
+                                // if x_offset > 0.0 {
+                                //     ui.add_space(x_offset.min(500.0)); // Limit offset to reasonable screen space
+                                // }
+                            
+                                // Left stepper ("out" stepper)
+                                ui.vertical(|ui| {
+                                    ui.label(trf("Stepper {0} (out)", &[&left_idx]));
+                                    self.enable_toggle(ui, left_idx);
+                                    self.encoder_label(ui, left_idx);
+                                    self.motion_indicator(ui, Board::Main, left_idx);
+                            
+                                // Horizontal layout: slider on left, number box with buttons on right (tight spacing)
+                                ui.with_layout(egui::Layout::left_to_right(egui::Align::Center).with_main_justify(false), |ui| {
+                                    ui.set_width(80.0); // Constrain width to keep layout tight
+                                
+                                    // Read-only vertical slider for visualization with colored background
+                                    let pos_display = self.positions[left_idx];
+                                    let pos_normalized = Self::normalize_position(pos_display, z_lo, z_hi); // Normalize Z min..max to 0..1
+                                
+                                    // Draw colored slider area (half size: 20x100 instead of 40x200)
+                                    let desired_size = egui::vec2(20.0, 100.0);
+                                    let response = ui.allocate_response(desired_size, egui::Sense::hover());
+                                    let rect = response.rect;
+                                    let painter = ui.painter();
+                                    // Draw background
+                                    painter.rect_filled(rect, 0.0, egui::Color32::from_rgb(40, 40, 40));
+                                    // Draw filled portion with channel color
+                                    let fill_height = rect.height() * pos_normalized;
+                                    let fill_rect = egui::Rect::from_min_size(
+                                        rect.min,
+                                        egui::vec2(rect.width(), fill_height)
+                                    );
+                                    painter.rect_filled(fill_rect, 0.0, color);
+                                    // Draw slider thumb
+                                    let thumb_y = rect.min.y + rect.height() * (1.0 - pos_normalized);
+                                    painter.circle_filled(egui::pos2(rect.center().x, thumb_y), 4.0, Color32::WHITE);
+                                    self.motion_highlight(painter, rect, Board::Main, left_idx);
+                                
+                                    // Vertical stack: + button, number box, - button
+                                    // Number box should align with slider center (0 position)
+                                    ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                                        // Add space to align number box center with slider center
+                                        // Slider is 100px tall, center is at 50px
+                                        // Estimate: button ~20px, number box ~20px, so add ~20px space
+                                        ui.add_space(20.0);
+                                    
+                                        // Inc (+) button above number box
+                                        self.jog_button(ui, "+", JogTarget::Stepper(left_idx), self.z_up_step);
+                                    
+                                        // Use DragValue for proper number input, but only commit on Enter
+                                        let current_pos = self.positions[left_idx];
+                                        let pending = self.pending_positions.entry(left_idx).or_insert(current_pos);
+                                        let response = ui.add(egui::DragValue::new(pending)
+                                            .clamp_range(z_lo..=z_hi)
+                                            .speed(1.0));
+                                    
+                                        let has_focus = response.has_focus();
+                                        let lost_focus = response.lost_focus();
+                                        let enter_pressed = ui.input(|i| i.key_pressed(egui::Key::Enter));
+                                    
+                                        // Only send command when Enter is pressed (lost focus + Enter key)
+                                        // Check this FIRST before syncing, otherwise we'll reset pending value
+                                        if lost_focus && enter_pressed {
+                                            let pending_value = *pending; // Capture value before any reset
+                                            let _ = pending; // Release borrow
+                                            self.log(&format!("DEBUG Enter pressed for left_idx={}: pending_value={}, current_pos={}", 
+                                                left_idx, pending_value, current_pos));
+                                            let clamped = pending_value.clamp(z_lo, z_hi);
+                                            // Move stepper to absolute position - Arduino is source of truth
+                                            self.move_stepper_absolute_with_source("UI", left_idx, clamped);
+                                            self.pending_positions.insert(left_idx, clamped);
+                                        } else {
+                                            // Only sync pending value if user is NOT editing (widget not focused)
+                                            // This prevents overwriting user's input while they're typing
+                                            if !has_focus && *pending != current_pos {
+                                                *pending = current_pos;
+                                            }
+                                        }
+                                    
+                                        // Dec (-) button below number box
+                                        self.jog_button(ui, "-", JogTarget::Stepper(left_idx), self.z_down_step);
+                                    });
+                                });
+                            });
+                            
+                                // Right stepper ("in" stepper)
+                                ui.vertical(|ui| {
+                                    ui.label(trf("Stepper {0} (in)", &[&right_idx]));
+                                    self.enable_toggle(ui, right_idx);
+                                    self.encoder_label(ui, right_idx);
+                                    self.motion_indicator(ui, Board::Main, right_idx);
+                            
+                                // Horizontal layout: slider on left, number box with buttons on right (tight spacing)
+                                ui.with_layout(egui::Layout::left_to_right(egui::Align::Center).with_main_justify(false), |ui| {
+                                    ui.set_width(80.0); // Constrain width to keep layout tight
+                                
+                                    // Read-only vertical slider for visualization with colored background
+                                    let pos_display = self.positions[right_idx];
+                                    let pos_normalized = Self::normalize_position(pos_display, z_lo, z_hi); // Normalize Z min..max to 0..1
+                                
+                                    // Draw colored slider area (half size: 20x100 instead of 40x200)
+                                    let desired_size = egui::vec2(20.0, 100.0);
+                                    let response = ui.allocate_response(desired_size, egui::Sense::hover());
+                                    let rect = response.rect;
+                                    let painter = ui.painter();
+                                    // Draw background
+                                    painter.rect_filled(rect, 0.0, egui::Color32::from_rgb(40, 40, 40));
+                                    // Draw filled portion with channel color
+                                    let fill_height = rect.height() * pos_normalized;
+                                    let fill_rect = egui::Rect::from_min_size(
+                                        rect.min,
+                                        egui::vec2(rect.width(), fill_height)
+                                    );
+                                    painter.rect_filled(fill_rect, 0.0, color);
+                                    // Draw slider thumb
+                                    let thumb_y = rect.min.y + rect.height() * (1.0 - pos_normalized);
+                                    painter.circle_filled(egui::pos2(rect.center().x, thumb_y), 4.0, Color32::WHITE);
+                                    self.motion_highlight(painter, rect, Board::Main, right_idx);
+                                
+                                    // Vertical stack: + button, number box, - button
+                                    // Number box should align with slider center (0 position)
+                                    ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                                        // Add space to align number box center with slider center
+                                        // Slider is 100px tall, center is at 50px
+                                        // Estimate: button ~20px, number box ~20px, so add ~20px space
+                                        ui.add_space(20.0);
+                                    
+                                        // Inc (+) button above number box
+                                        self.jog_button(ui, "+", JogTarget::Stepper(right_idx), self.z_up_step);
+                                    
+                                        // Use DragValue for proper number input, but only commit on Enter
+                                        let current_pos = self.positions[right_idx];
+                                        let pending = self.pending_positions.entry(right_idx).or_insert(current_pos);
+                                        let response = ui.add(egui::DragValue::new(pending)
+                                            .clamp_range(z_lo..=z_hi)
+                                            .speed(1.0));
+                                    
+                                        let has_focus = response.has_focus();
+                                        let lost_focus = response.lost_focus();
+                                        let enter_pressed = ui.input(|i| i.key_pressed(egui::Key::Enter));
+                                    
+                                        // Only send command when Enter is pressed (lost focus + Enter key)
+                                        // Check this FIRST before syncing, otherwise we'll reset pending value
+                                        if lost_focus && enter_pressed {
+                                            let pending_value = *pending; // Capture value before any reset
+                                            let _ = pending; // Release borrow
+                                            self.log(&format!("DEBUG Enter pressed for right_idx={}: pending_value={}, current_pos={}", 
+                                                right_idx, pending_value, current_pos));
+                                            let clamped = pending_value.clamp(z_lo, z_hi);
+                                            // Move stepper to absolute position - Arduino is source of truth
+                                            self.move_stepper_absolute_with_source("UI", right_idx, clamped);
+                                            self.pending_positions.insert(right_idx, clamped);
+                                        } else {
+                                            // Only sync pending value if user is NOT editing (widget not focused)
+                                            // This prevents overwriting user's input while they're typing
+                                            if !has_focus && *pending != current_pos {
+                                                *pending = current_pos;
+                                            }
+                                        }
+                                    
+                                        // Dec (-) button below number box
+                                        self.jog_button(ui, "-", JogTarget::Stepper(right_idx), self.z_down_step);
+                                    });
+                                });
+                            });
+                        });
+                        }
+                    }
+                });
+                
+                // Z stepper parameter controls (after all pairs)
+                if technician {
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Accel:"));
+                        let accel_response = ui.add(egui::DragValue::new(&mut self.z_accel).speed(100.0));
+                        if accel_response.changed() {
+                            self.apply_z_params_to_all();
+                        }
+                        ui.label(tr("Speed:"));
+                        let speed_response = ui.add(egui::DragValue::new(&mut self.z_speed).speed(10.0));
+                        if speed_response.changed() {
+                            self.apply_z_params_to_all();
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Min:"));
+                        let min_response = ui.add(egui::DragValue::new(&mut self.z_min).speed(10.0));
+                        if min_response.changed() {
+                            self.apply_z_params_to_all();
+                        }
+                        ui.label(tr("Max:"));
+                        let max_response = ui.add(egui::DragValue::new(&mut self.z_max).speed(10.0));
+                        if max_response.changed() {
+                            self.apply_z_params_to_all();
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Z Down Step:"));
+                        let mut down_step = self.z_down_step;
+                        let down_response = ui.add(egui::DragValue::new(&mut down_step).speed(1.0).clamp_range(-10..=-2));
+                        if down_response.changed() {
+                            self.z_down_step = down_step;
+                        }
+                        ui.label(tr("Z Up Step:"));
+                        let mut up_step = self.z_up_step;
+                        let up_response = ui.add(egui::DragValue::new(&mut up_step).speed(1.0).clamp_range(2..=10));
+                        if up_response.changed() {
+                            self.z_up_step = up_step;
+                        }
+                    });
+                }
+                ui.separator();
+            });
+            if technician {
+                gui_state::collapsing(ui, "stepper.motion_params", tr("Motion Params"), |ui| {
+                    ui.label(if self.motion_sync {
+                        "From MOTION_PARAMS; sent to the boards after every connect"
+                    } else {
+                        "No MOTION_PARAMS configured; boards keep their firmware defaults until changed here"
+                    });
+                    if let Some(last) = &self.last_motion_sync {
+                        ui.label(trf("Last sync: {0}", &[&last]));
+                    }
+                    if ui.button(tr("Sync now")).clicked() {
+                        self.sync_motion_params_now();
+                    }
+                });
+            }
+            gui_state::collapsing(ui, "stepper.about", tr("About"), |ui| {
+                let own = self.build_info();
+                ui.label(own.describe());
+                if let Some(tuner) = self.tuner_firmware_protocol {
+                    ui.label(trf("Tuner board firmware protocol {0}", &[&tuner]));
+                }
+                for peer in self.peers.values() {
+                    ui.label(trf("Client: {0}", &[&peer.describe()]));
+                    for problem in own.mismatches(peer) {
+                        ui.colored_label(Color32::from_rgb(255, 165, 0), problem);
+                    }
+                }
+            });
+            gui_state::collapsing(ui, "stepper.messages", tr("Messages"), |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Clear")).clicked() {
+                        logging::clear_buffer(&self.log_buffer);
+                    }
+                    if ui.button(tr("Copy")).clicked() {
+                        let log = logging::buffer_text(&self.log_buffer);
+                        ui.output_mut(|o| o.copied_text = log);
+                    }
+                });
+                let mut log_text = logging::buffer_text(&self.log_buffer);
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .auto_shrink([false; 2])
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut log_text)
+                                .desired_width(f32::INFINITY)
+                                .interactive(true)
+                                .code_editor()
+                        );
+                    });
+            });
+
+            self.service_jog(Instant::now());
+            if self.jog.is_some() {
+                // Come back for the release and the next jog move
+                ctx.request_repaint_after(Duration::from_millis(20));
+            }
+            ctx.request_repaint_after(Duration::from_millis(500));
+    }
+}
+
+#[cfg(feature = "gui")]
+impl eframe::App for StepperCore {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        profiling::time(profiling::Probe::Frame, || {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                self.render_ui(ui, ctx);
+            });
+        });
+        profiling::show_overlay(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixStream;
+    use arduino_connection::ArduinoConnectionManager;
+    use loopback_serial::{FirmwareProtocol, LoopbackBoard, ReceivedCommand};
+
+    fn protocol_for(command_set: &CommandSet) -> FirmwareProtocol {
+        let id = |cmd: &[u8]| std::str::from_utf8(&cmd[..cmd.len() - 1]).unwrap().parse().unwrap();
+        FirmwareProtocol {
+            positions_id: id(command_set.positions_cmd),
+            encoders_id: command_set.encoders_cmd.map(id),
+            amove_id: command_set.amove_id,
+            rmove_id: command_set.rmove_id,
+            set_stepper_id: command_set.set_stepper_id,
+            enable_id: command_set.enable_id,
+            value_bytes: command_set.position_bytes,
+            checksum: command_set.checksum,
+            version: command_set.version_cmd.map(|cmd| (id(cmd), build_info::FIRMWARE_PROTOCOL)),
+        }
+    }
+
+    fn loopback_worker(firmware: ArduinoFirmware, num: usize) -> (SerialWorker, LoopbackBoard, mpsc::Receiver<SerialEvent>) {
+        loopback_worker_with(CommandSet::for_firmware(firmware), num)
+    }
+
+    fn loopback_worker_with(command_set: CommandSet, num: usize) -> (SerialWorker, LoopbackBoard, mpsc::Receiver<SerialEvent>) {
+        let board = LoopbackBoard::new(protocol_for(&command_set), num);
+        let (events, rx) = mpsc::channel();
+        let audit_dir = std::env::temp_dir().join(format!("stringdriver_loopback_{}", std::process::id()));
+        let worker = SerialWorker {
+            board: Board::Main,
+            port: board.open().unwrap(),
+            command_set,
+            num_positions: num,
+            events,
+            audit_log: Arc::new(Mutex::new(AuditLog::open(audit_dir))),
+            wedged: false,
+        };
+        (worker, board, rx)
+    }
+
+    fn command(cmd_id: u8, stepper: i16, value: i32) -> SerialRequest {
+        SerialRequest::Command { cmd_id, stepper, value, refresh_after: None, audit: None }
+    }
+
+    fn reported_positions(rx: &mpsc::Receiver<SerialEvent>) -> Vec<Vec<i32>> {
+        rx.try_iter()
+            .filter_map(|event| match event {
+                SerialEvent::Positions(_, positions) => Some(positions),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reserved_bytes_escaped_on_the_wire() {
+        let (mut worker, board, _rx) = loopback_worker(ArduinoFirmware::StringDriverV3, 4);
+        // ',' ';' '/' and NUL in both arguments
+        let value = i32::from_le_bytes([b',', b';', b'/', 0]);
+        worker.handle(command(worker.command_set.amove_id, 0x3B2C, value));
+        worker.handle(command(worker.command_set.rmove_id, 1, -47));
+        assert_eq!(
+            board.commands(),
+            vec![
+                ReceivedCommand { id: 2, args: vec![0x3B2C, value] },
+                ReceivedCommand { id: 3, args: vec![1, -47] },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_driver_enable_cuts_current() {
+        let (mut worker, board, _rx) = loopback_worker(ArduinoFirmware::StringDriverV3, 3);
+        let cs = worker.command_set;
+        let enable_id = cs.enable_id.unwrap();
+        worker.handle(command(enable_id, 1, 0));
+        worker.handle(command(cs.rmove_id, 1, 5));
+        assert!(!board.driver_enabled(1));
+        assert_eq!(board.positions(), vec![0, 0, 0]);
+        worker.handle(command(enable_id, 1, 1));
+        worker.handle(command(cs.rmove_id, 1, 5));
+        assert_eq!(board.positions(), vec![0, 5, 0]);
+        assert!(CommandSet::for_firmware(ArduinoFirmware::StringDriverV2).enable_id.is_none());
+    }
+
+    #[test]
+    fn test_command_sets_per_firmware() {
+        // Golden command tables: (positions query, amove, rmove, set_stepper, set_accel, set_speed, set_min, set_max, position bytes)
+        let table = |cs: CommandSet| {
+            (cs.positions_cmd, cs.amove_id, cs.rmove_id, cs.set_stepper_id, cs.set_accel_id, cs.set_speed_id, cs.set_min_id, cs.set_max_id, cs.position_bytes)
+        };
+        let v1 = CommandSet::for_firmware(ArduinoFirmware::StringDriverV1);
+        let v2 = CommandSet::for_firmware(ArduinoFirmware::StringDriverV2);
+        let v3 = CommandSet::for_firmware(ArduinoFirmware::StringDriverV3);
+        assert_eq!(table(v1), (&b"2;"[..], 3, 4, 7, 8, 9, 10, 11, 2));
+        assert_eq!(table(v2), (&b"1;"[..], 2, 3, 6, 7, 8, 9, 10, 2));
+        assert_eq!(table(v3), (&b"1;"[..], 2, 3, 6, 7, 8, 9, 10, 4));
+        for cs in [v1, v2] {
+            assert!(cs.encoders_cmd.is_none() && cs.set_encoder_id.is_none() && cs.enable_id.is_none() && cs.version_cmd.is_none());
+        }
+        assert_eq!((v3.encoders_cmd, v3.set_encoder_id), (Some(&b"11;"[..]), Some(12)));
+        assert_eq!((v3.enable_id, v3.version_cmd), (Some(13), Some(&b"14;"[..])));
+    }
+
+    #[test]
+    fn test_two_digit_command_ids() {
+        let (mut worker, board, _rx) = loopback_worker(ArduinoFirmware::StringDriverV1, 2);
+        worker.handle(command(worker.command_set.set_min_id, 1, -100));
+        worker.handle(command(worker.command_set.set_max_id, 1, 100));
+        let ids: Vec<u8> = board.commands().iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![10, 11]);
+    }
+
+    #[test]
+    fn test_motion_commands_update_model() {
+        let (mut worker, board, rx) = loopback_worker(ArduinoFirmware::StringDriverV2, 3);
+        let cs = worker.command_set;
+        worker.handle(command(cs.amove_id, 0, 1200));
+        worker.handle(command(cs.rmove_id, 1, -5));
+        worker.handle(command(cs.set_stepper_id, 2, 59));
+        worker.handle(SerialRequest::Command {
+            cmd_id: cs.rmove_id,
+            stepper: 0,
+            value: 2,
+            refresh_after: Some(Duration::ZERO),
+            audit: None,
+        });
+        assert_eq!(board.positions(), vec![1202, -5, 59]);
+        assert_eq!(reported_positions(&rx), vec![vec![1202, -5, 59]]);
+    }
+
+    #[cfg(feature = "gui")]
+    #[test]
+    fn test_moves_in_flight_tracked_until_done() {
+        // 100 steps/s at 1000 steps/s²: 10 steps of ramps, the rest at full speed
+        assert_eq!(travel_time(200, 100, 1000), Duration::from_millis(2100));
+        assert_eq!(travel_time(-200, 100, 1000), Duration::from_millis(2100));
+        // Too short to reach full speed: 2 * sqrt(4 / 1000)
+        assert!((travel_time(4, 100, 1000).as_secs_f64() - 0.1265).abs() < 1e-3);
+        assert_eq!(travel_time(0, 100, 1000), Duration::ZERO);
+
+        let (requests, _rx) = mpsc::channel();
+        let mut gui = StepperCore { positions: vec![0; 5], topology: topology::Topology::new(2, Some(1), None, topology::TunerLayout::None, None), serial: Some(requests), ..StepperCore::default() };
+        let rmove = gui.command_set.rmove_id;
+        gui.move_stepper_with_source("UI", 1, 10);
+        gui.move_stepper_with_source("UI", 1, 10);
+        assert_eq!(gui.moves_in_flight.iter().map(|m| m.target).collect::<Vec<_>>(), vec![10, 20]);
+        assert_eq!(gui.moves_in_flight[0].estimate, travel_time(10, gui.z_speed, gui.z_accel));
+        let now = Instant::now();
+        assert_eq!(gui.move_in_flight(Board::Main, 1).unwrap().progress(now), MoveProgress::Queued);
+
+        gui.serial_events_tx.send(SerialEvent::CommandStarted(Board::Main, rmove, 1)).unwrap();
+        gui.drain_serial_events();
+        assert!(matches!(gui.moves_in_flight[0].progress(Instant::now()), MoveProgress::Moving { .. }));
+        assert_eq!(gui.moves_in_flight[1].progress(Instant::now()), MoveProgress::Queued);
+        let late = gui.moves_in_flight[0].estimate + MOVE_OVERDUE_GRACE + Duration::from_secs(1);
+        assert!(matches!(gui.moves_in_flight[0].progress(gui.moves_in_flight[0].started.unwrap() + late), MoveProgress::Overdue { .. }));
+
+        gui.serial_events_tx.send(SerialEvent::CommandDone(Board::Main, rmove, 1)).unwrap();
+        gui.drain_serial_events();
+        assert_eq!(gui.moves_in_flight.len(), 1);
+        assert_eq!(gui.move_in_flight(Board::Main, 1).unwrap().target, 20);
+
+        // The serial thread reports the start and end of every command it runs
+        let (mut worker, _board, rx) = loopback_worker(ArduinoFirmware::StringDriverV2, 2);
+        worker.handle(command(rmove, 1, 3));
+        let events: Vec<String> = rx.try_iter().map(|e| format!("{:?}", e)).collect();
+        assert_eq!(events, vec![format!("CommandStarted(Main, {}, 1)", rmove), format!("CommandDone(Main, {}, 1)", rmove)]);
+    }
+
+    #[cfg(feature = "gui")]
+    #[test]
+    fn test_jog_streams_moves_while_held_and_stops_on_release() {
+        let (requests, rx) = mpsc::channel();
+        let audit_dir = std::env::temp_dir().join(format!("stringdriver_jog_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&audit_dir);
+        let mut gui = StepperCore {
+            positions: vec![0; 5],
+            topology: topology::Topology::new(2, Some(1), None, topology::TunerLayout::None, None),
+            serial: Some(requests),
+            audit_log: Arc::new(Mutex::new(AuditLog::open(audit_dir.clone()))),
+            ..StepperCore::default()
+        };
+        gui.set_jog_rate(20.0);
+        let rmove = gui.command_set.rmove_id;
+        let done = |gui: &mut StepperCore| {
+            gui.serial_events_tx.send(SerialEvent::CommandDone(Board::Main, rmove, 3)).unwrap();
+            gui.drain_serial_events();
+        };
+        let sent = |rx: &mpsc::Receiver<SerialRequest>| rx.try_iter().map(|r| match r {
+            SerialRequest::Command { value, refresh_after, audit, .. } => (value, refresh_after, audit.is_some()),
+            other => panic!("unexpected {:?}", other),
+        }).collect::<Vec<_>>();
+        let start = Instant::now();
+        let hold = |gui: &mut StepperCore, at: Duration| {
+            gui.jog_requests.push((JogTarget::Stepper(3), 2));
+            gui.service_jog(start + at);
+        };
+
+        // The press steps once, like a click, and nothing more before the hold delay
+        hold(&mut gui, Duration::ZERO);
+        assert_eq!(sent(&rx), vec![(2, Some(Duration::from_millis(500)), true)]);
+        done(&mut gui);
+        hold(&mut gui, JOG_HOLD_DELAY / 2);
+        assert!(sent(&rx).is_empty());
+        assert_eq!(gui.jog_key_target, Some(JogTarget::Stepper(3)));
+
+        // Held: one move per 1/20 s, read back at once, never more than one queued
+        hold(&mut gui, JOG_HOLD_DELAY);
+        hold(&mut gui, JOG_HOLD_DELAY + Duration::from_millis(60));
+        assert_eq!(sent(&rx), vec![(2, Some(Duration::ZERO), false)]);
+        done(&mut gui);
+        hold(&mut gui, JOG_HOLD_DELAY + Duration::from_millis(70));
+        hold(&mut gui, JOG_HOLD_DELAY + Duration::from_millis(80));
+        assert_eq!(sent(&rx).len(), 1);
+        assert_eq!(gui.jog.as_ref().map(|j| (j.steps, j.total)), Some((2, 4)));
+
+        // Released: nothing further is sent
+        done(&mut gui);
+        gui.service_jog(start + Duration::from_secs(1));
+        assert!(gui.jog.is_none());
+        assert!(sent(&rx).is_empty());
+        // The jog moves are audited as one, after the press's own step
+        let audit = std::fs::read_to_string(audit_dir.join("motion_audit.log")).unwrap();
+        assert!(audit.contains("source=UI action=jog stepper=3 value=4"), "{}", audit);
+
+        // A disabled stepper stops the jog at its first move
+        gui.disabled_steppers.insert(3);
+        hold(&mut gui, Duration::from_secs(2));
+        hold(&mut gui, Duration::from_secs(3));
+        assert!(sent(&rx).is_empty());
+        assert!(gui.jog.as_ref().unwrap().stopped);
+        std::fs::remove_dir_all(&audit_dir).unwrap();
+    }
+
+    #[test]
+    fn test_v2_values_truncate_to_int() {
+        // v2 firmware reads the 4-byte value argument as a 2-byte int
+        let (mut worker, board, _rx) = loopback_worker(ArduinoFirmware::StringDriverV2, 1);
+        worker.handle(command(worker.command_set.amove_id, 0, 70000));
+        assert_eq!(board.positions(), vec![70000_i32 as i16 as i32]);
+    }
+
+    #[test]
+    fn test_positions_and_encoders_round_trip() {
+        let (mut worker, board, rx) = loopback_worker(ArduinoFirmware::StringDriverV3, 4);
+        let positions = vec![0, 59, -100000, i32::from_le_bytes([b'/', b',', b';', 0])];
+        board.set_positions(&positions);
+        worker.handle(SerialRequest::RefreshPositions);
+        let events: Vec<SerialEvent> = rx.try_iter().collect();
+        assert!(matches!(&events[..], [
+            SerialEvent::Positions(Board::Main, p),
+            SerialEvent::Encoders(Board::Main, e),
+        ] if *p == positions && e.iter().copied().eq(positions.iter().map(|v| Some(*v)))));
+    }
+
+    #[test]
+    fn test_partial_reads_with_escaped_terminator() {
+        let (mut worker, board, rx) = loopback_worker(ArduinoFirmware::StringDriverV2, 3);
+        // 0x3B3B is sent as "/;/;" - an escaped ';' must not end the read early
+        board.set_positions(&[0x3B3B, 59, -1]);
+        board.split_replies(1, true);
+        worker.handle(SerialRequest::RefreshPositions);
+        assert_eq!(reported_positions(&rx), vec![vec![0x3B3B, 59, -1]]);
+    }
+
+    #[test]
+    fn test_malformed_replies_dropped() {
+        let (mut worker, board, rx) = loopback_worker(ArduinoFirmware::StringDriverV2, 2);
+        board.set_positions(&[10, 20]);
+        for corrupt in [&b"1;"[..], b"garbage;", b"1,\x05\x00;", b"1,\x05/"] {
+            board.corrupt_next_reply(corrupt);
+            worker.handle(SerialRequest::RefreshPositions);
+            assert!(reported_positions(&rx).is_empty(), "accepted {:?}", String::from_utf8_lossy(corrupt));
+        }
+        worker.handle(SerialRequest::RefreshPositions);
+        assert_eq!(reported_positions(&rx), vec![vec![10, 20]]);
+    }
+
+    #[test]
+    fn test_debug_prints_around_reply_skipped() {
+        let (mut worker, board, rx) = loopback_worker(ArduinoFirmware::StringDriverV2, 2);
+        board.set_positions(&[300, -2]);
+        // Looks like the start of a positions frame until its text overruns a value
+        board.print_before_next_reply("stepper 1, done\r\n1,");
+        board.split_replies(3, false);
+        worker.handle(SerialRequest::RefreshPositions);
+        assert_eq!(reported_positions(&rx), vec![vec![300, -2]]);
+    }
+
+    #[test]
+    fn test_frame_decoder_resumes_across_pushes() {
+        let mut decoder = arduino_connection::FrameDecoder::new(1, 2, 2, false);
+        let frame = b"\r\n11,xx;1,/;\x01,/,\x00;";
+        for (i, b) in frame.iter().enumerate() {
+            let decoded = decoder.push(&[*b]);
+            assert_eq!(decoded.is_some(), i == frame.len() - 1);
+            if let Some(values) = decoded {
+                assert_eq!(values, vec![0x013B, 0x002C]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_frame_decoder_rejects_wrong_lengths() {
+        for frame in [&b"1,\x01\x00;"[..], b"1,\x01\x00,\x02;", b"1,\x01\x00,\x02\x00,\x03\x00;"] {
+            let mut decoder = arduino_connection::FrameDecoder::new(1, 2, 2, false);
+            assert_eq!(decoder.push(frame), None, "accepted {:?}", String::from_utf8_lossy(frame));
+        }
+    }
+
+    #[test]
+    fn test_reply_checksum_verified() {
+        let mut command_set = CommandSet::for_firmware(ArduinoFirmware::StringDriverV3);
+        command_set.checksum = true;
+        let (mut worker, board, rx) = loopback_worker_with(command_set, 2);
+        board.set_positions(&[5000, -7]);
+        // Right shape, wrong CRC
+        let mut bad = b"1,\x88\x13\x00\x00,\xf9\xff\xff\xff,".to_vec();
+        let good_crc = arduino_connection::crc8(&[0x88, 0x13, 0, 0, 0xf9, 0xff, 0xff, 0xff]);
+        bad.extend_from_slice(&[good_crc ^ 0x01, b';']);
+        board.corrupt_next_reply(&bad);
+        worker.handle(SerialRequest::RefreshPositions);
+        assert!(reported_positions(&rx).is_empty());
+        worker.handle(SerialRequest::RefreshPositions);
+        assert_eq!(reported_positions(&rx), vec![vec![5000, -7]]);
+    }
+
+    #[test]
+    fn test_unanswered_queries_reported_then_probed() {
+        let mut command_set = CommandSet::for_firmware(ArduinoFirmware::StringDriverV2);
+        command_set.positions_timeout = Duration::from_millis(400);
+        let (mut worker, board, rx) = loopback_worker_with(command_set, 2);
+        board.set_positions(&[1, 2]);
+        let failure = |rx: &mpsc::Receiver<SerialEvent>| match rx.try_iter().collect::<Vec<_>>().as_slice() {
+            [SerialEvent::PositionsFailed(Board::Main, e)] => e.clone(),
+            other => panic!("expected one failure event, got {:?}", other),
+        };
+
+        board.corrupt_next_reply(b"");
+        worker.handle(SerialRequest::RefreshPositions);
+        assert_eq!(failure(&rx), QueryError::TimedOut { what: "positions", after: Duration::from_millis(400) });
+
+        // Still silent: the next query gives up after the short probe
+        board.corrupt_next_reply(b"");
+        let started = Instant::now();
+        worker.handle(SerialRequest::RefreshPositions);
+        assert_eq!(failure(&rx), QueryError::TimedOut { what: "positions", after: WEDGED_PROBE_TIMEOUT });
+        assert!(started.elapsed() < Duration::from_millis(400));
+
+        worker.handle(SerialRequest::RefreshPositions);
+        assert_eq!(reported_positions(&rx), vec![vec![1, 2]]);
+        assert!(!worker.wedged);
+    }
+
+    #[test]
+    fn test_firmware_version_reaches_hello() {
+        use std::io::{BufRead, BufReader};
+        let (mut worker, board, rx) = loopback_worker(ArduinoFirmware::StringDriverV3, 2);
+        worker.handle(SerialRequest::QueryFirmwareVersion);
+        let answered = rx.try_recv().unwrap();
+        assert!(matches!(answered, SerialEvent::FirmwareVersion(Board::Main, Some(build_info::FIRMWARE_PROTOCOL))));
+
+        // Older firmware stays silent; that is not a wedged board
+        let old = LoopbackBoard::new(FirmwareProtocol { version: None, ..protocol_for(&worker.command_set) }, 2);
+        worker.port = old.open().unwrap();
+        worker.handle(SerialRequest::QueryFirmwareVersion);
+        assert!(matches!(rx.try_recv().unwrap(), SerialEvent::FirmwareVersion(Board::Main, None)));
+        assert!(!worker.wedged);
+        drop(board);
+
+        let mut gui = StepperCore::default();
+        gui.serial_events_tx.send(answered).unwrap();
+        gui.drain_serial_events();
+        let (server, client) = UnixStream::pair().unwrap();
+        let mut server = LinkStream::from(server);
+        let peer = BuildInfo { protocol: build_info::PROTOCOL_VERSION + 1, ..BuildInfo::local("operations_gui") };
+        gui.handle_command(&peer.hello_line(), "test", Some(&mut server));
+        let mut line = String::new();
+        BufReader::new(client).read_line(&mut line).unwrap();
+        let reply = BuildInfo::parse_hello(&line).unwrap();
+        assert_eq!(reply.firmware, Some(build_info::FIRMWARE_PROTOCOL));
+        assert_eq!(gui.peers["operations_gui"], peer);
+        assert_eq!(gui.build_info().mismatches(&peer).len(), 1);
+    }
+
+    #[test]
+    fn test_motion_params_synced_once_per_axis() {
+        use config_loader::AxisMotion;
+        let mut gui = StepperCore {
+            positions: vec![0; 3],
+            topology: topology::Topology::new(1, Some(1), Some(0), topology::TunerLayout::None, None),
+            ..StepperCore::default()
+        };
+        gui.set_motion_params(MotionParams {
+            x: AxisMotion { speed: Some(700), ..AxisMotion::default() },
+            z: AxisMotion { min: Some(-50), max: Some(60), ..AxisMotion::default() },
+            ..MotionParams::default()
+        });
+        let (tx, rx) = mpsc::channel();
+        gui.serial = Some(tx);
+        gui.sync_motion_params(Board::Main, "connect");
+
+        let cs = gui.command_set;
+        let sent: Vec<(u8, i16, i32)> = rx
+            .try_iter()
+            .filter_map(|r| match r {
+                SerialRequest::Command { cmd_id, stepper, value, .. } => Some((cmd_id, stepper, value)),
+                _ => None,
+            })
+            .collect();
+        assert!(sent.contains(&(cs.set_speed_id, 0, 700)));
+        assert!(sent.contains(&(cs.set_accel_id, 0, 10000)));
+        assert!(sent.contains(&(cs.set_speed_id, 2, 100)));
+        let z_limits: Vec<_> = sent.iter().filter(|(id, axis, _)| (*id == cs.set_min_id || *id == cs.set_max_id) && *axis == 1).collect();
+        assert_eq!(z_limits, vec![&(cs.set_min_id, 1, -50), &(cs.set_max_id, 1, 60)]);
+        assert!(gui.last_motion_sync.as_deref().unwrap().contains("X speed 700"));
+    }
+
+    #[test]
+    fn test_set_limits_writes_the_firmware_group_row() {
+        fn limits(rx: &mpsc::Receiver<SerialRequest>) -> Vec<(i16, i32)> {
+            rx.try_iter()
+                .filter_map(|r| match r {
+                    SerialRequest::Command { stepper, value, .. } => Some((stepper, value)),
+                    _ => None,
+                })
+                .collect()
+        }
+        // v1 numbers its minmax rows tuners, gantry (X), coils (Z)
+        let mut gui = StepperCore { command_set: CommandSet::for_firmware(ArduinoFirmware::StringDriverV1), ..StepperCore::default() };
+        let (tx, rx) = mpsc::channel();
+        gui.serial = Some(tx);
+        gui.set_limits(LimitGroup::X, 0, 2600).unwrap();
+        gui.set_limits(LimitGroup::Z, -100, 100).unwrap();
+        gui.set_limits(LimitGroup::Tuners, -25000, 25000).unwrap();
+        assert_eq!(limits(&rx), vec![(1, 0), (1, 2600), (2, -100), (2, 100), (0, -25000), (0, 25000)]);
+        assert!(gui.set_limits(LimitGroup::X, 10, 0).is_err());
+
+        // v2 keeps X and Z rows only; a tuner board has one row for all its tuners
+        let mut gui = StepperCore::default();
+        let (tx, rx) = mpsc::channel();
+        gui.serial = Some(tx);
+        gui.set_limits(LimitGroup::Z, -50, 60).unwrap();
+        assert_eq!(limits(&rx), vec![(1, -50), (1, 60)]);
+        assert!(gui.set_limits(LimitGroup::Tuners, -100, 100).is_err());
+        let (tuner_tx, tuner_rx) = mpsc::channel();
+        gui.tuner_serial = Some(tuner_tx);
+        gui.tuner_command_set = CommandSet::for_firmware(ArduinoFirmware::StringDriverV2).on_tuner_board();
+        gui.handle_command("set_limits tuners -5000 5000", "test", None);
+        assert_eq!(limits(&tuner_rx), vec![(0, -5000), (0, 5000)]);
+        assert_eq!(gui.tuner_limits(), (-5000, 5000));
+        assert!(limits(&rx).is_empty());
+    }
+
+    #[test]
+    fn test_ipc_move_finishes_before_the_next_command() {
+        use std::io::{BufRead, BufReader};
+        let command_set = CommandSet::for_firmware(ArduinoFirmware::StringDriverV3);
+        let board = LoopbackBoard::new(protocol_for(&command_set), 2);
+        let gui = StepperCore { positions: vec![0; 2], command_set, ..StepperCore::default() };
+        let audit_dir = std::env::temp_dir().join(format!("stringdriver_ipc_move_{}", std::process::id()));
+        let audit_log = Arc::new(Mutex::new(AuditLog::open(audit_dir)));
+        let serial = SerialWorker::spawn(Board::Main, board.open().unwrap(), command_set, 2, gui.serial_events_tx.clone(), audit_log);
+        let app = Arc::new(Mutex::new(StepperCore { serial: Some(serial), ..gui }));
+
+        let (server, client) = UnixStream::pair().unwrap();
+        let mut replies = BufReader::new(client);
+        let job = |cmd: &str| {
+            let mut job = SerialJob { cmd: cmd.to_string(), client: "IPC#1".to_string(), responder: Some(LinkStream::from(server.try_clone().unwrap())) };
+            StepperCore::run_job(&app, &mut job);
+        };
+        job("rel_move 1 5");
+        job("abs_move 0 -20");
+        job("get_positions");
+        let mut line = String::new();
+        replies.read_line(&mut line).unwrap();
+        assert_eq!(line, "positions 0=-20 1=5\n");
+        assert_eq!(board.positions(), vec![-20, 5]);
+    }
+
+    #[test]
+    fn test_run_command_reports_refused_moves() {
+        let command_set = CommandSet::for_firmware(ArduinoFirmware::StringDriverV3);
+        let board = LoopbackBoard::new(protocol_for(&command_set), 2);
+        let gui = StepperCore { positions: vec![0; 2], command_set, ..StepperCore::default() };
+        let audit_dir = std::env::temp_dir().join(format!("stringdriver_run_command_{}", std::process::id()));
+        let audit_log = Arc::new(Mutex::new(AuditLog::open(audit_dir)));
+        let serial = SerialWorker::spawn(Board::Main, board.open().unwrap(), command_set, 2, gui.serial_events_tx.clone(), audit_log);
+        let mut gui = StepperCore { serial: Some(serial), connected: true, ..gui };
+
+        gui.run_command("rel_move 1 5", "stringdriverd").unwrap();
+        assert_eq!(board.positions(), vec![0, 5]);
+        gui.run_command("disable 1", "stringdriverd").unwrap();
+        let refused = gui.run_command("rel_move 1 5", "stringdriverd").unwrap_err();
+        assert!(refused.to_string().contains("stepper is disabled"), "{}", refused);
+        assert_eq!(board.positions(), vec![0, 5]);
+
+        let mut unplugged = StepperCore::default();
+        assert!(unplugged.run_command("abs_move 0 10", "stringdriverd").is_err());
+    }
+
+    #[test]
+    fn test_stale_positions_refused_over_socket() {
+        use std::io::{BufRead, BufReader};
+        let mut gui = StepperCore::default();
+        let (server, client) = UnixStream::pair().unwrap();
+        let mut server = LinkStream::from(server);
+        let mut replies = BufReader::new(client);
+        let mut ask = |gui: &mut StepperCore, cmd: &str| {
+            gui.handle_command(cmd, "test", Some(&mut server));
+            let mut line = String::new();
+            replies.read_line(&mut line).unwrap();
+            line
+        };
+
+        let timeout = QueryError::TimedOut { what: "positions", after: Duration::from_secs(2) };
+        gui.serial_events_tx.send(SerialEvent::PositionsFailed(Board::Main, timeout)).unwrap();
+        gui.drain_serial_events();
+        assert_eq!(ask(&mut gui, "get_positions"), "error timeout 2000\n");
+        assert!(ask(&mut gui, "get_status").contains(" positions=stale "));
+
+        gui.serial_events_tx.send(SerialEvent::Positions(Board::Main, vec![5, -5])).unwrap();
+        gui.drain_serial_events();
+        assert_eq!(ask(&mut gui, "get_positions"), "positions 0=5 1=-5\n");
+        assert!(ask(&mut gui, "get_status").contains(" positions=ok "));
+    }
+
+    #[test]
+    fn test_startup_check_flags_lost_positions() {
+        use std::io::{BufRead, BufReader};
+        let path = std::env::temp_dir().join(format!("stringdriver_position_model_{}.json", std::process::id()));
+        let saved = PositionModel { main: vec![0, -40, 100], tuner: Vec::new(), saved_at: String::new() };
+        position_model::save(&path, &saved).unwrap();
+
+        let mut gui = StepperCore { topology: topology::Topology::new(1, Some(1), None, topology::TunerLayout::None, None), ..StepperCore::default() };
+        gui.set_position_model_path(path.clone());
+        let (server, client) = UnixStream::pair().unwrap();
+        let mut server = LinkStream::from(server);
+        let mut replies = BufReader::new(client);
+        let mut status = |gui: &mut StepperCore| {
+            gui.handle_command("get_status", "test", Some(&mut server));
+            let mut line = String::new();
+            replies.read_line(&mut line).unwrap();
+            line
+        };
+        assert!(status(&mut gui).contains(" startup=pending "));
+
+        // Power loss: the board forgot stepper 1
+        gui.serial_events_tx.send(SerialEvent::Positions(Board::Main, vec![0, 0, 100])).unwrap();
+        gui.drain_serial_events();
+        assert_eq!(gui.startup_mismatch, vec![(Board::Main, Discrepancy { stepper: 1, expected: -40, reported: 0 })]);
+        assert!(status(&mut gui).contains(" startup=mismatch rehome=Z "));
+        assert_eq!(position_model::load(&path).unwrap().main, vec![0, -40, 100]);
+
+        #[cfg(feature = "gui")]
+        {
+            gui.accept_reported_positions();
+            assert!(status(&mut gui).contains(" startup=ok rehome=- "));
+            assert_eq!(position_model::load(&path).unwrap().main, vec![0, 0, 100]);
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_shared_state_changes_reach_subscribers() {
+        use std::io::{BufRead, BufReader};
+        let mut gui = StepperCore { positions: vec![0; 3], ..StepperCore::default() };
+        let (server, client) = UnixStream::pair().unwrap();
+        let mut server = LinkStream::from(server);
+        let mut updates = BufReader::new(client);
+        let mut next = || {
+            let mut line = String::new();
+            updates.read_line(&mut line).unwrap();
+            line
+        };
+        gui.handle_command("subscribe shared", "IPC#1", Some(&mut server));
+        assert_eq!(next(), "shared z_up_step=2 z_down_step=-2 x_step=10 disabled=-\n");
+        gui.broadcast_shared_if_changed(); // Unchanged: nothing pushed
+
+        gui.handle_command("set_shared z_up_step=4 z_down_step=3", "IPC#2", None);
+        gui.handle_command("disable 1", "IPC#2", None);
+        gui.broadcast_shared_if_changed();
+        assert_eq!(next(), "shared z_up_step=4 z_down_step=-2 x_step=10 disabled=1\n"); // z_down_step=3 refused
+    }
+
+    #[test]
+    fn test_disabled_stepper_is_locked_out() {
+        use std::io::{BufRead, BufReader};
+        let mut gui = StepperCore { positions: vec![0; 3], ..StepperCore::default() };
+        let (server, client) = UnixStream::pair().unwrap();
+        let mut server = LinkStream::from(server);
+        let mut replies = BufReader::new(client);
+        let mut status = |gui: &mut StepperCore| {
+            gui.handle_command("get_status", "test", Some(&mut server));
+            let mut line = String::new();
+            replies.read_line(&mut line).unwrap();
+            line
+        };
+        gui.handle_command("disable 2 src=bump_check", "IPC#1", None);
+        assert!(status(&mut gui).ends_with(" disabled=2\n"));
+        assert!(!gui.move_allowed("UI", 2));
+        assert!(gui.move_allowed("UI", 1));
+        gui.handle_command("enable 2", "IPC#1", None);
+        assert!(status(&mut gui).ends_with(" disabled=-\n"));
+        assert!(gui.move_allowed("UI", 2));
+    }
+
+    #[test]
+    fn test_bow_speed_goes_to_the_board() {
+        use std::io::{BufRead, BufReader};
+        let (requests, rx) = mpsc::channel();
+        let mut gui = StepperCore { serial: Some(requests), ..StepperCore::default() };
+        let (server, client) = UnixStream::pair().unwrap();
+        let mut server = LinkStream::from(server);
+        let mut replies = BufReader::new(client);
+        let mut speeds = |gui: &mut StepperCore| {
+            gui.handle_command("get_bow_speeds", "test", Some(&mut server));
+            let mut line = String::new();
+            replies.read_line(&mut line).unwrap();
+            line
+        };
+        assert_eq!(speeds(&mut gui), "bow_speeds none\n");
+
+        let settings = config_loader::BowDriveSettings {
+            output: config_loader::BowOutput::Serial { command_id: 15 },
+            speeds: vec![120],
+            max_speed: 200,
+            min_speed: 0,
+            speed_step: 5,
+            modulate: false,
+        };
+        gui.set_bow_drive(Some(bow_drive::BowDrive::new(settings, 2)));
+        gui.handle_command("bow_speed 1 250", "IPC#1", None);
+        let sent = rx.try_recv().unwrap();
+        assert!(matches!(sent, SerialRequest::Command { cmd_id: 15, stepper: 1, value: 200, .. }), "{:?}", sent);
+        assert_eq!(speeds(&mut gui), "bow_speeds 0=0 1=200 max=200\n");
+        // No wheel for string 2: nothing is sent
+        gui.handle_command("bow_speed 2 100", "IPC#1", None);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_damp_goes_to_the_board() {
+        let (requests, rx) = mpsc::channel();
+        let topology = topology::Topology::new(2, Some(0), None, topology::TunerLayout::None, None);
+        let mut gui = StepperCore { serial: Some(requests), topology, ..StepperCore::default() };
+        // No serial DAMPERS: nothing is sent
+        gui.handle_command("damp 0 1", "IPC#1", None);
+        assert!(rx.try_recv().is_err());
+
+        gui.set_damper_command(Some(16));
+        gui.handle_command("damp 1 1", "IPC#1", None);
+        let sent = rx.try_recv().unwrap();
+        assert!(matches!(sent, SerialRequest::Command { cmd_id: 16, stepper: 1, value: 1, .. }), "{:?}", sent);
+        gui.handle_command("damp 1 0", "IPC#1", None);
+        let sent = rx.try_recv().unwrap();
+        assert!(matches!(sent, SerialRequest::Command { cmd_id: 16, stepper: 1, value: 0, .. }), "{:?}", sent);
+        gui.handle_command("damp 2 1", "IPC#1", None);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_manager_reconnects_after_unplug() {
+        let protocol = protocol_for(&CommandSet::for_firmware(ArduinoFirmware::StringDriverV2));
+        let board = LoopbackBoard::new(protocol, 2);
+        let opener_board = board.clone();
+        let mut manager = ArduinoConnectionManager::new("/dev/loopback".to_string())
+            .with_port_opener(move |_| opener_board.open());
+        manager.connect().unwrap();
+        board.set_positions(&[7, 59]);
+        assert_eq!(manager.read_positions(2).unwrap(), vec![7, 59]);
+
+        board.unplug();
+        assert!(manager.read_positions(2).is_err());
+        assert!(!manager.is_connected());
+        // Still gone: the reconnect attempt fails
+        assert!(manager.rel_move(0, 1).is_err());
+
+        board.plug_in();
+        manager.rel_move(0, 3).unwrap();
+        assert!(manager.is_connected());
+        assert_eq!(manager.read_positions(2).unwrap(), vec![10, 59]);
+        assert_eq!(board.open_count(), 2);
+    }
+}
@@ -0,0 +1,192 @@
+/// Connections to stepper_gui's command socket - local Unix socket or TCP bridge
+///
+/// A target is either a Unix socket path or `host:port` of a stepper_gui on
+/// another machine running with STEPPER_BRIDGE. The text protocol is the same
+/// over both; a TCP connection first sends `auth <token>` and stepper_gui
+/// answers `auth ok` or closes it. A Unix socket client sends the same line
+/// when it has a token (IPC_AUTH, see access.rs). Targets are passed around as
+/// strings, so everything that took a socket path takes a bridge address as well.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long the bridge waits for the auth line, and the client for its answer
+pub const AUTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Tokens for targets, registered once from YAML by whoever resolved the target
+static BRIDGE_TOKENS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+#[derive(Debug)]
+pub enum LinkStream {
+    Unix(UnixStream),
+    Tcp(TcpStream),
+}
+
+impl LinkStream {
+    pub fn try_clone(&self) -> std::io::Result<Self> {
+        match self {
+            LinkStream::Unix(s) => s.try_clone().map(LinkStream::Unix),
+            LinkStream::Tcp(s) => s.try_clone().map(LinkStream::Tcp),
+        }
+    }
+
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+        match self {
+            LinkStream::Unix(s) => s.set_read_timeout(timeout),
+            LinkStream::Tcp(s) => s.set_read_timeout(timeout),
+        }
+    }
+
+    pub fn set_write_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+        match self {
+            LinkStream::Unix(s) => s.set_write_timeout(timeout),
+            LinkStream::Tcp(s) => s.set_write_timeout(timeout),
+        }
+    }
+}
+
+impl From<UnixStream> for LinkStream {
+    fn from(stream: UnixStream) -> Self {
+        LinkStream::Unix(stream)
+    }
+}
+
+impl Read for LinkStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            LinkStream::Unix(s) => s.read(buf),
+            LinkStream::Tcp(s) => s.read(buf),
+        }
+    }
+}
+
+impl Write for LinkStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            LinkStream::Unix(s) => s.write(buf),
+            LinkStream::Tcp(s) => s.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            LinkStream::Unix(s) => s.flush(),
+            LinkStream::Tcp(s) => s.flush(),
+        }
+    }
+}
+
+/// `host:port` rather than a socket path
+pub fn is_bridge(target: &str) -> bool {
+    !target.contains('/') && target.rsplit_once(':').is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok())
+}
+
+/// Token to present when connecting to `address` (bridge or socket path)
+pub fn register_token(address: &str, token: &str) {
+    if let Ok(mut tokens) = BRIDGE_TOKENS.lock() {
+        tokens.retain(|(a, _)| a != address);
+        tokens.push((address.to_string(), token.to_string()));
+    }
+}
+
+fn token_for(address: &str) -> Option<String> {
+    BRIDGE_TOKENS.lock().ok()?.iter().find(|(a, _)| a == address).map(|(_, t)| t.clone())
+}
+
+/// Worth trying to connect: the socket file exists, or the target is a bridge
+/// (whose reachability only a connect attempt can tell)
+pub fn target_ready(target: &str) -> bool {
+    is_bridge(target) || Path::new(target).exists()
+}
+
+pub fn connect(target: &str) -> std::io::Result<LinkStream> {
+    let token = token_for(target);
+    if !is_bridge(target) {
+        let mut stream = LinkStream::Unix(UnixStream::connect(target)?);
+        if let Some(token) = token {
+            present_token(&mut stream, target, &token)?;
+        }
+        return Ok(stream);
+    }
+    let token = token.ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::PermissionDenied, format!("no STEPPER_BRIDGE token configured for {}", target))
+    })?;
+    let stream = TcpStream::connect(target)?;
+    stream.set_nodelay(true)?;
+    let mut stream = LinkStream::Tcp(stream);
+    present_token(&mut stream, target, &token)?;
+    Ok(stream)
+}
+
+fn present_token(stream: &mut LinkStream, target: &str, token: &str) -> std::io::Result<()> {
+    stream.write_all(format!("auth {}\n", token).as_bytes())?;
+    stream.flush()?;
+    // Read the answer byte by byte so nothing after it is swallowed by a buffer
+    stream.set_read_timeout(Some(AUTH_TIMEOUT))?;
+    let mut answer = Vec::new();
+    let mut byte = [0u8; 1];
+    while answer.len() < 64 && stream.read(&mut byte)? == 1 && byte[0] != b'\n' {
+        answer.push(byte[0]);
+    }
+    stream.set_read_timeout(None)?;
+    if answer != b"auth ok" {
+        return Err(std::io::Error::new(std::io::ErrorKind::PermissionDenied, format!("stepper socket {} refused the token", target)));
+    }
+    Ok(())
+}
+
+/// Bridge side: read the client's `auth <token>` line and answer it; `check`
+/// decides what an offered token is worth (None refuses it)
+pub fn accept_auth<T>(stream: &mut TcpStream, check: impl FnOnce(&str) -> Option<T>) -> Option<T> {
+    let _ = stream.set_read_timeout(Some(AUTH_TIMEOUT));
+    let mut line = String::new();
+    let read = stream.try_clone().map(|s| BufReader::new(s.take(256)).read_line(&mut line));
+    let _ = stream.set_read_timeout(None);
+    let granted = match (read, line.trim().strip_prefix("auth ")) {
+        (Ok(Ok(n)), Some(offered)) if n > 0 => check(offered),
+        _ => None,
+    };
+    let answer: &[u8] = if granted.is_some() { b"auth ok\n" } else { b"auth denied\n" };
+    let _ = stream.write_all(answer).and_then(|_| stream.flush());
+    granted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_bridge_connect_requires_token() {
+        assert!(is_bridge("closet-pc:7878"));
+        assert!(!is_bridge("/tmp/stepper_gui__dev_ttyACM0.sock"));
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let mut results = Vec::new();
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let accepted = accept_auth(&mut stream, |offered| (offered == "s3cret").then_some(()));
+                if accepted.is_some() {
+                    stream.write_all(b"positions 0=5\n").unwrap();
+                }
+                results.push(accepted.is_some());
+            }
+            results
+        });
+
+        register_token(&address, "wrong");
+        assert!(connect(&address).is_err());
+        register_token(&address, "s3cret");
+        let mut reader = BufReader::new(connect(&address).unwrap());
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "positions 0=5\n");
+        assert_eq!(server.join().unwrap(), vec![false, true]);
+    }
+}
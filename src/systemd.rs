@@ -0,0 +1,82 @@
+/// systemd service notifications (sd_notify) for stringdriverd
+///
+/// Under `Type=notify` systemd counts the service as started only once READY=1
+/// arrives, and with `WatchdogSec=` it restarts the service when WATCHDOG=1
+/// pings stop. Both are datagrams to $NOTIFY_SOCKET (a path, or an abstract
+/// socket when it starts with '@'). Outside systemd the variable is unset and
+/// every call is a no-op.
+
+use std::io;
+use std::os::unix::net::UnixDatagram;
+use std::time::Duration;
+
+/// Send `state` (newline-separated KEY=VALUE pairs); false when not run by systemd or the send failed
+pub fn notify(state: &str) -> bool {
+    let Some(socket) = std::env::var_os("NOTIFY_SOCKET") else {
+        return false;
+    };
+    match notify_to(&socket.to_string_lossy(), state) {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!("sd_notify '{}' failed: {}", state.replace('\n', " "), e);
+            false
+        }
+    }
+}
+
+fn notify_to(socket: &str, state: &str) -> io::Result<()> {
+    let datagram = UnixDatagram::unbound()?;
+    match socket.strip_prefix('@') {
+        Some(name) => send_abstract(&datagram, name, state),
+        None => datagram.send_to(state.as_bytes(), socket).map(|_| ()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn send_abstract(datagram: &UnixDatagram, name: &str, state: &str) -> io::Result<()> {
+    use std::os::linux::net::SocketAddrExt;
+    let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+    datagram.send_to_addr(state.as_bytes(), &addr).map(|_| ())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn send_abstract(_datagram: &UnixDatagram, name: &str, _state: &str) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, format!("abstract socket @{} needs Linux", name)))
+}
+
+/// How often to send WATCHDOG=1: half of WatchdogSec, None without a watchdog for this process
+pub fn watchdog_interval() -> Option<Duration> {
+    let usec = std::env::var("WATCHDOG_USEC").ok();
+    let pid = std::env::var("WATCHDOG_PID").ok();
+    watchdog_from(usec.as_deref(), pid.as_deref(), std::process::id())
+}
+
+fn watchdog_from(usec: Option<&str>, pid: Option<&str>, own_pid: u32) -> Option<Duration> {
+    if pid.is_some_and(|p| p.parse() != Ok(own_pid)) {
+        return None;
+    }
+    let usec: u64 = usec?.parse().ok().filter(|&u| u > 0)?;
+    Some(Duration::from_micros(usec / 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notify_and_watchdog_settings() {
+        let path = std::env::temp_dir().join(format!("stringdriver_notify_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixDatagram::bind(&path).unwrap();
+        notify_to(path.to_str().unwrap(), "READY=1\nSTATUS=Connected").unwrap();
+        let mut buf = [0u8; 64];
+        let len = listener.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"READY=1\nSTATUS=Connected");
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(watchdog_from(Some("30000000"), None, 7), Some(Duration::from_secs(15)));
+        assert_eq!(watchdog_from(Some("30000000"), Some("7"), 7), Some(Duration::from_secs(15)));
+        assert_eq!(watchdog_from(Some("30000000"), Some("8"), 7), None);
+        assert_eq!(watchdog_from(None, None, 7), None);
+    }
+}
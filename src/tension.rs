@@ -0,0 +1,308 @@
+/// Tuner safety - each string's pitch against its tuner rotation
+///
+/// A string's tension goes with the square of its pitch and, over a tuner's working
+/// range, close to linearly with rotation, so a straight line through pitch² against
+/// tuner position predicts where a move will take the string. Samples (tuner position
+/// and the pitch heard there) are logged per string to `<LOG_DIR>/string_<n>.csv` and
+/// the newest SAMPLES of them are fitted. A tuner move predicted to go past the
+/// string's MAX_PITCH (STRINGS) is refused; until a string has been heard at two tuner
+/// positions nothing can be predicted, so its moves are held to PROBE_STEPS.
+
+use crate::config_loader::TunerSafetySettings;
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const CSV_HEADER: &str = "unix_time,position,pitch_hz";
+/// Partials quieter than this share of the loudest are not taken for the fundamental
+const FUNDAMENTAL_SHARE: f32 = 0.1;
+const MIN_PITCH: f32 = 20.0; // Hz; anything lower is rumble, not a string
+/// A tuner must sit still this long before the pitch heard is logged against it
+const SETTLE: Duration = Duration::from_secs(1);
+
+/// The string's pitch in a channel's partials: the lowest partial at least
+/// FUNDAMENTAL_SHARE as loud as the loudest, or None when the channel is silent
+pub fn estimate_pitch(partials: &[(f32, f32)]) -> Option<f32> {
+    let loudest = partials.iter().map(|(_, amp)| *amp).fold(0.0f32, f32::max);
+    if loudest <= 0.0 {
+        return None;
+    }
+    partials.iter()
+        .filter(|(freq, amp)| *freq >= MIN_PITCH && *amp >= loudest * FUNDAMENTAL_SHARE)
+        .map(|(freq, _)| *freq)
+        .reduce(f32::min)
+}
+
+/// One point on a string's curve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PitchSample {
+    pub time: u64, // Unix seconds
+    pub position: i32,
+    pub pitch: f32,
+}
+
+/// pitch² = intercept + slope × tuner position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PitchFit {
+    pub intercept: f64,
+    pub slope: f64,
+}
+
+impl PitchFit {
+    pub fn predict(&self, position: i32) -> f32 {
+        (self.intercept + self.slope * position as f64).max(0.0).sqrt() as f32
+    }
+
+    /// Tension change per 100 tuner steps at `position`, in percent
+    pub fn trend(&self, position: i32) -> Option<f32> {
+        let tension = self.intercept + self.slope * position as f64;
+        (tension > 0.0).then(|| (self.slope * 100.0 / tension * 100.0) as f32)
+    }
+}
+
+/// A string's newest samples, one per tuner position
+#[derive(Debug, Clone, Default)]
+pub struct PitchCurve {
+    samples: VecDeque<PitchSample>,
+    capacity: usize,
+}
+
+impl PitchCurve {
+    pub fn new(capacity: usize) -> Self {
+        Self { samples: VecDeque::new(), capacity: capacity.max(2) }
+    }
+
+    /// The newest `capacity` samples of a curve log; a missing log is an empty curve
+    pub fn load(path: &Path, capacity: usize) -> std::io::Result<Self> {
+        let mut curve = Self::new(capacity);
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(curve),
+            Err(e) => return Err(e),
+        };
+        for line in text.lines().skip_while(|l| *l == CSV_HEADER) {
+            let mut fields = line.split(',');
+            let (Some(time), Some(position), Some(pitch)) = (fields.next(), fields.next(), fields.next()) else { continue };
+            if let (Ok(time), Ok(position), Ok(pitch)) = (time.parse(), position.parse(), pitch.parse()) {
+                curve.push(PitchSample { time, position, pitch });
+            }
+        }
+        Ok(curve)
+    }
+
+    /// Add a sample, replacing an older one at the same tuner position
+    pub fn push(&mut self, sample: PitchSample) {
+        self.samples.retain(|s| s.position != sample.position);
+        self.samples.push_back(sample);
+        while self.samples.len() > self.capacity {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = &PitchSample> {
+        self.samples.iter()
+    }
+
+    pub fn latest(&self) -> Option<PitchSample> {
+        self.samples.back().copied()
+    }
+
+    /// Least squares line through pitch² against position; None below two positions
+    pub fn fit(&self) -> Option<PitchFit> {
+        if self.samples.len() < 2 {
+            return None;
+        }
+        let n = self.samples.len() as f64;
+        let mean_x = self.samples.iter().map(|s| s.position as f64).sum::<f64>() / n;
+        let mean_y = self.samples.iter().map(|s| (s.pitch as f64).powi(2)).sum::<f64>() / n;
+        let (mut sxy, mut sxx) = (0.0, 0.0);
+        for s in &self.samples {
+            let dx = s.position as f64 - mean_x;
+            sxy += dx * ((s.pitch as f64).powi(2) - mean_y);
+            sxx += dx * dx;
+        }
+        if sxx == 0.0 {
+            return None;
+        }
+        let slope = sxy / sxx;
+        Some(PitchFit { intercept: mean_y - slope * mean_x, slope })
+    }
+}
+
+/// Checks tuner moves against every string's curve and MAX_PITCH, and keeps the curve logs
+#[derive(Debug)]
+pub struct TunerGuard {
+    settings: TunerSafetySettings,
+    curves: Vec<PitchCurve>,
+    pending: Vec<Option<(i32, Instant, bool)>>, // Per string: tuner position last seen, since when, logged yet
+}
+
+impl TunerGuard {
+    /// Start from the curves already logged under LOG_DIR
+    pub fn new(settings: &TunerSafetySettings, string_num: usize) -> Self {
+        let mut guard = Self {
+            settings: settings.clone(),
+            curves: vec![PitchCurve::new(settings.samples); string_num],
+            pending: vec![None; string_num],
+        };
+        for string in 0..string_num {
+            guard.reload(string);
+        }
+        guard
+    }
+
+    pub fn log_path(&self, string: usize) -> PathBuf {
+        self.settings.log_dir.join(format!("string_{}.csv", string))
+    }
+
+    /// Re-read a string's log, picking up samples another process added
+    pub fn reload(&mut self, string: usize) {
+        let path = self.log_path(string);
+        if let (Some(curve), Ok(loaded)) = (self.curves.get_mut(string), PitchCurve::load(&path, self.settings.samples)) {
+            *curve = loaded;
+        }
+    }
+
+    pub fn curve(&self, string: usize) -> Option<&PitchCurve> {
+        self.curves.get(string)
+    }
+
+    pub fn max_pitch(&self, string: usize) -> Option<f32> {
+        self.settings.max_pitch.get(string).copied().flatten()
+    }
+
+    /// Log `pitch` heard with the tuner at `position`, unless the curve already has
+    /// that position at the same pitch
+    pub fn record(&mut self, string: usize, position: i32, pitch: f32) -> std::io::Result<()> {
+        let path = self.log_path(string);
+        let Some(curve) = self.curves.get_mut(string) else { return Ok(()) };
+        if curve.samples().any(|s| s.position == position && (s.pitch - pitch).abs() < 0.5) {
+            return Ok(());
+        }
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let sample = PitchSample { time, position, pitch };
+        curve.push(sample);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let is_new = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        if is_new {
+            writeln!(file, "{}", CSV_HEADER)?;
+        }
+        writeln!(file, "{},{},{:.2}", sample.time, sample.position, sample.pitch)
+    }
+
+    /// Record the pitch once per tuner position, after the tuner has sat there for
+    /// SETTLE so the ring-down of a move is not logged as the new pitch
+    pub fn observe(&mut self, string: usize, position: i32, pitch: f32, now: Instant) -> std::io::Result<()> {
+        let Some(pending) = self.pending.get_mut(string) else { return Ok(()) };
+        match pending {
+            Some((seen, since, logged)) if *seen == position => {
+                if !*logged && now.duration_since(*since) >= SETTLE {
+                    *logged = true;
+                    return self.record(string, position, pitch);
+                }
+            }
+            _ => *pending = Some((position, now, false)),
+        }
+        Ok(())
+    }
+
+    /// Refuse a tuner move from `from` to `to` that the curve says passes MAX_PITCH,
+    /// or that is bigger than PROBE_STEPS while the curve is still unknown
+    pub fn check(&self, string: usize, from: i32, to: i32) -> Result<(), String> {
+        let Some(max) = self.max_pitch(string) else { return Ok(()) };
+        match self.curves.get(string).and_then(PitchCurve::fit) {
+            Some(fit) => {
+                let predicted = fit.predict(to);
+                // Always allowed to come down, even from above the limit
+                if predicted > max && predicted > fit.predict(from) {
+                    return Err(format!("tuner to {} would take it to {:.1} Hz, over its MAX_PITCH {:.1} Hz", to, predicted, max));
+                }
+                Ok(())
+            }
+            None if (to - from).abs() > self.settings.probe_steps => Err(format!(
+                "no pitch curve yet; tuner moves are held to PROBE_STEPS ({}) until it has been heard at two positions",
+                self.settings.probe_steps
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// "112.4 Hz (max 115.0), tension +1.8% per 100 steps" for a string's last sample
+    pub fn describe(&self, string: usize) -> Option<String> {
+        let curve = self.curves.get(string)?;
+        let latest = curve.latest()?;
+        let mut text = format!("{:.1} Hz", latest.pitch);
+        if let Some(max) = self.max_pitch(string) {
+            text.push_str(&format!(" (max {:.1})", max));
+        }
+        if let Some(trend) = curve.fit().and_then(|fit| fit.trend(latest.position)) {
+            text.push_str(&format!(", tension {:+.1}% per 100 steps", trend));
+        }
+        Some(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(dir: &Path) -> TunerSafetySettings {
+        TunerSafetySettings { max_pitch: vec![Some(115.0)], probe_steps: 50, log_dir: dir.to_path_buf(), samples: 8 }
+    }
+
+    #[test]
+    fn test_pitch_is_the_lowest_loud_partial() {
+        assert_eq!(estimate_pitch(&[(330.0, 0.4), (110.0, 1.0), (220.0, 0.8), (55.0, 0.01)]), Some(110.0));
+        assert_eq!(estimate_pitch(&[(110.0, 0.0), (220.0, 0.0)]), None);
+    }
+
+    #[test]
+    fn test_curve_predicts_and_refuses_moves_past_max_pitch() {
+        let dir = std::env::temp_dir().join(format!("stringdriver-tension-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut guard = TunerGuard::new(&settings(&dir), 1);
+
+        // Unknown curve: small probes only
+        assert!(guard.check(0, 0, 40).is_ok());
+        assert!(guard.check(0, 0, 400).unwrap_err().contains("PROBE_STEPS"));
+
+        // pitch² rises 100 Hz² per step: 100 Hz at 0, 110 Hz at 21
+        guard.record(0, 0, 100.0).unwrap();
+        guard.record(0, 21, 110.0).unwrap();
+        let fit = guard.curve(0).unwrap().fit().unwrap();
+        assert!((fit.predict(21) - 110.0).abs() < 0.01);
+        assert!(guard.check(0, 21, 30).is_ok());
+        let err = guard.check(0, 21, 40).unwrap_err();
+        assert!(err.contains("118.3 Hz") && err.contains("MAX_PITCH"), "{}", err);
+        // Loosening is never refused
+        assert!(guard.check(0, 60, 50).is_ok());
+        assert!(guard.describe(0).unwrap().starts_with("110.0 Hz (max 115.0), tension +"));
+
+        // The log survives a restart
+        let reloaded = TunerGuard::new(&settings(&dir), 1);
+        assert_eq!(reloaded.curve(0).unwrap().samples().count(), 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_observe_waits_for_the_tuner_to_settle() {
+        let dir = std::env::temp_dir().join(format!("stringdriver-tension-settle-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut guard = TunerGuard::new(&settings(&dir), 1);
+        let start = Instant::now();
+        guard.observe(0, 10, 104.0, start).unwrap();
+        guard.observe(0, 10, 104.0, start + Duration::from_millis(500)).unwrap();
+        assert!(guard.curve(0).unwrap().latest().is_none());
+        guard.observe(0, 10, 104.5, start + Duration::from_millis(1200)).unwrap();
+        assert_eq!(guard.curve(0).unwrap().latest().map(|s| (s.position, s.pitch)), Some((10, 104.5)));
+        // Once per position
+        guard.observe(0, 10, 106.0, start + Duration::from_millis(2000)).unwrap();
+        assert_eq!(guard.curve(0).unwrap().latest().map(|s| s.pitch), Some(104.5));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,310 @@
+/// Operation timeline: where the time of the current or latest operation went
+///
+/// Built from the spans logging keeps for the latest operation (PhaseLog): the
+/// laps and the dwell at each X position of a sweep, calibrations, bump_check
+/// recoveries, z_adjust passes. operations_gui draws it as horizontal bars, one
+/// lane per nesting level, coloured by kind, with each phase's duration on hover
+/// and a table of the total time per phase below.
+
+#[cfg(feature = "gui")]
+use crate::i18n::{tr, trf};
+use crate::logging::PhaseLog;
+#[cfg(feature = "gui")]
+use eframe::egui;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PhaseKind {
+    Dwell,        // The sweep holding at one X position
+    Calibration,  // z_calibrate, z_home, axis homing and calibration, height_calibrate
+    BumpRecovery, // bump_check backing Z steppers off the strings
+    Adjust,       // z_adjust, z_hold, laps and cycles
+    Other,
+}
+
+impl PhaseKind {
+    /// By span name (see the info_span! calls in operations)
+    pub fn of(name: &str) -> Self {
+        match name {
+            "dwell" => PhaseKind::Dwell,
+            "z_calibrate" | "z_home" | "axis_home" | "axis_away" | "axis_calibrate" | "height_calibrate" | "response_map" => PhaseKind::Calibration,
+            "bump_check" => PhaseKind::BumpRecovery,
+            "z_adjust" | "z_hold" | "lap" | "performance_mode" | "x_sweep" => PhaseKind::Adjust,
+            _ => PhaseKind::Other,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PhaseKind::Dwell => "dwell",
+            PhaseKind::Calibration => "calibration",
+            PhaseKind::BumpRecovery => "bump recovery",
+            PhaseKind::Adjust => "adjust",
+            PhaseKind::Other => "other",
+        }
+    }
+}
+
+/// One phase, in time since the operation started
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phase {
+    pub name: String,
+    pub detail: String, // Span fields, e.g. "x=1200"
+    pub kind: PhaseKind,
+    pub lane: usize, // Nesting below the operation, from 0
+    pub start: Duration,
+    pub end: Duration,
+    pub open: bool, // Still running: `end` is now
+}
+
+impl Phase {
+    pub fn duration(&self) -> Duration {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// "dwell x=1200: 1:02.5"
+    pub fn describe(&self) -> String {
+        let name = if self.detail.is_empty() { self.name.clone() } else { format!("{} {}", self.name, self.detail) };
+        format!("{}: {}{}", name, format_duration(self.duration()), if self.open { " (running)" } else { "" })
+    }
+}
+
+/// Time spent in phases of one name
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseTotal {
+    pub name: String,
+    pub kind: PhaseKind,
+    pub count: usize,
+    pub total: Duration,
+    pub longest: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timeline {
+    pub operation: String,
+    pub elapsed: Duration,
+    pub running: bool,
+    pub phases: Vec<Phase>,
+    pub dropped: usize, // Earlier phases no longer kept
+}
+
+impl Timeline {
+    /// The operation in `log` as of `now`; None before the first operation
+    pub fn from_log(log: &PhaseLog, now: Instant) -> Option<Self> {
+        let operation = log.operation.as_ref()?;
+        let end = operation.end.unwrap_or(now);
+        let offset = |at: Instant| at.saturating_duration_since(operation.start);
+        let phases = log.phases.iter()
+            .map(|record| Phase {
+                name: record.name.to_string(),
+                detail: record.fields.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<_>>().join(" "),
+                kind: PhaseKind::of(record.name),
+                lane: record.depth.saturating_sub(1),
+                start: offset(record.start),
+                end: offset(record.end.unwrap_or(end)),
+                open: record.end.is_none() && operation.end.is_none(),
+            })
+            .collect();
+        Some(Self {
+            operation: operation.field("name").unwrap_or(operation.name).to_string(),
+            elapsed: offset(end),
+            running: operation.end.is_none(),
+            phases,
+            dropped: log.dropped,
+        })
+    }
+
+    pub fn lanes(&self) -> usize {
+        self.phases.iter().map(|phase| phase.lane + 1).max().unwrap_or(0)
+    }
+
+    /// Time per phase name, most first. Nested phases count in their parents too.
+    pub fn totals(&self) -> Vec<PhaseTotal> {
+        let mut by_name: BTreeMap<&str, PhaseTotal> = BTreeMap::new();
+        for phase in &self.phases {
+            let total = by_name.entry(&phase.name).or_insert_with(|| PhaseTotal {
+                name: phase.name.clone(),
+                kind: phase.kind,
+                count: 0,
+                total: Duration::ZERO,
+                longest: Duration::ZERO,
+            });
+            total.count += 1;
+            total.total += phase.duration();
+            total.longest = total.longest.max(phase.duration());
+        }
+        let mut totals: Vec<PhaseTotal> = by_name.into_values().collect();
+        totals.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+        totals
+    }
+
+    /// The innermost phase at `at` in `lane`
+    pub fn phase_at(&self, lane: usize, at: Duration) -> Option<&Phase> {
+        self.phases.iter().rev().find(|phase| phase.lane == lane && phase.start <= at && at <= phase.end)
+    }
+}
+
+/// "42.0s", "3:07.2", "2:41:05"
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs < 60.0 {
+        format!("{:.1}s", secs)
+    } else if secs < 3600.0 {
+        format!("{}:{:04.1}", (secs / 60.0) as u64, secs % 60.0)
+    } else {
+        let whole = duration.as_secs();
+        format!("{}:{:02}:{:02}", whole / 3600, whole / 60 % 60, whole % 60)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl PhaseKind {
+    fn color(self) -> egui::Color32 {
+        match self {
+            PhaseKind::Dwell => egui::Color32::from_rgb(70, 130, 200),
+            PhaseKind::Calibration => egui::Color32::from_rgb(150, 100, 200),
+            PhaseKind::BumpRecovery => egui::Color32::from_rgb(230, 120, 0),
+            PhaseKind::Adjust => egui::Color32::from_rgb(60, 170, 90),
+            PhaseKind::Other => egui::Color32::from_gray(130),
+        }
+    }
+}
+
+/// Lane height in points
+#[cfg(feature = "gui")]
+const LANE_HEIGHT: f32 = 18.0;
+/// Phases at least this wide get their name written in
+#[cfg(feature = "gui")]
+const LABEL_MIN_WIDTH: f32 = 60.0;
+
+/// Draw `timeline`: the bars, a time axis, the legend and the totals table
+#[cfg(feature = "gui")]
+pub fn render_timeline(ui: &mut egui::Ui, timeline: &Timeline) {
+    let heading = if timeline.running { "{0} running for {1}" } else { "{0} took {1}" };
+    ui.label(trf(heading, &[&timeline.operation, &format_duration(timeline.elapsed)]));
+    if timeline.dropped > 0 {
+        ui.label(trf("{0} earlier phases not kept", &[&timeline.dropped]));
+    }
+    let lanes = timeline.lanes().max(1);
+    let width = ui.available_width().max(100.0);
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(width, lanes as f32 * LANE_HEIGHT + 14.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+    let span = timeline.elapsed.as_secs_f32().max(0.001);
+    let x_of = |at: Duration| rect.left() + at.as_secs_f32() / span * rect.width();
+    for phase in &timeline.phases {
+        let top = rect.top() + phase.lane as f32 * LANE_HEIGHT;
+        let (x0, x1) = (x_of(phase.start), x_of(phase.end));
+        let bar = egui::Rect::from_min_max(egui::pos2(x0, top + 1.0), egui::pos2(x1.max(x0 + 1.0), top + LANE_HEIGHT - 1.0));
+        painter.rect_filled(bar, 1.0, phase.kind.color());
+        if bar.width() >= LABEL_MIN_WIDTH {
+            painter.with_clip_rect(bar).text(
+                bar.left_center() + egui::vec2(3.0, 0.0),
+                egui::Align2::LEFT_CENTER,
+                format!("{} {}", phase.name, format_duration(phase.duration())),
+                egui::FontId::proportional(11.0),
+                egui::Color32::WHITE,
+            );
+        }
+    }
+    // Time axis: start, quarters and end
+    let axis_y = rect.bottom() - 7.0;
+    for quarter in 0..=4 {
+        let at = timeline.elapsed.mul_f32(quarter as f32 / 4.0);
+        let align = match quarter {
+            0 => egui::Align2::LEFT_CENTER,
+            4 => egui::Align2::RIGHT_CENTER,
+            _ => egui::Align2::CENTER_CENTER,
+        };
+        painter.text(egui::pos2(x_of(at), axis_y), align, format_duration(at), egui::FontId::proportional(10.0), ui.visuals().text_color());
+    }
+    if let Some(pointer) = response.hover_pos() {
+        let lane = ((pointer.y - rect.top()) / LANE_HEIGHT) as usize;
+        let at = Duration::from_secs_f32(((pointer.x - rect.left()) / rect.width()).clamp(0.0, 1.0) * span);
+        if let Some(phase) = timeline.phase_at(lane, at) {
+            response.on_hover_text_at_pointer(format!("{} ({})", phase.describe(), tr(phase.kind.name())));
+        }
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        for kind in [PhaseKind::Dwell, PhaseKind::Calibration, PhaseKind::BumpRecovery, PhaseKind::Adjust, PhaseKind::Other] {
+            ui.colored_label(kind.color(), "■");
+            ui.label(tr(kind.name()));
+        }
+    });
+    egui::Grid::new("timeline_totals").striped(true).show(ui, |ui| {
+        for header in ["Phase", "Count", "Total", "Longest", "Share"] {
+            ui.strong(tr(header));
+        }
+        ui.end_row();
+        for total in timeline.totals() {
+            ui.colored_label(total.kind.color(), &total.name);
+            ui.label(total.count.to_string());
+            ui.label(format_duration(total.total));
+            ui.label(format_duration(total.longest));
+            ui.label(format!("{:.0}%", total.total.as_secs_f32() / span * 100.0));
+            ui.end_row();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging::PhaseRecord;
+
+    fn record(seq: u64, name: &'static str, depth: usize, start: Instant, secs: (u64, Option<u64>)) -> PhaseRecord {
+        PhaseRecord {
+            seq,
+            name,
+            fields: if name == "dwell" { vec![("x", (seq * 10).to_string())] } else { Vec::new() },
+            depth,
+            start: start + Duration::from_secs(secs.0),
+            end: secs.1.map(|end| start + Duration::from_secs(end)),
+        }
+    }
+
+    #[test]
+    fn test_timeline_of_a_running_sweep() {
+        let start = Instant::now();
+        let mut operation = record(0, "operation", 0, start, (0, None));
+        operation.fields = vec![("name", "ping_pong_move".to_string())];
+        let log = PhaseLog {
+            operation: Some(operation),
+            phases: [
+                record(1, "x_sweep", 1, start, (0, None)),
+                record(2, "dwell", 2, start, (1, Some(40))),
+                record(3, "bump_check", 3, start, (10, Some(25))),
+                record(4, "dwell", 2, start, (40, Some(50))),
+                record(5, "dwell", 2, start, (50, None)),
+            ].into(),
+            dropped: 0,
+        };
+        let timeline = Timeline::from_log(&log, start + Duration::from_secs(62)).unwrap();
+        assert_eq!((timeline.operation.as_str(), timeline.elapsed, timeline.running), ("ping_pong_move", Duration::from_secs(62), true));
+        assert_eq!(timeline.lanes(), 3);
+        assert_eq!(timeline.phases[1].describe(), "dwell x=20: 39.0s");
+        assert_eq!(timeline.phases[4].describe(), "dwell x=50: 12.0s (running)");
+        assert_eq!(timeline.phase_at(1, Duration::from_secs(45)).map(|p| p.detail.as_str()), Some("x=40"));
+        assert_eq!(timeline.phase_at(2, Duration::from_secs(30)), None);
+
+        let totals = timeline.totals();
+        let summary: Vec<(&str, usize, Duration)> = totals.iter().map(|t| (t.name.as_str(), t.count, t.total)).collect();
+        assert_eq!(summary, vec![
+            ("x_sweep", 1, Duration::from_secs(62)),
+            ("dwell", 3, Duration::from_secs(61)),
+            ("bump_check", 1, Duration::from_secs(15)),
+        ]);
+        assert_eq!(totals[1].longest, Duration::from_secs(39));
+        assert_eq!(totals[2].kind, PhaseKind::BumpRecovery);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(42_040)), "42.0s");
+        assert_eq!(format_duration(Duration::from_millis(187_200)), "3:07.2");
+        assert_eq!(format_duration(Duration::from_secs(9665)), "2:41:05");
+        assert!(Timeline::from_log(&PhaseLog::default(), Instant::now()).is_none());
+    }
+}
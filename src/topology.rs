@@ -0,0 +1,278 @@
+/// Which stepper does what, built once from the host's config
+///
+/// Strings are numbered in Z pair order: string i has its inner Z at
+/// Z_FIRST_INDEX + 2i with the outer Z right after it, an optional tuner (on the
+/// carriage board or on the tuner board) and the audio channel CHANNEL_MAP gives
+/// it. The X carriage is the one axis shared by every string. Operations and the
+/// GUIs ask the topology instead of redoing the index arithmetic, so a new axis
+/// type is added here and in the role it reports.
+
+/// Board a stepper index refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bank {
+    Main,  // Carriage board (ARD_PORT)
+    Tuner, // Separate tuner board (ARD_T_PORT)
+}
+
+/// A stepper on one of the boards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StepperRef {
+    pub bank: Bank,
+    pub index: usize,
+}
+
+/// Which of a string's two Z steppers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZSide {
+    In,
+    Out,
+}
+
+impl ZSide {
+    pub fn name(self) -> &'static str {
+        match self {
+            ZSide::In => "inner",
+            ZSide::Out => "outer",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    X,
+    Z,
+    Tuner,
+}
+
+impl Axis {
+    pub fn name(self) -> &'static str {
+        match self {
+            Axis::X => "X",
+            Axis::Z => "Z",
+            Axis::Tuner => "tuners",
+        }
+    }
+}
+
+/// What a stepper moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepperRole {
+    X,
+    Z { string: usize, side: ZSide },
+    Tuner { string: usize },
+}
+
+impl StepperRole {
+    pub fn axis(self) -> Axis {
+        match self {
+            StepperRole::X => Axis::X,
+            StepperRole::Z { .. } => Axis::Z,
+            StepperRole::Tuner { .. } => Axis::Tuner,
+        }
+    }
+
+    pub fn string(self) -> Option<usize> {
+        match self {
+            StepperRole::X => None,
+            StepperRole::Z { string, .. } | StepperRole::Tuner { string } => Some(string),
+        }
+    }
+}
+
+/// Where a host's tuners are
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum TunerLayout {
+    #[default]
+    None,
+    Main(Vec<usize>), // Carriage board indices, string order (TUNER_FIRST_INDEX up to X / Z)
+    Board(usize),     // Tuners 0..n on the tuner board
+}
+
+/// The steppers and audio channel of one string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StringSteppers {
+    pub string: usize,
+    pub z_pair: Option<(usize, usize)>, // (inner, outer); None without Z steppers
+    pub tuner: Option<StepperRef>,
+    pub channel: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Topology {
+    strings: Vec<StringSteppers>,
+    z_first_index: Option<usize>,
+    x: Option<usize>,
+    tuners: Vec<StepperRef>, // In string order; may outnumber the strings
+}
+
+impl Topology {
+    /// `channel_map[channel] = string`; None maps channel i to string i
+    pub fn new(string_num: usize, z_first_index: Option<usize>, x_step_index: Option<usize>, tuners: TunerLayout, channel_map: Option<&[usize]>) -> Self {
+        let tuners: Vec<StepperRef> = match tuners {
+            TunerLayout::None => Vec::new(),
+            TunerLayout::Main(indices) => indices.into_iter().map(|index| StepperRef { bank: Bank::Main, index }).collect(),
+            TunerLayout::Board(count) => (0..count).map(|index| StepperRef { bank: Bank::Tuner, index }).collect(),
+        };
+        let strings = (0..string_num)
+            .map(|string| StringSteppers {
+                string,
+                z_pair: z_first_index.map(|first| (first + string * 2, first + string * 2 + 1)),
+                tuner: tuners.get(string).copied(),
+                channel: match channel_map {
+                    Some(map) => map.iter().position(|&s| s == string),
+                    None => Some(string),
+                },
+            })
+            .collect();
+        Self { strings, z_first_index, x: x_step_index, tuners }
+    }
+
+    /// From the ARDUINO settings: tuners on the tuner board when ARD_T_PORT is set,
+    /// otherwise the carriage board's (see config_loader::mainboard_tuner_indices)
+    pub fn from_settings(settings: &crate::config_loader::ArduinoSettings, channel_map: Option<&[usize]>) -> Self {
+        let tuners = match (settings.ard_t_port.as_ref(), settings.ard_t_num_steppers) {
+            (Some(_), Some(count)) => TunerLayout::Board(count),
+            (Some(_), None) => TunerLayout::None,
+            (None, _) => TunerLayout::Main(crate::config_loader::mainboard_tuner_indices(settings)),
+        };
+        Self::new(settings.string_num, settings.z_first_index, settings.x_step_index, tuners, channel_map)
+    }
+
+    pub fn string_num(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn strings(&self) -> &[StringSteppers] {
+        &self.strings
+    }
+
+    pub fn string(&self, string: usize) -> Option<&StringSteppers> {
+        self.strings.get(string)
+    }
+
+    pub fn x_index(&self) -> Option<usize> {
+        self.x
+    }
+
+    pub fn has_z(&self) -> bool {
+        self.z_first_index.is_some() && !self.strings.is_empty()
+    }
+
+    pub fn z_first_index(&self) -> Option<usize> {
+        self.z_first_index
+    }
+
+    /// (inner, outer) Z steppers of a string
+    pub fn z_pair(&self, string: usize) -> Option<(usize, usize)> {
+        self.strings.get(string).and_then(|s| s.z_pair)
+    }
+
+    /// Every Z stepper, inner then outer, string by string
+    pub fn z_indices(&self) -> Vec<usize> {
+        self.strings.iter().filter_map(|s| s.z_pair).flat_map(|(z_in, z_out)| [z_in, z_out]).collect()
+    }
+
+    /// Touch sensor (GPIO Z_TOUCH_PINS position) of a Z stepper: sensors follow the Z order
+    pub fn sensor_for(&self, stepper: usize) -> Option<usize> {
+        let first = self.z_first_index?;
+        (stepper >= first && stepper < first + self.strings.len() * 2).then(|| stepper - first)
+    }
+
+    /// Z stepper watched by a touch sensor
+    pub fn stepper_for_sensor(&self, sensor: usize) -> Option<usize> {
+        let first = self.z_first_index?;
+        (sensor < self.strings.len() * 2).then_some(first + sensor)
+    }
+
+    /// Every tuner in string order
+    pub fn tuners(&self) -> &[StepperRef] {
+        &self.tuners
+    }
+
+    pub fn tuner(&self, tuner: usize) -> Option<StepperRef> {
+        self.tuners.get(tuner).copied()
+    }
+
+    /// Tuners driven through the carriage board (empty with a tuner board)
+    pub fn mainboard_tuner_indices(&self) -> Vec<usize> {
+        self.tuners.iter().filter(|t| t.bank == Bank::Main).map(|t| t.index).collect()
+    }
+
+    pub fn tuners_on_board(&self) -> bool {
+        self.tuners.iter().any(|t| t.bank == Bank::Tuner)
+    }
+
+    /// String driven by an audio channel's analysis
+    pub fn string_for_channel(&self, channel: usize) -> Option<usize> {
+        self.strings.iter().find(|s| s.channel == Some(channel)).map(|s| s.string)
+    }
+
+    pub fn channel_for_string(&self, string: usize) -> Option<usize> {
+        self.strings.get(string).and_then(|s| s.channel)
+    }
+
+    /// (inner, outer) Z steppers an audio channel moves
+    pub fn z_pair_for_channel(&self, channel: usize) -> Option<(usize, usize)> {
+        self.string_for_channel(channel).and_then(|string| self.z_pair(string))
+    }
+
+    /// What a stepper on `bank` moves; None for spare steppers
+    pub fn role_of(&self, bank: Bank, index: usize) -> Option<StepperRole> {
+        if let Some(string) = self.tuners.iter().position(|t| *t == StepperRef { bank, index }) {
+            return Some(StepperRole::Tuner { string });
+        }
+        if bank == Bank::Tuner {
+            return None;
+        }
+        if self.x == Some(index) {
+            return Some(StepperRole::X);
+        }
+        let offset = self.sensor_for(index)?;
+        let side = if offset % 2 == 0 { ZSide::In } else { ZSide::Out };
+        Some(StepperRole::Z { string: offset / 2, side })
+    }
+
+    /// Every carriage board stepper in use (X, Z and carriage board tuners), ascending
+    pub fn main_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self.x.into_iter().chain(self.z_indices()).chain(self.mainboard_tuner_indices()).collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mainboard_tuners_and_channel_map() {
+        // stringdriver-1: tuners 0-1, X 2, Z 3-6, channels swapped
+        let topology = Topology::new(2, Some(3), Some(2), TunerLayout::Main(vec![0, 1]), Some(&[1, 0]));
+        assert_eq!(topology.z_indices(), vec![3, 4, 5, 6]);
+        assert_eq!(topology.z_pair(1), Some((5, 6)));
+        assert_eq!(topology.z_pair_for_channel(0), Some((5, 6)));
+        assert_eq!(topology.channel_for_string(0), Some(1));
+        assert_eq!(topology.role_of(Bank::Main, 4), Some(StepperRole::Z { string: 0, side: ZSide::Out }));
+        assert_eq!(topology.role_of(Bank::Main, 1), Some(StepperRole::Tuner { string: 1 }));
+        assert_eq!(topology.role_of(Bank::Main, 2).map(StepperRole::axis), Some(Axis::X));
+        assert_eq!(topology.role_of(Bank::Main, 7), None);
+        assert_eq!((topology.sensor_for(5), topology.stepper_for_sensor(3), topology.sensor_for(2)), (Some(2), Some(6), None));
+        assert_eq!(topology.main_indices(), vec![0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_tuner_board_and_no_z() {
+        let topology = Topology::new(3, Some(1), Some(0), TunerLayout::Board(3), None);
+        assert_eq!(topology.string(2).and_then(|s| s.tuner), Some(StepperRef { bank: Bank::Tuner, index: 2 }));
+        assert_eq!(topology.role_of(Bank::Tuner, 0), Some(StepperRole::Tuner { string: 0 }));
+        // Index 0 on the carriage board is X, not the first tuner
+        assert_eq!(topology.role_of(Bank::Main, 0), Some(StepperRole::X));
+        assert!(topology.mainboard_tuner_indices().is_empty() && topology.tuners_on_board());
+
+        let no_z = Topology::new(2, None, None, TunerLayout::None, None);
+        assert!(!no_z.has_z() && no_z.z_indices().is_empty());
+        assert_eq!(no_z.z_pair_for_channel(0), None);
+        assert_eq!(no_z.string_for_channel(1), Some(1));
+    }
+}
@@ -0,0 +1,438 @@
+/// Board links - what the CmdMessenger bytes travel over
+///
+/// Boards are reached over a serialport device (USB/UART) by default. A board
+/// with a BLE UART (Nordic UART service) is selected per host with
+/// ARD_TRANSPORT / ARD_T_TRANSPORT and found by advertised name and/or MAC
+/// address. Both links hand back a `SerialPort`, so the serial threads and frame
+/// decoding are the same for either.
+///
+/// A BLE link drops far more often than a USB cable, so the BLE port reconnects
+/// by itself: once the board stops answering or the link reports a loss, the
+/// next write scans for the board again and re-subscribes, at most once per scan
+/// window while it stays away. Reads in between fail, which the serial threads
+/// already report as a port error.
+///
+/// The GATT side needs the `ble` feature (btleplug, BlueZ over D-Bus on Linux);
+/// without it a BLE transport fails to open with a message saying so.
+
+use crate::config_loader::TransportSettings;
+use serialport::SerialPort;
+use std::time::Duration;
+
+/// Nordic UART service; the board notifies on TX and is written on RX
+pub const NUS_SERVICE_UUID: uuid::Uuid = uuid::Uuid::from_u128(0x6e400001_b5a3_f393_e0a9_e50e24dcca9e);
+pub const NUS_RX_CHAR_UUID: uuid::Uuid = uuid::Uuid::from_u128(0x6e400002_b5a3_f393_e0a9_e50e24dcca9e);
+pub const NUS_TX_CHAR_UUID: uuid::Uuid = uuid::Uuid::from_u128(0x6e400003_b5a3_f393_e0a9_e50e24dcca9e);
+
+/// Which BLE peripheral is the board; unset fields match anything, at least one is set
+#[derive(Debug, Clone, PartialEq)]
+pub struct BleTarget {
+    pub name: Option<String>,
+    pub address: Option<String>, // MAC, compared case-insensitively
+    pub scan: Duration,          // How long discovery looks before giving up
+}
+
+impl BleTarget {
+    pub fn matches(&self, name: Option<&str>, address: &str) -> bool {
+        self.name.as_deref().is_none_or(|want| name == Some(want))
+            && self.address.as_deref().is_none_or(|want| want.eq_ignore_ascii_case(address))
+    }
+
+    pub fn describe(&self) -> String {
+        match (&self.name, &self.address) {
+            (Some(name), Some(address)) => format!("'{}' ({})", name, address),
+            (Some(name), None) => format!("'{}'", name),
+            (None, Some(address)) => address.clone(),
+            (None, None) => "any device".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Transport {
+    #[default]
+    Serial,
+    BleUart(BleTarget),
+}
+
+impl Transport {
+    /// The link ARD_TRANSPORT / ARD_T_TRANSPORT describes
+    pub fn from_settings(settings: &TransportSettings) -> Self {
+        match settings {
+            TransportSettings::Serial => Transport::Serial,
+            TransportSettings::Ble { name, address, scan } => {
+                Transport::BleUart(BleTarget { name: name.clone(), address: address.clone(), scan: *scan })
+            }
+        }
+    }
+
+    /// Opening a USB serial port resets an Arduino; a BLE link does not
+    pub fn resets_board(&self) -> bool {
+        matches!(self, Transport::Serial)
+    }
+
+    /// Open the link; `device_path` is only used by the serial transport
+    pub fn open(&self, device_path: &str, timeout: Duration) -> serialport::Result<Box<dyn SerialPort>> {
+        match self {
+            Transport::Serial => serialport::new(device_path, 115200).timeout(timeout).open(),
+            Transport::BleUart(target) => open_ble_uart(target, timeout),
+        }
+    }
+
+    pub fn describe(&self, device_path: &str) -> String {
+        match self {
+            Transport::Serial => format!("{} @115200", device_path),
+            Transport::BleUart(target) => format!("BLE UART {}", target.describe()),
+        }
+    }
+}
+
+#[cfg(feature = "ble")]
+fn open_ble_uart(target: &BleTarget, timeout: Duration) -> serialport::Result<Box<dyn SerialPort>> {
+    Ok(Box::new(ble::BleUartPort::open(target.clone(), timeout)?))
+}
+
+#[cfg(not(feature = "ble"))]
+fn open_ble_uart(target: &BleTarget, _timeout: Duration) -> serialport::Result<Box<dyn SerialPort>> {
+    Err(serialport::Error::new(
+        serialport::ErrorKind::NoDevice,
+        format!("cannot reach BLE UART {}: built without the ble feature - rebuild with --features ble or use ARD_TRANSPORT: serial", target.describe()),
+    ))
+}
+
+#[cfg(feature = "ble")]
+mod ble {
+    use super::{BleTarget, NUS_RX_CHAR_UUID, NUS_TX_CHAR_UUID};
+    use btleplug::api::{Central, CharPropFlags, Characteristic, Manager as _, Peripheral as _, ScanFilter, WriteType};
+    use btleplug::platform::{Manager, Peripheral};
+    use futures::StreamExt;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::io::{self, Read, Write};
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+
+    /// Bytes per GATT write; 20 fits the default ATT MTU every board accepts
+    const WRITE_CHUNK: usize = 20;
+
+    /// How often discovery looks through the peripherals found so far
+    const SCAN_POLL: Duration = Duration::from_millis(200);
+
+    /// A connected board: its RX characteristic and the TX notifications
+    struct Link {
+        peripheral: Peripheral,
+        rx: Characteristic,
+        write_type: WriteType,
+        notifications: mpsc::Receiver<Vec<u8>>, // Disconnected once the board drops the link
+    }
+
+    /// The Nordic UART service of one board as a serial port
+    pub struct BleUartPort {
+        target: BleTarget,
+        runtime: tokio::runtime::Runtime, // Runs btleplug and the notification forwarder
+        link: Option<Link>,
+        incoming: RefCell<VecDeque<u8>>, // Received, not read yet; clear() takes &self
+        timeout: Duration,
+        next_attempt: Instant, // No new scan before this while the board stays away
+    }
+
+    fn ble_error(context: &str, e: impl std::fmt::Display) -> io::Error {
+        io::Error::new(io::ErrorKind::NotConnected, format!("{}: {}", context, e))
+    }
+
+    impl BleUartPort {
+        /// Find the board and subscribe to its TX characteristic
+        pub fn open(target: BleTarget, timeout: Duration) -> serialport::Result<Self> {
+            let runtime = tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(1)
+                .enable_all()
+                .build()
+                .map_err(|e| serialport::Error::new(serialport::ErrorKind::Io(e.kind()), format!("BLE runtime: {}", e)))?;
+            let mut port = Self { target, runtime, link: None, incoming: RefCell::default(), timeout, next_attempt: Instant::now() };
+            port.reconnect().map_err(|e| serialport::Error::new(serialport::ErrorKind::NoDevice, e.to_string()))?;
+            Ok(port)
+        }
+
+        /// Scan for the board, connect and subscribe; replaces any previous link
+        fn reconnect(&mut self) -> io::Result<()> {
+            self.drop_link();
+            self.next_attempt = Instant::now() + self.target.scan;
+            let peripheral = self.discover()?;
+            let link = self.runtime.block_on(async {
+                if !peripheral.is_connected().await.unwrap_or(false) {
+                    peripheral.connect().await.map_err(|e| ble_error("connect", e))?;
+                }
+                peripheral.discover_services().await.map_err(|e| ble_error("service discovery", e))?;
+                let characteristics = peripheral.characteristics();
+                let find = |uuid| characteristics.iter().find(|c| c.uuid == uuid).cloned();
+                let (Some(rx), Some(tx)) = (find(NUS_RX_CHAR_UUID), find(NUS_TX_CHAR_UUID)) else {
+                    return Err(io::Error::new(io::ErrorKind::NotFound, "no Nordic UART service (RX/TX characteristics)"));
+                };
+                let write_type = if rx.properties.contains(CharPropFlags::WRITE_WITHOUT_RESPONSE) {
+                    WriteType::WithoutResponse
+                } else {
+                    WriteType::WithResponse
+                };
+                peripheral.subscribe(&tx).await.map_err(|e| ble_error("subscribe", e))?;
+                let mut stream = peripheral.notifications().await.map_err(|e| ble_error("notifications", e))?;
+                let (sender, notifications) = mpsc::channel();
+                tokio::spawn(async move {
+                    while let Some(notification) = stream.next().await {
+                        if notification.uuid == NUS_TX_CHAR_UUID && sender.send(notification.value).is_err() {
+                            break;
+                        }
+                    }
+                });
+                Ok(Link { peripheral: peripheral.clone(), rx, write_type, notifications })
+            })?;
+            tracing::info!("BLE UART {} connected ({})", self.target.describe(), link.peripheral.address());
+            self.link = Some(link);
+            Ok(())
+        }
+
+        /// The first advertising peripheral matching the target within its scan window
+        fn discover(&self) -> io::Result<Peripheral> {
+            let adapter = self.runtime.block_on(async {
+                let manager = Manager::new().await.map_err(|e| ble_error("Bluetooth", e))?;
+                let adapter = manager.adapters().await.map_err(|e| ble_error("Bluetooth adapters", e))?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no Bluetooth adapter"))?;
+                adapter.start_scan(ScanFilter::default()).await.map_err(|e| ble_error("scan", e))?;
+                Ok::<_, io::Error>(adapter)
+            })?;
+            let started = Instant::now();
+            let found = loop {
+                let peripherals = self.runtime.block_on(adapter.peripherals()).map_err(|e| ble_error("scan", e))?;
+                let matching = peripherals.into_iter().find(|p| {
+                    let name = self.runtime.block_on(p.properties()).ok().flatten().and_then(|props| props.local_name);
+                    self.target.matches(name.as_deref(), &p.address().to_string())
+                });
+                if matching.is_some() || started.elapsed() >= self.target.scan {
+                    break matching;
+                }
+                std::thread::sleep(SCAN_POLL);
+            };
+            let _ = self.runtime.block_on(adapter.stop_scan());
+            found.ok_or_else(|| io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("BLE UART {} not found within {:.1}s", self.target.describe(), self.target.scan.as_secs_f32()),
+            ))
+        }
+
+        fn drop_link(&mut self) {
+            if let Some(link) = self.link.take() {
+                let _ = self.runtime.block_on(link.peripheral.disconnect());
+            }
+            self.incoming.get_mut().clear();
+        }
+
+        /// The current link, reconnecting first when it was lost (once per scan window)
+        fn link(&mut self) -> io::Result<&Link> {
+            if self.link.is_none() {
+                if Instant::now() < self.next_attempt {
+                    return Err(io::Error::new(io::ErrorKind::NotConnected, format!("BLE UART {} not connected", self.target.describe())));
+                }
+                self.reconnect()?;
+            }
+            self.link.as_ref().ok_or_else(|| io::Error::new(io::ErrorKind::NotConnected, "BLE UART not connected"))
+        }
+
+        /// Move notifications already received into the read buffer
+        fn drain_notifications(&mut self) {
+            if let Some(link) = &self.link {
+                self.incoming.get_mut().extend(link.notifications.try_iter().flatten());
+            }
+        }
+
+        /// Forget a link the board has dropped, so the next write reconnects
+        fn link_lost(&mut self, reason: &str) -> io::Error {
+            tracing::warn!("BLE UART {} lost: {}", self.target.describe(), reason);
+            self.drop_link();
+            io::Error::new(io::ErrorKind::BrokenPipe, format!("BLE UART {} lost: {}", self.target.describe(), reason))
+        }
+    }
+
+    impl Drop for BleUartPort {
+        fn drop(&mut self) {
+            self.drop_link();
+        }
+    }
+
+    impl Read for BleUartPort {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.incoming.get_mut().is_empty() {
+                let timeout = self.timeout;
+                let received = self.link()?.notifications.recv_timeout(timeout);
+                match received {
+                    Ok(bytes) => self.incoming.get_mut().extend(bytes),
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return Err(self.link_lost("notifications ended")),
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        let connected = self.link.as_ref().map(|link| self.runtime.block_on(link.peripheral.is_connected()).unwrap_or(false));
+                        if connected == Some(false) {
+                            return Err(self.link_lost("disconnected"));
+                        }
+                        return Err(io::Error::new(io::ErrorKind::TimedOut, "BLE UART read timed out"));
+                    }
+                }
+                self.drain_notifications();
+            }
+            let incoming = self.incoming.get_mut();
+            let count = buf.len().min(incoming.len());
+            for (slot, byte) in buf.iter_mut().zip(incoming.drain(..count)) {
+                *slot = byte;
+            }
+            Ok(count)
+        }
+    }
+
+    impl Write for BleUartPort {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let link = self.link()?;
+            let (peripheral, rx, write_type) = (link.peripheral.clone(), link.rx.clone(), link.write_type);
+            for chunk in buf.chunks(WRITE_CHUNK) {
+                if let Err(e) = self.runtime.block_on(peripheral.write(&rx, chunk, write_type)) {
+                    return Err(self.link_lost(&e.to_string()));
+                }
+            }
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Line settings mean nothing over GATT: reported as a plain 115200 8N1 port, changes ignored
+    impl serialport::SerialPort for BleUartPort {
+        fn name(&self) -> Option<String> {
+            Some(format!("BLE UART {}", self.target.describe()))
+        }
+
+        fn baud_rate(&self) -> serialport::Result<u32> {
+            Ok(115200)
+        }
+
+        fn data_bits(&self) -> serialport::Result<serialport::DataBits> {
+            Ok(serialport::DataBits::Eight)
+        }
+
+        fn flow_control(&self) -> serialport::Result<serialport::FlowControl> {
+            Ok(serialport::FlowControl::None)
+        }
+
+        fn parity(&self) -> serialport::Result<serialport::Parity> {
+            Ok(serialport::Parity::None)
+        }
+
+        fn stop_bits(&self) -> serialport::Result<serialport::StopBits> {
+            Ok(serialport::StopBits::One)
+        }
+
+        fn timeout(&self) -> Duration {
+            self.timeout
+        }
+
+        fn set_baud_rate(&mut self, _baud_rate: u32) -> serialport::Result<()> {
+            Ok(())
+        }
+
+        fn set_data_bits(&mut self, _data_bits: serialport::DataBits) -> serialport::Result<()> {
+            Ok(())
+        }
+
+        fn set_flow_control(&mut self, _flow_control: serialport::FlowControl) -> serialport::Result<()> {
+            Ok(())
+        }
+
+        fn set_parity(&mut self, _parity: serialport::Parity) -> serialport::Result<()> {
+            Ok(())
+        }
+
+        fn set_stop_bits(&mut self, _stop_bits: serialport::StopBits) -> serialport::Result<()> {
+            Ok(())
+        }
+
+        fn set_timeout(&mut self, timeout: Duration) -> serialport::Result<()> {
+            self.timeout = timeout;
+            Ok(())
+        }
+
+        fn write_request_to_send(&mut self, _level: bool) -> serialport::Result<()> {
+            Ok(())
+        }
+
+        fn write_data_terminal_ready(&mut self, _level: bool) -> serialport::Result<()> {
+            Ok(())
+        }
+
+        fn read_clear_to_send(&mut self) -> serialport::Result<bool> {
+            Ok(true)
+        }
+
+        fn read_data_set_ready(&mut self) -> serialport::Result<bool> {
+            Ok(self.link.is_some())
+        }
+
+        fn read_ring_indicator(&mut self) -> serialport::Result<bool> {
+            Ok(false)
+        }
+
+        fn read_carrier_detect(&mut self) -> serialport::Result<bool> {
+            Ok(self.link.is_some())
+        }
+
+        fn bytes_to_read(&self) -> serialport::Result<u32> {
+            Ok(self.incoming.borrow().len() as u32)
+        }
+
+        fn bytes_to_write(&self) -> serialport::Result<u32> {
+            Ok(0) // Writes complete before write() returns
+        }
+
+        /// Drops received bytes, including notifications not read yet
+        fn clear(&self, buffer_to_clear: serialport::ClearBuffer) -> serialport::Result<()> {
+            if !matches!(buffer_to_clear, serialport::ClearBuffer::Output) {
+                self.incoming.borrow_mut().clear();
+                if let Some(link) = &self.link {
+                    while link.notifications.try_recv().is_ok() {}
+                }
+            }
+            Ok(())
+        }
+
+        fn try_clone(&self) -> serialport::Result<Box<dyn serialport::SerialPort>> {
+            Err(serialport::Error::new(serialport::ErrorKind::Unknown, "a BLE UART port cannot be cloned"))
+        }
+
+        fn set_break(&self) -> serialport::Result<()> {
+            Ok(())
+        }
+
+        fn clear_break(&self) -> serialport::Result<()> {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ble_target_matching() {
+        let target = BleTarget {
+            name: Some("StringDriver".to_string()),
+            address: Some("AA:BB:CC:DD:EE:01".to_string()),
+            scan: Duration::from_secs(5),
+        };
+        assert!(target.matches(Some("StringDriver"), "aa:bb:cc:dd:ee:01"));
+        assert!(!target.matches(Some("StringDriver"), "AA:BB:CC:DD:EE:02"));
+        assert!(!target.matches(None, "AA:BB:CC:DD:EE:01"));
+
+        let by_name = BleTarget { address: None, ..target };
+        assert!(by_name.matches(Some("StringDriver"), "11:22:33:44:55:66"));
+        assert!(!Transport::BleUart(by_name).resets_board());
+        assert!(Transport::Serial.resets_board());
+    }
+}
@@ -0,0 +1,149 @@
+/// Z controller module - closed-loop Z height regulation
+///
+/// Treats a per-channel audio metric (amp_sum or voice_count) as the process
+/// variable and Z position as the actuator. Each string gets its own PID loop;
+/// gains come from the Z_CONTROLLER block in string_driver.yaml via
+/// config_loader::load_z_controller_settings().
+
+use crate::config_loader::{PidGainsSettings, ZControllerSettings};
+
+/// Process variable the controller regulates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControllerMetric {
+    AmpSum,
+    VoiceCount,
+}
+
+impl ControllerMetric {
+    fn from_name(name: &str) -> Self {
+        match name {
+            "voice_count" => ControllerMetric::VoiceCount,
+            _ => ControllerMetric::AmpSum,
+        }
+    }
+}
+
+/// Single PID loop with integral clamping and conditional integration (anti-windup)
+#[derive(Debug, Clone)]
+pub struct PidController {
+    pub gains: PidGainsSettings,
+    integral_limit: f32,
+    output_limit: i32,
+    integral: f32,
+    prev_error: Option<f32>,
+}
+
+impl PidController {
+    pub fn new(gains: PidGainsSettings, integral_limit: f32, output_limit: i32) -> Self {
+        Self {
+            gains,
+            integral_limit: integral_limit.abs(),
+            output_limit: output_limit.abs(),
+            integral: 0.0,
+            prev_error: None,
+        }
+    }
+
+    /// Clear accumulated integral and derivative history
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.prev_error = None;
+    }
+
+    /// Compute the next Z move in steps.
+    /// Positive output moves the stepper away from the string (metric too high),
+    /// negative output moves it toward the string (metric too low).
+    pub fn update(&mut self, setpoint: f32, measured: f32, dt: f32) -> i32 {
+        let dt = if dt > 0.0 { dt } else { 1.0 };
+        // Error sign chosen so a metric above setpoint yields a positive (upward) move
+        let error = measured - setpoint;
+        let derivative = self.prev_error.map(|prev| (error - prev) / dt).unwrap_or(0.0);
+        self.prev_error = Some(error);
+
+        self.integral = (self.integral + error * dt).clamp(-self.integral_limit, self.integral_limit);
+        let limit = self.output_limit as f32;
+        let p_d = self.gains.kp * error + self.gains.kd * derivative;
+        let raw = p_d + self.gains.ki * self.integral;
+
+        // Back-calculate the integral when saturated so it never holds more than the limit needs
+        if self.gains.ki != 0.0 && raw.abs() > limit {
+            let target = limit.copysign(raw);
+            self.integral = ((target - p_d) / self.gains.ki).clamp(-self.integral_limit, self.integral_limit);
+        }
+
+        let output = self.gains.kp * error + self.gains.ki * self.integral + self.gains.kd * derivative;
+        output.clamp(-limit, limit).round() as i32
+    }
+}
+
+/// Bank of per-string PID loops
+#[derive(Debug, Clone)]
+pub struct ZController {
+    pub metric: ControllerMetric,
+    pub period: f32,
+    settings: ZControllerSettings,
+    loops: Vec<PidController>,
+}
+
+impl ZController {
+    pub fn new(settings: &ZControllerSettings) -> Self {
+        Self {
+            metric: ControllerMetric::from_name(&settings.metric),
+            period: settings.period.max(0.0),
+            settings: settings.clone(),
+            loops: Vec::new(),
+        }
+    }
+
+    /// Gains for a channel: per-string override, falling back to the host default
+    pub fn gains_for(&self, ch_idx: usize) -> PidGainsSettings {
+        self.settings.string_gains.get(ch_idx)
+            .copied()
+            .flatten()
+            .unwrap_or(self.settings.default_gains)
+    }
+
+    fn ensure_channels(&mut self, num_channels: usize) {
+        while self.loops.len() < num_channels {
+            let gains = self.gains_for(self.loops.len());
+            self.loops.push(PidController::new(gains, self.settings.integral_limit, self.settings.output_limit));
+        }
+    }
+
+    /// Step the loop for one channel and return the Z move in steps
+    pub fn update(&mut self, ch_idx: usize, setpoint: f32, measured: f32, dt: f32) -> i32 {
+        self.ensure_channels(ch_idx + 1);
+        self.loops[ch_idx].update(setpoint, measured, dt)
+    }
+
+    /// Reset one channel (e.g. after a bump or a manual move)
+    pub fn reset_channel(&mut self, ch_idx: usize) {
+        if let Some(pid) = self.loops.get_mut(ch_idx) {
+            pid.reset();
+        }
+    }
+
+    pub fn reset(&mut self) {
+        for pid in &mut self.loops {
+            pid.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pid_anti_windup() {
+        let gains = PidGainsSettings { kp: 0.0, ki: 1.0, kd: 0.0 };
+        let mut pid = PidController::new(gains, 1000.0, 5);
+        // Sustained large error saturates the output; integral must not keep growing
+        for _ in 0..100 {
+            assert_eq!(pid.update(0.0, 50.0, 1.0), 5);
+        }
+        // Error reverses: output should leave saturation promptly
+        let out = pid.update(0.0, -50.0, 1.0);
+        assert!(out < 5, "output stayed saturated after error reversal: {}", out);
+    }
+}
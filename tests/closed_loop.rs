@@ -0,0 +1,461 @@
+/// Closed-loop tests: Operations against the loopback board and the simulated audio response
+///
+/// The fixture host sim-bench (tests/fixtures/closed-loop.yaml) has two strings on
+/// the loopback board; audio_sim turns the board's Z and X positions into partials
+/// frames and Operations analyses them as it would audio_monitor's. Each test puts
+/// the strings out of range (for x_sweep, by moving the carriage) and asserts that
+/// z_adjust, the Z_CONTROLLER loops (z_hold) or x_sweep bring them back in and
+/// keep them there. The operation descriptors are checked against the bench's
+/// hardware as well, a custom operation (see plugins) runs on it, a score's
+/// transport drives it and a saved scene is recalled on it.
+///
+/// With `--features fault-injection` the same loops run with faults injected
+/// (dropped serial frames, corrupted partials frames, transient stepper errors)
+/// to check that they recover rather than wedge or panic.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+#[cfg(feature = "fault-injection")]
+use stringdriver::fault_injection::{self, Fault, FaultInjector, FaultSettings, Faulty};
+use stringdriver::audio_sim::{AudioSim, AudioSimSettings};
+use stringdriver::config_loader::{ParameterPreset, CONFIG_ENV};
+use stringdriver::gpio::GpioBoard;
+use stringdriver::logging::{PhaseBuffer, PhaseLayer, PhaseLog};
+use stringdriver::loopback_serial::{FirmwareProtocol, LoopbackBoard};
+use stringdriver::operations::{Operations, Requirement, SweepDirection, SweepParams};
+use stringdriver::partials_buffer::{triple_buffer, PartialsWriter};
+use stringdriver::score::{Score, ScoreAction, Transport, TransportState};
+use stringdriver::timeline::{PhaseKind, Timeline};
+use tracing_subscriber::layer::SubscriberExt;
+use stringdriver::plugins::{self, Operation, OperationContext, OperationDescriptor, OperationParam, PluginResult, Report};
+
+const HOST: &str = "sim-bench";
+const MIN_AMP: [f32; 2] = [60.0, 60.0];
+const MAX_AMP: [f32; 2] = [100.0, 100.0];
+const MIN_VOICES: [usize; 2] = [1, 1];
+const MAX_VOICES: [usize; 2] = [12, 12];
+
+/// Run `f` with STRING_DRIVER_CONFIG pointing at the closed-loop fixture.
+/// The variable is process-wide, so tests take turns.
+fn with_fixture<T>(f: impl FnOnce() -> T) -> T {
+    static FIXTURE_LOCK: Mutex<()> = Mutex::new(());
+    let _guard = FIXTURE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/closed-loop.yaml");
+    std::env::set_var(CONFIG_ENV, &path);
+    let result = f();
+    std::env::remove_var(CONFIG_ENV);
+    result
+}
+
+/// The sandbox: X on stepper 0, string 0 on Z 1/2 touching at -20, string 1 on Z 3/4 at -30
+struct Sandbox {
+    ops: Arc<Operations>,
+    board: LoopbackBoard,
+    sim: AudioSim,
+    writer: PartialsWriter,
+    max_positions: HashMap<usize, i32>,
+}
+
+impl Sandbox {
+    fn new(positions: &[i32]) -> Self {
+        let (writer, reader) = triple_buffer();
+        let mut ops = Operations::for_host(HOST, Some(reader)).unwrap();
+        // No touch sensors on the bench: bump_check reports "no GPIO" and passes
+        ops.gpio = Some(GpioBoard::disabled());
+        let protocol = FirmwareProtocol {
+            positions_id: 5, encoders_id: None, amove_id: 0, rmove_id: 1, set_stepper_id: 2,
+            enable_id: None, value_bytes: 2, checksum: false, version: None,
+        };
+        let board = LoopbackBoard::new(protocol, 5);
+        board.set_positions(positions);
+        let sim = AudioSim::new(&ops.topology, &[-20, -30], AudioSimSettings { x_max: ops.x_max_pos, ..AudioSimSettings::default() });
+        let max_positions = ops.get_z_stepper_indices().into_iter().map(|idx| (idx, 100)).collect();
+        Self { ops: Arc::new(ops), board, sim, writer, max_positions }
+    }
+
+    /// One audio frame for the board as it stands, analysed as the GUI's refresh does
+    fn listen(&mut self) -> Vec<f32> {
+        self.sim.publish(&mut self.writer, &self.board.positions());
+        self.ops.update_audio_analysis();
+        self.ops.get_amp_sum()
+    }
+
+    /// Keep listening on another thread while `f` runs an operation
+    fn with_audio<T>(&mut self, f: impl FnOnce(&Operations, &mut LoopbackBoard, &HashMap<usize, i32>) -> T) -> T {
+        let done = AtomicBool::new(false);
+        let Sandbox { ops, board, sim, writer, max_positions } = self;
+        let listener_board = board.clone();
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    sim.publish(writer, &listener_board.positions());
+                    ops.update_audio_analysis();
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+            });
+            let result = f(ops, board, max_positions);
+            done.store(true, Ordering::Relaxed);
+            result
+        })
+    }
+}
+
+fn in_band(amp_sums: &[f32]) -> bool {
+    amp_sums.len() == 2 && amp_sums.iter().zip(MIN_AMP.iter().zip(&MAX_AMP)).all(|(amp, (min, max))| amp >= min && amp <= max)
+}
+
+#[test]
+fn test_z_adjust_converges_from_either_side() {
+    with_fixture(|| {
+        // Clear of both strings (silent), then pressed far too hard (too loud)
+        for start in [0, -130] {
+            let mut sandbox = Sandbox::new(&[500, start, start, start, start]);
+            let mut passes = 0;
+            loop {
+                let amp_sums = sandbox.listen();
+                let mut positions = sandbox.board.positions();
+                let message = sandbox.ops.z_adjust(&mut sandbox.board, &mut positions, &sandbox.max_positions, &MIN_AMP, &MAX_AMP, &MIN_VOICES, &MAX_VOICES, None).unwrap();
+                if !message.contains("moved") {
+                    assert!(in_band(&amp_sums), "start {}: {:?}", start, amp_sums);
+                    break;
+                }
+                passes += 1;
+                assert!(passes < 60, "start {}: no convergence after {} passes, last {:?}\n{}", start, passes, amp_sums, message);
+            }
+
+            // Settled: noise near a band edge may nudge Z a step, but it does not wander off
+            let settled = sandbox.board.positions();
+            for _ in 0..20 {
+                sandbox.listen();
+                let mut positions = sandbox.board.positions();
+                sandbox.ops.z_adjust(&mut sandbox.board, &mut positions, &sandbox.max_positions, &MIN_AMP, &MAX_AMP, &MIN_VOICES, &MAX_VOICES, None).unwrap();
+            }
+            let drift = sandbox.board.positions().iter().zip(&settled).map(|(now, then)| (now - then).abs()).max();
+            assert!(drift <= Some(4), "start {}: settled at {:?}, now {:?}", start, settled, sandbox.board.positions());
+        }
+    });
+}
+
+#[test]
+fn test_z_hold_settles_on_the_setpoint() {
+    with_fixture(|| {
+        let mut sandbox = Sandbox::new(&[500, -10, -10, -120, -120]);
+        let setpoints = [80.0, 80.0];
+        let exit = Arc::new(AtomicBool::new(false));
+        let mut held_for = 0;
+        let started = Instant::now();
+        std::thread::scope(|scope| {
+            let ops = Arc::clone(&sandbox.ops);
+            let mut board = sandbox.board.clone();
+            let max_positions = sandbox.max_positions.clone();
+            let exit_flag = Arc::clone(&exit);
+            let hold = scope.spawn(move || {
+                let mut positions = board.positions();
+                ops.z_hold(&mut board, &mut positions, &max_positions, &setpoints, Some(&exit_flag), None)
+            });
+            // Within 15 of the setpoint (noise is ±5%) for 100 frames in a row
+            while held_for < 100 && started.elapsed() < Duration::from_secs(20) {
+                let amp_sums = sandbox.listen();
+                let close = amp_sums.len() == 2 && amp_sums.iter().zip(&setpoints).all(|(amp, target)| (amp - target).abs() < 15.0);
+                held_for = if close { held_for + 1 } else { 0 };
+                std::thread::sleep(Duration::from_millis(2));
+            }
+            exit.store(true, Ordering::Relaxed);
+            assert!(hold.join().unwrap().unwrap().contains("z_hold stopped"));
+        });
+        assert_eq!(held_for, 100, "z_hold never settled: {:?} at {:?}", sandbox.ops.get_amp_sum(), sandbox.board.positions());
+    });
+}
+
+#[test]
+fn test_sweep_keeps_strings_in_range_along_x() {
+    with_fixture(|| {
+        // In range at X=100; the strings get louder towards mid-travel and Z has to follow
+        let mut sandbox = Sandbox::new(&[100, -80, -80, -90, -90]);
+        let params = SweepParams {
+            min_thresholds: &MIN_AMP,
+            max_thresholds: &MAX_AMP,
+            min_voices: &MIN_VOICES,
+            max_voices: &MAX_VOICES,
+            laps: 1,
+            converge_below: 0,
+        };
+        // Record the spans as the GUI does, for the operation timeline
+        let phases: PhaseBuffer = Arc::new(Mutex::new(PhaseLog::default()));
+        let subscriber = tracing_subscriber::registry().with(PhaseLayer::new(Arc::clone(&phases)));
+        let result = tracing::subscriber::with_default(subscriber, || {
+            let _operation = tracing::info_span!("operation", name = "right_left_move").entered();
+            sandbox.with_audio(|ops, board, max_positions| {
+                let mut positions = board.positions();
+                ops.x_sweep(board, &mut positions, max_positions, SweepDirection::RightLeft, &params, None, None)
+            })
+        });
+        let message = result.unwrap();
+        let report = sandbox.ops.get_last_sweep().unwrap();
+        let lap = &report.laps[0];
+        assert_eq!((lap.from, lap.to), (100, 900));
+        // 100, 300, 500 and 700 each met the adjustment level; the step to 900 ends the lap
+        assert_eq!(lap.positions, 4, "{}", message);
+        assert_eq!(lap.calibrations, 0);
+        assert!(lap.z_moves > 0 && !lap.cancelled);
+        assert_eq!(sandbox.board.positions()[0], 900);
+        let evaluation = sandbox.ops.get_last_evaluation().unwrap();
+        assert_eq!(evaluation.x, 700);
+        assert!(evaluation.passed());
+
+        // One dwell per X position, inside the lap, inside the sweep
+        let timeline = Timeline::from_log(&phases.lock().unwrap(), Instant::now()).unwrap();
+        assert_eq!((timeline.operation.as_str(), timeline.running), ("right_left_move", false));
+        let dwells: Vec<&str> = timeline.phases.iter().filter(|p| p.kind == PhaseKind::Dwell).map(|p| p.detail.as_str()).collect();
+        assert_eq!(dwells, ["x=100", "x=300", "x=500", "x=700"]);
+        assert!(timeline.phases.iter().all(|p| p.name != "dwell" || p.lane == 2));
+        assert_eq!(timeline.totals().iter().find(|t| t.name == "lap").map(|t| t.count), Some(1));
+    });
+}
+
+#[test]
+fn test_operation_descriptors_match_the_bench() {
+    with_fixture(|| {
+        let sandbox = Sandbox::new(&[500, 0, 0, 0, 0]);
+        // Built-ins only: custom operations registered by other tests sit before park
+        let catalog: Vec<_> = sandbox.ops.operation_descriptors().into_iter().filter(|op| plugins::find(&op.name).is_none()).collect();
+        let names: Vec<&str> = catalog.iter().map(|op| op.name.as_ref()).collect();
+        assert_eq!(&names[names.len() - 4..], ["x_home", "x_away", "x_calibrate", "park"]);
+        assert!(names.contains(&"z_adjust") && names.contains(&"ping_pong_move"));
+
+        // The bench has an X axis but no GPIO
+        let z_calibrate = sandbox.ops.describe_operation("z_calibrate").unwrap();
+        assert_eq!(sandbox.ops.missing_hardware(&z_calibrate), [Requirement::Gpio]);
+        let sweep = sandbox.ops.describe_operation("right_left_move").unwrap();
+        assert!(sandbox.ops.missing_hardware(&sweep).is_empty());
+        assert!(sandbox.ops.describe_operation("y_home").is_none());
+
+        // Every parameter is a PRESETS key, so the GUI form can apply it as a preset
+        for op in &catalog {
+            for param in op.params {
+                assert!(param.min <= param.max && param.default.is_none_or(|d| (param.min..=param.max).contains(&d)), "{} {}", op.name, param.key);
+                let value = if param.integer { serde_json::json!(param.min as i64) } else { serde_json::json!(param.min) };
+                let preset: Result<ParameterPreset, _> = serde_json::from_value(serde_json::json!({ param.key: value }));
+                assert!(preset.is_ok_and(|p| p != ParameterPreset::default()), "{} {}", op.name, param.key);
+            }
+        }
+    });
+}
+
+/// Lower one string's Z pair until it sounds, as a prototype algorithm would
+struct Approach;
+
+const APPROACH_PARAMS: &[OperationParam] = &[OperationParam::new("APPROACH_STEP", "Z steps per move towards the string", 1.0, 20.0, 5.0, true)];
+
+impl Operation for Approach {
+    fn descriptor(&self) -> OperationDescriptor {
+        OperationDescriptor::new("approach_string_0", "Approach", "Lower string 0 until it sounds", &[], APPROACH_PARAMS)
+    }
+
+    fn run(&self, ctx: &mut OperationContext<'_>) -> PluginResult<Report> {
+        let (left, right) = ctx.z_pair_for_channel(0).ok_or("no Z pair for channel 0")?;
+        let step = ctx.param("APPROACH_STEP").unwrap_or(1.0) as i32;
+        let mut report = Report::default();
+        while !ctx.stop_requested() && ctx.amp_sum().first().is_none_or(|amp| *amp < MIN_AMP[0]) {
+            if ctx.positions()[left] < -100 {
+                return Err("string 0 never sounded".to_string());
+            }
+            ctx.rel_move(left, -step)?;
+            ctx.rel_move(right, -step)?;
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        report.line(format!("string 0 sounds at Z {}", ctx.positions()[left]));
+        Ok(report)
+    }
+}
+
+#[test]
+fn test_custom_operation_runs_on_the_bench() {
+    with_fixture(|| {
+        plugins::register(Arc::new(Approach)).unwrap();
+        let mut sandbox = Sandbox::new(&[500, 0, 0, 0, 0]);
+        let names: Vec<String> = sandbox.ops.operation_descriptors().iter().map(|op| op.name.to_string()).collect();
+        assert_eq!(&names[names.len() - 2..], ["approach_string_0", "park"]);
+
+        let exit_flag = Arc::new(AtomicBool::new(false));
+        let approach = plugins::find("approach_string_0").unwrap();
+        let message = sandbox.with_audio(|ops, board, max_positions| {
+            let mut positions = board.positions();
+            let message = ops.run_plugin(&*approach, board, &mut positions, max_positions, Some(&exit_flag)).unwrap();
+            assert_eq!(positions, board.positions());
+            message
+        });
+        let z = sandbox.board.positions()[1];
+        assert!((-100..-20).contains(&z), "{}", message); // Touching at -20
+        assert_eq!(message, format!("string 0 sounds at Z {}", z));
+
+        // Moves keep to the steppers' max positions
+        struct Lift;
+        impl Operation for Lift {
+            fn descriptor(&self) -> OperationDescriptor {
+                OperationDescriptor::new("lift_past_max", "Lift", "", &[], &[])
+            }
+            fn run(&self, ctx: &mut OperationContext<'_>) -> PluginResult<Report> {
+                ctx.abs_move(1, 500).map(|_| Report::default())
+            }
+        }
+        let mut positions = sandbox.board.positions();
+        let result = sandbox.ops.run_plugin(&Lift, &mut sandbox.board, &mut positions, &sandbox.max_positions, None);
+        assert!(result.is_err_and(|e| e.to_string().contains("max 100")));
+        assert_eq!(sandbox.board.positions()[1], z);
+    });
+}
+
+#[test]
+fn test_score_cues_move_the_bench() {
+    with_fixture(|| {
+        let mut sandbox = Sandbox::new(&[500, 0, 0, 40, 40]);
+        let score = Score::parse(r#"
+EVENTS:
+  - { AT: 0, X: 300 }
+  - { AT: 0, Z: { STRING: 1, POSITION: -25 } }
+  - { AT: 0.05, X: 400, OVER: 0.1 }
+  - { AT: 0.2, TUNE: { STRING: 0, STEPS: 5 } }
+"#).unwrap();
+        let start = Instant::now();
+        let mut transport = Transport::new(score);
+        transport.play(start);
+        let mut positions = sandbox.board.positions();
+        let mut tick = 0;
+        let mut errors = Vec::new();
+        let mut x_path = Vec::new();
+        while transport.state() == TransportState::Playing {
+            for cue in transport.poll(start + Duration::from_millis(tick * 5)) {
+                let result = match cue {
+                    ScoreAction::MoveX(x) => {
+                        let result = sandbox.ops.move_x_to(&mut sandbox.board, &mut positions, x).map(|_| ());
+                        x_path.push(sandbox.board.positions()[0]);
+                        result
+                    }
+                    ScoreAction::MoveZ(z) => sandbox.ops.move_string_z(&mut sandbox.board, &mut positions, &sandbox.max_positions, z.string, z.position),
+                    ScoreAction::Tune(t) => sandbox.ops.tune_string(&mut sandbox.board, &mut positions, t.string, t.steps),
+                    other => panic!("unexpected cue {:?}", other),
+                };
+                errors.extend(result.err());
+            }
+            tick += 1;
+        }
+
+        // The Z cue went down, but no lower than Z_MIN_POS
+        assert_eq!(sandbox.board.positions(), [400, 0, 0, 0, 0]);
+        assert_eq!(positions, sandbox.board.positions());
+        // The glide went out in steps, not as one jump
+        assert!(x_path.len() > 5 && x_path.windows(2).skip(1).all(|w| w[0] < w[1]), "{:?}", x_path);
+        // The bench has no tuners
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+    });
+}
+
+#[test]
+fn test_scene_recall_drives_the_bench_back() {
+    with_fixture(|| {
+        let mut sandbox = Sandbox::new(&[400, 10, 10, 25, 25]);
+        let known: HashMap<usize, i32> = sandbox.board.positions().into_iter().enumerate().collect();
+        let scene = sandbox.ops.capture_scene("verse", &known, ParameterPreset { lap_rest: Some(2.0), ..ParameterPreset::default() });
+        assert_eq!((scene.x, scene.z.len(), scene.tuners.len()), (Some(400), 4, 0));
+
+        sandbox.board.set_positions(&[900, 50, -40, 0, 0]);
+        let mut positions = sandbox.board.positions();
+        let notes = sandbox.ops.recall_scene(&mut sandbox.board, &mut positions, &sandbox.max_positions, &scene).unwrap();
+        assert!(notes.is_empty(), "{:?}", notes);
+        assert_eq!(sandbox.board.positions(), [400, 10, 10, 25, 25]);
+        assert_eq!(positions, sandbox.board.positions());
+
+        // X is held to X_MAX_POS and Z to Z_MIN_POS and its max positions
+        let mut far = scene.clone();
+        far.x = Some(5000);
+        far.z.insert(1, 500);
+        far.z.insert(2, -30);
+        sandbox.ops.recall_scene(&mut sandbox.board, &mut positions, &sandbox.max_positions, &far).unwrap();
+        assert_eq!(sandbox.board.positions(), [1000, 100, 0, 25, 25]);
+
+        // Refused before anything moves: X is not a Z stepper, the bench has no tuners
+        let mut not_z = scene.clone();
+        not_z.z.insert(0, 5);
+        let mut tuned = scene.clone();
+        tuned.tuners.insert(0, 12);
+        for (bad, expected) in [(not_z, "not a Z stepper"), (tuned, "no tuner")] {
+            let err = sandbox.ops.recall_scene(&mut sandbox.board, &mut positions, &sandbox.max_positions, &bad).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}", err);
+        }
+        assert_eq!(sandbox.board.positions(), [1000, 100, 0, 25, 25]);
+    });
+}
+
+#[cfg(feature = "fault-injection")]
+#[test]
+fn test_z_adjust_rides_out_transient_stepper_errors() {
+    with_fixture(|| {
+        let mut sandbox = Sandbox::new(&[500, 0, 0, 0, 0]);
+        let injector = Arc::new(FaultInjector::new(FaultSettings { stepper_error: 0.2, seed: 3, ..FaultSettings::default() }));
+        let mut faulty = Faulty::new(sandbox.board.clone(), Arc::clone(&injector));
+        let (mut passes, mut errors) = (0, 0);
+        loop {
+            let amp_sums = sandbox.listen();
+            let mut positions = sandbox.board.positions();
+            // A failed call is retried on the next pass, as the GUI's auto-adjust does
+            match sandbox.ops.z_adjust(&mut faulty, &mut positions, &sandbox.max_positions, &MIN_AMP, &MAX_AMP, &MIN_VOICES, &MAX_VOICES, None) {
+                Ok(message) if !message.contains("moved") => {
+                    assert!(in_band(&amp_sums), "{:?}", amp_sums);
+                    break;
+                }
+                Ok(_) => {}
+                Err(_) => errors += 1,
+            }
+            passes += 1;
+            assert!(passes < 120, "no convergence after {} passes ({} failed), last {:?}", passes, errors, amp_sums);
+        }
+        assert!(injector.injected(Fault::StepperError) > 0);
+    });
+}
+
+#[cfg(feature = "fault-injection")]
+#[test]
+fn test_dropped_serial_frames_never_reach_the_board() {
+    use std::io::{ErrorKind, Read, Write};
+    let protocol = FirmwareProtocol {
+        positions_id: 5, encoders_id: None, amove_id: 0, rmove_id: 1, set_stepper_id: 2,
+        enable_id: None, value_bytes: 2, checksum: false, version: None,
+    };
+    let board = LoopbackBoard::new(protocol, 5);
+    let dropping = Arc::new(FaultInjector::new(FaultSettings { serial_drop: 1.0, ..FaultSettings::default() }));
+    let mut port = Faulty::new(board.open().unwrap(), Arc::clone(&dropping));
+    // The command is reported written but the board never sees it
+    port.write_all(b"5;").unwrap();
+    assert!(board.commands().is_empty());
+    // The reply to a command that got through is lost and reads as a timeout
+    port.inner.write_all(b"5;").unwrap();
+    let mut reply = [0u8; 64];
+    assert_eq!(port.read(&mut reply).unwrap_err().kind(), ErrorKind::TimedOut);
+    assert_eq!(dropping.injected(Fault::SerialDrop), 2);
+
+    let mut clean = Faulty::new(board.open().unwrap(), Arc::new(FaultInjector::new(FaultSettings::default())));
+    clean.write_all(b"5;").unwrap();
+    assert_eq!(board.commands().len(), 2);
+}
+
+#[cfg(feature = "fault-injection")]
+#[test]
+fn test_corrupted_partials_frames_do_not_break_the_analysis() {
+    with_fixture(|| {
+        let mut sandbox = Sandbox::new(&[500, -50, -50, -60, -60]);
+        let injector = Arc::new(FaultInjector::new(FaultSettings { shm_corrupt: 0.5, seed: 5, ..FaultSettings::default() }));
+        fault_injection::install(Some(Arc::clone(&injector)));
+        for _ in 0..200 {
+            sandbox.listen();
+        }
+        fault_injection::install(None);
+        assert!(injector.injected(Fault::ShmCorrupt) > 0);
+        // The first clean frame puts the analysis back where it belongs
+        let amp_sums = sandbox.listen();
+        assert!(in_band(&amp_sums), "{:?}", amp_sums);
+    });
+}
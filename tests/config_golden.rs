@@ -0,0 +1,554 @@
+/// Golden tests for config_loader against the fixture hosts in tests/fixtures
+///
+/// Each fixture is a small string_driver.yaml for one representative host:
+/// stringdriver-1 (string_driver_v1, tuners on the carriage board), stringdriver-3
+/// (string_driver_v2 with a separate tuner board), a host without GPIO and a
+/// carriage without X. Every value the GUIs and Operations derive from the YAML is
+/// pinned here, so a config regression fails `cargo test` instead of on the hardware.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use stringdriver::config_loader::{self, ArduinoFirmware, BowOutput, ClearanceAction, DamperOutput, GpioPull, HookEvent, InfluxTarget, RefreshRates, TransportSettings, TunerSafetySettings, UsbIdSettings, CONFIG_ENV};
+
+/// Run `f` with STRING_DRIVER_CONFIG pointing at `tests/fixtures/<name>.yaml`.
+/// The variable is process-wide, so fixture tests take turns.
+fn with_fixture<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    with_config_at(&PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(format!("{}.yaml", name)), f)
+}
+
+/// Run `f` with STRING_DRIVER_CONFIG pointing at `path`, taking turns with the fixture tests
+fn with_config_at<T>(path: &std::path::Path, f: impl FnOnce() -> T) -> T {
+    static FIXTURE_LOCK: Mutex<()> = Mutex::new(());
+    let _guard = FIXTURE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    std::env::set_var(CONFIG_ENV, path);
+    let result = f();
+    std::env::remove_var(CONFIG_ENV);
+    result
+}
+
+/// Z stepper indices as Operations numbers them: two per string from Z_FIRST_INDEX
+fn z_indices(settings: &config_loader::ArduinoSettings) -> Vec<usize> {
+    let first = settings.z_first_index.unwrap_or(0);
+    (first..first + settings.string_num * 2).collect()
+}
+
+#[test]
+fn test_stringdriver_1_v1_firmware_with_mainboard_tuners() {
+    with_fixture("stringdriver-1", || {
+        let host = "stringdriver-1";
+        let ard = config_loader::load_arduino_settings(host).unwrap();
+        assert_eq!(ard.firmware, ArduinoFirmware::StringDriverV1);
+        assert_eq!(ard.ard_t_firmware, None);
+        assert_eq!(ard.port.as_deref(), Some("/dev/ttyACM0"));
+        assert_eq!(ard.num_steppers, Some(7));
+        assert_eq!(ard.string_num, 2);
+        assert_eq!((ard.x_step_index, ard.x_max_pos), (Some(2), Some(2396)));
+        assert_eq!(ard.z_first_index, Some(3));
+        assert_eq!(z_indices(&ard), vec![3, 4, 5, 6]);
+        assert_eq!(ard.tuner_first_index, Some(0));
+        assert_eq!((ard.ard_t_port.as_deref(), ard.ard_t_num_steppers), (None, None));
+        // Tuners run up to X on the carriage board
+        assert_eq!(config_loader::mainboard_tuner_indices(&ard), vec![0, 1]);
+        // Link defaults
+        assert_eq!(ard.encoder_slip_threshold, 4);
+        assert_eq!((ard.transport.clone(), ard.usb.clone()), (TransportSettings::Serial, None));
+        assert!(!ard.checksum && !ard.ard_t_checksum);
+        assert_eq!(ard.reply_timeouts.positions, Duration::from_secs(2));
+        assert_eq!((ard.tuner_steps.coarse, ard.tuner_steps.fine), (100, 10));
+        assert_eq!(ard.jog_rate_hz, config_loader::DEFAULT_JOG_RATE_HZ);
+        assert!(ard.motion_params.is_none());
+        assert!(ard.strings.is_empty());
+
+        let ops = config_loader::load_operations_settings(host).unwrap();
+        // Only Z_UP_STEP / Z_DOWN_STEP are read: the lower-case keys this host carries are ignored
+        assert_eq!((ops.z_up_step, ops.z_down_step), (None, None));
+        assert!(ops.bump_check_enable);
+        assert!(!ops.adaptive_z_step && !ops.performance_use_controller && !ops.voice_floor_relative);
+        assert_eq!((ops.lap_rest, ops.x_start, ops.channel_map.clone()), (None, None, None));
+
+        let gpio = config_loader::load_gpio_settings(host).unwrap().unwrap();
+        assert_eq!((gpio.library.as_deref(), gpio.max_steps), (Some("gpiod"), Some(2396)));
+        let components = gpio.components.unwrap();
+        assert_eq!(components.z_touch_pins, Some(vec![6, 26, 19, 5]));
+        assert_eq!((components.x_limit_pin, components.x_home_pin, components.x_away_pin), (Some(16), None, None));
+        let encoder = components.rotary_encoder_pins.unwrap();
+        assert_eq!((encoder.a, encoder.b), (17, 27));
+        let distance = components.distance_sensor_pins.unwrap();
+        assert_eq!((distance.trig, distance.echo), (23, 24));
+        assert!(gpio.line_default.active_low && gpio.line_default.pull == GpioPull::Up);
+        assert!(gpio.lines.is_empty());
+
+        assert_eq!(config_loader::load_refresh_rates(host).unwrap(), RefreshRates::LOW_POWER);
+        let budgets = config_loader::load_time_budgets(host).unwrap();
+        assert_eq!(budgets.budget("z_calibrate"), Some(Duration::from_secs(600)));
+        assert_eq!(budgets.budget("ping_pong_move"), None);
+        assert!(config_loader::load_x_clearance(host).unwrap().zones.is_empty());
+        let park = config_loader::load_park_settings(host).unwrap();
+        assert!(park.z_positions.is_empty() && park.x_position.is_none() && !park.disable_motors);
+    });
+}
+
+#[test]
+fn test_stringdriver_3_v2_firmware_with_tuner_board() {
+    with_fixture("stringdriver-3", || {
+        let host = "stringdriver-3";
+        let ard = config_loader::load_arduino_settings(host).unwrap();
+        assert_eq!(ard.firmware, ArduinoFirmware::StringDriverV2);
+        assert_eq!(ard.ard_t_firmware, None); // Tuner board runs string_driver_v2 too
+        assert_eq!(ard.num_steppers, Some(13));
+        assert_eq!(ard.string_num, 6);
+        assert_eq!((ard.x_step_index, ard.x_max_pos), (Some(0), Some(1550)));
+        assert_eq!(z_indices(&ard), (1..13).collect::<Vec<_>>());
+        assert_eq!(ard.tuner_first_index, Some(0));
+        assert_eq!(ard.ard_t_port.as_deref(), Some("/dev/ttyACM1"));
+        assert_eq!(ard.ard_t_num_steppers, Some(6));
+        // Tuners live on the tuner board, none on the carriage board
+        assert!(config_loader::mainboard_tuner_indices(&ard).is_empty());
+        assert_eq!(
+            ard.usb,
+            Some(UsbIdSettings { vid: Some(0x2341), pid: Some(0x0042), serial_number: Some("75833353035351A0E1E1".to_string()) })
+        );
+        assert_eq!(ard.ard_t_usb, None);
+        // The tuner board is reached over BLE, the carriage board over USB
+        assert_eq!(ard.transport, TransportSettings::Serial);
+        assert_eq!(
+            ard.ard_t_transport,
+            TransportSettings::Ble { name: Some("StringTuner".to_string()), address: None, scan: Duration::from_millis(2500) }
+        );
+        assert_eq!(ard.reply_timeouts.positions, Duration::from_millis(1500));
+        assert_eq!(ard.reply_timeouts.encoders, Duration::from_secs(2));
+
+        let ops = config_loader::load_operations_settings(host).unwrap();
+        assert_eq!((ops.z_up_step, ops.z_down_step, ops.lap_rest), (Some(4), Some(-4), Some(2.0)));
+        let map = ops.channel_map.unwrap();
+        assert_eq!(map, vec![5, 4, 3, 2, 1, 0]);
+        config_loader::validate_channel_map(&map, ard.string_num).unwrap();
+
+        let gpio = config_loader::load_gpio_settings(host).unwrap().unwrap();
+        let components = gpio.components.unwrap();
+        assert_eq!(components.z_touch_pins.map(|pins| pins.len()), Some(ard.string_num * 2));
+        assert_eq!((components.x_home_pin, components.x_away_pin, components.x_limit_pin), (Some(16), Some(26), None));
+
+        let budgets = config_loader::load_time_budgets(host).unwrap();
+        assert_eq!(budgets.budget("z_calibrate"), Some(Duration::from_secs(600)));
+        assert_eq!(budgets.budget("ping_pong_move"), Some(Duration::from_secs(3600)));
+        assert_eq!(budgets.budget("x_home"), Some(Duration::from_secs(3600))); // Block default beats the seek fallback
+        let clearance = config_loader::load_x_clearance(host).unwrap();
+        assert_eq!(clearance.action, ClearanceAction::Refuse);
+        assert_eq!((clearance.zones[1].x_min, clearance.zones[1].x_max), (1400, 1500));
+        assert_eq!(clearance.required_z(200, 1000, 0), None);
+        assert_eq!(clearance.required_z(200, 100, 0), Some(30));
+        assert_eq!(clearance.required_z(1450, 1450, 4), Some(35));
+        assert_eq!(clearance.required_z(0, 1550, 1), Some(30)); // Highest Z_MIN on the path
+        let rests = config_loader::load_rest_overrides(host).unwrap();
+        assert_eq!(rests.len(), 1);
+        assert_eq!((rests["z_calibrate"].z_rest, rests["z_calibrate"].lap_rest), (Some(0.5), None));
+    });
+}
+
+#[test]
+fn test_host_without_gpio() {
+    with_fixture("no-gpio", || {
+        let host = "bench-desktop";
+        let ard = config_loader::load_arduino_settings(host).unwrap();
+        assert_eq!(ard.firmware, ArduinoFirmware::StringDriverV2); // ARDUINO_FIRMWARE unset
+        assert_eq!(ard.port.as_deref(), Some("/dev/ttyUSB0"));
+        assert_eq!((ard.x_step_index, ard.x_max_pos), (Some(0), Some(0))); // Dummy X
+        assert_eq!(z_indices(&ard), (1..9).collect::<Vec<_>>());
+        assert_eq!((ard.tuner_first_index, ard.ard_t_port.as_deref()), (None, None));
+        assert!(config_loader::mainboard_tuner_indices(&ard).is_empty());
+
+        assert!(config_loader::load_gpio_settings(host).unwrap().is_none());
+        assert_eq!(config_loader::load_refresh_rates(host).unwrap(), RefreshRates::DESKTOP);
+        let ops = config_loader::load_operations_settings(host).unwrap();
+        assert_eq!((ops.z_up_step, ops.z_down_step), (None, None));
+    });
+}
+
+#[test]
+fn test_host_without_x() {
+    with_fixture("no-x", || {
+        let host = "fixed-carriage";
+        let ard = config_loader::load_arduino_settings(host).unwrap();
+        assert_eq!(ard.firmware, ArduinoFirmware::StringDriverV1);
+        assert_eq!((ard.x_step_index, ard.x_max_pos), (None, None));
+        assert_eq!(ard.num_steppers, Some(8));
+        assert_eq!(z_indices(&ard), vec![2, 3, 4, 5, 6, 7]);
+        // Without X the tuners run up to Z_FIRST_INDEX
+        assert_eq!(config_loader::mainboard_tuner_indices(&ard), vec![0, 1]);
+
+        let gpio = config_loader::load_gpio_settings(host).unwrap().unwrap();
+        assert_eq!(gpio.max_steps, None);
+        let components = gpio.components.unwrap();
+        assert_eq!(components.z_touch_pins, Some(vec![6, 26, 19, 5, 12, 13]));
+        assert!(components.x_home_pin.is_none() && components.x_away_pin.is_none() && components.x_limit_pin.is_none());
+    });
+}
+
+#[test]
+fn test_host_with_second_axis() {
+    with_fixture("second-axis", || {
+        let host = "bow-tilt";
+        let ard = config_loader::load_arduino_settings(host).unwrap();
+        assert_eq!((ard.x_step_index, ard.x_max_pos), (Some(0), Some(1550)));
+        assert_eq!(ard.axes.len(), 1);
+        let y = &ard.axes[0];
+        assert_eq!((y.name.as_str(), y.step_index, y.max_pos), ("Y", 5, 800)); // NAME upper-cased
+        assert_eq!((y.step, y.rest), (10, Some(1.5)));
+        assert_eq!((y.home_pin, y.away_pin), (Some(22), Some(23)));
+        assert_eq!((y.motion.speed, y.motion.accel), (Some(200), None));
+
+        let components = config_loader::load_gpio_settings(host).unwrap().unwrap().components.unwrap();
+        assert_eq!(components.axis_pins, vec![("Y".to_string(), Some(22), Some(23))]);
+        // Y's seeks get the same fallback time limit as X's
+        let budgets = config_loader::load_time_budgets(host).unwrap();
+        assert_eq!(budgets.budget("y_calibrate"), Some(Duration::from_secs(600)));
+        assert_eq!(budgets.budget("y_adjust"), None);
+    });
+}
+
+#[test]
+fn test_hosts_with_bow_drive() {
+    with_fixture("bow-drive", || {
+        let pwm = config_loader::load_bow_drive_settings("bow-pwm").unwrap().unwrap();
+        assert_eq!(pwm.output, BowOutput::Pwm { chip: 2, channels: vec![0, 1], period_ns: 50_000 });
+        assert_eq!((pwm.speed_for(0), pwm.speed_for(1), pwm.speed_for(2)), (120, 140, 0));
+        assert_eq!((pwm.max_speed, pwm.min_speed, pwm.speed_step, pwm.modulate), (255, 60, 8, true));
+
+        let serial = config_loader::load_bow_drive_settings("bow-serial").unwrap().unwrap();
+        assert_eq!(serial.output, BowOutput::Serial { command_id: 15 });
+        // One SPEED applies to every string
+        assert_eq!((serial.speed_for(0), serial.speed_for(1)), (100, 100));
+        assert_eq!((serial.max_speed, serial.min_speed, serial.speed_step, serial.modulate), (1000, 0, 5, false));
+    });
+    // No BOW_DRIVE block: no wheels
+    with_fixture("second-axis", || {
+        assert_eq!(config_loader::load_bow_drive_settings("bow-tilt").unwrap(), None);
+    });
+}
+
+#[test]
+fn test_hosts_with_dampers() {
+    with_fixture("dampers", || {
+        let gpio = config_loader::load_damper_settings("damp-gpio").unwrap().unwrap();
+        assert_eq!(gpio.output, DamperOutput::Gpio { pins: vec![20, 21], active_low: true });
+        assert_eq!((gpio.settle, gpio.during.clone()), (Duration::from_millis(500), vec!["z_calibrate".to_string(), "x_calibrate".to_string()]));
+        assert_eq!(gpio.serial_command(), None);
+        // The relay lines are requested with the other GPIO components
+        let components = config_loader::load_gpio_settings("damp-gpio").unwrap().unwrap().components.unwrap();
+        assert_eq!((components.damper_pins, components.damper_active_low), (vec![20, 21], true));
+
+        let serial = config_loader::load_damper_settings("damp-serial").unwrap().unwrap();
+        assert_eq!(serial.serial_command(), Some(16));
+        assert_eq!((serial.settle, serial.during), (Duration::from_millis(300), vec!["z_calibrate".to_string()]));
+    });
+    // No DAMPERS block: no dampers
+    with_fixture("bow-drive", || {
+        assert_eq!(config_loader::load_damper_settings("bow-pwm").unwrap(), None);
+    });
+}
+
+#[test]
+fn test_hosts_with_tuner_safety() {
+    with_fixture("tuner-safety", || {
+        let safe = config_loader::load_arduino_settings("tune-safe").unwrap();
+        assert_eq!(safe.tuner_safety, Some(TunerSafetySettings {
+            max_pitch: vec![Some(115.0), None],
+            probe_steps: 20,
+            log_dir: PathBuf::from("/var/log/stringdriver/pitch"),
+            samples: 12,
+        }));
+
+        // A MAX_PITCH alone turns the checks on with the defaults
+        let limit = config_loader::load_arduino_settings("tune-limit").unwrap();
+        assert_eq!(limit.tuner_safety, Some(TunerSafetySettings {
+            max_pitch: vec![None, Some(152.5)],
+            probe_steps: 50,
+            log_dir: PathBuf::from("logs/pitch_curves"),
+            samples: 32,
+        }));
+
+        let err = config_loader::load_arduino_settings("tune-bad").unwrap_err();
+        assert!(err.to_string().contains("MAX_PITCH must be a positive number of Hz"), "{}", err);
+    });
+    // Neither: tuner moves are not checked
+    with_fixture("stringdriver-1", || {
+        assert_eq!(config_loader::load_arduino_settings("stringdriver-1").unwrap().tuner_safety, None);
+    });
+}
+
+#[test]
+fn test_per_string_z_steps() {
+    use stringdriver::effective_config::Source;
+    use stringdriver::operations::Operations;
+    with_fixture("string-z-steps", || {
+        let settings = config_loader::load_arduino_settings("wound-bass").unwrap();
+        let steps: Vec<(Option<i32>, Option<i32>)> = settings.strings.iter().map(|s| (s.z_up_step, s.z_down_step)).collect();
+        assert_eq!(steps, vec![(Some(6), Some(-4)), (None, Some(-3)), (None, None)]);
+
+        let ops = Operations::for_inspection("wound-bass").unwrap();
+        // Z steppers 4/5 are string 0, 6/7 string 1, 8/9 string 2
+        assert_eq!(ops.string_of_z_stepper(5), Some(0));
+        assert_eq!((ops.z_up_step_for(Some(0)), ops.z_down_step_for(Some(0))), (6, -4));
+        assert_eq!((ops.z_up_step_for(Some(1)), ops.z_down_step_for(Some(1))), (2, -3));
+        assert_eq!((ops.z_up_step_for(Some(2)), ops.z_down_step_for(None)), (2, -2));
+        let setting = ops.effective_config().get("STRING_Z_STEPS").cloned().unwrap();
+        assert_eq!((setting.value.as_str(), setting.source), ("string 0: up 6, down -4; string 1: up global, down -3", Source::Yaml));
+
+        // Changed in the GUI: a wrong-signed step is dropped, the global one applies
+        ops.set_string_z_steps(2, Some(8), Some(5));
+        assert_eq!((ops.z_up_step_for(Some(2)), ops.z_down_step_for(Some(2))), (8, -2));
+        assert_eq!(ops.effective_config().get("STRING_Z_STEPS").unwrap().source, Source::Runtime);
+
+        let err = config_loader::load_arduino_settings("steps-bad").unwrap_err();
+        assert!(err.to_string().contains("Z_DOWN_STEP must be a negative number of steps"), "{}", err);
+    });
+}
+
+#[test]
+fn test_hosts_with_hooks() {
+    with_fixture("hooks", || {
+        let hooks = config_loader::load_hook_settings("hooks-venue").unwrap();
+        assert_eq!(hooks.commands, vec![
+            (HookEvent::OperationStarted, "/opt/venue/lights.sh dim".to_string()),
+            (HookEvent::OperationFinished, "/opt/venue/lights.sh up".to_string()),
+            (HookEvent::OperationFinished, "logger -t stringdriver".to_string()),
+            (HookEvent::Estop, "/opt/venue/slack.sh".to_string()),
+        ]);
+        assert_eq!(hooks.timeout, Duration::from_secs(10));
+
+        let err = config_loader::load_hook_settings("hooks-typo").unwrap_err();
+        assert!(err.to_string().contains("Unknown HOOKS event 'operation_ended'"), "{}", err);
+    });
+    // No HOOKS block: nothing runs
+    with_fixture("dampers", || {
+        let hooks = config_loader::load_hook_settings("damp-serial").unwrap();
+        assert!(hooks.commands.is_empty());
+        assert_eq!(hooks.timeout, Duration::from_secs(30));
+    });
+}
+
+#[test]
+fn test_hosts_with_audio_trigger() {
+    use stringdriver::audio_trigger::TriggerState;
+    use stringdriver::operations::Operations;
+    with_fixture("audio-trigger", || {
+        let trigger = config_loader::load_audio_trigger("installation").unwrap().unwrap();
+        assert_eq!(trigger.operation, "performance_mode");
+        assert_eq!((trigger.threshold, trigger.sound_for, trigger.silence_for, trigger.armed), (20.0, Duration::from_secs(4), Duration::from_secs(900), true));
+        let ops = Operations::for_inspection("installation").unwrap();
+        assert_eq!(ops.audio_trigger_state(), Some(TriggerState::Armed { loud_since: None }));
+        // No analysis: nothing starts
+        assert_eq!(ops.observe_audio_trigger(false, false), None);
+        ops.arm_audio_trigger(false);
+        assert_eq!(ops.describe_audio_trigger().as_deref(), Some("disarmed"));
+
+        let gallery = config_loader::load_audio_trigger("gallery").unwrap().unwrap();
+        assert_eq!((gallery.operation.as_str(), gallery.sound_for, gallery.silence_for, gallery.armed), ("ping_pong_move", Duration::from_secs(3), Duration::from_secs(600), false));
+
+        let err = config_loader::load_audio_trigger("trigger-bad").unwrap_err();
+        assert!(err.to_string().contains("AUDIO_TRIGGER.THRESHOLD for 'trigger-bad' must be a positive amp sum"), "{}", err);
+    });
+    // No AUDIO_TRIGGER block: no trigger
+    with_fixture("hooks", || {
+        assert_eq!(config_loader::load_audio_trigger("hooks-venue").unwrap(), None);
+        assert_eq!(Operations::for_inspection("hooks-venue").unwrap().audio_trigger_state(), None);
+    });
+}
+
+#[test]
+fn test_hosts_sharing_settings() {
+    with_fixture("inheritance", || {
+        // DEFAULTS, then carriage_v2 under quiet_room, then the host's own keys
+        let a = config_loader::load_arduino_settings("studio-a").unwrap();
+        assert_eq!((a.port.as_deref(), a.num_steppers, a.string_num, a.x_max_pos), (Some("/dev/ttyACM0"), Some(5), 2, Some(1550)));
+        assert_eq!(a.firmware, ArduinoFirmware::StringDriverV2);
+        let trigger = config_loader::load_audio_trigger("studio-a").unwrap().unwrap();
+        assert_eq!((trigger.operation.as_str(), trigger.threshold, trigger.sound_for), ("performance_mode", 5.0, Duration::from_secs(3)));
+        let (os_key, section) = config_loader::load_host_section("studio-a").unwrap();
+        assert_eq!(os_key, "RaspberryPi");
+        assert!(!section.contains_key("EXTENDS"));
+        // As written, for rewriting through install_host_section
+        let (_, own) = config_loader::load_own_host_section("studio-a").unwrap();
+        assert_eq!(own.len(), 2);
+
+        let b = config_loader::load_arduino_settings("studio-b").unwrap();
+        assert_eq!((b.port.as_deref(), b.num_steppers, b.firmware), (Some("/dev/ttyACM1"), Some(7), ArduinoFirmware::StringDriverV2));
+        let ops = config_loader::load_operations_settings("studio-b").unwrap();
+        assert_eq!((ops.z_up_step, ops.z_down_step), (Some(4), Some(-2)));
+        assert_eq!(config_loader::load_audio_trigger("studio-b").unwrap(), None);
+
+        let c = config_loader::load_arduino_settings("studio-c").unwrap();
+        assert_eq!(c.x_max_pos, Some(2600));
+        assert_eq!(config_loader::load_operations_settings("studio-c").unwrap().z_up_step, Some(1));
+        let launch: Vec<String> = config_loader::load_launch_settings("studio-c").unwrap().into_iter().map(|c| c.name).collect();
+        assert_eq!(launch, ["operations_gui"]);
+
+        let err = config_loader::load_arduino_settings("loop-a").unwrap_err();
+        assert!(err.to_string().contains("EXTENDS loops back on itself: loop-a -> loop-b -> loop-a"), "{}", err);
+        let err = config_loader::load_operations_settings("extends-missing").unwrap_err();
+        assert!(err.to_string().contains("EXTENDS for 'extends-missing' names 'no_such_profile', which is neither a PROFILES entry nor a host"), "{}", err);
+        // PROFILES entries are not hosts
+        assert!(config_loader::load_host_section("carriage_v2").is_err());
+    });
+}
+
+#[test]
+fn test_launch_registry_for_shutdown() {
+    use std::path::Path;
+    use stringdriver::shutdown;
+    with_fixture("launch", || {
+        let launch = config_loader::load_launch_settings("studio").unwrap();
+        assert_eq!(launch[0].processes, ["target/release/audio_monitor", "Persist Monitor", "qjackctl"]);
+        assert!(launch[1].processes.is_empty());
+        let registry = shutdown::registry("studio", Path::new("/srv/stringdriver")).unwrap();
+        let names: Vec<&str> = registry.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["launcher", "audio_monitor", "stringdriverd"]);
+        assert_eq!(registry[1].patterns, ["bash audmon.sh", "target/release/audio_monitor", "Persist Monitor", "qjackctl"]);
+        assert_eq!(registry[2].patterns, ["stringdriverd"]);
+
+        let err = config_loader::load_launch_settings("launch-bad").unwrap_err();
+        assert!(err.to_string().contains("LAUNCH component 'audio_monitor' PROCESSES must be a list of strings"), "{}", err);
+        // A cargo run component needs PROCESSES: "cargo" alone would stop every build
+        let err = shutdown::registry("launch-cargo", Path::new("/srv/stringdriver")).unwrap_err();
+        assert!(err.to_string().contains("'stringdriverd' runs 'cargo run --release --bin stringdriverd'"), "{}", err);
+    });
+    // No LAUNCH list: both built-in lists
+    with_fixture("hooks", || {
+        let registry = shutdown::registry("hooks-venue", Path::new("/srv/stringdriver")).unwrap();
+        let names: Vec<&str> = registry.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["launcher", "master_gui", "audio_monitor", "stepper_gui", "operations_gui"]);
+        assert_eq!(registry[1].patterns, ["bash master_gui.sh", "target/release/master_gui", "Master GUI Persist Monitor", "qjackctl"]);
+    });
+}
+
+#[test]
+fn test_hosts_with_scenes() {
+    use std::collections::{BTreeMap, HashMap};
+    use stringdriver::operations::Operations;
+    with_fixture("scenes", || {
+        assert_eq!(config_loader::load_scene_dir("scene-stage").unwrap(), PathBuf::from("/media/usb/scenes"));
+        let err = config_loader::load_scene_dir("scene-bad").unwrap_err();
+        assert!(err.to_string().contains("SCENES for 'scene-bad' must be a block with a DIR"), "{}", err);
+    });
+    // No SCENES block: ./scenes/<host>; a scene holds the carriage board tuners by string
+    with_fixture("stringdriver-1", || {
+        assert_eq!(config_loader::load_scene_dir("stringdriver-1").unwrap(), PathBuf::from("scenes/stringdriver-1"));
+        let ops = Operations::for_inspection("stringdriver-1").unwrap();
+        let positions = HashMap::from([(0, 310), (1, -25), (2, 1200), (3, 40), (4, 42), (5, 100)]);
+        let scene = ops.capture_scene(" chorale ", &positions, config_loader::ParameterPreset::default());
+        assert_eq!((scene.name.as_str(), scene.host.as_str(), scene.x), ("chorale", "stringdriver-1", Some(1200)));
+        assert_eq!(scene.z, BTreeMap::from([(3, 40), (4, 42), (5, 100)]));
+        assert_eq!(scene.tuners, BTreeMap::from([(0, 310), (1, -25)]));
+    });
+}
+
+#[test]
+fn test_machine_state_backends() {
+    with_fixture("machine-state", || {
+        let both = config_loader::load_logging_settings("state-both").unwrap().machine_state;
+        assert!(both.postgres);
+        assert_eq!(both.influx, Some(InfluxTarget::Http {
+            url: "http://grafana.local:8086/api/v2/write?org=studio&bucket=stringdriver".to_string(),
+            token_env: Some("INFLUX_TOKEN".to_string()),
+        }));
+
+        let file = config_loader::load_logging_settings("state-file").unwrap();
+        assert!(!file.machine_state.postgres);
+        assert_eq!(file.machine_state.influx, Some(InfluxTarget::File(file.dir.join("machine_state.lp"))));
+
+        let err = config_loader::load_logging_settings("state-no-target").unwrap_err();
+        assert!(err.to_string().contains("needs an INFLUX mapping"), "{}", err);
+    });
+    // No MACHINE_STATE: Postgres only, as before
+    with_fixture("hooks", || {
+        let settings = config_loader::load_logging_settings("hooks-venue").unwrap().machine_state;
+        assert!(settings.postgres && settings.influx.is_none());
+    });
+}
+
+#[test]
+fn test_effective_config_sources() {
+    use stringdriver::effective_config::Source;
+    use stringdriver::operations::Operations;
+    let source = |ops: &Operations, key: &str| ops.effective_config().get(key).map(|s| (s.value.clone(), s.source));
+    with_fixture("stringdriver-3", || {
+        let ops = Operations::for_inspection("stringdriver-3").unwrap();
+        assert_eq!(source(&ops, "Z_UP_STEP"), Some(("4".to_string(), Source::Yaml)));
+        assert_eq!(source(&ops, "LAP_REST"), Some(("2".to_string(), Source::Yaml)));
+        assert_eq!(source(&ops, "Z_REST"), Some(("1".to_string(), Source::Default)));
+        assert_eq!(source(&ops, "X_CLEARANCE").map(|(_, s)| s), Some(Source::Yaml));
+        assert_eq!(source(&ops, "PARK").map(|(_, s)| s), Some(Source::Default));
+        ops.set_lap_rest(3.5);
+        assert_eq!(source(&ops, "LAP_REST"), Some(("3.5".to_string(), Source::Runtime)));
+        assert!(ops.effective_config().to_text().contains("[Rests]"));
+    });
+    // Lower-case z_up_step is not read: the default is what runs
+    with_fixture("stringdriver-1", || {
+        let ops = Operations::for_inspection("stringdriver-1").unwrap();
+        assert_eq!(source(&ops, "Z_UP_STEP"), Some(("2".to_string(), Source::Default)));
+        assert!(ops.gpio.is_none());
+    });
+}
+
+#[test]
+fn test_unknown_host_is_missing_from_fixture() {
+    with_fixture("stringdriver-1", || {
+        let err = config_loader::load_arduino_settings("stringdriver-3").unwrap_err();
+        assert!(err.to_string().contains("No host entry for 'stringdriver-3'"));
+    });
+}
+
+#[test]
+fn test_profile_round_trip() {
+    use stringdriver::profile::{self, ProfileThresholds};
+    let dir = std::env::temp_dir().join(format!("stringdriver-profile-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let yaml = dir.join("string_driver.yaml");
+    std::fs::write(&yaml, format!(
+        "PROFILES:\n  cloned-pi:\n    ARD_NUM_STEPPERS: 3\n\n\
+         RaspberryPi:\n  tuned-pi:\n    ARD_PORT: /dev/ttyACM0\n    MOTION_PARAMS:\n      Z: {{ SPEED: 300, ACCEL: 150 }}\n\
+         \x20   RESPONSE_MAP: {{ OUTPUT_DIR: {0}/maps-tuned }}\n    SCENES: {{ DIR: {0}/scenes-tuned }}\n\
+         \x20   HEIGHT_CALIBRATION: {{ FILE: {0}/tuned-height.json }}\n\n\
+         Ubuntu:\n  new-pi:\n    ARD_PORT: /dev/ttyUSB0\n",
+        dir.display()
+    )).unwrap();
+    std::fs::create_dir_all(dir.join("maps-tuned")).unwrap();
+    std::fs::write(dir.join("maps-tuned/response_map_tuned-pi_20261016.csv"), "z,amp\n0,1.5\n").unwrap();
+    std::fs::write(dir.join("maps-tuned/notes.txt"), "not a sweep").unwrap();
+    std::fs::create_dir_all(dir.join("scenes-tuned")).unwrap();
+    std::fs::write(dir.join("scenes-tuned/chorale.scene.yaml"), "X: 1200\nZ: { 1: 40 }\n").unwrap();
+    std::fs::write(dir.join("tuned-height.json"), "{\"curves\": {}}").unwrap();
+
+    with_config_at(&yaml, || {
+        let thresholds = ProfileThresholds { voice_count_min: vec![1, 2], amp_sum_max: vec![90], ..Default::default() };
+        let exported = profile::build_profile("tuned-pi", Some(thresholds.clone())).unwrap();
+        assert_eq!(exported.response_maps.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(), ["response_map_tuned-pi_20261016.csv"]);
+        assert_eq!(exported.scenes.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(), ["chorale.scene.yaml"]);
+        assert_eq!(exported.height_map.as_ref().unwrap().contents, "{\"curves\": {}}");
+        let bundle = dir.join("tuned-pi-profile.json");
+        profile::write_profile(&exported, &bundle).unwrap();
+        let read = profile::read_profile(&bundle).unwrap();
+        assert_eq!(read.thresholds, Some(thresholds));
+
+        // Installed as another host: its files go where the copied section says
+        std::fs::remove_dir_all(dir.join("maps-tuned")).unwrap();
+        std::fs::remove_dir_all(dir.join("scenes-tuned")).unwrap();
+        std::fs::remove_file(dir.join("tuned-height.json")).unwrap();
+        let err = profile::import_profile(&read, "new-pi").unwrap_err();
+        assert!(err.to_string().contains("'new-pi' is already defined under Ubuntu"), "{}", err);
+        let summary = profile::import_profile(&read, "cloned-pi").unwrap();
+        assert!(summary.contains("Restored 1 of 1 scene(s)"), "{}", summary);
+        assert_eq!(std::fs::read_to_string(dir.join("scenes-tuned/chorale.scene.yaml")).unwrap(), "X: 1200\nZ: { 1: 40 }\n");
+        assert_eq!(std::fs::read_to_string(dir.join("maps-tuned/response_map_tuned-pi_20261016.csv")).unwrap(), "z,amp\n0,1.5\n");
+        assert_eq!(std::fs::read_to_string(dir.join("tuned-height.json")).unwrap(), "{\"curves\": {}}");
+        let motion = config_loader::load_arduino_settings("cloned-pi").unwrap().motion_params.unwrap();
+        assert_eq!((motion.z.speed, motion.z.accel), (Some(300), Some(150)));
+        // The PROFILES entry of the same name was not taken for the host's block
+        let written: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(&yaml).unwrap()).unwrap();
+        assert_eq!(written["PROFILES"]["cloned-pi"]["ARD_NUM_STEPPERS"].as_i64(), Some(3));
+        assert_eq!(written["RaspberryPi"]["cloned-pi"]["ARD_PORT"].as_str(), Some("/dev/ttyACM0"));
+    });
+    std::fs::remove_dir_all(&dir).ok();
+}